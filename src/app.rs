@@ -0,0 +1,41 @@
+pub mod config_store;
+
+pub mod process;
+pub mod update;
+
+pub mod paths;
+
+pub mod manifest;
+
+pub mod lock;
+
+pub mod summary;
+
+pub mod metrics;
+
+pub mod flags;
+
+pub mod notify;
+
+pub mod frontend;
+
+pub mod workflow;
+
+pub mod batch;
+
+pub mod schedule;
+
+pub mod bundle;
+
+pub mod ledger;
+
+pub mod secrets;
+
+pub mod preflight;
+
+pub mod logging;
+
+pub mod taxonomy;
+
+#[cfg(feature = "scripting")]
+pub mod scripting;
@@ -0,0 +1,696 @@
+use std::{collections::HashMap, fs, path::{Path, PathBuf}, thread, time::{Duration, Instant}};
+
+use crate::{app::{config_store::{ConfigStore, LockedSettings, WarningAction}, frontend::{self, Frontend}, lock::{self, FileLock}, manifest::{self, ProcessedManifest}, notify, paths, preflight, process, summary::{self, RunSummary}, workflow}, io::{checksum, schema::{self, ExportFileKind}, sink::SinkOutput, source::{self, LoadOptions}}, model::{Data, SampleOutput}, pipeline};
+
+/// The csv and xml files from one run of the C-Grain, found in a
+/// folder being batch processed. Either file might be missing, such as
+/// when only the csv was exported for a run.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct BatchRun {
+    /// The name of the run, from the names of its files.
+    pub run_name: String,
+    /// The kernel data for the run, if there is any.
+    pub csv_file: Option<PathBuf>,
+    /// The sieve data for the run, if there is any.
+    pub xml_file: Option<PathBuf>,
+}//end struct BatchRun
+
+impl BatchRun {
+    /// Gets the input files of this run, csv first.
+    pub fn get_files(&self) -> Vec<&PathBuf> {
+        self.csv_file.iter().chain(self.xml_file.iter()).collect()
+    }//end get_files(self)
+}//end impl BatchRun
+
+/// Gets the name of the run file_path is from, which is the name of the
+/// file without its extension. Files from the same run share this name,
+/// such as run_0612.csv and run_0612.xml.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::batch::get_run_name;
+///
+/// assert_eq!(get_run_name(&PathBuf::from("exports").join("run_0612.csv")), "run_0612");
+/// assert_eq!(get_run_name(&PathBuf::from("run 7.export.xml")), "run 7.export");
+/// ```
+pub fn get_run_name(file_path: &Path) -> String {
+    file_path.file_stem().map(|stem| stem.to_string_lossy().trim().to_string()).unwrap_or_default()
+}//end get_run_name(file_path)
+
+/// Pairs each csv file with the xml file from the same run, as told by
+/// get_run_name(), ignoring case. Files without a match are given a run
+/// of their own. Runs are sorted by name.
+/// Returns the runs along with a message for each file which was left
+/// out, since its run already had a file of that kind.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::batch::pair_run_files;
+///
+/// let csv_files = vec![PathBuf::from("Run_2.csv"), PathBuf::from("run_1.csv"), PathBuf::from("run_1.xlsx")];
+/// let xml_files = vec![PathBuf::from("run_1.xml"), PathBuf::from("run_3.xml")];
+/// let (runs, problems) = pair_run_files(&csv_files, &xml_files);
+/// assert_eq!(runs.len(), 3);
+/// assert_eq!(runs[0].run_name, "run_1");
+/// assert_eq!(runs[0].csv_file, Some(PathBuf::from("run_1.csv")));
+/// assert_eq!(runs[0].xml_file, Some(PathBuf::from("run_1.xml")));
+/// assert_eq!(runs[1].run_name, "Run_2");
+/// assert_eq!(runs[1].xml_file, None);
+/// assert_eq!(runs[2].csv_file, None);
+/// assert_eq!(problems, vec!["run_1.xlsx: The run run_1 already has kernel data from run_1.csv, so this file was left out."]);
+/// ```
+pub fn pair_run_files(csv_files: &Vec<PathBuf>, xml_files: &Vec<PathBuf>) -> (Vec<BatchRun>, Vec<String>) {
+    let mut runs: Vec<BatchRun> = Vec::new();
+    let mut problems = Vec::new();
+    let file_name = |file_path: &PathBuf| file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    for (file_path, is_csv) in csv_files.iter().map(|csv_file| (csv_file, true)).chain(xml_files.iter().map(|xml_file| (xml_file, false))) {
+        let run_name = get_run_name(file_path);
+        let run_idx = match runs.iter().position(|run| run.run_name.to_lowercase() == run_name.to_lowercase()) {
+            Some(run_idx) => run_idx,
+            None => {
+                runs.push(BatchRun { run_name, csv_file: None, xml_file: None });
+                runs.len() - 1
+            },
+        };
+        let run = &mut runs[run_idx];
+        let (run_file, kind_name) = match is_csv {
+            true => (&mut run.csv_file, "kernel data"),
+            false => (&mut run.xml_file, "sieve data"),
+        };
+        match run_file {
+            Some(existing_file) => problems.push(format!("{}: The run {} already has {} from {}, so this file was left out.", file_name(file_path), run.run_name, kind_name, file_name(existing_file))),
+            None => *run_file = Some(file_path.clone()),
+        }//end matching whether this run already has a file of this kind
+    }//end adding each file to its run
+    runs.sort_by_key(|run| run.run_name.to_lowercase());
+    (runs, problems)
+}//end pair_run_files(csv_files, xml_files)
+
+/// Finds the runs in the folder at dir, pairing the csv and xml files with
+/// pair_run_files(). Each file is sorted as kernel or sieve data by its
+/// contents, like Open Data File(s). Output workbooks from this program,
+/// such as those from an earlier batch, and files which can't be loaded
+/// are skipped. Folders inside dir aren't searched.
+/// Returns the runs along with a message for each file that was left out.
+pub fn find_batch_runs(dir: &Path) -> Result<(Vec<BatchRun>, Vec<String>),String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) => return Err(format!("Couldn't read the files in \"{}\".\n{}", dir.to_string_lossy(), error)),
+    };
+    let mut file_paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).filter(|path| path.is_file()).collect();
+    file_paths.sort();
+    let mut csv_files = Vec::new();
+    let mut xml_files = Vec::new();
+    let mut problems = Vec::new();
+    for file_path in file_paths {
+        let file_name = file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        if checksum::is_output_workbook(&file_path) {continue;}
+        match source::find_source(&file_path).map(|data_source| data_source.export_kind()) {
+            Ok(ExportFileKind::Csv) => csv_files.push(file_path),
+            Ok(ExportFileKind::Xml) => xml_files.push(file_path),
+            Err(msg) => problems.push(format!("{}: {}", file_name, msg)),
+        }//end matching what kind of data this file has
+    }//end sorting each file
+    let (runs, mut pair_problems) = pair_run_files(&csv_files, &xml_files);
+    problems.append(&mut pair_problems);
+    Ok((runs, problems))
+}//end find_batch_runs(dir)
+
+/// Gets the output name template to use for each run of a batch from
+/// output_name_template in the config. Each run needs its own output
+/// file, so {csv_stem} is added to the end of templates which don't have
+/// it, and an empty template gives names like run_0612_summary.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::batch::get_run_output_template;
+///
+/// assert_eq!(get_run_output_template(""), "{csv_stem}_summary");
+/// assert_eq!(get_run_output_template("{date}_{preset}"), "{date}_{preset}_{csv_stem}");
+/// assert_eq!(get_run_output_template("{csv_stem}_{date}"), "{csv_stem}_{date}");
+/// ```
+pub fn get_run_output_template(output_name_template: &str) -> String {
+    match output_name_template.trim() {
+        "" => String::from("{csv_stem}_summary"),
+        template if template.contains("{csv_stem}") => template.to_string(),
+        template => format!("{}_{{csv_stem}}", template),
+    }//end matching whether the template names each run
+}//end get_run_output_template(output_name_template)
+
+/// Gets the report shown at the end of a batch, from the result of each
+/// run as (run name, result). Successful runs give a description of what
+/// was written, and failed runs give why they failed.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::batch::get_batch_report;
+///
+/// let results = vec![
+///     ("run_1".to_string(), Ok("wrote run_1_summary.xlsx".to_string())),
+///     ("run_2".to_string(), Err("the csv file has no rows".to_string())),
+/// ];
+/// assert_eq!(get_batch_report(&results, 3), "Processed 1 of 2 runs, and skipped 3 runs which were already processed.\n\nSucceeded:\nrun_1: wrote run_1_summary.xlsx\n\nFailed:\nrun_2: the csv file has no rows");
+/// ```
+pub fn get_batch_report(results: &Vec<(String, Result<String,String>)>, skipped_count: usize) -> String {
+    let successes: Vec<String> = results.iter().filter_map(|(run_name, result)| result.as_ref().ok().map(|description| format!("{}: {}", run_name, description))).collect();
+    let failures: Vec<String> = results.iter().filter_map(|(run_name, result)| result.as_ref().err().map(|msg| format!("{}: {}", run_name, msg))).collect();
+    let mut report = format!("Processed {} of {} runs", successes.len(), results.len());
+    match skipped_count {
+        0 => report.push('.'),
+        _ => report.push_str(&format!(", and skipped {} runs which were already processed.", skipped_count)),
+    }//end matching whether any runs were skipped
+    if successes.len() > 0 {report.push_str(&format!("\n\nSucceeded:\n{}", successes.join("\n")));}
+    if failures.len() > 0 {report.push_str(&format!("\n\nFailed:\n{}", failures.join("\n")));}
+    report
+}//end get_batch_report(results, skipped_count)
+
+/// Finds the first file of run which was changed less than min_age ago,
+/// and so might still be being written, such as while the C-Grain is
+/// still exporting it. Returns the name of that file, or None if every
+/// file has settled.
+pub fn get_unsettled_file(run: &BatchRun, min_age: Duration) -> Option<String> {
+    run.get_files().into_iter().find(|file_path| !source::is_file_settled(file_path, min_age))
+        .map(|file_path| file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default())
+}//end get_unsettled_file(run, min_age)
+
+/// Finds a problem with the files of run which might go away by waiting,
+/// so that only these are worth trying the run again for. These are a
+/// file changed less than min_age ago, which might still be being
+/// written, or a file open in another program which doesn't share it,
+/// including output if there is one, such as a workbook open in Excel.  
+/// Returns None if there's no such problem, in which case anything
+/// which goes wrong with the run would go wrong the same way again.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use usda_c_grain_sum::app::batch::{BatchRun, get_transient_problem};
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_transient_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let csv_file = dir.join("run_1.csv");
+/// std::fs::write(&csv_file, "external-sample-id,Area\nS1,12\n").unwrap();
+/// let run = BatchRun { run_name: "run_1".to_string(), csv_file: Some(csv_file), xml_file: None };
+///
+/// // the file was just written, so it might not be finished
+/// assert_eq!(get_transient_problem(&run, None, Duration::from_secs(3600)), Some("run_1.csv is still being written.".to_string()));
+/// assert_eq!(get_transient_problem(&run, Some(&dir.join("run_1.xlsx")), Duration::ZERO), None);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn get_transient_problem(run: &BatchRun, output: Option<&Path>, min_age: Duration) -> Option<String> {
+    if let Some(file_name) = get_unsettled_file(run, min_age) {return Some(format!("{} is still being written.", file_name));}
+    let in_use_file = run.get_files().into_iter().map(|file_path| file_path.as_path()).chain(output).find(|file_path| lock::is_file_in_use(file_path));
+    in_use_file.map(|file_path| format!("{} is open in another program.", file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()))
+}//end get_transient_problem(run, output, min_age)
+
+/// Gets the error report written next to the files of a run which was
+/// quarantined, from why each attempt to process it failed.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::batch::{BatchRun, get_quarantine_report};
+///
+/// let run = BatchRun { run_name: "run_1".to_string(), csv_file: Some(PathBuf::from("run_1.csv")), xml_file: None };
+/// let errors = vec!["run_1.csv is still being written.".to_string(), "The csv file has no rows.".to_string()];
+/// assert_eq!(get_quarantine_report(&run, &errors), "The run run_1 failed 2 times, so its files were moved here.\nFiles: run_1.csv\n\nAttempt 1: run_1.csv is still being written.\nAttempt 2: The csv file has no rows.\n");
+/// ```
+pub fn get_quarantine_report(run: &BatchRun, errors: &Vec<String>) -> String {
+    let file_names: Vec<String> = run.get_files().iter().map(|file_path| file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()).collect();
+    let mut report = format!("The run {} failed {} times, so its files were moved here.\nFiles: {}\n\n", run.run_name, errors.len(), file_names.join(", "));
+    for (attempt_idx, error) in errors.iter().enumerate() {
+        report.push_str(&format!("Attempt {}: {}\n", attempt_idx + 1, error));
+    }//end adding why each attempt failed
+    report
+}//end get_quarantine_report(run, errors)
+
+/// Moves the files of run into quarantine_dir, so a run which keeps
+/// failing doesn't hold up later batches, and writes report next to
+/// them, named after the run, such as run_1_error.txt.  
+/// Files which already have a file of the same name in quarantine_dir
+/// are left where they are, and why is returned as an error.
+pub fn quarantine_run(run: &BatchRun, quarantine_dir: &Path, report: &str) -> Result<(),String> {
+    if let Err(error) = fs::create_dir_all(quarantine_dir) {
+        return Err(format!("Couldn't make the quarantine folder \"{}\".\n{}", quarantine_dir.to_string_lossy(), error));
+    }//end if we couldn't make the quarantine folder
+    let report_path = quarantine_dir.join(format!("{}_error.txt", run.run_name));
+    if let Err(error) = fs::write(&report_path, report) {
+        return Err(format!("Couldn't write the error report \"{}\".\n{}", report_path.to_string_lossy(), error));
+    }//end if we couldn't write the error report
+    for file_path in run.get_files() {
+        let file_name = file_path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+        let quarantine_path = quarantine_dir.join(&file_name);
+        if quarantine_path.exists() {
+            return Err(format!("\"{}\" is already in the quarantine folder, so it was left where it is.", file_name.to_string_lossy()));
+        }//end if we'd replace a file already quarantined
+        if let Err(error) = fs::rename(file_path, &quarantine_path) {
+            return Err(format!("Couldn't move \"{}\" to the quarantine folder.\n{}", file_name.to_string_lossy(), error));
+        }//end if we couldn't move the file
+    }//end moving each file of the run
+    Ok(())
+}//end quarantine_run(run, quarantine_dir, report)
+
+/// Processes every run in the folder at input_dir, found by
+/// find_batch_runs(), without asking about each run, then shows
+/// which runs succeeded or failed.  
+/// Each run uses the preset of the profile pattern its files match, if any.
+/// If combined_output is None, a workbook is written next to the input
+/// files for each run, named from the output name template. Otherwise,
+/// the sheets of every run are merged into one workbook at combined_output,
+/// like Merge Summary Workbooks.  
+/// Runs whose files are all in the processed manifest can be skipped, and
+/// the files of each run that's written are added to the manifest. Each
+/// file which is skipped is logged.  
+/// The manifest is locked from when it's read until it's written, so a
+/// batch running at the same time, such as a scheduled one, can't process
+/// the same files or lose the files this batch adds. If another batch
+/// holds it for longer than manifest::MANIFEST_LOCK_TIMEOUT, nothing is processed.  
+/// If unattended, such as for scheduled processing, those runs are skipped
+/// without asking unless config.schedule_force_reprocess is set, runs with
+/// files still being written are retried and then quarantined by
+/// process_batch_run_with_retries(), and the report is only shown if there
+/// were problems.
+pub fn batch_process(gui: &mut impl Frontend, input_dir: &PathBuf, combined_output: &Option<PathBuf>, locked_settings: &LockedSettings, unattended: bool) {
+    let base_config = gui.get_config_store();
+    if !workflow::ensure_locked_settings_kept(gui, locked_settings, &base_config) {return;}
+    let (runs, file_problems) = match find_batch_runs(input_dir) {
+        Ok(found) => found,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't look for runs to process.\n{}", msg)); return;},
+    };
+    if runs.len() == 0 {
+        let msg = format!("Couldn't find any csv or xml files to process in \"{}\".\n{}", input_dir.to_string_lossy(), file_problems.join("\n"));
+        if unattended {log::warn!("{}", msg);}
+        else {gui.integrated_dialog_alert(&msg);}
+        return;
+    }//end if there's nothing to process
+
+    // the manifest knows files by their contents, so renamed files are still skipped
+    let manifest_path = manifest::get_manifest_path();
+    let _manifest_lock = match manifest_path {
+        Ok(ref manifest_path) => match FileLock::wait_acquire(manifest_path, manifest::MANIFEST_LOCK_TIMEOUT) {
+            Ok(manifest_lock) => Some(manifest_lock),
+            Err(msg) => {
+                let msg = format!("Nothing in \"{}\" was processed, since another batch is still using the processed manifest.\n{}", input_dir.to_string_lossy(), msg);
+                if unattended {log::warn!("{}", msg);}
+                else {gui.integrated_dialog_alert(&msg);}
+                return;
+            },
+        },
+        Err(_) => None,
+    };
+    let mut processed_manifest = match manifest_path.as_ref().map_err(|msg| msg.clone()).and_then(manifest::try_read_manifest) {
+        Ok(processed_manifest) => processed_manifest,
+        Err(msg) => {log::warn!("Couldn't read the processed manifest, so no runs will be skipped.\n{}", msg); ProcessedManifest::default()},
+    };
+    // each file is hashed once, then the hash is used for skipping and for recording it
+    let file_hashes: HashMap<PathBuf, Result<String,String>> = runs.iter().flat_map(|run| run.get_files())
+        .map(|file_path| (file_path.clone(), manifest::hash_file(file_path)))
+        .collect();
+    let is_processed = |run: &BatchRun, processed_manifest: &ProcessedManifest| run.get_files().iter()
+        .all(|file_path| matches!(file_hashes.get(*file_path), Some(Ok(hash)) if processed_manifest.contains_hash(hash)));
+    let processed_count = runs.iter().filter(|run| is_processed(run, &processed_manifest)).count();
+    let skip_processed = processed_count > 0 && match unattended {
+        true => !base_config.schedule_force_reprocess,
+        false => gui.integrated_dialog_yes_no(&format!("{} of the {} runs in this folder were already processed.\nWould you like to skip them? Choose no to process them again.", processed_count, runs.len())),
+    };
+    if unattended && skip_processed && processed_count == runs.len() {
+        for run in runs.iter() {log_skipped_run(gui, run);}
+        log::info!("Every run in \"{}\" was already processed, so there's nothing new to process.", input_dir.to_string_lossy());
+        return;
+    }//end if there's nothing new to process
+
+    // every run is written to the same folder, so it's checked once for all of them
+    let output_folder = match combined_output {
+        Some(combined_output) => preflight::get_output_folder(combined_output),
+        None => input_dir.clone(),
+    };
+    let run_files: Vec<&PathBuf> = runs.iter().flat_map(|run| run.get_files()).collect();
+    if !workflow::ensure_output_folder_ready(gui, &output_folder, &run_files, unattended) {return;}
+
+    let process_start = Instant::now();
+    gui.start_wait();
+    let date = time::OffsetDateTime::now_utc().format(time::macros::format_description!("[year]-[month]-[day]")).unwrap_or_default();
+    // (run name, description of what was written or why the run failed)
+    let mut results: Vec<(String, Result<String,String>)> = Vec::new();
+    // (run name, output sheets of the run), for the combined workbook
+    let mut summaries = Vec::new();
+    // files of the runs in the combined workbook, added to the manifest once it's written
+    let mut combined_files = Vec::new();
+    // (run name, output file, summary of the run or why it failed), for the webhook
+    let mut webhook_runs: Vec<(String, PathBuf, Result<Option<RunSummary>,String>)> = Vec::new();
+    let mut skipped_count = 0;
+    for run in runs.iter() {
+        if skip_processed && is_processed(run, &processed_manifest) {
+            log_skipped_run(gui, run);
+            skipped_count += 1;
+            continue;
+        }//end if this run was already processed
+        let config = match get_batch_run_config(&base_config, run, locked_settings) {
+            Ok(config) => config,
+            Err(msg) => {results.push((run.run_name.clone(), Err(msg))); continue;},
+        };
+        let output = match combined_output {
+            Some(combined_output) => combined_output.clone(),
+            None => {
+                let name_tokens = process::get_output_name_tokens(&run.csv_file, &run.xml_file, &config, &date);
+                match process::expand_output_name(&get_run_output_template(&config.output_name_template), &name_tokens) {
+                    Ok(output_name) => input_dir.join(output_name).with_extension("xlsx"),
+                    Err(msg) => {results.push((run.run_name.clone(), Err(format!("Couldn't fill in the output file name. {}", msg)))); continue;},
+                }//end matching whether we could name the output of this run
+            },
+        };
+        let output = match combined_output.is_some() {
+            true => output,
+            false => match workflow::ensure_output_writable(gui, &output, unattended) {
+                Some(output) => output,
+                None => {results.push((run.run_name.clone(), Err(format!("{} was open in another program, so it wasn't written.", paths::get_file_name(&output))))); continue;},
+            },
+        };
+        let run_result = match unattended {
+            true => process_batch_run_with_retries(gui, run, &config, &output, combined_output.is_none(), &base_config, input_dir),
+            false => process_batch_run(gui, run, &config, &output, combined_output.is_none()),
+        };
+        match run_result {
+            Ok(run_output) => {
+                let mut description = match combined_output {
+                    Some(_) => format!("added {} sheets to the combined workbook", run_output.output_sheets.len()),
+                    None => format!("wrote {}", paths::get_file_name(&output)),
+                };
+                if run_output.problems.len() > 0 {description.push_str(&format!(", with these problems:\n{}", run_output.problems.join("\n")));}
+                results.push((run.run_name.clone(), Ok(description)));
+                webhook_runs.push((run.run_name.clone(), output.clone(), Ok(run_output.run_summary.clone())));
+                match combined_output {
+                    Some(_) => {
+                        summaries.push((run.run_name.clone(), run_output.output_sheets));
+                        combined_files.extend(run.get_files());
+                    },
+                    None => {
+                        record_processed_files(&mut processed_manifest, &run.get_files(), &file_hashes, &output);
+                        workflow::record_ledger_run(gui, &run.get_files(), &output, &config, &run_output.output_sheets, run_output.run_summary.as_ref());
+                    },
+                }//end matching whether the run was written yet
+            },
+            Err(msg) => {
+                results.push((run.run_name.clone(), Err(msg.clone())));
+                webhook_runs.push((run.run_name.clone(), output, Err(msg)));
+            },
+        }//end matching whether the run could be processed
+    }//end processing each run
+
+    let mut report = get_batch_report(&results, skipped_count);
+    let mut had_problems = file_problems.len() > 0 || results.iter().any(|(_, result)| result.is_err());
+    if let (Some(combined_output), true) = (combined_output, summaries.len() > 0) {
+        let merged_sheets = process::merge_summary_sheets(&summaries);
+        // the combined workbook from the last batch is often still open in Excel
+        let writable_output = workflow::ensure_output_writable(gui, combined_output, unattended);
+        let combined_written = match writable_output {
+            Some(ref writable_output) => {
+                match workflow::get_anonymized_output(gui, &merged_sheets, &None, &base_config, writable_output) {
+                    Ok(anonymized_output) => {
+                        let sheets_to_write = match anonymized_output {
+                            Some((ref anonymized_sheets, _)) => anonymized_sheets,
+                            None => &merged_sheets,
+                        };
+                        let sink_output = SinkOutput { sheets: sheets_to_write, input_snapshot: None, run_info: None, signing_key: None };
+                        workflow::write_output_sinks(gui, &sink_output, &base_config, writable_output)
+                    },
+                    Err(msg) => {log::warn!("Couldn't anonymize the sample ids, so the combined output wasn't written.\n{}", msg); false},
+                }//end matching whether we could anonymize the combined output
+            },
+            None => false,
+        };
+        match (combined_written, writable_output) {
+            (true, Some(writable_output)) => {
+                record_processed_files(&mut processed_manifest, &combined_files, &file_hashes, &writable_output);
+                workflow::record_ledger_run(gui, &combined_files, &writable_output, &base_config, &merged_sheets, None);
+                report.push_str(&format!("\n\nWrote the combined workbook to \"{}\".", writable_output.to_string_lossy()));
+            },
+            _ => {
+                had_problems = true;
+                report.push_str(&format!("\n\nCouldn't write the combined workbook to \"{}\", so none of the runs were written.", combined_output.to_string_lossy()));
+                for (_, _, webhook_result) in webhook_runs.iter_mut().filter(|(_, _, webhook_result)| webhook_result.is_ok()) {
+                    *webhook_result = Err(String::from("Couldn't write the combined workbook."));
+                }//end marking each run in the combined workbook as failed
+            },
+        }//end matching whether we wrote the combined workbook
+    }//end if we should write the combined workbook
+    if !base_config.webhook_url.trim().is_empty() {
+        let mut webhook_problems = Vec::new();
+        for (run_name, output, webhook_result) in webhook_runs.iter() {
+            let payload = notify::get_webhook_payload(run_name, output, &webhook_result.as_ref().map(|run_summary| run_summary.as_ref()).map_err(|msg| msg.clone()));
+            if let Err(msg) = notify::post_webhook(base_config.webhook_url.trim(), &payload) {webhook_problems.push(format!("{}: {}", run_name, msg));}
+        }//end sending each run to the webhook
+        if webhook_problems.len() > 0 {
+            had_problems = true;
+            report.push_str(&format!("\n\nCouldn't send these runs to the webhook:\n{}", webhook_problems.join("\n")));
+        }//end if any runs couldn't be sent
+    }//end if we should let the webhook know about each run
+    if file_problems.len() > 0 {report.push_str(&format!("\n\nThese files were left out:\n{}", file_problems.join("\n")));}
+    if let Ok(ref manifest_path) = manifest_path {
+        if let Err(msg) = manifest::try_write_manifest(manifest_path, &processed_manifest) {log::warn!("Couldn't update the processed manifest.\n{}", msg);}
+    }//end if we know where the manifest is
+    gui.end_wait();
+    gui.notify_completion(process_start.elapsed(), &paths::get_file_name(input_dir));
+    if had_problems {gui.integrated_dialog_alert(&report);}
+    else if unattended {log::info!("{}", report);}
+    else {gui.integrated_dialog_message(&report);}
+}//end batch_process(gui, input_dir, combined_output, locked_settings, unattended)
+
+/// Logs each file of run, which batch_process() skipped since it was
+/// already processed.
+fn log_skipped_run(gui: &mut impl Frontend, run: &BatchRun) {
+    for file_path in run.get_files() {
+        gui.log(&format!("Skipped \"{}\", since it was already processed.", file_path.to_string_lossy()));
+    }//end logging each file of the run
+}//end log_skipped_run(gui, run)
+
+/// Processes run with process_batch_run(), for unattended batches where
+/// nobody is around to sort out a failed run.  
+/// A run which has a file that might still be being written, or is open
+/// in another program, found by get_transient_problem(), is tried
+/// again after waiting config.schedule_retry_seconds, up to
+/// config.schedule_retry_attempts times in all. If every try fails, the
+/// files of the run are moved to a Quarantine folder in input_dir with a
+/// report of why, so later batches aren't held up by them.  
+/// Any other failure, such as a file which can't be parsed or settings
+/// which don't fit the run, would fail the same way again, so it's
+/// returned right away and the files are left where they are.  
+/// Checking and waiting happen on a worker thread, so the window
+/// doesn't freeze between tries.
+fn process_batch_run_with_retries<F: Frontend>(gui: &mut F, run: &BatchRun, run_config: &ConfigStore, output: &PathBuf, write_output: bool, config: &ConfigStore, input_dir: &PathBuf) -> Result<BatchRunOutput,String> {
+    let retry_wait = Duration::from_secs(config.schedule_retry_seconds);
+    let run_output = match write_output {
+        true => Some(output.clone()),
+        false => None,
+    };
+    // gets a problem which might go away by waiting, after waiting for wait
+    let get_transient_problem = move |gui: &mut F, wait: Duration| {
+        let (worker_run, worker_output) = (run.clone(), run_output.clone());
+        frontend::run_in_background(gui, move |progress| {
+            if !wait.is_zero() {
+                progress(0.0, &format!("Waiting {} seconds to try {} again", wait.as_secs(), worker_run.run_name));
+                thread::sleep(wait);
+            }//end if we should wait before checking
+            progress(1.0, &format!("Checking the files of {}", worker_run.run_name));
+            get_transient_problem(&worker_run, worker_output.as_deref(), retry_wait)
+        })
+    };
+    // why each try failed
+    let mut errors = Vec::new();
+    loop {
+        let wait = match errors.len() {
+            0 => Duration::ZERO,
+            _ => retry_wait,
+        };
+        let problem = match get_transient_problem(gui, wait)? {
+            Some(problem) => problem,
+            None => match process_batch_run(gui, run, run_config, output, write_output) {
+                Ok(run_output) => return Ok(run_output),
+                // a file might have been opened or changed while the run was processed
+                Err(msg) => match get_transient_problem(gui, Duration::ZERO)? {
+                    Some(problem) => format!("{} {}", msg, problem),
+                    None => return Err(msg),
+                },
+            },
+        };
+        errors.push(problem);
+        if errors.len() >= config.schedule_retry_attempts.max(1) {break;}
+        log::warn!("Try {} of run {} failed, so it will be tried again in {} seconds.\n{}", errors.len(), run.run_name, retry_wait.as_secs(), errors.last().cloned().unwrap_or_default());
+    }//end trying the run until it succeeds or we run out of tries
+    let last_error = errors.last().cloned().unwrap_or_default();
+    let quarantine_dir = input_dir.join("Quarantine");
+    match quarantine_run(run, &quarantine_dir, &get_quarantine_report(run, &errors)) {
+        Ok(_) => Err(format!("{}\nIt failed {} times, so its files were moved to \"{}\".", last_error, errors.len(), quarantine_dir.to_string_lossy())),
+        Err(msg) => Err(format!("{}\nIt failed {} times, but couldn't be quarantined.\n{}", last_error, errors.len(), msg)),
+    }//end matching whether we could quarantine the run
+}//end process_batch_run_with_retries(gui, run, run_config, output, write_output, config, input_dir)
+
+/// The output of one run of a batch, from process_batch_run().
+struct BatchRunOutput {
+    /// The output sheets of the run, with the real sample ids.
+    output_sheets: Vec<(String, SampleOutput)>,
+    /// The summary of the csv data of the run, for the webhook,
+    /// or None if there's no webhook or no csv data.
+    run_summary: Option<RunSummary>,
+    /// Problems which left out part of the output, and warnings nobody was asked about.
+    problems: Vec<String>,
+}//end struct BatchRunOutput
+
+/// Gets the settings to process run with in a batch, which are the preset
+/// of the profile pattern its files match, or base_config if there isn't one,
+/// with the active filter set. Settings locked by an administrator are checked,
+/// since the preset might have changed them.
+fn get_batch_run_config(base_config: &ConfigStore, run: &BatchRun, locked_settings: &LockedSettings) -> Result<ConfigStore,String> {
+    let mut config = base_config.clone();
+    for file_path in run.get_files() {
+        match base_config.get_file_profile_config(file_path) {
+            Ok(Some((profile_name, profile_config))) => {
+                log::info!("{} matches a profile pattern, so the {} preset will be used for it.", paths::get_file_name(file_path), profile_name);
+                config = profile_config;
+                break;
+            },
+            Ok(None) => {},
+            Err(msg) => return Err(format!("Couldn't check the file names against the profile patterns. {}", msg)),
+        }//end matching whether this file matches a profile
+    }//end checking each file of the run for a profile
+    let config = config.with_active_filter_set()?;
+    match locked_settings.get_overridden(&config)? {
+        overridden if overridden.len() > 0 => Err(format!("These settings are locked by an administrator, but are changed by the preset for this run: {}", overridden.join(", "))),
+        _ => Ok(config),
+    }//end matching whether any locked settings were changed
+}//end get_batch_run_config(base_config, run, locked_settings)
+
+/// Loads and processes the files of run with config, for a batch.  
+/// If write_output is true, the output sheets are written to each enabled
+/// sink, with output as the output file. Either way, output is where
+/// kernel csv files and split workbooks are written next to.  
+/// Nobody is asked anything, so warnings which would prompt are returned
+/// as problems along with the output sheets, and anything which would
+/// stop processing fails the run instead.
+fn process_batch_run(gui: &mut impl Frontend, run: &BatchRun, config: &ConfigStore, output: &PathBuf, write_output: bool) -> Result<BatchRunOutput,String> {
+    let mut problems = Vec::new();
+    let input_csv_data = match run.csv_file {
+        Some(ref csv_file) => Some(load_batch_data(csv_file, ExportFileKind::Csv, config, &mut problems)?),
+        None => None,
+    };
+    let input_xml_data = match run.xml_file {
+        Some(ref xml_file) => Some(load_batch_data(xml_file, ExportFileKind::Xml, config, &mut problems)?),
+        None => None,
+    };
+    if input_csv_data.is_none() && config.is_csv_output_enabled() {return Err(String::from("There's no csv file for this run, which is needed by the csv output in the settings."));}
+    if input_xml_data.is_none() && config.xml_sieve_cols_enabled {return Err(String::from("There's no xml file for this run, which is needed by the xml output in the settings."));}
+
+    let piped_csv = input_csv_data.as_ref().map(|csv_data| match pipeline::run_pipeline(csv_data, config) {
+        Ok((piped_csv, _)) => piped_csv,
+        Err(_) => csv_data.clone(),
+    });
+    for (category, msg) in process::get_input_warnings(piped_csv.as_ref(), input_xml_data.as_ref(), config) {
+        match config.get_warning_action(category) {
+            WarningAction::Abort => return Err(format!("{}: {} Your warning policies stop processing for this.", category.get_name(), msg)),
+            WarningAction::Prompt => problems.push(format!("{}: {}", category.get_name(), msg)),
+            WarningAction::Log => log::warn!("Warning, {}: {}", category.get_name(), msg),
+        }//end matching what to do about this warning
+    }//end handling each warning
+    let excluded_samples = workflow::get_invalid_id_exclusions(&input_csv_data, &input_xml_data, config)?;
+    let (input_csv_data, input_xml_data, piped_csv) = match excluded_samples.len() > 0 {
+        true => {
+            let (input_csv_data, input_xml_data) = workflow::exclude_samples(input_csv_data, input_xml_data, &excluded_samples, config);
+            // the run summary comes from the piped data, so it leaves them out too
+            let (piped_csv, _) = workflow::exclude_samples(piped_csv, None, &excluded_samples, config);
+            (input_csv_data, input_xml_data, piped_csv)
+        },
+        false => (input_csv_data, input_xml_data, piped_csv),
+    };
+
+    let mut output_sheets = workflow::get_output_sheets(&input_csv_data, &input_xml_data, config, output, &mut problems, &|_, _| {})
+        .map_err(|msg| format!("The processing pipeline couldn't run, so nothing was processed. {}", msg))?;
+    if output_sheets.len() == 0 {return Err(format!("None of the output sheets could be processed. {}", problems.join(" ")));}
+    for (sheet_name, sheet_data) in output_sheets.iter_mut() {
+        match workflow::apply_before_write_script(&config.script_path, sheet_name, sheet_data) {
+            Ok(scripted_sheet) => *sheet_data = scripted_sheet,
+            Err(msg) => problems.push(format!("The user script couldn't change the {} sheet, so it was written as it is. {}", sheet_name, msg)),
+        }//end matching whether the script could change this sheet
+    }//end running the script on each sheet
+    let run_summary = piped_csv.as_ref().and_then(|piped_csv| summary::get_run_summary(piped_csv, config).ok());
+    if !write_output {return Ok(BatchRunOutput { output_sheets, run_summary, problems });}
+
+    let anonymized_output = workflow::get_anonymized_output(gui, &output_sheets, &input_csv_data, config, output)?;
+    let (sheets_to_write, snapshot_data) = match anonymized_output {
+        Some((ref anonymized_sheets, ref anonymized_csv)) => (anonymized_sheets, anonymized_csv.as_ref()),
+        None => (&output_sheets, input_csv_data.as_ref()),
+    };
+    let run_info = match config.run_info_sheet_enabled || excluded_samples.len() > 0 {
+        true => Some(workflow::get_run_info(config, &run.csv_file, &run.xml_file, &excluded_samples)),
+        false => None,
+    };
+    let signing_key = workflow::get_output_signing_key(&run_info)?;
+    let sink_output = SinkOutput { sheets: sheets_to_write, input_snapshot: snapshot_data, run_info, signing_key: signing_key.as_deref() };
+    if !workflow::write_output_sinks(gui, &sink_output, config, output) {return Err(format!("Couldn't write the output file \"{}\".", output.to_string_lossy()));}
+    if config.split_output_enabled {
+        match workflow::get_output_sample_id_key(&output_sheets, &input_csv_data, config) {
+            Ok(key) => workflow::write_split_workbooks(gui, &output_sheets, &key, config, output),
+            Err(msg) => problems.push(format!("Couldn't anonymize the sample ids, so the workbooks split by metadata column weren't written.\n{}", msg)),
+        }//end matching whether we could get the pseudonyms
+    }//end if we should also write a workbook for each metadata value
+    Ok(BatchRunOutput { output_sheets, run_summary, problems })
+}//end process_batch_run(gui, run, config, output, write_output)
+
+/// Loads the input file at file_path for a batch, like opening it from the window,
+/// but without asking the user anything. Columns are only renamed by a
+/// column mapping already saved for the layout of the file, and files
+/// which would need the user to fix them aren't loaded.  
+/// If the user script fails, the data is loaded as it is, and why is
+/// added to problems.
+fn load_batch_data(file_path: &PathBuf, export_kind: ExportFileKind, config: &ConfigStore, problems: &mut Vec<String>) -> Result<Data,String> {
+    let kind_name = if export_kind == ExportFileKind::Xml {"xml"} else {"csv"};
+    if config.input_settle_seconds > 0 {
+        source::wait_for_settled_file(file_path, Duration::from_secs(config.input_settle_seconds), source::SETTLE_TIMEOUT)?;
+    }//end if we should wait for files still being written
+    let mut data = source::load_data_file(file_path, &LoadOptions::from_config(config))
+        .map_err(|msg| format!("Couldn't load {}. {}", paths::get_file_name(file_path), msg))?;
+    match schema::detect_schema(&data, export_kind) {
+        Ok(export_schema) => {
+            let applied = data.rename_headers(export_schema.header_aliases);
+            if applied.len() > 0 {log::info!("Renamed {} columns of {} to match the {} layout.", applied.len(), paths::get_file_name(file_path), export_schema.name);}
+        },
+        Err(msg) if config.strict_schema_enabled => return Err(format!("{} wasn't loaded, since only known C-Grain files are accepted. {}", paths::get_file_name(file_path), msg)),
+        Err(_) => log::warn!("{} doesn't match a known C-Grain export layout.", paths::get_file_name(file_path)),
+    }//end matching whether the file has a known layout
+    if data.has_generated_headers() {
+        return Err(format!("The header row of {} (row index {}) looks like data instead of column names.", paths::get_file_name(file_path), config.csv_header_row));
+    }//end if we had to make up headers for the file
+    if export_kind == ExportFileKind::Csv {
+        let fingerprint = schema::get_header_fingerprint(data.get_headers_ref());
+        if let Some(header_mapping) = config.header_mappings.iter().find(|header_mapping| header_mapping.fingerprint == fingerprint) {
+            pipeline::apply_header_aliases(&mut data, &header_mapping.aliases)
+                .map_err(|msg| format!("Couldn't use the column mapping saved for the layout of {}. {}", paths::get_file_name(file_path), msg))?;
+        }//end if we have a saved mapping for this layout
+    }//end if this is kernel data
+    match workflow::apply_after_load_script(&config.script_path, kind_name, data) {
+        Ok(data) => Ok(data),
+        Err((data, msg)) => {
+            problems.push(format!("The user script couldn't change the {} data, so it was loaded as it is. {}", kind_name, msg));
+            Ok(data)
+        },
+    }//end matching whether the script could change the data
+}//end load_batch_data(file_path, export_kind, config, problems)
+
+/// Adds each of file_paths to processed_manifest, by the hash of its
+/// contents from file_hashes, as written to output.
+fn record_processed_files(processed_manifest: &mut ProcessedManifest, file_paths: &Vec<&PathBuf>, file_hashes: &HashMap<PathBuf, Result<String,String>>, output: &PathBuf) {
+    for file_path in file_paths {
+        match file_hashes.get(*file_path) {
+            Some(Ok(hash)) => processed_manifest.record(hash, &file_path.to_string_lossy(), &output.to_string_lossy()),
+            Some(Err(msg)) => log::warn!("Couldn't add a file to the processed manifest.\n{}", msg),
+            None => log::warn!("Couldn't add \"{}\" to the processed manifest, since it wasn't hashed.", file_path.to_string_lossy()),
+        }//end matching whether we could hash the file
+    }//end recording each file
+}//end record_processed_files(processed_manifest, file_paths, file_hashes, output)
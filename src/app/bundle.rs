@@ -0,0 +1,123 @@
+use std::{fs::{self, File}, io::Write, path::{Path, PathBuf}};
+
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use crate::app::config_store::ConfigStore;
+
+/// The name of the file in a run bundle describing how the output was made.
+pub const PROVENANCE_FILE_NAME: &str = "provenance.json";
+/// The name of the file in a run bundle holding the messages logged during the run.
+pub const LOG_FILE_NAME: &str = "log.txt";
+
+/// Gets the path a run bundle is offered to be saved at by default,
+/// which is next to output_path, named after it.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::bundle::get_bundle_path;
+///
+/// let output_path = PathBuf::from("results").join("sum.xlsx");
+/// assert_eq!(get_bundle_path(&output_path), PathBuf::from("results").join("sum_bundle.zip"));
+/// ```
+pub fn get_bundle_path(output_path: &PathBuf) -> PathBuf {
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output_path.with_file_name(format!("{}_bundle.zip", stem))
+}//end get_bundle_path(output_path)
+
+/// Gets the json describing how a run's output was made, holding the
+/// lines of the Run Info sheet, the hash of each input file, given as
+/// (file name, hash), and every setting in config.  
+/// Since bundles are meant to be shared, passwords, addresses, and key
+/// paths are left out, using ConfigStore::without_secrets().
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::bundle::get_provenance_json;
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+///
+/// let run_info = vec![("Program Version".to_string(), "0.4.3".to_string()), ("Operator".to_string(), "Dan".to_string())];
+/// let input_hashes = vec![("run_0612.csv".to_string(), "cbf29ce484222325".to_string())];
+/// let provenance = get_provenance_json(&run_info, &input_hashes, &ConfigStore::default()).unwrap();
+/// let provenance: serde_json::Value = serde_json::from_str(&provenance).unwrap();
+/// assert_eq!(provenance["run_info"]["Operator"], "Dan");
+/// assert_eq!(provenance["input_hashes"]["run_0612.csv"], "cbf29ce484222325");
+/// assert_eq!(provenance["config"]["csv_sample_id_header"], ConfigStore::default().csv_sample_id_header);
+///
+/// // passwords aren't shared
+/// let mut config = ConfigStore::default();
+/// config.sheet_protection_password = "hunter2".to_string();
+/// config.webhook_url = "https://dashboard.example/hook?token=abc123".to_string();
+/// let provenance = get_provenance_json(&run_info, &input_hashes, &config).unwrap();
+/// assert!(!provenance.contains("hunter2"));
+/// assert!(!provenance.contains("abc123"));
+/// ```
+pub fn get_provenance_json(run_info: &Vec<(String, String)>, input_hashes: &Vec<(String, String)>, config: &ConfigStore) -> Result<String,String> {
+    let to_json_object = |pairs: &Vec<(String, String)>| -> serde_json::Map<String, serde_json::Value> {
+        let mut object = serde_json::Map::new();
+        for (key, value) in pairs.iter() {
+            // labels like Excluded Sample can be repeated, so later ones are kept as a list
+            match object.get_mut(key) {
+                Some(serde_json::Value::Array(values)) => values.push(serde_json::Value::String(value.clone())),
+                Some(first_value) => *first_value = serde_json::Value::Array(vec![first_value.clone(), serde_json::Value::String(value.clone())]),
+                None => {object.insert(key.clone(), serde_json::Value::String(value.clone()));},
+            }//end matching whether we've seen this key already
+        }//end adding each pair
+        object
+    };
+    let config_value = serde_json::to_value(config.without_secrets()).map_err(|error| format!("Couldn't write the settings to json.\n{}", error))?;
+    let mut provenance = serde_json::Map::new();
+    provenance.insert("run_info".to_string(), serde_json::Value::Object(to_json_object(run_info)));
+    provenance.insert("input_hashes".to_string(), serde_json::Value::Object(to_json_object(input_hashes)));
+    provenance.insert("config".to_string(), config_value);
+    serde_json::to_string_pretty(&serde_json::Value::Object(provenance)).map_err(|error| format!("Couldn't write the provenance json.\n{}", error))
+}//end get_provenance_json(run_info, input_hashes, config)
+
+/// Writes a zip file at bundle_path holding each of files, given as
+/// (name in the bundle, path of the file), and each of text_files,
+/// given as (name in the bundle, contents). Names can have folders,
+/// like "inputs/run_0612.csv".
+/// Returns the number of files in the bundle, or an error if any of
+/// files can't be read or the bundle can't be written.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::bundle::write_run_bundle;
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_bundle_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let output_path = dir.join("sum.xlsx");
+/// std::fs::write(&output_path, b"workbook").unwrap();
+/// let bundle_path = dir.join("sum_bundle.zip");
+/// let files = vec![("output/sum.xlsx".to_string(), output_path)];
+/// let text_files = vec![("log.txt".to_string(), "Finished outputing processed file.".to_string())];
+/// assert_eq!(write_run_bundle(&bundle_path, &files, &text_files), Ok(2));
+/// assert!(std::fs::metadata(&bundle_path).unwrap().len() > 0);
+///
+/// let missing_files = vec![("inputs/missing.csv".to_string(), dir.join("missing.csv"))];
+/// assert!(write_run_bundle(&bundle_path, &missing_files, &text_files).is_err());
+/// ```
+pub fn write_run_bundle(bundle_path: &Path, files: &Vec<(String, PathBuf)>, text_files: &Vec<(String, String)>) -> Result<usize,String> {
+    // read everything first, so a missing file doesn't leave half a bundle
+    let mut entries: Vec<(&str, Vec<u8>)> = Vec::new();
+    for (name, file_path) in files.iter() {
+        match fs::read(file_path) {
+            Ok(contents) => entries.push((name, contents)),
+            Err(error) => return Err(format!("Couldn't read {} to add it to the bundle.\n{}", file_path.to_string_lossy(), error)),
+        }//end matching whether we could read this file
+    }//end reading each file
+    for (name, contents) in text_files.iter() {entries.push((name, contents.as_bytes().to_vec()));}
+
+    let bundle_file = File::create(bundle_path).map_err(|error| format!("Couldn't create the bundle at {}.\n{}", bundle_path.to_string_lossy(), error))?;
+    let mut zip_writer = ZipWriter::new(bundle_file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, contents) in entries.iter() {
+        zip_writer.start_file(*name, options).map_err(|error| format!("Couldn't add {} to the bundle.\n{}", name, error))?;
+        zip_writer.write_all(contents).map_err(|error| format!("Couldn't add {} to the bundle.\n{}", name, error))?;
+    }//end adding each entry to the bundle
+    zip_writer.finish().map_err(|error| format!("Couldn't finish writing the bundle at {}.\n{}", bundle_path.to_string_lossy(), error))?;
+    Ok(entries.len())
+}//end write_run_bundle(bundle_path, files, text_files)
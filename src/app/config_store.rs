@@ -0,0 +1,1636 @@
+use serde::{Deserialize, Serialize};
+use std::{fs::{self, File}, io::Write, path::{Path, PathBuf}};
+
+use crate::app::paths;
+
+/// This struct is meant to store configuration inforamation
+/// in a way that is not reliant on a specific ui implementation,
+/// such that it can be passed around easily.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+#[non_exhaustive]
+pub struct ConfigStore {
+    /// Tells whether or not we should be filtering csv
+    /// data to only include rows with a specific classification.
+    pub csv_class_filter_enabled: bool,
+    /// If we're filtering input csv data to only include rows
+    /// with a specific classification, this tells us what
+    /// classification we're filtering for, such as "Sound".
+    pub csv_class_filter_filters: Vec<String>,
+    /// Tells us whether we should include columns in output
+    /// that are essentially statistics about certain columns
+    /// in the input csv.
+    pub csv_stat_columns_enabled: bool,
+    /// If we're including columns in the output that are essentially
+    /// statistics about certain columns in the input csv, this tells
+    /// us which columns in the input csv to do statistics on.
+    pub csv_stat_columns_columns: Vec<String>,
+    /// Tells us whether we should include columns in the output
+    /// about what percentage of each sample has each classification.  
+    /// So, %Sound, %Sorghum, etc.
+    pub csv_class_percent_enabled: bool,
+    /// Tells us whether we should include columns in the output
+    /// that are pulled from sieve data in the xml file. If no
+    /// xml file is loaded, then this is meaningless.
+    pub xml_sieve_cols_enabled: bool,
+    /// Gives potential information on whether this config is
+    /// personalized for a particular person.  
+    /// The handling for this is likely to be kinda jank.
+    pub personalized_config_name: String,
+    /// Tells us which column name to look for to determine class
+    /// when doing class filtering.
+    pub csv_class_filter_class: String,
+    /// The column header which we should use as the sample-id for csv data.
+    pub csv_sample_id_header: String,
+    /// The column header which we should use as the sample-id for xml data.
+    pub xml_sample_id_header: String,
+    /// Additional columns which should be included in xml output.
+    pub xml_tags_to_include: Vec<String>,
+    /// The tag which encloses a sample in the xml files.
+    /// When the parser sees a closing tag with this name,
+    /// it assumes we've reached the end of one sample.
+    pub xml_sample_closing_tag: String,
+    /// The number of rows at the end of the csv file which
+    /// should be skipped, such as a summary row appended
+    /// by the C-Grain. Trailing blank rows are not counted.
+    pub csv_footer_rows_to_skip: usize,
+    /// The 0-based index of the row in the csv file which holds
+    /// the headers. Any rows before this one are skipped.
+    pub csv_header_row: usize,
+    /// Headers to use for csv files which don't have a header row.  
+    /// If this is not empty, then the row at csv_header_row is
+    /// read as the first row of data instead of as headers.
+    pub csv_manual_headers: Vec<String>,
+    /// The order and visibility of columns in each output sheet.  
+    /// Sheets without a layout here are written with all their
+    /// columns in the default order.
+    pub output_column_layouts: Vec<OutputColumnLayout>,
+    /// The template for naming average columns in csv stat output.  
+    /// Any instance of {col} is replaced with the name of the csv column.
+    pub csv_stat_avg_header_template: String,
+    /// The template for naming standard deviation columns in csv stat output.  
+    /// Any instance of {col} is replaced with the name of the csv column.
+    pub csv_stat_std_header_template: String,
+    /// The template for naming columns in class percent output.  
+    /// Any instance of {class} is replaced with the name of the class.
+    pub csv_class_percent_header_template: String,
+    /// The name of the font to use for all cells in the output workbook.  
+    /// If this is empty, the default font of the workbook is used.
+    pub output_font_name: String,
+    /// The size of the font to use for all cells in the output workbook.  
+    /// If this is 0, the default font size of the workbook is used.
+    pub output_font_size: u16,
+    /// The version of the program which last wrote this config.  
+    /// This is used to detect when the config came from a different
+    /// version, so it can be migrated.
+    pub config_version: String,
+    /// A url or file path, such as one on a network share, which holds
+    /// information on the latest release of the program.  
+    /// If this is empty, then no update check is done on startup.
+    pub update_check_location: String,
+    /// Which kernels are counted in the denominator of class percents.
+    pub csv_class_percent_denominator: ClassPercentDenominator,
+    /// The class, such as "Debris", which is left out of class percents
+    /// when csv_class_percent_denominator is ExcludingClass.
+    pub csv_class_percent_excluded_class: String,
+    /// Filters on numeric csv columns, such as "Area > 10" or "Length <= 7.5".  
+    /// Used when csv_class_percent_denominator is PassingNumericFilters.
+    pub csv_numeric_filters: Vec<String>,
+    /// Tells us whether samples should be grouped into lots, with
+    /// a row for each lot added to the output after its samples.
+    pub lot_grouping_enabled: bool,
+    /// The column header holding the lot id of each row.  
+    /// If this is empty or not found, the lot id is taken from
+    /// the sample id, using lot_id_delimiter.
+    pub lot_id_header: String,
+    /// When the lot id is taken from the sample id, it is the part
+    /// of the sample id before the last instance of this delimiter.  
+    /// For example, with "-", the lot id of "W23-07-3" is "W23-07".
+    pub lot_id_delimiter: String,
+    /// The template for labeling lot rows in the output.  
+    /// Any instance of {lot} is replaced with the lot id.
+    pub lot_row_label_template: String,
+    /// Tells us whether we should include a sheet in the output
+    /// comparing treatments, as given by the metadata file.
+    pub treatment_sheet_enabled: bool,
+    /// The path to a csv file with metadata about each sample,
+    /// such as the treatment or variety of each sample.
+    pub metadata_file_path: String,
+    /// The column header in the metadata file which holds the
+    /// sample id, used to match metadata to csv samples.
+    pub metadata_sample_id_header: String,
+    /// The columns in the metadata file which samples are grouped
+    /// by in the treatment comparison, such as "Treatment" and "Variety".
+    pub treatment_columns: Vec<String>,
+    /// The columns in the csv data whose means and standard deviations
+    /// are compared across treatments.
+    pub treatment_metrics: Vec<String>,
+    /// Tells us whether the kernel rows of each sample, after class
+    /// filtering, should be written to their own csv file in a folder
+    /// next to the output file.
+    pub kernel_csv_export_enabled: bool,
+    /// Tells us whether the cells of each output sheet should be locked,
+    /// while still allowing sorting and filtering.
+    pub sheet_protection_enabled: bool,
+    /// The password needed to unprotect output sheets.  
+    /// If this is empty, sheets are protected without a password.
+    pub sheet_protection_password: String,
+    /// Tells us whether the csv rows which pass the class filters should
+    /// be copied into their own sheet in the output, so the output can be
+    /// archived without the input file.
+    pub input_snapshot_enabled: bool,
+    /// Tells us whether the output should have a Run Info sheet with the
+    /// program version, input files, and a checksum of the output values.
+    pub run_info_sheet_enabled: bool,
+    /// Tells us whether the output should have a Diagnostics sheet counting,
+    /// for each sample and csv stat column, the values which were non-numeric
+    /// or missing, and so were left out of the statistics.
+    pub diagnostics_sheet_enabled: bool,
+    /// Tells us whether input files have to match one of the known
+    /// C-Grain export layouts in schema::KNOWN_SCHEMAS to be loaded.
+    pub strict_schema_enabled: bool,
+    /// Tells us whether sample ids in the output workbook should be
+    /// replaced with pseudonyms, and how the pseudonyms are made.
+    pub sample_id_anonymization: SampleIdAnonymization,
+    /// The order that the preprocessing steps in the pipeline module are
+    /// run on csv data, before any output is calculated.  
+    /// Steps which aren't listed are skipped.
+    pub pipeline_steps: Vec<PipelineStep>,
+    /// Columns to rename before processing, such as "Mass -> Weight".
+    pub csv_header_aliases: Vec<String>,
+    /// Conversions to apply to numeric csv columns before processing,
+    /// such as "Weight * 1000" to go from grams to milligrams.
+    pub unit_conversions: Vec<String>,
+    /// Filters on numeric csv columns, such as "Area > 10".  
+    /// Kernels which don't pass every filter are removed before processing.
+    pub csv_row_filters: Vec<String>,
+    /// Numeric csv columns to check for outliers within each sample.  
+    /// Kernels outside 1.5 times the interquartile range of any of
+    /// these columns are removed before processing.
+    pub outlier_columns: Vec<String>,
+    /// Columns to calculate from other columns before processing,
+    /// such as "Aspect = Length / Width".
+    pub derived_columns: Vec<String>,
+    /// Saved sets of filters which can be switched between when processing.
+    pub filter_sets: Vec<FilterSet>,
+    /// The name of the filter set to use when processing, or empty to
+    /// use the class filters and row filters set in the config itself.
+    pub active_filter_set: String,
+    /// Tells us whether a separate workbook should also be written for
+    /// each value of split_output_column in the metadata file.
+    pub split_output_enabled: bool,
+    /// The metadata column whose values the output is split by,
+    /// such as "Customer" or "Location".
+    pub split_output_column: String,
+    /// The character separating values in csv files written by the
+    /// program, such as ';' for European-locale machines.
+    pub csv_output_delimiter: char,
+    /// The decimal separator of numbers in csv files written by the
+    /// program, such as ',' for European-locale machines.
+    pub csv_output_decimal_separator: char,
+    /// Presets to switch to for input files with matching names,
+    /// such as "*_sorghum_* -> Sorghum", checked in order.
+    pub profile_patterns: Vec<String>,
+    /// The screen size in mm of each xml sieve tag, with an optional
+    /// target percent, such as "filter-sieving-1 -> 4.76, 10".  
+    /// If empty, sieve columns are written as they're named in the xml.
+    pub xml_sieve_sizes: Vec<String>,
+    /// How many percentage points the sieve fractions of a sample can add
+    /// up to away from 100% before the sample is flagged, such as "2".  
+    /// If empty, sieve totals aren't checked.
+    pub xml_sieve_sum_tolerance: String,
+    /// Color spaces to add columns for before processing, calculated
+    /// from the red, green, and blue columns, such as "Lab" for L*, a*,
+    /// and b*, or "LCh from R, G, B" for Chroma and Hue Angle.
+    pub color_conversions: Vec<String>,
+    /// The csv column holding the frame, or image, each kernel was seen
+    /// in. If the loaded csv has this column, a Frame_Stats sheet is
+    /// added to the output. If empty, frame stats are never added.
+    pub csv_frame_header: String,
+    /// Tells us whether the output should have a Drift_Check sheet,
+    /// comparing the first and second halves of the run for each
+    /// csv stat column.
+    pub drift_sheet_enabled: bool,
+    /// The csv column giving the time each kernel was measured, used to
+    /// order kernels for the drift check. If empty, row order is used.
+    pub drift_timestamp_header: String,
+    /// Tells us whether flagged samples, such as those with few kernels,
+    /// should be shown for review before the output is written, so that
+    /// some can be excluded.
+    pub review_flagged_enabled: bool,
+    /// Samples with fewer kernels than this are flagged for review.
+    pub review_min_kernels: usize,
+    /// Tells us whether the program is in operator mode, where settings
+    /// are hidden and only file selection and processing are available.  
+    /// The admin password needed to leave operator mode isn't kept in the
+    /// config. Only a salted hash of it is kept, in the data directory,
+    /// with app::secrets::save_password_hash().
+    pub operator_mode_enabled: bool,
+    /// The size and position of the main window and its sections when
+    /// the program last closed. If None, the default layout is used.  
+    /// The layout saved in the session, if there is one, is used first.
+    pub window_layout: Option<WindowLayout>,
+    /// Tells us whether the program should make any sounds.  
+    /// If false, no beeps are made, whatever the other sound settings are.
+    pub sounds_enabled: bool,
+    /// Tells us whether to beep when showing an error message.
+    pub error_beep_enabled: bool,
+    /// Tells us whether to beep when processing finishes.
+    pub completion_beep_enabled: bool,
+    /// Tells us whether to show a desktop notification when a long run finishes.
+    pub desktop_notification_enabled: bool,
+    /// Runs taking at least this many seconds get a desktop notification.
+    pub desktop_notification_min_seconds: u64,
+    /// The path of a Rhai script with hooks run after data is loaded and
+    /// before each output sheet is written, or empty for no script.  
+    /// This is only used by builds with the scripting feature.
+    pub script_path: String,
+    /// Tells us whether to output the Metrics sheet.
+    pub metrics_sheet_enabled: bool,
+    /// The definition of each report metric, such as "TKW = mean(Weight) * 1000".  
+    /// See metrics::Metric::parse for the format.
+    pub metric_definitions: Vec<String>,
+    /// The names of the metrics shown on the Metrics sheet, in order.
+    pub metrics_sheet_metrics: Vec<String>,
+    /// Tells us whether the output sheets should also be written to a
+    /// json file next to the output file.
+    pub json_output_enabled: bool,
+    /// Tells us whether the output sheets should also be written to a
+    /// sqlite database next to the output file.  
+    /// This is only used by builds with the sqlite feature.
+    pub sqlite_output_enabled: bool,
+    /// The name used for the output file when none is typed, which can hold
+    /// tokens such as {csv_stem} and {date}, or empty for no template.  
+    /// See process::OUTPUT_NAME_TOKENS for the tokens.
+    pub output_name_template: String,
+    /// The name of the person running the program on this machine,
+    /// used for the {operator} token in output names.
+    pub operator_name: String,
+    /// Tells us whether the operator name should be asked for each time
+    /// the program starts, such as on machines shared by several people.
+    pub operator_prompt_enabled: bool,
+    /// The columns the user chose for each layout of csv file which
+    /// was missing columns, so files with the same headers are
+    /// renamed the same way without asking again.
+    pub header_mappings: Vec<HeaderMapping>,
+    /// What to do when the loaded files are missing columns the
+    /// enabled output needs.
+    pub missing_column_action: WarningAction,
+    /// What to do when stat columns hold text which isn't a number.
+    pub non_numeric_value_action: WarningAction,
+    /// What to do when samples in the csv file aren't in the
+    /// xml file, or the other way around.
+    pub unmatched_sample_action: WarningAction,
+    /// Tells us whether each output sheet with sample rows should get a
+    /// Flags column, listing the flag rules each sample trips.
+    pub flags_column_enabled: bool,
+    /// The rules used for the Flags column, such as "LOW_N = kernels < 50".  
+    /// See flags::FlagRule::parse for the format.
+    pub flag_rules: Vec<String>,
+    /// The formula columns added to output sheets, such as
+    /// "CSV_Stats: L/W Ratio = Avg Length / Avg Width", which are written
+    /// as excel formulas. See process::parse_formula_column for the format.
+    pub formula_columns: Vec<String>,
+    /// The folder or web address to link each sample id in the output to,
+    /// with {sample_id} in place of the sample id, or empty for no links.
+    pub sample_link_template: String,
+    /// Tells us whether merged summaries should also be pushed to a Google Sheet.  
+    /// This is only used by builds with the google-sheets feature.
+    pub google_sheets_enabled: bool,
+    /// The id of the Google Sheet to push to, from the middle of its address.
+    pub google_sheets_spreadsheet_id: String,
+    /// The path of the json key file for the service account used to
+    /// push to the Google Sheet, which needs edit access to the sheet.
+    pub google_sheets_key_path: String,
+    /// The url json about each run of a batch is posted to, such as to
+    /// update the lab dashboard, or empty to not post anything.
+    pub webhook_url: String,
+    /// Tells us whether the output should have a Merged sheet, joining
+    /// the csv stat columns and xml sieve data of each sample on one row.
+    pub merged_sheet_enabled: bool,
+    /// Tells us whether schedule_folder should be batch processed into
+    /// a combined workbook at the times in schedule_cron, while the
+    /// program is open.
+    pub schedule_enabled: bool,
+    /// The folder swept by scheduled processing.
+    pub schedule_folder: String,
+    /// When scheduled processing runs, as a cron entry such as
+    /// "0 18 * * *" for 6 pm every day.
+    pub schedule_cron: String,
+    /// How many times scheduled processing tries a run whose files are
+    /// still being written or are open in another program before giving
+    /// up and moving its files to the Quarantine folder.
+    pub schedule_retry_attempts: usize,
+    /// How many seconds scheduled processing waits between tries of a
+    /// run. Files changed more recently than this are treated as still
+    /// being written.
+    pub schedule_retry_seconds: u64,
+    /// The statistics, from stats::EXTRA_STATS, added after the average
+    /// and standard deviation of each csv stat column, such as Median or CV.
+    pub csv_stat_columns_stats: Vec<String>,
+    /// How many seconds an input file must go without changing before
+    /// it's loaded, so files still being exported aren't read half
+    /// written. If 0, files are loaded without waiting.
+    pub input_settle_seconds: u64,
+    /// Saved conventions of the people the output is sent to, such as
+    /// a partner lab using commas as decimal points, which the output
+    /// can also be written in when processing.
+    pub recipient_profiles: Vec<RecipientProfile>,
+    /// The names of the recipient profiles last chosen when processing,
+    /// which are checked by default the next time.
+    pub selected_recipient_profiles: Vec<String>,
+    /// The formats the output is written in, which is always an xlsx
+    /// workbook, and optionally csv copies of the output sheets.
+    pub output_format: OutputFormat,
+    /// The csv column, such as "Treatment" or "Plot", used to group the
+    /// rows of each sample in CSV_Stats, so there are stats for each group
+    /// within each sample. If this is empty, rows are only grouped by sample id.
+    pub csv_stat_group_header: String,
+    /// How the groups from csv_stat_group_header are shown in the output.
+    pub csv_stat_group_output: StatGroupOutput,
+    /// Tells us whether to add charts to the output workbook, which are a
+    /// stacked column chart of the class percents of each sample, and a
+    /// scatter of the average Area against the average Weight.
+    pub output_charts_enabled: bool,
+    /// A regular expression, such as `\d{8}-\d{3}`, which every sample id
+    /// should match in full, to catch barcode misreads. If this is empty,
+    /// sample ids aren't checked.
+    pub sample_id_pattern: String,
+    /// What's done with samples whose id doesn't match sample_id_pattern.
+    pub invalid_sample_id_handling: InvalidSampleIdHandling,
+    /// What to do when sample ids don't match sample_id_pattern,
+    /// on top of invalid_sample_id_handling.
+    pub invalid_sample_id_action: WarningAction,
+    /// Classes from the instrument to report as another class, in the form
+    /// of "raw -> report", such as "SorghumBroken -> Damaged". These are
+    /// applied to the csv_class_filter_class column by the ClassRemapping
+    /// step of the pipeline, so class filters and class percents use the
+    /// classes of the report.
+    pub class_remappings: Vec<String>,
+    /// The path of a class taxonomy file, which lists the child classes of
+    /// parent classes, such as the damage classes under Damaged. Class
+    /// percents get a column for each parent, totaling its children.
+    /// Relative paths are from the folder of the config file. If this is
+    /// empty, there are no parent classes.
+    pub class_taxonomy_file: String,
+    /// The template for the header written above the stat columns of each
+    /// csv column in excel output, such as "{col} (mm)", where {col} is
+    /// replaced with the name of the csv column. If this is empty, stat
+    /// columns aren't grouped.
+    pub csv_stat_column_group_template: String,
+    /// Groups of output columns to write under a shared header in excel
+    /// output, in the form of "group: column, column", such as
+    /// "Length (mm): Avg Length, Std Length, CV Length". These take the
+    /// place of groups from csv_stat_column_group_template.
+    pub output_column_groups: Vec<String>,
+    /// Whether scheduled processing processes runs again even if their
+    /// files are already in the processed manifest, instead of skipping them.
+    pub schedule_force_reprocess: bool,
+}//end struct ConfigStore
+
+/// This enum represents the different ways that the denominator
+/// of class percents can be defined, since different grading
+/// protocols define it differently.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum ClassPercentDenominator {
+    /// Percents are out of all kernels in the sample.
+    AllKernels,
+    /// Percents are out of kernels which pass all of the numeric filters.  
+    /// Kernels which don't pass are not counted in any class.
+    PassingNumericFilters,
+    /// Percents are out of kernels which aren't in the excluded class.  
+    /// Kernels in the excluded class are not counted in any class.
+    ExcludingClass,
+}//end enum ClassPercentDenominator
+
+/// This enum represents the ways that sample ids can be replaced with
+/// pseudonyms in the output, for sharing results with external
+/// collaborators without revealing grower identities.  
+/// Either way, a key file matching pseudonyms to sample ids is
+/// written next to the output.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum SampleIdAnonymization {
+    /// Sample ids are written as they are.
+    Off,
+    /// Each sample id is replaced with a hash of itself keyed with the
+    /// secret salt of this install, so the same sample has the same
+    /// pseudonym in every output.
+    StableHash,
+    /// Sample ids are numbered in order, such as ID-01, ID-02, etc.
+    SequentialCodes,
+}//end enum SampleIdAnonymization
+
+/// This enum represents the formats the output can be written in.
+/// The xlsx workbook is always written, so the csv formats are for
+/// sending the output to programs which can't read xlsx files.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum OutputFormat {
+    /// Only the xlsx workbook is written.
+    Xlsx,
+    /// Each output sheet is also written to its own csv file,
+    /// in a folder next to the output file named after it.
+    XlsxAndCsvFiles,
+    /// Every output sheet is also written to a single csv file next to
+    /// the output file, with a section for each sheet, starting with the
+    /// name of the sheet and separated by a blank line.
+    XlsxAndCsvSections,
+}//end enum OutputFormat
+
+/// This enum represents how the stats for each group within each sample
+/// are shown, when CSV_Stats rows are grouped by a second column.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum StatGroupOutput {
+    /// CSV_Stats has a row for each group in each sample, with a
+    /// column after the sample id holding the group.
+    GroupColumn,
+    /// Each group gets its own stats sheet, such as CSV_Stats_Control,
+    /// with a row for each sample in that group.
+    SheetPerGroup,
+}//end enum StatGroupOutput
+
+/// This enum represents how the sheets of a workbook written for a
+/// recipient are laid out, such as to suit a macro which reads them.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default, Deserialize, Serialize)]
+pub enum SheetLayout {
+    /// The sheets are laid out the same as the main output.
+    #[default]
+    Standard,
+    /// CSV_Stats is split into a sheet for each csv stat column, such as
+    /// Area, with a row for each sample and a column for each stat.
+    SheetPerMetric,
+}//end enum SheetLayout
+
+/// This enum represents what's done with samples whose sample id
+/// doesn't match the sample id pattern, such as a misread barcode.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum InvalidSampleIdHandling {
+    /// The sample is kept, with BAD_ID in its Flags column.
+    Flag,
+    /// The sample is left out of the output, and listed on the Run Info sheet.
+    Exclude,
+}//end enum InvalidSampleIdHandling
+
+/// This enum represents what to do about a kind of problem found in
+/// the loaded files before processing, so each lab can decide which
+/// problems should stop a run.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub enum WarningAction {
+    /// Nothing is processed, and the user is told why.
+    Abort,
+    /// The user is asked whether to process anyway.
+    Prompt,
+    /// The problem is logged, and processing goes on.
+    Log,
+}//end enum WarningAction
+
+/// This enum represents the kinds of problems which can be found in
+/// the loaded files before processing, each with its own WarningAction.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum WarningCategory {
+    /// A column needed by the enabled output isn't in the loaded file.
+    MissingColumn,
+    /// A stat column holds text which isn't a number.
+    NonNumericValue,
+    /// A sample is in only one of the csv and xml files.
+    UnmatchedSample,
+    /// A sample id doesn't match the sample id pattern.
+    InvalidSampleId,
+}//end enum WarningCategory
+
+impl WarningCategory {
+    /// Every category of warning, in the order they're checked.
+    pub const ALL: [WarningCategory; 4] = [WarningCategory::MissingColumn, WarningCategory::NonNumericValue, WarningCategory::UnmatchedSample, WarningCategory::InvalidSampleId];
+
+    /// Gets the name of this category shown to the user.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            WarningCategory::MissingColumn => "Missing Column",
+            WarningCategory::NonNumericValue => "Text in Numeric Column",
+            WarningCategory::UnmatchedSample => "Unmatched Sample",
+            WarningCategory::InvalidSampleId => "Invalid Sample Id",
+        }//end matching the name of this category
+    }//end get_name(self)
+}//end impl WarningCategory
+
+/// This enum represents the preprocessing steps which can be run on
+/// csv data before any output is calculated. The order of the steps
+/// is set in the config, so that, for example, kernels can be filtered
+/// on a derived column by putting DerivedColumns before Filters.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+#[non_exhaustive]
+pub enum PipelineStep {
+    /// Renames columns, using csv_header_aliases.
+    AliasMapping,
+    /// Changes the class of kernels to the classes of the report, using class_remappings.
+    ClassRemapping,
+    /// Converts the units of numeric columns, using unit_conversions.
+    UnitConversion,
+    /// Removes kernels which don't pass csv_row_filters.
+    Filters,
+    /// Removes outlier kernels within each sample, using outlier_columns.
+    OutlierRemoval,
+    /// Adds columns in other color spaces, using color_conversions.
+    ColorConversion,
+    /// Calculates new columns, using derived_columns.
+    DerivedColumns,
+}//end enum PipelineStep
+
+impl PipelineStep {
+    /// Every step, in the default order.
+    pub const ALL: [PipelineStep; 7] = [PipelineStep::AliasMapping, PipelineStep::ClassRemapping, PipelineStep::UnitConversion, PipelineStep::Filters, PipelineStep::OutlierRemoval, PipelineStep::ColorConversion, PipelineStep::DerivedColumns];
+
+    /// Gets the name of this step to show the user.
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            PipelineStep::AliasMapping => "Alias Mapping",
+            PipelineStep::ClassRemapping => "Class Remapping",
+            PipelineStep::UnitConversion => "Unit Conversion",
+            PipelineStep::Filters => "Filters",
+            PipelineStep::OutlierRemoval => "Outlier Removal",
+            PipelineStep::ColorConversion => "Color Conversion",
+            PipelineStep::DerivedColumns => "Derived Columns",
+        }//end matching self
+    }//end get_name(self)
+}//end impl PipelineStep
+
+impl Default for ConfigStore {
+    fn default() -> Self {
+        let class_filters_vec = vec!["Sound"];
+        let stat_columns_vec = vec!["Area","Length","Width","Thickness","Ratio","Mean Width","Volume","Weight","Light","Hue","Saturation","Red","Green","Blue"];
+        Self {
+            csv_class_filter_enabled: true,
+            csv_class_filter_filters: class_filters_vec.into_iter().map(|elem| elem.to_string()).collect(),
+            csv_stat_columns_enabled: true,
+            csv_stat_columns_columns: stat_columns_vec.into_iter().map(|elem| elem.to_string()).collect(),
+            csv_class_percent_enabled: true,
+            xml_sieve_cols_enabled: true,
+            personalized_config_name: "".to_string(),
+            csv_class_filter_class: "raw-filtered-as".to_string(),
+            csv_sample_id_header: "external-sample-id".to_string(),
+            xml_sample_id_header: "reference".to_string(),
+            xml_tags_to_include: Vec::new(),
+            xml_sample_closing_tag: "sample-result".to_string(),
+            csv_footer_rows_to_skip: 0,
+            csv_header_row: 0,
+            csv_manual_headers: Vec::new(),
+            output_column_layouts: Vec::new(),
+            csv_stat_avg_header_template: "Avg {col}".to_string(),
+            csv_stat_std_header_template: "Std {col}".to_string(),
+            csv_class_percent_header_template: "%{class}".to_string(),
+            output_font_name: "".to_string(),
+            output_font_size: 0,
+            config_version: env!("CARGO_PKG_VERSION").to_string(),
+            update_check_location: "".to_string(),
+            csv_class_percent_denominator: ClassPercentDenominator::AllKernels,
+            csv_class_percent_excluded_class: "Debris".to_string(),
+            csv_numeric_filters: Vec::new(),
+            lot_grouping_enabled: false,
+            lot_id_header: "".to_string(),
+            lot_id_delimiter: "-".to_string(),
+            lot_row_label_template: "Lot {lot}".to_string(),
+            treatment_sheet_enabled: false,
+            metadata_file_path: "".to_string(),
+            metadata_sample_id_header: "external-sample-id".to_string(),
+            treatment_columns: vec!["Treatment".to_string()],
+            treatment_metrics: vec!["Area","Length","Width","Weight"].into_iter().map(|elem| elem.to_string()).collect(),
+            kernel_csv_export_enabled: false,
+            sheet_protection_enabled: false,
+            sheet_protection_password: "".to_string(),
+            input_snapshot_enabled: false,
+            run_info_sheet_enabled: false,
+            diagnostics_sheet_enabled: false,
+            strict_schema_enabled: false,
+            sample_id_anonymization: SampleIdAnonymization::Off,
+            pipeline_steps: PipelineStep::ALL.to_vec(),
+            csv_header_aliases: Vec::new(),
+            unit_conversions: Vec::new(),
+            csv_row_filters: Vec::new(),
+            outlier_columns: Vec::new(),
+            derived_columns: Vec::new(),
+            filter_sets: Vec::new(),
+            active_filter_set: "".to_string(),
+            split_output_enabled: false,
+            split_output_column: "".to_string(),
+            csv_output_delimiter: ',',
+            csv_output_decimal_separator: '.',
+            profile_patterns: Vec::new(),
+            xml_sieve_sizes: Vec::new(),
+            xml_sieve_sum_tolerance: "2".to_string(),
+            color_conversions: Vec::new(),
+            csv_frame_header: "image-id".to_string(),
+            drift_sheet_enabled: false,
+            drift_timestamp_header: String::new(),
+            review_flagged_enabled: false,
+            review_min_kernels: 50,
+            operator_mode_enabled: false,
+            window_layout: None,
+            sounds_enabled: true,
+            error_beep_enabled: true,
+            completion_beep_enabled: false,
+            desktop_notification_enabled: false,
+            desktop_notification_min_seconds: 30,
+            script_path: "".to_string(),
+            metrics_sheet_enabled: false,
+            metric_definitions: vec![
+                "Kernel Count = count()".to_string(),
+                "%Sound = percent(Sound)".to_string(),
+                "Mean Length = mean(Length)".to_string(),
+                "Mean Width = mean(Width)".to_string(),
+                "TKW = mean(Weight) * 1000".to_string(),
+            ],
+            metrics_sheet_metrics: vec![
+                "Kernel Count".to_string(),
+                "%Sound".to_string(),
+                "TKW".to_string(),
+            ],
+            json_output_enabled: false,
+            sqlite_output_enabled: false,
+            output_name_template: "".to_string(),
+            operator_name: "".to_string(),
+            operator_prompt_enabled: false,
+            header_mappings: Vec::new(),
+            missing_column_action: WarningAction::Prompt,
+            non_numeric_value_action: WarningAction::Log,
+            unmatched_sample_action: WarningAction::Prompt,
+            flags_column_enabled: false,
+            flag_rules: vec![
+                "LOW_N = kernels < 50".to_string(),
+                "OUTLIERS_REMOVED = outliers > 0".to_string(),
+                "CHECK_FAIL = failed_checks > 0".to_string(),
+            ],
+            formula_columns: Vec::new(),
+            sample_link_template: "".to_string(),
+            google_sheets_enabled: false,
+            google_sheets_spreadsheet_id: "".to_string(),
+            google_sheets_key_path: "".to_string(),
+            webhook_url: "".to_string(),
+            merged_sheet_enabled: false,
+            schedule_enabled: false,
+            schedule_folder: "".to_string(),
+            schedule_cron: "0 18 * * *".to_string(),
+            schedule_retry_attempts: 3,
+            schedule_retry_seconds: 10,
+            csv_stat_columns_stats: Vec::new(),
+            input_settle_seconds: 2,
+            recipient_profiles: Vec::new(),
+            selected_recipient_profiles: Vec::new(),
+            output_format: OutputFormat::Xlsx,
+            csv_stat_group_header: "".to_string(),
+            csv_stat_group_output: StatGroupOutput::GroupColumn,
+            output_charts_enabled: false,
+            sample_id_pattern: "".to_string(),
+            invalid_sample_id_handling: InvalidSampleIdHandling::Flag,
+            invalid_sample_id_action: WarningAction::Prompt,
+            class_remappings: Vec::new(),
+            class_taxonomy_file: String::new(),
+            csv_stat_column_group_template: String::new(),
+            output_column_groups: Vec::new(),
+            schedule_force_reprocess: false,
+        }//end struct initialization
+    }//end default()
+}//end impl Default for ConfigStore
+
+impl ConfigStore {
+    /// Gets the names of any csv columns this config needs which
+    /// are not found in headers.
+    /// This includes the sample-id column, the class column if class
+    /// filtering or class percents are enabled, and the stat columns,
+    /// along with the column to group them by, if stat columns are enabled.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.csv_stat_columns_columns = vec!["Area".to_string(), "Hue".to_string()];
+    /// let headers = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string(), "Area".to_string()];
+    /// assert_eq!(config.get_missing_csv_headers(&headers), vec!["Hue".to_string()]);
+    ///
+    /// // other firmware's names for a column need an alias
+    /// let other_headers = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string(), "area".to_string(), "HUE".to_string()];
+    /// assert_eq!(config.get_missing_csv_headers(&other_headers), vec!["Area".to_string(), "Hue".to_string()]);
+    ///
+    /// config.csv_stat_group_header = "Treatment".to_string();
+    /// assert_eq!(config.get_missing_csv_headers(&headers), vec!["Hue".to_string(), "Treatment".to_string()]);
+    ///
+    /// config.csv_stat_columns_enabled = false;
+    /// assert!(config.get_missing_csv_headers(&headers).is_empty());
+    /// ```
+    pub fn get_missing_csv_headers(&self, headers: &Vec<String>) -> Vec<String> {
+        let mut needed_headers = vec![&self.csv_sample_id_header];
+        if self.csv_class_filter_enabled || self.csv_class_percent_enabled {needed_headers.push(&self.csv_class_filter_class);}
+        if self.csv_stat_columns_enabled {needed_headers.extend(self.csv_stat_columns_columns.iter());}
+        if self.csv_stat_columns_enabled && !self.csv_stat_group_header.trim().is_empty() {needed_headers.push(&self.csv_stat_group_header);}
+        needed_headers.into_iter()
+            .filter(|header| !headers.contains(header))
+            .cloned()
+            .collect()
+    }//end get_missing_csv_headers(self, headers)
+
+    /// Gets what should be done about warnings of the given category.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::{ConfigStore, WarningAction, WarningCategory};
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.set_warning_action(WarningCategory::UnmatchedSample, WarningAction::Abort);
+    /// assert_eq!(config.get_warning_action(WarningCategory::UnmatchedSample), WarningAction::Abort);
+    /// assert_eq!(config.get_warning_action(WarningCategory::NonNumericValue), WarningAction::Log);
+    /// ```
+    pub fn get_warning_action(&self, category: WarningCategory) -> WarningAction {
+        match category {
+            WarningCategory::MissingColumn => self.missing_column_action,
+            WarningCategory::NonNumericValue => self.non_numeric_value_action,
+            WarningCategory::UnmatchedSample => self.unmatched_sample_action,
+            WarningCategory::InvalidSampleId => self.invalid_sample_id_action,
+        }//end matching the setting for this category
+    }//end get_warning_action(self, category)
+
+    /// Sets what should be done about warnings of the given category.
+    pub fn set_warning_action(&mut self, category: WarningCategory, action: WarningAction) {
+        match category {
+            WarningCategory::MissingColumn => self.missing_column_action = action,
+            WarningCategory::NonNumericValue => self.non_numeric_value_action = action,
+            WarningCategory::UnmatchedSample => self.unmatched_sample_action = action,
+            WarningCategory::InvalidSampleId => self.invalid_sample_id_action = action,
+        }//end matching the setting for this category
+    }//end set_warning_action(self, category, action)
+
+    /// Tells whether the output gets a Flags column, which is when it's
+    /// enabled, or when samples whose id doesn't match sample_id_pattern
+    /// are flagged, so they're flagged even without any flag rules.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::{ConfigStore, InvalidSampleIdHandling};
+    ///
+    /// let mut config = ConfigStore::default();
+    /// assert!(!config.is_flags_column_needed());
+    /// config.sample_id_pattern = r"\d{8}-\d{3}".to_string();
+    /// assert!(config.is_flags_column_needed());
+    /// config.invalid_sample_id_handling = InvalidSampleIdHandling::Exclude;
+    /// assert!(!config.is_flags_column_needed());
+    /// ```
+    pub fn is_flags_column_needed(&self) -> bool {
+        self.flags_column_enabled || (!self.sample_id_pattern.trim().is_empty() && self.invalid_sample_id_handling == InvalidSampleIdHandling::Flag)
+    }//end is_flags_column_needed(self)
+
+    /// Gets a copy of this config with the class filters and row filters
+    /// of the active filter set in place of its own, so a run can use a
+    /// saved filter set without the config being changed.  
+    /// If no filter set is active, the copy is unchanged.
+    /// Returns an error if the active filter set can't be found.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::{ConfigStore, FilterSet};
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.filter_sets.push(FilterSet {
+    ///     name: "Research".to_string(),
+    ///     class_filters: vec!["Sound".to_string(), "Broken".to_string()],
+    ///     row_filters: vec!["Area > 10".to_string()],
+    /// });
+    /// assert_eq!(config.with_active_filter_set().unwrap(), config);
+    ///
+    /// config.active_filter_set = "Research".to_string();
+    /// let run_config = config.with_active_filter_set().unwrap();
+    /// assert_eq!(run_config.csv_class_filter_filters, vec!["Sound".to_string(), "Broken".to_string()]);
+    /// assert_eq!(run_config.csv_row_filters, vec!["Area > 10".to_string()]);
+    /// assert!(config.csv_row_filters.is_empty());
+    ///
+    /// config.active_filter_set = "Export Grade".to_string();
+    /// assert!(config.with_active_filter_set().is_err());
+    /// ```
+    pub fn with_active_filter_set(&self) -> Result<ConfigStore,String> {
+        let mut run_config = self.clone();
+        if self.active_filter_set.is_empty() {return Ok(run_config);}
+        let filter_set = match self.filter_sets.iter().find(|filter_set| filter_set.name == self.active_filter_set) {
+            Some(filter_set) => filter_set,
+            None => return Err(format!("Couldn't find the filter set \"{}\" in the config.", self.active_filter_set)),
+        };
+        run_config.csv_class_filter_enabled = filter_set.class_filters.len() > 0;
+        run_config.csv_class_filter_filters = filter_set.class_filters.clone();
+        run_config.csv_row_filters = filter_set.row_filters.clone();
+        Ok(run_config)
+    }//end with_active_filter_set(self)
+
+    /// Gets a copy of this config with the passwords, addresses, and key
+    /// paths blanked out, for sharing the settings of a run with others,
+    /// such as in a run bundle. Blanked settings are set to "(redacted)"
+    /// if they had a value, so it's clear they were set.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.sheet_protection_password = "hunter2".to_string();
+    /// let redacted = config.without_secrets();
+    /// assert_eq!(redacted.sheet_protection_password, "(redacted)");
+    /// assert_eq!(redacted.webhook_url, "");
+    /// assert_eq!(redacted.csv_sample_id_header, config.csv_sample_id_header);
+    /// ```
+    pub fn without_secrets(&self) -> ConfigStore {
+        let mut shared_config = self.clone();
+        for secret in [&mut shared_config.sheet_protection_password, &mut shared_config.webhook_url, &mut shared_config.google_sheets_key_path, &mut shared_config.google_sheets_spreadsheet_id] {
+            if !secret.is_empty() {*secret = String::from("(redacted)");}
+        }//end blanking each secret
+        shared_config
+    }//end without_secrets(self)
+
+    /// Tells whether any of the output enabled in this config is made
+    /// from csv data, so a csv file needs to be loaded to process.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.csv_stat_columns_enabled = false;
+    /// config.csv_class_percent_enabled = false;
+    /// config.metrics_sheet_enabled = false;
+    /// config.drift_sheet_enabled = true;
+    /// assert!(config.is_csv_output_enabled());
+    /// config.drift_sheet_enabled = false;
+    /// assert_eq!(config.is_csv_output_enabled(), config.treatment_sheet_enabled || config.kernel_csv_export_enabled || config.input_snapshot_enabled || config.diagnostics_sheet_enabled || config.merged_sheet_enabled);
+    /// ```
+    pub fn is_csv_output_enabled(&self) -> bool {
+        self.csv_stat_columns_enabled || self.csv_class_percent_enabled || self.treatment_sheet_enabled || self.kernel_csv_export_enabled || self.input_snapshot_enabled || self.diagnostics_sheet_enabled || self.drift_sheet_enabled || self.metrics_sheet_enabled || self.merged_sheet_enabled
+    }//end is_csv_output_enabled(self)
+
+    /// Finds the first of profile_patterns, given as "pattern -> preset",
+    /// whose pattern matches the file name of file_path, and gets the
+    /// config of that preset. The preset keeps the profile patterns and
+    /// update check location of this config.  
+    /// Returns the name of the preset along with its config, or None if
+    /// no pattern matches. Returns an error if a profile pattern can't be
+    /// read, or names a preset which doesn't exist.
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    ///
+    /// let mut config = ConfigStore::default();
+    /// config.profile_patterns = vec!["*_sorghum_* -> Sorghum".to_string(), "*_wheat_* -> Wheat".to_string()];
+    /// let (profile_name, profile_config) = config.get_file_profile_config(&PathBuf::from("exports").join("0612_wheat_a.csv")).unwrap().unwrap();
+    /// assert_eq!(profile_name, "Wheat");
+    /// assert_eq!(profile_config.personalized_config_name, "Wheat");
+    /// assert_eq!(profile_config.profile_patterns, config.profile_patterns);
+    /// assert!(config.get_file_profile_config(&PathBuf::from("0612_barley_a.csv")).unwrap().is_none());
+    ///
+    /// config.profile_patterns = vec!["*_barley_* -> Barley".to_string()];
+    /// assert!(config.get_file_profile_config(&PathBuf::from("0612_barley_a.csv")).is_err());
+    /// ```
+    pub fn get_file_profile_config(&self, file_path: &PathBuf) -> Result<Option<(String, ConfigStore)>,String> {
+        let file_name = file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        for profile_pattern in self.profile_patterns.iter().filter(|profile_pattern| !profile_pattern.trim().is_empty()) {
+            let (pattern, preset_name) = match profile_pattern.split_once("->") {
+                Some((pattern, preset_name)) => (pattern.trim(), preset_name.trim()),
+                None => return Err(format!("Couldn't read profile pattern \"{}\". It should look like \"*_sorghum_* -> Sorghum\".", profile_pattern)),
+            };
+            if !matches_file_pattern(pattern, &file_name) {continue;}
+            let mut profile_config = get_preset_config(preset_name).ok_or(format!("There isn't a preset named \"{}\" for the profile pattern \"{}\".", preset_name, profile_pattern))?;
+            profile_config.profile_patterns = self.profile_patterns.clone();
+            profile_config.update_check_location = self.update_check_location.clone();
+            profile_config.operator_mode_enabled = self.operator_mode_enabled;
+            return Ok(Some((profile_config.personalized_config_name.clone(), profile_config)));
+        }//end checking each profile pattern
+        Ok(None)
+    }//end get_file_profile_config(self, file_path)
+}//end impl ConfigStore
+
+/// This struct stores how the user wants the columns of
+/// a particular output sheet to be arranged.  
+/// Columns in column_order are written first, in that order,
+/// followed by any other columns in their default order.  
+/// Columns in hidden_columns are not written at all.  
+/// If transposed is true, then the sheet is written with samples
+/// as columns and output columns (metrics) as rows.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub struct OutputColumnLayout {
+    /// The name of the output sheet this layout applies to, such as "CSV_Stats".
+    pub sheet_name: String,
+    /// The headers of columns in the order they should be written.
+    pub column_order: Vec<String>,
+    /// The headers of columns which should not be written.
+    pub hidden_columns: Vec<String>,
+    /// Whether samples should be written as columns instead of rows.
+    pub transposed: bool,
+}//end struct OutputColumnLayout
+
+/// The columns chosen by the user for one layout of csv file, which
+/// didn't have every column needed by their settings.  
+/// The layout is told apart by the fingerprint of its headers, so the
+/// same columns are renamed for every file with the same headers.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub struct HeaderMapping {
+    /// The fingerprint of the headers, from schema::get_header_fingerprint().
+    pub fingerprint: String,
+    /// The columns to rename, such as "Sample Name -> external-sample-id".
+    pub aliases: Vec<String>,
+}//end struct HeaderMapping
+
+/// This struct stores the size and position of the main window, along
+/// with where the dividers between its sections are, so that the layout
+/// can be restored the next time the program starts.  
+/// Divider positions are relative to the window, not the screen.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub struct WindowLayout {
+    /// The x position of the window on the screen.
+    pub x: i32,
+    /// The y position of the window on the screen.
+    pub y: i32,
+    /// The width of the window.
+    pub width: i32,
+    /// The height of the window.
+    pub height: i32,
+    /// The x position of the divider between the configuration
+    /// section and the rest of the window.
+    pub config_divider_x: i32,
+    /// The y position of the divider below the header.
+    pub header_divider_y: i32,
+    /// The y position of the divider above the dialog section.
+    pub dialog_divider_y: i32,
+}//end struct WindowLayout
+
+impl WindowLayout {
+    /// Returns true if the dividers of this layout are in order and
+    /// inside the window, leaving each section at least min_section
+    /// pixels, so that the layout can be safely restored.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::WindowLayout;
+    ///
+    /// let mut layout = WindowLayout { x: 50, y: 50, width: 700, height: 435, config_divider_x: 400, header_divider_y: 90, dialog_divider_y: 310 };
+    /// assert!(layout.is_valid(20));
+    /// layout.dialog_divider_y = 80;
+    /// assert!(!layout.is_valid(20));
+    /// layout.dialog_divider_y = 310;
+    /// layout.config_divider_x = 690;
+    /// assert!(!layout.is_valid(20));
+    /// ```
+    pub fn is_valid(&self, min_section: i32) -> bool {
+        self.width > 0 && self.height > 0
+            && self.config_divider_x >= min_section && self.config_divider_x <= self.width - min_section
+            && self.header_divider_y >= min_section
+            && self.dialog_divider_y >= self.header_divider_y + min_section
+            && self.dialog_divider_y <= self.height - min_section
+    }//end is_valid(self, min_section)
+}//end impl WindowLayout
+
+/// The name of the file the session is saved to, in the folder from
+/// paths::get_config_dir().
+pub const SESSION_FILE_NAME: &str = "session.json";
+/// How many files are kept in the list of recently opened files.
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// This struct stores the files in use when the program was last
+/// closed, along with the window layout and recently opened files,
+/// so the next start can pick up where the user left off.  
+/// It's kept in its own file instead of the config, since it changes
+/// every time the program is used, and isn't a setting to share.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionState {
+    /// The csv input file loaded when the program was closed.
+    pub csv_input_file: Option<PathBuf>,
+    /// The xml input file loaded when the program was closed.
+    pub xml_input_file: Option<PathBuf>,
+    /// The output file chosen when the program was closed.
+    pub output_file: Option<PathBuf>,
+    /// The layout of the main window when the program was closed.
+    pub window_layout: Option<WindowLayout>,
+    /// The input files opened most recently, newest first.
+    pub recent_files: Vec<PathBuf>,
+}//end struct SessionState
+
+impl SessionState {
+    /// Adds file_path to the front of the recent files, removing it from
+    /// further down the list if it was already there, then drops the
+    /// oldest files past MAX_RECENT_FILES.
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use usda_c_grain_sum::app::config_store::{SessionState, MAX_RECENT_FILES};
+    ///
+    /// let mut session = SessionState::default();
+    /// session.add_recent_file(&PathBuf::from("run1.csv"));
+    /// session.add_recent_file(&PathBuf::from("run1.xml"));
+    /// session.add_recent_file(&PathBuf::from("run1.csv"));
+    /// assert_eq!(session.recent_files, vec![PathBuf::from("run1.csv"), PathBuf::from("run1.xml")]);
+    ///
+    /// for run in 2..20 {session.add_recent_file(&PathBuf::from(format!("run{}.csv", run)));}
+    /// assert_eq!(session.recent_files.len(), MAX_RECENT_FILES);
+    /// assert_eq!(session.recent_files[0], PathBuf::from("run19.csv"));
+    /// ```
+    pub fn add_recent_file(&mut self, file_path: &Path) {
+        self.recent_files.retain(|recent_file| recent_file != file_path);
+        self.recent_files.insert(0, file_path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }//end add_recent_file(self, file_path)
+}//end impl SessionState
+
+/// This struct stores a named set of filters, such as one for
+/// export grading and one for research, which can be chosen when
+/// processing instead of the filters in the config.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub struct FilterSet {
+    /// The name shown in the Filter Set dropdown.
+    pub name: String,
+    /// The classes to filter csv rows to, like csv_class_filter_filters.  
+    /// If empty, rows aren't filtered by class.
+    pub class_filters: Vec<String>,
+    /// Filters on numeric csv columns, like csv_row_filters.
+    pub row_filters: Vec<String>,
+}//end struct FilterSet
+
+/// This struct stores the conventions one recipient of the output
+/// expects, such as the decimal places, units, and names of columns
+/// and sheets, which are applied to the output when writing a
+/// workbook for them.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Deserialize, Serialize)]
+pub struct RecipientProfile {
+    /// The name of the recipient, which is also added to the name of
+    /// the workbook written for them.
+    pub name: String,
+    /// The decimal places shown in every column, or None to keep the
+    /// decimal places of each column.
+    pub decimal_places: Option<usize>,
+    /// Conversions of output columns to other units, such as "Avg Length * 0.03937".
+    pub unit_conversions: Vec<String>,
+    /// Output columns to rename, such as "Avg Length -> Longueur moyenne".
+    pub column_renames: Vec<String>,
+    /// Output sheets to rename, such as "CSV_Stats -> Kernel Stats".
+    pub sheet_renames: Vec<String>,
+    /// How the sheets of the workbook are laid out. Profiles saved
+    /// before there were layouts use the standard layout.
+    #[serde(default)]
+    pub sheet_layout: SheetLayout,
+}//end struct RecipientProfile
+
+/// The name of the config file in which an administrator can lock settings,
+/// kept in the same directory as the regular config file.
+pub const ADMIN_CONFIG_NAME: &str = "admin";
+
+/// This struct stores settings which an administrator has locked, such as
+/// to keep several lab stations processing the same way.  
+/// Each locked setting is stored by its name in the config file, paired
+/// with the value it's locked to. Locked settings can't be changed in the
+/// GUI, and processing refuses to run with any other value.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LockedSettings {
+    /// The value of each locked setting, keyed by setting name.
+    values: serde_json::Map<String, serde_json::Value>,
+}//end struct LockedSettings
+
+impl LockedSettings {
+    /// Reads locked settings from the contents of an admin config file,
+    /// which should be a json object with the value of each locked setting,
+    /// such as `{"csv_stat_columns_columns": ["Area", "Length"]}`.  
+    /// Returns an error if a name isn't a setting, or a value has the wrong type.
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::{ConfigStore, LockedSettings};
+    ///
+    /// let locked = LockedSettings::parse(r#"{"csv_stat_columns_columns": ["Area"], "lot_grouping_enabled": true}"#).unwrap();
+    /// assert!(locked.is_locked("lot_grouping_enabled"));
+    /// assert!(!locked.is_locked("csv_class_filter_enabled"));
+    ///
+    /// let config = ConfigStore::default();
+    /// assert_eq!(locked.get_overridden(&config).unwrap(), vec!["csv_stat_columns_columns", "lot_grouping_enabled"]);
+    /// let locked_config = locked.apply(&config).unwrap();
+    /// assert_eq!(locked_config.csv_stat_columns_columns, vec!["Area"]);
+    /// assert!(locked_config.lot_grouping_enabled);
+    /// assert!(locked.get_overridden(&locked_config).unwrap().is_empty());
+    ///
+    /// assert!(LockedSettings::parse(r#"{"not_a_setting": 5}"#).is_err());
+    /// assert!(LockedSettings::parse(r#"{"lot_grouping_enabled": "yes"}"#).is_err());
+    /// ```
+    pub fn parse(json: &str) -> Result<LockedSettings,String> {
+        let values = match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(serde_json::Value::Object(values)) => values,
+            Ok(_) => return Err(String::from("The admin config doesn't hold a json object.")),
+            Err(error) => return Err(error.to_string()),
+        };
+        let default_values = get_config_values(&ConfigStore::default())?;
+        if let Some(name) = values.keys().find(|name| !default_values.contains_key(name.as_str())) {
+            return Err(format!("The admin config locks \"{}\", which isn't a setting.", name));
+        }//end if a locked name isn't a setting
+        let locked = LockedSettings { values };
+        if let Err(msg) = locked.apply(&ConfigStore::default()) {
+            return Err(format!("The admin config has a value of the wrong type.\n{}", msg));
+        }//end if the locked values don't fit their settings
+        Ok(locked)
+    }//end parse(json)
+
+    /// Returns true if the setting with the given name is locked.
+    pub fn is_locked(&self, setting_name: &str) -> bool {
+        self.values.contains_key(setting_name)
+    }//end is_locked(self, setting_name)
+
+    /// Returns true if no settings are locked.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }//end is_empty(self)
+
+    /// Returns a copy of config with each locked setting set to its locked value.
+    pub fn apply(&self, config: &ConfigStore) -> Result<ConfigStore,String> {
+        if self.values.is_empty() {return Ok(config.clone());}
+        let mut config_values = get_config_values(config)?;
+        for (name, value) in self.values.iter() {
+            config_values.insert(name.clone(), value.clone());
+        }//end setting each locked value
+        match serde_json::from_value::<ConfigStore>(serde_json::Value::Object(config_values)) {
+            Ok(locked_config) => Ok(locked_config),
+            Err(error) => Err(error.to_string()),
+        }//end matching whether the locked config can be deserialized
+    }//end apply(self, config)
+
+    /// Returns the names of the locked settings which have a different
+    /// value in config than they're locked to.
+    pub fn get_overridden(&self, config: &ConfigStore) -> Result<Vec<String>,String> {
+        let config_values = get_config_values(config)?;
+        Ok(self.values.iter()
+            .filter(|(name, value)| config_values.get(name.as_str()) != Some(value))
+            .map(|(name, _)| name.clone())
+            .collect())
+    }//end get_overridden(self, config)
+}//end impl LockedSettings
+
+/// Gets the value of each setting in config, keyed by setting name,
+/// as they would be written to the config file.
+fn get_config_values(config: &ConfigStore) -> Result<serde_json::Map<String, serde_json::Value>,String> {
+    match serde_json::to_value(config) {
+        Ok(serde_json::Value::Object(values)) => Ok(values),
+        Ok(_) => Err(String::from("The config didn't serialize to a json object.")),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether config could be serialized
+}//end get_config_values(config)
+
+/// Attempts to read the settings locked in the admin config file at admin_config_path.  
+/// If there's no file there, no settings are locked.
+pub fn try_read_locked_settings(admin_config_path: &PathBuf) -> Result<LockedSettings,String> {
+    if !admin_config_path.exists() {return Ok(LockedSettings::default());}
+    match fs::read_to_string(admin_config_path) {
+        Ok(file_contents) => LockedSettings::parse(&file_contents),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could read the admin config
+}//end try_read_locked_settings(admin_config_path)
+
+/// Gets default config which is personalized for needs of Sorghum
+pub fn get_scott_config() -> ConfigStore {
+    let mut conf = ConfigStore {
+        personalized_config_name: String::from("Sorghum"),
+        ..Default::default()
+    };
+    conf.csv_stat_columns_enabled = true;
+    conf.csv_class_filter_filters = vec!["Sorghum".to_string()];
+    conf.csv_stat_columns_columns = ["Area","Length","Width","Volume","Weight","Light","Hue","Saturation","Red","Green","Blue"].iter().map(|i| i.to_string()).collect();
+    conf.csv_class_percent_enabled = true;
+    conf.xml_sieve_cols_enabled = false;
+    return conf;
+}//end get_scott_config()
+
+/// Gets default config which is personalized for needs of Wheat
+pub fn get_rhett_config() -> ConfigStore {
+    let mut conf = ConfigStore {
+        personalized_config_name: String::from("Wheat"),
+        ..Default::default()
+    };
+    conf.csv_class_filter_filters = vec!["Sound".to_string()];
+    conf.csv_class_percent_enabled = false;
+    return conf;
+}//end get_rhett_config()
+
+/// Gets default config which is personalized for needs of Corn
+pub fn get_corn_config() -> ConfigStore {
+    let mut conf = ConfigStore {
+        personalized_config_name: String::from("Corn"),
+        ..Default::default()
+    };
+    conf.csv_stat_columns_enabled = true;
+    conf.csv_class_filter_filters = vec!["Sound".to_string()];
+    conf.csv_stat_columns_columns = ["Area","Length","Width","Thickness","Volume","Weight","Light","Hue","Saturation"].iter().map(|i| i.to_string()).collect();
+    conf.csv_class_percent_enabled = true;
+    conf.xml_sieve_cols_enabled = true;
+    return conf;
+}//end get_corn_config()
+
+/// The names of the presets which come with the program. They're saved
+/// to the preset folder the first time it's used, by seed_builtin_presets(),
+/// so they can be changed, renamed, or deleted like presets the user saved.
+pub const PRESET_NAMES: [&str; 3] = ["Wheat", "Sorghum", "Corn"];
+
+/// The name of the file which is put in the preset folder once the
+/// built-in presets have been saved there, so deleted ones don't come back.
+pub const PRESETS_SEEDED_FLAG_NAME: &str = "builtin_presets_added.flag";
+
+/// Gets the config of the built-in preset named preset_name, ignoring
+/// case, as it comes with the program, or None if there isn't one.
+pub fn get_builtin_preset_config(preset_name: &str) -> Option<ConfigStore> {
+    match preset_name.trim().to_lowercase().as_str() {
+        "wheat" => Some(get_rhett_config()),
+        "sorghum" => Some(get_scott_config()),
+        "corn" => Some(get_corn_config()),
+        _ => None,
+    }//end matching preset name to built-in preset
+}//end get_builtin_preset_config(preset_name)
+
+/// Saves each built-in preset to preset_dir, unless it has been done
+/// before, so the built-in presets can be managed like any other preset.
+/// A preset already in preset_dir with the name of a built-in preset is kept.  
+/// Returns the number of presets saved.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store;
+///
+/// let preset_dir = std::env::temp_dir().join("c_grain_sum_seed_presets_doctest");
+/// let _ = std::fs::remove_dir_all(&preset_dir);
+/// std::fs::create_dir_all(&preset_dir).unwrap();
+/// assert_eq!(config_store::seed_builtin_presets(&preset_dir), Ok(3));
+/// assert_eq!(config_store::list_presets(&preset_dir), vec!["Corn", "Sorghum", "Wheat"]);
+///
+/// // deleted presets aren't saved again
+/// std::fs::remove_file(preset_dir.join("Corn.config")).unwrap();
+/// assert_eq!(config_store::seed_builtin_presets(&preset_dir), Ok(0));
+/// assert_eq!(config_store::list_presets(&preset_dir), vec!["Sorghum", "Wheat"]);
+/// std::fs::remove_dir_all(&preset_dir).unwrap();
+/// ```
+pub fn seed_builtin_presets(preset_dir: &Path) -> Result<usize,String> {
+    let flag_path = preset_dir.join(PRESETS_SEEDED_FLAG_NAME);
+    if flag_path.exists() {return Ok(0);}
+    let existing_names = list_presets(preset_dir);
+    let mut presets_saved = 0;
+    for preset_name in PRESET_NAMES.iter() {
+        if existing_names.iter().any(|name| name.eq_ignore_ascii_case(preset_name)) {continue;}
+        let mut preset_config = get_builtin_preset_config(preset_name).unwrap_or_default();
+        preset_config.personalized_config_name = preset_name.to_string();
+        try_write_config(&preset_dir.join(format!("{}.config", preset_name)), &preset_config)
+            .map_err(|error| format!("Couldn't save the built-in preset {}.\n{}", preset_name, error))?;
+        presets_saved += 1;
+    }//end saving each built-in preset the folder doesn't have
+    if let Err(error) = fs::write(&flag_path, "") {return Err(format!("Couldn't create \"{}\".\n{}", flag_path.to_string_lossy(), error));}
+    Ok(presets_saved)
+}//end seed_builtin_presets(preset_dir)
+
+/// Gets the names of the presets saved in preset_dir, which are the
+/// .config files in it, in alphabetical order.
+pub fn list_presets(preset_dir: &Path) -> Vec<String> {
+    let mut preset_names = Vec::new();
+    if let Ok(entries) = fs::read_dir(preset_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().map(|ext| ext == "config").unwrap_or(false) {
+                if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {preset_names.push(stem.to_string());}
+            }//end if this is a config file
+        }//end checking each file in the preset folder
+    }//end if we can read the preset folder
+    preset_names.sort_by_key(|name| name.to_lowercase());
+    preset_names
+}//end list_presets(preset_dir)
+
+/// Gets the preset folder from paths::get_preset_dir(), after making
+/// sure the built-in presets have been saved to it.
+fn get_seeded_preset_dir() -> Result<PathBuf,String> {
+    let preset_dir = paths::get_preset_dir()?;
+    seed_builtin_presets(&preset_dir)?;
+    Ok(preset_dir)
+}//end get_seeded_preset_dir()
+
+/// Gets the path of the file of the preset named preset_name in
+/// preset_dir, ignoring case, or None if there isn't one.
+fn find_preset_path(preset_dir: &Path, preset_name: &str) -> Option<PathBuf> {
+    list_presets(preset_dir).into_iter()
+        .find(|name| name.eq_ignore_ascii_case(preset_name.trim()))
+        .map(|name| preset_dir.join(format!("{}.config", name)))
+}//end find_preset_path(preset_dir, preset_name)
+
+/// Checks that preset_name can be used as the name of a preset, which
+/// is also the name of its file.
+fn check_preset_name(preset_name: &str) -> Result<(),String> {
+    if preset_name.trim().is_empty() {return Err(String::from("Preset names can't be empty."));}
+    if preset_name.trim().chars().any(|c| !(c.is_alphanumeric() || c == ' ' || c == '-' || c == '_')) {
+        return Err(String::from("Preset names can only contain letters, numbers, spaces, - and _."));
+    }//end if the name can't be used as a file name
+    Ok(())
+}//end check_preset_name(preset_name)
+
+/// Gets the names of all the presets which can be chosen, which are
+/// the .config files in paths::get_preset_dir(), in alphabetical order.  
+/// If the preset folder can't be used, the built-in presets are listed instead.
+pub fn get_preset_names() -> Vec<String> {
+    match get_seeded_preset_dir() {
+        Ok(preset_dir) => list_presets(&preset_dir),
+        Err(error) => {
+            log::warn!("Couldn't read the preset folder, so only the built-in presets are listed.\n{}", error);
+            PRESET_NAMES.iter().map(|name| name.to_string()).collect()
+        },
+    }//end matching whether we can use the preset folder
+}//end get_preset_names()
+
+/// Gets the preset config named preset_name, ignoring case, or None if
+/// there isn't a preset with that name.  
+/// Presets are read from the preset folder. If the preset folder can't
+/// be used, the built-in presets are checked instead.
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store;
+///
+/// assert_eq!(config_store::get_builtin_preset_config("sorghum"), Some(config_store::get_scott_config()));
+/// assert_eq!(config_store::get_builtin_preset_config("Corn"), Some(config_store::get_corn_config()));
+/// assert_eq!(config_store::get_preset_config("Barley"), None);
+/// ```
+pub fn get_preset_config(preset_name: &str) -> Option<ConfigStore> {
+    let preset_dir = match get_seeded_preset_dir() {
+        Ok(preset_dir) => preset_dir,
+        Err(_) => return get_builtin_preset_config(preset_name),
+    };
+    let preset_path = find_preset_path(&preset_dir, preset_name)?;
+    let preset_contents = fs::read_to_string(preset_path).ok()?;
+    match migrate_config_json(&preset_contents) {
+        Ok((preset_config, _)) => Some(preset_config),
+        Err(error) => {log::warn!("Couldn't read preset {}: {}", preset_name, error); None},
+    }//end matching whether we could read the preset
+}//end get_preset_config(preset_name)
+
+/// Saves config as a preset named preset_name, replacing any preset
+/// with the same name, so that it can be chosen later.  
+/// The personalized_config_name of the saved preset is set to preset_name.  
+/// Returns the path the preset was saved to.
+pub fn try_write_preset(preset_name: &str, config: &ConfigStore) -> Result<PathBuf,String> {
+    check_preset_name(preset_name)?;
+    let preset_name = preset_name.trim();
+    let preset_dir = get_seeded_preset_dir()?;
+    // replacing a preset keeps the capitalization of the new name
+    if let Some(old_path) = find_preset_path(&preset_dir, preset_name) {
+        if let Err(error) = fs::remove_file(&old_path) {return Err(format!("Couldn't replace the preset at \"{}\".\n{}", old_path.to_string_lossy(), error));}
+    }//end if there's a preset to replace
+    let mut preset_config = config.clone();
+    preset_config.personalized_config_name = preset_name.to_string();
+    let preset_path = preset_dir.join(format!("{}.config", preset_name));
+    try_write_config(&preset_path, &preset_config)?;
+    Ok(preset_path)
+}//end try_write_preset(preset_name, config)
+
+/// Renames the preset named old_name to new_name, along with the
+/// personalized_config_name saved in it.  
+/// Returns the new path of the preset, or an error if there isn't a
+/// preset named old_name, or another preset is already named new_name.
+pub fn try_rename_preset(old_name: &str, new_name: &str) -> Result<PathBuf,String> {
+    check_preset_name(new_name)?;
+    let new_name = new_name.trim();
+    let preset_dir = get_seeded_preset_dir()?;
+    let old_path = find_preset_path(&preset_dir, old_name).ok_or(format!("There isn't a preset named \"{}\".", old_name))?;
+    if !old_name.trim().eq_ignore_ascii_case(new_name) && find_preset_path(&preset_dir, new_name).is_some() {
+        return Err(format!("There's already a preset named \"{}\", so please choose a different name.", new_name));
+    }//end if the new name is taken by another preset
+    let mut preset_config = get_preset_config(old_name).ok_or(format!("Couldn't read the preset \"{}\".", old_name))?;
+    preset_config.personalized_config_name = new_name.to_string();
+    if let Err(error) = fs::remove_file(&old_path) {return Err(format!("Couldn't rename the preset at \"{}\".\n{}", old_path.to_string_lossy(), error));}
+    let new_path = preset_dir.join(format!("{}.config", new_name));
+    try_write_config(&new_path, &preset_config)?;
+    Ok(new_path)
+}//end try_rename_preset(old_name, new_name)
+
+/// Deletes the preset named preset_name, including a built-in preset,
+/// which won't be saved to the preset folder again.
+pub fn try_delete_preset(preset_name: &str) -> Result<(),String> {
+    let preset_dir = get_seeded_preset_dir()?;
+    let preset_path = find_preset_path(&preset_dir, preset_name).ok_or(format!("There isn't a preset named \"{}\".", preset_name))?;
+    match fs::remove_file(&preset_path) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(format!("Couldn't delete the preset at \"{}\".\n{}", preset_path.to_string_lossy(), error)),
+    }//end matching whether we could delete the preset
+}//end try_delete_preset(preset_name)
+
+/// Checks whether file_name matches pattern, ignoring case.
+/// In pattern, * matches any number of characters, and ? matches
+/// exactly one character.
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::matches_file_pattern;
+///
+/// assert!(matches_file_pattern("*_sorghum_*", "2024_Sorghum_tray3.csv"));
+/// assert!(matches_file_pattern("plot?.csv", "plot7.csv"));
+/// assert!(!matches_file_pattern("plot?.csv", "plot17.csv"));
+/// assert!(!matches_file_pattern("*_wheat_*", "2024_sorghum_tray3.csv"));
+/// ```
+pub fn matches_file_pattern(pattern: &str, file_name: &str) -> bool {
+    let name: Vec<char> = file_name.to_lowercase().chars().collect();
+    // matched[idx] tells whether the pattern so far matches the first idx characters of name
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+    for pattern_char in pattern.trim().to_lowercase().chars() {
+        let mut next_matched = vec![false; name.len() + 1];
+        match pattern_char {
+            '*' => {
+                let mut any_before = false;
+                for idx in 0..=name.len() {
+                    any_before = any_before || matched[idx];
+                    next_matched[idx] = any_before;
+                }//end letting * match any number of characters
+            },
+            _ => {
+                for idx in 1..=name.len() {
+                    next_matched[idx] = matched[idx - 1] && (pattern_char == '?' || pattern_char == name[idx - 1]);
+                }//end matching one character
+            },
+        }//end matching the kind of pattern character
+        matched = next_matched;
+    }//end matching each character of the pattern
+    matched[name.len()]
+}//end matches_file_pattern(pattern, file_name)
+
+/// Attempts to determine the path to the config file.  
+/// Assumes that config file has filename of config_name and extension of .config.  
+/// The directory of the config file is determined by paths::get_config_dir().  
+/// If create_if_missing is true, and the file at path does not exist, then it will be created with default values.  
+/// If create_if_missing is false, then this function does not check whether or not the filepath exists.
+pub fn try_read_config_path(config_name: &str, create_if_missing: bool) -> Result<PathBuf, String> {
+    // config directory depends on whether we're in portable or installed mode
+    let config_path = paths::get_config_path(config_name)?;
+
+    // configs used to always be next to the exe, so bring over an old one if we find it
+    if !config_path.exists() && !paths::is_portable_mode() {
+        if let Ok(exe_dir) = paths::get_exe_dir() {
+            let mut legacy_path = exe_dir.join(config_name);
+            legacy_path.set_extension("config");
+            if legacy_path.is_file() {
+                if let Err(error) = fs::copy(&legacy_path, &config_path) {return Err(error.to_string());}
+            }//end if there's an old config next to the exe
+        }//end if we can find the exe directory
+    }//end if config doesn't exist in installed location
+
+    // depending on parameter, ensure config file exists
+    if !config_path.exists() && create_if_missing {
+        match File::create(config_path.clone()) {
+            Ok(mut file) => {
+                let default_config = ConfigStore::default();
+                match serde_json::to_string(&default_config) {
+                    Ok(serialized_config) => {
+                        match file.write_all(serialized_config.as_bytes()) {
+                            Ok(_) => (),
+                            Err(error) => return Err(error.to_string()),
+                        }//end matching whether file write was successful
+                    },
+                    Err(error) => return Err(error.to_string()),
+                }//end matching whether or not serde serialization worked
+            },
+            Err(error) => return Err(error.to_string()),
+        }//end matching if file was created
+    }//end if config_path does not exist
+
+    Ok(config_path)
+}//end try_read_config_path()
+
+/// Attempts to read contents of file at path and deserialize into ConfigStore object.
+pub fn try_read_config(config_path: &PathBuf) -> Result<ConfigStore,String> {
+    match fs::read_to_string(config_path) {
+        Ok(file_contents) => {
+            match serde_json::from_str(&file_contents) {
+                Ok(config_store) => Ok(config_store),
+                Err(error) => Err(error.to_string()),
+            }//end matching whether we can deserialize config
+        },
+        Err(error) => Err(error.to_string())
+    }//end matching whether we could read string from file
+}//end try_read_config()
+
+/// Gets the admin password saved as plain text in a config from before
+/// only its hash was kept, given the serialized contents of that config,
+/// so it can be hashed and removed from the config. If there isn't one,
+/// or it's empty, returns None.
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store;
+///
+/// assert_eq!(config_store::get_legacy_admin_password(r#"{"admin_password": "letmein"}"#), Some("letmein".to_string()));
+/// assert_eq!(config_store::get_legacy_admin_password(r#"{"admin_password": ""}"#), None);
+/// assert_eq!(config_store::get_legacy_admin_password(r#"{"operator_mode_enabled": true}"#), None);
+/// ```
+pub fn get_legacy_admin_password(old_config: &str) -> Option<String> {
+    match serde_json::from_str::<serde_json::Value>(old_config) {
+        Ok(old_values) => old_values.get("admin_password").and_then(|password| password.as_str()).filter(|password| !password.is_empty()).map(|password| password.to_string()),
+        Err(_) => None,
+    }//end matching whether the old config is json
+}//end get_legacy_admin_password(old_config)
+
+/// Attempts to migrate a config from a different version of the program,
+/// given the serialized contents of that config.  
+/// Any settings missing from the old config are filled in with their
+/// default values, and any settings no longer used are dropped.
+/// The config_version of the result is set to the current version.  
+/// Returns the migrated config, along with the names of the settings
+/// which were filled in with defaults.
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::{self, ConfigStore};
+///
+/// let old_config = r#"{"csv_class_percent_enabled": false, "removed_setting": 5}"#;
+/// let (config, added) = config_store::migrate_config_json(old_config).unwrap();
+/// assert!(!config.csv_class_percent_enabled);
+/// assert_eq!(config.csv_sample_id_header, ConfigStore::default().csv_sample_id_header);
+/// assert!(added.contains(&"csv_sample_id_header".to_string()));
+/// assert!(!added.contains(&"csv_class_percent_enabled".to_string()));
+/// assert!(config_store::migrate_config_json("not json").is_err());
+///
+/// // csv output from before there were output formats is kept
+/// let (config, _) = config_store::migrate_config_json(r#"{"csv_output_enabled": true}"#).unwrap();
+/// assert_eq!(config.output_format, config_store::OutputFormat::XlsxAndCsvFiles);
+/// ```
+pub fn migrate_config_json(old_config: &str) -> Result<(ConfigStore, Vec<String>),String> {
+    let mut old_values = match serde_json::from_str::<serde_json::Value>(old_config) {
+        Ok(serde_json::Value::Object(old_values)) => old_values,
+        Ok(_) => return Err(String::from("The config file doesn't hold a json object.")),
+        Err(error) => return Err(error.to_string()),
+    };
+    let default_values = match serde_json::to_value(ConfigStore::default()) {
+        Ok(serde_json::Value::Object(default_values)) => default_values,
+        Ok(_) => return Err(String::from("The default config didn't serialize to a json object.")),
+        Err(error) => return Err(error.to_string()),
+    };
+
+    // csv_output_enabled was replaced by output_format
+    if !old_values.contains_key("output_format") && old_values.get("csv_output_enabled") == Some(&serde_json::Value::Bool(true)) {
+        old_values.insert(String::from("output_format"), serde_json::to_value(OutputFormat::XlsxAndCsvFiles).unwrap_or_default());
+    }//end if the old config had csv output enabled
+
+    let mut added_settings = Vec::new();
+    for (key, default_value) in default_values.iter() {
+        if !old_values.contains_key(key) {
+            old_values.insert(key.clone(), default_value.clone());
+            if key != "config_version" {added_settings.push(key.clone());}
+        }//end if the old config is missing this setting
+    }//end filling in each missing setting
+
+    match serde_json::from_value::<ConfigStore>(serde_json::Value::Object(old_values)) {
+        Ok(mut config) => {
+            config.config_version = env!("CARGO_PKG_VERSION").to_string();
+            Ok((config, added_settings))
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether the filled in config can be deserialized
+}//end migrate_config_json(old_config)
+
+/// Gets the path of the session file, SESSION_FILE_NAME in the folder
+/// from paths::get_config_dir().
+pub fn get_session_path() -> Result<PathBuf,String> {
+    Ok(paths::get_config_dir()?.join(SESSION_FILE_NAME))
+}//end get_session_path()
+
+/// Attempts to read the session saved at session_path.  
+/// If there isn't a session file yet, such as the first time the
+/// program is run, an empty session is returned.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::config_store::{self, SessionState};
+///
+/// let session_path = std::env::temp_dir().join("c_grain_sum_session_doctest.json");
+/// let _ = std::fs::remove_file(&session_path);
+/// assert_eq!(config_store::try_read_session(&session_path), Ok(SessionState::default()));
+///
+/// let mut session = SessionState::default();
+/// session.csv_input_file = Some(PathBuf::from("run1.csv"));
+/// session.add_recent_file(&PathBuf::from("run1.csv"));
+/// config_store::try_write_session(&session_path, &session).unwrap();
+/// assert_eq!(config_store::try_read_session(&session_path), Ok(session));
+///
+/// // sessions missing newer fields are still read
+/// std::fs::write(&session_path, r#"{"output_file": "sum.xlsx"}"#).unwrap();
+/// assert_eq!(config_store::try_read_session(&session_path).unwrap().output_file, Some(PathBuf::from("sum.xlsx")));
+/// std::fs::remove_file(&session_path).unwrap();
+/// ```
+pub fn try_read_session(session_path: &Path) -> Result<SessionState,String> {
+    if !session_path.exists() {return Ok(SessionState::default());}
+    match fs::read_to_string(session_path) {
+        Ok(file_contents) => serde_json::from_str(&file_contents).map_err(|error| format!("Couldn't read the session file \"{}\": {}", session_path.to_string_lossy(), error)),
+        Err(error) => Err(format!("Couldn't open the session file \"{}\": {}", session_path.to_string_lossy(), error)),
+    }//end matching whether we could read the session file
+}//end try_read_session(session_path)
+
+/// Attempts to write session to session_path.
+pub fn try_write_session(session_path: &Path, session: &SessionState) -> Result<(),String> {
+    match serde_json::to_string_pretty(session) {
+        Ok(session_serial) => fs::write(session_path, session_serial).map_err(|error| format!("Couldn't write the session file \"{}\": {}", session_path.to_string_lossy(), error)),
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could serialize the session
+}//end try_write_session(session_path, session)
+
+/// Attempts to write given config_store to the given path.
+pub fn try_write_config(config_path: &PathBuf, config_store: &ConfigStore) -> Result<(),String> {
+    match File::create(config_path) {
+        Ok(mut file) => {
+            match serde_json::to_string(config_store) {
+                Ok(config_serial) => {
+                    match file.write_all(config_serial.as_bytes()) {
+                        Ok(_) => Ok(()),
+                        Err(error) => Err(error.to_string()),
+                    }//end matching whether or not write succeeded
+                },
+                Err(error) => Err(error.to_string()),
+            }//end matching whether we could serialize config
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we can see the file
+}//end try_write_config()
\ No newline at end of file
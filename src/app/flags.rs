@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use crate::{app::{config_store::{ConfigStore, InvalidSampleIdHandling, PipelineStep}, process}, model::{Data, DataVal, OutputColumn, SampleOutput}, pipeline};
+
+/// The header of the column added to each output sheet by add_flags_column().
+pub const FLAGS_HEADER: &str = "Flags";
+/// The flag given to samples whose sample id doesn't match the sample id
+/// pattern in the config, when they're flagged rather than left out.
+pub const INVALID_ID_FLAG: &str = "BAD_ID";
+
+/// What a flag rule measures about each sample.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum FlagMeasure {
+    /// The number of kernels in the sample, after the processing pipeline.
+    Kernels,
+    /// The number of kernels removed from the sample by the Outlier
+    /// Removal step of the processing pipeline.
+    Outliers,
+    /// The number of check columns in the output, such as Sieve Total
+    /// Check, which aren't empty for the sample.
+    FailedChecks,
+}//end enum FlagMeasure
+
+impl FlagMeasure {
+    /// Gets the measure written as name in a flag rule, if there is one.
+    pub fn from_name(name: &str) -> Option<FlagMeasure> {
+        match name.trim().to_lowercase().as_str() {
+            "kernels" => Some(FlagMeasure::Kernels),
+            "outliers" => Some(FlagMeasure::Outliers),
+            "failed_checks" => Some(FlagMeasure::FailedChecks),
+            _ => None,
+        }//end matching which measure name is
+    }//end from_name(name)
+}//end impl FlagMeasure
+
+/// A rule which flags samples whose measure is past a threshold, such as
+/// LOW_N for samples with fewer than 50 kernels.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FlagRule {
+    /// The flag written in the Flags column when a sample trips this rule.
+    pub name: String,
+    pub measure: FlagMeasure,
+    /// Whether samples are flagged for being below the threshold,
+    /// rather than above it.
+    pub below: bool,
+    pub threshold: f64,
+}//end struct FlagRule
+
+impl FlagRule {
+    /// Parses a rule from text in the form of "name = measure < number" or
+    /// "name = measure > number", where measure is kernels, outliers, or
+    /// failed_checks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::flags::{FlagMeasure, FlagRule};
+    ///
+    /// let low_n = FlagRule::parse("LOW_N = kernels < 50").unwrap();
+    /// assert_eq!(low_n.name, "LOW_N");
+    /// assert_eq!(low_n.measure, FlagMeasure::Kernels);
+    /// assert!(low_n.is_tripped(49.0));
+    /// assert!(!low_n.is_tripped(50.0));
+    ///
+    /// let outliers = FlagRule::parse("OUTLIERS_REMOVED = outliers > 0").unwrap();
+    /// assert!(outliers.is_tripped(2.0));
+    ///
+    /// assert!(FlagRule::parse("LOW_N kernels < 50").is_err());
+    /// assert!(FlagRule::parse("LOW_N = weight < 50").is_err());
+    /// assert!(FlagRule::parse("LOW_N = kernels = 50").is_err());
+    /// assert!(FlagRule::parse("LOW_N = kernels < fifty").is_err());
+    /// ```
+    pub fn parse(definition: &str) -> Result<FlagRule,String> {
+        let (name, rule_text) = match definition.split_once('=') {
+            Some((name, rule_text)) if !name.trim().is_empty() => (name.trim().to_string(), rule_text.trim()),
+            _ => return Err(format!("The flag rule \"{}\" should be in the form of \"name = measure < number\", such as \"LOW_N = kernels < 50\".", definition)),
+        };
+        let (measure_text, threshold_text, below) = match (rule_text.split_once('<'), rule_text.split_once('>')) {
+            (Some((measure_text, threshold_text)), None) => (measure_text, threshold_text, true),
+            (None, Some((measure_text, threshold_text))) => (measure_text, threshold_text, false),
+            _ => return Err(format!("The flag rule \"{}\" should compare its measure with < or >.", definition)),
+        };
+        let measure = match FlagMeasure::from_name(measure_text) {
+            Some(measure) => measure,
+            None => return Err(format!("The flag rule \"{}\" uses {}, but only kernels, outliers, and failed_checks can be used.", definition, measure_text.trim())),
+        };
+        let threshold = match threshold_text.trim().parse::<f64>() {
+            Ok(threshold) => threshold,
+            Err(_) => return Err(format!("Couldn't read \"{}\" in the flag rule \"{}\" as a number.", threshold_text.trim(), definition)),
+        };
+        Ok(FlagRule { name, measure, below, threshold })
+    }//end parse(definition)
+
+    /// Tells whether a sample whose measure is value trips this rule.
+    pub fn is_tripped(&self, value: f64) -> bool {
+        match self.below {
+            true => value < self.threshold,
+            false => value > self.threshold,
+        }//end matching which side of the threshold is flagged
+    }//end is_tripped(self, value)
+}//end impl FlagRule
+
+/// Parses each of the flag rules in config.
+pub fn get_flag_rules(config: &ConfigStore) -> Result<Vec<FlagRule>,String> {
+    config.flag_rules.iter()
+        .filter(|definition| !definition.trim().is_empty())
+        .map(|definition| FlagRule::parse(definition))
+        .collect()
+}//end get_flag_rules(config)
+
+/// Counts the kernels the Outlier Removal step of the processing pipeline
+/// removes from each sample of csv_data, as (sample id, kernels removed).
+/// The steps before Outlier Removal are run first, so outliers are found
+/// the same way as when processing.
+fn get_outliers_by_sample(csv_data: &Data, config: &ConfigStore) -> Result<HashMap<String, usize>,String> {
+    let mut outliers = HashMap::new();
+    let outlier_step_idx = match config.pipeline_steps.iter().position(|step| *step == PipelineStep::OutlierRemoval) {
+        Some(outlier_step_idx) if config.outlier_columns.len() > 0 => outlier_step_idx,
+        _ => return Ok(outliers),
+    };
+    let mut before_config = config.clone();
+    before_config.pipeline_steps.truncate(outlier_step_idx);
+    let (before_data, _) = pipeline::run_pipeline(csv_data, &before_config)?;
+    let (after_data, _) = pipeline::remove_outliers(&before_data, &config.outlier_columns, &config.csv_sample_id_header)?;
+    for sample_id in process::get_sample_ids(&before_data, &config.csv_sample_id_header) {
+        let count_rows = |data: &Data| data.get_header_index(&config.csv_sample_id_header)
+            .map(|col_idx| data.get_records_ref().iter().filter(|row| row.get_data(col_idx).map(|cell| cell.get_data().to_string() == sample_id).unwrap_or(false)).count())
+            .unwrap_or(0);
+        let removed = count_rows(&before_data) - count_rows(&after_data);
+        outliers.insert(sample_id, removed);
+    }//end counting the outliers of each sample
+    Ok(outliers)
+}//end get_outliers_by_sample(csv_data, config)
+
+/// Gets the flags tripped by each sample, using the flag rules in config,
+/// as (sample id, flags), with an empty list for samples which don't trip
+/// any rule. Samples are found in csv_data, and in the output sheets with
+/// check columns, such as XML_Sieve_Data.  
+/// csv_data is the csv data as loaded, before the processing pipeline, and
+/// is needed for rules on kernels and outliers. Without it, those rules
+/// are skipped.  
+/// If samples with ids which don't match the sample id pattern are flagged,
+/// they're given INVALID_ID_FLAG before any other flags.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::flags::get_sample_flags;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal, OutputColumn, SampleOutput};
+///
+/// let headers: Vec<String> = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let mut rows = Vec::new();
+/// for (sample_id, kernel_count) in [("S1", 10), ("S2", 3)] {
+///     for _ in 0..kernel_count {
+///         let cells = vec![DataCell::new(&headers[0], sample_id.to_string()), DataCell::new(&headers[1], "12.5".to_string())];
+///         rows.push(DataRow::new(rows.len(), cells));
+///     }
+/// }
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// let mut sieve_output = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Sieve Total Check", 0));
+/// sieve_output.add_row("S1", vec![DataVal::String("Off by -8.5%".to_string())]);
+/// sieve_output.add_row("S2", vec![DataVal::String(String::new())]);
+/// let output_sheets = vec![("XML_Sieve_Data".to_string(), sieve_output)];
+///
+/// let mut config = ConfigStore::default();
+/// config.flag_rules = vec!["LOW_N = kernels < 5".to_string(), "CHECK_FAIL = failed_checks > 0".to_string()];
+/// let flags = get_sample_flags(Some(&data), &output_sheets, &config).unwrap();
+/// assert_eq!(flags, vec![
+///     ("S1".to_string(), vec!["CHECK_FAIL".to_string()]),
+///     ("S2".to_string(), vec!["LOW_N".to_string()]),
+/// ]);
+///
+/// config.flag_rules = vec!["LOW_N = kernels < 3".to_string()];
+/// let flags = get_sample_flags(Some(&data), &output_sheets, &config).unwrap();
+/// assert_eq!(flags, vec![("S1".to_string(), Vec::new()), ("S2".to_string(), Vec::new())]);
+///
+/// config.sample_id_pattern = "S[0-1]".to_string();
+/// let flags = get_sample_flags(Some(&data), &output_sheets, &config).unwrap();
+/// assert_eq!(flags, vec![("S1".to_string(), Vec::new()), ("S2".to_string(), vec!["BAD_ID".to_string()])]);
+/// ```
+pub fn get_sample_flags(csv_data: Option<&Data>, output_sheets: &Vec<(String, SampleOutput)>, config: &ConfigStore) -> Result<Vec<(String, Vec<String>)>,String> {
+    let rules = get_flag_rules(config)?;
+    let sample_id_regex = match config.invalid_sample_id_handling {
+        InvalidSampleIdHandling::Flag => process::get_sample_id_regex(&config.sample_id_pattern)?,
+        InvalidSampleIdHandling::Exclude => None,
+    };
+    // (sample id, number of failed checks), in the order samples are found
+    let mut failed_checks: Vec<(String, usize)> = Vec::new();
+    let mut kernel_counts: HashMap<String, usize> = HashMap::new();
+    let mut outliers = HashMap::new();
+    if let Some(csv_data) = csv_data {
+        let (piped_csv, _) = pipeline::run_pipeline(csv_data, config)?;
+        if let Some(sample_id_col_idx) = piped_csv.get_header_index(&config.csv_sample_id_header) {
+            for row in piped_csv.get_records_ref().iter() {
+                let sample_id = row.get_data(sample_id_col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default();
+                if !kernel_counts.contains_key(&sample_id) {failed_checks.push((sample_id.clone(), 0));}
+                *kernel_counts.entry(sample_id).or_default() += 1;
+            }//end counting the kernels of each sample
+        }//end if we can find the sample id column
+        if rules.iter().any(|rule| rule.measure == FlagMeasure::Outliers) {outliers = get_outliers_by_sample(csv_data, config)?;}
+    }//end if we have csv data to count kernels from
+
+    for (_, sheet_data) in output_sheets.iter() {
+        let check_cols: Vec<usize> = sheet_data.columns.iter().enumerate()
+            .filter(|(_, column)| column.name.ends_with("Check"))
+            .map(|(col_idx, _)| col_idx)
+            .collect();
+        if check_cols.len() == 0 {continue;}
+        for output_row in sheet_data.rows.iter() {
+            let row_failures = check_cols.iter()
+                .filter(|col_idx| match output_row.cells.get(**col_idx) {
+                    Some(DataVal::String(s)) => !s.trim().is_empty(),
+                    _ => false,
+                })
+                .count();
+            match failed_checks.iter_mut().find(|(sample_id, _)| sample_id.eq(&output_row.sample_id)) {
+                Some((_, failures)) => *failures += row_failures,
+                None => failed_checks.push((output_row.sample_id.clone(), row_failures)),
+            }//end matching whether we've seen this sample already
+        }//end counting the failed checks of each row
+    }//end looking through each sheet
+
+    let mut sample_flags = Vec::new();
+    for (sample_id, failures) in failed_checks {
+        let mut flags = Vec::new();
+        if let Some(regex) = sample_id_regex.as_ref() {
+            if !regex.is_match(&sample_id) {flags.push(INVALID_ID_FLAG.to_string());}
+        }//end if sample ids are checked against a pattern
+        for rule in rules.iter() {
+            let value = match rule.measure {
+                FlagMeasure::Kernels => kernel_counts.get(&sample_id).map(|count| *count as f64),
+                FlagMeasure::Outliers => outliers.get(&sample_id).map(|count| *count as f64),
+                FlagMeasure::FailedChecks => Some(failures as f64),
+            };
+            if let Some(value) = value {
+                if rule.is_tripped(value) && !flags.contains(&rule.name) {flags.push(rule.name.clone());}
+            }//end if we know this measure for this sample
+        }//end checking each rule
+        sample_flags.push((sample_id, flags));
+    }//end flagging each sample
+    Ok(sample_flags)
+}//end get_sample_flags(csv_data, output_sheets, config)
+
+/// Adds a Flags column to the end of each sheet in output_sheets which has
+/// a row for any sample in sample_flags, giving the flags for each row.
+/// Other rows, such as lot rows, are left empty. Sheets which
+/// already have a Flags column, such as after reprocessing samples, have
+/// that column filled in again instead.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::flags::add_flags_column;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+///
+/// let mut stats = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Area", 2));
+/// stats.add_row("S1", vec![DataVal::Float(12.5)]);
+/// stats.add_row("S2", vec![DataVal::Float(13.0)]);
+/// let mut output_sheets = vec![("CSV_Stats".to_string(), stats)];
+/// let mut drift = SampleOutput::new("Column")
+///     .with_column(OutputColumn::number("Drift", 0));
+/// drift.add_row("Area", vec![DataVal::String(String::new())]);
+/// output_sheets.push(("Drift_Check".to_string(), drift));
+/// let sample_flags = vec![
+///     ("S1".to_string(), Vec::new()),
+///     ("S2".to_string(), vec!["LOW_N".to_string(), "CHECK_FAIL".to_string()]),
+/// ];
+///
+/// add_flags_column(&mut output_sheets, &sample_flags);
+/// assert_eq!(output_sheets[1].1.get_column_index("Flags"), None);
+/// let stats = &output_sheets[0].1;
+/// assert_eq!(stats.get_column_index("Flags"), Some(1));
+/// assert_eq!(stats.rows[0].cells[1], DataVal::String(String::new()));
+/// assert_eq!(stats.rows[1].cells[1], DataVal::String("LOW_N, CHECK_FAIL".to_string()));
+/// ```
+pub fn add_flags_column(output_sheets: &mut Vec<(String, SampleOutput)>, sample_flags: &Vec<(String, Vec<String>)>) {
+    for (_, sheet_data) in output_sheets.iter_mut() {
+        let flags_col_idx = match sheet_data.get_column_index(FLAGS_HEADER) {
+            Some(flags_col_idx) => flags_col_idx,
+            None => {
+                if !sheet_data.rows.iter().any(|output_row| sample_flags.iter().any(|(sample_id, _)| sample_id.eq(&output_row.sample_id))) {continue;}
+                sheet_data.add_column(OutputColumn::number(FLAGS_HEADER, 0));
+                sheet_data.columns.len() - 1
+            },
+        };
+        for output_row in sheet_data.rows.iter_mut() {
+            let flags = sample_flags.iter().find(|(sample_id, _)| sample_id.eq(&output_row.sample_id))
+                .map(|(_, flags)| flags.join(", "))
+                .unwrap_or_default();
+            output_row.cells.resize(flags_col_idx.max(output_row.cells.len()), DataVal::String(String::new()));
+            match output_row.cells.get_mut(flags_col_idx) {
+                Some(flags_cell) => *flags_cell = DataVal::String(flags),
+                None => output_row.cells.push(DataVal::String(flags)),
+            }//end matching whether this row already has a flags cell
+        }//end filling in the flags of each row
+    }//end adding the column to each sheet
+}//end add_flags_column(output_sheets, sample_flags)
@@ -0,0 +1,50 @@
+use std::{sync::mpsc, thread, time::Duration};
+
+use crate::app::config_store::ConfigStore;
+
+/// What processing needs from whoever started it, to show what happened
+/// and ask what to do about problems. The window does this for the user,
+/// while schedule::ScheduledFrontend lets scheduled processing run on a
+/// worker thread, where there's nobody to ask.
+pub trait Frontend {
+    /// Gets the settings to process with.
+    fn get_config_store(&self) -> ConfigStore;
+    /// Shows txt, such as when something has finished.
+    fn integrated_dialog_message(&mut self, txt: &str);
+    /// Shows txt as an error.
+    fn integrated_dialog_alert(&mut self, txt: &str);
+    /// Asks the yes or no question in txt, returning whether the answer was yes.
+    fn integrated_dialog_yes_no(&mut self, txt: &str) -> bool;
+    /// Asks which of options to choose, returning its index, or None if cancelled.
+    fn integrated_dialog_message_choice(&mut self, txt: &str, options: Vec<&str>) -> Option<usize>;
+    /// Adds msg to the log.
+    fn log(&mut self, msg: &str);
+    /// Shows that something is being done.
+    fn start_wait(&mut self);
+    /// Stops showing what start_wait() showed.
+    fn end_wait(&mut self);
+    /// Lets whoever started processing know that it finished.
+    fn notify_completion(&mut self, run_duration: Duration, output_name: &str);
+    /// Shows how far along work on a worker thread is.
+    fn show_progress(&mut self, fraction: f64, stage: &str);
+    /// Stops showing what show_progress() showed.
+    fn end_progress(&mut self);
+    /// Waits for up to seconds, while work is done on a worker thread.
+    fn wait_for(&self, seconds: f64);
+}//end trait Frontend
+
+/// Runs work on a worker thread, such as reading or processing a large
+/// file, so the window stays responsive. The progress work reports, as
+/// (fraction done, step), is shown with gui.show_progress() until it finishes.  
+/// Returns what work returned, or an error if the worker thread panicked.
+pub fn run_in_background<T: Send + 'static>(gui: &mut impl Frontend, work: impl FnOnce(&dyn Fn(f64, &str)) -> T + Send + 'static) -> Result<T,String> {
+    let (progress_sender, progress_receiver) = mpsc::channel::<(f64, String)>();
+    let worker = thread::spawn(move || work(&|fraction, step| {let _ = progress_sender.send((fraction, step.to_string()));}));
+    gui.show_progress(0.0, "Starting");
+    while !worker.is_finished() {
+        gui.wait_for(0.05);
+        while let Ok((fraction, step)) = progress_receiver.try_recv() {gui.show_progress(fraction, &step);}
+    }//end handling events until the worker is done
+    gui.end_progress();
+    worker.join().map_err(|_| String::from("The worker thread stopped unexpectedly. There might be more information in the console."))
+}//end run_in_background(gui, work)
\ No newline at end of file
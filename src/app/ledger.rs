@@ -0,0 +1,224 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs::{self, OpenOptions}, io::Write, path::{Path, PathBuf}, time::Duration};
+
+use crate::{app::{config_store::ConfigStore, lock::FileLock, manifest, paths, secrets, summary::RunSummary}, model::SampleOutput};
+
+/// The name of the ledger file kept in the data directory.
+pub const LEDGER_FILE_NAME: &str = "run_ledger.jsonl";
+
+/// How long append_ledger_entry() waits for another copy of the program
+/// to finish adding to the ledger.
+pub const LEDGER_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An input file of a run recorded in the ledger.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LedgerInput {
+    /// The path the input file had when it was processed.
+    pub path: String,
+    /// The hash of the contents of the input file, from manifest::hash_bytes().
+    pub hash: String,
+}//end struct LedgerInput
+
+/// A single successful run, stored as one line of json in the ledger.
+/// Each entry has the checksum of the entry before it, so editing or
+/// removing a line in the middle of the ledger can be noticed.  
+/// Checksums are HMAC-SHA256 keyed with the ledger key of this install,
+/// so someone editing the ledger can't work out new checksums to match.
+#[derive(Clone, PartialEq, Debug, Deserialize, Serialize)]
+pub struct LedgerEntry {
+    /// When the run finished, such as "2024-06-12 18:04:11 UTC".
+    pub timestamp: String,
+    /// Each input file read by the run, with the hash of its contents.
+    pub inputs: Vec<LedgerInput>,
+    /// The path of the output file written by the run.
+    pub output_path: String,
+    /// The hash of every setting used for the run, so runs with the
+    /// same settings can be told apart from runs with different ones.
+    pub config_hash: String,
+    /// Key numbers from the run, such as "Samples" or "%Sound".
+    pub metrics: BTreeMap<String, f64>,
+    /// The checksum of the entry before this one, or empty for the first entry.
+    pub previous_checksum: String,
+    /// The checksum of this entry, from get_checksum().
+    pub checksum: String,
+}//end struct LedgerEntry
+
+impl LedgerEntry {
+    /// Creates an entry for a run which read input_files and wrote
+    /// output_path with config. Each input file is hashed, so an error is
+    /// returned if one can't be read. The checksums are filled in once the
+    /// entry is added to the ledger with append_ledger_entry().
+    pub fn new(timestamp: &str, input_files: &Vec<&PathBuf>, output_path: &Path, config: &ConfigStore, metrics: BTreeMap<String, f64>) -> Result<LedgerEntry,String> {
+        let mut inputs = Vec::new();
+        for input_file in input_files.iter() {
+            inputs.push(LedgerInput { path: input_file.to_string_lossy().into_owned(), hash: manifest::hash_file(input_file)? });
+        }//end hashing each input file
+        let config_serial = serde_json::to_string(config).map_err(|error| format!("Couldn't write the settings to json to hash them.\n{}", error))?;
+        Ok(LedgerEntry {
+            timestamp: timestamp.to_string(),
+            inputs,
+            output_path: output_path.to_string_lossy().into_owned(),
+            config_hash: manifest::hash_bytes(config_serial.as_bytes()),
+            metrics,
+            previous_checksum: String::new(),
+            checksum: String::new(),
+        })
+    }//end new(timestamp, input_files, output_path, config, metrics)
+
+    /// Gets the checksum of this entry, which is the HMAC-SHA256 of its json,
+    /// with the checksum itself left empty, keyed with key.
+    pub fn get_checksum(&self, key: &[u8]) -> Result<String,String> {
+        let mut unsigned = self.clone();
+        unsigned.checksum = String::new();
+        let unsigned_serial = serde_json::to_string(&unsigned).map_err(|error| format!("Couldn't write the ledger entry to json to get its checksum.\n{}", error))?;
+        secrets::get_keyed_hash(key, unsigned_serial.as_bytes())
+    }//end get_checksum(self, key)
+
+    /// Tells whether the checksum of this entry was made by an older
+    /// version, which used an unkeyed 16 character hash that anyone
+    /// could recalculate after editing the entry.
+    pub fn has_legacy_checksum(&self) -> bool {self.checksum.len() == 16}
+}//end impl LedgerEntry
+
+/// Gets the key numbers of a run for its ledger entry, which are the
+/// number of samples in output_sheets, along with the number of kernels
+/// and the overall class percent from run_summary, if there is one.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::ledger::get_ledger_metrics;
+/// use usda_c_grain_sum::app::summary::RunSummary;
+/// use usda_c_grain_sum::model::{DataVal, SampleOutput};
+///
+/// let mut stats = SampleOutput::new("external-sample-id");
+/// stats.add_row("S1", vec![DataVal::Float(1.0)]);
+/// stats.add_row("S2", vec![DataVal::Float(2.0)]);
+/// let mut sieve = SampleOutput::new("external-sample-id");
+/// sieve.add_row("S3", vec![DataVal::Float(3.0)]);
+/// let output_sheets = vec![("CSV_Stats".to_string(), stats), ("XML_Sieve_Data".to_string(), sieve)];
+/// let run_summary = RunSummary { sample_count: 2, kernel_count: 40, class_name: "Sound".to_string(), overall_percent: 92.5, flagged_samples: Vec::new() };
+///
+/// let metrics = get_ledger_metrics(&output_sheets, Some(&run_summary));
+/// assert_eq!(metrics["Samples"], 3.0);
+/// assert_eq!(metrics["Kernels"], 40.0);
+/// assert_eq!(metrics["%Sound"], 92.5);
+/// assert_eq!(get_ledger_metrics(&output_sheets, None).len(), 1);
+/// ```
+pub fn get_ledger_metrics(output_sheets: &Vec<(String, SampleOutput)>, run_summary: Option<&RunSummary>) -> BTreeMap<String, f64> {
+    let mut sample_ids: Vec<&str> = output_sheets.iter().flat_map(|(_, sheet_data)| sheet_data.rows.iter().map(|row| row.sample_id.as_str())).collect();
+    sample_ids.sort();
+    sample_ids.dedup();
+    let mut metrics = BTreeMap::new();
+    metrics.insert(String::from("Samples"), sample_ids.len() as f64);
+    if let Some(run_summary) = run_summary {
+        metrics.insert(String::from("Kernels"), run_summary.kernel_count as f64);
+        metrics.insert(format!("%{}", run_summary.class_name), run_summary.overall_percent);
+    }//end if we know about the kernels of the run
+    metrics
+}//end get_ledger_metrics(output_sheets, run_summary)
+
+/// Gets the path of the ledger file in the data directory.
+pub fn get_ledger_path() -> Result<PathBuf,String> {
+    Ok(paths::get_data_dir()?.join(LEDGER_FILE_NAME))
+}//end get_ledger_path()
+
+/// Adds entry to the end of the ledger at ledger_path, creating the ledger
+/// if it doesn't exist yet. Lines already in the ledger are never changed.
+/// The checksums of entry are filled in with key, such as from
+/// secrets::get_install_salt(secrets::LEDGER_KEY_FILE_NAME), chaining it
+/// to the last entry, and the entry as written is returned.  
+/// The ledger is locked from reading the last entry until entry is added,
+/// so two copies of the program finishing runs at once can't both chain
+/// to the same entry.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::ledger::{append_ledger_entry, read_ledger, LedgerEntry};
+///
+/// let key = b"ledger key";
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_ledger_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let input_path = dir.join("run_0612.csv");
+/// std::fs::write(&input_path, "external-sample-id,Area\nS1,12.5\n").unwrap();
+/// let ledger_path = dir.join("run_ledger.jsonl");
+/// let _ = std::fs::remove_file(&ledger_path);
+///
+/// for timestamp in ["2024-06-12 18:04:11 UTC", "2024-06-13 09:30:00 UTC"] {
+///     let entry = LedgerEntry::new(timestamp, &vec![&input_path], &dir.join("sum.xlsx"), &ConfigStore::default(), BTreeMap::new()).unwrap();
+///     append_ledger_entry(&ledger_path, entry, key).unwrap();
+/// }
+/// let (entries, problems) = read_ledger(&ledger_path, key).unwrap();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[1].previous_checksum, entries[0].checksum);
+/// assert_eq!(entries[0].inputs[0].hash, entries[1].inputs[0].hash);
+/// assert!(problems.is_empty());
+///
+/// // checksums can't be worked out without the key
+/// assert_eq!(read_ledger(&ledger_path, b"another key").unwrap().1.len(), 2);
+///
+/// // editing an earlier line is noticed
+/// let edited = std::fs::read_to_string(&ledger_path).unwrap().replacen("18:04:11", "18:05:11", 1);
+/// std::fs::write(&ledger_path, edited).unwrap();
+/// let (entries, problems) = read_ledger(&ledger_path, key).unwrap();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(problems.len(), 1);
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn append_ledger_entry(ledger_path: &Path, mut entry: LedgerEntry, key: &[u8]) -> Result<LedgerEntry,String> {
+    let _ledger_lock = FileLock::wait_acquire(ledger_path, LEDGER_LOCK_TIMEOUT)?;
+    let ledger_contents = match ledger_path.exists() {
+        true => fs::read_to_string(ledger_path).map_err(|error| format!("Couldn't read the ledger at \"{}\".\n{}", ledger_path.to_string_lossy(), error))?,
+        false => String::new(),
+    };
+    entry.previous_checksum = match ledger_contents.lines().rfind(|line| !line.trim().is_empty()) {
+        Some(last_line) => match serde_json::from_str::<LedgerEntry>(last_line) {
+            Ok(last_entry) => last_entry.checksum,
+            // a damaged last line is still chained to, so it can't be quietly replaced later
+            Err(_) => secrets::get_keyed_hash(key, last_line.as_bytes())?,
+        },
+        None => String::new(),
+    };
+    entry.checksum = entry.get_checksum(key)?;
+    let entry_serial = serde_json::to_string(&entry).map_err(|error| format!("Couldn't write the ledger entry to json.\n{}", error))?;
+    let mut ledger_file = OpenOptions::new().create(true).append(true).open(ledger_path).map_err(|error| format!("Couldn't open the ledger at \"{}\".\n{}", ledger_path.to_string_lossy(), error))?;
+    writeln!(ledger_file, "{}", entry_serial).map_err(|error| format!("Couldn't add to the ledger at \"{}\".\n{}", ledger_path.to_string_lossy(), error))?;
+    Ok(entry)
+}//end append_ledger_entry(ledger_path, entry, key)
+
+/// Reads every entry in the ledger at ledger_path, oldest first.
+/// If there isn't a ledger there yet, no entries are returned.
+/// Along with the entries, returns a description of each problem found,
+/// such as a line which can't be read, an entry whose checksum doesn't
+/// match, meaning it was edited, or an entry which isn't chained to the
+/// one before it, meaning a line was removed or edited.  
+/// Checksums are checked with key, the same key the ledger was written with.
+/// Entries from older versions, whose checksums weren't keyed, are noted
+/// as problems, since their checksums can't show they weren't edited.
+pub fn read_ledger(ledger_path: &Path, key: &[u8]) -> Result<(Vec<LedgerEntry>, Vec<String>),String> {
+    if !ledger_path.exists() {return Ok((Vec::new(), Vec::new()));}
+    let ledger_contents = fs::read_to_string(ledger_path).map_err(|error| format!("Couldn't read the ledger at \"{}\".\n{}", ledger_path.to_string_lossy(), error))?;
+    let mut entries = Vec::new();
+    let mut problems = Vec::new();
+    let mut previous_checksum = String::new();
+    for (line_idx, line) in ledger_contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        match serde_json::from_str::<LedgerEntry>(line) {
+            Ok(entry) => {
+                if entry.has_legacy_checksum() {problems.push(format!("Line {} of the ledger was written by an older version, whose checksums anyone could recalculate, so it can't be checked for edits.", line_idx + 1));}
+                else if entry.checksum != entry.get_checksum(key)? {problems.push(format!("Line {} of the ledger doesn't match its checksum, so it was edited.", line_idx + 1));}
+                if entry.previous_checksum != previous_checksum {problems.push(format!("Line {} of the ledger doesn't follow the line before it, so a line before it was removed or edited.", line_idx + 1));}
+                previous_checksum = entry.checksum.clone();
+                entries.push(entry);
+            },
+            Err(error) => {
+                problems.push(format!("Couldn't read line {} of the ledger.\n{}", line_idx + 1, error));
+                previous_checksum = secrets::get_keyed_hash(key, line.as_bytes())?;
+            },
+        }//end matching whether we could read this line
+    }//end reading each line of the ledger
+    Ok((entries, problems))
+}//end read_ledger(ledger_path)
@@ -0,0 +1,197 @@
+use std::{fs::{self, OpenOptions}, io::{self, ErrorKind, Write}, path::{Path, PathBuf}, process, thread, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
+
+/// How long wait_for_file_released() waits for another program to
+/// close a file before giving up.
+pub const IN_USE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A lock on a file, such as the config or an output file, which keeps
+/// other copies of the program from writing that file at the same time.
+/// The lock is held by creating a .lock file next to the locked file,
+/// which holds the process id of the holder. The .lock file is
+/// removed when the FileLock is dropped.
+#[derive(Debug)]
+pub struct FileLock {
+    lock_path: PathBuf,
+}//end struct FileLock
+
+impl FileLock {
+    /// Gets the path of the lock file used to lock the file at path,
+    /// which is the same path with .lock added to the end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use usda_c_grain_sum::app::lock::FileLock;
+    ///
+    /// assert_eq!(FileLock::get_lock_path(&PathBuf::from("dir").join("config.config")), PathBuf::from("dir").join("config.config.lock"));
+    /// ```
+    pub fn get_lock_path(path: &Path) -> PathBuf {
+        let mut lock_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+        lock_name.push(".lock");
+        path.with_file_name(lock_name)
+    }//end get_lock_path(path)
+
+    /// Attempts to lock the file at path.
+    /// If another copy of the program already holds the lock, an error
+    /// is returned describing who holds it, suitable for showing the user.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::lock::FileLock;
+    ///
+    /// let path = std::env::temp_dir().join("c_grain_sum_lock_doctest.xlsx");
+    /// let _ = std::fs::remove_file(FileLock::get_lock_path(&path));
+    /// let lock = FileLock::try_acquire(&path).unwrap();
+    /// assert!(FileLock::try_acquire(&path).is_err());
+    /// drop(lock);
+    /// assert!(FileLock::try_acquire(&path).is_ok());
+    /// ```
+    pub fn try_acquire(path: &Path) -> Result<FileLock,String> {
+        let lock_path = FileLock::get_lock_path(path);
+        match OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+            Ok(mut lock_file) => {
+                let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs()).unwrap_or(0);
+                if let Err(error) = write!(lock_file, "{}\n{}", process::id(), started) {
+                    let _ = fs::remove_file(&lock_path);
+                    return Err(format!("Couldn't write lock file \"{}\".\n{}", lock_path.to_string_lossy(), error));
+                }//end if we couldn't write the lock file
+                Ok(FileLock { lock_path })
+            },
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+                let holder = match fs::read_to_string(&lock_path) {
+                    Ok(contents) => match contents.lines().next() {
+                        Some(pid) if !pid.trim().is_empty() => format!("another copy of this program (process {})", pid.trim()),
+                        _ => String::from("another copy of this program"),
+                    },
+                    Err(_) => String::from("another copy of this program"),
+                };
+                Err(format!("\"{}\" is in use by {}.\nIf you're sure no other copy is running, the lock may have been left behind by a crash, and you can delete \"{}\".", path.to_string_lossy(), holder, lock_path.to_string_lossy()))
+            },
+            Err(error) => Err(format!("Couldn't create lock file \"{}\".\n{}", lock_path.to_string_lossy(), error)),
+        }//end matching whether we could create the lock file
+    }//end try_acquire(path)
+
+    /// Attempts to lock the file at path, checking every tenth of a second
+    /// while another copy of the program holds the lock, such as for a file
+    /// which copies only hold for a moment while adding to it.  
+    /// Returns the error from try_acquire() if the lock is still held after timeout.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use usda_c_grain_sum::app::lock::FileLock;
+    ///
+    /// let path = std::env::temp_dir().join("c_grain_sum_wait_lock_doctest.jsonl");
+    /// let _ = std::fs::remove_file(FileLock::get_lock_path(&path));
+    /// let lock = FileLock::wait_acquire(&path, Duration::from_secs(1)).unwrap();
+    /// assert!(FileLock::wait_acquire(&path, Duration::from_millis(300)).is_err());
+    /// drop(lock);
+    /// assert!(FileLock::wait_acquire(&path, Duration::from_millis(300)).is_ok());
+    /// ```
+    pub fn wait_acquire(path: &Path, timeout: Duration) -> Result<FileLock,String> {
+        let wait_start = Instant::now();
+        loop {
+            match FileLock::try_acquire(path) {
+                Ok(lock) => return Ok(lock),
+                Err(msg) if wait_start.elapsed() >= timeout => return Err(msg),
+                Err(_) => thread::sleep(Duration::from_millis(100)),
+            }//end matching whether we could lock the file
+        }//end trying to lock the file until timeout
+    }//end wait_acquire(path, timeout)
+
+    /// Locks the file at path even if another copy of the program seems
+    /// to hold the lock, such as when a lock was left behind by a crash.
+    pub fn force_acquire(path: &Path) -> Result<FileLock,String> {
+        let lock_path = FileLock::get_lock_path(path);
+        if lock_path.exists() {
+            if let Err(error) = fs::remove_file(&lock_path) {return Err(format!("Couldn't remove old lock file \"{}\".\n{}", lock_path.to_string_lossy(), error));}
+        }//end if there's an old lock to remove
+        FileLock::try_acquire(path)
+    }//end force_acquire(path)
+}//end impl FileLock
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(error) = fs::remove_file(&self.lock_path) {
+            log::warn!("Couldn't remove lock file \"{}\": {}", self.lock_path.to_string_lossy(), error);
+        }//end if we couldn't remove the lock file
+    }//end drop(self)
+}//end impl Drop for FileLock
+
+/// Tells whether error came from a file being open in another program
+/// which doesn't share it, such as a workbook open in Excel on Windows.
+pub fn is_sharing_violation(error: &io::Error) -> bool {
+    // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION, which are other errors elsewhere
+    cfg!(windows) && matches!(error.raw_os_error(), Some(32) | Some(33))
+}//end is_sharing_violation(error)
+
+/// Tells whether the file at path is open in another program in a way
+/// that keeps it from being written, such as the last output still being
+/// open in Excel. Files which don't exist yet aren't in use.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::lock;
+///
+/// let path = std::env::temp_dir().join("c_grain_sum_in_use_doctest.xlsx");
+/// std::fs::write(&path, b"workbook").unwrap();
+/// assert!(!lock::is_file_in_use(&path));
+/// std::fs::remove_file(&path).unwrap();
+/// assert!(!lock::is_file_in_use(&path));
+/// ```
+pub fn is_file_in_use(path: &Path) -> bool {
+    // opening without truncating leaves the file as it was
+    match OpenOptions::new().write(true).open(path) {
+        Ok(_) => false,
+        Err(error) => is_sharing_violation(&error),
+    }//end matching whether we could open the file to write
+}//end is_file_in_use(path)
+
+/// Waits until the file at path is no longer in use by another program,
+/// checking every half second.  
+/// Returns whether we had to wait, or an error if the file was still in
+/// use after timeout.
+pub fn wait_for_file_released(path: &Path, timeout: Duration) -> Result<bool,String> {
+    if !is_file_in_use(path) {return Ok(false);}
+    let wait_start = Instant::now();
+    while is_file_in_use(path) {
+        if wait_start.elapsed() >= timeout {
+            return Err(format!("\"{}\" was still open in another program after waiting {} seconds.", path.to_string_lossy(), timeout.as_secs()));
+        }//end if we've waited too long
+        thread::sleep(Duration::from_millis(500));
+    }//end checking the file until it's closed
+    Ok(true)
+}//end wait_for_file_released(path, timeout)
+
+/// Gets a path next to path to write to instead of it, such as when it's
+/// open in another program, by adding the first number after it which
+/// doesn't give a file that already exists.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::lock::get_alternate_path;
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_alternate_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let path = dir.join("sum.xlsx");
+/// let _ = std::fs::remove_file(dir.join("sum (2).xlsx"));
+/// assert_eq!(get_alternate_path(&path), dir.join("sum (2).xlsx"));
+/// std::fs::write(dir.join("sum (2).xlsx"), b"workbook").unwrap();
+/// assert_eq!(get_alternate_path(&path), dir.join("sum (3).xlsx"));
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn get_alternate_path(path: &Path) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|extension| format!(".{}", extension.to_string_lossy())).unwrap_or_default();
+    let mut number = 2;
+    loop {
+        let alternate_path = path.with_file_name(format!("{} ({}){}", stem, number, extension));
+        if !alternate_path.exists() {return alternate_path;}
+        number += 1;
+    }//end trying numbers until we find a name not taken
+}//end get_alternate_path(path)
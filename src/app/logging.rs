@@ -0,0 +1,188 @@
+use std::{fs::{self, File, OpenOptions}, io::Write, path::{Path, PathBuf}, sync::Mutex};
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::app::paths;
+
+/// The name of the log file currently being written.
+pub const LOG_FILE_NAME: &str = "c-grain-sum.log";
+/// The name of the folder holding the log files.
+pub const LOG_DIR_NAME: &str = "logs";
+/// The size a log file can grow to before it's rotated out.
+pub const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// How many old log files are kept, as c-grain-sum.1.log, c-grain-sum.2.log,
+/// and so on, with c-grain-sum.1.log being the newest.
+pub const MAX_OLD_LOGS: usize = 5;
+
+/// Gets the folder where log files are kept, creating it if needed.
+/// This is a logs folder next to the exe, or in the folder from
+/// paths::get_data_dir() if the exe's folder can't be written to,
+/// such as when the program is installed in Program Files.
+pub fn get_log_dir() -> Result<PathBuf,String> {
+    let exe_log_dir = paths::get_exe_dir().map(|exe_dir| exe_dir.join(LOG_DIR_NAME));
+    if let Ok(exe_log_dir) = &exe_log_dir {
+        if fs::create_dir_all(exe_log_dir).is_ok() && is_dir_writable(exe_log_dir) {return Ok(exe_log_dir.clone());}
+    }//end if we found the exe's folder
+    let data_log_dir = paths::get_data_dir()?.join(LOG_DIR_NAME);
+    match fs::create_dir_all(&data_log_dir) {
+        Ok(_) => Ok(data_log_dir),
+        Err(error) => Err(format!("Couldn't create the log folder \"{}\": {}", data_log_dir.to_string_lossy(), error)),
+    }//end matching whether we could create the log folder
+}//end get_log_dir()
+
+/// Returns true if a log file can be created or added to in dir.
+fn is_dir_writable(dir: &Path) -> bool {
+    OpenOptions::new().create(true).append(true).open(dir.join(LOG_FILE_NAME)).is_ok()
+}//end is_dir_writable(dir)
+
+/// Gets the path of the log file currently being written, in the
+/// folder from get_log_dir().
+pub fn get_log_path() -> Result<PathBuf,String> {
+    Ok(get_log_dir()?.join(LOG_FILE_NAME))
+}//end get_log_path()
+
+/// Gets the path of the old log file with the given number in log_dir,
+/// where 1 is the newest.
+fn get_old_log_path(log_dir: &Path, number: usize) -> PathBuf {
+    let stem = LOG_FILE_NAME.trim_end_matches(".log");
+    log_dir.join(format!("{}.{}.log", stem, number))
+}//end get_old_log_path(log_dir, number)
+
+/// Rotates the log file in log_dir if it's at least max_size bytes,
+/// renaming it to c-grain-sum.1.log, moving each older log up a number,
+/// and deleting any past max_old_logs.
+/// Returns true if the log was rotated.
+///
+/// ```
+/// use usda_c_grain_sum::app::logging::{self, LOG_FILE_NAME};
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_log_rotate_doctest");
+/// let _ = std::fs::remove_dir_all(&dir);
+/// std::fs::create_dir_all(&dir).unwrap();
+/// std::fs::write(dir.join(LOG_FILE_NAME), "first run\n").unwrap();
+/// assert_eq!(logging::rotate_logs(&dir, 1024, 2), Ok(false));
+/// assert_eq!(logging::rotate_logs(&dir, 5, 2), Ok(true));
+/// std::fs::write(dir.join(LOG_FILE_NAME), "second run\n").unwrap();
+/// assert_eq!(logging::rotate_logs(&dir, 5, 2), Ok(true));
+/// std::fs::write(dir.join(LOG_FILE_NAME), "third run\n").unwrap();
+/// assert_eq!(logging::rotate_logs(&dir, 5, 2), Ok(true));
+/// assert!(!dir.join(LOG_FILE_NAME).exists());
+/// assert_eq!(std::fs::read_to_string(dir.join("c-grain-sum.1.log")).unwrap(), "third run\n");
+/// assert_eq!(std::fs::read_to_string(dir.join("c-grain-sum.2.log")).unwrap(), "second run\n");
+/// assert!(!dir.join("c-grain-sum.3.log").exists());
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn rotate_logs(log_dir: &Path, max_size: u64, max_old_logs: usize) -> Result<bool,String> {
+    let log_path = log_dir.join(LOG_FILE_NAME);
+    match fs::metadata(&log_path) {
+        Ok(metadata) if metadata.len() >= max_size => {},
+        _ => return Ok(false),
+    }//end matching whether the log is big enough to rotate
+    if max_old_logs == 0 {
+        return fs::remove_file(&log_path).map(|_| true).map_err(|error| format!("Couldn't remove the log file \"{}\": {}", log_path.to_string_lossy(), error));
+    }//end if no old logs are kept
+    let oldest_path = get_old_log_path(log_dir, max_old_logs);
+    if oldest_path.exists() {
+        if let Err(error) = fs::remove_file(&oldest_path) {return Err(format!("Couldn't remove the old log file \"{}\": {}", oldest_path.to_string_lossy(), error));}
+    }//end if there's a log to drop off the end
+    for number in (1..max_old_logs).rev() {
+        let old_path = get_old_log_path(log_dir, number);
+        if !old_path.exists() {continue;}
+        let new_path = get_old_log_path(log_dir, number + 1);
+        if let Err(error) = fs::rename(&old_path, &new_path) {return Err(format!("Couldn't rename the old log file \"{}\": {}", old_path.to_string_lossy(), error));}
+    }//end moving each old log up a number
+    match fs::rename(&log_path, get_old_log_path(log_dir, 1)) {
+        Ok(_) => Ok(true),
+        Err(error) => Err(format!("Couldn't rename the log file \"{}\": {}", log_path.to_string_lossy(), error)),
+    }//end matching whether we could rotate the current log
+}//end rotate_logs(log_dir, max_size, max_old_logs)
+
+/// Formats a log entry as a line of the log file, starting with
+/// timestamp and the level, with each line after the first indented
+/// so multi-line messages stay grouped with their entry.
+///
+/// ```
+/// use log::Level;
+/// use usda_c_grain_sum::app::logging::format_entry;
+///
+/// assert_eq!(format_entry("2024-05-30 18:00:00", Level::Info, "Config file updated!"), "2024-05-30 18:00:00 [INFO ] Config file updated!\n");
+/// assert_eq!(format_entry("2024-05-30 18:00:00", Level::Warn, "Couldn't hash a file.\nAccess is denied."), "2024-05-30 18:00:00 [WARN ] Couldn't hash a file.\n    Access is denied.\n");
+/// ```
+pub fn format_entry(timestamp: &str, level: Level, message: &str) -> String {
+    let message = message.trim().replace('\n', "\n    ");
+    format!("{} [{:<5}] {}\n", timestamp, level, message)
+}//end format_entry(timestamp, level, message)
+
+/// Gets the current local time for a log entry, such as 2024-05-30 18:00:00.
+fn get_timestamp() -> String {
+    let now = time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc());
+    now.format(time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second]")).unwrap_or_default()
+}//end get_timestamp()
+
+/// Writes log entries to the console and to a log file, rotating
+/// the log file once it reaches MAX_LOG_SIZE.
+struct FileLogger {
+    /// The folder holding the log files, or None if it couldn't be found,
+    /// in which case entries only go to the console.
+    log_dir: Option<PathBuf>,
+    /// The open log file, or None if it couldn't be opened.
+    log_file: Mutex<Option<File>>,
+}//end struct FileLogger
+
+impl FileLogger {
+    /// Opens the log file in log_dir for adding entries, creating it if needed.
+    fn open_log_file(log_dir: &Path) -> Option<File> {
+        OpenOptions::new().create(true).append(true).open(log_dir.join(LOG_FILE_NAME)).ok()
+    }//end open_log_file(log_dir)
+}//end impl FileLogger
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info
+    }//end enabled(self, metadata)
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {return;}
+        let message = record.args().to_string();
+        // the console still gets every message, for when the program is run from a terminal
+        println!("{}", message);
+        let entry = format_entry(&get_timestamp(), record.level(), &message);
+        if let Ok(mut log_file) = self.log_file.lock() {
+            let needs_rotation = log_file.as_ref().and_then(|file| file.metadata().ok()).map(|metadata| metadata.len() >= MAX_LOG_SIZE).unwrap_or(false);
+            if let (true, Some(log_dir)) = (needs_rotation, self.log_dir.as_ref()) {
+                // the file has to be closed before it can be renamed on Windows
+                *log_file = None;
+                if let Err(msg) = rotate_logs(log_dir, MAX_LOG_SIZE, MAX_OLD_LOGS) {println!("{}", msg);}
+                *log_file = FileLogger::open_log_file(log_dir);
+            }//end if the log file has gotten too big
+            if let Some(file) = log_file.as_mut() {let _ = file.write_all(entry.as_bytes());}
+        }//end if we could get the log file
+    }//end log(self, record)
+
+    fn flush(&self) {
+        if let Ok(mut log_file) = self.log_file.lock() {
+            if let Some(file) = log_file.as_mut() {let _ = file.flush();}
+        }//end if we could get the log file
+    }//end flush(self)
+}//end impl Log for FileLogger
+
+/// Starts writing everything logged with the log macros, such as
+/// log::warn!(), to the log file from get_log_path(), as well as the
+/// console. The log is rotated first if it's too big.
+/// Returns the path of the log file. If the log file can't be set up,
+/// messages still go to the console, and the reason is returned.
+pub fn init() -> Result<PathBuf,String> {
+    let log_dir = get_log_dir();
+    let rotate_result = match &log_dir {
+        Ok(log_dir) => rotate_logs(log_dir, MAX_LOG_SIZE, MAX_OLD_LOGS),
+        Err(_) => Ok(false),
+    };
+    let logger = FileLogger {
+        log_file: Mutex::new(log_dir.as_ref().ok().and_then(|log_dir| FileLogger::open_log_file(log_dir))),
+        log_dir: log_dir.clone().ok(),
+    };
+    if let Err(error) = log::set_boxed_logger(Box::new(logger)) {return Err(format!("Couldn't start logging: {}", error));}
+    log::set_max_level(LevelFilter::Info);
+    if let Err(msg) = rotate_result {log::warn!("{}", msg);}
+    Ok(log_dir?.join(LOG_FILE_NAME))
+}//end init()
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::{fs, path::{Path, PathBuf}, time::Duration};
+
+use crate::app::paths;
+
+/// The name of the manifest file kept in the data directory.
+pub const MANIFEST_FILE_NAME: &str = "processed_manifest.json";
+
+/// How long a batch waits for another batch, such as a scheduled one,
+/// to finish with the manifest before giving up.
+pub const MANIFEST_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A single input file which has already been summarized.
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    /// The hash of the contents of the input file, from hash_bytes().
+    pub hash: String,
+    /// The path the input file had when it was processed,
+    /// kept only to make the manifest readable.
+    pub input_path: String,
+    /// The path of the output file written from the input.
+    pub output_path: String,
+}//end struct ManifestEntry
+
+/// Keeps track of the input files which have already been processed,
+/// by the hash of their contents, so that running over the same folder
+/// again can skip files which have already been summarized.
+/// Because files are matched by contents, a renamed or copied file
+/// is still recognized, and an edited file is processed again.
+#[derive(Clone, PartialEq, Eq, Debug, Default, Deserialize, Serialize)]
+pub struct ProcessedManifest {
+    pub entries: Vec<ManifestEntry>,
+}//end struct ProcessedManifest
+
+impl ProcessedManifest {
+    /// Returns true if an input with the given hash has already been processed.
+    pub fn contains_hash(&self, hash: &str) -> bool {
+        self.entries.iter().any(|entry| entry.hash.eq(hash))
+    }//end contains_hash(self, hash)
+
+    /// Records that an input with the given hash was processed, replacing
+    /// any older entry with the same hash.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::manifest::{hash_bytes, ProcessedManifest};
+    ///
+    /// let mut manifest = ProcessedManifest::default();
+    /// let hash = hash_bytes(b"external-sample-id,Area\nA,10\n");
+    /// assert!(!manifest.contains_hash(&hash));
+    /// manifest.record(&hash, "in/run1.csv", "out/run1.xlsx");
+    /// manifest.record(&hash, "in/run1_copy.csv", "out/run1_copy.xlsx");
+    /// assert!(manifest.contains_hash(&hash));
+    /// assert_eq!(manifest.entries.len(), 1);
+    /// assert_eq!(manifest.entries[0].input_path, "in/run1_copy.csv");
+    /// ```
+    pub fn record(&mut self, hash: &str, input_path: &str, output_path: &str) {
+        self.entries.retain(|entry| !entry.hash.eq(hash));
+        self.entries.push(ManifestEntry {
+            hash: hash.to_string(),
+            input_path: input_path.to_string(),
+            output_path: output_path.to_string(),
+        });
+    }//end record(self, hash, input_path, output_path)
+}//end impl ProcessedManifest
+
+/// Hashes bytes with 64-bit FNV-1a, returned as 16 hex digits.
+/// This is used instead of the standard library hasher because
+/// the result needs to stay the same between versions of the program.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::manifest::hash_bytes;
+///
+/// assert_eq!(hash_bytes(b""), "cbf29ce484222325");
+/// assert_eq!(hash_bytes(b"a"), "af63dc4c8601ec8c");
+/// assert_ne!(hash_bytes(b"A,10"), hash_bytes(b"A,11"));
+/// ```
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }//end mixing in each byte
+    format!("{:016x}", hash)
+}//end hash_bytes(bytes)
+
+/// Reads the file at path and hashes its contents with hash_bytes().
+pub fn hash_file(path: &Path) -> Result<String,String> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(hash_bytes(&bytes)),
+        Err(error) => Err(format!("Couldn't read \"{}\" to hash it.\n{}", path.to_string_lossy(), error)),
+    }//end matching whether we could read the file
+}//end hash_file(path)
+
+/// Gets the path of the manifest file in the data directory.
+pub fn get_manifest_path() -> Result<PathBuf,String> {
+    Ok(paths::get_data_dir()?.join(MANIFEST_FILE_NAME))
+}//end get_manifest_path()
+
+/// Reads the manifest at manifest_path.
+/// If there isn't a manifest there yet, an empty manifest is returned.
+pub fn try_read_manifest(manifest_path: &PathBuf) -> Result<ProcessedManifest,String> {
+    if !manifest_path.exists() {return Ok(ProcessedManifest::default());}
+    match fs::read_to_string(manifest_path) {
+        Ok(file_contents) => match serde_json::from_str(&file_contents) {
+            Ok(manifest) => Ok(manifest),
+            Err(error) => Err(format!("Couldn't read the manifest at \"{}\".\n{}", manifest_path.to_string_lossy(), error)),
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could read the manifest file
+}//end try_read_manifest(manifest_path)
+
+/// Writes manifest to manifest_path, replacing any manifest already there.
+pub fn try_write_manifest(manifest_path: &PathBuf, manifest: &ProcessedManifest) -> Result<(),String> {
+    match serde_json::to_string_pretty(manifest) {
+        Ok(manifest_serial) => match fs::write(manifest_path, manifest_serial) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!("Couldn't write the manifest to \"{}\".\n{}", manifest_path.to_string_lossy(), error)),
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could serialize the manifest
+}//end try_write_manifest(manifest_path, manifest)
@@ -0,0 +1,195 @@
+use crate::{app::{config_store::ConfigStore, process}, model::{Data, DataRow, DataVal}};
+
+/// How the value of a metric is calculated from the kernels of one sample.
+#[derive(Clone, PartialEq, Debug)]
+#[non_exhaustive]
+pub enum MetricCalc {
+    /// The number of kernels in the sample.
+    Count,
+    /// The average of a column, over the kernels passing the class filter.
+    Mean(String),
+    /// The standard deviation of a column, over the kernels passing the class filter.
+    Stdev(String),
+    /// The fraction of kernels in the sample with the given class.
+    ClassPercent(String),
+}//end enum MetricCalc
+
+/// A named value calculated for each sample, such as %Sound, Mean Length,
+/// or TKW, defined once along with how it's calculated and formatted,
+/// so sheets can refer to it by name.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Metric {
+    /// The name used to refer to the metric, also used as the column header.
+    pub name: String,
+    pub calc: MetricCalc,
+    /// The number the calculated value is multiplied by, such as 1000
+    /// to get thousand kernel weight from the mean weight of a kernel.
+    pub factor: f64,
+    /// The number of decimal places to show.
+    pub decimals: usize,
+}//end struct Metric
+
+impl Metric {
+    /// Parses a metric from text in the form of "name = calc", where calc is
+    /// count(), mean(column), std(column), or percent(class).
+    /// The calc can be followed by "* number" or "/ number" to scale it, and
+    /// then by ", decimals" to choose the number of decimal places to show.
+    /// Otherwise, means and standard deviations use the same decimal places as
+    /// CSV_Stats, percents use 1, and counts use 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::metrics::{Metric, MetricCalc};
+    ///
+    /// let tkw = Metric::parse("TKW = mean(Weight) * 1000, 1").unwrap();
+    /// assert_eq!(tkw.name, "TKW");
+    /// assert_eq!(tkw.calc, MetricCalc::Mean("Weight".to_string()));
+    /// assert_eq!((tkw.factor, tkw.decimals), (1000.0, 1));
+    ///
+    /// let sound = Metric::parse("%Sound = percent(Sound)").unwrap();
+    /// assert_eq!(sound.calc, MetricCalc::ClassPercent("Sound".to_string()));
+    /// assert!(sound.is_percent());
+    /// assert_eq!(Metric::parse("Mean Weight = mean(Weight)").unwrap().decimals, 4);
+    /// assert_eq!(Metric::parse("Kernels = count()").unwrap().calc, MetricCalc::Count);
+    ///
+    /// assert!(Metric::parse("TKW mean(Weight)").is_err());
+    /// assert!(Metric::parse("TKW = median(Weight)").is_err());
+    /// assert!(Metric::parse("TKW = mean(Weight) / 0").is_err());
+    /// ```
+    pub fn parse(definition: &str) -> Result<Metric,String> {
+        let (name, calc_text) = match definition.split_once('=') {
+            Some((name, calc_text)) if !name.trim().is_empty() => (name.trim().to_string(), calc_text.trim()),
+            _ => return Err(format!("The metric \"{}\" should be in the form of \"name = calc\", such as \"TKW = mean(Weight) * 1000\".", definition)),
+        };
+        let (calc_text, decimals_text) = match calc_text.rsplit_once(',') {
+            Some((calc_text, decimals_text)) => (calc_text.trim(), Some(decimals_text.trim())),
+            None => (calc_text, None),
+        };
+        let (func_text, scale_text) = match calc_text.find(')') {
+            Some(close_idx) => (&calc_text[..close_idx + 1], calc_text[close_idx + 1..].trim()),
+            None => return Err(format!("Couldn't find the closing parenthesis in the metric \"{}\".", definition)),
+        };
+        let (func_name, arg) = match func_text.split_once('(') {
+            Some((func_name, arg)) => (func_name.trim().to_lowercase(), arg.trim_end_matches(')').trim().to_string()),
+            None => return Err(format!("Couldn't find the opening parenthesis in the metric \"{}\".", definition)),
+        };
+        let calc = match (func_name.as_str(), arg.is_empty()) {
+            ("count", _) => MetricCalc::Count,
+            ("mean", false) => MetricCalc::Mean(arg),
+            ("std", false) => MetricCalc::Stdev(arg),
+            ("percent", false) => MetricCalc::ClassPercent(arg),
+            ("mean" | "std" | "percent", true) => return Err(format!("The {} in the metric \"{}\" needs a column or class in the parentheses.", func_name, definition)),
+            _ => return Err(format!("The metric \"{}\" uses {}, but only count(), mean(column), std(column), and percent(class) can be used.", definition, func_name)),
+        };
+        let factor = match scale_text {
+            "" => 1.0,
+            scale_text => {
+                let (op, number_text) = scale_text.split_at(1);
+                let number = match number_text.trim().parse::<f64>() {
+                    Ok(number) => number,
+                    Err(_) => return Err(format!("Couldn't read \"{}\" in the metric \"{}\" as a number.", number_text.trim(), definition)),
+                };
+                match op {
+                    "*" => number,
+                    "/" if number == 0.0 => return Err(format!("The metric \"{}\" divides by zero.", definition)),
+                    "/" => 1.0 / number,
+                    _ => return Err(format!("The metric \"{}\" can only be scaled with * or /.", definition)),
+                }//end matching whether we multiply or divide
+            },
+        };
+        let decimals = match decimals_text {
+            Some(decimals_text) => match decimals_text.parse::<usize>() {
+                Ok(decimals) => decimals,
+                Err(_) => return Err(format!("Couldn't read \"{}\" in the metric \"{}\" as a number of decimal places.", decimals_text, definition)),
+            },
+            None => match &calc {
+                MetricCalc::Count => 0,
+                MetricCalc::Mean(column) | MetricCalc::Stdev(column) => process::get_stat_decimal_places(column),
+                MetricCalc::ClassPercent(_) => 1,
+            },
+        };
+        Ok(Metric { name, calc, factor, decimals })
+    }//end parse(definition)
+
+    /// Tells whether the value of this metric is a percent, which is
+    /// stored as a fraction and shown out of 100.
+    pub fn is_percent(&self) -> bool {
+        matches!(self.calc, MetricCalc::ClassPercent(_))
+    }//end is_percent(self)
+
+    /// Calculates this metric for one sample.
+    /// sample_rows are all the kernels in the sample, while filtered_rows are
+    /// the kernels passing the class filter, both taken from data.
+    /// If no kernels pass the class filter, means and standard deviations are left empty.
+    pub fn calculate(&self, data: &Data, config: &ConfigStore, sample_rows: &Vec<&DataRow>, filtered_rows: &Vec<&DataRow>) -> Result<DataVal,String> {
+        let get_col_idx = |column: &str| data.get_header_index(column)
+            .ok_or(format!("Couldn't find the column \"{}\" used by the metric \"{}\".", column, self.name));
+        let value = match &self.calc {
+            MetricCalc::Count => sample_rows.len() as f64,
+            MetricCalc::Mean(column) => {
+                let col_idx = get_col_idx(column)?;
+                if filtered_rows.len() == 0 {return Ok(DataVal::String(String::new()));}
+                process::get_col_avg_sngl(filtered_rows, col_idx)?
+            },
+            MetricCalc::Stdev(column) => {
+                let col_idx = get_col_idx(column)?;
+                if filtered_rows.len() == 0 {return Ok(DataVal::String(String::new()));}
+                process::get_col_stdev_sngl(filtered_rows, col_idx)?
+            },
+            MetricCalc::ClassPercent(class) => {
+                let class_idx = get_col_idx(&config.csv_class_filter_class)?;
+                if sample_rows.len() == 0 {return Ok(DataVal::String(String::new()));}
+                let class_count = sample_rows.iter()
+                    .filter(|row| row.get_data(class_idx).is_some_and(|cell| cell.get_data().to_string().eq(class)))
+                    .count();
+                class_count as f64 / sample_rows.len() as f64
+            },
+        };
+        match self.calc {
+            MetricCalc::Count if self.factor == 1.0 => Ok(DataVal::Int(sample_rows.len() as i64)),
+            _ => Ok(DataVal::Float(value * self.factor)),
+        }//end matching whether the value is a whole number
+    }//end calculate(self, data, config, sample_rows, filtered_rows)
+}//end impl Metric
+
+/// Gets every metric defined in config.metric_definitions, which is the
+/// one place metrics are defined. Sheets refer to these metrics by name.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::metrics::get_metric_registry;
+///
+/// let mut config = ConfigStore::default();
+/// config.metric_definitions = vec!["TKW = mean(Weight) * 1000".to_string(), "Kernels = count()".to_string()];
+/// let registry = get_metric_registry(&config).unwrap();
+/// assert_eq!(registry.len(), 2);
+///
+/// config.metric_definitions.push("tkw = std(Weight)".to_string());
+/// assert!(get_metric_registry(&config).is_err());
+/// ```
+pub fn get_metric_registry(config: &ConfigStore) -> Result<Vec<Metric>,String> {
+    let mut registry: Vec<Metric> = Vec::new();
+    for definition in config.metric_definitions.iter() {
+        let metric = Metric::parse(definition)?;
+        if registry.iter().any(|other| other.name.eq_ignore_ascii_case(&metric.name)) {
+            return Err(format!("The metric \"{}\" is defined more than once.", metric.name));
+        }//end if this metric has the same name as another
+        registry.push(metric);
+    }//end parsing each metric definition
+    Ok(registry)
+}//end get_metric_registry(config)
+
+/// Finds the metric named name in registry, ignoring case.
+/// If there isn't one, the error lists the metrics which are defined.
+pub fn find_metric<'a>(registry: &'a Vec<Metric>, name: &str) -> Result<&'a Metric,String> {
+    match registry.iter().find(|metric| metric.name.eq_ignore_ascii_case(name.trim())) {
+        Some(metric) => Ok(metric),
+        None => {
+            let defined: Vec<&str> = registry.iter().map(|metric| metric.name.as_str()).collect();
+            Err(format!("There isn't a metric named \"{}\". The defined metrics are: {}", name.trim(), defined.join(", ")))
+        },
+    }//end matching whether we found the metric
+}//end find_metric(registry, name)
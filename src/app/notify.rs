@@ -0,0 +1,139 @@
+use std::{path::Path, process::Command, time::Duration};
+
+use serde_json::{json, Value};
+
+use crate::app::summary::RunSummary;
+
+/// Shows a desktop notification with the given title and body, such as to let
+/// the user know that a long run has finished while they're in another window.
+/// This uses the notification tool built into each platform, which is a
+/// toast on Windows, notify-send on Linux, and osascript on macOS.
+/// The notification is shown in the background, so this returns as soon as
+/// the tool has started.
+pub fn send_desktop_notification(title: &str, body: &str) -> Result<(),String> {
+    match get_notification_command(title, body).spawn() {
+        Ok(_) => Ok(()),
+        Err(error) => Err(format!("Couldn't show a desktop notification: {}", error)),
+    }//end matching whether the notification tool started
+}//end send_desktop_notification(title, body)
+
+/// Gets the command which shows a toast notification on Windows, using PowerShell.
+#[cfg(target_os = "windows")]
+fn get_notification_command(title: &str, body: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    // keeps a console window from flashing up while the notification is sent
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    // Windows only shows notifications from registered apps, so the id of PowerShell is borrowed
+    const WINDOWS_NOTIFIER_ID: &str = "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+        $template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+        $text = $template.GetElementsByTagName('text'); \
+        $text.Item(0).AppendChild($template.CreateTextNode('{}')) > $null; \
+        $text.Item(1).AppendChild($template.CreateTextNode('{}')) > $null; \
+        [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{}').Show([Windows.UI.Notifications.ToastNotification]::new($template))",
+        escape_powershell_text(title), escape_powershell_text(body), WINDOWS_NOTIFIER_ID);
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}//end get_notification_command(title, body)
+
+/// Gets the command which shows a notification on macOS, using osascript.
+#[cfg(target_os = "macos")]
+fn get_notification_command(title: &str, body: &str) -> Command {
+    let script = format!("display notification \"{}\" with title \"{}\"", escape_applescript_text(body), escape_applescript_text(title));
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    command
+}//end get_notification_command(title, body)
+
+/// Gets the command which shows a notification on Linux and other
+/// platforms, using notify-send.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn get_notification_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args([title, body]);
+    command
+}//end get_notification_command(title, body)
+
+/// Escapes text so it can be put in a single-quoted PowerShell string.
+///
+/// ```
+/// use usda_c_grain_sum::app::notify::escape_powershell_text;
+///
+/// assert_eq!(escape_powershell_text("Finished sum.xlsx"), "Finished sum.xlsx");
+/// assert_eq!(escape_powershell_text("Dan's run"), "Dan''s run");
+/// ```
+pub fn escape_powershell_text(text: &str) -> String {
+    text.replace('\'', "''")
+}//end escape_powershell_text(text)
+
+/// Escapes text so it can be put in a double-quoted AppleScript string.
+///
+/// ```
+/// use usda_c_grain_sum::app::notify::escape_applescript_text;
+///
+/// assert_eq!(escape_applescript_text("Finished \"sum.xlsx\""), "Finished \\\"sum.xlsx\\\"");
+/// assert_eq!(escape_applescript_text("C:\\data"), "C:\\\\data");
+/// ```
+pub fn escape_applescript_text(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}//end escape_applescript_text(text)
+
+/// Gets the json sent to the webhook after a run of a batch is processed,
+/// so a dashboard can show it. It has the run id, the path of the output
+/// file, whether the run succeeded, and the key metrics from run_summary,
+/// if there's a summary of the csv data. Failed runs have an error instead.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::notify::get_webhook_payload;
+/// use usda_c_grain_sum::app::summary::RunSummary;
+///
+/// let summary = RunSummary {
+///     sample_count: 2,
+///     kernel_count: 10,
+///     class_name: "Sound".to_string(),
+///     overall_percent: 90.0,
+///     flagged_samples: vec![("S2".to_string(), 80.0)],
+/// };
+/// let payload = get_webhook_payload("run_0612", &PathBuf::from("run_0612_summary.xlsx"), &Ok(Some(&summary)));
+/// assert_eq!(payload.to_string(), r#"{"metrics":{"class_name":"Sound","flagged_samples":["S2"],"kernels":10,"overall_percent":90.0,"samples":2},"output_path":"run_0612_summary.xlsx","run_id":"run_0612","succeeded":true}"#);
+///
+/// let payload = get_webhook_payload("run_0613", &PathBuf::from("run_0613_summary.xlsx"), &Err("the csv file has no rows".to_string()));
+/// assert_eq!(payload["succeeded"], false);
+/// assert_eq!(payload["error"], "the csv file has no rows");
+/// ```
+pub fn get_webhook_payload(run_id: &str, output_path: &Path, result: &Result<Option<&RunSummary>,String>) -> Value {
+    let mut payload = json!({
+        "run_id": run_id,
+        "output_path": output_path.to_string_lossy(),
+        "succeeded": result.is_ok(),
+    });
+    match result {
+        Ok(Some(run_summary)) => payload["metrics"] = json!({
+            "samples": run_summary.sample_count,
+            "kernels": run_summary.kernel_count,
+            "class_name": run_summary.class_name,
+            "overall_percent": run_summary.overall_percent,
+            "flagged_samples": run_summary.flagged_samples.iter().map(|(sample_id, _)| sample_id).collect::<Vec<&String>>(),
+        }),
+        Ok(None) => {},
+        Err(msg) => payload["error"] = json!(msg),
+    }//end matching what to add about how the run went
+    payload
+}//end get_webhook_payload(run_id, output_path, result)
+
+/// Posts payload as json to the webhook at url, such as one which updates
+/// the lab dashboard. Gives up after a few seconds, so a webhook which is
+/// down doesn't hold up the rest of the batch.
+pub fn post_webhook(url: &str, payload: &Value) -> Result<(),String> {
+    match ureq::post(url).timeout(Duration::from_secs(5)).set("Content-Type", "application/json").send_string(&payload.to_string()) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => Err(format!("The webhook answered with status {}.", code)),
+        Err(error) => Err(format!("Couldn't reach the webhook: {}", error)),
+    }//end matching whether the webhook got the payload
+}//end post_webhook(url, payload)
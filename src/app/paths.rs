@@ -0,0 +1,201 @@
+use std::{env, fs, path::{Path, PathBuf}};
+
+/// The name of the file which, when placed next to the exe,
+/// makes the program run in portable mode.
+pub const PORTABLE_FLAG_NAME: &str = "portable.flag";
+/// The name of the folder used for config and data in installed mode.
+pub const APP_DIR_NAME: &str = "usda-c-grain-sum";
+
+/// Gets the directory which contains the exe this program runs from.
+pub fn get_exe_dir() -> Result<PathBuf,String> {
+    match env::current_exe() {
+        Ok(exe_path) => match exe_path.parent() {
+            Some(exe_dir) => Ok(exe_dir.to_path_buf()),
+            None => Err(format!("Couldn't get the directory of the exe at \"{}\".", exe_path.to_string_lossy())),
+        },
+        Err(error) => Err(error.to_string()),
+    }//end matching whether we could get the current exe path
+}//end get_exe_dir()
+
+/// Returns true if dir holds a portable flag file, meaning that
+/// config and data should be kept in dir instead of in user directories.
+///
+/// ```
+/// use usda_c_grain_sum::app::paths;
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_portable_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let _ = std::fs::remove_file(dir.join(paths::PORTABLE_FLAG_NAME));
+/// assert!(!paths::is_portable_dir(&dir));
+/// std::fs::write(dir.join(paths::PORTABLE_FLAG_NAME), "").unwrap();
+/// assert!(paths::is_portable_dir(&dir));
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn is_portable_dir(dir: &Path) -> bool {
+    dir.join(PORTABLE_FLAG_NAME).is_file()
+}//end is_portable_dir(dir)
+
+/// Returns true if the program is running in portable mode, which
+/// is when there's a portable flag file next to the exe.
+pub fn is_portable_mode() -> bool {
+    match get_exe_dir() {
+        Ok(exe_dir) => is_portable_dir(&exe_dir),
+        Err(_) => false,
+    }//end matching whether we can find the exe directory
+}//end is_portable_mode()
+
+/// Gets the per-user base directory for config files on this platform,
+/// without the application folder.
+fn get_user_config_base() -> Option<PathBuf> {
+    if cfg!(windows) {env::var_os("APPDATA").map(PathBuf::from)}
+    else if cfg!(target_os = "macos") {env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))}
+    else {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }
+}//end get_user_config_base()
+
+/// Gets the per-user base directory for data files on this platform,
+/// without the application folder.
+fn get_user_data_base() -> Option<PathBuf> {
+    if cfg!(windows) {env::var_os("LOCALAPPDATA").map(PathBuf::from)}
+    else if cfg!(target_os = "macos") {env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))}
+    else {
+        env::var_os("XDG_DATA_HOME").map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))
+    }
+}//end get_user_data_base()
+
+/// Creates dir if it doesn't already exist, then returns it.
+fn ensure_dir(dir: PathBuf) -> Result<PathBuf,String> {
+    if !dir.exists() {
+        if let Err(error) = fs::create_dir_all(&dir) {
+            return Err(format!("Couldn't create directory \"{}\": {}", dir.to_string_lossy(), error));
+        }//end if we couldn't create the directory
+    }//end if directory doesn't exist yet
+    Ok(dir)
+}//end ensure_dir(dir)
+
+/// Gets the directory where config files should be kept, creating it if needed.
+/// In portable mode, this is the directory of the exe.
+/// In installed mode, this is a folder in the user's config directory.
+pub fn get_config_dir() -> Result<PathBuf,String> {
+    let exe_dir = get_exe_dir()?;
+    if is_portable_dir(&exe_dir) {return Ok(exe_dir);}
+    match get_user_config_base() {
+        Some(base_dir) => ensure_dir(base_dir.join(APP_DIR_NAME)),
+        None => Err(String::from("Couldn't find the user config directory.")),
+    }//end matching whether we could find the user config directory
+}//end get_config_dir()
+
+/// Gets the directory where per-user data, such as recent files, should
+/// be kept, creating it if needed.
+/// In portable mode, this is the directory of the exe.
+/// In installed mode, this is a folder in the user's data directory.
+pub fn get_data_dir() -> Result<PathBuf,String> {
+    let exe_dir = get_exe_dir()?;
+    if is_portable_dir(&exe_dir) {return Ok(exe_dir);}
+    match get_user_data_base() {
+        Some(base_dir) => ensure_dir(base_dir.join(APP_DIR_NAME)),
+        None => Err(String::from("Couldn't find the user data directory.")),
+    }//end matching whether we could find the user data directory
+}//end get_data_dir()
+
+/// Gets the path of the config file with the given name, in the directory
+/// from get_config_dir(). The extension of the file is .config.
+pub fn get_config_path(config_name: &str) -> Result<PathBuf,String> {
+    let mut config_path = get_config_dir()?.join(config_name);
+    config_path.set_extension("config");
+    Ok(config_path)
+}//end get_config_path(config_name)
+
+/// Gets the directory where presets saved by the user are kept,
+/// creating it if needed. This is a presets folder in get_config_dir().
+pub fn get_preset_dir() -> Result<PathBuf,String> {
+    ensure_dir(get_config_dir()?.join("presets"))
+}//end get_preset_dir()
+
+/// Resolves a file or folder path from a config field, relative to
+/// config_dir. Absolute paths are returned as they are, so only relative
+/// paths, such as "metadata/lots.csv", are affected.
+/// An empty path stays empty.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::paths;
+///
+/// let config_dir = std::env::temp_dir().join("c_grain_sum_shared_config");
+/// assert_eq!(paths::resolve_path_from("metadata/lots.csv", &config_dir), config_dir.join("metadata/lots.csv"));
+/// let absolute_path = std::env::temp_dir().join("lots.csv");
+/// assert_eq!(paths::resolve_path_from(&absolute_path.to_string_lossy(), &config_dir), absolute_path);
+/// assert_eq!(paths::resolve_path_from("  ", &config_dir), PathBuf::new());
+/// ```
+pub fn resolve_path_from(path: &str, config_dir: &Path) -> PathBuf {
+    let path = path.trim();
+    if path.is_empty() {return PathBuf::new();}
+    let path_buf = PathBuf::from(path);
+    match path_buf.is_absolute() {
+        true => path_buf,
+        false => config_dir.join(path_buf),
+    }//end matching whether the path is already absolute
+}//end resolve_path_from(path, config_dir)
+
+/// Resolves a file or folder path from a config field, such as the
+/// metadata file or script, relative to the folder from get_config_dir().
+/// This lets a config shared between machines point at files next to it,
+/// even if its drive is mapped to a different letter on each machine.
+/// If the config folder can't be found, the path is used as it is.
+pub fn resolve_setting_path(path: &str) -> PathBuf {
+    match get_config_dir() {
+        Ok(config_dir) => resolve_path_from(path, &config_dir),
+        Err(error) => {
+            log::warn!("Couldn't find the config folder to resolve \"{}\", so it was used as it is.\n{}", path.trim(), error);
+            PathBuf::from(path.trim())
+        },
+    }//end matching whether we could find the config folder
+}//end resolve_setting_path(path)
+
+/// Gets the text to store in a config field for path, which is relative
+/// to config_dir if path is inside it, or path as it is otherwise.
+/// Relative paths are written with / so they work on every platform.
+///
+/// ```
+/// use usda_c_grain_sum::app::paths;
+///
+/// let config_dir = std::env::temp_dir().join("c_grain_sum_shared_config");
+/// assert_eq!(paths::get_setting_path_from(&config_dir.join("metadata").join("lots.csv"), &config_dir), "metadata/lots.csv");
+/// let outside_path = std::env::temp_dir().join("lots.csv");
+/// assert_eq!(paths::get_setting_path_from(&outside_path, &config_dir), outside_path.to_string_lossy());
+/// ```
+pub fn get_setting_path_from(path: &Path, config_dir: &Path) -> String {
+    match path.strip_prefix(config_dir) {
+        Ok(relative_path) if relative_path.as_os_str().len() > 0 => relative_path.components()
+            .map(|component| component.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<String>>()
+            .join("/"),
+        _ => path.to_string_lossy().into_owned(),
+    }//end matching whether path is inside config_dir
+}//end get_setting_path_from(path, config_dir)
+
+/// Gets the text to store in a config field for a path the user picked,
+/// which is relative to the folder from get_config_dir() if the path is
+/// inside it, so the config still works when that folder is moved or shared.
+pub fn get_setting_path(path: &Path) -> String {
+    match get_config_dir() {
+        Ok(config_dir) => get_setting_path_from(path, &config_dir),
+        Err(_) => path.to_string_lossy().into_owned(),
+    }//end matching whether we could find the config folder
+}//end get_setting_path(path)
+
+/// Gets the name of the file at file_path, or an empty string if it doesn't have one.
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::paths::get_file_name;
+///
+/// assert_eq!(get_file_name(&PathBuf::from("exports").join("run_0612.csv")), "run_0612.csv");
+/// assert_eq!(get_file_name(&PathBuf::from("")), "");
+/// ```
+pub fn get_file_name(file_path: &Path) -> String {
+    file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+}//end get_file_name(file_path)
@@ -0,0 +1,146 @@
+use std::{fs::{self, OpenOptions}, path::{Path, PathBuf}, process};
+
+/// Extra free space wanted past the estimated size of the output, for
+/// the csv copies, bundles, and temporary files written alongside it.
+pub const FREE_SPACE_MARGIN: u64 = 5 * 1024 * 1024;
+
+/// A problem with where the output is going to be written, found
+/// before processing, so a run doesn't fail at the very end.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OutputLocationProblem {
+    /// The folder the output would be written to doesn't exist,
+    /// such as a network share which isn't connected.
+    MissingFolder(PathBuf),
+    /// The folder exists, but a file can't be created there.
+    /// Holds the folder and why the file couldn't be created.
+    NotWritable(PathBuf, String),
+    /// The drive holding the folder doesn't have enough free space.
+    LowSpace { folder: PathBuf, available: u64, needed: u64 },
+}//end enum OutputLocationProblem
+
+impl OutputLocationProblem {
+    /// Gets a description of the problem suitable for showing the user.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::PathBuf;
+    /// use usda_c_grain_sum::app::preflight::OutputLocationProblem;
+    ///
+    /// let problem = OutputLocationProblem::LowSpace { folder: PathBuf::from("results"), available: 1_500_000, needed: 12_000_000 };
+    /// assert_eq!(problem.get_description(), "The drive holding \"results\" only has 1.4 MB free, but about 11.4 MB is needed to write the output.");
+    /// ```
+    pub fn get_description(&self) -> String {
+        match self {
+            OutputLocationProblem::MissingFolder(folder) => format!("The folder \"{}\" doesn't exist, or can't be reached, such as a network share which isn't connected.", folder.to_string_lossy()),
+            OutputLocationProblem::NotWritable(folder, error) => format!("Files can't be created in the folder \"{}\", so the output can't be written there.\n{}", folder.to_string_lossy(), error),
+            OutputLocationProblem::LowSpace { folder, available, needed } => format!("The drive holding \"{}\" only has {} free, but about {} is needed to write the output.", folder.to_string_lossy(), format_size(*available), format_size(*needed)),
+        }//end matching the kind of problem
+    }//end get_description(self)
+}//end impl OutputLocationProblem
+
+/// Gets a size in bytes as megabytes, or kilobytes if it's small,
+/// such as "11.4 MB".
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::preflight::format_size;
+///
+/// assert_eq!(format_size(2048), "2.0 KB");
+/// assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+/// ```
+pub fn format_size(bytes: u64) -> String {
+    match bytes < 1024 * 1024 {
+        true => format!("{:.1} KB", bytes as f64 / 1024.0),
+        false => format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0)),
+    }//end matching whether the size is under a megabyte
+}//end format_size(bytes)
+
+/// Estimates the free space needed to write the output of processing
+/// input_files, which is twice their combined size plus FREE_SPACE_MARGIN.
+/// The output is usually much smaller than the input, so this errs on
+/// the side of asking for too much. Files which can't be read are
+/// counted as empty.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::preflight::{estimate_output_size, FREE_SPACE_MARGIN};
+///
+/// let input_path = std::env::temp_dir().join("c_grain_sum_preflight_estimate.csv");
+/// std::fs::write(&input_path, "external-sample-id,Area\nS1,12.5\n").unwrap();
+/// let missing_path = std::env::temp_dir().join("c_grain_sum_preflight_missing.csv");
+/// assert_eq!(estimate_output_size(&vec![&input_path, &missing_path]), 64 + FREE_SPACE_MARGIN);
+/// ```
+pub fn estimate_output_size(input_files: &Vec<&PathBuf>) -> u64 {
+    let input_size: u64 = input_files.iter().filter_map(|input_file| fs::metadata(input_file).ok()).map(|metadata| metadata.len()).sum();
+    input_size * 2 + FREE_SPACE_MARGIN
+}//end estimate_output_size(input_files)
+
+/// Gets the folder the file at output will be written to.
+/// A bare file name is written to the current folder.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::preflight::get_output_folder;
+///
+/// assert_eq!(get_output_folder(&PathBuf::from("results").join("sum.xlsx")), PathBuf::from("results"));
+/// assert_eq!(get_output_folder(&PathBuf::from("sum.xlsx")), PathBuf::from("."));
+/// ```
+pub fn get_output_folder(output: &Path) -> PathBuf {
+    match output.parent() {
+        Some(parent) if parent.as_os_str().len() > 0 => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }//end matching whether output has a folder
+}//end get_output_folder(output)
+
+/// Checks that output can be written to folder before anything is
+/// processed, which means the folder exists, a file can be created there,
+/// and the drive has at least needed bytes free.
+/// If the free space can't be found, such as on some network shares,
+/// it isn't checked.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::preflight::{check_output_folder, OutputLocationProblem};
+///
+/// let dir = std::env::temp_dir().join("c_grain_sum_preflight_doctest");
+/// std::fs::create_dir_all(&dir).unwrap();
+/// assert_eq!(check_output_folder(&dir, 1024), Ok(()));
+///
+/// let missing_dir = dir.join("not_connected");
+/// assert_eq!(check_output_folder(&missing_dir, 1024), Err(OutputLocationProblem::MissingFolder(missing_dir)));
+///
+/// match check_output_folder(&dir, u64::MAX) {
+///     Err(OutputLocationProblem::LowSpace { needed, .. }) => assert_eq!(needed, u64::MAX),
+///     other => panic!("expected low space, got {:?}", other),
+/// }
+/// std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub fn check_output_folder(folder: &Path, needed: u64) -> Result<(),OutputLocationProblem> {
+    let folder = folder.to_path_buf();
+    if !folder.is_dir() {return Err(OutputLocationProblem::MissingFolder(folder));}
+
+    // creating a file is the only reliable way to know, since permissions on shares aren't always reported
+    let test_path = folder.join(format!(".c_grain_sum_write_check_{}.tmp", process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&test_path) {
+        Ok(test_file) => {
+            drop(test_file);
+            let _ = fs::remove_file(&test_path);
+        },
+        Err(error) => return Err(OutputLocationProblem::NotWritable(folder, error.to_string())),
+    }//end matching whether we could create a file in the folder
+
+    match fs2::available_space(&folder) {
+        Ok(available) if available < needed => Err(OutputLocationProblem::LowSpace { folder, available, needed }),
+        Ok(_) => Ok(()),
+        Err(error) => {
+            log::warn!("Couldn't find the free space in \"{}\", so it wasn't checked.\n{}", folder.to_string_lossy(), error);
+            Ok(())
+        },
+    }//end matching whether there's enough free space
+}//end check_output_folder(folder, needed)
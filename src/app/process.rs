@@ -0,0 +1,3609 @@
+use std::{collections::{HashMap, VecDeque}, fs, path::{Path, PathBuf}};
+
+use csv::StringRecord;
+use regex::Regex;
+
+use crate::{app::{config_store::{ClassPercentDenominator, ConfigStore, InvalidSampleIdHandling, OutputFormat, RecipientProfile, SampleIdAnonymization, SheetLayout, WarningCategory}, metrics, secrets, taxonomy}, io::xlsx, model::{self, Data, DataCell, DataRow, DataVal, OutputColumn, OutputRow, SampleOutput}, pipeline::{self, DerivedColumn, Operand, UnitConversion}, stats};
+
+
+
+/// Gets the records in data which match the class filters in config.  
+/// If class filtering is disabled, or there are no filters, then all
+/// records are returned.
+pub fn get_class_filtered_records<'a>(data: &'a Data, config: &ConfigStore) -> Result<Vec<&'a DataRow>,String> {
+    let base_data = data.get_records();
+    let filtered_data = match config.csv_class_filter_enabled {
+        false => base_data,
+        true => {
+            let mut multi_filter_holding_vec = Vec::new();
+            let filter_col_idx = data.get_header_index(&config.csv_class_filter_class).unwrap_or_else(|| { log::warn!("Couldn't find class filter header \"{}\"!\nResorting to Default!", &config.csv_class_filter_class); return 5;});
+            for filter in config.csv_class_filter_filters.iter() {
+                let expected = DataVal::String(filter.clone());
+                for row in base_data.iter() {
+                    match row.get_data(filter_col_idx) {
+                        Some(cell) => if expected.eq(cell.get_data()) {multi_filter_holding_vec.push(*row);},
+                        None => return Err(format!("Couldn't filter records for some reason. Err msg below:\nCouldn't get DataCell at col idx {} and row idx {}.", filter_col_idx, row.get_row_idx())),
+                    }//end matching whether we can get the class of this row
+                }//end checking each row against this filter
+            }//end filtering to data for each class filter
+            // edge case of zero filters
+            if config.csv_class_filter_filters.len() == 0 {base_data}
+            else {multi_filter_holding_vec}
+        },
+    };
+    Ok(filtered_data)
+}//end get_class_filtered_records(data, config)
+
+/// Processes the data provided, using the config provided,
+/// to get csv stat columns for the data.  
+/// Also uses config options to filter and split the data.  
+/// Each column gets an average and standard deviation, followed by each
+/// statistic in config.csv_stat_columns_stats, such as "Median Area".
+/// Coefficients of variation are percents.  
+/// If config.csv_stat_group_header is set, such as to "Treatment", each
+/// sample gets a row for each of its treatments, and the treatment is in
+/// a column after the sample id. Use split_output_by_group() to get a
+/// sheet for each group instead.  
+/// If a column isn't in data, such as when missing columns are only
+/// logged, its stat columns are still added, but left empty, so the
+/// columns after it keep their values.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_csv_stat_cols;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "1"), ("A", "3"), ("A", "8")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_sample_id_header = "external-sample-id".to_string();
+/// config.csv_stat_columns_columns = vec!["Area".to_string()];
+/// config.csv_stat_columns_stats = vec!["Median".to_string(), "max".to_string()];
+/// 
+/// let output = proc_csv_stat_cols(&data, &config).unwrap();
+/// let names: Vec<&str> = output.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Avg Area", "Std Area", "Median Area", "Max Area"]);
+/// assert_eq!(output.rows[0].cells[2..], [DataVal::Float(3.0), DataVal::Float(8.0)]);
+/// 
+/// // a missing column leaves its stats empty, rather than moving Area's stats under it
+/// config.csv_stat_columns_columns = vec!["Hue".to_string(), "Area".to_string()];
+/// config.csv_stat_columns_stats = vec!["Max".to_string()];
+/// let output = proc_csv_stat_cols(&data, &config).unwrap();
+/// let names: Vec<&str> = output.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Avg Hue", "Std Hue", "Max Hue", "Avg Area", "Std Area", "Max Area"]);
+/// assert_eq!(output.rows[0].cells[..3], [DataVal::String(String::new()), DataVal::String(String::new()), DataVal::String(String::new())]);
+/// assert_eq!(output.rows[0].cells[3], DataVal::Float(4.0));
+/// assert_eq!(output.rows[0].cells[5], DataVal::Float(8.0));
+/// ```
+pub fn proc_csv_stat_cols(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.csv_stat_columns_enabled {return Err(format!("CSV Stat columns are disabled in config!"));}
+    if config.csv_stat_columns_columns.len() < 1 {return Err(format!("No columns set in config to calculate stats on!"));}
+    let extra_stats = config.csv_stat_columns_stats.iter().map(|stat| stats::parse_extra_stat(stat)).collect::<Result<Vec<&str>,String>>()?;
+
+    let filtered_data = get_class_filtered_records(data, config)?;
+    let group_col_idx = match config.csv_stat_group_header.trim() {
+        "" => None,
+        group_header => Some(data.get_header_index(group_header).ok_or(format!("Couldn't find column \"{}\" to group the rows of each sample by.", group_header))?),
+    };
+    // split data up based on reading in column external-sample-id, prob index 2
+    // (sample id, group within the sample if grouping, rows)
+    let split_data: Vec<(String, Option<String>, Vec<&DataRow>)> = {
+        let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).unwrap_or_else(|| {log::warn!("Couldn't find sample id header \"{}\"!\nResorting to Default!",&config.csv_sample_id_header); return 2;});
+        match group_col_idx {
+            None => match model::get_split_records(&filtered_data,sample_id_col_idx) {
+                Ok(split_data_ok) => split_data_ok.into_iter().map(|(sample_id_val, rows)| (sample_id_val.to_string(), None, rows)).collect(),
+                Err(msg) => return Err(format!("Couldn't split records based on \"{}\", which we think has 0-based col index {}. More info below:\n{}",config.csv_sample_id_header,sample_id_col_idx,msg)),
+            },
+            Some(group_col_idx) => match model::get_nested_split_records(&filtered_data, sample_id_col_idx, group_col_idx) {
+                Ok(nested_data) => nested_data.into_iter()
+                    .flat_map(|(sample_id_val, groups)| groups.into_iter().map(move |(group_val, rows)| (sample_id_val.to_string(), Some(group_val.to_string()), rows)))
+                    .collect(),
+                Err(msg) => return Err(format!("Couldn't split records based on \"{}\" and \"{}\". More info below:\n{}",config.csv_sample_id_header,config.csv_stat_group_header,msg)),
+            },
+        }//end matching whether we can get split data properly
+    };
+
+    // create struct to hold the data we'll put in
+    let mut output = SampleOutput::new("external-sample-id");
+    if group_col_idx.is_some() {output.add_column(OutputColumn::text(config.csv_stat_group_header.trim()));}
+    // pre-fill output.columns with values
+    for col_label in config.csv_stat_columns_columns.iter() {
+        let decimal_places = get_stat_decimal_places(col_label);
+        let mut stat_columns = vec![
+            OutputColumn::number(&fill_header_template(&config.csv_stat_avg_header_template, "{col}", col_label), decimal_places),
+            OutputColumn::number(&fill_header_template(&config.csv_stat_std_header_template, "{col}", col_label), decimal_places),
+        ];
+        for stat in extra_stats.iter() {
+            match *stat {
+                "CV" => stat_columns.push(OutputColumn::percent(&format!("{} {}", stat, col_label), 1)),
+                _ => stat_columns.push(OutputColumn::number(&format!("{} {}", stat, col_label), decimal_places)),
+            }//end matching whether this stat is a percent
+        }//end adding a header for each extra stat
+        let group = get_stat_column_group(col_label, config);
+        for stat_column in stat_columns {
+            match &group {
+                Some(group) => output.add_column(stat_column.with_group(group)),
+                None => output.add_column(stat_column),
+            }//end matching whether the stats of this column are grouped
+        }//end adding each stat column of this column
+    }//end adding each header we'll use to output
+
+    // process data for each group, then add to output
+    for (sample_id_val, group_val, rows) in split_data {
+        let mut output_row = Vec::new();
+        let mut notes = Vec::new();
+        if let Some(group_val) = group_val {output_row.push(DataVal::String(group_val));}
+
+        for stat_col_header in config.csv_stat_columns_columns.iter() {
+            if let Some(col_idx) = data.get_header_index(stat_col_header) {
+                let col_avg = match get_col_avg_sngl(&rows, col_idx) {
+                    Ok(avg) => avg,
+                    Err(msg) => return Err(format!("Encountered an error while trying to find the average value in column {} for rows with sample id {}:\n{}",stat_col_header, sample_id_val, msg)),
+                };
+                let col_std = match get_col_stdev_sngl(&rows, col_idx) {
+                    Ok(stdev) => stdev,
+                    Err(msg) => {
+                        match msg {
+                            s if s.starts_with("Encountered a string where there should be a number") => {
+                                log::warn!("\nCouldn't calculate standard deviation for column {} and sample id {} because of a string being present in the data.",stat_col_header,sample_id_val);
+                                log::warn!("Standard deviation will be skipped for that column in that sample, instead listed as -1000.0. More information on how this happened:\n{}\n",s);
+                                notes.push((output_row.len() + 1, "stdev skipped: non-numeric data"));
+                                -1000.0
+                            },
+                            _ => return Err(format!("Encountered an error while trying to find the standard deviation of column {} for rows with sample id {}:\n{}",stat_col_header,sample_id_val,msg)),
+                        }//end matching behavior based on contents of error message
+                    },
+                };
+                output_row.push(DataVal::Float(col_avg));
+                output_row.push(DataVal::Float(col_std));
+                let (values, _, _) = stats::get_row_values(&rows, col_idx);
+                for stat in extra_stats.iter() {
+                    match stats::get_extra_stat(&values, stat)? {
+                        Some(stat_val) => output_row.push(DataVal::Float(stat_val)),
+                        None => output_row.push(DataVal::String(String::new())),
+                    }//end matching whether this stat could be calculated
+                }//end adding each extra stat
+            } else {
+                // the column's cells are still filled, so later columns line up with their headers
+                for _ in 0..2 + extra_stats.len() {output_row.push(DataVal::String(String::new()));}
+            }//end if we can find the stat column for that header
+        }//end looping over each base col header
+
+        output.add_row(&sample_id_val.to_string(), output_row);
+        if let Some(added_row) = output.rows.last_mut() {
+            for (col_idx, note) in notes {added_row.add_note(col_idx, note);}
+        }//end attaching notes about skipped values
+    }//end looping over each sample split
+    
+    Ok(output)
+}//end proc_csv_stat_cols(data, config)
+
+/// Replaces any characters in name which aren't allowed in an excel
+/// sheet name with underscores, and shortens it to the 31 characters
+/// excel allows.  
+/// Excel doesn't tell sheet names apart by case, so if the name is
+/// already in used_names, ignoring case, such as after being shortened,
+/// " (2)", " (3)", and so on is added to the end to keep it unique.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::get_safe_sheet_name;
+/// 
+/// assert_eq!(get_safe_sheet_name("CSV_Stats_Plot 3/4", &[]), "CSV_Stats_Plot 3_4");
+/// assert_eq!(get_safe_sheet_name("CSV_Stats_Nitrogen Rate 120 kg per ha", &[]).len(), 31);
+/// 
+/// let used_names = vec!["CSV_Stats_Control".to_string()];
+/// assert_eq!(get_safe_sheet_name("CSV_Stats_control", &used_names), "CSV_Stats_control (2)");
+/// let used_names = vec!["CSV_Stats_Nitrogen Rate 120 kg ".to_string(), "CSV_Stats_Nitrogen Rate 120 (2)".to_string()];
+/// assert_eq!(get_safe_sheet_name("CSV_Stats_Nitrogen Rate 120 kg per ha", &used_names[..1]), "CSV_Stats_Nitrogen Rate 120 (2)");
+/// assert_eq!(get_safe_sheet_name("CSV_Stats_Nitrogen Rate 120 kg per ha", &used_names), "CSV_Stats_Nitrogen Rate 120 (3)");
+/// ```
+pub fn get_safe_sheet_name(name: &str, used_names: &[String]) -> String {
+    let safe_name: String = name.chars()
+        .map(|c| if ['[', ']', ':', '*', '?', '/', '\\'].contains(&c) {'_'} else {c})
+        .collect();
+    let is_used = |name: &str| used_names.iter().any(|used_name| used_name.to_lowercase() == name.to_lowercase());
+    let mut sheet_name: String = safe_name.chars().take(31).collect();
+    let mut copy_num = 2;
+    while is_used(&sheet_name) {
+        let suffix = format!(" ({})", copy_num);
+        sheet_name = safe_name.chars().take(31 - suffix.len()).chain(suffix.chars()).collect();
+        copy_num += 1;
+    }//end adding a number until the name is unique
+    sheet_name
+}//end get_safe_sheet_name(name, used_names)
+
+/// Splits sheet_data, a stats sheet with a column named group_header
+/// from proc_csv_stat_cols(), into a sheet for each group, in order of
+/// first appearance. Each sheet is named after sheet_name and its group,
+/// such as CSV_Stats_Control, and has the rows of that group, without
+/// the group column. Groups which only differ by case, or after being
+/// shortened, get names made unique by get_safe_sheet_name().  
+/// If sheet_data has no column named group_header, it's returned as it is.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::{proc_csv_stat_cols, split_output_by_group};
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Treatment".to_string(), "Area".to_string()];
+/// let rows = vec![("S1", "Control", "10"), ("S1", "Dried", "14"), ("S2", "Control", "12"), ("S1", "Control", "12")].into_iter().enumerate()
+///     .map(|(idx, (id, treatment, area))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], treatment.to_string()),
+///         DataCell::new(&headers[2], area.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Area".to_string()];
+/// config.csv_stat_group_header = "Treatment".to_string();
+/// 
+/// // a row for each treatment in each sample
+/// let output = proc_csv_stat_cols(&data, &config).unwrap();
+/// let names: Vec<&str> = output.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Treatment", "Avg Area", "Std Area"]);
+/// let ids: Vec<&str> = output.rows.iter().map(|row| row.sample_id.as_str()).collect();
+/// assert_eq!(ids, vec!["S1", "S1", "S2"]);
+/// assert_eq!(output.rows[0].cells[..2], [DataVal::String("Control".to_string()), DataVal::Float(11.0)]);
+/// 
+/// // or a sheet for each treatment
+/// let group_sheets = split_output_by_group("CSV_Stats", &output, "Treatment");
+/// assert_eq!(group_sheets.len(), 2);
+/// assert_eq!(group_sheets[0].0, "CSV_Stats_Control");
+/// assert_eq!(group_sheets[0].1.columns[0].name, "Avg Area");
+/// assert_eq!(group_sheets[0].1.rows.len(), 2);
+/// assert_eq!(group_sheets[1].0, "CSV_Stats_Dried");
+/// assert_eq!(group_sheets[1].1.rows[0].cells[0], DataVal::Float(14.0));
+/// ```
+pub fn split_output_by_group(sheet_name: &str, sheet_data: &SampleOutput, group_header: &str) -> Vec<(String, SampleOutput)> {
+    let group_col_idx = match sheet_data.get_column_index(group_header) {
+        Some(group_col_idx) => group_col_idx,
+        None => return vec![(sheet_name.to_string(), sheet_data.clone())],
+    };
+    let mut group_columns = sheet_data.columns.clone();
+    group_columns.remove(group_col_idx);
+    // (group, sheet of that group)
+    let mut group_sheets: Vec<(String, SampleOutput)> = Vec::new();
+    for output_row in sheet_data.rows.iter() {
+        let group = output_row.cells.get(group_col_idx).map(|cell| cell.to_string()).unwrap_or_default();
+        let mut group_row = output_row.clone();
+        if group_col_idx < group_row.cells.len() {group_row.cells.remove(group_col_idx);}
+        group_row.notes = output_row.notes.iter()
+            .filter(|(col_idx, _)| *col_idx != group_col_idx)
+            .map(|(col_idx, note)| (if *col_idx > group_col_idx {col_idx - 1} else {*col_idx}, note.clone()))
+            .collect();
+        match group_sheets.iter_mut().find(|(known_group, _)| known_group.eq(&group)) {
+            Some((_, group_sheet)) => group_sheet.rows.push(group_row),
+            None => {
+                let mut group_sheet = SampleOutput::new(&sheet_data.id_header);
+                group_sheet.columns = group_columns.clone();
+                group_sheet.rows.push(group_row);
+                group_sheets.push((group, group_sheet));
+            },
+        }//end matching whether we've seen this group already
+    }//end moving each row to the sheet of its group
+    let mut sheet_names: Vec<String> = Vec::new();
+    for (group, _) in group_sheets.iter() {sheet_names.push(get_safe_sheet_name(&format!("{}_{}", sheet_name, group), &sheet_names));}
+    sheet_names.into_iter().zip(group_sheets.into_iter().map(|(_, group_sheet)| group_sheet)).collect()
+}//end split_output_by_group(sheet_name, sheet_data, group_header)
+
+/// Splits sheet_data, a stats sheet from proc_csv_stat_cols(), into a
+/// sheet for each csv stat column in config, such as Area, with a column
+/// for each of its stats, named by the stat alone, such as Avg and Std.
+/// Columns which aren't the stats of a csv column, such as Flags, are
+/// kept on every sheet, except formula columns, since they use stats
+/// which end up on other sheets.  
+/// Each sheet is named by replacing CSV_Stats in sheet_name with the csv
+/// column, so CSV_Stats_Control gives a sheet named Area_Control, made
+/// unique by get_safe_sheet_name() if another csv column gives the same name.
+/// Csv columns without any stats in sheet_data don't get a sheet, and
+/// if none of them have stats, sheet_data is returned as it is.
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::{proc_csv_stat_cols, split_output_by_metric};
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal, OutputColumn};
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string(), "Length".to_string()];
+/// let rows = vec![("S1", "10", "4"), ("S1", "14", "6"), ("S2", "12", "5")].into_iter().enumerate()
+///     .map(|(idx, (id, area, length))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], area.to_string()),
+///         DataCell::new(&headers[2], length.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Area".to_string(), "Length".to_string()];
+/// config.csv_stat_columns_stats = vec!["Max".to_string()];
+/// let mut output = proc_csv_stat_cols(&data, &config).unwrap();
+/// output.add_column(OutputColumn::number("Flags", 0));
+/// for output_row in output.rows.iter_mut() {output_row.cells.push(DataVal::String(String::new()));}
+/// output.rows[1].cells[6] = DataVal::String("LOW_N".to_string());
+/// 
+/// let metric_sheets = split_output_by_metric("CSV_Stats", &output, &config);
+/// assert_eq!(metric_sheets.len(), 2);
+/// assert_eq!(metric_sheets[0].0, "Area");
+/// assert_eq!(metric_sheets[1].0, "Length");
+/// let names: Vec<&str> = metric_sheets[1].1.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Avg", "Std", "Max", "Flags"]);
+/// assert_eq!(metric_sheets[1].1.rows[0].cells[0], DataVal::Float(5.0));
+/// assert_eq!(metric_sheets[1].1.rows[1].cells[3], DataVal::String("LOW_N".to_string()));
+/// 
+/// // grouped sheets keep their group in the name
+/// assert_eq!(split_output_by_metric("CSV_Stats_Control", &output, &config)[0].0, "Area_Control");
+/// ```
+pub fn split_output_by_metric(sheet_name: &str, sheet_data: &SampleOutput, config: &ConfigStore) -> Vec<(String, SampleOutput)> {
+    // the stat alone, such as Avg, from the template of its header
+    let get_stat_name = |template: &str, default_name: &str| match fill_header_template(template, "{col}", "").trim() {
+        "" => default_name.to_string(),
+        stat_name => stat_name.to_string(),
+    };
+    let extra_stats: Vec<&str> = config.csv_stat_columns_stats.iter().filter_map(|stat| stats::parse_extra_stat(stat).ok()).collect();
+    // the csv column whose stat is in each column of sheet_data, with the name of that stat
+    let mut column_metrics: Vec<Option<(&String, String)>> = vec![None; sheet_data.columns.len()];
+    for col_label in config.csv_stat_columns_columns.iter() {
+        let mut stat_headers = vec![
+            (fill_header_template(&config.csv_stat_avg_header_template, "{col}", col_label), get_stat_name(&config.csv_stat_avg_header_template, "Avg")),
+            (fill_header_template(&config.csv_stat_std_header_template, "{col}", col_label), get_stat_name(&config.csv_stat_std_header_template, "Std")),
+        ];
+        for stat in extra_stats.iter() {stat_headers.push((format!("{} {}", stat, col_label), stat.to_string()));}
+        for (header, stat_name) in stat_headers {
+            if let Some(col_idx) = sheet_data.get_column_index(&header) {column_metrics[col_idx] = Some((col_label, stat_name));}
+        }//end finding the column of each stat
+    }//end finding the stats of each csv column
+
+    let mut metric_sheets: Vec<(String, SampleOutput)> = Vec::new();
+    for col_label in config.csv_stat_columns_columns.iter() {
+        if !column_metrics.iter().any(|metric| matches!(metric, Some((metric_label, _)) if *metric_label == col_label)) {continue;}
+        // (index of the column in sheet_data, the column on this sheet)
+        let metric_columns: Vec<(usize, OutputColumn)> = sheet_data.columns.iter().enumerate()
+            .filter_map(|(col_idx, column)| match &column_metrics[col_idx] {
+                Some((metric_label, stat_name)) if *metric_label == col_label => Some((col_idx, OutputColumn { name: stat_name.clone(), group: None, ..column.clone() })),
+                None if column.formula.is_none() => Some((col_idx, column.clone())),
+                _ => None,
+            }).collect();
+        let mut metric_sheet = SampleOutput::new(&sheet_data.id_header);
+        metric_sheet.columns = metric_columns.iter().map(|(_, column)| column.clone()).collect();
+        for output_row in sheet_data.rows.iter() {
+            let cells = metric_columns.iter().map(|(col_idx, _)| output_row.cells.get(*col_idx).cloned().unwrap_or(DataVal::String(String::new()))).collect();
+            let mut metric_row = OutputRow::new(&output_row.sample_id, cells);
+            metric_row.notes = output_row.notes.iter()
+                .filter_map(|(col_idx, note)| metric_columns.iter().position(|(metric_col_idx, _)| metric_col_idx == col_idx).map(|new_col_idx| (new_col_idx, note.clone())))
+                .collect();
+            metric_sheet.rows.push(metric_row);
+        }//end copying the stats of this csv column from each row
+        let used_names: Vec<String> = metric_sheets.iter().map(|(metric_sheet_name, _)| metric_sheet_name.clone()).collect();
+        metric_sheets.push((get_safe_sheet_name(&sheet_name.replacen("CSV_Stats", col_label, 1), &used_names), metric_sheet));
+    }//end making a sheet for each csv column
+    match metric_sheets.is_empty() {
+        true => vec![(sheet_name.to_string(), sheet_data.clone())],
+        false => metric_sheets,
+    }//end matching whether any csv column had stats
+}//end split_output_by_metric(sheet_name, sheet_data, config)
+
+/// Counts, for each sample and each csv stat column, how many values were
+/// non-numeric or missing, and so were left out of the averages and
+/// standard deviations in CSV_Stats. This makes data quality problems
+/// visible, instead of being silently absorbed into the statistics.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_csv_diagnostics;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "10"), ("A", "bad"), ("A", ""), ("B", "12")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Area".to_string()];
+/// config.diagnostics_sheet_enabled = true;
+/// 
+/// let output = proc_csv_diagnostics(&data, &config).unwrap();
+/// assert_eq!(output.columns[0].name, "Non-Numeric Area");
+/// assert_eq!(output.columns[1].name, "Missing Area");
+/// let sample_a = output.rows.iter().find(|row| row.sample_id == "A").unwrap();
+/// assert_eq!(sample_a.cells, vec![DataVal::Int(1), DataVal::Int(1)]);
+/// let sample_b = output.rows.iter().find(|row| row.sample_id == "B").unwrap();
+/// assert_eq!(sample_b.cells, vec![DataVal::Int(0), DataVal::Int(0)]);
+/// ```
+pub fn proc_csv_diagnostics(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.diagnostics_sheet_enabled {return Err(format!("Diagnostics sheet is disabled in config!"));}
+    if config.csv_stat_columns_columns.len() < 1 {return Err(format!("No columns set in config to check for non-numeric values!"));}
+
+    let filtered_data = get_class_filtered_records(data, config)?;
+    let split_data = {
+        let sample_id_col_idx = match data.get_header_index(&config.csv_sample_id_header) {
+            Some(col_idx) => col_idx,
+            None => return Err(format!("Couldn't find sample id header \"{}\" in the csv.", config.csv_sample_id_header)),
+        };
+        match model::get_split_records(&filtered_data,sample_id_col_idx) {
+            Ok(split_data_ok) => split_data_ok,
+            Err(msg) => return Err(format!("Couldn't split records based on \"{}\", which we think has 0-based col index {}. More info below:\n{}",config.csv_sample_id_header,sample_id_col_idx,msg)),
+        }//end matching whether we can get split data properly
+    };
+
+    let mut output = SampleOutput::new("external-sample-id");
+    // columns which can't be found are reported as missing for every kernel
+    let stat_col_idxs: Vec<Option<usize>> = config.csv_stat_columns_columns.iter().map(|col_label| data.get_header_index(col_label)).collect();
+    for col_label in config.csv_stat_columns_columns.iter() {
+        output.add_column(OutputColumn::number(&format!("Non-Numeric {}", col_label), 0));
+        output.add_column(OutputColumn::number(&format!("Missing {}", col_label), 0));
+    }//end adding each header we'll use to output
+
+    for (sample_id_val, rows) in split_data {
+        let mut output_row = Vec::new();
+        for stat_col_idx in stat_col_idxs.iter() {
+            let (non_numeric_count, missing_count) = match stat_col_idx {
+                Some(col_idx) => {
+                    let (_, non_numeric_count, missing_count) = stats::get_row_values(&rows, *col_idx);
+                    (non_numeric_count, missing_count)
+                },
+                None => (0, rows.len()),
+            };
+            output_row.push(DataVal::Int(non_numeric_count as i64));
+            output_row.push(DataVal::Int(missing_count as i64));
+        }//end counting problems in each stat column
+        output.add_row(&sample_id_val.to_string(), output_row);
+    }//end looping over each sample split
+
+    Ok(output)
+}//end proc_csv_diagnostics(data, config)
+
+/// Counts the frames, or images, that the kernels of each sample were
+/// seen in, using the column config.csv_frame_header, along with
+/// statistics on the number of kernels in each frame of the sample.  
+/// All kernels are counted, even those filtered out by class, since an
+/// unusual number of kernels per frame can point to a conveyor jam.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_csv_frame_stats;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "image-id".to_string()];
+/// let rows = vec![("A", "1"), ("A", "1"), ("A", "1"), ("A", "2"), ("B", "7")].into_iter().enumerate()
+///     .map(|(idx, (id, frame))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], frame.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// 
+/// let output = proc_csv_frame_stats(&data, &ConfigStore::default()).unwrap();
+/// assert_eq!(output.columns[2].name, "Avg Kernels per Frame");
+/// let sample_a = output.rows.iter().find(|row| row.sample_id == "A").unwrap();
+/// assert_eq!(sample_a.cells, vec![DataVal::Int(2), DataVal::Int(4), DataVal::Float(2.0), DataVal::Float(1.0), DataVal::Int(3)]);
+/// 
+/// let mut config = ConfigStore::default();
+/// config.csv_frame_header = "frame".to_string();
+/// assert!(proc_csv_frame_stats(&data, &config).is_err());
+/// ```
+pub fn proc_csv_frame_stats(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if config.csv_frame_header.trim().is_empty() {return Err(format!("No frame column set in config for frame stats!"));}
+    let frame_col_idx = match data.get_header_index(&config.csv_frame_header) {
+        Some(col_idx) => col_idx,
+        None => return Err(format!("Couldn't find frame header \"{}\" in the csv.", config.csv_frame_header)),
+    };
+    let sample_id_col_idx = match data.get_header_index(&config.csv_sample_id_header) {
+        Some(col_idx) => col_idx,
+        None => return Err(format!("Couldn't find sample id header \"{}\" in the csv.", config.csv_sample_id_header)),
+    };
+    let base_data = data.get_records();
+    let split_data = match model::get_split_records(&base_data, sample_id_col_idx) {
+        Ok(split_data_ok) => split_data_ok,
+        Err(msg) => return Err(format!("Couldn't split records based on \"{}\", which we think has 0-based col index {}. More info below:\n{}",config.csv_sample_id_header,sample_id_col_idx,msg)),
+    };
+
+    let mut output = SampleOutput::new("external-sample-id")
+        .with_column(OutputColumn::number("Frames", 0))
+        .with_column(OutputColumn::number("Kernels", 0))
+        .with_column(OutputColumn::number("Avg Kernels per Frame", 2))
+        .with_column(OutputColumn::number("Std Kernels per Frame", 2))
+        .with_column(OutputColumn::number("Max Kernels per Frame", 0));
+
+    for (sample_id_val, rows) in split_data {
+        // the frames of a sample are grouped the same way samples are
+        let frame_split = match model::get_split_records(&rows, frame_col_idx) {
+            Ok(frame_split) => frame_split,
+            Err(msg) => return Err(format!("Couldn't split the kernels of sample {} by frame. More info below:\n{}", sample_id_val, msg)),
+        };
+        let frame_counts: Vec<f64> = frame_split.iter().map(|(_, frame_rows)| frame_rows.len() as f64).collect();
+        let frame_count = frame_counts.len() as f64;
+        let mean = frame_counts.iter().sum::<f64>() / frame_count;
+        let variance = frame_counts.iter().map(|count| (count - mean).powf(2.0)).sum::<f64>() / frame_count;
+        let max = frame_counts.iter().copied().fold(0.0, f64::max);
+        output.add_row(&sample_id_val.to_string(), vec![
+            DataVal::Int(frame_counts.len() as i64),
+            DataVal::Int(rows.len() as i64),
+            DataVal::Float(mean),
+            DataVal::Float(variance.sqrt()),
+            DataVal::Int(max as i64),
+        ]);
+    }//end looping over each sample split
+
+    Ok(output)
+}//end proc_csv_frame_stats(data, config)
+
+/// The size of the t statistic from Welch's t-test above which a
+/// difference between the halves of a run counts as drift.
+/// This is the two-sided critical value for a significance level of 0.01.
+pub const DRIFT_T_THRESHOLD: f64 = 2.576;
+
+/// Checks each csv stat column for drift over the course of the run,
+/// such as from the instrument warming up, by comparing the mean of
+/// the first half of the kernels to the mean of the second half.  
+/// Kernels are taken in the order of config.drift_timestamp_header, or
+/// in row order if that's empty. Only kernels passing the class filters
+/// are used, the same as for the stat columns.  
+/// Each row of the output is a stat column rather than a sample, and
+/// a column is flagged if the halves differ by more than
+/// DRIFT_T_THRESHOLD using Welch's t-test.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_csv_drift_check;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string(), "Time".to_string(), "Area".to_string(), "Weight".to_string()];
+/// // rows are out of time order, and Area grows over time while Weight doesn't
+/// let values = vec![("8", "19", "30"), ("1", "10", "31"), ("2", "11", "29"), ("7", "18", "29"), ("3", "10", "30"), ("6", "20", "31"), ("4", "9", "30"), ("5", "21", "30")];
+/// let rows = values.into_iter().enumerate()
+///     .map(|(idx, (time, area, weight))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], "A".to_string()),
+///         DataCell::new(&headers[1], "Sound".to_string()),
+///         DataCell::new(&headers[2], time.to_string()),
+///         DataCell::new(&headers[3], area.to_string()),
+///         DataCell::new(&headers[4], weight.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_stat_columns_columns = vec!["Area".to_string(), "Weight".to_string()];
+/// config.drift_sheet_enabled = true;
+/// config.drift_timestamp_header = "Time".to_string();
+/// 
+/// let output = proc_csv_drift_check(&data, &config).unwrap();
+/// assert_eq!(output.rows[0].sample_id, "Area");
+/// let area_row = &output.rows[0].cells;
+/// assert_eq!(area_row[0], DataVal::Float(10.0));
+/// assert_eq!(area_row[1], DataVal::Float(19.5));
+/// assert_eq!(area_row[5], DataVal::String("Drift".to_string()));
+/// assert_eq!(output.rows[1].cells[5], DataVal::String("".to_string()));
+/// ```
+pub fn proc_csv_drift_check(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.drift_sheet_enabled {return Err(format!("Drift check is disabled in config!"));}
+    if config.csv_stat_columns_columns.len() < 1 {return Err(format!("No columns set in config to check for drift!"));}
+
+    let mut records = get_class_filtered_records(data, config)?;
+    if !config.drift_timestamp_header.trim().is_empty() {
+        let time_col_idx = match data.get_header_index(&config.drift_timestamp_header) {
+            Some(col_idx) => col_idx,
+            None => return Err(format!("Couldn't find timestamp header \"{}\" in the csv.", config.drift_timestamp_header)),
+        };
+        let get_time = |row: &DataRow| match row.get_data(time_col_idx).map(|cell| cell.get_data()) {
+            Some(DataVal::Int(i)) => (Some(*i as f64), i.to_string()),
+            Some(DataVal::Float(f)) => (Some(*f), f.to_string()),
+            Some(DataVal::String(s)) => (None, s.clone()),
+            None => (None, String::new()),
+        };
+        // numbers are compared as numbers, and anything else, like iso dates, as text
+        records.sort_by(|a, b| match (get_time(a), get_time(b)) {
+            ((Some(a_num), _), (Some(b_num), _)) => a_num.total_cmp(&b_num),
+            ((_, a_text), (_, b_text)) => a_text.cmp(&b_text),
+        });
+    }//end if we should sort kernels by time
+    if records.len() < 4 {return Err(format!("At least 4 kernels are needed to check for drift, but there are only {}.", records.len()));}
+    let (first_half, second_half) = records.split_at(records.len() / 2);
+    let (first_half, second_half) = (first_half.to_vec(), second_half.to_vec());
+
+    let mut output = SampleOutput::new("Metric")
+        .with_column(OutputColumn::number("First Half Mean", 2))
+        .with_column(OutputColumn::number("Second Half Mean", 2))
+        .with_column(OutputColumn::number("Change", 2))
+        .with_column(OutputColumn::number("Change %", 2))
+        .with_column(OutputColumn::number("t", 2))
+        .with_column(OutputColumn::number("Drift", 0));
+
+    // gives (mean, sample variance, count) of values
+    let get_mean_var = |values: &Vec<f64>| {
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = values.iter().map(|value| (value - mean).powf(2.0)).sum::<f64>() / (count - 1.0);
+        (mean, variance, count)
+    };
+    for col_label in config.csv_stat_columns_columns.iter() {
+        let col_idx = match data.get_header_index(col_label) {
+            Some(col_idx) => col_idx,
+            None => return Err(format!("Couldn't find header \"{}\" in the csv to check for drift.", col_label)),
+        };
+        let (first_values, _, _) = stats::get_row_values(&first_half, col_idx);
+        let (second_values, _, _) = stats::get_row_values(&second_half, col_idx);
+        if first_values.len() < 2 || second_values.len() < 2 {
+            log::warn!("Skipping drift check for {}, since it doesn't have enough numbers in each half of the run.", col_label);
+            continue;
+        }//end if there aren't enough numbers to compare
+        let (first_mean, first_var, first_count) = get_mean_var(&first_values);
+        let (second_mean, second_var, second_count) = get_mean_var(&second_values);
+        let change = second_mean - first_mean;
+        let std_error = (first_var / first_count + second_var / second_count).sqrt();
+        let t = if std_error > 0.0 {Some(change / std_error)} else {None};
+        let has_drift = match t {
+            Some(t) => t.abs() > DRIFT_T_THRESHOLD,
+            None => change != 0.0,
+        };
+        if has_drift {log::warn!("Warning: {} drifted from a mean of {:.2} to {:.2} over the run.", col_label, first_mean, second_mean);}
+        output.add_row(col_label, vec![
+            DataVal::Float(first_mean),
+            DataVal::Float(second_mean),
+            DataVal::Float(change),
+            if first_mean != 0.0 {DataVal::Float(change / first_mean * 100.0)} else {DataVal::String(String::new())},
+            match t {Some(t) => DataVal::Float(t), None => DataVal::String(String::new())},
+            DataVal::String(if has_drift {"Drift".to_string()} else {String::new()}),
+        ]);
+    }//end checking each stat column for drift
+
+    Ok(output)
+}//end proc_csv_drift_check(data, config)
+
+/// Does processing to find the percentage of each sample that belong to 
+/// each class. 
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_csv_class_per;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string()];
+/// let rows = vec![("A", "Sound"), ("A", "Sound"), ("A", "Sound"), ("A", "Broken"), ("B", "Sound")].into_iter().enumerate()
+///     .map(|(idx, (id, class))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], class.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let config = ConfigStore::default();
+/// 
+/// let output = proc_csv_class_per(&data, &config).unwrap();
+/// assert_eq!(output.rows[0].cells[0], DataVal::Float(0.75));
+/// assert_eq!(output.rows[0].cells[1], DataVal::Float(0.25));
+/// ```
+pub fn proc_csv_class_per(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.csv_class_percent_enabled {return Err(format!("CSV Class Percents are disabled in config!"));}
+    
+    // use cor-filtered-as, expected col 6 for class
+    let class_idx = data.get_header_index(&config.csv_class_filter_class).unwrap_or_else(|| {log::warn!("Couldn't find class header \"{}\"!\nResorting to Default!",&config.csv_class_filter_class); return 6;});
+
+    // only keep the kernels which should count towards the denominator
+    let base_data: Vec<&DataRow> = match config.csv_class_percent_denominator {
+        ClassPercentDenominator::AllKernels => data.get_records(),
+        ClassPercentDenominator::PassingNumericFilters => {
+            let numeric_filters = get_numeric_filters(data, &config.csv_numeric_filters)?;
+            data.get_records().into_iter().filter(|row| row_passes_numeric_filters(row, &numeric_filters)).collect()
+        },
+        ClassPercentDenominator::ExcludingClass => {
+            data.get_records().into_iter().filter(|row| match row.get_data(class_idx) {
+                Some(cell) => !cell.get_data().to_string().eq(&config.csv_class_percent_excluded_class),
+                None => true,
+            }).collect()
+        },
+    };
+    let split_data = {
+        let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).unwrap_or_else(|| {log::warn!("Couldn't find sample id header \"{}\"!\nResorting to Default!",&config.csv_sample_id_header); return 2;});
+        match model::get_split_records(&base_data, sample_id_col_idx) {
+            Ok(split_data_ok) => split_data_ok,
+            Err(msg) => return Err(format!("Couldn't split records based on \"{}\", which we think has 0-based col index {}. More info below:\n{}",&config.csv_sample_id_header,sample_id_col_idx,msg)),
+        }//end matching whether we can get split data properly
+    };
+
+    // (sample-id, vec<(class_name, count of class)>)
+    let sample_class_totals: Vec<(&DataVal, Vec<(&DataVal, usize)>)> = {
+        let mut s_c_t = Vec::new();
+        for (sample_id, sample_data) in split_data {
+            // s_c_t.push((sample_id, Vec::new()));
+            let mut this_sample_count: Vec<(&DataVal,usize)> = Vec::new();
+            for sample_row in sample_data {
+                match sample_row.get_data(class_idx) {
+                    Some(cell) => {
+                        let this_val = cell.get_data();
+                        if this_sample_count.iter().filter(|elem| (*elem.0).eq(this_val)).count() == 0 {this_sample_count.push((this_val, 0))}
+                        for (class_name, class_count) in &mut this_sample_count {
+                            if (*class_name).eq(this_val) {*class_count += 1; break;}
+                        }//end looping to find classes with this name
+                    },
+                    None => log::warn!("Couldn't access cell in 0-based col index {}, row {:?}!",class_idx,sample_row),
+                }//end matching whether we can access index
+            }//end looping over each row in data for this sample
+            s_c_t.push((sample_id, this_sample_count));
+        }//end looping over each sample
+        s_c_t
+    };
+
+    let all_class_options = {
+        let mut running_class_options = Vec::new();
+        for (_, class_totals) in sample_class_totals.iter() {
+            for (class_name, _) in class_totals.iter() {
+                if !running_class_options.contains(class_name) {
+                    running_class_options.push(class_name);
+                }//end if we don't already know this one
+            }//end looping over classes within this sample
+        }//end looping over class counts for each sample
+        running_class_options
+    };
+
+    let mut output = SampleOutput::new("external-sample-id");
+
+    for class_option in all_class_options.iter() {
+        output.add_column(OutputColumn::percent(&fill_header_template(&config.csv_class_percent_header_template, "{class}", &class_option.to_string()), 1));
+    }//end adding each class option as a header
+
+    for (sample_id, class_counts) in sample_class_totals {
+        let all_classes_count = class_counts.iter().fold(0, |accum, elem| accum + elem.1);
+        let mut this_sample_row = Vec::new();
+        for class_name in all_class_options.iter() {
+            let count_for_class = class_counts.iter().filter(|elem| (*elem.0).eq(class_name)).fold(0, |accum, elem| accum + elem.1);
+            let class_percent = count_for_class as f64 / all_classes_count as f64;// * 100.;
+            this_sample_row.push(DataVal::Float(class_percent));
+        }//end adding percent for each class option
+        output.add_row(&sample_id.to_string(), this_sample_row);
+    }//end looping over each sample's class counts
+
+    if let Some(class_taxonomy) = taxonomy::load_config_taxonomy(config)? {
+        taxonomy::add_rollup_columns(&mut output, &class_taxonomy, &config.csv_class_percent_header_template)?;
+    }//end if there are parent classes to total
+
+    return Ok(output);
+}//end proc_csv_class_per(data, config)
+
+/// The running totals of one stat column for one sample, kept while a csv
+/// file is streamed, so the average and standard deviation can be found
+/// without keeping the sample's rows.
+#[derive(Clone, PartialEq, Debug, Default)]
+struct RunningStat {
+    count: usize,
+    sum: f64,
+    sum_of_squares: f64,
+    /// The number of values which weren't numbers.
+    non_numeric: usize,
+}//end struct RunningStat
+
+/// Tells whether the current settings can be used by
+/// proc_csv_file_streaming(), returning an error describing the first
+/// setting which needs the whole file to be loaded if not.
+pub fn check_streaming_supported(config: &ConfigStore) -> Result<(),String> {
+    if !config.csv_stat_columns_enabled && !config.csv_class_percent_enabled {return Err(format!("Streaming a csv file makes the CSV_Stats and Class_Percents sheets, but both are disabled in config!"));}
+    let active_steps = pipeline::get_active_steps(config);
+    if active_steps.len() > 0 {
+        let step_names: Vec<&str> = active_steps.iter().map(|step| step.get_name()).collect();
+        return Err(format!("Streaming a csv file can't run the processing pipeline, but these steps have settings: {}.", step_names.join(", ")));
+    }//end if the pipeline would change the data
+    if config.csv_stat_columns_enabled && config.csv_stat_columns_stats.len() > 0 {return Err(format!("Streaming a csv file only finds averages and standard deviations, but extra stats are set: {}.", config.csv_stat_columns_stats.join(", ")));}
+    if config.lot_grouping_enabled {return Err(format!("Streaming a csv file can't group samples into lots, since lot grouping needs every sample's rows."));}
+    if config.csv_stat_columns_enabled && !config.csv_stat_group_header.trim().is_empty() {return Err(format!("Streaming a csv file can't group the rows of each sample by {}.", config.csv_stat_group_header));}
+    if !config.sample_id_pattern.trim().is_empty() && config.invalid_sample_id_handling == InvalidSampleIdHandling::Exclude {return Err(format!("Streaming a csv file can't leave out samples whose id doesn't match the sample id pattern {}.", config.sample_id_pattern.trim()));}
+    Ok(())
+}//end check_streaming_supported(config)
+
+/// Reads the csv file at file_path one row at a time, keeping only running
+/// sums and sums of squares for each sample, so that the CSV_Stats and
+/// Class_Percents sheets can be made for files too large to hold in memory.
+/// The header row, manual headers, and footer rows to skip are used the same
+/// way as when loading the file, and the sheets match those from
+/// proc_csv_stat_cols() and proc_csv_class_per() for the enabled outputs.
+/// Entirely blank rows are skipped.  
+/// progress is called now and then with the fraction of the file read so far.  
+/// See check_streaming_supported() for the settings which can't be used.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::{proc_csv_class_per, proc_csv_file_streaming, proc_csv_stat_cols};
+/// use usda_c_grain_sum::io::source::{CsvSource, DataSource, LoadOptions};
+/// use usda_c_grain_sum::model::DataVal;
+/// 
+/// let path = std::env::temp_dir().join("c_grain_sum_streaming_doctest.csv");
+/// std::fs::write(&path, "external-sample-id,raw-filtered-as,Area\nA,Sound,1.5\nA,Broken,2.5\nB,Sound,4.25\nA,Sound,6.5\nTotal,,14.75\n").unwrap();
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_class_filter_class = "raw-filtered-as".to_string();
+/// config.csv_stat_columns_columns = vec!["Area".to_string()];
+/// config.csv_footer_rows_to_skip = 1;
+/// 
+/// let sheets = proc_csv_file_streaming(&path, &config, &|_| {}).unwrap();
+/// let data = CsvSource.load(&path, &LoadOptions::from_config(&config)).unwrap();
+/// let stats = proc_csv_stat_cols(&data, &config).unwrap();
+/// assert_eq!(sheets[0].0, "CSV_Stats");
+/// assert_eq!(sheets[0].1.columns, stats.columns);
+/// assert_eq!(sheets[0].1.rows[0].cells[0], stats.rows[0].cells[0]);
+/// match (&sheets[0].1.rows[0].cells[1], &stats.rows[0].cells[1]) {
+///     (DataVal::Float(streamed), DataVal::Float(loaded)) => assert!((streamed - loaded).abs() < 0.000001),
+///     _ => panic!("standard deviations should be numbers"),
+/// }
+/// assert_eq!(sheets[1], ("Class_Percents".to_string(), proc_csv_class_per(&data, &config).unwrap()));
+/// 
+/// config.csv_stat_columns_stats = vec!["Median".to_string()];
+/// assert!(proc_csv_file_streaming(&path, &config, &|_| {}).is_err());
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn proc_csv_file_streaming(file_path: &Path, config: &ConfigStore, progress: &dyn Fn(f64)) -> Result<Vec<(String, SampleOutput)>,String> {
+    check_streaming_supported(config)?;
+    // the taxonomy is read first, so a bad file doesn't waste a long read
+    let class_taxonomy = match config.csv_class_percent_enabled {
+        true => taxonomy::load_config_taxonomy(config)?,
+        false => None,
+    };
+    let file_len = fs::metadata(file_path).map(|metadata| metadata.len()).unwrap_or(0).max(1) as f64;
+    let mut reader = match csv::ReaderBuilder::new().has_headers(false).flexible(true).from_path(file_path) {
+        Ok(reader) => reader,
+        Err(error) => return Err(format!("Couldn't get csv reader.\n{}", error)),
+    };
+    let mut records = reader.records();
+
+    // figure out the headers the same way as Data::from_csv_reader()
+    let mut rows_seen = 0;
+    let mut first_data_row = None;
+    let headers: Vec<String> = loop {
+        let record = match records.next() {
+            Some(Ok(record)) => record,
+            Some(Err(error)) => return Err(format!("Couldn't read row {} of the csv file.\n{}", rows_seen, error)),
+            None => return Err(format!("Couldn't find a header row at row index {} in the csv file.", config.csv_header_row)),
+        };
+        let record: StringRecord = match rows_seen {
+            0 => record.iter().enumerate().map(|(idx, cell)| if idx == 0 {cell.trim_start_matches('\u{feff}')} else {cell}).collect(),
+            _ => record,
+        };
+        rows_seen += 1;
+        if rows_seen <= config.csv_header_row {continue;}
+        if config.csv_manual_headers.len() > 0 {
+            first_data_row = Some(record);
+            break config.csv_manual_headers.clone();
+        } else if Data::looks_like_header_row(&record) {
+            break record.iter().map(|header| header.to_string()).collect();
+        } else {
+            log::warn!("The header row at index {} looks like data, so generic headers will be used instead.", config.csv_header_row);
+            let generated_headers = (1..=record.len()).map(|col_num| format!("Column {}", col_num)).collect();
+            first_data_row = Some(record);
+            break generated_headers;
+        }//end else the header row is really data
+    };
+    let get_col_idx = |header: &str| match headers.iter().position(|this_header| this_header == header) {
+        Some(col_idx) => Ok(col_idx),
+        None => Err(format!("Couldn't find the column \"{}\" in the csv file.", header)),
+    };
+    let sample_id_idx = get_col_idx(&config.csv_sample_id_header)?;
+    let needs_class = config.csv_class_percent_enabled || (config.csv_stat_columns_enabled && config.csv_class_filter_enabled && config.csv_class_filter_filters.len() > 0);
+    let class_idx = match needs_class {
+        true => Some(get_col_idx(&config.csv_class_filter_class)?),
+        false => None,
+    };
+    let stat_col_idxs = match config.csv_stat_columns_enabled {
+        true => config.csv_stat_columns_columns.iter().map(|header| get_col_idx(header)).collect::<Result<Vec<usize>,String>>()?,
+        false => Vec::new(),
+    };
+    let numeric_filters = match (config.csv_class_percent_enabled, config.csv_class_percent_denominator) {
+        (true, ClassPercentDenominator::PassingNumericFilters) => {
+            let mut numeric_filters = Vec::new();
+            for filter in config.csv_numeric_filters.iter().filter(|filter| !filter.trim().is_empty()) {
+                let numeric_filter = NumericFilter::parse(filter)?;
+                numeric_filters.push((get_col_idx(&numeric_filter.column)?, numeric_filter));
+            }//end parsing each filter
+            numeric_filters
+        },
+        _ => Vec::new(),
+    };
+
+    // (sample id, running totals of each stat column), in order of first appearance
+    let mut sample_stats: Vec<(String, Vec<RunningStat>)> = Vec::new();
+    let mut sample_stats_idxs: HashMap<String, usize> = HashMap::new();
+    // (sample id, (class, count) in order of first appearance)
+    let mut sample_classes: Vec<(String, Vec<(DataVal, usize)>)> = Vec::new();
+    let mut sample_classes_idxs: HashMap<String, usize> = HashMap::new();
+    let mut skipped_rows = 0;
+    let mut add_row = |record: &StringRecord| {
+        if record.len() != headers.len() {skipped_rows += 1; return;}
+        let get_val = |col_idx: usize| DataCell::new(&headers[col_idx], record[col_idx].to_string()).get_data().clone();
+        let sample_id = get_val(sample_id_idx).to_string();
+        let class_val = class_idx.map(&get_val);
+
+        let passes_class_filter = !config.csv_class_filter_enabled || config.csv_class_filter_filters.len() == 0 || match &class_val {
+            Some(DataVal::String(class)) => config.csv_class_filter_filters.contains(class),
+            _ => false,
+        };
+        if config.csv_stat_columns_enabled && passes_class_filter {
+            let sample_idx = *sample_stats_idxs.entry(sample_id.clone()).or_insert_with(|| {
+                sample_stats.push((sample_id.clone(), vec![RunningStat::default(); stat_col_idxs.len()]));
+                sample_stats.len() - 1
+            });
+            for (running_stat, col_idx) in sample_stats[sample_idx].1.iter_mut().zip(stat_col_idxs.iter()) {
+                let value = match get_val(*col_idx) {
+                    DataVal::Int(value) => value as f64,
+                    DataVal::Float(value) => value,
+                    DataVal::String(_) => {running_stat.non_numeric += 1; continue;},
+                };
+                running_stat.count += 1;
+                running_stat.sum += value;
+                running_stat.sum_of_squares += value * value;
+            }//end adding to the running totals of each stat column
+        }//end if this row counts towards the stat columns
+
+        if let (true, Some(class_val)) = (config.csv_class_percent_enabled, class_val) {
+            let counts_toward_denominator = match config.csv_class_percent_denominator {
+                ClassPercentDenominator::AllKernels => true,
+                ClassPercentDenominator::ExcludingClass => !class_val.to_string().eq(&config.csv_class_percent_excluded_class),
+                ClassPercentDenominator::PassingNumericFilters => numeric_filters.iter().all(|(col_idx, filter)| match get_val(*col_idx) {
+                    DataVal::Int(val) => filter.passes(val as f64),
+                    DataVal::Float(val) => filter.passes(val),
+                    DataVal::String(_) => false,
+                }),
+            };
+            if counts_toward_denominator {
+                let sample_idx = *sample_classes_idxs.entry(sample_id.clone()).or_insert_with(|| {
+                    sample_classes.push((sample_id.clone(), Vec::new()));
+                    sample_classes.len() - 1
+                });
+                let class_counts = &mut sample_classes[sample_idx].1;
+                match class_counts.iter_mut().find(|(class_name, _)| class_name.eq(&class_val)) {
+                    Some((_, class_count)) => *class_count += 1,
+                    None => class_counts.push((class_val, 1)),
+                }//end matching whether we've seen this class in this sample
+            }//end if this row counts towards the class percents
+        }//end if we're finding class percents
+    };
+
+    // rows are held back until we know they aren't footer rows
+    let mut pending_rows: VecDeque<StringRecord> = VecDeque::new();
+    let mut row_idx = 0;
+    for record in first_data_row.into_iter().map(Ok).chain(&mut records) {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {log::warn!("{}", error); continue;},
+        };
+        row_idx += 1;
+        if row_idx % 10000 == 0 {progress(record.position().map(|position| position.byte() as f64 / file_len).unwrap_or(0.0));}
+        if record.iter().all(|cell| cell.trim() == "") {continue;}
+        pending_rows.push_back(record);
+        if pending_rows.len() > config.csv_footer_rows_to_skip {
+            if let Some(record) = pending_rows.pop_front() {add_row(&record);}
+        }//end if the oldest held row can't be a footer row
+    }//end reading each row of the csv file
+    if skipped_rows > 0 {log::warn!("Skipped {} rows because they didn't have the same number of cells as there are headers.", skipped_rows);}
+    progress(1.0);
+
+    let mut output_sheets = Vec::new();
+    if config.csv_stat_columns_enabled {
+        let mut output = SampleOutput::new("external-sample-id");
+        for col_label in config.csv_stat_columns_columns.iter() {
+            let decimal_places = get_stat_decimal_places(col_label);
+            let avg_column = OutputColumn::number(&fill_header_template(&config.csv_stat_avg_header_template, "{col}", col_label), decimal_places);
+            let std_column = OutputColumn::number(&fill_header_template(&config.csv_stat_std_header_template, "{col}", col_label), decimal_places);
+            match get_stat_column_group(col_label, config) {
+                Some(group) => {
+                    output.add_column(avg_column.with_group(&group));
+                    output.add_column(std_column.with_group(&group));
+                },
+                None => {
+                    output.add_column(avg_column);
+                    output.add_column(std_column);
+                },
+            }//end matching whether the stats of this column are grouped
+        }//end adding each header we'll use to output
+        for (sample_id, running_stats) in sample_stats {
+            let mut output_row = Vec::new();
+            let mut notes = Vec::new();
+            for (running_stat, stat_col_header) in running_stats.iter().zip(config.csv_stat_columns_columns.iter()) {
+                let count = running_stat.count as f64;
+                let col_avg = running_stat.sum / count;
+                let col_std = match running_stat.non_numeric {
+                    0 => ((running_stat.sum_of_squares - running_stat.sum * running_stat.sum / count) / count).max(0.0).sqrt(),
+                    _ => {
+                        log::warn!("\nCouldn't calculate standard deviation for column {} and sample id {} because of a string being present in the data.", stat_col_header, sample_id);
+                        notes.push((output_row.len() + 1, "stdev skipped: non-numeric data"));
+                        -1000.0
+                    },
+                };
+                output_row.push(DataVal::Float(col_avg));
+                output_row.push(DataVal::Float(col_std));
+            }//end finding the stats of each column
+            output.add_row(&sample_id, output_row);
+            if let Some(added_row) = output.rows.last_mut() {
+                for (col_idx, note) in notes {added_row.add_note(col_idx, note);}
+            }//end attaching notes about skipped values
+        }//end adding a row for each sample
+        output_sheets.push((String::from("CSV_Stats"), output));
+    }//end if we're making the stat columns
+
+    if config.csv_class_percent_enabled {
+        let mut all_class_options: Vec<&DataVal> = Vec::new();
+        for (_, class_counts) in sample_classes.iter() {
+            for (class_name, _) in class_counts.iter() {
+                if !all_class_options.contains(&class_name) {all_class_options.push(class_name);}
+            }//end looping over classes within this sample
+        }//end looping over class counts for each sample
+        let mut output = SampleOutput::new("external-sample-id");
+        for class_option in all_class_options.iter() {
+            output.add_column(OutputColumn::percent(&fill_header_template(&config.csv_class_percent_header_template, "{class}", &class_option.to_string()), 1));
+        }//end adding each class option as a header
+        for (sample_id, class_counts) in sample_classes.iter() {
+            let all_classes_count = class_counts.iter().fold(0, |accum, elem| accum + elem.1);
+            let this_sample_row = all_class_options.iter().map(|class_name| {
+                let count_for_class = class_counts.iter().filter(|elem| elem.0.eq(*class_name)).fold(0, |accum, elem| accum + elem.1);
+                DataVal::Float(count_for_class as f64 / all_classes_count as f64)
+            }).collect();
+            output.add_row(sample_id, this_sample_row);
+        }//end looping over each sample's class counts
+        if let Some(class_taxonomy) = &class_taxonomy {
+            taxonomy::add_rollup_columns(&mut output, class_taxonomy, &config.csv_class_percent_header_template)?;
+        }//end if there are parent classes to total
+        output_sheets.push((String::from("Class_Percents"), output));
+    }//end if we're making the class percents
+
+    Ok(output_sheets)
+}//end proc_csv_file_streaming(file_path, config, progress)
+
+/// Processes the data provided to get the Metrics sheet, which has a
+/// column for each metric named in config.metrics_sheet_metrics.  
+/// Metrics are defined once in config.metric_definitions, and are found
+/// by name, so the same metric is always calculated and formatted the same way.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal, OutputColumn, OutputRow};
+/// use usda_c_grain_sum::app::process::proc_csv_metrics;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string(), "Weight".to_string()];
+/// let rows = vec![("A", "Sound", "0.03"), ("A", "Broken", "0.01"), ("B", "Sound", "0.02")].into_iter().enumerate()
+///     .map(|(idx, (id, class, weight))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], class.to_string()),
+///         DataCell::new(&headers[2], weight.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.metrics_sheet_metrics = vec!["Kernel Count".to_string(), "%Sound".to_string()];
+/// 
+/// let output = proc_csv_metrics(&data, &config).unwrap();
+/// assert_eq!(output.columns, vec![OutputColumn::number("Kernel Count", 0), OutputColumn::percent("%Sound", 1)]);
+/// assert_eq!(output.rows[0], OutputRow::new("A", vec![DataVal::Int(2), DataVal::Float(0.5)]));
+/// 
+/// config.metrics_sheet_metrics = vec!["Median Weight".to_string()];
+/// assert!(proc_csv_metrics(&data, &config).is_err());
+/// ```
+pub fn proc_csv_metrics(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if config.metrics_sheet_metrics.len() < 1 {return Err(format!("No metrics set in config for the Metrics sheet!"));}
+    let registry = metrics::get_metric_registry(config)?;
+    let mut sheet_metrics = Vec::new();
+    for metric_name in config.metrics_sheet_metrics.iter() {
+        sheet_metrics.push(metrics::find_metric(&registry, metric_name)?);
+    }//end finding each metric used on the sheet
+
+    // remember which kernels pass the class filter, so each sample can be filtered
+    let filtered_row_idxs: Vec<usize> = get_class_filtered_records(data, config)?.iter().map(|row| *row.get_row_idx()).collect();
+    let base_data = data.get_records();
+    let split_data = {
+        let sample_id_col_idx = match data.get_header_index(&config.csv_sample_id_header) {
+            Some(col_idx) => col_idx,
+            None => return Err(format!("Couldn't find sample id header \"{}\" in the csv.", config.csv_sample_id_header)),
+        };
+        match model::get_split_records(&base_data, sample_id_col_idx) {
+            Ok(split_data_ok) => split_data_ok,
+            Err(msg) => return Err(format!("Couldn't split records based on \"{}\", which we think has 0-based col index {}. More info below:\n{}",config.csv_sample_id_header,sample_id_col_idx,msg)),
+        }//end matching whether we can get split data properly
+    };
+
+    let mut output = SampleOutput::new("external-sample-id");
+    for metric in sheet_metrics.iter() {
+        output.add_column(match metric.is_percent() {
+            true => OutputColumn::percent(&metric.name, metric.decimals),
+            false => OutputColumn::number(&metric.name, metric.decimals),
+        });
+    }//end adding a column for each metric
+    for (sample_id_val, rows) in split_data {
+        let filtered_rows: Vec<&DataRow> = rows.iter().copied().filter(|row| filtered_row_idxs.contains(row.get_row_idx())).collect();
+        let mut output_row = Vec::new();
+        for metric in sheet_metrics.iter() {
+            match metric.calculate(data, config, &rows, &filtered_rows) {
+                Ok(value) => output_row.push(value),
+                Err(msg) => return Err(format!("Couldn't calculate the metric {} for sample {}:\n{}", metric.name, sample_id_val, msg)),
+            }//end matching whether we could calculate the metric
+        }//end calculating each metric for this sample
+        output.add_row(&sample_id_val.to_string(), output_row);
+    }//end looping over each sample split
+
+    Ok(output)
+}//end proc_csv_metrics(data, config)
+
+/// A filter on a numeric column, such as "Area > 10".  
+/// Rows pass the filter if their value in column compares
+/// to value as indicated by op.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NumericFilter {
+    /// The name of the column to compare.
+    pub column: String,
+    /// The comparison, one of >, >=, <, <=, =, or !=.
+    pub op: String,
+    /// The value to compare the column against.
+    pub value: f64,
+}//end struct NumericFilter
+
+impl NumericFilter {
+    /// Parses a filter from text in the form of "column op value",
+    /// such as "Area > 10" or "Mean Width<=2.5".
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::app::process::NumericFilter;
+    /// 
+    /// let filter = NumericFilter::parse("Mean Width <= 2.5").unwrap();
+    /// assert_eq!(filter.column, "Mean Width");
+    /// assert_eq!(filter.op, "<=");
+    /// assert_eq!(filter.value, 2.5);
+    /// assert!(filter.passes(2.5));
+    /// assert!(!filter.passes(3.0));
+    /// assert!(NumericFilter::parse("Area is big").is_err());
+    /// assert!(NumericFilter::parse("Area > big").is_err());
+    /// ```
+    pub fn parse(filter: &str) -> Result<NumericFilter,String> {
+        // two-character operators are checked first so that ">=" isn't read as ">"
+        for op in [">=", "<=", "!=", ">", "<", "="] {
+            if let Some(op_idx) = filter.find(op) {
+                let column = filter[..op_idx].trim().to_string();
+                let value_str = filter[op_idx + op.len()..].trim();
+                if column.is_empty() {return Err(format!("The numeric filter \"{}\" has no column name.", filter));}
+                return match value_str.parse::<f64>() {
+                    Ok(value) => Ok(NumericFilter { column, op: op.to_string(), value }),
+                    Err(_) => Err(format!("Couldn't read \"{}\" in numeric filter \"{}\" as a number.", value_str, filter)),
+                };
+            }//end if we found this operator in the filter
+        }//end checking for each operator
+        Err(format!("Couldn't find a comparison like >, >=, <, <=, =, or != in numeric filter \"{}\".", filter))
+    }//end parse(filter)
+
+    /// Returns true if val passes this filter.
+    pub fn passes(&self, val: f64) -> bool {
+        match self.op.as_str() {
+            ">=" => val >= self.value,
+            "<=" => val <= self.value,
+            "!=" => val != self.value,
+            ">" => val > self.value,
+            "<" => val < self.value,
+            "=" => val == self.value,
+            _ => false,
+        }//end matching the comparison to use
+    }//end passes(self, val)
+}//end impl NumericFilter
+
+/// Parses each filter in filters, pairing it with the index
+/// of the column it filters on in data.
+pub fn get_numeric_filters(data: &Data, filters: &Vec<String>) -> Result<Vec<(usize, NumericFilter)>,String> {
+    let mut numeric_filters = Vec::new();
+    for filter in filters.iter().filter(|filter| !filter.trim().is_empty()) {
+        let numeric_filter = NumericFilter::parse(filter)?;
+        match data.get_header_index(&numeric_filter.column) {
+            Some(col_idx) => numeric_filters.push((col_idx, numeric_filter)),
+            None => return Err(format!("Couldn't find column \"{}\" from numeric filter \"{}\".", numeric_filter.column, filter)),
+        }//end matching whether we can find the column to filter on
+    }//end parsing each filter
+    Ok(numeric_filters)
+}//end get_numeric_filters(data, filters)
+
+/// Returns true if row passes every filter in filters.  
+/// Rows with a non-numeric value in a filtered column don't pass.
+pub fn row_passes_numeric_filters(row: &DataRow, filters: &Vec<(usize, NumericFilter)>) -> bool {
+    filters.iter().all(|(col_idx, filter)| {
+        match row.get_data(*col_idx).map(|cell| cell.get_data()) {
+            Some(DataVal::Int(val)) => filter.passes(*val as f64),
+            Some(DataVal::Float(val)) => filter.passes(*val),
+            _ => false,
+        }//end matching the value in the filtered column
+    })
+}//end row_passes_numeric_filters(row, filters)
+
+/// Gets the header of the group holding the stat columns of col_label,
+/// such as Avg Length and Std Length, from
+/// config.csv_stat_column_group_template, such as "{col} (mm)".  
+/// Returns None if there's no template, so the stat columns aren't grouped.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::get_stat_column_group;
+/// 
+/// let mut config = ConfigStore::default();
+/// assert_eq!(get_stat_column_group("Length", &config), None);
+/// config.csv_stat_column_group_template = "{col} (mm)".to_string();
+/// assert_eq!(get_stat_column_group("Length", &config), Some("Length (mm)".to_string()));
+/// ```
+pub fn get_stat_column_group(col_label: &str, config: &ConfigStore) -> Option<String> {
+    match config.csv_stat_column_group_template.trim() {
+        "" => None,
+        template => Some(fill_header_template(template, "{col}", col_label)),
+    }//end matching whether there's a group template
+}//end get_stat_column_group(col_label, config)
+
+/// Creates a header name from a template, such as "Avg {col}", by
+/// replacing every instance of token in the template with value.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::fill_header_template;
+/// 
+/// assert_eq!(fill_header_template("Avg {col}", "{col}", "Length"), "Avg Length");
+/// assert_eq!(fill_header_template("Mean{col}_mm", "{col}", "Length"), "MeanLength_mm");
+/// assert_eq!(fill_header_template("%{class}", "{class}", "Sound"), "%Sound");
+/// ```
+pub fn fill_header_template(template: &str, token: &str, value: &str) -> String {
+    template.replace(token, value)
+}//end fill_header_template(template, token, value)
+
+/// Gets the number of decimal places that statistics on the
+/// csv column col_label should be displayed with.
+pub fn get_stat_decimal_places(col_label: &str) -> usize {
+    match col_label {
+        "Weight" | "Light" | "Saturation" => 4,
+        "Hue" | "Red" | "Green" | "Blue" => 1,
+        _ => 2,
+    }//end matching col_label to decimal places
+}//end get_stat_decimal_places(col_label)
+
+/// Processes csv data along with metadata about each sample, such as
+/// the treatment or variety of each sample, to get a SampleOutput with
+/// a row for each treatment.  
+/// Each row has the number of samples in the treatment, followed by the
+/// mean and standard deviation, across those samples, of each sample's
+/// average for each of config.treatment_metrics.  
+/// Samples are matched to metadata rows using config.csv_sample_id_header
+/// and config.metadata_sample_id_header. Samples without metadata are skipped.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::app::process::proc_treatment_comparison;
+/// 
+/// let make_data = |headers: Vec<&str>, rows: Vec<Vec<&str>>| {
+///     let headers: Vec<String> = headers.into_iter().map(|h| h.to_string()).collect();
+///     let rows = rows.into_iter().enumerate().map(|(idx, row)| DataRow::new(idx, row.into_iter().zip(headers.iter()).map(|(val, h)| DataCell::new(h, val.to_string())).collect())).collect();
+///     Data::from_row_data(headers, rows)
+/// };
+/// let data = make_data(vec!["external-sample-id", "Area"], vec![vec!["S1", "1.0"], vec!["S1", "3.0"], vec!["S2", "4.0"], vec!["S3", "9.0"]]);
+/// let metadata = make_data(vec!["external-sample-id", "Treatment"], vec![vec!["S1", "Dry"], vec!["S2", "Dry"], vec!["S3", "Wet"]]);
+/// 
+/// let mut config = ConfigStore::default();
+/// config.treatment_sheet_enabled = true;
+/// config.treatment_columns = vec!["Treatment".to_string()];
+/// config.treatment_metrics = vec!["Area".to_string()];
+/// let output = proc_treatment_comparison(&data, &metadata, &config).unwrap();
+/// 
+/// assert_eq!(output.id_header, "Treatment");
+/// assert_eq!(output.rows[0].sample_id, "Dry");
+/// // sample averages of 2.0 and 4.0
+/// assert_eq!(output.rows[0].cells, vec![DataVal::Int(2), DataVal::Float(3.0), DataVal::Float(1.0)]);
+/// assert_eq!(output.rows[1].sample_id, "Wet");
+/// ```
+pub fn proc_treatment_comparison(data: &Data, metadata: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.treatment_sheet_enabled {return Err(format!("Treatment comparison is disabled in config!"));}
+    if config.treatment_columns.len() < 1 {return Err(format!("No treatment columns set in config to group samples by!"));}
+    if config.treatment_metrics.len() < 1 {return Err(format!("No metrics set in config to compare treatments on!"));}
+
+    let meta_id_col_idx = metadata.get_header_index(&config.metadata_sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the metadata.", config.metadata_sample_id_header))?;
+    let mut treatment_col_idxs = Vec::new();
+    for treatment_col in config.treatment_columns.iter() {
+        treatment_col_idxs.push(metadata.get_header_index(treatment_col).ok_or(format!("Couldn't find treatment column \"{}\" in the metadata.", treatment_col))?);
+    }//end finding each treatment column
+    let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the csv data.", config.csv_sample_id_header))?;
+    let mut metric_col_idxs = Vec::new();
+    for metric in config.treatment_metrics.iter() {
+        metric_col_idxs.push(data.get_header_index(metric).ok_or(format!("Couldn't find metric column \"{}\" in the csv data.", metric))?);
+    }//end finding each metric column
+
+    let base_data = data.get_records();
+    let split_data = model::get_split_records(&base_data, sample_id_col_idx)?;
+
+    // (treatment, vec of each sample's average for each metric)
+    let mut treatment_groups: Vec<(String, Vec<Vec<f64>>)> = Vec::new();
+    for (sample_id, rows) in split_data {
+        let sample_id = sample_id.to_string();
+        let meta_row = metadata.get_records_ref().iter().find(|row| {
+            row.get_data(meta_id_col_idx).map(|cell| cell.get_data().to_string().eq(&sample_id)).unwrap_or(false)
+        });
+        let meta_row = match meta_row {
+            Some(meta_row) => meta_row,
+            None => {log::warn!("Couldn't find metadata for sample {}, so it will be left out of treatment comparison.", sample_id); continue;},
+        };
+        let treatment = treatment_col_idxs.iter()
+            .map(|col_idx| meta_row.get_data(*col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default())
+            .collect::<Vec<String>>().join(" / ");
+        let mut sample_avgs = Vec::new();
+        for (metric, col_idx) in config.treatment_metrics.iter().zip(metric_col_idxs.iter()) {
+            match get_col_avg_sngl(&rows, *col_idx) {
+                Ok(avg) => sample_avgs.push(avg),
+                Err(msg) => return Err(format!("Encountered an error while trying to find the average value in column {} for rows with sample id {}:\n{}", metric, sample_id, msg)),
+            }//end matching whether we could get the average for this sample
+        }//end getting the average of each metric
+        match treatment_groups.iter_mut().find(|(known_treatment, _)| known_treatment.eq(&treatment)) {
+            Some((_, samples)) => samples.push(sample_avgs),
+            None => treatment_groups.push((treatment, vec![sample_avgs])),
+        }//end matching whether we've seen this treatment already
+    }//end looping over each sample
+
+    let mut output = SampleOutput::new(&config.treatment_columns.join(" / "));
+    output.add_column(OutputColumn::number("Samples", 0));
+    for metric in config.treatment_metrics.iter() {
+        let decimal_places = get_stat_decimal_places(metric);
+        output.add_column(OutputColumn::number(&fill_header_template(&config.csv_stat_avg_header_template, "{col}", metric), decimal_places));
+        output.add_column(OutputColumn::number(&fill_header_template(&config.csv_stat_std_header_template, "{col}", metric), decimal_places));
+    }//end adding headers for each metric
+
+    for (treatment, samples) in treatment_groups {
+        let mut output_row = vec![DataVal::Int(samples.len() as i64)];
+        for metric_idx in 0..config.treatment_metrics.len() {
+            let count = samples.len() as f64;
+            let mean = samples.iter().map(|sample| sample[metric_idx]).sum::<f64>() / count;
+            let variance = samples.iter().map(|sample| (sample[metric_idx] - mean).powf(2.0)).sum::<f64>() / count;
+            output_row.push(DataVal::Float(mean));
+            output_row.push(DataVal::Float(variance.sqrt()));
+        }//end calculating mean and stdev of each metric
+        output.add_row(&treatment, output_row);
+    }//end adding a row for each treatment
+
+    return Ok(output);
+}//end proc_treatment_comparison(data, metadata, config)
+
+/// The screen size of one sieve in the xml data, along with the
+/// percent of each sample that's expected to be caught by it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SieveSize {
+    /// The xml tag holding the sieve data, such as "filter-sieving-1".
+    pub tag: String,
+    /// The size of the screen, in mm.
+    pub screen_size: f64,
+    /// The target percent for the sieve, if there is one.
+    pub target_percent: Option<f64>,
+}//end struct SieveSize
+
+impl SieveSize {
+    /// Parses a sieve size from text in the form of "tag -> screen size"
+    /// or "tag -> screen size, target percent".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::process::SieveSize;
+    ///
+    /// assert_eq!(SieveSize::parse("filter-sieving-1 -> 4.76, 10").unwrap(), SieveSize { tag: "filter-sieving-1".to_string(), screen_size: 4.76, target_percent: Some(10.0) });
+    /// assert_eq!(SieveSize::parse("filter-sieving-2 -> 2.38").unwrap().target_percent, None);
+    /// assert!(SieveSize::parse("filter-sieving-2 -> 5/64 in").is_err());
+    /// assert!(SieveSize::parse("filter-sieving-2").is_err());
+    /// ```
+    pub fn parse(sieve_size: &str) -> Result<SieveSize,String> {
+        let (tag, size_text) = match sieve_size.split_once("->") {
+            Some((tag, size_text)) => (tag.trim().to_string(), size_text),
+            None => return Err(format!("Couldn't find -> in sieve size \"{}\". It should look like \"filter-sieving-1 -> 4.76, 10\".", sieve_size)),
+        };
+        if tag.is_empty() {return Err(format!("The sieve size \"{}\" has no tag.", sieve_size));}
+        let (screen_text, target_text) = match size_text.split_once(',') {
+            Some((screen_text, target_text)) => (screen_text.trim(), Some(target_text.trim())),
+            None => (size_text.trim(), None),
+        };
+        let screen_size = match screen_text.parse::<f64>() {
+            Ok(screen_size) if screen_size > 0.0 => screen_size,
+            _ => return Err(format!("Couldn't read \"{}\" in sieve size \"{}\" as a screen size in mm.", screen_text, sieve_size)),
+        };
+        let target_percent = match target_text {
+            Some(target_text) => match target_text.trim_end_matches('%').trim().parse::<f64>() {
+                Ok(target_percent) => Some(target_percent),
+                Err(_) => return Err(format!("Couldn't read \"{}\" in sieve size \"{}\" as a target percent.", target_text, sieve_size)),
+            },
+            None => None,
+        };
+        Ok(SieveSize { tag, screen_size, target_percent })
+    }//end parse(sieve_size)
+}//end impl SieveSize
+
+/// Parses each non-empty line of sieve_sizes, such as from
+/// config.xml_sieve_sizes, with SieveSize::parse().  
+/// Returns an error if any line can't be read, or if a tag is listed twice.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::process::parse_sieve_sizes;
+///
+/// let sieve_sizes = vec!["filter-sieving-1 -> 4.76, 10".to_string(), "".to_string(), "filter-sieving-2 -> 2.38".to_string()];
+/// assert_eq!(parse_sieve_sizes(&sieve_sizes).unwrap().len(), 2);
+/// assert!(parse_sieve_sizes(&vec!["filter-sieving-1 -> 4.76".to_string(), "filter-sieving-1 -> 2.38".to_string()]).is_err());
+/// ```
+pub fn parse_sieve_sizes(sieve_sizes: &Vec<String>) -> Result<Vec<SieveSize>,String> {
+    let mut parsed: Vec<SieveSize> = Vec::new();
+    for sieve_size in sieve_sizes.iter().filter(|sieve_size| !sieve_size.trim().is_empty()) {
+        let sieve_size = SieveSize::parse(sieve_size)?;
+        if parsed.iter().any(|other| other.tag == sieve_size.tag) {
+            return Err(format!("The sieve tag \"{}\" is listed more than once in the sieve sizes.", sieve_size.tag));
+        }//end if this tag was already listed
+        parsed.push(sieve_size);
+    }//end parsing each sieve size
+    Ok(parsed)
+}//end parse_sieve_sizes(sieve_sizes)
+
+/// Checks the sieve sizes against the columns of data loaded from xml.  
+/// Returns a description of each problem found, which are sieve tags
+/// that aren't in data, and sieving columns in data without a size.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::{check_sieve_sizes, parse_sieve_sizes};
+///
+/// let headers: Vec<String> = vec!["reference", "filter-sieving-1", "filter-sieving-2"].into_iter().map(|h| h.to_string()).collect();
+/// let cells = headers.iter().zip(["S1", "12.5", "80"]).map(|(h, v)| DataCell::new(h, v.to_string())).collect();
+/// let data = Data::from_row_data(headers.clone(), vec![DataRow::new(0, cells)]);
+///
+/// let sieve_sizes = parse_sieve_sizes(&vec!["filter-sieving-1 -> 4.76".to_string(), "filter-sieving-3 -> 2.38".to_string()]).unwrap();
+/// let problems = check_sieve_sizes(&data, &sieve_sizes);
+/// assert_eq!(problems.len(), 2);
+/// assert!(problems[0].contains("filter-sieving-3"));
+/// assert!(problems[1].contains("filter-sieving-2"));
+/// ```
+pub fn check_sieve_sizes(data: &Data, sieve_sizes: &Vec<SieveSize>) -> Vec<String> {
+    let mut problems = Vec::new();
+    for sieve_size in sieve_sizes.iter() {
+        if data.get_header_index(&sieve_size.tag).is_none() {
+            problems.push(format!("The sieve tag \"{}\" isn't in the xml file.", sieve_size.tag));
+        }//end if the tag is missing from the data
+    }//end checking each sieve size
+    for header in data.get_headers_ref().iter().filter(|header| header.starts_with("filter-sieving")) {
+        if !sieve_sizes.iter().any(|sieve_size| sieve_size.tag.eq(header)) {
+            problems.push(format!("The xml column \"{}\" doesn't have a sieve size.", header));
+        }//end if this sieving column isn't in the table
+    }//end checking each sieving column
+    problems
+}//end check_sieve_sizes(data, sieve_sizes)
+
+/// Parses the tolerance for the sieve total check, such as from
+/// config.xml_sieve_sum_tolerance, in percentage points.  
+/// Returns None if tolerance is empty, meaning the check is skipped.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::process::parse_sieve_sum_tolerance;
+///
+/// assert_eq!(parse_sieve_sum_tolerance("2.5"), Ok(Some(2.5)));
+/// assert_eq!(parse_sieve_sum_tolerance(" "), Ok(None));
+/// assert!(parse_sieve_sum_tolerance("-1").is_err());
+/// assert!(parse_sieve_sum_tolerance("two").is_err());
+/// ```
+pub fn parse_sieve_sum_tolerance(tolerance: &str) -> Result<Option<f64>,String> {
+    if tolerance.trim().is_empty() {return Ok(None);}
+    match tolerance.trim().trim_end_matches('%').trim().parse::<f64>() {
+        Ok(tolerance) if tolerance >= 0.0 => Ok(Some(tolerance)),
+        _ => Err(format!("Couldn't read \"{}\" as a tolerance for the sieve total. It should be a number of percentage points, such as 2.", tolerance)),
+    }//end matching whether tolerance is a usable number
+}//end parse_sieve_sum_tolerance(tolerance)
+
+/// Gets the flag for a sample whose sieve fractions add to total
+/// percent, which is empty if total is within tolerance of 100%, or
+/// otherwise says how far off it is.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::process::get_sieve_total_flag;
+///
+/// assert_eq!(get_sieve_total_flag(99.2, 2.0), "");
+/// assert_eq!(get_sieve_total_flag(91.5, 2.0), "Off by -8.5%");
+/// assert_eq!(get_sieve_total_flag(103.0, 2.0), "Off by +3.0%");
+/// ```
+pub fn get_sieve_total_flag(total: f64, tolerance: f64) -> String {
+    let difference = total - 100.0;
+    match difference.abs() > tolerance {
+        true => format!("Off by {:+.1}%", difference),
+        false => String::new(),
+    }//end matching whether total is close enough to 100%
+}//end get_sieve_total_flag(total, tolerance)
+
+/// Converts Data from xml into a SampleOutput.  
+/// It is assumed that any necessary processing has already been done,
+/// and the sample id is called "external-sample-id" or has index 1.  
+/// Columns listed in config.xml_sieve_sizes are labeled with their
+/// screen size and written last, largest screen first. Each one with a
+/// target percent is followed by a column giving the difference from
+/// that target.  
+/// Unless config.xml_sieve_sum_tolerance is empty, the sieve fractions
+/// of each sample are added up, and samples whose total is too far
+/// from 100%, such as from a short pour, are flagged in the output and
+/// logged as a warning.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::proc_xml_sieve_data;
+/// use usda_c_grain_sum::model::DataVal;
+/// 
+/// let headers = vec!["reference".to_string(), "filter-sieving-top".to_string(), "filter-sieving-bottom".to_string()];
+/// let rows = vec![("S1", "60", "40"), ("S2", "50", "40")].into_iter().enumerate()
+///     .map(|(idx, (id, top, bottom))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], top.to_string()),
+///         DataCell::new(&headers[2], bottom.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let config = ConfigStore::default();
+/// 
+/// let output = proc_xml_sieve_data(&data, &config).unwrap();
+/// let names: Vec<&str> = output.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["filter-sieving-top", "filter-sieving-bottom", "Sieve Total %", "Sieve Total Check"]);
+/// assert_eq!(output.rows[1].cells[3], DataVal::String("Off by -10.0%".to_string()));
+/// ```
+pub fn proc_xml_sieve_data(data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.xml_sieve_cols_enabled {return Err(format!("XML Sieve Data is disabled in the config!"));}
+    let sieve_sizes = parse_sieve_sizes(&config.xml_sieve_sizes)?;
+    let sum_tolerance = parse_sieve_sum_tolerance(&config.xml_sieve_sum_tolerance)?;
+
+    let base_data = data.get_records();
+
+    let mut output = SampleOutput::new("external-sample-id");
+
+    let sample_id_col_idx = data.get_header_index(&config.xml_sample_id_header).unwrap_or_else(|| {log::warn!("Couldn't find xml sample-id header \"{}\"!\nResorting to Default!",&config.xml_sample_id_header); return 0;});
+
+    // (col idx, sieve size) of each column to output, with columns from the sieve sizes last
+    let mut output_cols: Vec<(usize, Option<&SieveSize>)> = Vec::new();
+    for (col_idx, header) in data.get_headers().iter().enumerate() {
+        if col_idx <= sample_id_col_idx {continue;}
+        if sieve_sizes.iter().any(|sieve_size| sieve_size.tag.eq(header.as_str())) {continue;}
+        output_cols.push((col_idx, None));
+    }//end finding columns from sample_id_col_idx onwards without a sieve size
+    let mut sorted_sizes: Vec<&SieveSize> = sieve_sizes.iter().collect();
+    sorted_sizes.sort_by(|a, b| b.screen_size.total_cmp(&a.screen_size));
+    for sieve_size in sorted_sizes {
+        match data.get_header_index(&sieve_size.tag) {
+            Some(col_idx) if col_idx > sample_id_col_idx => output_cols.push((col_idx, Some(sieve_size))),
+            _ => log::warn!("Couldn't find sieve tag \"{}\" in the xml data, so it will be left out.", sieve_size.tag),
+        }//end matching whether the sieve is in the data
+    }//end adding sieve columns, largest screen first
+
+    for (col_idx, sieve_size) in output_cols.iter() {
+        match sieve_size {
+            Some(sieve_size) => {
+                output.add_column(OutputColumn::number(&format!("{} ({} mm)", sieve_size.tag, sieve_size.screen_size), 2));
+                if sieve_size.target_percent.is_some() {output.add_column(OutputColumn::number(&format!("{} vs Target", sieve_size.tag), 2));}
+            },
+            None => output.add_column(OutputColumn::number(&data.get_header_from_index(*col_idx).cloned().unwrap_or_default(), 2)),
+        }//end matching whether this column has a sieve size
+    }//end filling output with headers
+    if sum_tolerance.is_some() {
+        output.add_column(OutputColumn::number("Sieve Total %", 2));
+        output.add_column(OutputColumn::number("Sieve Total Check", 0));
+    }//end if we're checking sieve totals
+
+    // just add the raw data to output, we assume it was processed already
+    for row in base_data {
+        match row.get_data(sample_id_col_idx) {
+            Some(sample_id) => {
+                let mut datavals: Vec<DataVal> = Vec::new();
+                let mut sieve_total = 0.0;
+                for (col_idx, sieve_size) in output_cols.iter() {
+                    let dataval = row.get_data(*col_idx).map(|datacell| datacell.get_data().clone()).unwrap_or(DataVal::String(String::new()));
+                    let target_val = match (sieve_size.and_then(|sieve_size| sieve_size.target_percent), &dataval) {
+                        (Some(target_percent), DataVal::Int(i)) => Some(DataVal::Float(*i as f64 - target_percent)),
+                        (Some(target_percent), DataVal::Float(f)) => Some(DataVal::Float(f - target_percent)),
+                        (Some(_), _) => Some(DataVal::String(String::new())),
+                        (None, _) => None,
+                    };
+                    let is_sieve_col = sieve_size.is_some() || data.get_header_from_index(*col_idx).map(|header| header.starts_with("filter-sieving")).unwrap_or(false);
+                    match &dataval {
+                        DataVal::Int(i) if is_sieve_col => sieve_total += *i as f64,
+                        DataVal::Float(f) if is_sieve_col => sieve_total += f,
+                        _ => {},
+                    }//end matching whether this value counts towards the sieve total
+                    datavals.push(dataval);
+                    if let Some(target_val) = target_val {datavals.push(target_val);}
+                }//end looping over each output column
+                if let Some(sum_tolerance) = sum_tolerance {
+                    let flag = get_sieve_total_flag(sieve_total, sum_tolerance);
+                    if !flag.is_empty() {log::warn!("Warning: The sieve fractions of sample {} add to {:.1}%, which is more than {}% from 100%.", sample_id.get_data(), sieve_total, sum_tolerance);}
+                    datavals.push(DataVal::Float(sieve_total));
+                    datavals.push(DataVal::String(flag));
+                }//end if we're checking sieve totals
+                output.add_row(&sample_id.get_data().to_string(), datavals);
+            },
+            None => log::warn!("\nSkipping a row during XML Output!: {:?}\nCouldn't get the sample_id for row idx {}.\nExpected 0-based col-idx of {} for header \"external-sample-id\", but row data has length of {}.\n",row,row.get_row_idx(),sample_id_col_idx,row.get_row_data().len()),
+        }//en dmatching whether we can get the row data
+    }//end looping over each row
+
+    return Ok(output);
+}//end proc_xml_sieve_data(data,config)
+
+/// Joins right onto left by sample id, giving one SampleOutput with the
+/// columns of left followed by the columns of right. Sample ids are
+/// matched ignoring surrounding whitespace. Samples only in left or only
+/// in right still get a row, with the cells of the other side left blank,
+/// so no sample is dropped. Rows of left come first, in their order,
+/// then the rows only in right.  
+/// Columns of right whose name is already used in left have right_label
+/// added after their name, such as "Moisture (XML)".
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+/// use usda_c_grain_sum::app::process::join_sample_outputs;
+/// 
+/// let mut csv_stats = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Area", 2))
+///     .with_column(OutputColumn::number("Moisture", 1));
+/// csv_stats.add_row("S1", vec![DataVal::Float(12.5), DataVal::Float(11.0)]);
+/// csv_stats.add_row("S2", vec![DataVal::Float(13.0), DataVal::Float(10.5)]);
+/// let mut sieve_data = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Moisture", 2));
+/// sieve_data.add_row(" S2 ", vec![DataVal::Float(10.75)]);
+/// sieve_data.add_row("S3", vec![DataVal::Float(9.5)]);
+/// sieve_data.rows[0].add_note(0, "short pour");
+/// 
+/// let merged = join_sample_outputs(&csv_stats, &sieve_data, "XML");
+/// let names: Vec<&str> = merged.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Avg Area", "Moisture", "Moisture (XML)"]);
+/// assert_eq!(merged.rows.len(), 3);
+/// assert_eq!(merged.rows[0].cells, vec![DataVal::Float(12.5), DataVal::Float(11.0), DataVal::String(String::new())]);
+/// assert_eq!(merged.rows[1].cells, vec![DataVal::Float(13.0), DataVal::Float(10.5), DataVal::Float(10.75)]);
+/// assert_eq!(merged.rows[1].get_note(2).unwrap(), "short pour");
+/// assert_eq!(merged.rows[2], OutputRow::new("S3", vec![DataVal::String(String::new()), DataVal::String(String::new()), DataVal::Float(9.5)]));
+/// ```
+pub fn join_sample_outputs(left: &SampleOutput, right: &SampleOutput, right_label: &str) -> SampleOutput {
+    let mut joined = SampleOutput::new(&left.id_header);
+    joined.columns = left.columns.clone();
+    for column in right.columns.iter() {
+        let mut column = column.clone();
+        if left.get_column_index(&column.name).is_some() {column.name = format!("{} ({})", column.name, right_label);}
+        joined.add_column(column);
+    }//end adding each column of right after those of left
+    let left_col_count = left.columns.len();
+    let blank = DataVal::String(String::new());
+    // indices of the rows of right which were matched to a row of left
+    let mut matched_right = Vec::new();
+    for left_row in left.rows.iter() {
+        let mut joined_row = left_row.clone();
+        joined_row.cells.resize(left_col_count, blank.clone());
+        if let Some(right_idx) = right.rows.iter().position(|right_row| right_row.sample_id.trim() == left_row.sample_id.trim()) {
+            let right_row = &right.rows[right_idx];
+            joined_row.cells.extend(right_row.cells.iter().cloned());
+            for (col_idx, note) in right_row.notes.iter() {joined_row.add_note(col_idx + left_col_count, note);}
+            matched_right.push(right_idx);
+        }//end if right has this sample
+        joined_row.cells.resize(joined.columns.len(), blank.clone());
+        joined.rows.push(joined_row);
+    }//end joining each row of left
+    for (right_idx, right_row) in right.rows.iter().enumerate() {
+        if matched_right.contains(&right_idx) {continue;}
+        let mut joined_row = OutputRow::new(right_row.sample_id.trim(), vec![blank.clone(); left_col_count]);
+        joined_row.cells.extend(right_row.cells.iter().cloned());
+        joined_row.cells.resize(joined.columns.len(), blank.clone());
+        for (col_idx, note) in right_row.notes.iter() {joined_row.add_note(col_idx + left_col_count, note);}
+        joined.rows.push(joined_row);
+    }//end adding the samples only in right
+    joined
+}//end join_sample_outputs(left, right, right_label)
+
+/// Processes csv_data into csv stat columns and xml_data into sieve data,
+/// like proc_csv_stat_cols() and proc_xml_sieve_data(), and joins them by
+/// the configured sample-id headers with join_sample_outputs(), so each
+/// sample has its kernel stats and sieve data side by side on one row.  
+/// The csv stat columns and sieve data are used even if their own sheets
+/// are turned off in config.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::proc_merged_output;
+/// 
+/// let make_data = |headers: Vec<&str>, rows: Vec<Vec<&str>>| {
+///     let headers: Vec<String> = headers.into_iter().map(|h| h.to_string()).collect();
+///     let rows = rows.into_iter().enumerate().map(|(idx, row)| DataRow::new(idx, row.into_iter().zip(headers.iter()).map(|(val, h)| DataCell::new(h, val.to_string())).collect())).collect();
+///     Data::from_row_data(headers, rows)
+/// };
+/// let csv_data = make_data(vec!["external-sample-id", "Area"], vec![vec!["S1", "10"], vec!["S1", "14"], vec!["S2", "12"]]);
+/// let xml_data = make_data(vec!["reference", "filter-sieving-top"], vec![vec!["S2", "55"], vec!["S3", "60"]]);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Area".to_string()];
+/// config.xml_sieve_sum_tolerance = String::new();
+/// config.merged_sheet_enabled = true;
+/// 
+/// let output = proc_merged_output(&csv_data, &xml_data, &config).unwrap();
+/// let names: Vec<&str> = output.columns.iter().map(|column| column.name.as_str()).collect();
+/// assert_eq!(names, vec!["Avg Area", "Std Area", "filter-sieving-top"]);
+/// let ids: Vec<&str> = output.rows.iter().map(|row| row.sample_id.as_str()).collect();
+/// assert_eq!(ids, vec!["S1", "S2", "S3"]);
+/// ```
+pub fn proc_merged_output(csv_data: &Data, xml_data: &Data, config: &ConfigStore) -> Result<SampleOutput,String> {
+    if !config.merged_sheet_enabled {return Err(format!("Merged sheet is disabled in config!"));}
+    let mut merge_config = config.clone();
+    merge_config.csv_stat_columns_enabled = true;
+    merge_config.xml_sieve_cols_enabled = true;
+    let csv_output = proc_csv_stat_cols(csv_data, &merge_config)?;
+    let xml_output = proc_xml_sieve_data(xml_data, &merge_config)?;
+    Ok(join_sample_outputs(&csv_output, &xml_output, "XML"))
+}//end proc_merged_output(csv_data, xml_data, config)
+
+/// Gets the lot id from a sample id, which is the part of the
+/// sample id before the last instance of delimiter.  
+/// If delimiter isn't found, or is empty, then the whole
+/// sample id is used as the lot id.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::get_lot_id;
+/// 
+/// assert_eq!(get_lot_id("W23-07-3", "-"), "W23-07");
+/// assert_eq!(get_lot_id("W23_07", "-"), "W23_07");
+/// assert_eq!(get_lot_id("W23-07", ""), "W23-07");
+/// ```
+pub fn get_lot_id(sample_id: &str, delimiter: &str) -> String {
+    if delimiter.is_empty() {return sample_id.to_string();}
+    match sample_id.rfind(delimiter) {
+        Some(delim_idx) => sample_id[..delim_idx].to_string(),
+        None => sample_id.to_string(),
+    }//end matching whether we found the delimiter
+}//end get_lot_id(sample_id, delimiter)
+
+/// Gets the lot id of each row in data, in the same order as the rows.  
+/// If config has a lot id header which is found in data, then the value
+/// in that column is used. Otherwise, the lot id is found from the sample
+/// id in sample_id_header, using get_lot_id() and the configured delimiter.
+pub fn get_row_lot_ids(data: &Data, sample_id_header: &str, config: &ConfigStore) -> Result<Vec<String>,String> {
+    let lot_col_idx = match config.lot_id_header.is_empty() {
+        true => None,
+        false => data.get_header_index(&config.lot_id_header),
+    };
+    let sample_id_col_idx = match data.get_header_index(sample_id_header) {
+        Some(col_idx) => col_idx,
+        None => return Err(format!("Couldn't find sample id header \"{}\" when grouping samples into lots.", sample_id_header)),
+    };
+    let mut lot_ids = Vec::new();
+    for row in data.get_records_ref() {
+        let lot_id = match lot_col_idx {
+            Some(lot_col_idx) => row.get_data(lot_col_idx).map(|cell| cell.get_data().to_string()),
+            None => row.get_data(sample_id_col_idx).map(|cell| get_lot_id(&cell.get_data().to_string(), &config.lot_id_delimiter)),
+        };
+        match lot_id {
+            Some(lot_id) => lot_ids.push(lot_id),
+            None => return Err(format!("Couldn't get the lot id for row idx {}.", row.get_row_idx())),
+        }//end matching whether we could get the lot id
+    }//end getting the lot id for each row
+    Ok(lot_ids)
+}//end get_row_lot_ids(data, sample_id_header, config)
+
+/// Creates a copy of data where the value in the sample id column of each
+/// row is replaced with the lot id of that row, so that processing the
+/// copy gives one row per lot instead of one row per sample.  
+/// Also returns the lot id of each sample id, in order of first appearance.
+pub fn get_lot_data(data: &Data, sample_id_header: &str, config: &ConfigStore) -> Result<(Data, Vec<(String, String)>),String> {
+    let lot_ids = get_row_lot_ids(data, sample_id_header, config)?;
+    let sample_id_col_idx = data.get_header_index(sample_id_header).unwrap_or(0);
+    let mut sample_lots: Vec<(String, String)> = Vec::new();
+    let mut lot_rows = Vec::new();
+    for (row, lot_id) in data.get_records_ref().iter().zip(lot_ids) {
+        let mut row_data = row.get_row_data().clone();
+        if let Some(sample_cell) = row_data.get_mut(sample_id_col_idx) {
+            let sample_id = sample_cell.get_data().to_string();
+            if !sample_lots.iter().any(|(known_id, _)| known_id.eq(&sample_id)) {sample_lots.push((sample_id, lot_id.clone()));}
+            *sample_cell = DataCell::new_from_val(sample_cell.get_header(), DataVal::String(lot_id));
+        }//end if we can replace the sample id with the lot id
+        lot_rows.push(DataRow::new(*row.get_row_idx(), row_data));
+    }//end replacing sample id of each row
+    Ok((Data::from_row_data(data.get_headers_ref().clone(), lot_rows), sample_lots))
+}//end get_lot_data(data, sample_id_header, config)
+
+/// Creates a SampleOutput with one row per lot, where each value is
+/// the average of the numeric values for the samples in that lot.  
+/// Non-numeric values are left blank. This is meant for sheets, like
+/// xml sieve data, which only have one row per sample to begin with.
+pub fn get_lot_avg_output(sample_output: &SampleOutput, sample_lots: &Vec<(String, String)>) -> SampleOutput {
+    let mut lot_output = SampleOutput::new(&sample_output.id_header);
+    lot_output.columns = sample_output.columns.clone();
+    let mut lot_order: Vec<&String> = Vec::new();
+    for (_, lot_id) in sample_lots.iter() {if !lot_order.contains(&lot_id) {lot_order.push(lot_id);}}
+    for lot_id in lot_order {
+        let lot_rows: Vec<&Vec<DataVal>> = sample_output.rows.iter()
+            .filter(|output_row| sample_lots.iter().any(|(known_id, known_lot)| known_id.eq(&output_row.sample_id) && known_lot.eq(lot_id)))
+            .map(|output_row| &output_row.cells).collect();
+        let mut lot_row = Vec::new();
+        for col_idx in 0..sample_output.columns.len() {
+            let nums: Vec<f64> = lot_rows.iter().filter_map(|row| match row.get(col_idx) {
+                Some(DataVal::Int(val)) => Some(*val as f64),
+                Some(DataVal::Float(val)) => Some(*val),
+                _ => None,
+            }).collect();
+            if nums.len() == 0 {lot_row.push(DataVal::String(String::new()));}
+            else {lot_row.push(DataVal::Float(nums.iter().sum::<f64>() / nums.len() as f64));}
+        }//end averaging each column
+        lot_output.add_row(lot_id, lot_row);
+    }//end making a row for each lot
+    lot_output
+}//end get_lot_avg_output(sample_output, sample_lots)
+
+/// Adds lot rows from lot_output into sample_output, so that the rows
+/// for each lot's samples are followed by the row for that lot.  
+/// Lot rows are labeled with config.lot_row_label_template, where {lot} is
+/// replaced with the lot id. Values in lot rows are matched to the columns
+/// of sample_output by header name, and left blank if there's no match.  
+/// Samples without a known lot are kept at the end.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::app::process::merge_lot_rows;
+/// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let mut sample_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2)],
+///     rows: vec![
+///         OutputRow::new("A-1", vec![DataVal::Float(1.0)]),
+///         OutputRow::new("B-1", vec![DataVal::Float(3.0)]),
+///         OutputRow::new("A-2", vec![DataVal::Float(2.0)]),
+///     ],
+/// };
+/// let lot_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2)],
+///     rows: vec![
+///         OutputRow::new("A", vec![DataVal::Float(1.5)]),
+///         OutputRow::new("B", vec![DataVal::Float(3.0)]),
+///     ],
+/// };
+/// let sample_lots = vec![("A-1".to_string(), "A".to_string()), ("B-1".to_string(), "B".to_string()), ("A-2".to_string(), "A".to_string())];
+/// merge_lot_rows(&mut sample_output, lot_output, &sample_lots, &ConfigStore::default());
+/// 
+/// let ids: Vec<&str> = sample_output.rows.iter().map(|output_row| output_row.sample_id.as_str()).collect();
+/// assert_eq!(ids, vec!["A-1", "A-2", "Lot A", "B-1", "Lot B"]);
+/// assert_eq!(sample_output.rows[2].cells, vec![DataVal::Float(1.5)]);
+/// ```
+pub fn merge_lot_rows(sample_output: &mut SampleOutput, lot_output: SampleOutput, sample_lots: &Vec<(String, String)>, config: &ConfigStore) {
+    let mut sample_rows: Vec<Option<OutputRow>> = sample_output.rows.drain(..).map(Some).collect();
+    for lot in lot_output.rows.iter() {
+        for sample_row in sample_rows.iter_mut() {
+            let in_lot = match sample_row {
+                Some(output_row) => sample_lots.iter().any(|(known_id, known_lot)| known_id.eq(&output_row.sample_id) && known_lot.eq(&lot.sample_id)),
+                None => false,
+            };
+            if in_lot {sample_output.rows.push(sample_row.take().unwrap());}
+        }//end moving over each sample in this lot
+        let mut lot_row = Vec::new();
+        for column in sample_output.columns.iter() {
+            match lot_output.get_column_index(&column.name) {
+                Some(lot_col_idx) => lot_row.push(lot.cells.get(lot_col_idx).cloned().unwrap_or(DataVal::String(String::new()))),
+                None => lot_row.push(DataVal::String(String::new())),
+            }//end matching whether lot output has this header
+        }//end matching lot values to sample headers
+        sample_output.add_row(&fill_header_template(&config.lot_row_label_template, "{lot}", &lot.sample_id), lot_row);
+    }//end adding each lot after its samples
+    sample_output.rows.extend(sample_rows.into_iter().flatten());
+}//end merge_lot_rows(sample_output, lot_output, sample_lots, config)
+
+/// Gets each unique sample id in data, in order of first appearance.
+/// If the sample id column can't be found, no ids are returned.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_sample_ids;
+/// 
+/// let headers = vec!["external-sample-id".to_string()];
+/// let rows = vec!["B", "A", "B"].into_iter().enumerate()
+///     .map(|(idx, id)| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// assert_eq!(get_sample_ids(&data, "external-sample-id"), vec!["B".to_string(), "A".to_string()]);
+/// assert!(get_sample_ids(&data, "sample").is_empty());
+/// ```
+pub fn get_sample_ids(data: &Data, sample_id_header: &str) -> Vec<String> {
+    let mut sample_ids: Vec<String> = Vec::new();
+    if let Some(sample_id_col_idx) = data.get_header_index(sample_id_header) {
+        for row in data.get_records_ref().iter() {
+            if let Some(cell) = row.get_data(sample_id_col_idx) {
+                let sample_id = cell.get_data().to_string();
+                if !sample_ids.contains(&sample_id) {sample_ids.push(sample_id);}
+            }//end if this row has a sample id
+        }//end checking each row for a new sample id
+    }//end if we can find the sample id column
+    sample_ids
+}//end get_sample_ids(data, sample_id_header)
+
+/// Compiles pattern, a regular expression from the config, into a Regex
+/// which only matches a whole sample id, not just part of one.  
+/// Returns None if pattern is empty, since then sample ids aren't checked.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::get_sample_id_regex;
+/// 
+/// let regex = get_sample_id_regex(r"\d{12}").unwrap().unwrap();
+/// assert!(regex.is_match("202403190019"));
+/// assert!(!regex.is_match("2024031900l9"));
+/// assert!(!regex.is_match("2024031900190"));
+/// assert!(get_sample_id_regex(" ").unwrap().is_none());
+/// assert!(get_sample_id_regex(r"\d{12").is_err());
+/// ```
+pub fn get_sample_id_regex(pattern: &str) -> Result<Option<Regex>,String> {
+    if pattern.trim().is_empty() {return Ok(None);}
+    match Regex::new(&format!("^(?:{})$", pattern.trim())) {
+        Ok(regex) => Ok(Some(regex)),
+        Err(error) => Err(format!("Couldn't read the sample id pattern \"{}\".\n{}", pattern.trim(), error)),
+    }//end matching whether the pattern is a valid regular expression
+}//end get_sample_id_regex(pattern)
+
+/// Gets the sample ids in data which don't match the sample id pattern in
+/// config, such as misread barcodes, in the order they're found.  
+/// Returns an empty list if there's no pattern.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::get_invalid_sample_ids;
+/// 
+/// let headers = vec!["external-sample-id".to_string()];
+/// let rows = vec!["202403190018", "2024031900l9", "202403190020", "2024031900l9"].into_iter().enumerate()
+///     .map(|(idx, id)| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// assert!(get_invalid_sample_ids(&data, &config.csv_sample_id_header, &config).unwrap().is_empty());
+/// config.sample_id_pattern = r"\d{12}".to_string();
+/// assert_eq!(get_invalid_sample_ids(&data, &config.csv_sample_id_header, &config).unwrap(), vec!["2024031900l9".to_string()]);
+/// ```
+pub fn get_invalid_sample_ids(data: &Data, sample_id_header: &str, config: &ConfigStore) -> Result<Vec<String>,String> {
+    match get_sample_id_regex(&config.sample_id_pattern)? {
+        Some(regex) => Ok(get_sample_ids(data, sample_id_header).into_iter().filter(|sample_id| !regex.is_match(sample_id)).collect()),
+        None => Ok(Vec::new()),
+    }//end matching whether there's a pattern to check
+}//end get_invalid_sample_ids(data, sample_id_header, config)
+
+/// Finds problems in the loaded csv and xml data which the enabled
+/// output could be affected by, as (category, description) for each.  
+/// Main decides what to do about each with the WarningAction set in
+/// config for its category, so this only finds them.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::config_store::{ConfigStore, WarningCategory};
+/// use usda_c_grain_sum::app::process::get_input_warnings;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "10"), ("A", "n/a"), ("B", "12")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let csv_data = Data::from_row_data(headers.clone(), rows);
+/// let xml_header = vec!["sample-id".to_string()];
+/// let xml_rows = vec![DataRow::new(0, vec![DataCell::new(&xml_header[0], "A".to_string())])];
+/// let xml_data = Data::from_row_data(xml_header.clone(), xml_rows);
+/// 
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_class_percent_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Area".to_string(), "Weight".to_string()];
+/// config.xml_sample_id_header = "sample-id".to_string();
+/// let categories: Vec<WarningCategory> = get_input_warnings(Some(&csv_data), Some(&xml_data), &config)
+///     .into_iter().map(|(category, _)| category).collect();
+/// assert_eq!(categories, vec![WarningCategory::MissingColumn, WarningCategory::NonNumericValue, WarningCategory::UnmatchedSample]);
+/// assert!(get_input_warnings(None, Some(&xml_data), &config).is_empty());
+/// 
+/// config.sample_id_pattern = "[A-Z]-[0-9]+".to_string();
+/// let warnings = get_input_warnings(None, Some(&xml_data), &config);
+/// assert_eq!(warnings[0].0, WarningCategory::InvalidSampleId);
+/// assert_eq!(warnings[0].1, "1 sample id(s) in the xml file don't match the sample id pattern [A-Z]-[0-9]+, so they'll be flagged as BAD_ID: A.");
+/// ```
+pub fn get_input_warnings(csv_data: Option<&Data>, xml_data: Option<&Data>, config: &ConfigStore) -> Vec<(WarningCategory, String)> {
+    let mut warnings = Vec::new();
+    // the most sample ids to list in one warning, so the message stays readable
+    let max_listed_ids = 5;
+    let list_ids = |sample_ids: &Vec<&String>| -> String {
+        let mut id_list = sample_ids.iter().take(max_listed_ids).map(|sample_id| sample_id.as_str()).collect::<Vec<&str>>().join(", ");
+        if sample_ids.len() > max_listed_ids {id_list.push_str(&format!(", and {} more", sample_ids.len() - max_listed_ids));}
+        id_list
+    };
+
+    if let Some(csv_data) = csv_data {
+        let missing_headers = config.get_missing_csv_headers(csv_data.get_headers_ref());
+        if missing_headers.len() > 0 {
+            warnings.push((WarningCategory::MissingColumn, format!("The csv file doesn't have the column(s) {}.", missing_headers.join(", "))));
+        }//end if the csv file is missing columns
+        if config.csv_stat_columns_enabled {
+            for col_label in config.csv_stat_columns_columns.iter() {
+                let col_idx = match csv_data.get_header_index(col_label) {
+                    Some(col_idx) => col_idx,
+                    None => continue,
+                };
+                let (_, non_numeric_count, _) = stats::get_column_values(csv_data, col_idx);
+                if non_numeric_count > 0 {
+                    warnings.push((WarningCategory::NonNumericValue, format!("The csv column {} has {} value(s) which aren't numbers.", col_label, non_numeric_count)));
+                }//end if this column has text in it
+            }//end checking each stat column for text
+        }//end if stat columns will be processed
+    }//end if we have csv data to check
+    if let Some(xml_data) = xml_data {
+        if config.xml_sieve_cols_enabled && xml_data.get_header_index(&config.xml_sample_id_header).is_none() {
+            warnings.push((WarningCategory::MissingColumn, format!("The xml file doesn't have the tag {}.", config.xml_sample_id_header)));
+        }//end if the xml file is missing the sample id
+    }//end if we have xml data to check
+    if let (Some(csv_data), Some(xml_data)) = (csv_data, xml_data) {
+        let csv_ids = get_sample_ids(csv_data, &config.csv_sample_id_header);
+        let xml_ids = get_sample_ids(xml_data, &config.xml_sample_id_header);
+        let csv_only_ids: Vec<&String> = csv_ids.iter().filter(|sample_id| !xml_ids.contains(sample_id)).collect();
+        let xml_only_ids: Vec<&String> = xml_ids.iter().filter(|sample_id| !csv_ids.contains(sample_id)).collect();
+        if csv_only_ids.len() > 0 && xml_ids.len() > 0 {
+            warnings.push((WarningCategory::UnmatchedSample, format!("{} sample(s) in the csv file aren't in the xml file: {}.", csv_only_ids.len(), list_ids(&csv_only_ids))));
+        }//end if some csv samples have no xml data
+        if xml_only_ids.len() > 0 && csv_ids.len() > 0 {
+            warnings.push((WarningCategory::UnmatchedSample, format!("{} sample(s) in the xml file aren't in the csv file: {}.", xml_only_ids.len(), list_ids(&xml_only_ids))));
+        }//end if some xml samples have no csv data
+    }//end if we can match samples between files
+    let handling = match config.invalid_sample_id_handling {
+        InvalidSampleIdHandling::Flag => "flagged as BAD_ID",
+        InvalidSampleIdHandling::Exclude => "left out of the output",
+    };
+    for (kind_name, data, sample_id_header) in [("csv", csv_data, &config.csv_sample_id_header), ("xml", xml_data, &config.xml_sample_id_header)] {
+        let data = match data {Some(data) => data, None => continue};
+        match get_invalid_sample_ids(data, sample_id_header, config) {
+            Ok(invalid_ids) if invalid_ids.len() > 0 => {
+                let invalid_ids: Vec<&String> = invalid_ids.iter().collect();
+                warnings.push((WarningCategory::InvalidSampleId, format!("{} sample id(s) in the {} file don't match the sample id pattern {}, so they'll be {}: {}.", invalid_ids.len(), kind_name, config.sample_id_pattern.trim(), handling, list_ids(&invalid_ids))));
+            },
+            Ok(_) => {},
+            Err(msg) => {
+                warnings.push((WarningCategory::InvalidSampleId, msg));
+                break;
+            },
+        }//end matching whether any sample ids don't match the pattern
+    }//end checking the sample ids of each file
+    warnings
+}//end get_input_warnings(csv_data, xml_data, config)
+
+/// Creates a copy of data holding only the rows whose value in the
+/// sample id column is one of sample_ids, so that only those samples
+/// can be processed again.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_sample_subset;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "10"), ("B", "12"), ("A", "14"), ("C", "9")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// 
+/// let subset = get_sample_subset(&data, "external-sample-id", &vec!["A".to_string(), "C".to_string()]).unwrap();
+/// assert_eq!(subset.get_records_ref().len(), 3);
+/// assert!(get_sample_subset(&data, "sample", &vec!["A".to_string()]).is_err());
+/// ```
+pub fn get_sample_subset(data: &Data, sample_id_header: &str, sample_ids: &Vec<String>) -> Result<Data,String> {
+    let sample_id_col_idx = data.get_header_index(sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\".", sample_id_header))?;
+    let subset_rows = data.get_records_ref().iter()
+        .filter(|row| match row.get_data(sample_id_col_idx) {
+            Some(cell) => sample_ids.contains(&cell.get_data().to_string()),
+            None => false,
+        })
+        .cloned()
+        .collect();
+    Ok(Data::from_row_data(data.get_headers_ref().clone(), subset_rows))
+}//end get_sample_subset(data, sample_id_header, sample_ids)
+
+/// Creates a copy of data without the rows whose value in the sample
+/// id column is one of sample_ids, such as samples the user chose to
+/// exclude from the output.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_data_without_samples;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "10"), ("B", "12"), ("A", "14"), ("C", "9")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// 
+/// let remaining = get_data_without_samples(&data, "external-sample-id", &vec!["A".to_string()]).unwrap();
+/// assert_eq!(remaining.get_records_ref().len(), 2);
+/// assert!(get_data_without_samples(&data, "sample", &vec!["A".to_string()]).is_err());
+/// ```
+pub fn get_data_without_samples(data: &Data, sample_id_header: &str, sample_ids: &Vec<String>) -> Result<Data,String> {
+    let sample_id_col_idx = data.get_header_index(sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\".", sample_id_header))?;
+    let remaining_rows = data.get_records_ref().iter()
+        .filter(|row| match row.get_data(sample_id_col_idx) {
+            Some(cell) => !sample_ids.contains(&cell.get_data().to_string()),
+            None => true,
+        })
+        .cloned()
+        .collect();
+    Ok(Data::from_row_data(data.get_headers_ref().clone(), remaining_rows))
+}//end get_data_without_samples(data, sample_id_header, sample_ids)
+
+/// Replaces the rows in sample_output with the rows in new_output that have
+/// the same sample id, leaving all other rows, such as lot rows, as they were.  
+/// If both have a column named group_header, such as from
+/// config.csv_stat_group_header, a sample has a row for each group, so rows
+/// are matched by sample id and group. A group a reprocessed sample now has
+/// gets a row after the other rows of that sample, and the rows of groups
+/// it no longer has are removed. Use "" for group_header if rows aren't grouped.  
+/// Values are matched to the columns of sample_output by header name, and
+/// left blank if new_output doesn't have that header.  
+/// Returns the number of rows that were replaced or added.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::app::process::replace_sample_rows;
+/// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let mut sample_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2), OutputColumn::number("Avg Length", 2)],
+///     rows: vec![
+///         OutputRow::new("A", vec![DataVal::Float(1.0), DataVal::Float(5.0)]),
+///         OutputRow::new("B", vec![DataVal::Float(3.0), DataVal::Float(6.0)]),
+///     ],
+/// };
+/// let new_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Length", 2)],
+///     rows: vec![OutputRow::new("B", vec![DataVal::Float(6.5)])],
+/// };
+/// assert_eq!(replace_sample_rows(&mut sample_output, new_output, ""), 1);
+/// assert_eq!(sample_output.rows[0].cells, vec![DataVal::Float(1.0), DataVal::Float(5.0)]);
+/// assert_eq!(sample_output.rows[1].cells, vec![DataVal::String(String::new()), DataVal::Float(6.5)]);
+/// 
+/// // with a row for each treatment of a sample
+/// let treatment = |name: &str| DataVal::String(name.to_string());
+/// let mut sample_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::text("Treatment"), OutputColumn::number("Avg Area", 2)],
+///     rows: vec![
+///         OutputRow::new("A", vec![treatment("Control"), DataVal::Float(1.0)]),
+///         OutputRow::new("A", vec![treatment("Dried"), DataVal::Float(2.0)]),
+///         OutputRow::new("B", vec![treatment("Control"), DataVal::Float(3.0)]),
+///     ],
+/// };
+/// let new_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::text("Treatment"), OutputColumn::number("Avg Area", 2)],
+///     rows: vec![
+///         OutputRow::new("A", vec![treatment("Dried"), DataVal::Float(2.5)]),
+///         OutputRow::new("A", vec![treatment("Wet"), DataVal::Float(4.0)]),
+///     ],
+/// };
+/// assert_eq!(replace_sample_rows(&mut sample_output, new_output, "Treatment"), 2);
+/// let rows: Vec<(&str, &DataVal, &DataVal)> = sample_output.rows.iter().map(|row| (row.sample_id.as_str(), &row.cells[0], &row.cells[1])).collect();
+/// assert_eq!(rows, vec![
+///     ("A", &treatment("Dried"), &DataVal::Float(2.5)),
+///     ("A", &treatment("Wet"), &DataVal::Float(4.0)),
+///     ("B", &treatment("Control"), &DataVal::Float(3.0)),
+/// ]);
+/// ```
+pub fn replace_sample_rows(sample_output: &mut SampleOutput, new_output: SampleOutput, group_header: &str) -> usize {
+    let (old_group_idx, new_group_idx) = match group_header.trim() {
+        "" => (None, None),
+        group_header => match (sample_output.get_column_index(group_header), new_output.get_column_index(group_header)) {
+            (Some(old_group_idx), Some(new_group_idx)) => (Some(old_group_idx), Some(new_group_idx)),
+            _ => (None, None),
+        },
+    };
+    // the group of a row, or None if rows aren't grouped
+    let get_group = |row: &OutputRow, group_idx: Option<usize>| group_idx.map(|group_idx| row.cells.get(group_idx).map(|cell| cell.to_string()).unwrap_or_default());
+    let mut rows_replaced = 0;
+    for new_row in new_output.rows.iter() {
+        let new_group = get_group(new_row, new_group_idx);
+        let mut row = Vec::new();
+        let mut notes = Vec::new();
+        for (col_idx, column) in sample_output.columns.iter().enumerate() {
+            match new_output.get_column_index(&column.name) {
+                Some(new_col_idx) => {
+                    row.push(new_row.cells.get(new_col_idx).cloned().unwrap_or(DataVal::String(String::new())));
+                    if let Some(note) = new_row.get_note(new_col_idx) {notes.push((col_idx, note.clone()));}
+                },
+                None => row.push(DataVal::String(String::new())),
+            }//end matching whether new output has this header
+        }//end matching new values to existing headers
+        match sample_output.rows.iter_mut().find(|old_row| old_row.sample_id.eq(&new_row.sample_id) && get_group(old_row, old_group_idx) == new_group) {
+            Some(old_row) => {
+                old_row.cells = row;
+                old_row.notes = notes;
+                rows_replaced += 1;
+            },
+            // a group the sample didn't have before goes with its other rows
+            None if new_group.is_some() => if let Some(last_idx) = sample_output.rows.iter().rposition(|old_row| old_row.sample_id.eq(&new_row.sample_id)) {
+                let mut added_row = OutputRow::new(&new_row.sample_id, row);
+                added_row.notes = notes;
+                sample_output.rows.insert(last_idx + 1, added_row);
+                rows_replaced += 1;
+            },
+            None => {},
+        }//end matching whether this sample was already in the output
+    }//end replacing each reprocessed sample
+    if old_group_idx.is_some() {
+        sample_output.rows.retain(|old_row| {
+            let mut new_rows = new_output.rows.iter().filter(|new_row| new_row.sample_id.eq(&old_row.sample_id)).peekable();
+            new_rows.peek().is_none() || new_rows.any(|new_row| get_group(new_row, new_group_idx) == get_group(old_row, old_group_idx))
+        });
+    }//end if a reprocessed sample might not have all its groups anymore
+    rows_replaced
+}//end replace_sample_rows(sample_output, new_output, group_header)
+
+/// Parses a formula column from text in the form of
+/// "sheet: name = left op right", such as
+/// "CSV_Stats: L/W Ratio = Avg Length / Avg Width", giving the name of
+/// the sheet and the calculation. The calculation is read the same way
+/// as a derived column, so each side can be a column of that sheet or
+/// a number.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::parse_formula_column;
+/// use usda_c_grain_sum::pipeline::Operand;
+/// 
+/// let (sheet_name, derived) = parse_formula_column("CSV_Stats: L/W Ratio = Avg Length / Avg Width").unwrap();
+/// assert_eq!(sheet_name, "CSV_Stats");
+/// assert_eq!(derived.name, "L/W Ratio");
+/// assert_eq!(derived.right, Operand::Column("Avg Width".to_string()));
+/// assert!(parse_formula_column("L/W Ratio = Avg Length / Avg Width").is_err());
+/// ```
+pub fn parse_formula_column(definition: &str) -> Result<(String, DerivedColumn),String> {
+    match definition.split_once(':') {
+        Some((sheet_name, derived)) if !sheet_name.trim().is_empty() && !sheet_name.contains('=') => {
+            Ok((sheet_name.trim().to_string(), DerivedColumn::parse(derived)?))
+        },
+        _ => Err(format!("The formula column \"{}\" should start with the name of its sheet and a colon, like \"CSV_Stats: L/W Ratio = Avg Length / Avg Width\".", definition)),
+    }//end matching whether the definition has a sheet name
+}//end parse_formula_column(definition)
+
+/// Parses a column group from text in the form of "group: column, column",
+/// such as "Length (mm): Avg Length, Std Length, CV Length", giving the
+/// header of the group and the names of the columns in it.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::parse_column_group;
+/// 
+/// let (group, columns) = parse_column_group("Length (mm): Avg Length, Std Length").unwrap();
+/// assert_eq!(group, "Length (mm)");
+/// assert_eq!(columns, vec!["Avg Length".to_string(), "Std Length".to_string()]);
+/// assert!(parse_column_group("Avg Length, Std Length").is_err());
+/// assert!(parse_column_group("Length:").is_err());
+/// ```
+pub fn parse_column_group(definition: &str) -> Result<(String, Vec<String>),String> {
+    match definition.split_once(':') {
+        Some((group, columns)) if !group.trim().is_empty() => {
+            let columns: Vec<String> = columns.split(',').map(|column| column.trim().to_string()).filter(|column| !column.is_empty()).collect();
+            match columns.is_empty() {
+                true => Err(format!("The column group \"{}\" doesn't list any columns after the colon.", definition)),
+                false => Ok((group.trim().to_string(), columns)),
+            }//end matching whether the group has columns
+        },
+        _ => Err(format!("The column group \"{}\" should start with the header of the group and a colon, like \"Length (mm): Avg Length, Std Length\".", definition)),
+    }//end matching whether the definition has a group header
+}//end parse_column_group(definition)
+
+/// Gets the header of the group of each column in sample_output, in the
+/// same order as its columns, or None for columns which aren't in a group.
+/// Columns listed in column_groups, in the form of "group: column, column",
+/// are in that group, and other columns are in the group they were made
+/// with, such as from get_stat_column_group().
+/// Column groups which can't be read are skipped.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::get_column_groups;
+/// use usda_c_grain_sum::model::{OutputColumn, SampleOutput};
+/// 
+/// let stats = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Length", 3).with_group("Length"))
+///     .with_column(OutputColumn::number("Std Length", 3).with_group("Length"))
+///     .with_column(OutputColumn::number("Kernel Count", 0));
+/// let column_groups = vec!["Length (mm): Avg Length".to_string(), "not a group".to_string()];
+/// assert_eq!(get_column_groups(&stats, &column_groups), vec![Some("Length (mm)".to_string()), Some("Length".to_string()), None]);
+/// ```
+pub fn get_column_groups(sample_output: &SampleOutput, column_groups: &Vec<String>) -> Vec<Option<String>> {
+    let column_groups: Vec<(String, Vec<String>)> = column_groups.iter().filter_map(|definition| parse_column_group(definition).ok()).collect();
+    sample_output.columns.iter().map(|column| {
+        match column_groups.iter().find(|(_, group_columns)| group_columns.contains(&column.name)) {
+            Some((group, _)) => Some(group.clone()),
+            None => column.group.clone(),
+        }//end matching whether the config puts this column in a group
+    }).collect()
+}//end get_column_groups(sample_output, column_groups)
+
+/// Adds each of formula_columns for the sheet sheet_name to the end of
+/// sample_output, then fills in their values with fill_formula_columns().
+/// Each formula column shows as many decimal places as the columns it uses.
+/// Formula columns for other sheets are skipped.  
+/// Returns the number of columns added.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::add_formula_columns;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+/// 
+/// let mut stats = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Length", 3))
+///     .with_column(OutputColumn::number("Avg Width", 2));
+/// stats.add_row("S1", vec![DataVal::Float(6.0), DataVal::Float(2.0)]);
+/// stats.add_row("S2", vec![DataVal::Float(5.0), DataVal::Float(0.0)]);
+/// let formula_columns = vec![
+///     "CSV_Stats: L/W Ratio = Avg Length / Avg Width".to_string(),
+///     "XML_Sieve_Data: Double = Sieve 1 * 2".to_string(),
+/// ];
+/// 
+/// assert_eq!(add_formula_columns("CSV_Stats", &mut stats, &formula_columns), Ok(1));
+/// assert_eq!(stats.columns[2], OutputColumn::number("L/W Ratio", 3).with_formula("Avg Length / Avg Width"));
+/// assert_eq!(stats.rows[0].cells[2], DataVal::Float(3.0));
+/// assert_eq!(stats.rows[1].cells[2], DataVal::String(String::new()));
+/// assert!(add_formula_columns("CSV_Stats", &mut stats, &vec!["CSV_Stats: Area x2 = Avg Area * 2".to_string()]).is_err());
+/// ```
+pub fn add_formula_columns(sheet_name: &str, sample_output: &mut SampleOutput, formula_columns: &Vec<String>) -> Result<usize,String> {
+    let mut columns_added = 0;
+    for definition in formula_columns.iter().filter(|definition| !definition.trim().is_empty()) {
+        let (formula_sheet, derived) = parse_formula_column(definition)?;
+        if !formula_sheet.eq(sheet_name) {continue;}
+        if sample_output.get_column_index(&derived.name).is_some() {return Err(format!("The formula column \"{}\" has the same name as a column already in the {} sheet.", derived.name, sheet_name));}
+        let mut precision = None;
+        for operand in [&derived.left, &derived.right] {
+            if let Operand::Column(column) = operand {
+                match sample_output.get_column_index(column) {
+                    Some(col_idx) => precision = Some(precision.unwrap_or(0).max(sample_output.columns[col_idx].precision)),
+                    None => return Err(format!("Couldn't find column \"{}\" in the {} sheet, which is used by the formula column \"{}\".", column, sheet_name, definition)),
+                }//end matching whether the sheet has this column
+            }//end if this side of the calculation is a column
+        }//end finding the precision of the columns used
+        let formula = definition.split_once('=').map(|(_, formula)| formula.trim()).unwrap_or_default();
+        sample_output.add_column(OutputColumn::number(&derived.name, precision.unwrap_or(2)).with_formula(formula));
+        columns_added += 1;
+    }//end adding each formula column for this sheet
+    fill_formula_columns(sample_output);
+    Ok(columns_added)
+}//end add_formula_columns(sheet_name, sample_output, formula_columns)
+
+/// Calculates the value of each formula column in each row of
+/// sample_output, such as after some rows were reprocessed. These values
+/// are used wherever the formula itself can't be, such as in csv output.  
+/// Rows where a value isn't a number, or which would divide by zero,
+/// get an empty cell.
+pub fn fill_formula_columns(sample_output: &mut SampleOutput) {
+    for col_idx in 0..sample_output.columns.len() {
+        let derived = match &sample_output.columns[col_idx].formula {
+            Some(formula) => match DerivedColumn::parse(&format!("{} = {}", sample_output.columns[col_idx].name, formula)) {
+                Ok(derived) => derived,
+                Err(_) => continue,
+            },
+            None => continue,
+        };
+        let operand_cols: Vec<Option<usize>> = [&derived.left, &derived.right].iter()
+            .map(|operand| match operand {
+                Operand::Column(column) => sample_output.get_column_index(column),
+                Operand::Number(_) => None,
+            }).collect();
+        for output_row in sample_output.rows.iter_mut() {
+            let get_value = |operand: &Operand, operand_col: Option<usize>| match operand {
+                Operand::Number(number) => Some(*number),
+                Operand::Column(_) => match operand_col.and_then(|operand_col| output_row.cells.get(operand_col)) {
+                    Some(DataVal::Int(i)) => Some(*i as f64),
+                    Some(DataVal::Float(f)) => Some(*f),
+                    _ => None,
+                },
+            };
+            let value = match (get_value(&derived.left, operand_cols[0]), get_value(&derived.right, operand_cols[1])) {
+                (Some(left), Some(right)) => derived.calculate(left, right),
+                _ => None,
+            };
+            if output_row.cells.len() <= col_idx {output_row.cells.resize(col_idx + 1, DataVal::String(String::new()));}
+            output_row.cells[col_idx] = match value {
+                Some(value) => DataVal::Float(value),
+                None => DataVal::String(String::new()),
+            };
+        }//end calculating the value in each row
+    }//end filling in each formula column
+}//end fill_formula_columns(sample_output)
+
+/// Gets a copy of sample_output with only the rows whose id is in
+/// sample_ids, in their original order.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::app::process::get_sample_output_subset;
+/// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let sample_output = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2)],
+///     rows: vec![
+///         OutputRow::new("A", vec![DataVal::Float(1.0)]),
+///         OutputRow::new("B", vec![DataVal::Float(3.0)]),
+///         OutputRow::new("C", vec![DataVal::Float(5.0)]),
+///     ],
+/// };
+/// let subset = get_sample_output_subset(&sample_output, &vec!["C".to_string(), "A".to_string()]);
+/// assert_eq!(subset.columns, sample_output.columns);
+/// assert_eq!(subset.rows.iter().map(|row| row.sample_id.as_str()).collect::<Vec<&str>>(), vec!["A", "C"]);
+/// ```
+pub fn get_sample_output_subset(sample_output: &SampleOutput, sample_ids: &Vec<String>) -> SampleOutput {
+    SampleOutput {
+        id_header: sample_output.id_header.clone(),
+        columns: sample_output.columns.clone(),
+        rows: sample_output.rows.iter()
+            .filter(|output_row| sample_ids.contains(&output_row.sample_id))
+            .cloned()
+            .collect(),
+    }
+}//end get_sample_output_subset(sample_output, sample_ids)
+
+/// Replaces any characters in name which might not be allowed
+/// in a file name with underscores.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::get_safe_file_name;
+/// 
+/// assert_eq!(get_safe_file_name("W23-07.3"), "W23-07.3");
+/// assert_eq!(get_safe_file_name("Lot 7/Rep:2"), "Lot_7_Rep_2");
+/// assert_eq!(get_safe_file_name(""), "_");
+/// ```
+pub fn get_safe_file_name(name: &str) -> String {
+    let safe_name: String = name.trim().chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {c} else {'_'})
+        .collect();
+    if safe_name.is_empty() {String::from("_")} else {safe_name}
+}//end get_safe_file_name(name)
+
+/// Gets the folder that per-sample kernel csv files should be written
+/// to, which is a folder next to output_path named after its file stem.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::process::get_kernel_csv_dir;
+/// 
+/// let output_path = PathBuf::from("results").join("sum.xlsx");
+/// assert_eq!(get_kernel_csv_dir(&output_path), PathBuf::from("results").join("sum_kernels"));
+/// ```
+pub fn get_kernel_csv_dir(output_path: &PathBuf) -> PathBuf {
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output_path.with_file_name(format!("{}_kernels", stem))
+}//end get_kernel_csv_dir(output_path)
+
+/// Gets the folder that csv copies of the output sheets should be written
+/// to, which is a folder next to output_path named after its file stem.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::process::get_csv_output_dir;
+/// 
+/// let output_path = PathBuf::from("results").join("sum.xlsx");
+/// assert_eq!(get_csv_output_dir(&output_path), PathBuf::from("results").join("sum_csv"));
+/// ```
+pub fn get_csv_output_dir(output_path: &PathBuf) -> PathBuf {
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output_path.with_file_name(format!("{}_csv", stem))
+}//end get_csv_output_dir(output_path)
+
+/// Writes each of output_sheets to its own csv file in output_dir, named
+/// after the sheet, with the text shown in the output, the columns from
+/// the column layout in config, and the delimiter and decimal separator
+/// from config.  
+/// Returns the number of files written.
+pub fn write_output_csvs(output_sheets: &Vec<(String, SampleOutput)>, config: &ConfigStore, output_dir: &PathBuf) -> Result<usize,String> {
+    if let Err(error) = fs::create_dir_all(output_dir) {return Err(format!("Couldn't create folder \"{}\" for csv output.\n{}", output_dir.to_string_lossy(), error));}
+    let mut files_written = 0;
+    for (sheet_name, sheet_data) in output_sheets.iter() {
+        let file_path = output_dir.join(format!("{}.csv", get_safe_file_name(sheet_name)));
+        let (headers, rows) = get_sheet_text_table_with_separator(sheet_data, sheet_name, config, config.csv_output_decimal_separator);
+        let mut writer = match get_csv_writer(&file_path, config) {
+            Ok(writer) => writer,
+            Err(error) => return Err(format!("Couldn't create csv file \"{}\".\n{}", file_path.to_string_lossy(), error)),
+        };
+        if let Err(error) = writer.write_record(&headers) {return Err(format!("Couldn't write headers to \"{}\".\n{}", file_path.to_string_lossy(), error));}
+        for row in rows.iter() {
+            if let Err(error) = writer.write_record(row) {return Err(format!("Couldn't write the row for {} to \"{}\".\n{}", row.first().cloned().unwrap_or_default(), file_path.to_string_lossy(), error));}
+        }//end writing each row of this sheet
+        if let Err(error) = writer.flush() {return Err(format!("Couldn't finish writing \"{}\".\n{}", file_path.to_string_lossy(), error));}
+        files_written += 1;
+    }//end writing a file for each sheet
+    Ok(files_written)
+}//end write_output_csvs(output_sheets, config, output_dir)
+
+/// Writes output_sheets as csv, in the csv output format from config.  
+/// If the format is OutputFormat::XlsxAndCsvSections, every sheet is
+/// written to the single file at csv_path, with a section for each sheet
+/// made of a line with the name of the sheet, then its headers and rows,
+/// with a blank line between sections. Otherwise, each sheet is written
+/// to its own file in the folder at csv_path, as in write_output_csvs().  
+/// Returns the number of sheets written.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::{ConfigStore, OutputFormat};
+/// use usda_c_grain_sum::app::process::write_output_to_csv;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let stats = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2)],
+///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.5)])],
+/// };
+/// let percents = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::percent("%Sound", 1)],
+///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(0.9)])],
+/// };
+/// let output_sheets = vec![("CSV_Stats".to_string(), stats), ("Class_Percents".to_string(), percents)];
+/// let mut config = ConfigStore::default();
+/// config.output_format = OutputFormat::XlsxAndCsvSections;
+/// 
+/// let csv_path = std::env::temp_dir().join("c_grain_sum_csv_sections_doctest.csv");
+/// assert_eq!(write_output_to_csv(&output_sheets, &config, &csv_path), Ok(2));
+/// let contents = std::fs::read_to_string(&csv_path).unwrap();
+/// assert_eq!(contents, "CSV_Stats\nexternal-sample-id,Avg Area\nS1,12.50\n\nClass_Percents\nexternal-sample-id,%Sound\nS1,90.0%\n");
+/// std::fs::remove_file(&csv_path).unwrap();
+/// ```
+pub fn write_output_to_csv(output_sheets: &Vec<(String, SampleOutput)>, config: &ConfigStore, csv_path: &PathBuf) -> Result<usize,String> {
+    if config.output_format != OutputFormat::XlsxAndCsvSections {return write_output_csvs(output_sheets, config, csv_path);}
+    let mut sections = Vec::new();
+    for (sheet_name, sheet_data) in output_sheets.iter() {
+        let (headers, rows) = get_sheet_text_table_with_separator(sheet_data, sheet_name, config, config.csv_output_decimal_separator);
+        // sections have a different number of columns than the lines around them
+        let mut writer = csv::WriterBuilder::new().delimiter(get_csv_delimiter(config)).flexible(true).from_writer(Vec::new());
+        if let Err(error) = writer.write_record([sheet_name]) {return Err(format!("Couldn't write the name of the {} sheet.\n{}", sheet_name, error));}
+        if let Err(error) = writer.write_record(&headers) {return Err(format!("Couldn't write the headers of the {} sheet.\n{}", sheet_name, error));}
+        for row in rows.iter() {
+            if let Err(error) = writer.write_record(row) {return Err(format!("Couldn't write the row for {} in the {} sheet.\n{}", row.first().cloned().unwrap_or_default(), sheet_name, error));}
+        }//end writing each row of this sheet
+        match writer.into_inner() {
+            Ok(section) => sections.push(String::from_utf8_lossy(&section).into_owned()),
+            Err(error) => return Err(format!("Couldn't finish writing the {} sheet.\n{}", sheet_name, error)),
+        }//end matching whether we could finish this section
+    }//end writing a section for each sheet
+    match fs::write(csv_path, sections.join("\n")) {
+        Ok(_) => Ok(sections.len()),
+        Err(error) => Err(format!("Couldn't write csv file \"{}\".\n{}", csv_path.to_string_lossy(), error)),
+    }//end matching whether we could write the csv file
+}//end write_output_to_csv(output_sheets, config, csv_path)
+
+/// Groups the sample ids in metadata by their value in the column
+/// group_header, such as grouping samples by customer.  
+/// Returns (value, sample ids with that value) for each value, in order
+/// of first appearance. Samples with an empty value are left out.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_metadata_groups;
+/// 
+/// let headers: Vec<String> = vec!["external-sample-id".to_string(), "Customer".to_string()];
+/// let rows = vec![("S1", "Acme"), ("S2", "Birch"), ("S3", "Acme"), ("S4", "")].into_iter().enumerate()
+///     .map(|(idx, (id, customer))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], customer.to_string())]))
+///     .collect();
+/// let metadata = Data::from_row_data(headers.clone(), rows);
+/// 
+/// let groups = get_metadata_groups(&metadata, "external-sample-id", "Customer").unwrap();
+/// assert_eq!(groups, vec![
+///     ("Acme".to_string(), vec!["S1".to_string(), "S3".to_string()]),
+///     ("Birch".to_string(), vec!["S2".to_string()]),
+/// ]);
+/// assert!(get_metadata_groups(&metadata, "external-sample-id", "Location").is_err());
+/// ```
+pub fn get_metadata_groups(metadata: &Data, sample_id_header: &str, group_header: &str) -> Result<Vec<(String, Vec<String>)>,String> {
+    let sample_id_col_idx = metadata.get_header_index(sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the metadata.", sample_id_header))?;
+    let group_col_idx = metadata.get_header_index(group_header).ok_or(format!("Couldn't find column \"{}\" in the metadata.", group_header))?;
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for row in metadata.get_records_ref().iter() {
+        let sample_id = row.get_data(sample_id_col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default();
+        let group = row.get_data(group_col_idx).map(|cell| cell.get_data().to_string().trim().to_string()).unwrap_or_default();
+        if sample_id.is_empty() || group.is_empty() {continue;}
+        match groups.iter_mut().find(|(known_group, _)| known_group.eq(&group)) {
+            Some((_, sample_ids)) => if !sample_ids.contains(&sample_id) {sample_ids.push(sample_id)},
+            None => groups.push((group, vec![sample_id])),
+        }//end matching whether we've seen this group already
+    }//end grouping each metadata row
+    Ok(groups)
+}//end get_metadata_groups(metadata, sample_id_header, group_header)
+
+/// Gets the path of the workbook holding only the samples in group,
+/// which is next to output_path, named after it and the group.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::process::get_split_output_path;
+/// 
+/// let output_path = PathBuf::from("results").join("sum.xlsx");
+/// assert_eq!(get_split_output_path(&output_path, "Acme Farms"), PathBuf::from("results").join("sum_Acme_Farms.xlsx"));
+/// ```
+pub fn get_split_output_path(output_path: &PathBuf, group: &str) -> PathBuf {
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output_path.with_file_name(format!("{}_{}.xlsx", stem, get_safe_file_name(group)))
+}//end get_split_output_path(output_path, group)
+
+/// Gets a copy of output_sheets laid out with sheet_layout. For a sheet
+/// per metric, each CSV_Stats sheet is split with split_output_by_metric(),
+/// and the other sheets are kept as they are. A sheet with the name of an
+/// earlier sheet, ignoring case, such as after being shortened, has " (2)"
+/// or such added to its name.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::{ConfigStore, SheetLayout};
+/// use usda_c_grain_sum::app::process::{apply_sheet_layout, proc_csv_stat_cols, split_output_by_group};
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Plot".to_string(), "Kernel Area Corrected".to_string()];
+/// let rows = vec![("S1", "Nitrogen Rate 120 kg per ha North", "10"), ("S1", "Nitrogen Rate 120 kg per ha South", "14")].into_iter().enumerate()
+///     .map(|(idx, (id, plot, area))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], plot.to_string()),
+///         DataCell::new(&headers[2], area.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_stat_columns_columns = vec!["Kernel Area Corrected".to_string()];
+/// config.csv_stat_group_header = "Plot".to_string();
+/// 
+/// let output = proc_csv_stat_cols(&data, &config).unwrap();
+/// let group_sheets = split_output_by_group("CSV_Stats", &output, "Plot");
+/// let names: Vec<&str> = group_sheets.iter().map(|(sheet_name, _)| sheet_name.as_str()).collect();
+/// assert_eq!(names, vec!["CSV_Stats_Nitrogen Rate 120 kg ", "CSV_Stats_Nitrogen Rate 120 (2)"]);
+/// 
+/// // both metric sheets shorten to the same name
+/// let layout_sheets = apply_sheet_layout(&group_sheets, SheetLayout::SheetPerMetric, &config);
+/// let names: Vec<&str> = layout_sheets.iter().map(|(sheet_name, _)| sheet_name.as_str()).collect();
+/// assert_eq!(names, vec!["Kernel Area Corrected_Nitrogen ", "Kernel Area Corrected_Nitro (2)"]);
+/// ```
+pub fn apply_sheet_layout(output_sheets: &Vec<(String, SampleOutput)>, sheet_layout: SheetLayout, config: &ConfigStore) -> Vec<(String, SampleOutput)> {
+    match sheet_layout {
+        SheetLayout::Standard => output_sheets.clone(),
+        SheetLayout::SheetPerMetric => {
+            let mut layout_sheets: Vec<(String, SampleOutput)> = Vec::new();
+            for (sheet_name, sheet_data) in output_sheets.iter() {
+                let new_sheets = match sheet_name.starts_with("CSV_Stats") {
+                    true => split_output_by_metric(sheet_name, sheet_data, config),
+                    false => vec![(sheet_name.clone(), sheet_data.clone())],
+                };
+                // a metric sheet might have the name of a sheet from another group, or another sheet entirely
+                for (new_sheet_name, new_sheet) in new_sheets {
+                    let used_names: Vec<String> = layout_sheets.iter().map(|(layout_sheet_name, _)| layout_sheet_name.clone()).collect();
+                    layout_sheets.push((get_safe_sheet_name(&new_sheet_name, &used_names), new_sheet));
+                }//end adding each new sheet with a unique name
+            }//end laying out each sheet
+            layout_sheets
+        },
+    }//end matching how the sheets are laid out
+}//end apply_sheet_layout(output_sheets, sheet_layout, config)
+
+/// Gets a copy of output_sheets in the conventions of profile, with
+/// its unit conversions, decimal places, and column and sheet names.  
+/// Conversions are matched to columns by their names before renaming.
+/// Formula columns keep their formulas, using the new column names,
+/// unless they're converted themselves.  
+/// Returns an error if any conversion or rename in profile can't be read.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::{RecipientProfile, SheetLayout};
+/// use usda_c_grain_sum::app::process::apply_recipient_profile;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+/// 
+/// let mut sheet_data = SampleOutput::new("Sample Id")
+///     .with_column(OutputColumn::number("Avg Length", 3))
+///     .with_column(OutputColumn::number("Avg Width", 3));
+/// sheet_data.columns.push(OutputColumn { formula: Some("Avg Length / Avg Width".to_string()), ..OutputColumn::number("Ratio", 3) });
+/// sheet_data.add_row("A", vec![DataVal::Float(5.0), DataVal::Int(2), DataVal::Float(2.5)]);
+/// let profile = RecipientProfile {
+///     name: "Partner Lab".to_string(),
+///     decimal_places: Some(2),
+///     unit_conversions: vec!["Avg Width * 10".to_string()],
+///     column_renames: vec!["Avg Length -> Longueur".to_string()],
+///     sheet_renames: vec!["CSV_Stats -> Kernel Stats".to_string()],
+///     sheet_layout: SheetLayout::Standard,
+/// };
+/// let recipient_sheets = apply_recipient_profile(&vec![("CSV_Stats".to_string(), sheet_data)], &profile).unwrap();
+/// let (sheet_name, recipient_data) = &recipient_sheets[0];
+/// assert_eq!(sheet_name, "Kernel Stats");
+/// assert_eq!(recipient_data.columns.iter().map(|column| column.name.as_str()).collect::<Vec<&str>>(), vec!["Longueur", "Avg Width", "Ratio"]);
+/// assert!(recipient_data.columns.iter().all(|column| column.precision == 2));
+/// assert_eq!(recipient_data.columns[2].formula, Some("Longueur / Avg Width".to_string()));
+/// assert_eq!(recipient_data.rows[0].cells, vec![DataVal::Float(5.0), DataVal::Float(20.0), DataVal::Float(2.5)]);
+/// 
+/// let bad_profile = RecipientProfile { column_renames: vec!["Avg Length = Longueur".to_string()], ..profile };
+/// assert!(apply_recipient_profile(&Vec::new(), &bad_profile).is_err());
+/// ```
+pub fn apply_recipient_profile(output_sheets: &Vec<(String, SampleOutput)>, profile: &RecipientProfile) -> Result<Vec<(String, SampleOutput)>,String> {
+    let parse_renames = |renames: &Vec<String>, kind: &str| -> Result<Vec<(String, String)>,String> {
+        let mut rename_pairs = Vec::new();
+        for rename in renames.iter().filter(|rename| !rename.trim().is_empty()) {
+            match rename.split_once("->") {
+                Some((old_name, new_name)) if !new_name.trim().is_empty() => rename_pairs.push((old_name.trim().to_string(), new_name.trim().to_string())),
+                _ => return Err(format!("Couldn't read the {} rename \"{}\" of the recipient profile \"{}\". It should be like \"old name -> new name\".", kind, rename, profile.name)),
+            }//end matching whether we could split the rename
+        }//end parsing each rename
+        Ok(rename_pairs)
+    };
+    let column_renames = parse_renames(&profile.column_renames, "column")?;
+    let sheet_renames = parse_renames(&profile.sheet_renames, "sheet")?;
+    let mut conversions = Vec::new();
+    for conversion in profile.unit_conversions.iter().filter(|conversion| !conversion.trim().is_empty()) {
+        conversions.push(UnitConversion::parse(conversion).map_err(|msg| format!("Couldn't read a unit conversion of the recipient profile \"{}\". {}", profile.name, msg))?);
+    }//end parsing each conversion
+    let get_new_name = |name: &str| column_renames.iter().find(|(old_name, _)| old_name == name).map(|(_, new_name)| new_name.clone()).unwrap_or(name.to_string());
+
+    let mut recipient_sheets = Vec::new();
+    for (sheet_name, sheet_data) in output_sheets.iter() {
+        let mut recipient_data = sheet_data.clone();
+        for (col_idx, column) in recipient_data.columns.iter_mut().enumerate() {
+            let factor = conversions.iter().filter(|conversion| conversion.column == column.name).fold(1.0, |accum, conversion| accum * conversion.factor);
+            if factor != 1.0 {
+                // the converted values are written in place of the formula
+                column.formula = None;
+                for output_row in recipient_data.rows.iter_mut() {
+                    match output_row.cells.get(col_idx) {
+                        Some(DataVal::Int(value)) => output_row.cells[col_idx] = DataVal::Float(*value as f64 * factor),
+                        Some(DataVal::Float(value)) => output_row.cells[col_idx] = DataVal::Float(value * factor),
+                        _ => {},
+                    }//end matching whether this cell is a number
+                }//end converting this column in each row
+            }//end if this column is converted
+            if let Some(ref formula) = column.formula {
+                if let Ok(derived) = DerivedColumn::parse(&format!("{} = {}", column.name, formula)) {
+                    let get_operand_text = |operand: &Operand| match operand {
+                        Operand::Column(name) => get_new_name(name),
+                        Operand::Number(number) => number.to_string(),
+                    };
+                    column.formula = Some(format!("{} {} {}", get_operand_text(&derived.left), derived.op, get_operand_text(&derived.right)));
+                }//end if we can read the formula
+            }//end if this is a formula column
+            if let Some(decimal_places) = profile.decimal_places {column.precision = decimal_places;}
+            column.name = get_new_name(&column.name);
+        }//end applying the profile to each column
+        let sheet_name = sheet_renames.iter().find(|(old_name, _)| old_name == sheet_name).map(|(_, new_name)| new_name.clone()).unwrap_or(sheet_name.clone());
+        recipient_sheets.push((sheet_name, recipient_data));
+    }//end applying the profile to each sheet
+    Ok(recipient_sheets)
+}//end apply_recipient_profile(output_sheets, profile)
+
+/// Merges several summaries, such as every workbook from a season, into
+/// one summary, with a sheet for each sheet name found in any of them.  
+/// summaries is given as (name of summary, sheets in that summary), and
+/// each merged sheet starts with a Source column holding the name of the
+/// summary each row came from. Columns are matched by header, in the
+/// order they're first seen, and rows missing a column are left empty
+/// there. Each column keeps the most decimal places it had in any summary.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::merge_summary_sheets;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let week_1 = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2)],
+///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.5)])],
+/// };
+/// let week_2 = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Length", 3), OutputColumn::number("Avg Area", 1)],
+///     rows: vec![OutputRow::new("S2", vec![DataVal::Float(6.25), DataVal::Float(13.0)])],
+/// };
+/// let summaries = vec![
+///     ("week_1".to_string(), vec![("CSV_Stats".to_string(), week_1)]),
+///     ("week_2".to_string(), vec![("CSV_Stats".to_string(), week_2)]),
+/// ];
+/// let merged = merge_summary_sheets(&summaries);
+/// assert_eq!(merged.len(), 1);
+/// let (sheet_name, merged_stats) = &merged[0];
+/// assert_eq!(sheet_name, "CSV_Stats");
+/// assert_eq!(merged_stats.columns, vec![OutputColumn::number("Source", 0), OutputColumn::number("Avg Area", 2), OutputColumn::number("Avg Length", 3)]);
+/// assert_eq!(merged_stats.rows[0], OutputRow::new("S1", vec![DataVal::String("week_1".to_string()), DataVal::Float(12.5), DataVal::String(String::new())]));
+/// assert_eq!(merged_stats.rows[1], OutputRow::new("S2", vec![DataVal::String("week_2".to_string()), DataVal::Float(13.0), DataVal::Float(6.25)]));
+/// ```
+pub fn merge_summary_sheets(summaries: &Vec<(String, Vec<(String, SampleOutput)>)>) -> Vec<(String, SampleOutput)> {
+    let mut merged_sheets: Vec<(String, SampleOutput)> = Vec::new();
+    for (summary_name, summary_sheets) in summaries.iter() {
+        for (sheet_name, sheet_data) in summary_sheets.iter() {
+            let merged_idx = match merged_sheets.iter().position(|(merged_name, _)| merged_name.eq(sheet_name)) {
+                Some(merged_idx) => merged_idx,
+                None => {
+                    let merged_data = SampleOutput::new(&sheet_data.id_header)
+                        .with_column(OutputColumn::number("Source", 0));
+                    merged_sheets.push((sheet_name.clone(), merged_data));
+                    merged_sheets.len() - 1
+                },
+            };
+            let merged_data = &mut merged_sheets[merged_idx].1;
+            // the index in the merged sheet of each column in this sheet
+            let mut merged_cols = Vec::new();
+            for column in sheet_data.columns.iter() {
+                match merged_data.columns.iter().skip(1).position(|merged_column| merged_column.name.eq(&column.name)) {
+                    Some(merged_col) => {
+                        let merged_column = &mut merged_data.columns[merged_col + 1];
+                        merged_column.precision = merged_column.precision.max(column.precision);
+                        merged_cols.push(merged_col + 1);
+                    },
+                    None => {
+                        merged_data.add_column(column.clone());
+                        merged_cols.push(merged_data.columns.len() - 1);
+                    },
+                }//end matching whether the merged sheet has this column yet
+            }//end matching each column to the merged sheet
+            for output_row in sheet_data.rows.iter() {
+                let mut merged_cells = vec![DataVal::String(String::new()); merged_data.columns.len()];
+                merged_cells[0] = DataVal::String(summary_name.clone());
+                for (col_idx, data_cell) in output_row.cells.iter().enumerate() {
+                    if let Some(merged_col) = merged_cols.get(col_idx) {merged_cells[*merged_col] = data_cell.clone();}
+                }//end moving each cell to its merged column
+                merged_data.add_row(&output_row.sample_id, merged_cells);
+            }//end adding each row of this sheet
+        }//end merging each sheet of this summary
+    }//end merging each summary
+    // rows added before later columns were found are missing those cells
+    for (_, merged_data) in merged_sheets.iter_mut() {
+        let col_count = merged_data.columns.len();
+        for output_row in merged_data.rows.iter_mut() {output_row.cells.resize(col_count, DataVal::String(String::new()));}
+    }//end filling in the missing cells of each sheet
+    merged_sheets
+}//end merge_summary_sheets(summaries)
+
+/// The tokens which can be used in the name of the output file,
+/// which are replaced with their values when the output is written.
+pub const OUTPUT_NAME_TOKENS: [&str; 4] = ["{csv_stem}", "{date}", "{preset}", "{operator}"];
+
+/// Gets the value of each of OUTPUT_NAME_TOKENS, as (token, value).  
+/// {csv_stem} is the name of the csv input file without its extension,
+/// or the xml input file if there's no csv file. {preset} is the
+/// active preset, {operator} is the operator name in config, and
+/// {date} is date, which should be like 2024-05-30.  
+/// Values are made safe for file names, and are empty if not known.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::get_output_name_tokens;
+/// 
+/// let mut config = ConfigStore::default();
+/// config.personalized_config_name = "Wheat".to_string();
+/// let tokens = get_output_name_tokens(&Some(PathBuf::from("runs").join("lot 7.csv")), &None, &config, "2024-05-30");
+/// assert_eq!(tokens, vec![
+///     ("{csv_stem}".to_string(), "lot_7".to_string()),
+///     ("{date}".to_string(), "2024-05-30".to_string()),
+///     ("{preset}".to_string(), "Wheat".to_string()),
+///     ("{operator}".to_string(), "".to_string()),
+/// ]);
+/// ```
+pub fn get_output_name_tokens(csv_input_file: &Option<PathBuf>, xml_input_file: &Option<PathBuf>, config: &ConfigStore, date: &str) -> Vec<(String, String)> {
+    let input_stem = csv_input_file.as_ref().or(xml_input_file.as_ref())
+        .and_then(|input_file| input_file.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let values = [input_stem, date.to_string(), config.personalized_config_name.clone(), config.operator_name.clone()];
+    OUTPUT_NAME_TOKENS.iter().zip(values.iter())
+        .map(|(token, value)| match value.trim().is_empty() {
+            true => (token.to_string(), String::new()),
+            false => (token.to_string(), get_safe_file_name(value)),
+        }).collect()
+}//end get_output_name_tokens(csv_input_file, xml_input_file, config, date)
+
+/// Replaces each token from tokens in name_template with its value,
+/// such as from get_output_name_tokens().  
+/// Returns an error if name_template has a token in braces which isn't
+/// known, so a misspelled token isn't written into the file name.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::expand_output_name;
+/// 
+/// let tokens = vec![("{csv_stem}".to_string(), "lot_7".to_string()), ("{date}".to_string(), "2024-05-30".to_string())];
+/// assert_eq!(expand_output_name("{csv_stem}_{date}_sum", &tokens), Ok("lot_7_2024-05-30_sum".to_string()));
+/// assert_eq!(expand_output_name("sum", &tokens), Ok("sum".to_string()));
+/// assert!(expand_output_name("{csv_stm}_sum", &tokens).is_err());
+/// ```
+pub fn expand_output_name(name_template: &str, tokens: &Vec<(String, String)>) -> Result<String,String> {
+    let mut name = name_template.to_string();
+    for (token, value) in tokens.iter() {name = name.replace(token, value);}
+    if let Some(token_start) = name.find('{') {
+        if let Some(token_len) = name[token_start..].find('}') {
+            let known_tokens: Vec<&str> = tokens.iter().map(|(token, _)| token.as_str()).collect();
+            return Err(format!("{} isn't a known token. The known tokens are {}.", &name[token_start..=token_start + token_len], known_tokens.join(", ")));
+        }//end if the brace starts a token
+    }//end if there's a brace left over
+    Ok(name)
+}//end expand_output_name(name_template, tokens)
+
+/// Creates a csv writer for the file at file_path, which separates values
+/// with the csv output delimiter in config, so that csv files written by
+/// this program open correctly in Excel on European-locale machines.  
+/// If the delimiter isn't a single-byte character, a comma is used instead.
+pub fn get_csv_writer(file_path: &PathBuf, config: &ConfigStore) -> Result<csv::Writer<fs::File>,csv::Error> {
+    csv::WriterBuilder::new().delimiter(get_csv_delimiter(config)).from_path(file_path)
+}//end get_csv_writer(file_path, config)
+
+/// Gets the csv output delimiter in config as a byte, or a comma if
+/// the delimiter isn't a single-byte character.
+fn get_csv_delimiter(config: &ConfigStore) -> u8 {
+    match config.csv_output_delimiter.is_ascii() {
+        true => config.csv_output_delimiter as u8,
+        false => b',',
+    }//end matching whether the delimiter fits in a byte
+}//end get_csv_delimiter(config)
+
+/// Writes the kernel rows of each sample in data to their own csv file
+/// in output_dir, after applying the class filters in config.  
+/// Each file is named after its sample id, and has the same headers as data.  
+/// If key, given as (sample id, pseudonym), has any pseudonyms, they're
+/// used in place of the sample ids, both in the file names and the rows.
+/// Nothing is written if a sample has no pseudonym, so real sample ids
+/// never end up in anonymized output.  
+/// The delimiter and decimal separator of the files are set in config.  
+/// Returns the number of files written.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::app::process::write_sample_kernel_csvs;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("Smith Farm 1", "12"), ("Jones Farm 4", "14")].into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.csv_class_filter_enabled = false;
+/// config.csv_sample_id_header = "external-sample-id".to_string();
+/// let output_dir = std::env::temp_dir().join("c_grain_sum_kernel_csv_doctest");
+/// let _ = std::fs::remove_dir_all(&output_dir);
+/// 
+/// let key = vec![("Smith Farm 1".to_string(), "ID-01".to_string()), ("Jones Farm 4".to_string(), "ID-02".to_string())];
+/// assert_eq!(write_sample_kernel_csvs(&data, &config, &output_dir, &key), Ok(2));
+/// assert!(!output_dir.join("Smith Farm 1.csv").exists());
+/// let kernel_csv = std::fs::read_to_string(output_dir.join("ID-01.csv")).unwrap();
+/// assert!(kernel_csv.contains("ID-01") && !kernel_csv.contains("Smith"));
+/// 
+/// // a sample without a pseudonym stops the whole export
+/// std::fs::remove_dir_all(&output_dir).unwrap();
+/// assert!(write_sample_kernel_csvs(&data, &config, &output_dir, &key[..1].to_vec()).is_err());
+/// assert!(!output_dir.exists());
+/// ```
+pub fn write_sample_kernel_csvs(data: &Data, config: &ConfigStore, output_dir: &PathBuf, key: &Vec<(String, String)>) -> Result<usize,String> {
+    let filtered_data = get_class_filtered_records(data, config)?;
+    let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the csv data.", config.csv_sample_id_header))?;
+    let split_data = model::get_split_records(&filtered_data, sample_id_col_idx)?;
+    // (sample id written in the file, rows of that sample)
+    let mut sample_files: Vec<(String, Vec<&DataRow>)> = Vec::new();
+    for (sample_id, rows) in split_data {
+        let sample_id = sample_id.to_string();
+        match key.is_empty() {
+            true => sample_files.push((sample_id, rows)),
+            false => match key.iter().find(|(key_id, _)| key_id.eq(&sample_id)) {
+                Some((_, pseudonym)) => sample_files.push((pseudonym.clone(), rows)),
+                None => return Err(String::from("A sample in the csv data doesn't have a pseudonym, so the kernel csv files weren't written, to keep real sample ids out of them.")),
+            },
+        }//end matching whether sample ids are replaced
+    }//end finding the id written for each sample
+    if let Err(error) = fs::create_dir_all(output_dir) {return Err(format!("Couldn't create folder \"{}\" for kernel csv files.\n{}", output_dir.to_string_lossy(), error));}
+
+    let mut files_written = 0;
+    for (sample_id, rows) in sample_files {
+        let file_path = output_dir.join(format!("{}.csv", get_safe_file_name(&sample_id)));
+        let mut writer = match get_csv_writer(&file_path, config) {
+            Ok(writer) => writer,
+            Err(error) => return Err(format!("Couldn't create kernel csv file \"{}\".\n{}", file_path.to_string_lossy(), error)),
+        };
+        if let Err(error) = writer.write_record(data.get_headers_ref()) {return Err(format!("Couldn't write headers to \"{}\".\n{}", file_path.to_string_lossy(), error));}
+        for row in rows {
+            let record: Vec<String> = row.get_row_data().iter().enumerate().map(|(col_idx, cell)| match cell.get_data() {
+                _ if col_idx == sample_id_col_idx => sample_id.clone(),
+                DataVal::Float(_) => cell.get_data().to_string().replace('.', &config.csv_output_decimal_separator.to_string()),
+                other => other.to_string(),
+            }).collect();
+            if let Err(error) = writer.write_record(&record) {return Err(format!("Couldn't write row idx {} to \"{}\".\n{}", row.get_row_idx(), file_path.to_string_lossy(), error));}
+        }//end writing each kernel row for this sample
+        if let Err(error) = writer.flush() {return Err(format!("Couldn't finish writing \"{}\".\n{}", file_path.to_string_lossy(), error));}
+        files_written += 1;
+    }//end writing a file for each sample
+    Ok(files_written)
+}//end write_sample_kernel_csvs(data, config, output_dir, key)
+
+/// Gets a pseudonym for each of sample_ids, for sharing output with
+/// external collaborators without revealing grower identities.  
+/// Returns (sample id, pseudonym) for each distinct sample id, in the
+/// order they first appear, or nothing if anonymization is Off.  
+/// StableHash gives a sample id the same pseudonym in every output,
+/// while SequentialCodes numbers the sample ids in order.  
+/// StableHash pseudonyms are a keyed hash of the sample id with salt,
+/// the secret salt of this install from secrets::get_install_salt(), so
+/// they can't be found by hashing a list of likely sample ids.  
+/// Returns an error if two sample ids would get the same pseudonym, or
+/// if a pseudonym is also one of the sample ids, since then the key
+/// couldn't tell them apart.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::SampleIdAnonymization;
+/// use usda_c_grain_sum::app::process::get_sample_id_key;
+/// 
+/// let salt = b"install salt";
+/// let sample_ids = vec!["Smith Farm 1".to_string(), "Jones Farm 4".to_string(), "Smith Farm 1".to_string()];
+/// let key = get_sample_id_key(&sample_ids, SampleIdAnonymization::SequentialCodes, salt).unwrap();
+/// assert_eq!(key, vec![("Smith Farm 1".to_string(), "ID-01".to_string()), ("Jones Farm 4".to_string(), "ID-02".to_string())]);
+/// 
+/// let key = get_sample_id_key(&sample_ids, SampleIdAnonymization::StableHash, salt).unwrap();
+/// assert_eq!(key.len(), 2);
+/// assert!(key[0].1.starts_with("ID-") && key[0].1.len() == 19);
+/// assert_eq!(key, get_sample_id_key(&vec!["Smith Farm 1".to_string(), "Jones Farm 4".to_string()], SampleIdAnonymization::StableHash, salt).unwrap());
+/// // another install has another salt, so its pseudonyms are different
+/// assert_ne!(key, get_sample_id_key(&sample_ids, SampleIdAnonymization::StableHash, b"other salt").unwrap());
+/// 
+/// // a sample already named like a pseudonym can't be told apart from one
+/// let sample_ids = vec!["ID-02".to_string(), "Jones Farm 4".to_string()];
+/// assert!(get_sample_id_key(&sample_ids, SampleIdAnonymization::SequentialCodes, salt).is_err());
+/// 
+/// assert!(get_sample_id_key(&sample_ids, SampleIdAnonymization::Off, salt).unwrap().is_empty());
+/// ```
+pub fn get_sample_id_key(sample_ids: &Vec<String>, anonymization: SampleIdAnonymization, salt: &[u8]) -> Result<Vec<(String, String)>,String> {
+    let mut distinct_ids: Vec<&String> = Vec::new();
+    for sample_id in sample_ids.iter() {
+        if !distinct_ids.contains(&sample_id) {distinct_ids.push(sample_id);}
+    }//end finding each distinct sample id
+    let code_width = distinct_ids.len().to_string().len().max(2);
+    let mut key: Vec<(String, String)> = Vec::new();
+    for (idx, sample_id) in distinct_ids.iter().enumerate() {
+        let pseudonym = match anonymization {
+            SampleIdAnonymization::Off => return Ok(Vec::new()),
+            SampleIdAnonymization::StableHash => format!("ID-{}", secrets::get_keyed_hash(salt, sample_id.as_bytes())?[..16].to_uppercase()),
+            SampleIdAnonymization::SequentialCodes => format!("ID-{:0width$}", idx + 1, width = code_width),
+        };
+        if let Some((other_id, _)) = key.iter().find(|(_, other_pseudonym)| other_pseudonym.eq(&pseudonym)) {
+            return Err(format!("Sample ids \"{}\" and \"{}\" would both get the pseudonym {}, so they couldn't be told apart.", other_id, sample_id, pseudonym));
+        }//end if another sample id already has this pseudonym
+        if distinct_ids.iter().any(|other_id| other_id.eq(&&pseudonym)) {
+            return Err(format!("The pseudonym {} for sample id \"{}\" is also the id of another sample, so they couldn't be told apart.", pseudonym, sample_id));
+        }//end if a sample already has this pseudonym as its id
+        key.push((sample_id.to_string(), pseudonym));
+    }//end finding the pseudonym for each distinct sample id
+    Ok(key)
+}//end get_sample_id_key(sample_ids, anonymization, salt)
+
+/// Replaces each sample id in sheet_data which is in key, given as
+/// (sample id, pseudonym), with its pseudonym.  
+/// Returns the number of rows which had their sample id replaced.
+pub fn replace_sample_ids(sheet_data: &mut SampleOutput, key: &Vec<(String, String)>) -> usize {
+    let mut rows_replaced = 0;
+    for output_row in sheet_data.rows.iter_mut() {
+        if let Some((_, pseudonym)) = key.iter().find(|(key_id, _)| key_id.eq(&output_row.sample_id)) {
+            output_row.sample_id = pseudonym.clone();
+            rows_replaced += 1;
+        }//end if we have a pseudonym for this sample
+    }//end replacing each sample id
+    rows_replaced
+}//end replace_sample_ids(sheet_data, key)
+
+/// Gets a copy of data where each value in the sample_id_header column
+/// which is in key, given as (sample id, pseudonym), is replaced with
+/// its pseudonym, such as for copying anonymized input into the output.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_data_with_replaced_ids;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let row = DataRow::new(0, vec![DataCell::new(&headers[0], "Smith Farm 1".to_string()), DataCell::new(&headers[1], "12".to_string())]);
+/// let data = Data::from_row_data(headers, vec![row]);
+/// let key = vec![("Smith Farm 1".to_string(), "ID-01".to_string())];
+/// 
+/// let anonymized = get_data_with_replaced_ids(&data, "external-sample-id", &key).unwrap();
+/// assert_eq!(anonymized.get_record(0, 0).unwrap().get_data().to_string(), "ID-01");
+/// assert_eq!(anonymized.get_record(0, 1), data.get_record(0, 1));
+/// ```
+pub fn get_data_with_replaced_ids(data: &Data, sample_id_header: &str, key: &Vec<(String, String)>) -> Result<Data,String> {
+    let sample_id_col_idx = data.get_header_index(sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the data.", sample_id_header))?;
+    let rows = data.get_records_ref().iter().map(|row| {
+        let cells = row.get_row_data().iter().enumerate().map(|(col_idx, cell)| {
+            let cell_text = cell.get_data().to_string();
+            match key.iter().find(|(key_id, _)| col_idx == sample_id_col_idx && key_id.eq(&cell_text)) {
+                Some((_, pseudonym)) => DataCell::new_from_val(cell.get_header(), DataVal::String(pseudonym.clone())),
+                None => cell.clone(),
+            }//end matching whether to replace this cell
+        }).collect();
+        DataRow::new(*row.get_row_idx(), cells)
+    }).collect();
+    Ok(Data::from_row_data(data.get_headers_ref().clone(), rows))
+}//end get_data_with_replaced_ids(data, sample_id_header, key)
+
+/// Gets a copy of text with each sample id in key, given as (sample id,
+/// pseudonym), replaced with its pseudonym, such as for sharing the log
+/// of an anonymized run. Sample ids are only replaced where they aren't
+/// part of a longer word or number, so S1 doesn't change S12.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::process::replace_sample_ids_in_text;
+/// 
+/// let key = vec![("S1".to_string(), "ID-01".to_string()), ("S12".to_string(), "ID-02".to_string())];
+/// assert_eq!(replace_sample_ids_in_text("Skipped stdev for S1 and S12.\nS123 wasn't in the run.", &key), "Skipped stdev for ID-01 and ID-02.\nS123 wasn't in the run.");
+/// assert_eq!(replace_sample_ids_in_text("S1", &Vec::new()), "S1");
+/// ```
+pub fn replace_sample_ids_in_text(text: &str, key: &Vec<(String, String)>) -> String {
+    // longer ids are tried first, in case one id starts with another
+    let mut sorted_key: Vec<&(String, String)> = key.iter().filter(|(sample_id, _)| !sample_id.is_empty()).collect();
+    sorted_key.sort_by_key(|(sample_id, _)| std::cmp::Reverse(sample_id.len()));
+    let is_word_char = |c: Option<char>| c.map(|c| c.is_alphanumeric()).unwrap_or(false);
+    let mut replaced = String::with_capacity(text.len());
+    let mut char_idx = 0;
+    while char_idx < text.len() {
+        let rest = &text[char_idx..];
+        let at_word_start = !is_word_char(text[..char_idx].chars().last());
+        let found = match at_word_start {
+            true => sorted_key.iter().find(|(sample_id, _)| rest.starts_with(sample_id.as_str()) && !is_word_char(rest[sample_id.len()..].chars().next())),
+            false => None,
+        };
+        match found {
+            Some((sample_id, pseudonym)) => {
+                replaced.push_str(pseudonym);
+                char_idx += sample_id.len();
+            },
+            None => {
+                let next_char = rest.chars().next().unwrap_or_default();
+                replaced.push(next_char);
+                char_idx += next_char.len_utf8();
+            },
+        }//end matching whether a sample id starts here
+    }//end looking for sample ids through text
+    replaced
+}//end replace_sample_ids_in_text(text, key)
+
+/// Gets the path of the file holding the key from pseudonyms back to
+/// sample ids for the output at output_path, which is a csv file next
+/// to the output, named after it.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::path::PathBuf;
+/// use usda_c_grain_sum::app::process::get_sample_id_key_path;
+/// 
+/// let output_path = PathBuf::from("results").join("sum.xlsx");
+/// assert_eq!(get_sample_id_key_path(&output_path), PathBuf::from("results").join("sum_id_key.csv"));
+/// ```
+pub fn get_sample_id_key_path(output_path: &PathBuf) -> PathBuf {
+    let stem = output_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    output_path.with_file_name(format!("{}_id_key.csv", stem))
+}//end get_sample_id_key_path(output_path)
+
+/// Writes key, given as (sample id, pseudonym), to a csv file at key_path,
+/// so that results shared with pseudonyms can be matched back to samples.
+pub fn write_sample_id_key(key_path: &PathBuf, key: &Vec<(String, String)>) -> Result<(),String> {
+    let mut writer = match csv::Writer::from_path(key_path) {
+        Ok(writer) => writer,
+        Err(error) => return Err(format!("Couldn't create sample id key file \"{}\".\n{}", key_path.to_string_lossy(), error)),
+    };
+    if let Err(error) = writer.write_record(["Pseudonym", "Sample ID"]) {return Err(format!("Couldn't write headers to \"{}\".\n{}", key_path.to_string_lossy(), error));}
+    for (sample_id, pseudonym) in key.iter() {
+        if let Err(error) = writer.write_record([pseudonym, sample_id]) {return Err(format!("Couldn't write the key for {} to \"{}\".\n{}", pseudonym, key_path.to_string_lossy(), error));}
+    }//end writing each pseudonym
+    if let Err(error) = writer.flush() {return Err(format!("Couldn't finish writing \"{}\".\n{}", key_path.to_string_lossy(), error));}
+    Ok(())
+}//end write_sample_id_key(key_path, key)
+
+/// Gets the text of each cell in sheet_data as it would be shown in
+/// the output, such as for printing or showing the results in a window.  
+/// Only visible columns are included, in the order from the column layout
+/// in config for sheet_name. Numbers are rounded to the decimal places of
+/// their column, and percents are shown out of 100 with a % sign.  
+/// Returns the headers, starting with the id header, and the text of each
+/// row, starting with the sample id.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::app::process::get_sheet_text_table;
+/// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let sheet_data = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2), OutputColumn::percent("%Sound", 1)],
+///     rows: vec![OutputRow::new("A", vec![DataVal::Float(12.345), DataVal::Float(0.9)])],
+/// };
+/// let (headers, rows) = get_sheet_text_table(&sheet_data, "CSV_Stats", &ConfigStore::default());
+/// assert_eq!(headers, vec!["external-sample-id", "Avg Area", "%Sound"]);
+/// assert_eq!(rows, vec![vec!["A", "12.35", "90.0%"]]);
+/// ```
+pub fn get_sheet_text_table(sheet_data: &SampleOutput, sheet_name: &str, config: &ConfigStore) -> (Vec<String>, Vec<Vec<String>>) {
+    get_sheet_text_table_with_separator(sheet_data, sheet_name, config, '.')
+}//end get_sheet_text_table(sheet_data, sheet_name, config)
+
+/// Gets the text of each cell in sheet_data like get_sheet_text_table(),
+/// but with decimal_separator in numbers instead of a period, such as
+/// a comma for csv files opened on European-locale machines.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::app::process::get_sheet_text_table_with_separator;
+/// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+/// 
+/// let sheet_data = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::number("Avg Area", 2), OutputColumn::percent("%Sound", 1)],
+///     rows: vec![OutputRow::new("W23.7", vec![DataVal::Float(12.345), DataVal::Float(0.9)])],
+/// };
+/// let (_, rows) = get_sheet_text_table_with_separator(&sheet_data, "CSV_Stats", &ConfigStore::default(), ',');
+/// assert_eq!(rows, vec![vec!["W23.7", "12,35", "90,0%"]]);
+/// ```
+pub fn get_sheet_text_table_with_separator(sheet_data: &SampleOutput, sheet_name: &str, config: &ConfigStore, decimal_separator: char) -> (Vec<String>, Vec<Vec<String>>) {
+    let column_layout = config.output_column_layouts.iter().find(|layout| layout.sheet_name.eq(sheet_name));
+    let header_names: Vec<String> = sheet_data.columns.iter().map(|column| column.name.clone()).collect();
+    let column_order: Vec<usize> = xlsx::get_column_order(&header_names, column_layout)
+        .into_iter().filter(|(_, is_visible)| *is_visible).map(|(col_idx, _)| col_idx).collect();
+
+    let mut headers = vec![sheet_data.id_header.clone()];
+    for col_idx in column_order.iter() {headers.push(header_names[*col_idx].clone());}
+    let mut rows = Vec::new();
+    for output_row in sheet_data.rows.iter() {
+        let mut row = vec![output_row.sample_id.clone()];
+        for col_idx in column_order.iter() {
+            let column = &sheet_data.columns[*col_idx];
+            let number = match output_row.cells.get(*col_idx) {
+                Some(DataVal::Float(f)) => Some(*f),
+                Some(DataVal::Int(i)) => Some(*i as f64),
+                Some(DataVal::String(s)) => {row.push(s.clone()); None},
+                None => {row.push(String::new()); None},
+            };
+            if let Some(number) = number {
+                let number_text = match column.is_percent() {
+                    true => format!("{:.*}%", column.precision, number * 100.0),
+                    false => format!("{:.*}", column.precision, number),
+                };//end matching whether this is a percent
+                row.push(number_text.replace('.', &decimal_separator.to_string()));
+            }//end if this cell is a number
+        }//end getting text of each visible column
+        rows.push(row);
+    }//end getting text of each row
+    (headers, rows)
+}//end get_sheet_text_table_with_separator(sheet_data, sheet_name, config, decimal_separator)
+
+/// Gets the text of every cell in data, as (headers, text of each row),
+/// such as for showing a preview of loaded data in a table.  
+/// Rows shorter than the headers are padded with empty cells.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::process::get_data_text_table;
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![
+///     DataRow::new(0, vec![DataCell::new(&headers[0], "W23-07-1".to_string()), DataCell::new(&headers[1], "12.5".to_string())]),
+///     DataRow::new(1, vec![DataCell::new(&headers[0], "W23-07-2".to_string())]),
+/// ];
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// 
+/// let (text_headers, text_rows) = get_data_text_table(&data);
+/// assert_eq!(text_headers, headers);
+/// assert_eq!(text_rows[0], vec!["W23-07-1", "12.5"]);
+/// assert_eq!(text_rows[1], vec!["W23-07-2", ""]);
+/// ```
+pub fn get_data_text_table(data: &Data) -> (Vec<String>, Vec<Vec<String>>) {
+    let headers = data.get_headers_ref().clone();
+    let rows = data.get_records_ref().iter()
+        .map(|row| (0..headers.len()).map(|col_idx| row.get_data(col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default()).collect())
+        .collect();
+    (headers, rows)
+}//end get_data_text_table(data)
+
+/// Gets information on sum and counts of different data types within columns.
+/// This is formatted as (sum_info, count_info).
+/// sum_info contains the sum of ints and sum of floats.
+/// count_info contains the number of ints, floats, and strings.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// use usda_c_grain_sum::model::Data;
+/// use usda_c_grain_sum::app::process::get_sum_count;
+/// 
+/// // set up headers
+/// let mut column_headers: Vec<String> = Vec::new();
+/// let header_0 = String::from("Class");
+/// let header_1 = String::from("Area");
+/// let header_2 = String::from("Red");
+/// column_headers.push(header_0.clone());
+/// column_headers.push(header_1.clone());
+/// column_headers.push(header_2.clone());
+/// 
+/// // set up rows of DataCells
+/// let mut cell_row_0: Vec<DataCell> = Vec::new();
+/// let mut cell_row_1: Vec<DataCell> = Vec::new();
+/// cell_row_0.push(DataCell::new_from_val(&header_0, DataVal::String("Sound".to_string())));
+/// cell_row_0.push(DataCell::new_from_val(&header_1, DataVal::Float(7.8)));
+/// cell_row_0.push(DataCell::new_from_val(&header_2, DataVal::Int(55)));
+/// cell_row_1.push(DataCell::new_from_val(&header_0, DataVal::String("Sound".to_string())));
+/// cell_row_1.push(DataCell::new_from_val(&header_1, DataVal::Float(5.6)));
+/// cell_row_1.push(DataCell::new_from_val(&header_2, DataVal::Int(60)));
+/// 
+/// // set up DataRows and add them to vec
+/// let mut datarow_vec: Vec<DataRow> = Vec::new();
+/// let datarow_0 = DataRow::new(0, cell_row_0);
+/// let datarow_1 = DataRow::new(1, cell_row_1);
+/// datarow_vec.push(datarow_0.clone());
+/// datarow_vec.push(datarow_1.clone());
+/// 
+/// // create the data struct from everything
+/// let data = Data::from_row_data(column_headers, datarow_vec);
+/// 
+/// let base_records = data.get_records();
+/// // test string count
+/// let class_sum_count = get_sum_count(&base_records, 0).unwrap();
+/// assert_eq!(class_sum_count, ((0,0.0),(0,0.0,2))); // two strs, none else
+/// // test int count and sum
+/// let red_sum_count = get_sum_count(&base_records, 2).unwrap();
+/// assert_eq!(red_sum_count, ((115,0.0),(2,0.0,0)))
+/// ```
+pub fn get_sum_count(records: &Vec<&DataRow>, col_idx: usize) -> Result<((i64,f64),(i64,f64,usize)), String> {
+    let mut running_sums: (i64, f64) = (0,0.0);
+    // int, float, string
+    let mut running_counts: (i64, f64, usize) = (0,0.0,0);
+    for row in records {
+        if let Some(this_cell_at_col) = row.get_data(col_idx) {
+            match this_cell_at_col.get_data() {
+                DataVal::Int(i) => {running_sums.0 += i; running_counts.0 += 1;},
+                DataVal::Float(f) => {running_sums.1 += f; running_counts.1 += 1.0;},
+                DataVal::String(_) => {running_counts.2 += 1;},
+            }//end matching type of cell data
+        } else { return Err(format!("Couldn't get data at col idx {} for row data {:?}", col_idx, row.get_row_data())); }
+    }//end looping over each row
+    return Ok((running_sums, running_counts));
+}//end get_sum_count
+
+/// Gets the average value from a single column of the grid made up of DataRows.
+/// Returns avg of integer values found, number of strings found, and avg of floats found.
+/// This is ordered as int, float, string in the output.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// use usda_c_grain_sum::model::Data;
+/// use usda_c_grain_sum::app::process::get_sum_count;
+/// use usda_c_grain_sum::app::process::get_col_avg;
+/// 
+/// // set up headers
+/// let mut column_headers: Vec<String> = Vec::new();
+/// let header_0 = String::from("Area");
+/// column_headers.push(header_0.clone());
+/// 
+/// // set up rows of DataCells
+/// let mut cell_row_0: Vec<DataCell> = Vec::new();
+/// let mut cell_row_1: Vec<DataCell> = Vec::new();
+/// let mut cell_row_2: Vec<DataCell> = Vec::new();
+/// let mut cell_row_3: Vec<DataCell> = Vec::new();
+/// 
+/// cell_row_0.push(DataCell::new_from_val(&header_0, DataVal::Float(5.6)));
+/// cell_row_1.push(DataCell::new_from_val(&header_0, DataVal::Float(7.8)));
+/// cell_row_2.push(DataCell::new_from_val(&header_0, DataVal::Int(7)));
+/// cell_row_3.push(DataCell::new_from_val(&header_0, DataVal::Int(5)));
+/// 
+/// // set up DataRows and add them to vec
+/// let mut datarow_vec: Vec<DataRow> = Vec::new();
+/// let datarow_0 = DataRow::new(0, cell_row_0);
+/// let datarow_1 = DataRow::new(1, cell_row_1);
+/// let datarow_2 = DataRow::new(2, cell_row_2);
+/// let datarow_3 = DataRow::new(3, cell_row_3);
+/// datarow_vec.push(datarow_0);
+/// datarow_vec.push(datarow_1);
+/// datarow_vec.push(datarow_2);
+/// datarow_vec.push(datarow_3);
+/// 
+/// // create the data struct from everything
+/// let data = Data::from_row_data(column_headers, datarow_vec);
+/// 
+/// let base_records = data.get_records();
+/// let avg_info = get_col_avg(&base_records, 0).unwrap();
+/// // test average for ints
+/// assert_eq!(avg_info.0, 6.0);
+/// // test average for floats
+/// let float_diff = (avg_info.1 - 6.7).abs();
+/// assert!(float_diff < 0.000001);
+/// // test count for strs
+/// assert_eq!(avg_info.2, 0);
+/// ```
+pub fn get_col_avg(records: &Vec<&DataRow>, col_idx: usize) -> Result<(f64, f64, usize), String> {
+    match get_sum_count(records, col_idx) {
+        Ok((sum_info, count_info)) => {
+            let int_avg = if count_info.0 != 0 {
+                sum_info.0 as f64 / count_info.0 as f64
+            } else {0.0};
+            let flt_avg = if count_info.1 != 0.0 {
+                sum_info.1 / count_info.1
+            } else {0.0};
+        
+            return Ok((int_avg, flt_avg, count_info.2));
+        },
+        Err(msg) => return Err(format!("While attempting to get sum and count, we encountered an error:\n{}", msg)),
+    }//end matching whether we could get sum and count
+}//end get_col_avg
+
+/// Gets the average value from a single column of the grid made up of DataRows.  
+/// Will combine all integer and float values found into a single average.  
+/// If the count of floats and ints is 0 or less, thsi function will return 0.  
+/// If the col_idx provided is invalid for records, this function will return an Err.
+pub fn get_col_avg_sngl(records: &Vec<&DataRow>, col_idx: usize) -> Result<f64, String> {
+    // make sure that col_idx is valid
+    if let Some(short_row) = records.iter().find(|row| col_idx >= row.get_row_data().len()) { return Err(format!("The column index {} is not valid for records, since row idx {} has length {}.", col_idx, short_row.get_row_idx(), short_row.get_row_data().len())); }
+    match get_sum_count(records, col_idx) {
+        Ok((sum_info, count_info)) => {
+            // get sum and count for everything
+            let sum_combined: f64 = sum_info.0 as f64 + sum_info.1;
+            let count_combined: f64 = count_info.0 as f64 + count_info.1;
+            if count_combined >= 0.0 {
+                let avg_combined = sum_combined / count_combined;
+                return Ok(avg_combined);
+            } else { return Ok(0.0); }
+        },
+        Err(msg) => return Err(format!("While trying to get sum and count, we encountered an error:\n{}", msg)),
+    }//end matching whether we could get sum and count
+}//end get_col_avg_sngl
+
+/// Gets standard deviation from a single column.
+/// We don't assume that every row in that column has same type, so we return
+/// the stdev of all integers found, stdev of all floats found, and the number
+/// of strings found.
+pub fn get_col_stdev(records: &Vec<&DataRow>, col_idx: usize) -> Result<(f64,f64,usize), String> {
+    match get_sum_count(records, col_idx) {
+        Ok((_, count_info)) => {
+            match get_col_avg(records, col_idx) {
+                Ok(avg_info) => {
+                    let mut running_sq_diff_sum: (f64, f64) = (0.0, 0.0);
+                    for row in records {
+                        if let Some(this_cell_at_col) = row.get_data(col_idx) {
+                            match &this_cell_at_col.get_data() {
+                                DataVal::Int(i) => {
+                                    let mean_diff = *i as f64 - avg_info.0;
+                                    let sq_mean_diff = mean_diff.powf(2.0);
+                                    running_sq_diff_sum.0 += sq_mean_diff;
+                                },
+                                DataVal::Float(f) => {
+                                    let mean_diff = f - avg_info.1;
+                                    let sq_mean_diff = mean_diff.powf(2.0);
+                                    running_sq_diff_sum.1 += sq_mean_diff;
+                                },
+                                DataVal::String(_) => {},
+                            }//end matching based on cell data type
+                        } else {log::warn!("Couldn't get data at col idx {} for row data {:?}", col_idx, row.get_row_data())}
+                    }//end looping over each row
+                
+                    let mut variance_info: (f64, f64) = (0.0, 0.0);
+                    if count_info.0 != 0 { variance_info.0 =  running_sq_diff_sum.0 / count_info.0 as f64; }
+                    if count_info.1 != 0.0 { variance_info.1 = running_sq_diff_sum.1 / count_info.1; }
+                
+                    let int_stdev = variance_info.0.sqrt();
+                    let flt_stdev = variance_info.1.sqrt();
+                
+                    return Ok((int_stdev, flt_stdev, count_info.2));
+                },
+                Err(msg) => return Err(format!("While trying to get averages, encountered an error:\n{}", msg)),
+            }//end matching whether we can get averages
+        },
+        Err(msg) => return Err(format!("While trying to get sum and count, we encountered an error:\n{}", msg)),
+    }//end matching whether we can get sum and count
+}//end get_col_stdev
+
+/// Gets standard deviation from a single column.  
+/// Will combine all integers and floats together, returning the stdev of the whole column.  
+/// An error will be returned in any of the following cases:
+/// - A string is encountered as a record
+/// - The column index provided is invalid for the records provided
+/// - The count of all numbers among the records is 0
+/// 
+pub fn get_col_stdev_sngl(records: &Vec<&DataRow>, col_idx: usize) -> Result<f64, String> {
+    match get_sum_count(records, col_idx) {
+        Ok((_, count_info)) => {
+            let full_count: f64 = count_info.0 as f64 + count_info.1;
+            match get_col_avg_sngl(records, col_idx) {
+                Ok(avg) => {
+                    let mut running_sq_diff_sum: f64 = 0.0;
+                    for row in records {
+                        if let Some(this_cell_at_col) = row.get_data(col_idx) {
+                            let val_at_cell = match &this_cell_at_col.get_data() {
+                                DataVal::Int(i) => *i as f64,
+                                DataVal::Float(f) => *f,
+                                DataVal::String(s) => return Err(format!("Encountered a string where there should be a number. Row idx {}, col idx {}. Data in cell is \"{}\"", row.get_row_idx(), col_idx, s)),
+                            };//end matching based on cell data type
+                            let mean_diff = val_at_cell - avg;
+                            let sq_mean_diff = mean_diff.powf(2.0);
+                            running_sq_diff_sum += sq_mean_diff;
+                        } else { return Err(format!("Couldn't get data at col idx {} and row idx {}. Row data is {:?}", col_idx, row.get_row_idx(), row.get_row_data())); }
+                    }//end adding sq diff of each row
+
+                    if full_count > 0.0 {
+                        let variance = running_sq_diff_sum / full_count;
+                        let stdev = variance.sqrt();
+                        return Ok(stdev);
+                    } else { return Err(format!("We couldn't do calculations because the count, {}, was 0 or less.", full_count)); }
+                },
+                Err(msg) => return Err(format!("While trying to get average, we encountered an error:\n{}", msg)),
+            }//end matching whether we can get average
+        },
+        Err(msg) => return Err(format!("While trying to get sum and count, we encountered an error:\n{}", msg)),
+    }//end matching whether we could get sum and count
+}//end get_col_stdev_sngl()
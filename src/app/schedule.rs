@@ -0,0 +1,233 @@
+use std::{fs, thread, time::Duration};
+
+use crate::app::{batch, config_store::{ConfigStore, LockedSettings}, frontend::Frontend, paths};
+
+/// The fields of a cron entry, in order, as (name, lowest value, highest value).
+const CRON_FIELDS: [(&str, u8, u8); 5] = [("minute", 0, 59), ("hour", 0, 23), ("day of month", 1, 31), ("month", 1, 12), ("day of week", 0, 6)];
+
+/// When scheduled processing should run, parsed from a cron-like entry
+/// such as "0 18 * * 1-5" for 6 pm on weekdays.
+/// Each field holds the values it matches, so * holds every value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CronSchedule {
+    /// The minutes of the hour to run at, from 0 to 59.
+    pub minutes: Vec<u8>,
+    /// The hours of the day to run at, from 0 to 23.
+    pub hours: Vec<u8>,
+    /// The days of the month to run on, from 1 to 31.
+    pub days: Vec<u8>,
+    /// The months to run in, from 1 to 12.
+    pub months: Vec<u8>,
+    /// The days of the week to run on, from 0 for Sunday to 6 for Saturday.
+    pub weekdays: Vec<u8>,
+}//end struct CronSchedule
+
+impl CronSchedule {
+    /// Parses entry, which has five fields separated by spaces, like cron:
+    /// minute, hour, day of month, month, and day of week, with 0 as Sunday.
+    /// Each field can be *, a number, a range like 1-5, or a list of these
+    /// like 8,12,17. Adding /n to * or a range only keeps every nth value,
+    /// such as */15 for every 15 minutes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::schedule::CronSchedule;
+    ///
+    /// let schedule = CronSchedule::parse("0 18 * * 1-5").unwrap();
+    /// assert_eq!(schedule.minutes, vec![0]);
+    /// assert_eq!(schedule.hours, vec![18]);
+    /// assert_eq!(schedule.weekdays, vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(schedule.days.len(), 31);
+    ///
+    /// let schedule = CronSchedule::parse("*/20 6,18 * * *").unwrap();
+    /// assert_eq!(schedule.minutes, vec![0, 20, 40]);
+    /// assert_eq!(schedule.hours, vec![6, 18]);
+    ///
+    /// assert!(CronSchedule::parse("0 18 * *").is_err());
+    /// assert!(CronSchedule::parse("0 25 * * *").is_err());
+    /// ```
+    pub fn parse(entry: &str) -> Result<CronSchedule,String> {
+        let fields: Vec<&str> = entry.split_whitespace().collect();
+        if fields.len() != CRON_FIELDS.len() {
+            return Err(format!("The schedule \"{}\" should have 5 fields separated by spaces, for the minute, hour, day of month, month, and day of week, but it has {}.", entry.trim(), fields.len()));
+        }//end if there aren't the right number of fields
+        let mut values = Vec::new();
+        for (field, (name, min, max)) in fields.iter().zip(CRON_FIELDS.iter()) {
+            values.push(parse_cron_field(field, *min, *max).map_err(|msg| format!("Couldn't read the {} of the schedule \"{}\". {}", name, entry.trim(), msg))?);
+        }//end parsing each field
+        let mut values = values.into_iter();
+        Ok(CronSchedule {
+            minutes: values.next().unwrap_or_default(),
+            hours: values.next().unwrap_or_default(),
+            days: values.next().unwrap_or_default(),
+            months: values.next().unwrap_or_default(),
+            weekdays: values.next().unwrap_or_default(),
+        })
+    }//end parse(entry)
+
+    /// Tells whether this schedule should run at the given time.
+    /// weekday is from 0 for Sunday to 6 for Saturday.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::schedule::CronSchedule;
+    ///
+    /// let schedule = CronSchedule::parse("0 18 * * 1-5").unwrap();
+    /// // Friday, May 31st at 6:00 pm
+    /// assert!(schedule.matches(0, 18, 31, 5, 5));
+    /// // Friday, May 31st at 6:01 pm
+    /// assert!(!schedule.matches(1, 18, 31, 5, 5));
+    /// // Saturday, June 1st at 6:00 pm
+    /// assert!(!schedule.matches(0, 18, 1, 6, 6));
+    /// ```
+    pub fn matches(&self, minute: u8, hour: u8, day: u8, month: u8, weekday: u8) -> bool {
+        self.minutes.contains(&minute) && self.hours.contains(&hour) && self.days.contains(&day) && self.months.contains(&month) && self.weekdays.contains(&weekday)
+    }//end matches(self, minute, hour, day, month, weekday)
+}//end impl CronSchedule
+
+/// Parses one field of a cron entry into the values it matches, which
+/// must be between min and max. See CronSchedule::parse() for the format.
+fn parse_cron_field(field: &str, min: u8, max: u8) -> Result<Vec<u8>,String> {
+    let parse_value = |value: &str| match value.parse::<u8>() {
+        Ok(value) if value >= min && value <= max => Ok(value),
+        _ => Err(format!("\"{}\" should be a number from {} to {}.", value, min, max)),
+    };
+    let mut values = Vec::new();
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => match step.parse::<u8>() {
+                Ok(step) if step > 0 => (range, step),
+                _ => return Err(format!("\"{}\" should be a number above 0.", step)),
+            },
+            None => (part, 1),
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (parse_value(start)?, parse_value(end)?),
+                None if step > 1 => (parse_value(range)?, max),
+                None => (parse_value(range)?, parse_value(range)?),
+            },
+        };
+        if start > end {return Err(format!("The range \"{}\" should start with the smaller number.", range));}
+        values.extend((start..=end).step_by(step as usize));
+    }//end parsing each part of the list
+    values.sort();
+    values.dedup();
+    Ok(values)
+}//end parse_cron_field(field, min, max)
+
+/// Gets the current time in the time zone of this computer, or in UTC
+/// if the time zone can't be found.
+pub fn get_local_time() -> time::OffsetDateTime {
+    time::OffsetDateTime::now_local().unwrap_or_else(|_| time::OffsetDateTime::now_utc())
+}//end get_local_time()
+
+/// Tells whether the schedule in schedule_cron has a time after
+/// last_check, up to and including now, checking each minute between them.  
+/// Checking every minute means a time isn't missed when the program was
+/// busy at that minute, such as while processing. At most one day is
+/// checked, so a computer waking from sleep doesn't run a backlog.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::schedule::is_schedule_due;
+///
+/// let last_check = time::macros::datetime!(2024-05-30 17:58 UTC);
+/// assert!(!is_schedule_due("0 18 * * *", last_check, time::macros::datetime!(2024-05-30 17:59 UTC)).unwrap());
+/// // 18:00 was missed while the program was busy, so it's still due at 18:02
+/// assert!(is_schedule_due("0 18 * * *", last_check, time::macros::datetime!(2024-05-30 18:02 UTC)).unwrap());
+/// assert!(!is_schedule_due("0 18 * * *", time::macros::datetime!(2024-05-30 18:00 UTC), time::macros::datetime!(2024-05-30 18:02 UTC)).unwrap());
+/// assert!(is_schedule_due("not a schedule", last_check, last_check).is_err());
+/// ```
+pub fn is_schedule_due(schedule_cron: &str, last_check: time::OffsetDateTime, now: time::OffsetDateTime) -> Result<bool,String> {
+    let schedule = CronSchedule::parse(schedule_cron)?;
+    let mut check_time = last_check.max(now - time::Duration::days(1)) + time::Duration::minutes(1);
+    while check_time <= now {
+        if schedule.matches(check_time.minute(), check_time.hour(), check_time.day(), check_time.month() as u8, check_time.weekday().number_days_from_sunday()) {return Ok(true);}
+        check_time += time::Duration::minutes(1);
+    }//end checking each minute since the last check
+    Ok(false)
+}//end is_schedule_due(schedule_cron, last_check, now)
+
+/// The Frontend of scheduled processing, which runs on a worker thread
+/// with nobody to ask about problems. Questions get the answer which
+/// changes the least, such as no or cancel, and alerts are logged and
+/// kept, so they can be shown once the sweep is done.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::{config_store::ConfigStore, frontend::Frontend, schedule::ScheduledFrontend};
+///
+/// let mut frontend = ScheduledFrontend { config: ConfigStore::default(), alerts: Vec::new(), completion: None };
+/// assert!(!frontend.integrated_dialog_yes_no("Would you like to skip the runs which were already processed?"));
+/// assert_eq!(frontend.integrated_dialog_message_choice("The output is open in another program.", vec!["Retry", "Cancel"]), None);
+/// frontend.integrated_dialog_alert("Couldn't write the combined workbook.");
+/// assert_eq!(frontend.alerts, vec!["Couldn't write the combined workbook."]);
+/// ```
+pub struct ScheduledFrontend {
+    /// The settings the sweep was started with.
+    pub config: ConfigStore,
+    /// Each alert shown during the sweep, oldest first.
+    pub alerts: Vec<String>,
+    /// How long the sweep took and what it wrote, once it's finished.
+    pub completion: Option<(Duration, String)>,
+}//end struct ScheduledFrontend
+
+impl Frontend for ScheduledFrontend {
+    fn get_config_store(&self) -> ConfigStore {self.config.clone()}
+    fn integrated_dialog_message(&mut self, txt: &str) {log::info!("{}", txt);}
+    fn integrated_dialog_alert(&mut self, txt: &str) {
+        log::error!("{}", txt);
+        self.alerts.push(txt.to_string());
+    }//end integrated_dialog_alert(self, txt)
+    fn integrated_dialog_yes_no(&mut self, txt: &str) -> bool {
+        log::warn!("Nobody was around to answer, so no was chosen.\n{}", txt);
+        false
+    }//end integrated_dialog_yes_no(self, txt)
+    fn integrated_dialog_message_choice(&mut self, txt: &str, _options: Vec<&str>) -> Option<usize> {
+        log::warn!("Nobody was around to choose, so it was cancelled.\n{}", txt);
+        None
+    }//end integrated_dialog_message_choice(self, txt, options)
+    fn log(&mut self, msg: &str) {log::info!("{}", msg);}
+    fn start_wait(&mut self) {}
+    fn end_wait(&mut self) {}
+    fn notify_completion(&mut self, run_duration: Duration, output_name: &str) {self.completion = Some((run_duration, output_name.to_string()));}
+    fn show_progress(&mut self, _fraction: f64, _stage: &str) {}
+    fn end_progress(&mut self) {}
+    fn wait_for(&self, seconds: f64) {thread::sleep(Duration::from_secs_f64(seconds));}
+}//end impl Frontend for ScheduledFrontend
+
+/// Starts run_scheduled_processing() on a worker thread with config and
+/// locked_settings, so the window can still be used during the sweep.  
+/// The ScheduledFrontend returned by the thread has what happened, for
+/// the window to show once the sweep is done.
+pub fn start_scheduled_processing(config: &ConfigStore, locked_settings: &LockedSettings, now: time::OffsetDateTime) -> thread::JoinHandle<ScheduledFrontend> {
+    let (worker_config, worker_locked_settings) = (config.clone(), locked_settings.clone());
+    thread::spawn(move || {
+        let mut frontend = ScheduledFrontend { config: worker_config.clone(), alerts: Vec::new(), completion: None };
+        run_scheduled_processing(&mut frontend, &worker_config, &worker_locked_settings, now);
+        frontend
+    })
+}//end start_scheduled_processing(config, locked_settings, now)
+
+/// Sweeps the schedule folder in config with batch::batch_process(), merging its
+/// new runs into one workbook in a Summaries folder inside it, named with
+/// the date and time, such as Summaries/combined_2024-05-30_1800.xlsx.  
+/// The workbook is kept in its own folder so later sweeps don't read it as a run.
+pub fn run_scheduled_processing(gui: &mut impl Frontend, config: &ConfigStore, locked_settings: &LockedSettings, now: time::OffsetDateTime) {
+    let schedule_dir = paths::resolve_setting_path(&config.schedule_folder);
+    let summaries_dir = schedule_dir.join("Summaries");
+    if let Err(error) = fs::create_dir_all(&summaries_dir) {
+        log::error!("Couldn't make the folder \"{}\" for scheduled processing, so nothing was processed.\n{}", summaries_dir.to_string_lossy(), error);
+        return;
+    }//end if we couldn't make the folder for the combined workbook
+    let stamp = now.format(time::macros::format_description!("[year]-[month]-[day]_[hour][minute]")).unwrap_or_default();
+    let combined_output = summaries_dir.join(format!("combined_{}.xlsx", stamp));
+    log::info!("Starting scheduled processing of \"{}\".", schedule_dir.to_string_lossy());
+    batch::batch_process(gui, &schedule_dir, &Some(combined_output), locked_settings, true);
+}//end run_scheduled_processing(gui, config, locked_settings, now)
@@ -0,0 +1,234 @@
+use std::path::PathBuf;
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+
+use crate::model::{Data, DataCell, DataRow, DataVal, OutputColumn, SampleOutput};
+
+/// The name of the function a script defines to change input data
+/// right after it's loaded.
+pub const AFTER_LOAD_HOOK: &str = "after_load";
+/// The name of the function a script defines to change each output
+/// sheet right before it's written.
+pub const BEFORE_WRITE_HOOK: &str = "before_write";
+
+/// A user script written in Rhai, with functions that are called at
+/// certain points while loading and processing, for lab-specific changes
+/// such as adding a custom column.
+/// A script can define either hook, or both:
+/// - `fn after_load(kind, rows)` is given "csv" or "xml" and an array with
+///   a map of header to value for each row. It returns the rows to use, and any
+///   new keys become new columns.
+/// - `fn before_write(sheet_name, sheet)` is given the name of an output sheet,
+///   such as "CSV_Stats", and a map with `id_header`, `headers` (each a map of
+///   `name`, `decimals`, and `percent`), and `rows` (each a map of `id` and
+///   `values`). It returns the sheet to write.
+///
+/// In Rhai, a for loop gets a copy of each item, so changes to the rows
+/// of a sheet need to go through an index, such as `sheet.rows[i]`.
+pub struct UserScript {
+    engine: Engine,
+    ast: AST,
+}//end struct UserScript
+
+impl UserScript {
+    /// Compiles the script held in source.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::scripting::UserScript;
+    ///
+    /// let script = UserScript::compile("fn after_load(kind, rows) { rows }").unwrap();
+    /// assert!(script.has_hook("after_load"));
+    /// assert!(!script.has_hook("before_write"));
+    /// assert!(UserScript::compile("fn after_load(kind, rows) {").is_err());
+    /// ```
+    pub fn compile(source: &str) -> Result<UserScript,String> {
+        let mut engine = Engine::new();
+        // debug builds default to shallower limits, which simple scripts can hit
+        engine.set_max_expr_depths(64, 32);
+        match engine.compile(source) {
+            Ok(ast) => Ok(UserScript { engine, ast }),
+            Err(error) => Err(format!("Couldn't compile the script: {}", error)),
+        }//end matching whether the script compiled
+    }//end compile(source)
+
+    /// Reads and compiles the script at script_path.
+    pub fn from_file(script_path: &PathBuf) -> Result<UserScript,String> {
+        match std::fs::read_to_string(script_path) {
+            Ok(source) => UserScript::compile(&source),
+            Err(error) => Err(format!("Couldn't read the script at {}: {}", script_path.to_string_lossy(), error)),
+        }//end matching whether we could read the script
+    }//end from_file(script_path)
+
+    /// Tells whether the script defines a function named hook_name.
+    pub fn has_hook(&self, hook_name: &str) -> bool {
+        self.ast.iter_functions().any(|function| function.name == hook_name)
+    }//end has_hook(self, hook_name)
+
+    /// Runs the after_load hook on data, returning the changed copy.
+    /// kind should be "csv" or "xml", so one script can handle both.
+    /// If the script has no after_load hook, data is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+    /// use usda_c_grain_sum::app::scripting::UserScript;
+    ///
+    /// let headers = vec!["id".to_string(), "Area".to_string()];
+    /// let rows = vec![
+    ///     DataRow::new(0, vec![DataCell::new(&headers[0], "S1".to_string()), DataCell::new(&headers[1], "12.5".to_string())]),
+    ///     DataRow::new(1, vec![DataCell::new(&headers[0], "S2".to_string()), DataCell::new(&headers[1], "3.0".to_string())]),
+    /// ];
+    /// let data = Data::from_row_data(headers, rows);
+    ///
+    /// let script = UserScript::compile("
+    ///     fn after_load(kind, rows) {
+    ///         let kept = [];
+    ///         for row in rows {
+    ///             if row.Area > 5.0 { row.Large = true; kept.push(row); }
+    ///         }
+    ///         kept
+    ///     }").unwrap();
+    /// let changed = script.run_after_load("csv", &data).unwrap();
+    /// assert_eq!(changed.get_headers(), vec!["id", "Area", "Large"]);
+    /// assert_eq!(changed.get_records().len(), 1);
+    /// assert_eq!(changed.get_record(0, 2).unwrap().get_data().to_string(), "true");
+    /// ```
+    pub fn run_after_load(&self, kind: &str, data: &Data) -> Result<Data,String> {
+        if !self.has_hook(AFTER_LOAD_HOOK) {return Ok(data.clone());}
+        let mut rows = Array::new();
+        for row in data.get_records() {
+            let mut row_map = Map::new();
+            for cell in row.get_row_data() {
+                row_map.insert(cell.get_header().as_str().into(), get_dynamic(cell.get_data()));
+            }//end adding each cell to the row
+            rows.push(Dynamic::from_map(row_map));
+        }//end converting each row for the script
+
+        let new_rows = self.call_hook(AFTER_LOAD_HOOK, (kind.to_string(), rows))?;
+        let new_rows = match new_rows.try_cast::<Array>() {
+            Some(new_rows) => new_rows,
+            None => return Err(format!("The {} hook in the script needs to return an array of rows.", AFTER_LOAD_HOOK)),
+        };
+        let mut headers: Vec<String> = data.get_headers_ref().clone();
+        let mut row_maps = Vec::new();
+        for (row_idx, new_row) in new_rows.into_iter().enumerate() {
+            let row_map = match new_row.try_cast::<Map>() {
+                Some(row_map) => row_map,
+                None => return Err(format!("Row {} returned by the {} hook in the script isn't a map of header to value.", row_idx, AFTER_LOAD_HOOK)),
+            };
+            for key in row_map.keys() {
+                if !headers.iter().any(|header| header.as_str() == key.as_str()) {headers.push(key.to_string());}
+            }//end adding any new columns
+            row_maps.push(row_map);
+        }//end checking each row returned by the script
+        let mut records = Vec::new();
+        for (row_idx, row_map) in row_maps.iter().enumerate() {
+            let cells = headers.iter().map(|header| match row_map.get(header.as_str()) {
+                Some(value) => DataCell::new_from_val(header, get_data_val(value)),
+                None => DataCell::new(header, String::new()),
+            }).collect();
+            records.push(DataRow::new(row_idx, cells));
+        }//end building each row from the script
+        Ok(Data::from_row_data(headers, records))
+    }//end run_after_load(self, kind, data)
+
+    /// Runs the before_write hook on one output sheet, returning the
+    /// changed copy. If the script has no before_write hook, the sheet
+    /// is returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::model::DataVal;
+    /// use usda_c_grain_sum::model::{OutputColumn, OutputRow, SampleOutput};
+    /// use usda_c_grain_sum::app::scripting::UserScript;
+    ///
+    /// let sheet = SampleOutput {
+    ///     id_header: "external-sample-id".to_string(),
+    ///     columns: vec![OutputColumn::number("Area Avg", 2)],
+    ///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.5)])],
+    /// };
+    /// let script = UserScript::compile(r#"
+    ///     fn before_write(sheet_name, sheet) {
+    ///         sheet.headers.push(#{ name: "Area Doubled", decimals: 1, percent: false });
+    ///         for i in 0..sheet.rows.len() { sheet.rows[i].values.push(sheet.rows[i].values[0] * 2.0); }
+    ///         sheet
+    ///     }"#).unwrap();
+    /// let changed = script.run_before_write("CSV_Stats", &sheet).unwrap();
+    /// assert_eq!(changed.columns[1], OutputColumn::number("Area Doubled", 1));
+    /// assert_eq!(changed.rows[0].cells[1], DataVal::Float(25.0));
+    /// ```
+    pub fn run_before_write(&self, sheet_name: &str, sheet: &SampleOutput) -> Result<SampleOutput,String> {
+        if !self.has_hook(BEFORE_WRITE_HOOK) {return Ok(sheet.clone());}
+        let mut sheet_map = Map::new();
+        sheet_map.insert("id_header".into(), Dynamic::from(sheet.id_header.clone()));
+        let headers: Array = sheet.columns.iter().map(|column| {
+            let mut header_map = Map::new();
+            header_map.insert("name".into(), Dynamic::from(column.name.clone()));
+            header_map.insert("decimals".into(), Dynamic::from(column.precision as i64));
+            header_map.insert("percent".into(), Dynamic::from(column.is_percent()));
+            Dynamic::from_map(header_map)
+        }).collect();
+        sheet_map.insert("headers".into(), Dynamic::from_array(headers));
+        let rows: Array = sheet.rows.iter().map(|output_row| {
+            let mut row_map = Map::new();
+            row_map.insert("id".into(), Dynamic::from(output_row.sample_id.clone()));
+            row_map.insert("values".into(), Dynamic::from_array(output_row.cells.iter().map(get_dynamic).collect()));
+            Dynamic::from_map(row_map)
+        }).collect();
+        sheet_map.insert("rows".into(), Dynamic::from_array(rows));
+
+        let new_sheet = self.call_hook(BEFORE_WRITE_HOOK, (sheet_name.to_string(), sheet_map))?;
+        let shape_error = format!("The {} hook in the script needs to return a sheet with id_header, headers, and rows.", BEFORE_WRITE_HOOK);
+        let mut new_sheet = new_sheet.try_cast::<Map>().ok_or(shape_error.clone())?;
+        let id_header = new_sheet.remove("id_header").map(|id_header| id_header.to_string()).ok_or(shape_error.clone())?;
+        let mut new_output = SampleOutput::new(&id_header);
+        for header in new_sheet.remove("headers").and_then(|headers| headers.try_cast::<Array>()).ok_or(shape_error.clone())? {
+            let header = header.try_cast::<Map>().ok_or(shape_error.clone())?;
+            let name = header.get("name").map(|name| name.to_string()).ok_or(shape_error.clone())?;
+            let decimals = header.get("decimals").and_then(|decimals| decimals.as_int().ok()).unwrap_or(2).max(0) as usize;
+            let percent = header.get("percent").and_then(|percent| percent.as_bool().ok()).unwrap_or(false);
+            new_output.add_column(match percent {
+                true => OutputColumn::percent(&name, decimals),
+                false => OutputColumn::number(&name, decimals),
+            });
+        }//end reading each header from the script
+        for row in new_sheet.remove("rows").and_then(|rows| rows.try_cast::<Array>()).ok_or(shape_error.clone())? {
+            let row = row.try_cast::<Map>().ok_or(shape_error.clone())?;
+            let sample_id = row.get("id").map(|sample_id| sample_id.to_string()).ok_or(shape_error.clone())?;
+            let values = row.get("values").and_then(|values| values.clone().try_cast::<Array>()).ok_or(shape_error.clone())?;
+            new_output.add_row(&sample_id, values.iter().map(get_data_val).collect());
+        }//end reading each row from the script
+        Ok(new_output)
+    }//end run_before_write(self, sheet_name, sheet)
+
+    /// Calls the function hook_name in the script with args,
+    /// turning any script error into a message for the user.
+    fn call_hook(&self, hook_name: &str, args: impl rhai::FuncArgs) -> Result<Dynamic,String> {
+        let mut scope = Scope::new();
+        match self.engine.call_fn::<Dynamic>(&mut scope, &self.ast, hook_name, args) {
+            Ok(result) => Ok(result),
+            Err(error) => Err(format!("The {} hook in the script failed: {}", hook_name, error)),
+        }//end matching whether the hook ran
+    }//end call_hook(self, hook_name, args)
+}//end impl UserScript
+
+/// Converts a value from the data into a value a script can use.
+fn get_dynamic(value: &DataVal) -> Dynamic {
+    match value {
+        DataVal::Int(i) => Dynamic::from(*i),
+        DataVal::Float(f) => Dynamic::from(*f),
+        DataVal::String(s) => Dynamic::from(s.clone()),
+    }//end matching the type of value
+}//end get_dynamic(value)
+
+/// Converts a value returned by a script back into a value for the data.
+/// Values which aren't numbers, such as true or false, are kept as text.
+fn get_data_val(value: &Dynamic) -> DataVal {
+    if let Ok(i) = value.as_int() {return DataVal::Int(i);}
+    if let Ok(f) = value.as_float() {return DataVal::Float(f);}
+    DataVal::String(value.to_string())
+}//end get_data_val(value)
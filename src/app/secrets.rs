@@ -0,0 +1,216 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::{fs::{self, OpenOptions}, io::{ErrorKind, Write}, path::{Path, PathBuf}};
+
+use crate::app::paths;
+
+/// The name of the file in the data directory which holds the secret salt
+/// that sample ids are hashed with to get their pseudonyms.
+pub const ANONYMIZATION_SALT_FILE_NAME: &str = "anonymization.salt";
+
+/// The name of the file in the data directory which holds the secret key
+/// the Run Info sheet signs output with. Only installs with this key can
+/// sign or verify output, so back it up along with the data directory.
+pub const OUTPUT_SIGNING_KEY_FILE_NAME: &str = "output_signing.key";
+
+/// The name of the file in the data directory which holds the secret key
+/// the checksums of the run ledger are made with.
+pub const LEDGER_KEY_FILE_NAME: &str = "ledger.key";
+
+/// The number of random bytes in a newly made salt.
+pub const SALT_LEN: usize = 32;
+
+/// The name of the file in the data directory which holds the salted
+/// hash of the admin password needed to leave operator mode.
+pub const ADMIN_PASSWORD_FILE_NAME: &str = "admin_password.hash";
+
+/// The number of rounds of PBKDF2 used to hash passwords, which makes
+/// guessing a password from its hash slow.
+pub const PASSWORD_HASH_ROUNDS: u32 = 100_000;
+
+/// Gets len random bytes from the operating system, such as for a salt.
+pub fn get_random_bytes(len: usize) -> Result<Vec<u8>,String> {
+    let mut bytes = vec![0u8; len];
+    match getrandom::getrandom(&mut bytes) {
+        Ok(_) => Ok(bytes),
+        Err(error) => Err(format!("Couldn't get random bytes from the operating system.\n{}", error)),
+    }//end matching whether we got random bytes
+}//end get_random_bytes(len)
+
+/// Gets bytes written as lowercase hex, two characters per byte.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}//end to_hex(bytes)
+
+/// Gets the bytes from text written by to_hex(), or an error if text
+/// isn't hex with two characters per byte.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::secrets::{from_hex, to_hex};
+///
+/// assert_eq!(from_hex(&to_hex(&[0, 15, 255])), Ok(vec![0, 15, 255]));
+/// assert!(from_hex("abc").is_err());
+/// assert!(from_hex("zz").is_err());
+/// ```
+pub fn from_hex(text: &str) -> Result<Vec<u8>,String> {
+    if text.len() % 2 != 0 || !text.is_ascii() {return Err(String::from("Hex text must have two characters for each byte."));}
+    (0..text.len()).step_by(2).map(|idx| {
+        u8::from_str_radix(&text[idx..idx + 2], 16).map_err(|_| format!("\"{}\" isn't a hex byte.", &text[idx..idx + 2]))
+    }).collect()
+}//end from_hex(text)
+
+/// Gets the HMAC-SHA256 of data keyed with key, as lowercase hex.
+/// Without the key, the hash can't be worked out from data, so a
+/// list of likely values can't be hashed to find which one was used.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::secrets::get_keyed_hash;
+///
+/// let hash = get_keyed_hash(b"salt one", b"Smith Farm 1").unwrap();
+/// assert_eq!(hash.len(), 64);
+/// assert_eq!(hash, get_keyed_hash(b"salt one", b"Smith Farm 1").unwrap());
+/// assert_ne!(hash, get_keyed_hash(b"salt two", b"Smith Farm 1").unwrap());
+/// ```
+pub fn get_keyed_hash(key: &[u8], data: &[u8]) -> Result<String,String> {
+    let mut mac = match Hmac::<Sha256>::new_from_slice(key) {
+        Ok(mac) => mac,
+        Err(error) => return Err(format!("Couldn't use the key for hashing.\n{}", error)),
+    };
+    mac.update(data);
+    Ok(to_hex(&mac.finalize().into_bytes()))
+}//end get_keyed_hash(key, data)
+
+/// Gets the salt kept in the file at path, or makes a new random salt
+/// and saves it there if the file doesn't exist yet.
+/// A salt file which can't be read is an error rather than replaced,
+/// since a new salt would change every hash made with the old one.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::secrets::{load_or_create_salt, SALT_LEN};
+///
+/// let path = std::env::temp_dir().join("c_grain_sum_salt_doctest.salt");
+/// let _ = std::fs::remove_file(&path);
+/// let salt = load_or_create_salt(&path).unwrap();
+/// assert_eq!(salt.len(), SALT_LEN);
+/// assert_eq!(load_or_create_salt(&path).unwrap(), salt);
+///
+/// std::fs::write(&path, "not a salt").unwrap();
+/// assert!(load_or_create_salt(&path).is_err());
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn load_or_create_salt(path: &Path) -> Result<Vec<u8>,String> {
+    // create_new means two copies of the program can't both write a salt
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(mut file) => {
+            let salt = get_random_bytes(SALT_LEN)?;
+            if let Err(error) = file.write_all(to_hex(&salt).as_bytes()).and_then(|_| file.sync_all()) {
+                let _ = fs::remove_file(path);
+                return Err(format!("Couldn't save the new salt to \"{}\".\n{}", path.to_string_lossy(), error));
+            }//end if we couldn't save the new salt
+            Ok(salt)
+        },
+        Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+            let salt_text = fs::read_to_string(path).map_err(|error| format!("Couldn't read the salt in \"{}\".\n{}", path.to_string_lossy(), error))?;
+            match from_hex(salt_text.trim()) {
+                Ok(salt) if salt.len() > 0 => Ok(salt),
+                _ => Err(format!("The salt in \"{}\" isn't valid. Restore it from a backup to keep the same pseudonyms, or delete it to start over.", path.to_string_lossy())),
+            }//end matching whether the salt is valid
+        },
+        Err(error) => Err(format!("Couldn't open the salt file \"{}\".\n{}", path.to_string_lossy(), error)),
+    }//end matching whether the salt file already exists
+}//end load_or_create_salt(path)
+
+/// Gets the PBKDF2-HMAC-SHA256 hash of password with salt, as lowercase hex.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::secrets::get_password_hash;
+///
+/// let hash = get_password_hash("letmein", b"salt one");
+/// assert_eq!(hash.len(), 64);
+/// assert_eq!(hash, get_password_hash("letmein", b"salt one"));
+/// assert_ne!(hash, get_password_hash("letmein", b"salt two"));
+/// assert_ne!(hash, get_password_hash("letmein2", b"salt one"));
+/// ```
+pub fn get_password_hash(password: &str, salt: &[u8]) -> String {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, PASSWORD_HASH_ROUNDS, &mut hash);
+    to_hex(&hash)
+}//end get_password_hash(password, salt)
+
+/// Saves a salted hash of password to the file at path, rather than the
+/// password itself, so it can be checked with check_password() without
+/// anyone being able to read it back. A new random salt is used each time.  
+/// An empty password isn't saved, since it would let anyone through.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::secrets::{check_password, is_password_set, save_password_hash};
+///
+/// let path = std::env::temp_dir().join("c_grain_sum_password_doctest.hash");
+/// save_password_hash(&path, "letmein").unwrap();
+/// assert!(is_password_set(&path));
+/// assert!(!std::fs::read_to_string(&path).unwrap().contains("letmein"));
+/// assert_eq!(check_password(&path, "letmein"), Ok(true));
+/// assert_eq!(check_password(&path, "letmein2"), Ok(false));
+/// assert!(save_password_hash(&path, "").is_err());
+/// assert_eq!(check_password(&path, "letmein"), Ok(true));
+///
+/// // without a saved hash, nothing is accepted
+/// std::fs::remove_file(&path).unwrap();
+/// assert!(!is_password_set(&path));
+/// assert!(check_password(&path, "").is_err());
+/// assert!(check_password(&path, "letmein").is_err());
+/// ```
+pub fn save_password_hash(path: &Path, password: &str) -> Result<(),String> {
+    if password.is_empty() {return Err(String::from("The admin password can't be empty."));}
+    let salt = get_random_bytes(SALT_LEN)?;
+    let password_hash = format!("{}${}", to_hex(&salt), get_password_hash(password, &salt));
+    fs::write(path, password_hash).map_err(|error| format!("Couldn't save the password hash to \"{}\".\n{}", path.to_string_lossy(), error))
+}//end save_password_hash(path, password)
+
+/// Tells whether there's a password hash saved at path.
+pub fn is_password_set(path: &Path) -> bool {path.exists()}
+
+/// Tells whether password matches the hash saved at path with
+/// save_password_hash().  
+/// If there isn't one, such as if its file was deleted, returns an error,
+/// so that removing the hash can't be used to get around the password.
+pub fn check_password(path: &Path, password: &str) -> Result<bool,String> {
+    let password_hash = match fs::read_to_string(path) {
+        Ok(password_hash) => password_hash,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Err(format!("There's no admin password saved in \"{}\", so no password can be accepted.", path.to_string_lossy())),
+        Err(error) => return Err(format!("Couldn't read the password hash in \"{}\".\n{}", path.to_string_lossy(), error)),
+    };
+    let (salt, expected_hash) = match password_hash.trim().split_once('$') {
+        Some((salt_hex, expected_hash)) => (from_hex(salt_hex)?, expected_hash),
+        None => return Err(format!("The password hash in \"{}\" isn't valid. Delete it to be asked for a new admin password.", path.to_string_lossy())),
+    };
+    let actual_hash = get_password_hash(password, &salt);
+    // every byte is compared, so how long this takes doesn't tell how much matched
+    let difference = actual_hash.bytes().zip(expected_hash.bytes()).fold(0u8, |difference, (actual, expected)| difference | (actual ^ expected));
+    Ok(actual_hash.len() == expected_hash.len() && difference == 0)
+}//end check_password(path, password)
+
+/// Gets the path of the file in the data directory holding the hash of
+/// the admin password. This is kept out of the config file, so it can't
+/// be read there, or changed by editing or sharing the config.
+pub fn get_admin_password_path() -> Result<PathBuf,String> {
+    Ok(paths::get_data_dir()?.join(ADMIN_PASSWORD_FILE_NAME))
+}//end get_admin_password_path()
+
+/// Gets the salt for this install with the given file name, from the
+/// data directory, making it the first time it's needed.
+/// The data directory is used instead of the config file, so the salt
+/// isn't shared along with settings, presets, or run bundles.
+pub fn get_install_salt(file_name: &str) -> Result<Vec<u8>,String> {
+    load_or_create_salt(&paths::get_data_dir()?.join(file_name))
+}//end get_install_salt(file_name)
@@ -0,0 +1,192 @@
+use crate::{app::config_store::ConfigStore, model::Data, stats};
+
+/// A short summary of a processing run, such as for pasting into the
+/// email sent out after each run.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RunSummary {
+    /// The number of samples in the run.
+    pub sample_count: usize,
+    /// The number of kernels in the run.
+    pub kernel_count: usize,
+    /// The class that percentages are given for, such as "Sound".
+    pub class_name: String,
+    /// The percent of all kernels in the run which are in class_name.
+    pub overall_percent: f64,
+    /// The samples whose percent of class_name is unusually low compared
+    /// to the rest of the run, as (sample id, percent).
+    pub flagged_samples: Vec<(String, f64)>,
+}//end struct RunSummary
+
+impl RunSummary {
+    /// Formats the summary as a few lines of text, which read fine as
+    /// plain text and as a Markdown list.
+    /// output_name is the name of the output file, shown in the first line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::summary::RunSummary;
+    ///
+    /// let summary = RunSummary {
+    ///     sample_count: 2,
+    ///     kernel_count: 10,
+    ///     class_name: "Sound".to_string(),
+    ///     overall_percent: 90.0,
+    ///     flagged_samples: vec![("S2".to_string(), 80.0)],
+    /// };
+    /// assert_eq!(summary.to_text("sum.xlsx"), "C-Grain summary for sum.xlsx\n- Samples: 2\n- Kernels: 10\n- Overall %Sound: 90.0%\n- Flagged samples (unusually low %Sound): S2 (80.0%)\n");
+    /// ```
+    pub fn to_text(&self, output_name: &str) -> String {
+        let flagged_text = match self.flagged_samples.len() {
+            0 => String::from("none"),
+            _ => self.flagged_samples.iter()
+                .map(|(sample_id, percent)| format!("{} ({:.1}%)", sample_id, percent))
+                .collect::<Vec<String>>()
+                .join(", "),
+        };
+        let mut text = format!("C-Grain summary for {}\n", output_name);
+        text.push_str(&format!("- Samples: {}\n", self.sample_count));
+        text.push_str(&format!("- Kernels: {}\n", self.kernel_count));
+        text.push_str(&format!("- Overall %{}: {:.1}%\n", self.class_name, self.overall_percent));
+        text.push_str(&format!("- Flagged samples (unusually low %{}): {}\n", self.class_name, flagged_text));
+        text
+    }//end to_text(self, output_name)
+}//end impl RunSummary
+
+/// Gets the samples in the csv data from a run which should be reviewed
+/// before the output is written, along with why each was flagged, as
+/// (sample id, reasons).  
+/// A sample is flagged if it has fewer kernels than
+/// config.review_min_kernels, or if get_run_summary() finds its percent
+/// of the summary class unusually low.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::summary::get_flagged_samples;
+///
+/// let headers: Vec<String> = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string()];
+/// let mut rows = Vec::new();
+/// for (sample_id, kernel_count) in [("S1", 10), ("S2", 10), ("S3", 3)] {
+///     for _ in 0..kernel_count {
+///         let cells = vec![DataCell::new(&headers[0], sample_id.to_string()), DataCell::new(&headers[1], "Sound".to_string())];
+///         rows.push(DataRow::new(rows.len(), cells));
+///     }
+/// }
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// let mut config = ConfigStore::default();
+/// config.review_min_kernels = 5;
+///
+/// let flagged = get_flagged_samples(&data, &config).unwrap();
+/// assert_eq!(flagged, vec![("S3".to_string(), "Only 3 kernels".to_string())]);
+/// ```
+pub fn get_flagged_samples(data: &Data, config: &ConfigStore) -> Result<Vec<(String, String)>,String> {
+    let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the csv data.", config.csv_sample_id_header))?;
+    // (sample id, reasons it was flagged)
+    let mut flagged: Vec<(String, Vec<String>)> = Vec::new();
+    let mut add_flag = |sample_id: &str, reason: String| {
+        match flagged.iter_mut().find(|(known_id, _)| known_id.eq(sample_id)) {
+            Some((_, reasons)) => reasons.push(reason),
+            None => flagged.push((sample_id.to_string(), vec![reason])),
+        }//end matching whether this sample was already flagged
+    };
+
+    let mut kernel_counts: Vec<(String, usize)> = Vec::new();
+    for row in data.get_records_ref().iter() {
+        let sample_id = row.get_data(sample_id_col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default();
+        match kernel_counts.iter_mut().find(|(known_id, _)| known_id.eq(&sample_id)) {
+            Some((_, kernel_count)) => *kernel_count += 1,
+            None => kernel_counts.push((sample_id, 1)),
+        }//end matching whether we've seen this sample already
+    }//end counting the kernels of each sample
+    for (sample_id, kernel_count) in kernel_counts.iter() {
+        if *kernel_count < config.review_min_kernels {add_flag(sample_id, format!("Only {} kernels", kernel_count));}
+    }//end flagging samples with too few kernels
+
+    let summary = get_run_summary(data, config)?;
+    for (sample_id, percent) in summary.flagged_samples.iter() {
+        add_flag(sample_id, format!("Unusually low %{} ({:.1}%)", summary.class_name, percent));
+    }//end flagging samples with an unusually low class percent
+
+    Ok(flagged.into_iter().map(|(sample_id, reasons)| (sample_id, reasons.join("; "))).collect())
+}//end get_flagged_samples(data, config)
+
+/// Gets a summary of the csv data from a run.
+/// Percentages are given for the first class in the class filters of
+/// config, or "Sound" if there isn't one. A sample is flagged if its
+/// percent of that class is more than 1.5 times the interquartile range
+/// below the first quartile of all the samples, so at least 4 samples
+/// are needed for any to be flagged.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::app::summary::get_run_summary;
+///
+/// let headers: Vec<String> = vec!["external-sample-id".to_string(), "raw-filtered-as".to_string()];
+/// let mut rows = Vec::new();
+/// // samples S1 to S4 are all sound, but S5 is only half sound
+/// for (sample_id, sound_count) in [("S1", 4), ("S2", 4), ("S3", 4), ("S4", 4), ("S5", 2)] {
+///     for kernel_idx in 0..4 {
+///         let class = if kernel_idx < sound_count {"Sound"} else {"Broken"};
+///         let cells = vec![DataCell::new(&headers[0], sample_id.to_string()), DataCell::new(&headers[1], class.to_string())];
+///         rows.push(DataRow::new(rows.len(), cells));
+///     }
+/// }
+/// let data = Data::from_row_data(headers.clone(), rows);
+///
+/// let summary = get_run_summary(&data, &ConfigStore::default()).unwrap();
+/// assert_eq!((summary.sample_count, summary.kernel_count), (5, 20));
+/// assert_eq!(summary.class_name, "Sound");
+/// assert_eq!(summary.overall_percent, 90.0);
+/// assert_eq!(summary.flagged_samples, vec![("S5".to_string(), 50.0)]);
+/// ```
+pub fn get_run_summary(data: &Data, config: &ConfigStore) -> Result<RunSummary,String> {
+    let sample_id_col_idx = data.get_header_index(&config.csv_sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" in the csv data.", config.csv_sample_id_header))?;
+    let class_col_idx = data.get_header_index(&config.csv_class_filter_class).ok_or(format!("Couldn't find class header \"{}\" in the csv data.", config.csv_class_filter_class))?;
+    let class_name = config.csv_class_filter_filters.iter()
+        .map(|class| class.trim())
+        .find(|class| !class.is_empty())
+        .unwrap_or("Sound")
+        .to_string();
+
+    // (sample id, kernels in class, kernels in sample)
+    let mut sample_counts: Vec<(String, usize, usize)> = Vec::new();
+    for row in data.get_records_ref().iter() {
+        let sample_id = row.get_data(sample_id_col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default();
+        let in_class = row.get_data(class_col_idx).map(|cell| cell.get_data().to_string().eq(&class_name)).unwrap_or(false);
+        let class_count = if in_class {1} else {0};
+        match sample_counts.iter_mut().find(|(known_id, _, _)| known_id.eq(&sample_id)) {
+            Some((_, sample_class_count, sample_count)) => {*sample_class_count += class_count; *sample_count += 1;},
+            None => sample_counts.push((sample_id, class_count, 1)),
+        }//end matching whether we've seen this sample already
+    }//end counting the kernels of each sample
+    if sample_counts.len() == 0 {return Err(String::from("There aren't any kernels in the csv data to summarize."));}
+
+    let kernel_count: usize = sample_counts.iter().map(|(_, _, sample_count)| sample_count).sum();
+    let class_count: usize = sample_counts.iter().map(|(_, sample_class_count, _)| sample_class_count).sum();
+    let sample_percents: Vec<(String, f64)> = sample_counts.into_iter()
+        .map(|(sample_id, sample_class_count, sample_count)| (sample_id, sample_class_count as f64 / sample_count as f64 * 100.0))
+        .collect();
+
+    let mut flagged_samples = Vec::new();
+    if sample_percents.len() >= 4 {
+        let percents = sample_percents.iter().map(|(_, percent)| *percent).collect();
+        if let (Some(q1), Some(q3)) = (stats::get_quantile(&percents, 0.25), stats::get_quantile(&percents, 0.75)) {
+            let fence = q1 - 1.5 * (q3 - q1);
+            flagged_samples = sample_percents.iter().filter(|(_, percent)| *percent < fence).cloned().collect();
+        }//end if we could find the quartiles
+    }//end if there are enough samples to find unusual ones
+
+    Ok(RunSummary {
+        sample_count: sample_percents.len(),
+        kernel_count,
+        class_name,
+        overall_percent: class_count as f64 / kernel_count as f64 * 100.0,
+        flagged_samples,
+    })
+}//end get_run_summary(data, config)
@@ -0,0 +1,172 @@
+use std::{fs, path::Path};
+
+use crate::{app::{config_store::ConfigStore, paths, process}, model::{DataVal, OutputColumn, SampleOutput}};
+
+/// A hierarchy of kernel classes, where each parent class, such as Damaged,
+/// is made up of child classes, such as SorghumBroken and SorghumChipped.
+/// Children can be parents themselves, so rollups can have several levels.
+///
+/// Taxonomy files have one parent per line, followed by a colon and its
+/// children, separated by commas. Empty lines and lines starting with #
+/// are skipped.
+/// ```text
+/// # every kind of damage
+/// Damaged: SorghumBroken, SorghumChipped, HeatDamaged
+/// Defects: Damaged, Foreign Material
+/// ```
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ClassTaxonomy {
+    /// Each parent class and its children, in the order they were listed.
+    pub parents: Vec<(String, Vec<String>)>,
+}//end struct ClassTaxonomy
+
+impl ClassTaxonomy {
+    /// Parses a taxonomy from the contents of a taxonomy file.
+    /// Returns an error if a line has no colon, a parent has no children,
+    /// a parent is listed twice, or a parent is its own descendant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::taxonomy::ClassTaxonomy;
+    ///
+    /// let taxonomy = ClassTaxonomy::parse("# damage\nDamaged: SorghumBroken, SorghumChipped\n\nDefects: Damaged, Foreign").unwrap();
+    /// assert_eq!(taxonomy.parents[0], ("Damaged".to_string(), vec!["SorghumBroken".to_string(), "SorghumChipped".to_string()]));
+    /// assert_eq!(taxonomy.parents.len(), 2);
+    /// assert!(ClassTaxonomy::parse("Damaged SorghumBroken").is_err());
+    /// assert!(ClassTaxonomy::parse("Damaged:").is_err());
+    /// assert!(ClassTaxonomy::parse("Damaged: Broken\nDamaged: Chipped").is_err());
+    /// assert!(ClassTaxonomy::parse("Damaged: Defects\nDefects: Damaged").is_err());
+    /// ```
+    pub fn parse(text: &str) -> Result<ClassTaxonomy,String> {
+        let mut parents: Vec<(String, Vec<String>)> = Vec::new();
+        for (line_idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {continue;}
+            let (parent, children) = match line.split_once(':') {
+                Some((parent, children)) if !parent.trim().is_empty() => (parent.trim().to_string(), children),
+                _ => return Err(format!("Line {} of the class taxonomy, \"{}\", needs a parent class, then a colon, then its children, like \"Damaged: Broken, Chipped\".", line_idx + 1, line)),
+            };
+            let children: Vec<String> = children.split(',').map(|child| child.trim().to_string()).filter(|child| !child.is_empty()).collect();
+            if children.is_empty() {return Err(format!("The class {} on line {} of the class taxonomy has no children.", parent, line_idx + 1));}
+            if parents.iter().any(|(other_parent, _)| other_parent.eq(&parent)) {return Err(format!("The class {} is listed as a parent more than once in the class taxonomy.", parent));}
+            parents.push((parent, children));
+        }//end parsing each line
+        let taxonomy = ClassTaxonomy { parents };
+        for (parent, _) in taxonomy.parents.iter() {
+            if taxonomy.is_descendant(parent, parent, &mut Vec::new()) {return Err(format!("The class {} is listed under itself in the class taxonomy, so its total can't be found.", parent));}
+        }//end checking each parent for loops
+        Ok(taxonomy)
+    }//end parse(text)
+
+    /// Reads and parses the taxonomy file at path.
+    pub fn from_file(path: &Path) -> Result<ClassTaxonomy,String> {
+        match fs::read_to_string(path) {
+            Ok(text) => ClassTaxonomy::parse(&text).map_err(|msg| format!("Couldn't read the class taxonomy file \"{}\".\n{}", path.to_string_lossy(), msg)),
+            Err(error) => Err(format!("Couldn't open the class taxonomy file \"{}\": {}", path.to_string_lossy(), error)),
+        }//end matching whether we could read the file
+    }//end from_file(path)
+
+    /// Gets the children listed under parent, or None if parent isn't a parent class.
+    fn get_children(&self, parent: &str) -> Option<&Vec<String>> {
+        self.parents.iter().find(|(other_parent, _)| other_parent.eq(parent)).map(|(_, children)| children)
+    }//end get_children(self, parent)
+
+    /// Tells whether class is anywhere below parent, keeping track of
+    /// the parents already checked so a loop doesn't go on forever.
+    fn is_descendant(&self, parent: &str, class: &str, checked: &mut Vec<String>) -> bool {
+        if checked.iter().any(|checked_parent| checked_parent.eq(parent)) {return false;}
+        checked.push(parent.to_string());
+        match self.get_children(parent) {
+            Some(children) => children.iter().any(|child| child.eq(class) || self.is_descendant(child, class, checked)),
+            None => false,
+        }//end matching whether parent has children
+    }//end is_descendant(self, parent, class, checked)
+
+    /// Gets the classes at the bottom of the taxonomy under parent,
+    /// which are the classes of kernels counted in its total.
+    /// Each class is only listed once, even if it's under parent more
+    /// than once. If parent isn't a parent class, it's returned by itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::taxonomy::ClassTaxonomy;
+    ///
+    /// let taxonomy = ClassTaxonomy::parse("Damaged: SorghumBroken, SorghumChipped\nDefects: Damaged, Foreign, SorghumBroken").unwrap();
+    /// assert_eq!(taxonomy.get_leaf_classes("Defects"), vec!["SorghumBroken", "SorghumChipped", "Foreign"]);
+    /// assert_eq!(taxonomy.get_leaf_classes("Sound"), vec!["Sound"]);
+    /// ```
+    pub fn get_leaf_classes(&self, parent: &str) -> Vec<String> {
+        let mut leaf_classes: Vec<String> = Vec::new();
+        match self.get_children(parent) {
+            Some(children) => {
+                for child in children.iter() {
+                    for leaf_class in self.get_leaf_classes(child) {
+                        if !leaf_classes.contains(&leaf_class) {leaf_classes.push(leaf_class);}
+                    }//end adding each leaf class under this child
+                }//end looking under each child
+            },
+            None => leaf_classes.push(parent.to_string()),
+        }//end matching whether parent has children
+        leaf_classes
+    }//end get_leaf_classes(self, parent)
+}//end impl ClassTaxonomy
+
+/// Reads the taxonomy file from config.class_taxonomy_file, resolved
+/// with paths::resolve_setting_path().
+/// Returns None if no taxonomy file is set.
+pub fn load_config_taxonomy(config: &ConfigStore) -> Result<Option<ClassTaxonomy>,String> {
+    if config.class_taxonomy_file.trim().is_empty() {return Ok(None);}
+    ClassTaxonomy::from_file(&paths::resolve_setting_path(&config.class_taxonomy_file)).map(Some)
+}//end load_config_taxonomy(config)
+
+/// Adds a column to class percent output for each parent class in
+/// taxonomy, after the other columns, holding the total of the percents
+/// of the classes under it. The columns are named with header_template,
+/// like the other class percent columns. Classes without a column, since
+/// no kernel had them, count as zero.
+/// Returns an error if a parent class already has a column, since it's
+/// also a class in the data.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::taxonomy::{self, ClassTaxonomy};
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+///
+/// let mut output = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::percent("%Sound", 1))
+///     .with_column(OutputColumn::percent("%SorghumBroken", 1))
+///     .with_column(OutputColumn::percent("%SorghumChipped", 1));
+/// output.add_row("A", vec![DataVal::Float(0.5), DataVal::Float(0.25), DataVal::Float(0.25)]);
+/// output.add_row("B", vec![DataVal::Float(0.9), DataVal::Float(0.1), DataVal::Float(0.0)]);
+/// let taxonomy = ClassTaxonomy::parse("Damaged: SorghumBroken, SorghumChipped, HeatDamaged").unwrap();
+/// taxonomy::add_rollup_columns(&mut output, &taxonomy, "%{class}").unwrap();
+/// assert_eq!(output.get_column_index("%Damaged"), Some(3));
+/// assert!(output.columns[3].is_total());
+/// assert_eq!(output.rows[0].cells[3], DataVal::Float(0.5));
+/// assert_eq!(output.rows[1].cells[3], DataVal::Float(0.1));
+///
+/// let sound_taxonomy = ClassTaxonomy::parse("Sound: Whole").unwrap();
+/// assert!(taxonomy::add_rollup_columns(&mut output, &sound_taxonomy, "%{class}").is_err());
+/// ```
+pub fn add_rollup_columns(output: &mut SampleOutput, taxonomy: &ClassTaxonomy, header_template: &str) -> Result<(),String> {
+    for (parent, _) in taxonomy.parents.iter() {
+        let header = process::fill_header_template(header_template, "{class}", parent);
+        if output.get_column_index(&header).is_some() {return Err(format!("The parent class {} in the class taxonomy is also a class in the data, so its total can't get its own column. Please give the parent another name.", parent));}
+        let leaf_col_idxs: Vec<usize> = taxonomy.get_leaf_classes(parent).iter()
+            .filter_map(|leaf_class| output.get_column_index(&process::fill_header_template(header_template, "{class}", leaf_class)))
+            .collect();
+        for row in output.rows.iter_mut() {
+            let total = leaf_col_idxs.iter().filter_map(|col_idx| match row.cells.get(*col_idx) {
+                Some(DataVal::Float(percent)) => Some(*percent),
+                Some(DataVal::Int(percent)) => Some(*percent as f64),
+                _ => None,
+            }).sum();
+            row.cells.push(DataVal::Float(total));
+        }//end adding the total to each row
+        output.add_column(OutputColumn::percent_total(&header, 1));
+    }//end adding a column for each parent class
+    Ok(())
+}//end add_rollup_columns(output, taxonomy, header_template)
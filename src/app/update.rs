@@ -0,0 +1,74 @@
+use std::{fs, path::PathBuf};
+
+/// This struct holds information about a released version
+/// of the program, as read from an update check location.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReleaseInfo {
+    /// The version number of the release, such as "0.5.0".
+    pub version: String,
+    /// Any release notes describing what changed in the release.
+    pub notes: String,
+}//end struct ReleaseInfo
+
+/// Parses the contents of a release info file.
+/// The first non-empty line is the version number, optionally
+/// starting with a v. All following lines are the release notes.
+///
+/// ```
+/// use usda_c_grain_sum::app::update::parse_release_info;
+///
+/// let info = parse_release_info("\nv0.5.0\nAdded a setup wizard.\nFixed a bug.").unwrap();
+/// assert_eq!(info.version, "0.5.0");
+/// assert_eq!(info.notes, "Added a setup wizard.\nFixed a bug.");
+/// assert!(parse_release_info("   \n").is_err());
+/// ```
+pub fn parse_release_info(contents: &str) -> Result<ReleaseInfo,String> {
+    let mut lines = contents.lines().skip_while(|line| line.trim().is_empty());
+    let version = match lines.next() {
+        Some(line) => line.trim().trim_start_matches(['v', 'V']).to_string(),
+        None => return Err(String::from("The release info was empty.")),
+    };
+    let notes = lines.collect::<Vec<&str>>().join("\n").trim().to_string();
+    Ok(ReleaseInfo { version, notes })
+}//end parse_release_info(contents)
+
+/// Returns true if candidate is a newer version than current.
+/// Versions are compared by each dot-separated number in turn,
+/// with missing numbers treated as 0. Anything after a - or +
+/// in a number is ignored.
+///
+/// ```
+/// use usda_c_grain_sum::app::update::is_newer_version;
+///
+/// assert!(is_newer_version("0.4.3", "0.5.0"));
+/// assert!(is_newer_version("0.4.3", "0.4.10"));
+/// assert!(!is_newer_version("0.4.3", "0.4.3"));
+/// assert!(!is_newer_version("0.4.3", "0.4"));
+/// assert!(!is_newer_version("1.0.0", "0.9.9"));
+/// ```
+pub fn is_newer_version(current: &str, candidate: &str) -> bool {
+    let to_numbers = |version: &str| -> Vec<u64> {
+        version.trim().trim_start_matches(['v', 'V'])
+            .split('.')
+            .map(|part| part.split(['-', '+']).next().unwrap_or("").parse::<u64>().unwrap_or(0))
+            .collect()
+    };
+    let current_nums = to_numbers(current);
+    let candidate_nums = to_numbers(candidate);
+    for idx in 0..current_nums.len().max(candidate_nums.len()) {
+        let current_num = current_nums.get(idx).unwrap_or(&0);
+        let candidate_num = candidate_nums.get(idx).unwrap_or(&0);
+        if candidate_num != current_num {return candidate_num > current_num;}
+    }//end comparing each part of the versions
+    return false;
+}//end is_newer_version(current, candidate)
+
+/// Attempts to read release info from a file, such as one on a network share.
+/// A leading file:// is removed from location if present.
+pub fn try_read_release_info_file(location: &str) -> Result<ReleaseInfo,String> {
+    let path = PathBuf::from(location.trim().trim_start_matches("file://"));
+    match fs::read_to_string(&path) {
+        Ok(contents) => parse_release_info(&contents),
+        Err(error) => Err(format!("Couldn't read release info from \"{}\": {}", path.to_string_lossy(), error)),
+    }//end matching whether we could read the file
+}//end try_read_release_info_file(location)
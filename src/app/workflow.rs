@@ -0,0 +1,507 @@
+use std::{fs, path::PathBuf};
+
+#[cfg(feature = "scripting")]
+use crate::app::scripting::UserScript;
+use crate::{app::{config_store::{ConfigStore, InvalidSampleIdHandling, LockedSettings, SampleIdAnonymization, StatGroupOutput}, flags, frontend::{self, Frontend}, ledger::{self, LedgerEntry}, lock::{self, FileLock}, paths, preflight::{self, OutputLocationProblem}, process, secrets, summary::RunSummary}, io::{sink::{self, SinkOutput}, xlsx}, model::{Data, SampleOutput}, pipeline};
+
+/// Runs the after_load hook of the user script at script_path on data,
+/// which was loaded from a file of the given kind, "csv" or "xml".  
+/// If there's no script, data is returned as it is. If the script fails,
+/// the error holds the unchanged data along with the message.
+#[cfg(feature = "scripting")]
+pub fn apply_after_load_script(script_path: &str, kind: &str, data: Data) -> Result<Data,(Data,String)> {
+    if script_path.trim().is_empty() {return Ok(data);}
+    match UserScript::from_file(&paths::resolve_setting_path(script_path)).and_then(|script| script.run_after_load(kind, &data)) {
+        Ok(scripted_data) => Ok(scripted_data),
+        Err(msg) => Err((data, msg)),
+    }//end matching whether the script ran
+}//end apply_after_load_script(script_path, kind, data)
+
+/// Without the scripting feature, user scripts are never run.
+#[cfg(not(feature = "scripting"))]
+pub fn apply_after_load_script(script_path: &str, _kind: &str, data: Data) -> Result<Data,(Data,String)> {
+    if !script_path.trim().is_empty() {log::warn!("A script is set in the config, but this build doesn't include scripting, so it was ignored.");}
+    Ok(data)
+}//end apply_after_load_script(script_path, _kind, data)
+
+/// Runs the before_write hook of the user script at script_path on the
+/// output sheet named sheet_name, returning the sheet to write.  
+/// If there's no script, a copy of sheet_data is returned.
+#[cfg(feature = "scripting")]
+pub fn apply_before_write_script(script_path: &str, sheet_name: &str, sheet_data: &SampleOutput) -> Result<SampleOutput,String> {
+    if script_path.trim().is_empty() {return Ok(sheet_data.clone());}
+    UserScript::from_file(&paths::resolve_setting_path(script_path))?.run_before_write(sheet_name, sheet_data)
+}//end apply_before_write_script(script_path, sheet_name, sheet_data)
+
+/// Without the scripting feature, user scripts are never run.
+#[cfg(not(feature = "scripting"))]
+pub fn apply_before_write_script(_script_path: &str, _sheet_name: &str, sheet_data: &SampleOutput) -> Result<SampleOutput,String> {
+    Ok(sheet_data.clone())
+}//end apply_before_write_script(_script_path, _sheet_name, sheet_data)
+
+/// If sample id anonymization is on in config, gets a copy of output_sheets
+/// and csv_data with each sample id replaced by its pseudonym, and writes
+/// the key from pseudonyms back to sample ids next to output.  
+/// If anonymization is off, returns None, so the sheets can be written as they are.  
+/// The copy of csv_data is None if there's no csv data, or if its sample
+/// ids couldn't be replaced, so that real sample ids are never written.  
+/// Returns an error if the pseudonyms couldn't be made, in which case
+/// nothing should be written.
+pub fn get_anonymized_output(gui: &mut impl Frontend, output_sheets: &Vec<(String, SampleOutput)>, csv_data: &Option<Data>, config: &ConfigStore, output: &PathBuf) -> Result<Option<(Vec<(String, SampleOutput)>, Option<Data>)>,String> {
+    if config.sample_id_anonymization == SampleIdAnonymization::Off {return Ok(None);}
+    let key = get_output_sample_id_key(output_sheets, csv_data, config)?;
+
+    let mut anonymized_sheets = output_sheets.clone();
+    for (_, sheet_data) in anonymized_sheets.iter_mut() {process::replace_sample_ids(sheet_data, &key);}
+    let anonymized_csv = match csv_data {
+        Some(ref csv_data) => match process::get_data_with_replaced_ids(csv_data, &config.csv_sample_id_header, &key) {
+            Ok(anonymized_csv) => Some(anonymized_csv),
+            Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't replace the sample ids in the input rows, so they won't be copied into the output.\n{}", msg)); None},
+        },
+        None => None,
+    };
+    let key_path = process::get_sample_id_key_path(output);
+    match process::write_sample_id_key(&key_path, &key) {
+        Ok(_) => log::info!("Wrote key for {} pseudonyms to {}", key.len(), key_path.to_string_lossy()),
+        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the key matching pseudonyms to sample ids, so keep the input file to match them later.\n{}", msg)),
+    }//end matching whether we could write the key file
+    Ok(Some((anonymized_sheets, anonymized_csv)))
+}//end get_anonymized_output(gui, output_sheets, csv_data, config, output)
+
+/// Writes a workbook next to output for each value of the split column in
+/// the metadata file, such as one per customer, with only the rows of the
+/// samples with that value. Sheets which don't have any of those samples,
+/// such as Treatment_Comparison, are left out of that workbook.  
+/// Sample ids are replaced with their pseudonyms from key, if it has any.
+pub fn write_split_workbooks(gui: &mut impl Frontend, output_sheets: &Vec<(String, SampleOutput)>, key: &Vec<(String, String)>, config: &ConfigStore, output: &PathBuf) {
+    let groups = match load_metadata(config).and_then(|metadata| process::get_metadata_groups(&metadata, &config.metadata_sample_id_header, &config.split_output_column)) {
+        Ok(groups) => groups,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't split the output by metadata column, so only the combined workbook was written.\n{}", msg)); return;},
+    };
+    let mut workbooks_written = 0;
+    for (group, sample_ids) in groups.iter() {
+        let mut wb = xlsx::get_workbook();
+        let mut sheets_written = 0;
+        for (sheet_name, sheet_data) in output_sheets.iter() {
+            let mut sheet_subset = process::get_sample_output_subset(sheet_data, sample_ids);
+            if sheet_subset.rows.len() == 0 {continue;}
+            process::replace_sample_ids(&mut sheet_subset, key);
+            match xlsx::write_output_to_sheet(&mut wb, &sheet_subset, sheet_name, config) {
+                Ok(_) => sheets_written += 1,
+                Err(msg) => gui.integrated_dialog_alert(&format!("Ecountered an error while attempting to write data to worksheet {} for {}.\n{}", sheet_name, group, msg)),
+            }//end matching whether writing to sheet was a success
+        }//end writing the rows of this group from each output sheet
+        if sheets_written == 0 {log::warn!("None of the samples for {} were in the output, so no workbook was written for it.", group); continue;}
+        let split_path = process::get_split_output_path(output, group);
+        match FileLock::try_acquire(&split_path) {
+            Ok(_split_lock) => match xlsx::close_workbook(&mut wb, &split_path) {
+                Ok(_) => workbooks_written += 1,
+                Err(error) => gui.integrated_dialog_alert(&format!("Encountered an error while attempting to write the workbook for {}.\n{}", group, error)),
+            },
+            Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the workbook for {}, since another copy of this program is writing it.\n{}", group, msg)),
+        }//end matching whether we can lock the split workbook
+    }//end writing a workbook for each group
+    log::info!("Wrote {} workbooks split by {} next to {}", workbooks_written, config.split_output_column, output.to_string_lossy());
+}//end write_split_workbooks(gui, output_sheets, key, config, output)
+
+/// Gets the key from sample ids to pseudonyms for the samples in
+/// output_sheets and csv_data, as (sample id, pseudonym).  
+/// If sample id anonymization is off in config, the key is empty.  
+/// Stable hashes are keyed with the salt of this install, which is made
+/// the first time it's needed.
+pub fn get_output_sample_id_key(output_sheets: &Vec<(String, SampleOutput)>, csv_data: &Option<Data>, config: &ConfigStore) -> Result<Vec<(String, String)>,String> {
+    let salt = match config.sample_id_anonymization {
+        SampleIdAnonymization::Off => return Ok(Vec::new()),
+        SampleIdAnonymization::StableHash => secrets::get_install_salt(secrets::ANONYMIZATION_SALT_FILE_NAME)?,
+        SampleIdAnonymization::SequentialCodes => Vec::new(),
+    };
+    let mut sample_ids: Vec<String> = output_sheets.iter()
+        .flat_map(|(_, sheet_data)| sheet_data.rows.iter().map(|output_row| output_row.sample_id.clone()))
+        .collect();
+    if let Some(ref csv_data) = csv_data {sample_ids.append(&mut process::get_sample_ids(csv_data, &config.csv_sample_id_header));}
+    process::get_sample_id_key(&sample_ids, config.sample_id_anonymization, &salt)
+}//end get_output_sample_id_key(output_sheets, csv_data, config)
+
+/// Makes sure the output file at output can be written, since the most
+/// common reason it can't is the last output still being open in Excel.  
+/// If it's open in another program, the user can retry once they've
+/// closed it, save the output under an alternate name, wait for it to be
+/// closed, or cancel. When unattended, we wait for it to be closed, then
+/// use an alternate name if it's still open.  
+/// Returns the path to write the output to, or None if the user cancelled.
+pub fn ensure_output_writable(gui: &mut impl Frontend, output: &PathBuf, unattended: bool) -> Option<PathBuf> {
+    while lock::is_file_in_use(output) {
+        let alternate_path = lock::get_alternate_path(output);
+        if unattended {
+            log::info!("\"{}\" is open in another program, so we'll wait up to {} seconds for it to be closed.", output.to_string_lossy(), lock::IN_USE_TIMEOUT.as_secs());
+            return match lock::wait_for_file_released(output, lock::IN_USE_TIMEOUT) {
+                Ok(_) => Some(output.clone()),
+                Err(msg) => {
+                    log::warn!("{}\nThe output will be written to \"{}\" instead.", msg, alternate_path.to_string_lossy());
+                    Some(alternate_path)
+                },
+            };
+        }//end if there's nobody to ask
+
+        let prompt = format!("\"{}\" is open in another program, such as Excel, so it can't be written.\nClose it and click Retry, save the output as \"{}\" instead, or wait up to {} seconds for it to be closed.", output.to_string_lossy(), paths::get_file_name(&alternate_path), lock::IN_USE_TIMEOUT.as_secs());
+        match gui.integrated_dialog_message_choice(&prompt, vec!["Retry", "Save As Alternate Name", "Wait", "Cancel"]) {
+            Some(0) => continue,
+            Some(1) => return Some(alternate_path),
+            Some(2) => {
+                let worker_output = output.clone();
+                let wait_result = frontend::run_in_background(gui, move |progress| {
+                    progress(0.0, "Waiting for the output file to be closed");
+                    lock::wait_for_file_released(&worker_output, lock::IN_USE_TIMEOUT)
+                });
+                match wait_result {
+                    Ok(Ok(_)) => return Some(output.clone()),
+                    Ok(Err(msg)) | Err(msg) => gui.integrated_dialog_alert(&msg),
+                }//end matching whether the file was closed in time
+            },
+            _ => return None,
+        }//end matching what the user wants to do about the file being open
+    }//end asking the user until the file can be written
+    Some(output.clone())
+}//end ensure_output_writable(gui, output, unattended)
+
+/// Checks that the output can be written to output_folder before anything
+/// is processed, with enough free space for the output of input_files.  
+/// The user is offered to create a missing folder, try again after fixing
+/// the problem, or process anyway if the drive seems too full.  
+/// If unattended, a missing folder is created, and any other problem is
+/// printed instead of asked about.  
+/// Returns false if nothing should be processed.
+pub fn ensure_output_folder_ready(gui: &mut impl Frontend, output_folder: &PathBuf, input_files: &Vec<&PathBuf>, unattended: bool) -> bool {
+    let needed = preflight::estimate_output_size(input_files);
+    loop {
+        let problem = match preflight::check_output_folder(output_folder, needed) {
+            Ok(_) => return true,
+            Err(problem) => problem,
+        };
+        match (problem, unattended) {
+            (OutputLocationProblem::MissingFolder(folder), true) => if let Err(error) = fs::create_dir_all(&folder) {
+                log::error!("Couldn't create the output folder \"{}\", so nothing was processed.\n{}", folder.to_string_lossy(), error);
+                return false;
+            },
+            (problem, true) => {log::error!("{}\nNothing was processed.", problem.get_description()); return false;},
+            (OutputLocationProblem::MissingFolder(folder), false) => {
+                let prompt = format!("{}\nWould you like to create it, or try again after connecting it?", OutputLocationProblem::MissingFolder(folder.clone()).get_description());
+                match gui.integrated_dialog_message_choice(&prompt, vec!["Create Folder", "Retry", "Cancel"]) {
+                    Some(0) => if let Err(error) = fs::create_dir_all(&folder) {gui.integrated_dialog_alert(&format!("Couldn't create the folder \"{}\".\n{}", folder.to_string_lossy(), error));},
+                    Some(1) => continue,
+                    _ => return false,
+                }//end matching what the user wants to do about the missing folder
+            },
+            (problem @ OutputLocationProblem::NotWritable(..), false) => {
+                let prompt = format!("{}\nPlease choose another output file, or fix the folder's permissions and click Retry.", problem.get_description());
+                if gui.integrated_dialog_message_choice(&prompt, vec!["Retry", "Cancel"]) != Some(0) {return false;}
+            },
+            (problem @ OutputLocationProblem::LowSpace { .. }, false) => {
+                let prompt = format!("{}\nFree up some space and click Retry, or process anyway.", problem.get_description());
+                match gui.integrated_dialog_message_choice(&prompt, vec!["Retry", "Process Anyway", "Cancel"]) {
+                    Some(0) => continue,
+                    Some(1) => return true,
+                    _ => return false,
+                }//end matching what the user wants to do about the low space
+            },
+        }//end matching how to handle the problem
+    }//end checking until the folder is ready or the user gives up
+}//end ensure_output_folder_ready(gui, output_folder, input_files, unattended)
+
+/// Writes output to each sink enabled in config, such as the output
+/// workbook and csv copies of its sheets, alerting the user of any
+/// which couldn't be written.  
+/// Returns whether the output file itself was written.
+pub fn write_output_sinks(gui: &mut impl Frontend, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> bool {
+    let mut output_file_written = false;
+    for output_sink in sink::get_enabled_sinks(config) {
+        let sink_path = output_sink.get_output_path(output_path);
+        match output_sink.write(output, config, &sink_path) {
+            Ok(description) => {
+                gui.log(&description);
+                if sink_path == *output_path {output_file_written = true;}
+            },
+            Err(msg) => gui.integrated_dialog_alert(&format!("An Error Occurred while trying to write the {} output!\n{}", output_sink.name(), msg)),
+        }//end matching whether this sink could be written
+    }//end writing each enabled sink
+    output_file_written
+}//end write_output_sinks(gui, output, config, output_path)
+
+/// Adds a line for a successful run to the run ledger, with the hash of
+/// each of input_files and key metrics from output_sheets and run_summary.  
+/// If the run can't be added, why is only logged, since the output was
+/// already written.
+pub fn record_ledger_run(gui: &mut impl Frontend, input_files: &Vec<&PathBuf>, output: &PathBuf, config: &ConfigStore, output_sheets: &Vec<(String, SampleOutput)>, run_summary: Option<&RunSummary>) {
+    let timestamp = time::OffsetDateTime::now_utc().format(time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second] UTC")).unwrap_or_default();
+    let metrics = ledger::get_ledger_metrics(output_sheets, run_summary);
+    let ledger_result = ledger::get_ledger_path().and_then(|ledger_path| {
+        let ledger_key = secrets::get_install_salt(secrets::LEDGER_KEY_FILE_NAME)?;
+        LedgerEntry::new(&timestamp, input_files, output, config, metrics).and_then(|entry| ledger::append_ledger_entry(&ledger_path, entry, &ledger_key))
+    });
+    match ledger_result {
+        Ok(entry) => gui.log(&format!("Added the run to the run ledger with checksum {}.", entry.checksum)),
+        Err(msg) => gui.log(&format!("Couldn't add the run to the run ledger.\n{}", msg)),
+    }//end matching whether we could add the run to the ledger
+}//end record_ledger_run(gui, input_files, output, config, output_sheets, run_summary)
+
+/// Gets the lines of the Run Info sheet, holding the program version,
+/// when the output was made, who made it, and the input files.  
+/// Any samples excluded after review are listed along with why they were flagged.  
+/// The signature of the output is added by the xlsx sink, after every other
+/// sheet has been written.
+pub fn get_run_info(config: &ConfigStore, csv_input_file: &Option<PathBuf>, xml_input_file: &Option<PathBuf>, excluded_samples: &Vec<(String, String)>) -> Vec<(String, String)> {
+    let created = time::OffsetDateTime::now_utc().format(time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second] UTC")).unwrap_or_default();
+    let to_file_name = |input_file: &Option<PathBuf>| input_file.as_ref().map(|path| path.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut run_info = vec![
+        ("Program Version".to_string(), env!("CARGO_PKG_VERSION").to_string()),
+        ("Created".to_string(), created),
+        ("Operator".to_string(), config.operator_name.clone()),
+        ("CSV Input".to_string(), to_file_name(csv_input_file)),
+        ("XML Input".to_string(), to_file_name(xml_input_file)),
+    ];
+    for (sample_id, reasons) in excluded_samples.iter() {
+        run_info.push(("Excluded Sample".to_string(), format!("{} ({})", sample_id, reasons)));
+    }//end listing each excluded sample
+    run_info
+}//end get_run_info(config, csv_input_file, xml_input_file, excluded_samples)
+
+/// Gets the key of this install which the Run Info sheet signs the output
+/// with, if run_info is going to be written. Otherwise, returns None.
+pub fn get_output_signing_key(run_info: &Option<Vec<(String, String)>>) -> Result<Option<Vec<u8>>,String> {
+    match run_info {
+        Some(_) => match secrets::get_install_salt(secrets::OUTPUT_SIGNING_KEY_FILE_NAME) {
+            Ok(signing_key) => Ok(Some(signing_key)),
+            Err(msg) => Err(format!("Couldn't get the key the Run Info sheet signs the output with, so nothing was written.\n{}", msg)),
+        },
+        None => Ok(None),
+    }//end matching whether the run info sheet will be written
+}//end get_output_signing_key(run_info)
+
+/// Gets the samples in the loaded data whose sample id doesn't match the
+/// sample id pattern in config, as (sample id, why it's left out), when
+/// they're set to be left out of the output. If they're flagged instead,
+/// or there's no pattern, this is empty.
+pub fn get_invalid_id_exclusions(input_csv_data: &Option<Data>, input_xml_data: &Option<Data>, config: &ConfigStore) -> Result<Vec<(String, String)>,String> {
+    let mut exclusions: Vec<(String, String)> = Vec::new();
+    if config.invalid_sample_id_handling != InvalidSampleIdHandling::Exclude {return Ok(exclusions);}
+    for (data, sample_id_header) in [(input_csv_data, &config.csv_sample_id_header), (input_xml_data, &config.xml_sample_id_header)] {
+        let data = match data {Some(data) => data, None => continue};
+        for sample_id in process::get_invalid_sample_ids(data, sample_id_header, config)? {
+            if exclusions.iter().any(|(excluded_id, _)| excluded_id.eq(&sample_id)) {continue;}
+            exclusions.push((sample_id, format!("Doesn't match the sample id pattern {}", config.sample_id_pattern.trim())));
+        }//end adding each sample id which doesn't match
+    }//end checking the sample ids of each file
+    Ok(exclusions)
+}//end get_invalid_id_exclusions(input_csv_data, input_xml_data, config)
+
+/// Removes the rows of each sample in excluded_samples, given as
+/// (sample id, why it's left out), from the csv and xml data.
+/// If the samples can't be removed from a file, it's kept as it is.
+pub fn exclude_samples(input_csv_data: Option<Data>, input_xml_data: Option<Data>, excluded_samples: &Vec<(String, String)>, config: &ConfigStore) -> (Option<Data>, Option<Data>) {
+    let excluded_ids: Vec<String> = excluded_samples.iter().map(|(sample_id, _)| sample_id.clone()).collect();
+    let input_csv_data = input_csv_data.map(|csv_data| match process::get_data_without_samples(&csv_data, &config.csv_sample_id_header, &excluded_ids) {
+        Ok(remaining_data) => remaining_data,
+        Err(msg) => {log::warn!("Couldn't exclude samples from the csv data.\n{}", msg); csv_data},
+    });
+    let input_xml_data = input_xml_data.map(|xml_data| match process::get_data_without_samples(&xml_data, &config.xml_sample_id_header, &excluded_ids) {
+        Ok(remaining_data) => remaining_data,
+        Err(msg) => {log::warn!("Couldn't exclude samples from the xml data.\n{}", msg); xml_data},
+    });
+    (input_csv_data, input_xml_data)
+}//end exclude_samples(input_csv_data, input_xml_data, excluded_samples, config)
+
+/// Reads the metadata file from the path in the config, such as
+/// a file giving the treatment of each sample.
+/// A relative path is read from the config folder.
+pub fn load_metadata(config: &ConfigStore) -> Result<Data,String> {
+    if config.metadata_file_path.trim().is_empty() {return Err(String::from("No metadata file has been chosen. You can choose one by right-clicking the output button."));}
+    let metadata_path = paths::resolve_setting_path(&config.metadata_file_path);
+    match csv::ReaderBuilder::new().flexible(true).from_path(&metadata_path) {
+        Ok(reader) => match Data::from_csv_reader(reader, 0, &Vec::new(), 0) {
+            Some(metadata) => Ok(metadata),
+            None => Err(format!("Couldn't find a header row in the metadata file \"{}\".", metadata_path.to_string_lossy())),
+        },
+        Err(error) => Err(format!("Couldn't open the metadata file \"{}\".\n{}", metadata_path.to_string_lossy(), error)),
+    }//end matching whether we could open the metadata file
+}//end load_metadata()
+
+/// Processes the loaded data into each output sheet enabled in config, in
+/// the order they're written, with any formula columns and the Flags column.
+/// Kernel csv files are written next to output, if they're enabled.  
+/// Problems which leave out part of the output, such as a sheet which
+/// couldn't be processed, are added to problems, so they can be shown all at once.  
+/// Each step is reported to progress, as (fraction done, step), before it runs.  
+/// Returns an error if the processing pipeline fails, since nothing can be processed then.
+pub fn get_output_sheets(input_csv_data: &Option<Data>, input_xml_data: &Option<Data>, config: &ConfigStore, output: &PathBuf, problems: &mut Vec<String>, progress: &dyn Fn(f64, &str)) -> Result<Vec<(String, SampleOutput)>,String> {
+    // (name of sheet, data to go in that sheet)
+    let mut output_sheets: Vec<(String, SampleOutput)> = Vec::new();
+    // the steps which will run, so progress can be given as the fraction of them done
+    let csv_step_count = match input_csv_data.is_some() && config.is_csv_output_enabled() {
+        true => 1 + [config.csv_stat_columns_enabled, config.csv_class_percent_enabled, config.metrics_sheet_enabled, config.treatment_sheet_enabled, config.diagnostics_sheet_enabled, config.drift_sheet_enabled, config.merged_sheet_enabled, config.kernel_csv_export_enabled].iter().filter(|enabled| **enabled).count(),
+        false => 0,
+    };
+    let step_count = csv_step_count + (input_xml_data.is_some() && config.xml_sieve_cols_enabled) as usize + 1;
+    let mut steps_started = 0;
+    let mut start_step = |step: &str| {
+        progress(steps_started as f64 / step_count as f64, step);
+        steps_started += 1;
+    };
+    // the csv data for the kernel csv files, which are written once every sheet is done, so their pseudonyms match
+    let mut kernel_csv_data: Option<Data> = None;
+
+    // get all data we might want, based on config
+    if let Some(input_csv) = input_csv_data.as_ref().filter(|_| config.is_csv_output_enabled()) {
+        // the pipeline works on a copy, so the input snapshot still has the rows as loaded
+        start_step("Running the processing pipeline");
+        let (piped_csv, step_messages) = pipeline::run_pipeline(input_csv, config)?;
+        for msg in step_messages {log::info!("{}", msg);}
+        // (copy of data with lot ids in place of sample ids, lot id of each sample)
+        let csv_lot_data = match config.lot_grouping_enabled {
+            true => match process::get_lot_data(&piped_csv, &config.csv_sample_id_header, config) {
+                Ok(lot_data) => Some(lot_data),
+                Err(msg) => {problems.push(format!("Couldn't group the csv samples into lots, so lot rows will be left out.\n{}", msg)); None},
+            },
+            false => None,
+        };
+        if config.csv_stat_columns_enabled {
+            start_step("Calculating csv stat columns");
+            match process::proc_csv_stat_cols(&piped_csv, config) {
+                Ok(mut sample_output) => {
+                    add_csv_lot_rows(problems, &mut sample_output, &csv_lot_data, config, process::proc_csv_stat_cols);
+                    match config.csv_stat_group_output {
+                        StatGroupOutput::SheetPerGroup if !config.csv_stat_group_header.trim().is_empty() => output_sheets.extend(process::split_output_by_group("CSV_Stats", &sample_output, config.csv_stat_group_header.trim())),
+                        _ => output_sheets.push(("CSV_Stats".to_string(), sample_output)),
+                    }//end matching whether each group gets its own sheet
+                },
+                Err(msg) => problems.push(format!("An Error Occurred while trying to process CSV STAT Columns!\n{}",msg)),
+            }//end matching whether or not csv stat columns were processed successfully
+        }//end if we should output csv stat columns
+        if config.csv_class_percent_enabled {
+            start_step("Calculating class percents");
+            match process::proc_csv_class_per(&piped_csv, config) {
+                Ok(mut sample_output) => {
+                    add_csv_lot_rows(problems, &mut sample_output, &csv_lot_data, config, process::proc_csv_class_per);
+                    output_sheets.push(("Class_Percents".to_string(), sample_output));
+                },
+                Err(msg) => problems.push(format!("An Error Occured while trying to process CSV Class Percent Columns!\n{}",msg)),
+            }//end matching whether or not csv class percents were processed successfully
+        }//end if we should output class percents
+        if config.metrics_sheet_enabled {
+            start_step("Calculating report metrics");
+            match process::proc_csv_metrics(&piped_csv, config) {
+                Ok(mut sample_output) => {
+                    add_csv_lot_rows(problems, &mut sample_output, &csv_lot_data, config, process::proc_csv_metrics);
+                    output_sheets.push(("Metrics".to_string(), sample_output));
+                },
+                Err(msg) => problems.push(format!("An Error Occurred while trying to calculate the report metrics for the Metrics sheet!\n{}",msg)),
+            }//end matching whether or not metrics were processed successfully
+        }//end if we should output metrics
+        if config.treatment_sheet_enabled {
+            start_step("Comparing treatments");
+            match load_metadata(config).and_then(|metadata| process::proc_treatment_comparison(&piped_csv, &metadata, config)) {
+                Ok(sample_output) => output_sheets.push(("Treatment_Comparison".to_string(), sample_output)),
+                Err(msg) => problems.push(format!("An Error Occurred while trying to process the Treatment Comparison!\n{}",msg)),
+            }//end matching whether or not treatment comparison was processed successfully
+        }//end if we should output treatment comparison
+        if config.diagnostics_sheet_enabled {
+            start_step("Counting non-numeric and missing values");
+            match process::proc_csv_diagnostics(&piped_csv, config) {
+                Ok(sample_output) => output_sheets.push(("Diagnostics".to_string(), sample_output)),
+                Err(msg) => problems.push(format!("An Error Occurred while trying to count non-numeric and missing values for the Diagnostics sheet!\n{}",msg)),
+            }//end matching whether or not diagnostics were processed successfully
+        }//end if we should output diagnostics
+        if !config.csv_frame_header.trim().is_empty() && piped_csv.get_header_index(&config.csv_frame_header).is_some() {
+            match process::proc_csv_frame_stats(&piped_csv, config) {
+                Ok(sample_output) => output_sheets.push(("Frame_Stats".to_string(), sample_output)),
+                Err(msg) => problems.push(format!("An Error Occurred while trying to count the frames of each sample!\n{}",msg)),
+            }//end matching whether or not frame stats were processed successfully
+        }//end if the csv has a frame column
+        if config.drift_sheet_enabled {
+            start_step("Checking for drift");
+            match process::proc_csv_drift_check(&piped_csv, config) {
+                Ok(sample_output) => output_sheets.push(("Drift_Check".to_string(), sample_output)),
+                Err(msg) => problems.push(format!("An Error Occurred while trying to check the run for drift!\n{}",msg)),
+            }//end matching whether or not the drift check was processed successfully
+        }//end if we should check for drift
+        if config.merged_sheet_enabled {
+            start_step("Joining the csv and xml data");
+            if let Some(input_xml) = input_xml_data {
+                match process::proc_merged_output(&piped_csv, input_xml, config) {
+                    Ok(sample_output) => output_sheets.push(("Merged".to_string(), sample_output)),
+                    Err(msg) => problems.push(format!("An Error Occurred while trying to join the csv and xml data for the Merged sheet!\n{}",msg)),
+                }//end matching whether or not the merged sheet was processed successfully
+            }//end if we have xml data to join with
+        }//end if we should output the merged sheet
+        if config.kernel_csv_export_enabled {kernel_csv_data = Some(piped_csv);}
+    }//end if we're doing csv stuff
+    if let Some(input_xml) = input_xml_data.as_ref().filter(|_| config.xml_sieve_cols_enabled) {
+        start_step("Calculating xml sieve data");
+        match process::proc_xml_sieve_data(input_xml, config) {
+            Ok(mut sample_output) => {
+                if config.lot_grouping_enabled {
+                    match process::get_lot_data(input_xml, &config.xml_sample_id_header, config) {
+                        Ok((_, sample_lots)) => {
+                            let lot_output = process::get_lot_avg_output(&sample_output, &sample_lots);
+                            process::merge_lot_rows(&mut sample_output, lot_output, &sample_lots, config);
+                        },
+                        Err(msg) => problems.push(format!("Couldn't group the xml samples into lots, so lot rows will be left out.\n{}", msg)),
+                    }//end matching whether we could find the lot of each sample
+                }//end if we should add lot rows
+                output_sheets.push(("XML_Sieve_Data".to_string(),sample_output));
+            },
+            Err(msg) => problems.push(format!("An Error occured while trying to process XML Sieve Data!\n{}", msg)),
+        }//end matching whether or not xml sieve stuff was processed correctly
+    }//end if we should output xml sieve cols
+    start_step("Adding formula columns and flags");
+    for (sheet_name, sheet_data) in output_sheets.iter_mut() {
+        if let Err(msg) = process::add_formula_columns(sheet_name, sheet_data, &config.formula_columns) {
+            problems.push(format!("Couldn't add the formula columns to the {} sheet, so some might be left out.\n{}", sheet_name, msg));
+        }//end if we couldn't add the formula columns
+    }//end adding formula columns to each sheet
+    if config.is_flags_column_needed() {
+        match flags::get_sample_flags(input_csv_data.as_ref(), &output_sheets, config) {
+            Ok(sample_flags) => flags::add_flags_column(&mut output_sheets, &sample_flags),
+            Err(msg) => problems.push(format!("An Error Occurred while trying to flag samples, so the Flags column will be left out!\n{}", msg)),
+        }//end matching whether we could flag the samples
+    }//end if we should add the flags column
+    if let Some(piped_csv) = kernel_csv_data {
+        start_step("Writing kernel csv files");
+        let kernel_csv_dir = process::get_kernel_csv_dir(output);
+        // the same key the workbook is anonymized with, or nothing if anonymization is off
+        let kernel_csvs_written = get_output_sample_id_key(&output_sheets, input_csv_data, config)
+            .and_then(|key| process::write_sample_kernel_csvs(&piped_csv, config, &kernel_csv_dir, &key));
+        match kernel_csvs_written {
+            Ok(files_written) => log::info!("Wrote {} kernel csv files to {}", files_written, kernel_csv_dir.to_string_lossy()),
+            Err(msg) => problems.push(format!("An Error Occurred while trying to write the kernel csv file for each sample!\n{}",msg)),
+        }//end matching whether or not kernel csv files were written successfully
+    }//end if we should write kernel csv files
+    progress(1.0, "Finished processing");
+    Ok(output_sheets)
+}//end get_output_sheets(input_csv_data, input_xml_data, config, output, problems, progress)
+
+/// Adds lot rows to sample_output by running proc_fn on the lot data
+/// from process::get_lot_data(), if there is any.  
+/// If processing the lot data fails, why is added to problems, and
+/// sample_output is left without lot rows.
+pub fn add_csv_lot_rows(problems: &mut Vec<String>, sample_output: &mut SampleOutput, csv_lot_data: &Option<(Data, Vec<(String, String)>)>, config: &ConfigStore, proc_fn: fn(&Data, &ConfigStore) -> Result<SampleOutput,String>) {
+    if let Some((lot_data, sample_lots)) = csv_lot_data {
+        match proc_fn(lot_data, config) {
+            Ok(lot_output) => process::merge_lot_rows(sample_output, lot_output, sample_lots, config),
+            Err(msg) => problems.push(format!("Couldn't calculate the lot rows, so they will be left out.\n{}", msg)),
+        }//end matching whether we could process the lot data
+    }//end if we have lot data to process
+}//end add_csv_lot_rows()
+
+/// Checks that config has the value of every setting locked by an administrator.  
+/// If not, tells the user which settings were changed and returns false,
+/// so that processing can be refused.
+pub fn ensure_locked_settings_kept(gui: &mut impl Frontend, locked_settings: &LockedSettings, config: &ConfigStore) -> bool {
+    match locked_settings.get_overridden(config) {
+        Ok(overridden) if overridden.len() > 0 => {
+            gui.integrated_dialog_alert(&format!("These settings are locked by an administrator, but have been changed, so nothing was processed:\n{}\nPlease change them back, or ask your administrator to update the admin config.", overridden.join(", ")));
+            false
+        },
+        Ok(_) => true,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't check the settings locked by an administrator, so nothing was processed.\n{}", msg)); false},
+    }//end matching whether any locked settings were changed
+}//end ensure_locked_settings_kept(gui, locked_settings, config)
\ No newline at end of file
@@ -1,13 +1,16 @@
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{cell::RefCell, path::PathBuf, rc::Rc, time::Duration};
 
-use fltk::{app::{self, App, Receiver, Sender}, button::{Button, CheckButton}, dialog::{self, BeepType}, enums::{Align, Color, Event, FrameType}, frame::Frame, group::{Flex, FlexType, Group, Tile}, prelude::{ButtonExt, DisplayExt, GroupExt, WidgetBase, WidgetExt, WindowExt}, text::{TextBuffer, TextDisplay, TextEditor}, window::{self, Window}};
+use serde::{Deserialize, Serialize};
+use fltk::{app::{self, App, Receiver, Sender}, browser::{CheckBrowser, HoldBrowser, MultiBrowser}, button::{Button, CheckButton}, dialog::{self, BeepType}, draw::{self, Offscreen}, enums::{Align, CallbackTrigger, Color, Event, Font, FrameType, Key}, frame::Frame, group::{Flex, FlexType, Group, Tile}, input::{Input, SecretInput}, menu::{Choice, MenuItem}, misc::Progress, printer::Printer, prelude::{BrowserExt, ButtonExt, DisplayExt, GroupExt, InputExt, MenuExt, TableExt, WidgetBase, WidgetExt, WindowExt}, table::{TableContext, TableRow}, text::{TextBuffer, TextDisplay, TextEditor}, widget::Widget, window::{self, Window}};
 
-use usda_c_grain_sum::config_store::ConfigStore;
+use usda_c_grain_sum::{app::{config_store::{self, ClassPercentDenominator, ConfigStore, FilterSet, InvalidSampleIdHandling, LockedSettings, OutputColumnLayout, OutputFormat, PipelineStep, RecipientProfile, SampleIdAnonymization, SheetLayout, StatGroupOutput, WarningAction, WarningCategory, WindowLayout}, flags, ledger::LedgerEntry, metrics, notify, paths, process, schedule::CronSchedule, secrets, taxonomy}, io::xlsx, model::Data, pipeline, stats};
+
+use crate::{help, palette::{self, PaletteAction, PaletteCommand}, view};
 
 /// This enum is specifically intended for message passing
 /// from the GUI to the main function. This is done
 /// with Sender and Receiver objects created in initialize()
-#[derive(Clone,PartialEq,Debug)]
+#[derive(Clone,PartialEq,Debug,Serialize,Deserialize)]
 pub enum InterfaceMessage {
     /// Indicates that the user has selected a CSV Input File.
     /// The filepath selected by the user is returned in the message.
@@ -15,6 +18,10 @@ pub enum InterfaceMessage {
     /// Indicates that the user has selected an XML Input File.
     /// The filepath selected by the user is returned in the message.
     XMLInputFile(PathBuf),
+    /// Indicates that the user has chosen one or more data files with
+    /// the Open Data File(s) button, which should be loaded as csv or xml
+    /// depending on what each file turns out to be.
+    OpenDataFiles(Vec<PathBuf>),
     /// Indicates that the user has selected an Output File.
     /// The filepath selected by the user is returned in the message.
     OutputFile(PathBuf),
@@ -27,6 +34,126 @@ pub enum InterfaceMessage {
     /// configuration preset to be reselected, as if they
     /// had started the program for the first time.
     ConfigReset,
+    /// Indicates that the user wants to change the order
+    /// and visibility of the columns in the output sheets.
+    EditColumnLayout,
+    /// Indicates that the user wants to process some samples from
+    /// the last run again, updating only those rows in the output.
+    ReprocessSamples,
+    /// Indicates that the user wants to check whether an output file
+    /// has been edited since it was written, using the signature in
+    /// its Run Info sheet. The filepath selected by the user is returned.
+    VerifyOutput(PathBuf),
+    /// Indicates that the user wants to look at a summary workbook written
+    /// before, or write it in another format, without processing anything.
+    /// The filepath selected by the user is returned.
+    OpenSummary(PathBuf),
+    /// Indicates that the user wants to merge the per-sample rows of several
+    /// summary workbooks into one, such as for a season roll-up.
+    /// The summaries selected by the user are returned, along with where
+    /// the merged workbook should be written.
+    MergeSummaries(Vec<PathBuf>, PathBuf),
+    /// Indicates that the user wants to process every run in a folder,
+    /// pairing the csv and xml files from each run.
+    /// The folder selected by the user is returned, along with where to
+    /// write one combined workbook, or None for a workbook for each run.
+    BatchProcess(PathBuf, Option<PathBuf>),
+    /// Indicates that the user wants to summarize a csv file too large to
+    /// load by reading it a row at a time, giving the csv file, then the
+    /// output file.
+    StreamLargeCsv(PathBuf, PathBuf),
+    /// Indicates that the user wants to print a table of
+    /// results from the last output.
+    PrintSummary,
+    /// Indicates that the user wants to look at the tables
+    /// of results from the last output in a window.
+    ViewResults,
+    /// Indicates that the user wants to change the order and
+    /// settings of the preprocessing steps run before processing.
+    EditPipeline,
+    /// Indicates that the user wants to edit the aliases which rename
+    /// csv columns named differently by other firmware.
+    EditHeaderAliases,
+    /// Indicates that the user wants to define the report metrics
+    /// and choose which are shown on the Metrics sheet.
+    EditMetrics,
+    /// Indicates that the user wants to define the rules for the Flags
+    /// column and choose whether it's added to the output.
+    EditFlagRules,
+    /// Indicates that the user wants to define the formula columns
+    /// added to the output sheets.
+    EditFormulaColumns,
+    /// Indicates that the user wants to define the groups of columns
+    /// written under a shared header in excel output.
+    EditColumnGroups,
+    /// Indicates that the user wants to choose what happens for each kind
+    /// of problem found in the loaded data, such as a missing column.
+    EditWarningPolicies,
+    /// Indicates that the user wants to define the conventions, such as
+    /// decimal places and sheet names, of the people the output is sent to.
+    EditRecipientProfiles,
+    /// Indicates that the user wants to look over the columns
+    /// of the loaded input data before processing.
+    ExploreData,
+    /// Indicates that the user wants to save the current filters
+    /// as a named filter set.
+    SaveFilterSet,
+    /// Indicates that the user wants to save the current settings as a preset.
+    SavePreset,
+    /// Indicates that the user wants to switch to, save, rename,
+    /// or delete presets.
+    ManagePresets,
+    /// Indicates that the user wants to delete the filter set
+    /// chosen in the Filter Set dropdown.
+    DeleteFilterSet,
+    /// Indicates that the user wants summary text of the last
+    /// output, such as for pasting into an email.
+    ShowRunSummary,
+    /// Indicates that the user wants a zip file holding the last output,
+    /// how it was made, and the log of the run, such as for archiving.
+    ExportRunBundle,
+    /// Indicates that the user wants to browse the runs recorded in the
+    /// run ledger and reopen their outputs.
+    ViewRunLedger,
+    /// Indicates that the user wants to open the log file, which holds
+    /// the errors, warnings, and other messages from each session.
+    ViewLog,
+    /// Indicates that the user wants to collapse the config panel,
+    /// or expand it again.
+    ToggleConfigPanel,
+    /// Indicates that the user wants to keep the results of the last
+    /// output, so they can be compared with the results of another dataset.
+    PinResults,
+    /// Indicates that the user wants to see the pinned results side by
+    /// side with the results of the last output.
+    CompareResults,
+    /// Indicates that the user wants to switch between operator mode,
+    /// where settings are locked, and analyst mode.
+    SwitchUserMode,
+    /// Indicates that the user wants to change whether the program
+    /// beeps or shows desktop notifications.
+    NotificationSettings,
+    /// Indicates that the user wants to change whether merged
+    /// summaries are pushed to a Google Sheet.
+    GoogleSheetsSettings,
+    /// Indicates that the user wants to change which folder is
+    /// processed on a schedule, and when.
+    ScheduleSettings,
+    /// Sent regularly by a timer, so the schedule can be checked to
+    /// see whether scheduled processing should run.
+    ScheduleTick,
+    /// Indicates that the user wants to search the list of all
+    /// actions, such as after pressing Ctrl+K.
+    ShowCommandPalette,
+    /// Indicates that the user wants to switch to the named
+    /// configuration preset, such as from the command palette.
+    SwitchPreset(String),
+    /// Indicates that the user wants to start recording the steps of
+    /// this session, or to stop and save the recording.
+    ToggleRecording,
+    /// Indicates that the user wants to replay a saved recording
+    /// on new input files.
+    ReplayRecording,
     /// Indicates that some other, unidentified message has been
     /// passed. In most cases, this is likely to be a mistake
     /// on the part of the sender.
@@ -69,9 +196,28 @@ pub struct GUI {
     /// Message Receiver, we give a reference to this to main, 
     /// allowing it to receive our messages.
     msg_receiver: Receiver<InterfaceMessage>,
+    /// The group holding the header information.  
+    /// This is stored here in order to restore the window layout.
+    ux_header_group: Group,
     /// The group holding all the configuration controls.  
     /// This is stored here in order to disable during dialog.
     ux_config_group: Group,
+    /// The column holding the configuration controls within ux_config_group.  
+    /// This is stored here in order to hide it when the config panel is collapsed.
+    ux_config_flex: Flex,
+    /// The button shown in place of the configuration controls
+    /// while they're collapsed, which shows them again.
+    ux_config_expand_btn: Button,
+    /// The width the config panel had before it was collapsed,
+    /// or None if it isn't collapsed.
+    ux_config_panel_width: Option<i32>,
+    /// The bar along the bottom of the window summarizing what's
+    /// loaded, the active preset, and how long the last run took.
+    ux_status_bar: Frame,
+    /// The bar at the right of the status bar showing how far along
+    /// work on a worker thread is, such as processing a large file.  
+    /// This is hidden while nothing is running.
+    ux_progress_bar: Progress,
     /// The group holding all the input and output controls.
     /// This is stored here in order to disable during dialog
     ux_io_controls_group: Group,
@@ -118,9 +264,16 @@ pub struct GUI {
     /// that are pulled from sieve data in the xml file. If no
     /// xml file is loaded, then this is meaningless.
     ux_cf_xml_sieve_chck: CheckButton,
+    /// Check button in config section.  
+    /// Tells us whether samples should be grouped into lots,
+    /// with a row for each lot added to the output.
+    ux_cf_lot_group_chck: CheckButton,
     /// The frame holding the text displayed to indicate
     /// which named setting preset is currently active.
     ux_cf_setting_preset_buf: Frame,
+    /// Dropdown in io section.  
+    /// Lets the user choose which saved filter set to use when processing.
+    ux_filter_set_choice: Choice,
     /// Stores the last config_store we've got.  
     /// It is initialized as ConfigStore::default().  
     /// It should be noted that this field is not updated automatically
@@ -129,6 +282,16 @@ pub struct GUI {
     /// in the config_store sent by main will be preserved, even if some
     /// of that information is not directly represented by a widget.
     config_store: Rc<RefCell<ConfigStore>>,
+    /// The settings locked by an administrator, shared with the
+    /// advanced option dialogs so they can show those settings as read-only.
+    locked_settings: Rc<RefCell<LockedSettings>>,
+    /// The widgets in the main window which each change a setting,
+    /// paired with the name of that setting, so they can be
+    /// made read-only if the setting is locked.
+    ux_setting_widgets: Vec<(Widget, &'static str)>,
+    /// The input files opened most recently, newest first, as kept
+    /// in the session by main, for the Recent Files menu.
+    recent_files: Rc<RefCell<Vec<PathBuf>>>,
 }//end struct GUI
 
 #[allow(dead_code)]
@@ -157,6 +320,756 @@ impl GUI {
         return (version_str, date_str);
     }//end default_header_info()
 
+    /// Shows a window with the version, build date, and git hash
+    /// of the program, along with the licenses of bundled crates.
+    pub fn show_about_dialog() {
+        let (version, build_date) = GUI::header_version_day();
+        let mut about_window = Window::default()
+            .with_size(520,400)
+            .with_label("About USDA C-Grain Sum");
+        about_window.make_resizable(true);
+        let mut about_label = Frame::default()
+            .with_pos(10,10)
+            .with_size(500,90)
+            .with_label(&format!("USDA C-Grain Sum\nVersion {}\nBuilt {} from commit {}\nNicholas Sixbury/Dan Brabec\tUSDA-ARS Manhattan,KS", version, build_date, env!("GIT_HASH")))
+            .with_align(Align::Inside.union(Align::Left));
+        about_label.set_label_color(Color::from_rgb(0,0,64));
+        let mut license_buf = TextBuffer::default();
+        license_buf.set_text(include_str!("../licenses/THIRD_PARTY_LICENSES.txt"));
+        let mut license_box = TextDisplay::default()
+            .with_pos(10,110)
+            .with_size(500,280);
+        license_box.set_frame(FrameType::GtkThinDownFrame);
+        license_box.set_color(Color::from_rgb(255,250,240));
+        license_box.set_text_font(fltk::enums::Font::Courier);
+        license_box.set_text_size(12);
+        license_box.set_scrollbar_align(Align::Right);
+        license_box.set_scrollbar_size(10);
+        license_box.set_buffer(license_buf);
+        about_window.resizable(&license_box);
+        about_window.end();
+        about_window.show();
+    }//end show_about_dialog()
+
+    /// Shows a window with the summary text of a run, which the user can
+    /// edit, then copy to paste into an email.
+    pub fn show_summary_dialog(summary_text: &str) {
+        let mut summary_window = Window::default()
+            .with_size(520,260)
+            .with_label("Run Summary");
+        summary_window.make_resizable(true);
+        let mut summary_buf = TextBuffer::default();
+        summary_buf.set_text(summary_text);
+        let mut summary_box = TextEditor::default()
+            .with_pos(10,10)
+            .with_size(500,200);
+        summary_box.set_frame(FrameType::GtkDownFrame);
+        summary_box.set_text_font(fltk::enums::Font::Courier);
+        summary_box.set_text_size(12);
+        summary_box.set_scrollbar_align(Align::Right);
+        summary_box.set_scrollbar_size(10);
+        summary_box.set_buffer(summary_buf.clone());
+        summary_box.set_tooltip("You can edit the summary before copying it.");
+        let mut copy_button = Button::default()
+            .with_pos(10,220)
+            .with_size(130,30)
+            .with_label("Copy to Clipboard");
+        copy_button.set_frame(FrameType::GtkRoundUpFrame);
+        copy_button.clear_visible_focus();
+        copy_button.set_callback(move |btn| {
+            app::copy(&summary_buf.text());
+            btn.set_label("Copied!");
+        });
+        summary_window.resizable(&summary_box);
+        summary_window.end();
+        summary_window.show();
+    }//end show_summary_dialog(summary_text)
+
+    /// Prints a table with the given headers and rows to a printer chosen
+    /// by the user, with title at the top of each page.  
+    /// The first column is repeated on each page, and any columns or rows
+    /// which don't fit on one page are continued on the next page.  
+    /// To get a pdf, the user can choose a pdf printer in the print dialog.  
+    /// Returns the number of pages printed.
+    pub fn print_table(title: &str, headers: &Vec<String>, rows: &Vec<Vec<String>>) -> Result<usize,String> {
+        let mut printer = Printer::default();
+        if printer.begin_job(0).is_err() {return Err(String::from("Printing was cancelled, or no printer could be found."));}
+        if let Err(error) = printer.begin_page() {printer.end_job(); return Err(format!("Couldn't start a page.\n{}", error));}
+        let (page_width, page_height) = printer.printable_rect();
+        let title_height = 24;
+        let row_height = 14;
+        let cell_padding = 6;
+
+        // size each column to fit its widest cell
+        draw::set_font(Font::Helvetica, 9);
+        let mut col_widths = Vec::new();
+        for (col_idx, header) in headers.iter().enumerate() {
+            let mut col_width = draw::width(header);
+            for row in rows.iter() {
+                if let Some(cell) = row.get(col_idx) {col_width = col_width.max(draw::width(cell));}
+            }//end checking the width of each cell in this column
+            col_widths.push(col_width.ceil() as i32 + cell_padding * 2);
+        }//end getting width of each column
+        let rows_per_page = ((page_height - title_height - row_height) / row_height).max(1) as usize;
+        let pages = view::get_print_pages(&col_widths, page_width, rows.len(), rows_per_page);
+
+        for (page_idx, (page_cols, first_row, end_row)) in pages.iter().enumerate() {
+            if page_idx > 0 {
+                if let Err(error) = printer.begin_page() {printer.end_job(); return Err(format!("Couldn't start page {}.\n{}", page_idx + 1, error));}
+            }//end if we need to start another page
+            draw::set_draw_color(Color::Black);
+            draw::set_font(Font::HelveticaBold, 11);
+            draw::draw_text2(&format!("{}    (page {} of {})", title, page_idx + 1, pages.len()), 0, 0, page_width, title_height, Align::Left);
+
+            let mut y = title_height;
+            let mut x = 0;
+            draw::set_font(Font::HelveticaBold, 9);
+            for col_idx in page_cols.iter() {
+                draw::draw_text2(&headers[*col_idx], x + cell_padding, y, col_widths[*col_idx] - cell_padding * 2, row_height, Align::Left);
+                x += col_widths[*col_idx];
+            }//end drawing each header
+            draw::draw_line(0, y + row_height, x, y + row_height);
+            draw::set_font(Font::Helvetica, 9);
+            for row in rows[*first_row..*end_row].iter() {
+                y += row_height;
+                x = 0;
+                for col_idx in page_cols.iter() {
+                    let align = if *col_idx == 0 {Align::Left} else {Align::Right};
+                    draw::draw_text2(row.get(*col_idx).map(|cell| cell.as_str()).unwrap_or(""), x + cell_padding, y, col_widths[*col_idx] - cell_padding * 2, row_height, align);
+                    x += col_widths[*col_idx];
+                }//end drawing each cell in this row
+            }//end drawing each row on this page
+            if let Err(error) = printer.end_page() {printer.end_job(); return Err(format!("Couldn't finish page {}.\n{}", page_idx + 1, error));}
+        }//end drawing each page
+        printer.end_job();
+        Ok(pages.len())
+    }//end print_table(title, headers, rows)
+
+    /// Shows a window with a table of results for each of sheets, which
+    /// are given as (name of sheet, headers, text of each row).  
+    /// The user can choose which sheet to look at, click a header to
+    /// sort by that column, and type in the filter box to only show
+    /// rows with a cell containing that text.
+    pub fn show_results_viewer(sheets: Vec<(String, Vec<String>, Vec<Vec<String>>)>) {
+        if sheets.len() == 0 {return;}
+        let sheets = Rc::from(sheets);
+        // (index of shown sheet, rows currently shown, (column sorted by, whether ascending))
+        let view_state: Rc<RefCell<(usize, Vec<Vec<String>>, Option<(usize, bool)>)>> = Rc::from(RefCell::from((0, Vec::new(), None)));
+
+        let mut viewer_window = Window::default()
+            .with_size(800,480)
+            .with_label("Results Viewer");
+        viewer_window.make_resizable(true);
+        let mut sheet_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(60,10)
+            .with_label("Table:");
+        for (sheet_name, _, _) in sheets.iter() {sheet_choice.add_choice(sheet_name);}
+        sheet_choice.set_value(0);
+        let mut filter_input = Input::default()
+            .with_size(200,25)
+            .with_pos(320,10)
+            .with_label("Filter:");
+        filter_input.set_tooltip("Only rows with a cell containing this text will be shown.");
+        filter_input.set_trigger(CallbackTrigger::Changed);
+        let count_label = Frame::default()
+            .with_size(170,25)
+            .with_pos(530,10)
+            .with_align(Align::Inside | Align::Left);
+        let mut chart_button = Button::default()
+            .with_size(80,25)
+            .with_pos(710,10)
+            .with_label("Chart...");
+        chart_button.set_tooltip("Show a bar chart of one column across the samples.");
+        chart_button.set_frame(FrameType::GtkRoundUpFrame);
+        chart_button.clear_visible_focus();
+        let mut table = TableRow::default()
+            .with_size(780,425)
+            .with_pos(10,45);
+        table.set_tooltip("Click a column header to sort by that column.\nClick it again to reverse the order.");
+        table.set_row_header(false);
+        table.set_col_header(true);
+        table.set_col_resize(true);
+        table.set_col_width_all(110);
+        table.end();
+        viewer_window.resizable(&table);
+        viewer_window.end();
+
+        table.draw_cell({
+            let sheets = (&sheets).clone();
+            let view_state = (&view_state).clone();
+            move |table, context, row, col, x, y, w, h| {
+                let view_state = view_state.borrow();
+                match context {
+                    TableContext::StartPage => draw::set_font(Font::Helvetica, 12),
+                    TableContext::ColHeader => {
+                        let mut header = sheets[view_state.0].1.get(col as usize).cloned().unwrap_or_default();
+                        if let Some((sort_col, ascending)) = view_state.2 {
+                            if sort_col == col as usize {header.push_str(if ascending {" ^"} else {" v"});}
+                        }//end if we should mark this column as sorted
+                        draw::push_clip(x, y, w, h);
+                        draw::draw_box(FrameType::ThinUpBox, x, y, w, h, Color::FrameDefault);
+                        draw::set_draw_color(Color::Black);
+                        draw::set_font(Font::HelveticaBold, 12);
+                        draw::draw_text2(&header, x + 4, y, w - 8, h, Align::Left);
+                        draw::set_font(Font::Helvetica, 12);
+                        draw::pop_clip();
+                    },
+                    TableContext::Cell => {
+                        let cell = view_state.1.get(row as usize).and_then(|cells| cells.get(col as usize)).cloned().unwrap_or_default();
+                        draw::push_clip(x, y, w, h);
+                        draw::draw_rect_fill(x, y, w, h, if table.row_selected(row) {Color::from_rgb(210,225,245)} else {Color::White});
+                        draw::set_draw_color(Color::Black);
+                        draw::draw_text2(&cell, x + 4, y, w - 8, h, if col == 0 {Align::Left} else {Align::Right});
+                        draw::set_draw_color(Color::Light2);
+                        draw::draw_rect(x, y, w, h);
+                        draw::pop_clip();
+                    },
+                    _ => {},
+                }//end matching what part of the table to draw
+            }//end moving for closure
+        });
+
+        // helper closure for updating the shown rows after the sheet, filter, or sort changes
+        let mut refresh = {
+            let sheets = (&sheets).clone();
+            let view_state = (&view_state).clone();
+            let mut table = table.clone();
+            let filter_input = filter_input.clone();
+            let mut count_label = count_label.clone();
+            move || {
+                let mut view_state_ref = view_state.borrow_mut();
+                let (_, headers, rows) = &sheets[view_state_ref.0];
+                let mut shown_rows = view::filter_text_rows(rows, &filter_input.value());
+                if let Some((sort_col, ascending)) = view_state_ref.2 {view::sort_text_rows(&mut shown_rows, sort_col, ascending);}
+                count_label.set_label(&format!("Showing {} of {} rows", shown_rows.len(), rows.len()));
+                let row_count = shown_rows.len() as i32;
+                view_state_ref.1 = shown_rows;
+                drop(view_state_ref);
+                table.set_cols(headers.len() as i32);
+                table.set_rows(row_count);
+                table.redraw();
+            }//end closure
+        };
+        refresh();
+
+        sheet_choice.set_callback({
+            let view_state = (&view_state).clone();
+            let mut refresh = refresh.clone();
+            move |choice| {
+                if choice.value() < 0 {return;}
+                {
+                    let mut view_state = view_state.borrow_mut();
+                    view_state.0 = choice.value() as usize;
+                    view_state.2 = None;
+                }
+                refresh();
+            }//end moving for closure
+        });
+        filter_input.set_callback({
+            let mut refresh = refresh.clone();
+            move |_| refresh()
+        });
+        chart_button.set_callback({
+            let sheets = (&sheets).clone();
+            let view_state = (&view_state).clone();
+            move |_| GUI::show_chart_window((*sheets).clone(), view_state.borrow().0)
+        });
+        table.set_callback({
+            let view_state = (&view_state).clone();
+            move |table| {
+                if table.callback_context() != TableContext::ColHeader {return;}
+                let clicked_col = table.callback_col() as usize;
+                {
+                    let mut view_state = view_state.borrow_mut();
+                    view_state.2 = match view_state.2 {
+                        Some((sort_col, ascending)) if sort_col == clicked_col => Some((clicked_col, !ascending)),
+                        _ => Some((clicked_col, true)),
+                    };
+                }
+                refresh();
+            }//end moving for closure
+        });
+
+        viewer_window.show();
+    }//end show_results_viewer(sheets)
+
+    /// Shows a window with the results of two runs side by side, such as to
+    /// compare two datasets processed in this session. Each run is given as
+    /// (label, sheets), with sheets given as (name of sheet, headers, text of each row).  
+    /// The user can choose which sheet to compare, and filter the rows of both runs at once.
+    pub fn show_comparison_viewer(left_run: (String, Vec<(String, Vec<String>, Vec<Vec<String>>)>), right_run: (String, Vec<(String, Vec<String>, Vec<Vec<String>>)>)) {
+        // the sheets in both runs, since there's nothing to compare otherwise
+        let sheet_names: Vec<String> = left_run.1.iter()
+            .map(|(sheet_name, _, _)| sheet_name.clone())
+            .filter(|sheet_name| right_run.1.iter().any(|(other_name, _, _)| other_name == sheet_name))
+            .collect();
+        if sheet_names.len() == 0 {
+            GUI::show_alert("The two runs don't have any output sheets in common, so there's nothing to compare.");
+            return;
+        }//end if there's nothing to compare
+
+        let mut compare_window = Window::default()
+            .with_size(1000,500)
+            .with_label("Compare Results");
+        compare_window.make_resizable(true);
+        let mut sheet_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(60,10)
+            .with_label("Table:");
+        for sheet_name in sheet_names.iter() {sheet_choice.add_choice(&sheet_name.replace("/", "\\/"));}
+        sheet_choice.set_value(0);
+        let mut filter_input = Input::default()
+            .with_size(200,25)
+            .with_pos(320,10)
+            .with_label("Filter:");
+        filter_input.set_tooltip("Only rows with a cell containing this text will be shown, in both tables.");
+        filter_input.set_trigger(CallbackTrigger::Changed);
+        let mut tile = Tile::default()
+            .with_size(980,440)
+            .with_pos(10,45);
+        let mut tables = Vec::new();
+        for (idx, (run_label, _)) in [&left_run, &right_run].iter().enumerate() {
+            let mut run_group = Group::default()
+                .with_size(490,440)
+                .with_pos(10 + idx as i32 * 490,45);
+            let mut run_label_frm = Frame::default()
+                .with_size(480,20)
+                .with_pos(run_group.x() + 5,run_group.y())
+                .with_label(run_label)
+                .with_align(Align::Inside | Align::Left);
+            run_label_frm.set_label_font(Font::HelveticaBold);
+            let mut table = TableRow::default()
+                .with_size(480,415)
+                .with_pos(run_group.x() + 5,run_group.y() + 25);
+            table.set_row_header(false);
+            table.set_col_header(true);
+            table.set_col_resize(true);
+            table.set_col_width_all(100);
+            table.end();
+            run_group.resizable(&table);
+            run_group.end();
+            tables.push(table);
+        }//end creating a table for each run
+        tile.end();
+        compare_window.resizable(&tile);
+        compare_window.end();
+
+        // (headers, rows currently shown) for each table
+        let shown_tables: Vec<Rc<RefCell<(Vec<String>, Vec<Vec<String>>)>>> = vec![Rc::from(RefCell::from((Vec::new(), Vec::new()))), Rc::from(RefCell::from((Vec::new(), Vec::new())))];
+        for (table, shown_table) in tables.iter_mut().zip(shown_tables.iter()) {
+            table.draw_cell({
+                let shown_table = shown_table.clone();
+                move |_, context, row, col, x, y, w, h| {
+                    let shown_table = shown_table.borrow();
+                    match context {
+                        TableContext::StartPage => draw::set_font(Font::Helvetica, 12),
+                        TableContext::ColHeader => {
+                            let header = shown_table.0.get(col as usize).cloned().unwrap_or_default();
+                            draw::push_clip(x, y, w, h);
+                            draw::draw_box(FrameType::ThinUpBox, x, y, w, h, Color::FrameDefault);
+                            draw::set_draw_color(Color::Black);
+                            draw::set_font(Font::HelveticaBold, 12);
+                            draw::draw_text2(&header, x + 4, y, w - 8, h, Align::Left);
+                            draw::set_font(Font::Helvetica, 12);
+                            draw::pop_clip();
+                        },
+                        TableContext::Cell => {
+                            let cell = shown_table.1.get(row as usize).and_then(|cells| cells.get(col as usize)).cloned().unwrap_or_default();
+                            draw::push_clip(x, y, w, h);
+                            draw::draw_rect_fill(x, y, w, h, Color::White);
+                            draw::set_draw_color(Color::Black);
+                            draw::draw_text2(&cell, x + 4, y, w - 8, h, if col == 0 {Align::Left} else {Align::Right});
+                            draw::set_draw_color(Color::Light2);
+                            draw::draw_rect(x, y, w, h);
+                            draw::pop_clip();
+                        },
+                        _ => {},
+                    }//end matching what part of the table to draw
+                }//end moving for closure
+            });
+        }//end setting how each table is drawn
+
+        // helper closure for updating both tables after the sheet or filter changes
+        let mut refresh = {
+            let runs: Rc<[Vec<(String, Vec<String>, Vec<Vec<String>>)>]> = Rc::from(vec![left_run.1, right_run.1]);
+            let sheet_names = sheet_names.clone();
+            let mut tables = tables.clone();
+            let sheet_choice = sheet_choice.clone();
+            let filter_input = filter_input.clone();
+            move || {
+                let sheet_name = match sheet_names.get(sheet_choice.value().max(0) as usize) {
+                    Some(sheet_name) => sheet_name,
+                    None => return,
+                };
+                for ((run_sheets, table), shown_table) in runs.iter().zip(tables.iter_mut()).zip(shown_tables.iter()) {
+                    if let Some((_, headers, rows)) = run_sheets.iter().find(|(other_name, _, _)| other_name == sheet_name) {
+                        let shown_rows = view::filter_text_rows(rows, &filter_input.value());
+                        table.set_cols(headers.len() as i32);
+                        table.set_rows(shown_rows.len() as i32);
+                        *shown_table.borrow_mut() = (headers.clone(), shown_rows);
+                        table.redraw();
+                    }//end if this run has the chosen sheet
+                }//end updating each table
+            }//end closure
+        };
+        refresh();
+
+        sheet_choice.set_callback({
+            let mut refresh = refresh.clone();
+            move |_| refresh()
+        });
+        filter_input.set_callback(move |_| refresh());
+
+        compare_window.show();
+    }//end show_comparison_viewer(left_run, right_run)
+
+    /// Shows a window with a bar chart of one column across the rows
+    /// of one of sheets, which are given as (name of sheet, headers,
+    /// text of each row). The user can choose the sheet and column,
+    /// starting with the sheet at initial_sheet.
+    pub fn show_chart_window(sheets: Vec<(String, Vec<String>, Vec<Vec<String>>)>, initial_sheet: usize) {
+        if sheets.len() == 0 {return;}
+        let initial_sheet = initial_sheet.min(sheets.len() - 1);
+        let sheets = Rc::from(sheets);
+        // (index of sheet, index of column)
+        let chart_state = Rc::from(RefCell::from((initial_sheet, 1)));
+
+        let mut chart_window = Window::default()
+            .with_size(720,440)
+            .with_label("Results Chart");
+        chart_window.make_resizable(true);
+        let mut sheet_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(60,10)
+            .with_label("Table:");
+        for (sheet_name, _, _) in sheets.iter() {sheet_choice.add_choice(sheet_name);}
+        sheet_choice.set_value(initial_sheet as i32);
+        let mut column_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(330,10)
+            .with_label("Column:");
+        let mut chart_frame = Frame::default()
+            .with_size(700,385)
+            .with_pos(10,45);
+        chart_window.resizable(&chart_frame);
+        chart_window.end();
+
+        // the first column holds the sample ids, so it can't be charted
+        let fill_columns = {
+            let sheets = (&sheets).clone();
+            move |column_choice: &mut Choice, sheet_idx: usize| {
+                column_choice.clear();
+                for header in sheets[sheet_idx].1.iter().skip(1) {column_choice.add_choice(&header.replace("/", "\\/"));}
+                column_choice.set_value(0);
+            }//end closure
+        };
+        fill_columns(&mut column_choice, initial_sheet);
+
+        chart_frame.draw({
+            let sheets = (&sheets).clone();
+            let chart_state = (&chart_state).clone();
+            move |frame| {
+                let (sheet_idx, col_idx) = *chart_state.borrow();
+                let (sheet_name, headers, rows) = &sheets[sheet_idx];
+                let title = format!("{} - {}", sheet_name, headers.get(col_idx).cloned().unwrap_or_default());
+                let values = view::get_chart_values(rows, col_idx);
+                // draw offscreen first, so the chart doesn't flicker while drawing
+                match Offscreen::new(frame.w(), frame.h()) {
+                    Some(mut offscreen) => {
+                        offscreen.begin();
+                        GUI::draw_bar_chart(0, 0, frame.w(), frame.h(), &title, &values);
+                        offscreen.end();
+                        offscreen.copy(frame.x(), frame.y(), frame.w(), frame.h(), 0, 0);
+                    },
+                    None => GUI::draw_bar_chart(frame.x(), frame.y(), frame.w(), frame.h(), &title, &values),
+                }//end matching whether we could draw offscreen
+            }//end moving for closure
+        });
+        sheet_choice.set_callback({
+            let chart_state = (&chart_state).clone();
+            let mut column_choice = column_choice.clone();
+            let mut chart_frame = chart_frame.clone();
+            move |choice| {
+                if choice.value() < 0 {return;}
+                fill_columns(&mut column_choice, choice.value() as usize);
+                *chart_state.borrow_mut() = (choice.value() as usize, 1);
+                chart_frame.redraw();
+            }//end moving for closure
+        });
+        column_choice.set_callback({
+            let chart_state = (&chart_state).clone();
+            let mut chart_frame = chart_frame.clone();
+            move |choice| {
+                if choice.value() < 0 {return;}
+                chart_state.borrow_mut().1 = choice.value() as usize + 1;
+                chart_frame.redraw();
+            }//end moving for closure
+        });
+
+        chart_window.show();
+    }//end show_chart_window(sheets, initial_sheet)
+
+    /// Draws a bar chart of values in the box at x, y with size w, h,
+    /// with a bar for each value, labeled with the name it's paired with.
+    fn draw_bar_chart(x: i32, y: i32, w: i32, h: i32, title: &str, values: &Vec<(String, f64)>) {
+        let (left_margin, right_margin, top_margin, bottom_margin) = (60, 15, 30, 90);
+        let plot_w = (w - left_margin - right_margin).max(1);
+        let plot_h = (h - top_margin - bottom_margin).max(1);
+        let plot_x = x + left_margin;
+        let plot_y = y + top_margin;
+        draw::draw_rect_fill(x, y, w, h, Color::White);
+        draw::set_draw_color(Color::Black);
+        draw::set_font(Font::HelveticaBold, 13);
+        draw::draw_text2(title, x, y + 5, w, 20, Align::Center);
+        if values.len() == 0 {
+            draw::set_font(Font::Helvetica, 12);
+            draw::draw_text2("There are no numbers in this column to chart.", x, y, w, h, Align::Center);
+            return;
+        }//end if there's nothing to chart
+
+        // figure out the range of the value axis, including 0
+        let max_value = values.iter().map(|(_, value)| *value).fold(0.0, f64::max);
+        let min_value = values.iter().map(|(_, value)| *value).fold(0.0, f64::min);
+        let axis_max = if max_value > 0.0 {view::get_nice_axis_max(max_value)} else {0.0};
+        let axis_min = if min_value < 0.0 {-view::get_nice_axis_max(-min_value)} else {0.0};
+        let axis_range = if axis_max - axis_min > 0.0 {axis_max - axis_min} else {1.0};
+        let value_to_y = |value: f64| plot_y + plot_h - ((value - axis_min) / axis_range * plot_h as f64).round() as i32;
+
+        // draw the value axis with gridlines
+        draw::set_font(Font::Helvetica, 10);
+        let tick_count = 5;
+        for tick in 0..=tick_count {
+            let tick_value = axis_min + axis_range * tick as f64 / tick_count as f64;
+            let tick_y = value_to_y(tick_value);
+            draw::set_draw_color(Color::Light2);
+            draw::draw_line(plot_x, tick_y, plot_x + plot_w, tick_y);
+            draw::set_draw_color(Color::Black);
+            draw::draw_text2(&format!("{}", (tick_value * 1000.0).round() / 1000.0), x, tick_y - 7, left_margin - 6, 14, Align::Right);
+        }//end drawing each tick on the value axis
+        draw::draw_line(plot_x, plot_y, plot_x, plot_y + plot_h);
+        let zero_y = value_to_y(0.0);
+        draw::draw_line(plot_x, zero_y, plot_x + plot_w, zero_y);
+
+        // draw a bar for each value, with its label below the axis
+        let slot_w = plot_w as f64 / values.len() as f64;
+        let bar_w = ((slot_w * 0.7).round() as i32).max(1);
+        for (idx, (label, value)) in values.iter().enumerate() {
+            let bar_x = plot_x + (slot_w * idx as f64 + (slot_w - bar_w as f64) / 2.0).round() as i32;
+            let value_y = value_to_y(*value);
+            let (bar_top, bar_h) = if value_y < zero_y {(value_y, zero_y - value_y)} else {(zero_y, value_y - zero_y)};
+            draw::draw_rect_fill(bar_x, bar_top, bar_w, bar_h.max(1), Color::from_rgb(70,110,170));
+            // only label bars if there's room for the text
+            if slot_w >= 12.0 {
+                draw::set_draw_color(Color::Black);
+                let label_w = draw::width(label);
+                draw::draw_text_angled(60, label, bar_x + bar_w / 2 - (label_w * 0.5) as i32, plot_y + plot_h + 10 + (label_w * 0.87) as i32);
+            }//end if there's room for a label
+        }//end drawing each bar
+    }//end draw_bar_chart(x, y, w, h, title, values)
+
+    /// Shows a window for looking over the columns of loaded data before
+    /// processing, with datasets given as (name of input, data, headers
+    /// of the columns to search, such as the sample id and class).  
+    /// The user picks a column from the list, and the window shows the
+    /// min, max, mean, and standard deviation of that column, along
+    /// with a histogram of its values. Below that is a preview table of
+    /// the loaded rows, which can be searched by sample id or class.
+    pub fn show_data_explorer(datasets: Vec<(String, Data, Vec<String>)>) {
+        if datasets.len() == 0 {return;}
+        // (headers, text of each row, index of each column to search) for each dataset
+        let previews: Rc<Vec<(Vec<String>, Vec<Vec<String>>, Vec<usize>)>> = Rc::from(datasets.iter().map(|(_, data, search_headers)| {
+            let (headers, rows) = process::get_data_text_table(data);
+            let search_cols = search_headers.iter().filter_map(|header| data.get_header_index(header)).collect();
+            (headers, rows, search_cols)
+        }).collect::<Vec<_>>());
+        let datasets = Rc::from(datasets);
+        // rows of the preview matching the search
+        let preview_rows: Rc<RefCell<Vec<Vec<String>>>> = Rc::from(RefCell::from(Vec::new()));
+        // (index of dataset, index of selected column)
+        let explorer_state: Rc<RefCell<(usize, Option<usize>)>> = Rc::from(RefCell::from((0, None)));
+        let histogram_bins = 12;
+
+        let mut explorer_window = Window::default()
+            .with_size(760,660)
+            .with_label("Loaded Data Explorer");
+        explorer_window.make_resizable(true);
+        let mut data_choice = Choice::default()
+            .with_size(150,25)
+            .with_pos(60,10)
+            .with_label("Input:");
+        for (data_name, _, _) in datasets.iter() {data_choice.add_choice(data_name);}
+        data_choice.set_value(0);
+        let mut column_browser = HoldBrowser::default()
+            .with_size(200,385)
+            .with_pos(10,45);
+        column_browser.set_tooltip("Click a column to see its statistics.");
+        let mut stats_buf = TextBuffer::default();
+        stats_buf.set_text("Choose a column on the left to see its statistics.");
+        let mut stats_display = TextDisplay::default()
+            .with_size(530,110)
+            .with_pos(220,45);
+        stats_display.set_frame(FrameType::GtkDownFrame);
+        stats_display.set_buffer(stats_buf.clone());
+        let mut histogram_frame = Frame::default()
+            .with_size(530,275)
+            .with_pos(220,155);
+        let mut search_input = Input::default()
+            .with_size(200,25)
+            .with_pos(70,440)
+            .with_label("Search:");
+        search_input.set_tooltip("Only rows with a sample id or class containing this text will be shown.");
+        search_input.set_trigger(CallbackTrigger::Changed);
+        let count_label = Frame::default()
+            .with_size(300,25)
+            .with_pos(280,440)
+            .with_align(Align::Inside | Align::Left);
+        let mut preview_table = TableRow::default()
+            .with_size(740,185)
+            .with_pos(10,470);
+        preview_table.set_row_header(false);
+        preview_table.set_col_header(true);
+        preview_table.set_col_resize(true);
+        preview_table.set_col_width_all(110);
+        preview_table.end();
+        explorer_window.resizable(&preview_table);
+        explorer_window.end();
+
+        preview_table.draw_cell({
+            let previews = (&previews).clone();
+            let explorer_state = (&explorer_state).clone();
+            let preview_rows = (&preview_rows).clone();
+            move |_, context, row, col, x, y, w, h| {
+                match context {
+                    TableContext::StartPage => draw::set_font(Font::Helvetica, 12),
+                    TableContext::ColHeader => {
+                        let header = previews[explorer_state.borrow().0].0.get(col as usize).cloned().unwrap_or_default();
+                        draw::push_clip(x, y, w, h);
+                        draw::draw_box(FrameType::ThinUpBox, x, y, w, h, Color::FrameDefault);
+                        draw::set_draw_color(Color::Black);
+                        draw::set_font(Font::HelveticaBold, 12);
+                        draw::draw_text2(&header, x + 4, y, w - 8, h, Align::Left);
+                        draw::set_font(Font::Helvetica, 12);
+                        draw::pop_clip();
+                    },
+                    TableContext::Cell => {
+                        let cell = preview_rows.borrow().get(row as usize).and_then(|cells| cells.get(col as usize)).cloned().unwrap_or_default();
+                        draw::push_clip(x, y, w, h);
+                        draw::draw_rect_fill(x, y, w, h, Color::White);
+                        draw::set_draw_color(Color::Black);
+                        draw::draw_text2(&cell, x + 4, y, w - 8, h, Align::Left);
+                        draw::set_draw_color(Color::Light2);
+                        draw::draw_rect(x, y, w, h);
+                        draw::pop_clip();
+                    },
+                    _ => {},
+                }//end matching what part of the table to draw
+            }//end moving for closure
+        });
+
+        // helper closure for updating the preview after the dataset or search changes
+        let mut refresh_preview = {
+            let previews = (&previews).clone();
+            let explorer_state = (&explorer_state).clone();
+            let preview_rows = (&preview_rows).clone();
+            let mut preview_table = preview_table.clone();
+            let search_input = search_input.clone();
+            let mut count_label = count_label.clone();
+            move || {
+                let (headers, rows, search_cols) = &previews[explorer_state.borrow().0];
+                let shown_rows = view::filter_text_rows_in_columns(rows, search_cols, &search_input.value());
+                count_label.set_label(&format!("Showing {} of {} rows", shown_rows.len(), rows.len()));
+                let row_count = shown_rows.len() as i32;
+                *preview_rows.borrow_mut() = shown_rows;
+                preview_table.set_cols(headers.len() as i32);
+                preview_table.set_rows(row_count);
+                preview_table.redraw();
+            }//end closure
+        };
+        refresh_preview();
+        search_input.set_callback({
+            let mut refresh_preview = refresh_preview.clone();
+            move |_| refresh_preview()
+        });
+
+        let fill_columns = {
+            let datasets = (&datasets).clone();
+            move |column_browser: &mut HoldBrowser, data_idx: usize| {
+                column_browser.clear();
+                for header in datasets[data_idx].1.get_headers_ref().iter() {column_browser.add(&header.replace("@", "@@"));}
+            }//end closure
+        };
+        fill_columns(&mut column_browser, 0);
+
+        histogram_frame.draw({
+            let datasets = (&datasets).clone();
+            let explorer_state = (&explorer_state).clone();
+            move |frame| {
+                let (data_idx, col_idx) = *explorer_state.borrow();
+                let (title, values) = match col_idx {
+                    Some(col_idx) => {
+                        let data = &datasets[data_idx].1;
+                        let (column_values, _, _) = stats::get_column_values(data, col_idx);
+                        let bins = stats::get_histogram(&column_values, histogram_bins);
+                        let values = bins.iter().map(|(start, end, count)| (format!("{}-{}", (start * 100.0).round() / 100.0, (end * 100.0).round() / 100.0), *count as f64)).collect();
+                        (format!("Histogram of {}", data.get_header_from_index(col_idx).cloned().unwrap_or_default()), values)
+                    },
+                    None => (String::from("Histogram"), Vec::new()),
+                };
+                // draw offscreen first, so the histogram doesn't flicker while drawing
+                match Offscreen::new(frame.w(), frame.h()) {
+                    Some(mut offscreen) => {
+                        offscreen.begin();
+                        GUI::draw_bar_chart(0, 0, frame.w(), frame.h(), &title, &values);
+                        offscreen.end();
+                        offscreen.copy(frame.x(), frame.y(), frame.w(), frame.h(), 0, 0);
+                    },
+                    None => GUI::draw_bar_chart(frame.x(), frame.y(), frame.w(), frame.h(), &title, &values),
+                }//end matching whether we could draw offscreen
+            }//end moving for closure
+        });
+        data_choice.set_callback({
+            let explorer_state = (&explorer_state).clone();
+            let mut refresh_preview = refresh_preview.clone();
+            let mut column_browser = column_browser.clone();
+            let mut stats_buf = stats_buf.clone();
+            let mut histogram_frame = histogram_frame.clone();
+            move |choice| {
+                if choice.value() < 0 {return;}
+                fill_columns(&mut column_browser, choice.value() as usize);
+                *explorer_state.borrow_mut() = (choice.value() as usize, None);
+                stats_buf.set_text("Choose a column on the left to see its statistics.");
+                histogram_frame.redraw();
+                refresh_preview();
+            }//end moving for closure
+        });
+        column_browser.set_callback({
+            let datasets = (&datasets).clone();
+            let explorer_state = (&explorer_state).clone();
+            let mut stats_buf = stats_buf.clone();
+            let mut histogram_frame = histogram_frame.clone();
+            move |browser| {
+                if browser.value() < 1 {return;}
+                let col_idx = browser.value() as usize - 1;
+                let data_idx = explorer_state.borrow().0;
+                let data = &datasets[data_idx].1;
+                let header = data.get_header_from_index(col_idx).cloned().unwrap_or_default();
+                match stats::get_column_summary(data, col_idx) {
+                    Ok(summary) => stats_buf.set_text(&format!("Column: {}\nNumbers: {}    Non-Numeric: {}    Missing: {}\nMin: {}    Max: {}\nMean: {:.4}    Standard Deviation: {:.4}",
+                        header, summary.numeric_count, summary.non_numeric_count, summary.missing_count, summary.min, summary.max, summary.mean, summary.std_dev)),
+                    Err(_) => {
+                        let (_, non_numeric_count, missing_count) = stats::get_column_values(data, col_idx);
+                        stats_buf.set_text(&format!("Column: {}\nThis column doesn't have any numbers.\nNon-Numeric: {}    Missing: {}", header, non_numeric_count, missing_count));
+                    },
+                }//end matching whether we could summarize the column
+                explorer_state.borrow_mut().1 = Some(col_idx);
+                histogram_frame.redraw();
+            }//end moving for closure
+        });
+
+        explorer_window.show();
+    }//end show_data_explorer(datasets)
+
     /// Closes the application.
     pub fn quit() {
         app::App::default().quit();
@@ -233,7 +1146,13 @@ impl GUI {
     /// Creates a modal error message that is integrated into the
     /// main window of the application.
     pub fn integrated_dialog_alert(&mut self, txt: &str) {
-        dialog::beep(BeepType::Error);
+        let should_beep = {
+            let config = self.config_store.borrow();
+            config.sounds_enabled && config.error_beep_enabled
+        };
+        if should_beep {dialog::beep(BeepType::Error);}
+        log::error!("{}", txt);
+        self.debug_log.push(txt.to_string());
         self.integrated_dialog_message(txt);
     }//end integrated_dialog_alert()
 
@@ -332,6 +1251,7 @@ impl GUI {
         config_clone.csv_stat_columns_columns = stat_columns;
         config_clone.csv_class_percent_enabled = self.ux_cf_class_perc_chck.is_checked();
         config_clone.xml_sieve_cols_enabled = self.ux_cf_xml_sieve_chck.is_checked();
+        config_clone.lot_grouping_enabled = self.ux_cf_lot_group_chck.is_checked();
         
         return config_clone;
     }//end get_config_store
@@ -339,6 +1259,12 @@ impl GUI {
     /// Updates the current configuration widgets in the interface to match
     /// the given ConfigStore.
     pub fn set_config_store(&mut self, config: &ConfigStore) {
+        // settings locked by an administrator keep their locked values
+        let locked_config = match self.locked_settings.borrow().apply(config) {
+            Ok(locked_config) => locked_config,
+            Err(msg) => {log::warn!("Couldn't apply locked settings: {}", msg); config.clone()},
+        };
+        let config = &locked_config;
         let config_ref = &self.config_store;
         let config_ref = config_ref.clone();
         let mut config_ref = config_ref.as_ref().borrow_mut();
@@ -351,9 +1277,19 @@ impl GUI {
         self.ux_cf_stat_cols_buf.set_text(&config.csv_stat_columns_columns.join("\n"));
         self.ux_cf_class_perc_chck.set_checked(config.csv_class_percent_enabled);
         self.ux_cf_xml_sieve_chck.set_checked(config.xml_sieve_cols_enabled);
+        self.ux_cf_lot_group_chck.set_checked(config.lot_grouping_enabled);
+        self.update_filter_set_choice(config);
+        // operator mode hides every setting, leaving only file selection and processing
+        if config.operator_mode_enabled {
+            self.ux_config_group.hide();
+            self.ux_filter_set_choice.hide();
+        } else {
+            self.ux_config_group.show();
+            self.ux_filter_set_choice.show();
+        }//end else we're in analyst mode
 
         match config.personalized_config_name.as_str() {
-            "Sorghum" | "Wheat"=> {
+            preset_name if !preset_name.is_empty() => {
                 // let new_header = GUI::default_header_info();
                 // self.ux_header_buf.set_text(&new_header);
                 self.ux_cf_setting_preset_buf.set_label(&format!("Configuration for {}",&config.personalized_config_name));
@@ -369,205 +1305,3139 @@ impl GUI {
         self.ux_config_group.redraw();
     }//end set_config_store(self, config)
 
-    /// Gives a small visual indication that the program is doing something in the background.
-    pub fn start_wait(&mut self) {
-        self.ux_main_window.set_cursor(fltk::enums::Cursor::Wait);
-    }//end start_wait(self)
+    /// Sets the settings locked by an administrator, making the widgets for
+    /// those settings read-only and setting them to their locked values.
+    pub fn set_locked_settings(&mut self, locked_settings: &LockedSettings) {
+        *self.locked_settings.borrow_mut() = locked_settings.clone();
+        GUI::lock_setting_widgets(locked_settings, &mut self.ux_setting_widgets);
+        let config = self.get_config_store();
+        self.set_config_store(&config);
+    }//end set_locked_settings(self, locked_settings)
 
-    /// Clears the visual indication from start_wait()
-    pub fn end_wait(&mut self) {
-        self.ux_main_window.set_cursor(fltk::enums::Cursor::Default);
-    }//end end_wait(self)
+    /// Deactivates each widget whose setting is locked in locked_settings,
+    /// so the user can see but not change it.  
+    /// Each widget is paired with the name of the setting it changes.
+    fn lock_setting_widgets(locked_settings: &LockedSettings, setting_widgets: &mut Vec<(Widget, &str)>) {
+        for (widget, setting_name) in setting_widgets.iter_mut() {
+            if locked_settings.is_locked(setting_name) {
+                widget.deactivate();
+                widget.set_tooltip("This setting is locked by an administrator.");
+            }//end if this widget's setting is locked
+        }//end checking each widget
+    }//end lock_setting_widgets(locked_settings, setting_widgets)
 
-    /// Sets up all the properties and appearances of
-    /// various widgets and UI settings.
-    pub fn initialize() -> GUI {
-        let c_grain_app = app::App::default();
-        let mut main_window = window::Window::default().with_size(700, 435).with_label("USDA C-Grain Summarizer");
-        main_window.end();
+    /// Shows a dialog window which allows the user to reorder columns in
+    /// each output sheet (by dragging or using the up/down buttons) and to
+    /// hide columns they don't want written. The user can also choose to
+    /// transpose each sheet, so that samples are written as columns.  
+    /// sheet_headers should contain the name of each output sheet, paired with
+    /// the headers that sheet currently has. Headers already in the layout
+    /// for a sheet will also be shown, even if they aren't in sheet_headers.  
+    /// If the user clicks Ok, the new layouts are saved to the config_store.
+    pub fn show_column_layout_dialog(&mut self, sheet_headers: Vec<(String, Vec<String>)>) {
+        if sheet_headers.len() == 0 {return;}
+        let visible_prefix = "[x] ";
+        let hidden_prefix = "[  ] ";
+        let config = {self.config_store.borrow().clone()};
 
-        let config_ref = Rc::from(RefCell::from(ConfigStore::default()));
-        // define some constants to be used repeatedly for sizing and styling
-        let io_btn_width = 150;
-        let io_btn_height = 30;
-        let io_btn_padding = 10;
-        let io_btn_frame = FrameType::GtkRoundUpFrame;
-        let io_btn_down_frame = FrameType::GtkRoundDownFrame;
-        let io_btn_color = Color::from_rgb(248,248,255);
-        let io_btn_down_color = Color::from_rgb(240,255,240);
-        // let io_box_width = 240; boxes are centered between btn and rest of space in tile
-        let io_box_height = 30;
-        let io_box_padding = 10;
-        let io_box_frame = FrameType::GtkDownFrame;
-        let cf_padding = 5;
-        let cf_chck_height = 20;
-        let cf_chck_frame = FrameType::GtkUpFrame;
-        let cf_box_frame = FrameType::GtkDownFrame;
+        // (sheet name, vec of (header, whether header is visible), whether sheet is transposed)
+        let mut working_layouts: Vec<(String, Vec<(String, bool)>, bool)> = Vec::new();
+        for (sheet_name, headers) in sheet_headers.iter() {
+            let layout = config.output_column_layouts.iter().find(|layout| layout.sheet_name.eq(sheet_name));
+            let mut all_headers = headers.clone();
+            if let Some(layout) = layout {
+                for header in layout.column_order.iter().chain(layout.hidden_columns.iter()) {
+                    if !all_headers.contains(header) {all_headers.push(header.clone());}
+                }//end adding headers we only know from the layout
+            }//end if we have a layout for this sheet
+            let ordered_headers = xlsx::get_column_order(&all_headers, layout)
+                .into_iter().map(|(col_idx, is_visible)| (all_headers[col_idx].clone(), is_visible)).collect();
+            let transposed = layout.map(|layout| layout.transposed).unwrap_or(false);
+            working_layouts.push((sheet_name.clone(), ordered_headers, transposed));
+        }//end building working layout for each sheet
+        let working_layouts = Rc::from(RefCell::from(working_layouts));
+        let current_sheet = Rc::from(RefCell::from(0));
+        let clicked_ok = Rc::from(RefCell::from(false));
 
-        let (s, r): (Sender<InterfaceMessage>, Receiver<InterfaceMessage>) = app::channel();
+        let mut dialog_window = Window::default()
+            .with_size(360,360)
+            .with_label("Output Column Layout");
+        dialog_window.make_modal(true);
+        let mut sheet_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(140,10)
+            .with_label("Output Sheet:");
+        for (sheet_name, _) in sheet_headers.iter() {sheet_choice.add_choice(sheet_name);}
+        sheet_choice.set_value(0);
+        let mut column_browser = HoldBrowser::default()
+            .with_size(230,230)
+            .with_pos(20,45);
+        column_browser.set_tooltip("Drag a column to move it.\nColumns marked with [x] will be written to the output.");
+        let mut up_button = Button::default()
+            .with_size(90,30)
+            .with_pos(260,45)
+            .with_label("Move Up");
+        up_button.set_frame(FrameType::GtkRoundUpFrame);
+        up_button.clear_visible_focus();
+        let mut down_button = Button::default()
+            .with_size(90,30)
+            .with_pos(260,85)
+            .with_label("Move Down");
+        down_button.set_frame(FrameType::GtkRoundUpFrame);
+        down_button.clear_visible_focus();
+        let mut toggle_button = Button::default()
+            .with_size(90,30)
+            .with_pos(260,125)
+            .with_label("Show/Hide");
+        toggle_button.set_frame(FrameType::GtkRoundUpFrame);
+        toggle_button.clear_visible_focus();
+        let mut transposed_chck = CheckButton::default()
+            .with_size(320,20)
+            .with_pos(20,285)
+            .with_label("Write samples as columns (transposed)");
+        transposed_chck.set_tooltip("If checked, this sheet will have a column for each sample and a row for each output column.");
+        transposed_chck.clear_visible_focus();
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(60,320)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(120,320)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
 
-        let mut tile_group = Tile::default()
-            .with_pos(0, 0)
-            .with_size(main_window.w(), main_window.h());
-        tile_group.end();
-        main_window.add(&tile_group);
+        // fill the browser with the first sheet
+        transposed_chck.set_checked(working_layouts.borrow()[0].2);
+        for (header, is_visible) in working_layouts.borrow()[0].1.iter() {
+            column_browser.add(&format!("{}{}", if *is_visible {visible_prefix} else {hidden_prefix}, header));
+        }//end adding each header for first sheet
 
-        // set up header information
-        let mut header_group = Group::default()
-            .with_pos(0,0)
-            .with_size(tile_group.w() / 7 * 4, 90);
-        header_group.end();
-        header_group.set_color(Color::from_rgb(255, 250, 240));
-        tile_group.add(&header_group);
+        // helper closure for moving a line in the browser from one spot to another
+        let move_line = move |browser: &mut HoldBrowser, from: i32, to: i32| {
+            if from < 1 || to < 1 || from > browser.size() || to > browser.size() || from == to {return;}
+            let line_text = browser.text(from).unwrap_or_default();
+            browser.remove(from);
+            browser.insert(to, &line_text);
+            browser.select(to);
+        };
+        // helper closure for saving the browser contents to the working layout
+        let save_browser = {
+            let working_layouts = (&working_layouts).clone();
+            let current_sheet = (&current_sheet).clone();
+            move |browser: &HoldBrowser, transposed_chck: &CheckButton| {
+                let mut working_layouts = working_layouts.borrow_mut();
+                let current_sheet = *current_sheet.borrow();
+                let mut new_headers = Vec::new();
+                for line in 1..=browser.size() {
+                    let line_text = browser.text(line).unwrap_or_default();
+                    if let Some(header) = line_text.strip_prefix(visible_prefix) {new_headers.push((header.to_string(), true));}
+                    else if let Some(header) = line_text.strip_prefix(hidden_prefix) {new_headers.push((header.to_string(), false));}
+                }//end reading each line of the browser
+                working_layouts[current_sheet].1 = new_headers;
+                working_layouts[current_sheet].2 = transposed_chck.is_checked();
+            }//end closure
+        };
 
-        let header_label_align = Align::Inside.union(Align::Left);
-        let header_label_frame = FrameType::NoBox;
-        let header_label_color = Color::from_rgb(0,0,64);
-        let mut header_label1 = Frame::default()
-            .with_pos(5,5)
-            .with_size(header_group.w() - 10, header_group.h() / 3)
-            .with_label(&format!("USDA C-Grain Sum\tv{}\t{}",GUI::header_version_day().0,GUI::header_version_day().1))
-            .with_align(header_label_align);
-        header_label1.set_label_size(18);
-        header_label1.set_label_type(fltk::enums::LabelType::Embossed);
-        header_label1.set_label_color(header_label_color);
-        header_label1.set_frame(header_label_frame);
-        header_group.add(&header_label1);
-        let mut header_label2 = Frame::default()
-            .with_pos(header_label1.x(),header_group.y() + (header_group.h() / 2 - 5))
-            .with_size(header_label1.w(), header_group.h() / 4)
-            .with_label("Processes CSV and XML Data from C-Grain into Sum Files")
-            .with_align(header_label_align);
-        header_label2.set_frame(header_label_frame);
-        header_label2.set_label_color(header_label_color);
-        header_group.add(&header_label2);
-        let mut header_label3 = Frame::default()
-            .with_pos(header_label2.x(), header_label2.y() + header_label2.h())
-            .with_size(header_label2.w(),header_label2.h())
-            .with_label("Nicholas Sixbury/Dan Brabec\tUSDA-ARS Manhattan,KS\n")
-            .with_align(header_label_align);
-        header_label3.set_frame(header_label_frame);
-        header_label3.set_label_color(header_label_color);
-        header_group.add(&header_label3);
-        // let mut header_buf = TextBuffer::default();
-        // let mut header_box = TextDisplay::default()
-        //     .with_pos(10, 10)
-        //     .with_size(header_group.w() - 20,header_group.h() - 20);
-        // header_group.add_resizable(&header_box);
-        // header_box.set_buffer(header_buf.clone());
-        // header_buf.append(&GUI::default_header_info());
-        // header_box.set_scrollbar_align(Align::empty());
+        // allow reordering by dragging lines
+        column_browser.handle({
+            let drag_from: Rc<RefCell<Option<i32>>> = Rc::from(RefCell::from(None));
+            move |browser, ev| {
+                match ev {
+                    Event::Drag => {
+                        let mut drag_from = drag_from.borrow_mut();
+                        if drag_from.is_none() {*drag_from = Some(browser.value());}
+                        false
+                    },
+                    Event::Released => {
+                        if let Some(from) = drag_from.borrow_mut().take() {
+                            let to = browser.value();
+                            move_line(browser, from, to);
+                        }//end if we were dragging a line
+                        false
+                    },
+                    _ => false
+                }
+            }//end moving for closure
+        });
+        up_button.set_callback({
+            let mut browser = column_browser.clone();
+            move |_| {
+                let selected = browser.value();
+                move_line(&mut browser, selected, selected - 1);
+            }//end moving for closure
+        });
+        down_button.set_callback({
+            let mut browser = column_browser.clone();
+            move |_| {
+                let selected = browser.value();
+                move_line(&mut browser, selected, selected + 1);
+            }//end moving for closure
+        });
+        toggle_button.set_callback({
+            let mut browser = column_browser.clone();
+            move |_| {
+                let selected = browser.value();
+                if selected < 1 {return;}
+                let line_text = browser.text(selected).unwrap_or_default();
+                let new_text = match line_text.strip_prefix(visible_prefix) {
+                    Some(header) => format!("{}{}", hidden_prefix, header),
+                    None => format!("{}{}", visible_prefix, line_text.strip_prefix(hidden_prefix).unwrap_or(&line_text)),
+                };
+                browser.set_text(selected, &new_text);
+            }//end moving for closure
+        });
+        sheet_choice.set_callback({
+            let mut browser = column_browser.clone();
+            let mut transposed_chck = transposed_chck.clone();
+            let working_layouts = (&working_layouts).clone();
+            let current_sheet = (&current_sheet).clone();
+            let save_browser = save_browser.clone();
+            move |choice| {
+                if choice.value() < 0 {return;}
+                save_browser(&browser, &transposed_chck);
+                *current_sheet.borrow_mut() = choice.value() as usize;
+                transposed_chck.set_checked(working_layouts.borrow()[choice.value() as usize].2);
+                browser.clear();
+                for (header, is_visible) in working_layouts.borrow()[choice.value() as usize].1.iter() {
+                    browser.add(&format!("{}{}", if *is_visible {visible_prefix} else {hidden_prefix}, header));
+                }//end adding each header for newly selected sheet
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let browser = column_browser.clone();
+            let transposed_chck = transposed_chck.clone();
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                save_browser(&browser, &transposed_chck);
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
 
-        // set up group with input and output controls, processing stuff
-        let mut io_controls_group = Group::default()
-            .with_pos(0, header_group.y() + header_group.h())
-            .with_size(tile_group.w() / 7 * 4, tile_group.h() - header_group.h() - 125);
-        io_controls_group.end();
-        io_controls_group.set_color(Color::from_rgb(245,255,250));
-        tile_group.add(&io_controls_group);
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
 
-        let mut io_controls_label = Frame::default()
-            .with_pos(io_controls_group.x(), io_controls_group.y() + 10)
-            .with_size(io_controls_group.w(), 20)
-            .with_label("Input and Output Controls")
-            .with_align(Align::Center);
-        io_controls_label.set_label_size(16);
-        io_controls_group.add(&io_controls_label);
+        if *clicked_ok.borrow() {
+            let mut config = self.config_store.borrow_mut();
+            for (sheet_name, headers, transposed) in working_layouts.borrow().iter() {
+                let new_layout = OutputColumnLayout {
+                    sheet_name: sheet_name.clone(),
+                    column_order: headers.iter().map(|(header, _)| header.clone()).collect(),
+                    hidden_columns: headers.iter().filter(|(_, is_visible)| !is_visible).map(|(header, _)| header.clone()).collect(),
+                    transposed: *transposed,
+                };
+                config.output_column_layouts.retain(|layout| !layout.sheet_name.eq(sheet_name));
+                if new_layout.column_order.len() > 0 || new_layout.transposed {config.output_column_layouts.push(new_layout);}
+            }//end saving layout for each sheet
+        }//end if user clicked ok
+    }//end show_column_layout_dialog(self, sheet_headers)
 
-        // get input file from user
-        let mut input_csv_btn = Button::default()
-            .with_label("Select Input CSV")
-            .with_pos(io_controls_label.x() + io_btn_padding, io_controls_label.y() +  io_controls_label.h() + io_btn_padding)
-            .with_size(io_btn_width, io_btn_height);
-        input_csv_btn.set_frame(io_btn_frame);
-        input_csv_btn.set_down_frame(io_btn_down_frame);
-        input_csv_btn.set_tooltip("Left Click this button to choose a csv input file.\nRight Click this button to configure advanced csv input options.");
-        input_csv_btn.clear_visible_focus();
-        input_csv_btn.set_color(io_btn_color);
-        input_csv_btn.set_selection_color(io_btn_down_color);
-        io_controls_group.add(&input_csv_btn);
+    /// Shows a dialog for choosing the order of the preprocessing steps
+    /// run on csv data before processing, turning steps on or off, and
+    /// editing the settings of each step. Changes are saved to the config
+    /// if the user clicks Ok.
+    pub fn show_pipeline_dialog(&mut self) {
+        let enabled_prefix = "[x] ";
+        let disabled_prefix = "[  ] ";
+        let config = {self.config_store.borrow().clone()};
+        let clicked_ok = Rc::from(RefCell::from(false));
 
-        let input_csv_buf = TextBuffer::default();
-        let mut input_csv_box = TextDisplay::default()
-            .with_pos(input_csv_btn.x() + input_csv_btn.w() + io_box_padding, input_csv_btn.y())
-            .with_size(io_controls_group.w() - (input_csv_btn.w() + (3 * io_box_padding)), io_box_height);
-        input_csv_box.set_frame(io_box_frame);
-        input_csv_box.set_scrollbar_align(Align::Bottom);
-        input_csv_box.set_scrollbar_size(7);
-        input_csv_box.set_color(Color::from_rgb(245,245,245));
-        input_csv_box.set_buffer(input_csv_buf);
-        io_controls_group.add_resizable(&input_csv_box);
-        let input_csv_ref = Rc::from(RefCell::from(input_csv_box));
+        let mut dialog_window = Window::default()
+            .with_size(560,500)
+            .with_label("Processing Pipeline");
+        dialog_window.make_modal(true);
+        let mut step_browser = HoldBrowser::default()
+            .with_size(200,150)
+            .with_pos(20,30)
+            .with_label("Steps, in the order they run:")
+            .with_align(Align::TopLeft);
+        step_browser.set_tooltip("Steps marked with [x] are run on the csv data before processing, from top to bottom.\nFor example, move Derived Columns above Filters to filter on a derived column.");
+        for step in config.pipeline_steps.iter() {step_browser.add(&format!("{}{}", enabled_prefix, step.get_name()));}
+        for step in PipelineStep::ALL.iter().filter(|step| !config.pipeline_steps.contains(step)) {
+            step_browser.add(&format!("{}{}", disabled_prefix, step.get_name()));
+        }//end adding each step which is turned off
+        let mut up_button = Button::default()
+            .with_size(90,30)
+            .with_pos(230,30)
+            .with_label("Move Up");
+        up_button.set_frame(FrameType::GtkRoundUpFrame);
+        up_button.clear_visible_focus();
+        let mut down_button = Button::default()
+            .with_size(90,30)
+            .with_pos(230,70)
+            .with_label("Move Down");
+        down_button.set_frame(FrameType::GtkRoundUpFrame);
+        down_button.clear_visible_focus();
+        let mut toggle_button = Button::default()
+            .with_size(90,30)
+            .with_pos(230,110)
+            .with_label("On/Off");
+        toggle_button.set_frame(FrameType::GtkRoundUpFrame);
+        toggle_button.clear_visible_focus();
 
-        input_csv_btn.set_callback({
-            let input_csv_ref_clone = (&input_csv_ref).clone();
-            let sender_clone = s.clone();
-            let config_ref_clone = (&config_ref).clone();
+        // helper closure for making a box with one setting per line
+        let make_list_box = |x: i32, y: i32, w: i32, h: i32, label: &str, tooltip: &str, lines: &Vec<String>| {
+            let mut list_buf = TextBuffer::default();
+            list_buf.set_text(&lines.join("\n"));
+            let mut list_box = TextEditor::default()
+                .with_size(w,h)
+                .with_pos(x,y)
+                .with_label(label)
+                .with_align(Align::TopLeft);
+            list_box.set_tooltip(tooltip);
+            list_box.set_frame(FrameType::GtkDownFrame);
+            list_box.set_scrollbar_align(Align::Right);
+            list_box.set_scrollbar_size(7);
+            list_box.set_buffer(list_buf);
+            list_box
+        };
+        let derived_box = make_list_box(20, 215, 300, 55, "Derived Columns:", "Columns to calculate from other columns, one per line.\nPut a space on each side of +, -, *, or /.\nExample: Aspect = Length / Width", &config.derived_columns);
+        let aliases_box = make_list_box(340, 30, 200, 60, "Alias Mapping:", "Columns to rename, one per line.\nExample: Mass -> Weight", &config.csv_header_aliases);
+        let conversions_box = make_list_box(340, 115, 200, 60, "Unit Conversion:", "Conversions of numeric columns, one per line.\nExample: Weight * 1000", &config.unit_conversions);
+        let filters_box = make_list_box(340, 200, 200, 60, "Filters:", "Kernels which don't pass every filter are removed, one filter per line.\nExample: Area > 10", &config.csv_row_filters);
+        let outliers_box = make_list_box(340, 285, 200, 60, "Outlier Removal:", "Columns to check for outliers within each sample, one per line.\nKernels beyond 1.5 times the interquartile range are removed.\nExample: Weight", &config.outlier_columns);
+        let class_remappings_box = make_list_box(340, 370, 200, 60, "Class Remapping:", "Classes from the instrument to report as another class, one per line.\nClass filters and class percents use the new classes.\nExample: SorghumBroken -> Damaged", &config.class_remappings);
+        let colors_box = make_list_box(20, 295, 300, 50, "Color Conversion:", "Color spaces to add columns for, calculated from the Red, Green, and Blue columns, one per line.\nLab adds L*, a*, and b*. LCh adds Chroma and Hue Angle.\nTo use other columns, give them after from.\nExample: Lab from R, G, B", &config.color_conversions);
+
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(60,450)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(120,450)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        // helper closure for moving a line in the browser from one spot to another
+        let move_line = move |browser: &mut HoldBrowser, from: i32, to: i32| {
+            if from < 1 || to < 1 || from > browser.size() || to > browser.size() || from == to {return;}
+            let line_text = browser.text(from).unwrap_or_default();
+            browser.remove(from);
+            browser.insert(to, &line_text);
+            browser.select(to);
+        };
+        up_button.set_callback({
+            let mut browser = step_browser.clone();
             move |_| {
-                if app::event_button() == 3 {
-                    let mut config = config_ref_clone.as_ref().borrow_mut();
-                    if let Some(choice) = dialog::input_default("Please enter the name of the column which indicates the sample id in the csv.\nThe default is external-sample-id", &config.csv_sample_id_header) {
-                        config.csv_sample_id_header = choice;
-                    }//end matching whether we got response from user
-                } else {
-                    let mut input_csv_ref = input_csv_ref_clone.as_ref().borrow_mut();
-                    let mut input_csv_buf = input_csv_ref.buffer().unwrap_or_else(|| TextBuffer::default());
-                    if let Err(err_message) = GUI::create_io_dialog(&sender_clone, "CSVInputFile", &mut input_csv_buf, dialog::NativeFileChooserType::BrowseFile, dialog::NativeFileChooserOptions::UseFilterExt, "*.csv", "Please select a csv input file") {
-                        println!("Encountered an error when attempting to show file dialog:\n{}", err_message);
-                    }//end if we got an error
-                    input_csv_ref.set_buffer(input_csv_buf);
-                }//end else user didn't right-click
+                let selected = browser.value();
+                move_line(&mut browser, selected, selected - 1);
             }//end moving for closure
         });
+        down_button.set_callback({
+            let mut browser = step_browser.clone();
+            move |_| {
+                let selected = browser.value();
+                move_line(&mut browser, selected, selected + 1);
+            }//end moving for closure
+        });
+        toggle_button.set_callback({
+            let mut browser = step_browser.clone();
+            move |_| {
+                let selected = browser.value();
+                if selected < 1 {return;}
+                let line_text = browser.text(selected).unwrap_or_default();
+                let new_text = match line_text.strip_prefix(enabled_prefix) {
+                    Some(step_name) => format!("{}{}", disabled_prefix, step_name),
+                    None => format!("{}{}", enabled_prefix, line_text.strip_prefix(disabled_prefix).unwrap_or(&line_text)),
+                };
+                browser.set_text(selected, &new_text);
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
 
-        let mut input_xml_btn = Button::default()
-            .with_label("Select Input XML")
-            .with_pos(input_csv_btn.x(), input_csv_btn.y() + input_csv_btn.h() + io_btn_padding)
-            .with_size(io_btn_width, io_btn_height);
-        input_xml_btn.set_frame(io_btn_frame);
-        input_xml_btn.set_down_frame(io_btn_down_frame);
-        input_xml_btn.set_tooltip("Left Click this button to choose an xml input file.\nRight click this button to configure advanced xml input options.");
-        input_xml_btn.clear_visible_focus();
-        input_xml_btn.set_color(io_btn_color);
-        input_xml_btn.set_selection_color(io_btn_down_color);
-        io_controls_group.add(&input_xml_btn);
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
 
-        let input_xml_buf = TextBuffer::default();
-        let mut input_xml_box = TextDisplay::default()
-            .with_pos(input_xml_btn.x() + input_xml_btn.w() + io_box_padding, input_xml_btn.y())
-            .with_size(io_controls_group.w() - (input_xml_btn.w() + (3 * io_box_padding)), io_box_height);
-        input_xml_box.set_frame(io_box_frame);
-        input_xml_box.set_scrollbar_align(Align::Bottom);
-        input_xml_box.set_scrollbar_size(7);
-        input_xml_box.set_color(Color::from_rgb(245,245,245));
-        input_xml_box.set_buffer(input_xml_buf);
-        io_controls_group.add_resizable(&input_xml_box);
-        let input_xml_ref = Rc::from(RefCell::from(input_xml_box));
+        if *clicked_ok.borrow() {
+            let get_lines = |list_box: &TextEditor| -> Vec<String> {
+                list_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+                    .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect()
+            };
+            let mut config = self.config_store.borrow_mut();
+            config.pipeline_steps = (1..=step_browser.size())
+                .filter_map(|line| step_browser.text(line).and_then(|line_text| line_text.strip_prefix(enabled_prefix).map(|step_name| step_name.to_string())))
+                .filter_map(|step_name| PipelineStep::ALL.into_iter().find(|step| step.get_name().eq(&step_name)))
+                .collect();
+            config.csv_header_aliases = get_lines(&aliases_box);
+            config.class_remappings = get_lines(&class_remappings_box);
+            config.unit_conversions = get_lines(&conversions_box);
+            config.csv_row_filters = get_lines(&filters_box);
+            config.outlier_columns = get_lines(&outliers_box);
+            config.derived_columns = get_lines(&derived_box);
+            config.color_conversions = get_lines(&colors_box);
+        }//end if user clicked ok
+    }//end show_pipeline_dialog(self)
+
+    /// Shows a dialog for editing the column aliases in
+    /// config.csv_header_aliases, which rename columns named differently
+    /// by other firmware, like MeanWidth, to the names used in the settings.  
+    /// file_headers, such as the headers of the loaded csv file, are listed
+    /// so one can be clicked to fill in the column in the file.  
+    /// Changes are saved to the config if the user clicks Ok. If there are
+    /// aliases and the Alias Mapping step is off, it's turned on, running first.
+    pub fn show_header_alias_dialog(&mut self, file_headers: &Vec<String>) {
+        let config = {self.config_store.borrow().clone()};
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(560,360)
+            .with_label("Column Aliases");
+        dialog_window.make_modal(true);
+        let mut alias_browser = HoldBrowser::default()
+            .with_size(300,220)
+            .with_pos(20,30)
+            .with_label("Aliases, as column in file -> column in settings:")
+            .with_align(Align::TopLeft);
+        alias_browser.set_tooltip("Each alias renames a column before processing.\nCase, spaces, and punctuation are ignored when finding the column in the file,\nso MeanWidth -> Mean Width also renames mean-width.");
+        for alias in config.csv_header_aliases.iter() {alias_browser.add(&alias.replace("@", "@@"));}
+        let mut header_browser = HoldBrowser::default()
+            .with_size(200,220)
+            .with_pos(340,30)
+            .with_label("Columns in the loaded csv:")
+            .with_align(Align::TopLeft);
+        header_browser.set_tooltip("Click a column to use it as the column in the file.");
+        for file_header in file_headers.iter() {header_browser.add(&file_header.replace("@", "@@"));}
+        if file_headers.is_empty() {header_browser.add("(no csv loaded)");}
+        let mut file_header_input = Input::default()
+            .with_size(140,25)
+            .with_pos(20,275)
+            .with_label("Column in file:")
+            .with_align(Align::TopLeft);
+        file_header_input.set_tooltip("The name the file uses for the column, such as MeanWidth.");
+        let mut config_header_input = Input::default()
+            .with_size(140,25)
+            .with_pos(180,275)
+            .with_label("Column in settings:")
+            .with_align(Align::TopLeft);
+        config_header_input.set_tooltip("The name your settings use for the column, such as Mean Width.");
+        let mut add_button = Button::default()
+            .with_size(90,25)
+            .with_pos(340,275)
+            .with_label("Add");
+        let mut remove_button = Button::default()
+            .with_size(90,25)
+            .with_pos(450,275)
+            .with_label("Remove");
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(20,315)
+            .with_label("Ok");
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(80,315)
+            .with_label("Cancel");
+        for button in [&mut add_button, &mut remove_button, &mut ok_button, &mut cancel_button] {
+            button.set_frame(FrameType::GtkRoundUpFrame);
+            button.clear_visible_focus();
+        }//end styling each button
+        dialog_window.end();
+
+        header_browser.set_callback({
+            let mut file_header_input = file_header_input.clone();
+            let file_headers = file_headers.clone();
+            move |browser| {
+                if browser.value() < 1 {return;}
+                if let Some(file_header) = file_headers.get(browser.value() as usize - 1) {
+                    file_header_input.set_value(file_header);
+                }//end if a column was clicked
+            }//end moving for closure
+        });
+        add_button.set_callback({
+            let mut alias_browser = alias_browser.clone();
+            let mut file_header_input = file_header_input.clone();
+            let mut config_header_input = config_header_input.clone();
+            move |_| {
+                let alias = format!("{} -> {}", file_header_input.value().trim(), config_header_input.value().trim());
+                match pipeline::parse_header_aliases(&vec![alias.clone()]) {
+                    Ok(_) => {
+                        alias_browser.add(&alias.replace("@", "@@"));
+                        file_header_input.set_value("");
+                        config_header_input.set_value("");
+                    },
+                    Err(_) => GUI::show_alert("Please give the column in the file and the column in your settings,\nwithout using ->."),
+                }//end matching whether the alias can be used
+            }//end moving for closure
+        });
+        remove_button.set_callback({
+            let mut alias_browser = alias_browser.clone();
+            move |_| {
+                match alias_browser.value() {
+                    0 => GUI::show_alert("Please select an alias to remove."),
+                    line => alias_browser.remove(line),
+                }//end matching whether an alias is selected
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if *clicked_ok.borrow() {
+            let mut config = self.config_store.borrow_mut();
+            config.csv_header_aliases = (1..=alias_browser.size())
+                .filter_map(|line| alias_browser.text(line))
+                .map(|alias| alias.replace("@@", "@"))
+                .collect();
+            if config.csv_header_aliases.len() > 0 && !config.pipeline_steps.contains(&PipelineStep::AliasMapping) {
+                config.pipeline_steps.insert(0, PipelineStep::AliasMapping);
+            }//end if the aliases wouldn't be used
+        }//end if user clicked ok
+    }//end show_header_alias_dialog(self, file_headers)
+
+    /// Updates the Filter Set dropdown to list the filter sets in config,
+    /// with the active filter set chosen.
+    fn update_filter_set_choice(&mut self, config: &ConfigStore) {
+        self.ux_filter_set_choice.clear();
+        self.ux_filter_set_choice.add_choice("(Config Filters)");
+        for filter_set in config.filter_sets.iter() {self.ux_filter_set_choice.add_choice(&filter_set.name.replace("/", "\\/"));}
+        let active_idx = config.filter_sets.iter()
+            .position(|filter_set| filter_set.name == config.active_filter_set)
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        self.ux_filter_set_choice.set_value(active_idx as i32);
+    }//end update_filter_set_choice(self, config)
+
+    /// Asks the user for a name, then saves the current class filters and
+    /// the filters from the processing pipeline as a filter set with that
+    /// name, replacing any set with the same name. The new set is then
+    /// chosen in the Filter Set dropdown.
+    pub fn save_filter_set(&mut self) {
+        let mut config = self.get_config_store();
+        let name = match dialog::input_default("Please enter a name for the filter set, such as Export Grade.\nThe current class filters and the filters in the processing pipeline will be saved in it.\nIf a set already has this name, it will be replaced.", &config.active_filter_set) {
+            Some(name) => name.trim().to_string(),
+            None => return,
+        };
+        if name.is_empty() || name.contains('|') {
+            self.integrated_dialog_alert("Filter set names can't be empty or contain |, so the filter set wasn't saved.");
+            return;
+        }//end if the name can't be used
+        let filter_set = FilterSet {
+            name: name.clone(),
+            class_filters: match config.csv_class_filter_enabled {
+                true => config.csv_class_filter_filters.clone(),
+                false => Vec::new(),
+            },
+            row_filters: config.csv_row_filters.clone(),
+        };
+        match config.filter_sets.iter_mut().find(|existing_set| existing_set.name == name) {
+            Some(existing_set) => *existing_set = filter_set,
+            None => config.filter_sets.push(filter_set),
+        }//end matching whether we're replacing a set
+        config.active_filter_set = name;
+        self.set_config_store(&config);
+    }//end save_filter_set(self)
+
+    /// Asks the user for a name, then saves the current settings as a
+    /// preset with that name, so it can be chosen later. The current
+    /// config is then labeled with the new preset.
+    pub fn save_preset(&mut self) {
+        let mut config = self.get_config_store();
+        let name = match dialog::input_default("Please enter a name for the preset, such as Barley.\nThe current settings will be saved in it, and it can be chosen\nlater by right clicking Configuration Settings.\nIf a preset already has this name, it will be replaced.", &config.personalized_config_name) {
+            Some(name) => name.trim().to_string(),
+            None => return,
+        };
+        match config_store::try_write_preset(&name, &config) {
+            Ok(preset_path) => {
+                log::info!("Saved preset to {}", preset_path.to_string_lossy());
+                config.personalized_config_name = name;
+                self.set_config_store(&config);
+            },
+            Err(error) => self.integrated_dialog_alert(&format!("The preset couldn't be saved:\n{}", error)),
+        }//end matching whether we could save the preset
+    }//end save_preset(self)
+
+    /// Shows a dialog listing every preset in the preset folder, where the
+    /// user can switch to a preset, save the current settings as a new one,
+    /// or rename and delete presets, including the built-in ones.  
+    /// Switching is done by sending InterfaceMessage::SwitchPreset, so
+    /// it happens once the dialog is closed.
+    pub fn show_preset_manager(&mut self) {
+        let mut config = self.get_config_store();
+        // the name the current settings should be labeled with once the dialog closes
+        let new_active_name: Rc<RefCell<Option<String>>> = Rc::from(RefCell::from(None));
+
+        let mut dialog_window = Window::default()
+            .with_size(400,360)
+            .with_label("Manage Presets");
+        dialog_window.make_modal(true);
+        let mut preset_label = Frame::default()
+            .with_size(360,25)
+            .with_pos(20,10)
+            .with_label("Presets, which are saved in the presets folder:")
+            .with_align(Align::Inside | Align::Left);
+        preset_label.set_frame(FrameType::NoBox);
+        let mut preset_browser = HoldBrowser::default()
+            .with_size(230,300)
+            .with_pos(20,40);
+        let mut switch_button = Button::default()
+            .with_size(130,30)
+            .with_pos(260,40)
+            .with_label("Switch To");
+        switch_button.set_tooltip("Uses the selected preset for the next runs.");
+        let mut save_button = Button::default()
+            .with_size(130,30)
+            .with_pos(260,80)
+            .with_label("Save Current...");
+        save_button.set_tooltip("Saves the current settings as a new preset,\nor replaces a preset with the same name.");
+        let mut rename_button = Button::default()
+            .with_size(130,30)
+            .with_pos(260,120)
+            .with_label("Rename...");
+        let mut delete_button = Button::default()
+            .with_size(130,30)
+            .with_pos(260,160)
+            .with_label("Delete");
+        let mut close_button = Button::default()
+            .with_size(130,30)
+            .with_pos(260,310)
+            .with_label("Close");
+        for button in [&mut switch_button, &mut save_button, &mut rename_button, &mut delete_button, &mut close_button] {
+            button.set_frame(FrameType::GtkRoundUpFrame);
+            button.clear_visible_focus();
+        }//end styling each button
+        dialog_window.end();
+
+        // lists the presets in the browser again, selecting the one named select_name
+        let fill_browser = |preset_browser: &mut HoldBrowser, select_name: &str| {
+            preset_browser.clear();
+            for preset_name in config_store::get_preset_names() {
+                preset_browser.add(&preset_name);
+                if preset_name.eq_ignore_ascii_case(select_name) {preset_browser.select(preset_browser.size());}
+            }//end listing each preset
+        };
+        fill_browser(&mut preset_browser, &config.personalized_config_name);
+        // gets the name of the selected preset, if one is selected
+        let get_selected = |preset_browser: &HoldBrowser| -> Option<String> {
+            match preset_browser.value() {
+                0 => None,
+                line => preset_browser.text(line),
+            }//end matching whether a preset is selected
+        };
+
+        switch_button.set_callback({
+            let preset_browser = preset_browser.clone();
+            let sender = self.msg_sender.clone();
+            let mut window = dialog_window.clone();
+            move |_| {
+                match get_selected(&preset_browser) {
+                    Some(preset_name) => {
+                        sender.send(InterfaceMessage::SwitchPreset(preset_name));
+                        window.hide();
+                    },
+                    None => GUI::show_alert("Please select a preset to switch to."),
+                }//end matching whether a preset is selected
+            }//end moving for closure
+        });
+        save_button.set_callback({
+            let mut preset_browser = preset_browser.clone();
+            let config = config.clone();
+            let new_active_name = (&new_active_name).clone();
+            move |_| {
+                let name = match dialog::input_default("Please enter a name for the preset, such as Barley.\nIf a preset already has this name, it will be replaced.", &config.personalized_config_name) {
+                    Some(name) => name.trim().to_string(),
+                    None => return,
+                };
+                match config_store::try_write_preset(&name, &config) {
+                    Ok(_) => {
+                        fill_browser(&mut preset_browser, &name);
+                        *new_active_name.borrow_mut() = Some(name);
+                    },
+                    Err(error) => GUI::show_alert(&format!("The preset couldn't be saved:\n{}", error)),
+                }//end matching whether we could save the preset
+            }//end moving for closure
+        });
+        rename_button.set_callback({
+            let mut preset_browser = preset_browser.clone();
+            let active_name = config.personalized_config_name.clone();
+            let new_active_name = (&new_active_name).clone();
+            move |_| {
+                let old_name = match get_selected(&preset_browser) {
+                    Some(old_name) => old_name,
+                    None => {GUI::show_alert("Please select a preset to rename."); return;},
+                };
+                let new_name = match dialog::input_default(&format!("Please enter the new name for the preset {}.", old_name), &old_name) {
+                    Some(new_name) => new_name.trim().to_string(),
+                    None => return,
+                };
+                match config_store::try_rename_preset(&old_name, &new_name) {
+                    Ok(_) => {
+                        fill_browser(&mut preset_browser, &new_name);
+                        let current_name = new_active_name.borrow().clone().unwrap_or(active_name.clone());
+                        if current_name.eq_ignore_ascii_case(&old_name) {*new_active_name.borrow_mut() = Some(new_name);}
+                    },
+                    Err(error) => GUI::show_alert(&format!("The preset couldn't be renamed:\n{}", error)),
+                }//end matching whether we could rename the preset
+            }//end moving for closure
+        });
+        delete_button.set_callback({
+            let mut preset_browser = preset_browser.clone();
+            move |_| {
+                let preset_name = match get_selected(&preset_browser) {
+                    Some(preset_name) => preset_name,
+                    None => {GUI::show_alert("Please select a preset to delete."); return;},
+                };
+                let is_builtin = config_store::PRESET_NAMES.iter().any(|name| name.eq_ignore_ascii_case(&preset_name));
+                let warning = if is_builtin {"\nIt comes with the program, but it won't be added back."} else {""};
+                if dialog::choice2_default(&format!("Are you sure you want to delete the preset \"{}\"?{}", preset_name, warning), "Yes", "No", "") != Some(0) {return;}
+                match config_store::try_delete_preset(&preset_name) {
+                    Ok(_) => fill_browser(&mut preset_browser, ""),
+                    Err(error) => GUI::show_alert(&format!("The preset couldn't be deleted:\n{}", error)),
+                }//end matching whether we could delete the preset
+            }//end moving for closure
+        });
+        close_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if let Some(name) = new_active_name.borrow_mut().take() {
+            config.personalized_config_name = name;
+            self.set_config_store(&config);
+        }//end if the current settings have a new preset name
+    }//end show_preset_manager(self)
+
+    /// Asks the user for the name of whoever is running the program, which
+    /// is written in the Run Info sheet and can be used in output names.  
+    /// If the user cancels, the operator name is left as it was.
+    pub fn ask_operator_name(&mut self) {
+        let mut config = self.get_config_store();
+        match dialog::input_default("Please enter your name or initials.\nThey'll be recorded in the Run Info sheet of each output,\nand can be used in output names with {operator}.", &config.operator_name) {
+            Some(operator_name) => {
+                config.operator_name = operator_name.trim().to_string();
+                self.set_config_store(&config);
+            },
+            None => log::info!("Kept the operator name as \"{}\".", config.operator_name),
+        }//end matching whether the user entered a name
+    }//end ask_operator_name(self)
+
+    /// Switches between operator mode, where only file selection and
+    /// processing are available, and analyst mode, where every setting
+    /// can be changed.  
+    /// Leaving operator mode asks for the admin password, and entering
+    /// operator mode asks the user to set that password.  
+    /// Only a salted hash of the password is kept, in the data directory
+    /// rather than the config, and the password entered is checked against it.
+    /// If the hash is missing, such as if its file was deleted, operator mode
+    /// stays on, and the user is asked for a new admin password instead.
+    pub fn switch_user_mode(&mut self) {
+        let mut config = self.get_config_store();
+        let password_path = match secrets::get_admin_password_path() {
+            Ok(password_path) => password_path,
+            Err(msg) => {GUI::show_alert(&format!("Couldn't find where the admin password is kept, so the mode wasn't switched.\n{}", msg)); return;},
+        };
+        if config.operator_mode_enabled {
+            if !secrets::is_password_set(&password_path) {
+                log::warn!("Operator mode is on, but there's no admin password saved in \"{}\".", password_path.to_string_lossy());
+                GUI::ask_new_admin_password(&password_path, "There's no admin password saved, such as if its file was deleted, so operator mode will stay on.\nPlease enter a new admin password, which you can then use to switch to analyst mode.");
+                return;
+            }//end if the password can't be checked
+            let password = match dialog::password_default("Please enter the admin password to switch to analyst mode.", "") {
+                Some(password) => password,
+                None => return,
+            };
+            match secrets::check_password(&password_path, &password) {
+                Ok(true) => {},
+                Ok(false) => {GUI::show_alert("That password is incorrect, so operator mode will stay on."); return;},
+                Err(msg) => {GUI::show_alert(&format!("Couldn't check the admin password, so operator mode will stay on.\n{}", msg)); return;},
+            }//end matching whether the password is correct
+            config.operator_mode_enabled = false;
+        } else {
+            if !GUI::ask_new_admin_password(&password_path, "Operator mode hides all settings, leaving only file selection and processing.\nPlease enter an admin password needed to switch back to analyst mode.") {return;}
+            config.operator_mode_enabled = true;
+        }//end else we're switching to operator mode
+        self.set_config_store(&config);
+    }//end switch_user_mode(self)
+
+    /// Asks the user for a new admin password with prompt, asking again
+    /// if it's empty, then saves its hash to password_path.  
+    /// Returns whether a password was saved, which is false if the
+    /// user cancelled or it couldn't be saved.
+    fn ask_new_admin_password(password_path: &PathBuf, prompt: &str) -> bool {
+        loop {
+            let password = match dialog::password_default(prompt, "") {
+                Some(password) => password,
+                None => return false,
+            };
+            if password.is_empty() {GUI::show_alert("The admin password can't be empty."); continue;}
+            match secrets::save_password_hash(password_path, &password) {
+                Ok(_) => return true,
+                Err(msg) => {GUI::show_alert(&format!("Couldn't save the admin password.\n{}", msg)); return false;},
+            }//end matching whether the password was saved
+        }//end asking until the user enters a password or cancels
+    }//end ask_new_admin_password(password_path, prompt)
+
+    /// Lets the user know that processing has finished, by beeping and
+    /// showing a desktop notification, depending on the notification settings.  
+    /// The desktop notification is only shown if the run took at least as long
+    /// as the minimum in the settings, so quick runs don't pile up notifications.
+    pub fn notify_completion(&mut self, run_duration: Duration, output_name: &str) {
+        let config = self.config_store.borrow().clone();
+        if config.sounds_enabled && config.completion_beep_enabled {dialog::beep(BeepType::Message);}
+        if config.desktop_notification_enabled && run_duration.as_secs() >= config.desktop_notification_min_seconds {
+            let body = format!("Finished writing {} in {:.1} s.", output_name, run_duration.as_secs_f64());
+            if let Err(msg) = notify::send_desktop_notification("Processing Complete", &body) {
+                log::warn!("{}", msg);
+            }//end if we couldn't show the notification
+        }//end if we should show a desktop notification
+    }//end notify_completion(self, run_duration, output_name)
+
+    /// Shows a window where the user can choose whether the program beeps
+    /// on errors or on completion, whether long runs show a desktop notification,
+    /// and the webhook which is told about each run of a batch.
+    pub fn show_notification_settings_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(360,320)
+            .with_label("Notification Settings");
+        dialog_window.make_modal(true);
+        let mut sounds_chck = CheckButton::default()
+            .with_size(320,25)
+            .with_pos(20,10)
+            .with_label("Enable Sounds");
+        sounds_chck.set_checked(config.sounds_enabled);
+        sounds_chck.set_tooltip("If this is unchecked, the program won't make any sounds,\nwhatever the other settings are.");
+        let mut error_beep_chck = CheckButton::default()
+            .with_size(300,25)
+            .with_pos(40,40)
+            .with_label("Beep on Error Messages");
+        error_beep_chck.set_checked(config.error_beep_enabled);
+        let mut completion_beep_chck = CheckButton::default()
+            .with_size(300,25)
+            .with_pos(40,70)
+            .with_label("Beep when Processing Completes");
+        completion_beep_chck.set_checked(config.completion_beep_enabled);
+        let mut desktop_chck = CheckButton::default()
+            .with_size(320,25)
+            .with_pos(20,110)
+            .with_label("Desktop Notification for Long Runs");
+        desktop_chck.set_checked(config.desktop_notification_enabled);
+        desktop_chck.set_tooltip("Shows a desktop notification when processing finishes,\nso you can work in other windows during long runs.");
+        let mut min_seconds_box = Input::default()
+            .with_size(80,25)
+            .with_pos(40,165)
+            .with_label("Minimum Run Time (seconds)")
+            .with_align(Align::TopLeft);
+        min_seconds_box.set_value(&config.desktop_notification_min_seconds.to_string());
+        min_seconds_box.set_tooltip("Runs shorter than this won't show a desktop notification.");
+        let mut webhook_box = Input::default()
+            .with_size(320,25)
+            .with_pos(20,225)
+            .with_label("Webhook URL for Batch Runs")
+            .with_align(Align::TopLeft);
+        webhook_box.set_value(&config.webhook_url);
+        webhook_box.set_tooltip("After Batch Process Folder, json about each run, such as its output file and key metrics,\nis posted to this url, such as to update the lab dashboard.\nLeave this empty to not post anything.");
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(115,275)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(175,275)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        match min_seconds_box.value().trim().parse::<u64>() {
+            Ok(min_seconds) => config.desktop_notification_min_seconds = min_seconds,
+            Err(_) => {
+                self.integrated_dialog_alert(&format!("Couldn't read \"{}\" as a whole number of seconds, so the notification settings weren't changed.", min_seconds_box.value()));
+                return;
+            }
+        }//end matching whether the minimum run time is a number
+        config.sounds_enabled = sounds_chck.is_checked();
+        config.error_beep_enabled = error_beep_chck.is_checked();
+        config.completion_beep_enabled = completion_beep_chck.is_checked();
+        config.desktop_notification_enabled = desktop_chck.is_checked();
+        config.webhook_url = webhook_box.value().trim().to_string();
+        self.set_config_store(&config);
+    }//end show_notification_settings_dialog(self)
+
+    /// Shows a window where the user can choose whether merged summaries
+    /// are pushed to a Google Sheet, which sheet, and the service account
+    /// key used to sign in.
+    pub fn show_google_sheets_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(460,230)
+            .with_label("Google Sheets Settings");
+        dialog_window.make_modal(true);
+        let mut enabled_chck = CheckButton::default()
+            .with_size(420,25)
+            .with_pos(20,10)
+            .with_label("Push Merged Summaries to Google Sheets");
+        enabled_chck.set_checked(config.google_sheets_enabled);
+        enabled_chck.set_tooltip("After Merge Summary Workbooks writes the merged workbook,\neach merged sheet also replaces the tab of the same name in the Google Sheet.");
+        let mut spreadsheet_id_box = Input::default()
+            .with_size(420,25)
+            .with_pos(20,65)
+            .with_label("Spreadsheet ID")
+            .with_align(Align::TopLeft);
+        spreadsheet_id_box.set_value(&config.google_sheets_spreadsheet_id);
+        spreadsheet_id_box.set_tooltip("The part of the address of the Google Sheet between /d/ and /edit.");
+        let mut key_path_box = Input::default()
+            .with_size(330,25)
+            .with_pos(20,120)
+            .with_label("Service Account Key File")
+            .with_align(Align::TopLeft);
+        key_path_box.set_value(&config.google_sheets_key_path);
+        key_path_box.set_tooltip("The json key file of a service account which the Google Sheet is shared with as an editor.");
+        let mut browse_button = Button::default()
+            .with_size(80,25)
+            .with_pos(360,120)
+            .with_label("Browse");
+        browse_button.set_frame(FrameType::GtkRoundUpFrame);
+        browse_button.clear_visible_focus();
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(165,180)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(225,180)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        browse_button.set_callback({
+            let mut key_path_box = key_path_box.clone();
+            move |_| {
+                let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+                dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+                dialog.set_filter("Service Account Keys\t*.json");
+                dialog.set_title("Please select the json key file of the service account");
+                dialog.show();
+                if dialog.filename().as_os_str().len() > 0 {key_path_box.set_value(&paths::get_setting_path(&dialog.filename()));}
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        if enabled_chck.is_checked() && (spreadsheet_id_box.value().trim().is_empty() || key_path_box.value().trim().is_empty()) {
+            self.integrated_dialog_alert("Pushing to Google Sheets needs both a spreadsheet id and a service account key file, so the Google Sheets settings weren't changed.");
+            return;
+        }//end if pushing is enabled without anywhere to push
+        config.google_sheets_enabled = enabled_chck.is_checked();
+        config.google_sheets_spreadsheet_id = spreadsheet_id_box.value().trim().to_string();
+        config.google_sheets_key_path = key_path_box.value().trim().to_string();
+        self.set_config_store(&config);
+    }//end show_google_sheets_dialog(self)
+
+    /// Shows a window where the user can choose a folder to be batch processed
+    /// into a combined workbook at set times, such as 6 pm every day, how
+    /// often failed runs are tried before being quarantined, and whether
+    /// runs which were already processed are processed again.
+    pub fn show_schedule_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(460,320)
+            .with_label("Scheduled Processing Settings");
+        dialog_window.make_modal(true);
+        let mut enabled_chck = CheckButton::default()
+            .with_size(420,25)
+            .with_pos(20,10)
+            .with_label("Process Folder on a Schedule");
+        enabled_chck.set_checked(config.schedule_enabled);
+        enabled_chck.set_tooltip("While the program is open, the new runs in the folder are processed at the scheduled times,\nand merged into one combined workbook in a Summaries folder inside it.");
+        let mut folder_box = Input::default()
+            .with_size(330,25)
+            .with_pos(20,65)
+            .with_label("Folder to Process")
+            .with_align(Align::TopLeft);
+        folder_box.set_value(&config.schedule_folder);
+        folder_box.set_tooltip("The folder the csv and xml files of each run are exported to.");
+        let mut browse_button = Button::default()
+            .with_size(80,25)
+            .with_pos(360,65)
+            .with_label("Browse");
+        browse_button.set_frame(FrameType::GtkRoundUpFrame);
+        browse_button.clear_visible_focus();
+        let mut cron_box = Input::default()
+            .with_size(420,25)
+            .with_pos(20,120)
+            .with_label("Schedule (minute hour day month weekday)")
+            .with_align(Align::TopLeft);
+        cron_box.set_value(&config.schedule_cron);
+        cron_box.set_tooltip("When to process the folder, written like cron, with * for every value.\nWeekdays go from 0 for Sunday to 6 for Saturday.\nExample: 0 18 * * * for 6 pm every day, or 30 12 * * 1-5 for 12:30 pm on weekdays.");
+        let mut attempts_box = Input::default()
+            .with_size(200,25)
+            .with_pos(20,175)
+            .with_label("Tries Before Quarantine")
+            .with_align(Align::TopLeft);
+        attempts_box.set_value(&config.schedule_retry_attempts.to_string());
+        attempts_box.set_tooltip("How many times a run is tried before its files are moved\nto a Quarantine folder inside the folder, with a report of why it failed.");
+        let mut retry_seconds_box = Input::default()
+            .with_size(200,25)
+            .with_pos(240,175)
+            .with_label("Seconds Between Tries")
+            .with_align(Align::TopLeft);
+        retry_seconds_box.set_value(&config.schedule_retry_seconds.to_string());
+        retry_seconds_box.set_tooltip("How long to wait before trying a failed run again.\nFiles changed more recently than this are treated as still being written.");
+        let mut force_chck = CheckButton::default()
+            .with_size(420,25)
+            .with_pos(20,215)
+            .with_label("Process Runs Again Even If Already Processed");
+        force_chck.set_checked(config.schedule_force_reprocess);
+        force_chck.set_tooltip("Runs whose files are in the processed manifest are usually skipped, and each skipped file is logged.\nCheck this to process them again anyway, such as after changing the settings.");
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(165,270)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(225,270)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        browse_button.set_callback({
+            let mut folder_box = folder_box.clone();
+            move |_| {
+                let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseDir);
+                dialog.set_title("Please select the folder to process on a schedule");
+                dialog.show();
+                if dialog.filename().as_os_str().len() > 0 {folder_box.set_value(&paths::get_setting_path(&dialog.filename()));}
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        if enabled_chck.is_checked() && folder_box.value().trim().is_empty() {
+            self.integrated_dialog_alert("Scheduled processing needs a folder to process, so the schedule settings weren't changed.");
+            return;
+        }//end if scheduling is enabled without a folder
+        if let Err(msg) = CronSchedule::parse(&cron_box.value()) {
+            self.integrated_dialog_alert(&format!("{}\nThe schedule settings weren't changed.", msg));
+            return;
+        }//end if the schedule couldn't be read
+        let (retry_attempts, retry_seconds) = match (attempts_box.value().trim().parse::<usize>(), retry_seconds_box.value().trim().parse::<u64>()) {
+            (Ok(retry_attempts), Ok(retry_seconds)) if retry_attempts > 0 => (retry_attempts, retry_seconds),
+            _ => {
+                self.integrated_dialog_alert("The tries before quarantine must be a whole number above 0, and the seconds between tries must be a whole number,\nso the schedule settings weren't changed.");
+                return;
+            },
+        };
+        config.schedule_enabled = enabled_chck.is_checked();
+        config.schedule_folder = folder_box.value().trim().to_string();
+        config.schedule_cron = cron_box.value().trim().to_string();
+        config.schedule_retry_attempts = retry_attempts;
+        config.schedule_retry_seconds = retry_seconds;
+        config.schedule_force_reprocess = force_chck.is_checked();
+        self.set_config_store(&config);
+    }//end show_schedule_dialog(self)
+
+    /// Starts a timer which sends InterfaceMessage::ScheduleTick every
+    /// 30 seconds, for as long as the program is open, so scheduled
+    /// processing can run even when nobody is using the program.
+    pub fn start_schedule_timer(&self) {
+        let sender = self.msg_sender.clone();
+        app::add_timeout3(30.0, move |handle| {
+            sender.send(InterfaceMessage::ScheduleTick);
+            app::repeat_timeout3(30.0, handle);
+        });
+    }//end start_schedule_timer(self)
+
+    /// Shows a window where the user can define the report metrics, such as
+    /// %Sound or TKW, and choose which of them are shown on the Metrics sheet.
+    pub fn show_metrics_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(480,400)
+            .with_label("Report Metrics");
+        dialog_window.make_modal(true);
+        let mut metrics_sheet_chck = CheckButton::default()
+            .with_size(440,25)
+            .with_pos(20,10)
+            .with_label("Output Metrics Sheet");
+        metrics_sheet_chck.set_checked(config.metrics_sheet_enabled);
+        metrics_sheet_chck.set_tooltip("If this is checked, the output has a Metrics sheet,\nwith a column for each metric listed under Metrics on Sheet.");
+        let mut definitions_buf = TextBuffer::default();
+        definitions_buf.set_text(&config.metric_definitions.join("\n"));
+        let mut definitions_box = TextEditor::default()
+            .with_size(440,170)
+            .with_pos(20,60)
+            .with_label("Metric Definitions:")
+            .with_align(Align::TopLeft);
+        definitions_box.set_tooltip("Each metric is defined once, one per line, as name = calculation.\nCalculations can be count(), mean(column), std(column), or percent(class).\nAdd * or / and a number to scale it, then a comma and a number to set the decimal places.\nExample: TKW = mean(Weight) * 1000, 1");
+        definitions_box.set_frame(FrameType::GtkDownFrame);
+        definitions_box.set_scrollbar_align(Align::Right);
+        definitions_box.set_scrollbar_size(7);
+        definitions_box.set_buffer(definitions_buf);
+        let mut sheet_metrics_buf = TextBuffer::default();
+        sheet_metrics_buf.set_text(&config.metrics_sheet_metrics.join("\n"));
+        let mut sheet_metrics_box = TextEditor::default()
+            .with_size(440,90)
+            .with_pos(20,255)
+            .with_label("Metrics on Sheet:")
+            .with_align(Align::TopLeft);
+        sheet_metrics_box.set_tooltip("The names of the metrics shown on the Metrics sheet, one per line, in order.\nExample: TKW");
+        sheet_metrics_box.set_frame(FrameType::GtkDownFrame);
+        sheet_metrics_box.set_scrollbar_align(Align::Right);
+        sheet_metrics_box.set_scrollbar_size(7);
+        sheet_metrics_box.set_buffer(sheet_metrics_buf);
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(175,360)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(235,360)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        let get_lines = |list_box: &TextEditor| -> Vec<String> {
+            list_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+                .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect()
+        };
+        config.metrics_sheet_enabled = metrics_sheet_chck.is_checked();
+        config.metric_definitions = get_lines(&definitions_box);
+        config.metrics_sheet_metrics = get_lines(&sheet_metrics_box);
+        // check the metrics now, rather than finding out when processing
+        let metric_check = metrics::get_metric_registry(&config).and_then(|registry| {
+            for metric_name in config.metrics_sheet_metrics.iter() {metrics::find_metric(&registry, metric_name)?;}
+            Ok(())
+        });
+        if let Err(msg) = metric_check {
+            self.integrated_dialog_alert(&format!("The report metrics weren't changed, because of a problem with them:\n{}", msg));
+            return;
+        }//end if the metrics couldn't be read
+        self.set_config_store(&config);
+    }//end show_metrics_dialog(self)
+
+    /// Shows a window where the user can define the rules for the Flags
+    /// column, such as LOW_N, choose whether it's added to the output, and
+    /// set the pattern sample ids should match.
+    pub fn show_flag_rules_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(480,360)
+            .with_label("Flag Rules");
+        dialog_window.make_modal(true);
+        let mut flags_column_chck = CheckButton::default()
+            .with_size(440,25)
+            .with_pos(20,10)
+            .with_label("Add Flags Column");
+        flags_column_chck.set_checked(config.flags_column_enabled);
+        flags_column_chck.set_tooltip("If this is checked, each output sheet with a row for each sample gets a Flags column,\nlisting the rules each sample trips.");
+        let mut rules_buf = TextBuffer::default();
+        rules_buf.set_text(&config.flag_rules.join("\n"));
+        let mut rules_box = TextEditor::default()
+            .with_size(440,170)
+            .with_pos(20,60)
+            .with_label("Flag Rules:")
+            .with_align(Align::TopLeft);
+        rules_box.set_tooltip("Each rule is one line, as name = measure < number, or name = measure > number.\nMeasures can be kernels, outliers, or failed_checks.\nExample: LOW_N = kernels < 50");
+        rules_box.set_frame(FrameType::GtkDownFrame);
+        rules_box.set_scrollbar_align(Align::Right);
+        rules_box.set_scrollbar_size(7);
+        rules_box.set_buffer(rules_buf);
+        let mut pattern_input = Input::default()
+            .with_size(260,25)
+            .with_pos(20,265)
+            .with_label("Sample id pattern:")
+            .with_align(Align::TopLeft);
+        pattern_input.set_value(&config.sample_id_pattern);
+        pattern_input.set_tooltip("A regular expression every sample id should match in full, such as \\d{12} for twelve digits,\nto catch barcode misreads like 2024031900l9. Leave this empty to not check sample ids.");
+        let mut handling_choice = Choice::default()
+            .with_size(170,25)
+            .with_pos(290,265)
+            .with_label("Ids that don't match:")
+            .with_align(Align::TopLeft);
+        handling_choice.add_choice("Flag as BAD_ID|Leave Out");
+        handling_choice.set_value(match config.invalid_sample_id_handling {
+            InvalidSampleIdHandling::Flag => 0,
+            InvalidSampleIdHandling::Exclude => 1,
+        });
+        handling_choice.set_tooltip("Samples flagged as BAD_ID are kept, with BAD_ID in the Flags column, which is added even if it isn't checked above.\nSamples left out are listed on the Run Info sheet.\nEither way, they're reported with the Invalid Sample Id warning policy.");
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(175,315)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(235,315)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        config.flags_column_enabled = flags_column_chck.is_checked();
+        config.sample_id_pattern = pattern_input.value().trim().to_string();
+        config.invalid_sample_id_handling = match handling_choice.value() {
+            1 => InvalidSampleIdHandling::Exclude,
+            _ => InvalidSampleIdHandling::Flag,
+        };
+        if let Err(msg) = process::get_sample_id_regex(&config.sample_id_pattern) {
+            self.integrated_dialog_alert(&format!("The flag rules weren't changed, because the sample id pattern couldn't be read:\n{}", msg));
+            return;
+        }//end if the sample id pattern couldn't be read
+        config.flag_rules = rules_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+            .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+        // check the rules now, rather than finding out when processing
+        if let Err(msg) = flags::get_flag_rules(&config) {
+            self.integrated_dialog_alert(&format!("The flag rules weren't changed, because of a problem with them:\n{}", msg));
+            return;
+        }//end if the rules couldn't be read
+        self.set_config_store(&config);
+    }//end show_flag_rules_dialog(self)
+
+    /// Shows a window where the user can define the formula columns added
+    /// to the output sheets, such as a ratio of two other columns.
+    pub fn show_formula_columns_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(480,260)
+            .with_label("Formula Columns");
+        dialog_window.make_modal(true);
+        let mut formulas_buf = TextBuffer::default();
+        formulas_buf.set_text(&config.formula_columns.join("\n"));
+        let mut formulas_box = TextEditor::default()
+            .with_size(440,170)
+            .with_pos(20,30)
+            .with_label("Formula Columns:")
+            .with_align(Align::TopLeft);
+        formulas_box.set_tooltip("Each formula column is one line, as sheet: name = left op right, where op is +, -, *, or /,\nwith a space on each side. Each side can be a column of that sheet, or a number.\nExample: CSV_Stats: L/W Ratio = Avg Length / Avg Width");
+        formulas_box.set_frame(FrameType::GtkDownFrame);
+        formulas_box.set_scrollbar_align(Align::Right);
+        formulas_box.set_scrollbar_size(7);
+        formulas_box.set_buffer(formulas_buf);
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(175,215)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(235,215)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        config.formula_columns = formulas_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+            .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+        // check the formula columns now, rather than finding out when processing
+        for definition in config.formula_columns.iter() {
+            if let Err(msg) = process::parse_formula_column(definition) {
+                self.integrated_dialog_alert(&format!("The formula columns weren't changed, because of a problem with them:\n{}", msg));
+                return;
+            }//end if this formula column couldn't be read
+        }//end checking each formula column
+        self.set_config_store(&config);
+    }//end show_formula_columns_dialog(self)
+
+    /// Shows a dialog for defining groups of output columns, which are
+    /// written under a shared header above their own headers in excel
+    /// output, using config.csv_stat_column_group_template for the stat
+    /// columns of each csv column, and config.output_column_groups for
+    /// any other columns.  
+    /// Changes are saved to the config if the user clicks Ok and the
+    /// groups can be read.
+    pub fn show_column_groups_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(480,300)
+            .with_label("Column Groups");
+        dialog_window.make_modal(true);
+        let mut template_input = Input::default()
+            .with_size(440,25)
+            .with_pos(20,30)
+            .with_label("Header above the stat columns of each csv column:")
+            .with_align(Align::TopLeft);
+        template_input.set_value(&config.csv_stat_column_group_template);
+        template_input.set_tooltip("{col} is replaced with the name of the csv column, so {col} (mm) puts Avg Length and Std Length under Length (mm).\nLeave this empty to not group the stat columns.");
+        let mut groups_buf = TextBuffer::default();
+        groups_buf.set_text(&config.output_column_groups.join("\n"));
+        let mut groups_box = TextEditor::default()
+            .with_size(440,150)
+            .with_pos(20,85)
+            .with_label("Other column groups:")
+            .with_align(Align::TopLeft);
+        groups_box.set_tooltip("Each group is one line, as the header of the group, a colon, then its columns separated by commas.\nThese take the place of the groups from the header above.\nExample: Length (mm): Avg Length, Std Length, CV Length");
+        groups_box.set_frame(FrameType::GtkDownFrame);
+        groups_box.set_scrollbar_align(Align::Right);
+        groups_box.set_scrollbar_size(7);
+        groups_box.set_buffer(groups_buf);
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(175,255)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(235,255)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        config.csv_stat_column_group_template = template_input.value().trim().to_string();
+        config.output_column_groups = groups_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+            .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect();
+        // check the column groups now, since ones which can't be read are skipped when writing
+        for definition in config.output_column_groups.iter() {
+            if let Err(msg) = process::parse_column_group(definition) {
+                self.integrated_dialog_alert(&format!("The column groups weren't changed, because of a problem with them:\n{}", msg));
+                return;
+            }//end if this column group couldn't be read
+        }//end checking each column group
+        self.set_config_store(&config);
+    }//end show_column_groups_dialog(self)
+
+    /// Shows a window where the user can define recipient profiles,
+    /// each holding the conventions of someone the output is sent to,
+    /// such as their decimal places, units, and sheet names.
+    pub fn show_recipient_profiles_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let profiles = Rc::from(RefCell::from(config.recipient_profiles.clone()));
+
+        let mut dialog_window = Window::default()
+            .with_size(480,560)
+            .with_label("Recipient Profiles");
+        dialog_window.make_modal(true);
+        let mut profile_choice = Choice::default()
+            .with_size(440,25)
+            .with_pos(20,30)
+            .with_label("Profile:")
+            .with_align(Align::TopLeft);
+        profile_choice.set_tooltip("Choose a profile to edit, or (new profile) to add one.");
+        let mut name_box = Input::default()
+            .with_size(325,25)
+            .with_pos(20,85)
+            .with_label("Recipient Name:")
+            .with_align(Align::TopLeft);
+        name_box.set_tooltip("The name of the recipient, which is added to the name of the workbook written for them.\nSaving a profile with the name of another profile replaces that profile.");
+        let mut decimals_box = Input::default()
+            .with_size(100,25)
+            .with_pos(360,85)
+            .with_label("Decimal Places:")
+            .with_align(Align::TopLeft);
+        decimals_box.set_tooltip("The decimal places shown in every column.\nLeave this empty to keep the decimal places of each column.");
+        let mut text_boxes = Vec::new();
+        for (label, tooltip, y, h) in [
+            ("Unit Conversions:", "Each line converts an output column to other units, as column * number or column / number.\nExample: Avg Length * 0.03937", 140, 80),
+            ("Column Renames:", "Each line renames an output column, as old name -> new name, such as for another language.\nConversions use the old names.\nExample: Avg Length -> Longueur moyenne", 245, 100),
+            ("Sheet Renames:", "Each line renames an output sheet, as old name -> new name.\nExample: CSV_Stats -> Kernel Stats", 370, 80),
+        ] {
+            let mut text_box = TextEditor::default()
+                .with_size(440,h)
+                .with_pos(20,y)
+                .with_label(label)
+                .with_align(Align::TopLeft);
+            text_box.set_tooltip(tooltip);
+            text_box.set_frame(FrameType::GtkDownFrame);
+            text_box.set_scrollbar_align(Align::Right);
+            text_box.set_scrollbar_size(7);
+            text_box.set_buffer(TextBuffer::default());
+            text_boxes.push(text_box);
+        }//end adding a box for each list in a profile
+        let mut layout_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(20,475)
+            .with_label("Sheet Layout:")
+            .with_align(Align::TopLeft);
+        layout_choice.add_choice("Standard|Sheet per Metric");
+        layout_choice.set_tooltip("Standard writes the sheets the same as the main output.\nSheet per Metric splits CSV_Stats into a sheet for each csv stat column, such as Area,\nwith a row for each sample and a column for each stat, such as Avg and Std, followed by Flags.\nSheet Renames and Column Renames use the names of the new sheets and columns.");
+        let mut save_button = Button::default()
+            .with_size(100,30)
+            .with_pos(20,515)
+            .with_label("Save Profile");
+        save_button.set_frame(FrameType::GtkRoundUpFrame);
+        save_button.clear_visible_focus();
+        let mut delete_button = Button::default()
+            .with_size(110,30)
+            .with_pos(130,515)
+            .with_label("Delete Profile");
+        delete_button.set_frame(FrameType::GtkRoundUpFrame);
+        delete_button.clear_visible_focus();
+        let mut close_button = Button::default()
+            .with_size(70,30)
+            .with_pos(390,515)
+            .with_label("Close");
+        close_button.set_frame(FrameType::GtkRoundUpFrame);
+        close_button.clear_visible_focus();
+        dialog_window.end();
+
+        // lists the profiles in the dropdown, then (new profile), and chooses the one at choice_idx
+        let fill_choice = {
+            let profiles = profiles.clone();
+            move |profile_choice: &mut Choice, choice_idx: i32| {
+                profile_choice.clear();
+                for profile in profiles.borrow().iter() {profile_choice.add_choice(&profile.name.replace("/", "\\/"));}
+                profile_choice.add_choice("(new profile)");
+                profile_choice.set_value(choice_idx);
+                profile_choice.do_callback();
+            }//end moving for closure
+        };
+        profile_choice.set_callback({
+            let profiles = profiles.clone();
+            let mut name_box = name_box.clone();
+            let mut decimals_box = decimals_box.clone();
+            let mut text_boxes = text_boxes.clone();
+            let mut layout_choice = layout_choice.clone();
+            move |choice| {
+                let profile = profiles.borrow().get(choice.value() as usize).cloned().unwrap_or(RecipientProfile { name: "".to_string(), decimal_places: None, unit_conversions: Vec::new(), column_renames: Vec::new(), sheet_renames: Vec::new(), sheet_layout: SheetLayout::Standard });
+                name_box.set_value(&profile.name);
+                decimals_box.set_value(&profile.decimal_places.map(|decimal_places| decimal_places.to_string()).unwrap_or_default());
+                for (text_box, lines) in text_boxes.iter_mut().zip([&profile.unit_conversions, &profile.column_renames, &profile.sheet_renames]) {
+                    if let Some(mut buf) = text_box.buffer() {buf.set_text(&lines.join("\n"));}
+                }//end filling in each list of the profile
+                layout_choice.set_value(match profile.sheet_layout {
+                    SheetLayout::Standard => 0,
+                    SheetLayout::SheetPerMetric => 1,
+                });
+            }//end moving for closure
+        });
+        save_button.set_callback({
+            let profiles = profiles.clone();
+            let mut profile_choice = profile_choice.clone();
+            let fill_choice = fill_choice.clone();
+            let name_box = name_box.clone();
+            let decimals_box = decimals_box.clone();
+            let text_boxes = text_boxes.clone();
+            let layout_choice = layout_choice.clone();
+            move |_| {
+                let decimal_places = match decimals_box.value().trim() {
+                    "" => None,
+                    decimals => match decimals.parse::<usize>() {
+                        Ok(decimal_places) => Some(decimal_places),
+                        Err(_) => {dialog::alert_default("The decimal places must be a whole number, or left empty, so the profile wasn't saved."); return;},
+                    },
+                };
+                let get_lines = |text_box: &TextEditor| -> Vec<String> {
+                    text_box.buffer().map(|buf| buf.text()).unwrap_or_default()
+                        .lines().map(|line| line.trim().to_string()).filter(|line| !line.is_empty()).collect()
+                };
+                let profile = RecipientProfile {
+                    name: name_box.value().trim().to_string(),
+                    decimal_places,
+                    unit_conversions: get_lines(&text_boxes[0]),
+                    column_renames: get_lines(&text_boxes[1]),
+                    sheet_renames: get_lines(&text_boxes[2]),
+                    sheet_layout: match layout_choice.value() {
+                        1 => SheetLayout::SheetPerMetric,
+                        _ => SheetLayout::Standard,
+                    },
+                };
+                if profile.name.is_empty() {dialog::alert_default("Please give the recipient a name before saving the profile."); return;}
+                // check the conversions and renames now, rather than finding out when processing
+                if let Err(msg) = process::apply_recipient_profile(&Vec::new(), &profile) {
+                    dialog::alert_default(&format!("The profile wasn't saved, because of a problem with it:\n{}", msg));
+                    return;
+                }//end if the profile couldn't be read
+                let choice_idx = {
+                    let mut profiles = profiles.borrow_mut();
+                    let chosen_idx = profile_choice.value() as usize;
+                    match profiles.iter().position(|other| other.name == profile.name) {
+                        Some(same_name_idx) => {profiles[same_name_idx] = profile; if chosen_idx < profiles.len() && chosen_idx != same_name_idx {profiles.remove(chosen_idx);}},
+                        None if chosen_idx < profiles.len() => profiles[chosen_idx] = profile,
+                        None => profiles.push(profile),
+                    }//end matching which profile to replace
+                    profiles.iter().position(|other| other.name == name_box.value().trim()).unwrap_or(0) as i32
+                };
+                fill_choice(&mut profile_choice, choice_idx);
+            }//end moving for closure
+        });
+        delete_button.set_callback({
+            let profiles = profiles.clone();
+            let mut profile_choice = profile_choice.clone();
+            let fill_choice = fill_choice.clone();
+            move |_| {
+                let chosen_idx = profile_choice.value() as usize;
+                if chosen_idx >= profiles.borrow().len() {return;}
+                let profile_name = profiles.borrow()[chosen_idx].name.clone();
+                if dialog::choice2_default(&format!("Are you sure you want to delete the recipient profile \"{}\"?", profile_name), "Yes", "No", "") != Some(0) {return;}
+                profiles.borrow_mut().remove(chosen_idx);
+                fill_choice(&mut profile_choice, 0);
+            }//end moving for closure
+        });
+        close_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        fill_choice(&mut profile_choice, 0);
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        config.recipient_profiles = profiles.borrow().clone();
+        let profile_names: Vec<String> = config.recipient_profiles.iter().map(|profile| profile.name.clone()).collect();
+        config.selected_recipient_profiles.retain(|profile_name| profile_names.contains(profile_name));
+        self.set_config_store(&config);
+    }//end show_recipient_profiles_dialog(self)
+
+    /// Shows a window listing the recipient profiles, so the user can
+    /// check the recipients to also write the output for, in their
+    /// conventions. The profiles checked last time are checked already.  
+    /// Returns the names of the checked profiles, or None if the user
+    /// cancels processing.
+    pub fn show_recipient_select_dialog(&mut self) -> Option<Vec<String>> {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(360,300)
+            .with_label("Choose Recipients");
+        dialog_window.make_modal(true);
+        let mut recipient_label = Frame::default()
+            .with_size(320,25)
+            .with_pos(20,10)
+            .with_label("Check any recipients to also write the output for:")
+            .with_align(Align::Inside | Align::Left);
+        recipient_label.set_frame(FrameType::NoBox);
+        let mut recipient_browser = CheckBrowser::default()
+            .with_size(320,200)
+            .with_pos(20,40);
+        recipient_browser.set_tooltip("A workbook is also written for each checked recipient, next to the output file,\nwith the decimal places, units, and names of their profile.");
+        for profile in config.recipient_profiles.iter() {recipient_browser.add(&profile.name, config.selected_recipient_profiles.contains(&profile.name));}
+        let mut ok_button = Button::default()
+            .with_size(110,30)
+            .with_pos(20,255)
+            .with_label("Write Output");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(140,255)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if !*clicked_ok.borrow() {return None;}
+        let mut selected_profiles = Vec::new();
+        for (index, profile) in config.recipient_profiles.iter().enumerate() {
+            if recipient_browser.checked(index as i32 + 1) {selected_profiles.push(profile.name.clone());}
+        }//end checking each recipient
+        config.selected_recipient_profiles = selected_profiles.clone();
+        self.set_config_store(&config);
+        Some(selected_profiles)
+    }//end show_recipient_select_dialog(self)
+
+    /// Shows a window where the user can choose, for each category of
+    /// warning, whether processing stops, asks first, or just logs it.
+    pub fn show_warning_policy_dialog(&mut self) {
+        let mut config = self.get_config_store();
+        let clicked_ok = Rc::from(RefCell::from(false));
+        // the actions in the order they're listed in each dropdown
+        let actions = [WarningAction::Abort, WarningAction::Prompt, WarningAction::Log];
+
+        let mut dialog_window = Window::default()
+            .with_size(320,80 + 50 * WarningCategory::ALL.len() as i32)
+            .with_label("Warning Policies");
+        dialog_window.make_modal(true);
+        let mut action_choices = Vec::new();
+        for (idx, category) in WarningCategory::ALL.iter().enumerate() {
+            let mut action_choice = Choice::default()
+                .with_size(280,25)
+                .with_pos(20,30 + 50 * idx as i32)
+                .with_label(&format!("{}:", category.get_name()))
+                .with_align(Align::TopLeft);
+            action_choice.set_tooltip("Abort stops processing and tells you why.\nPrompt asks whether to process anyway.\nLog only prints the warning, and processing goes on.");
+            action_choice.add_choice("Abort|Prompt|Log");
+            action_choice.set_value(actions.iter().position(|action| *action == config.get_warning_action(*category)).unwrap_or(1) as i32);
+            action_choices.push(action_choice);
+        }//end adding a dropdown for each category
+        let button_y = dialog_window.h() - 45;
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(95,button_y)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(155,button_y)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+        if !*clicked_ok.borrow() {return;}
+
+        for (category, action_choice) in WarningCategory::ALL.iter().zip(action_choices.iter()) {
+            config.set_warning_action(*category, actions.get(action_choice.value() as usize).copied().unwrap_or(WarningAction::Prompt));
+        }//end setting the action for each category
+        self.set_config_store(&config);
+    }//end show_warning_policy_dialog(self)
+
+    /// Sends message to main as if it came from the matching button, such
+    /// as for a file chosen with the Open Data File(s) button once its kind
+    /// is known, or a step of a replayed recording.  
+    /// For csv, xml, and output files, the file name is shown in the box
+    /// next to the matching button.
+    pub fn send_file_message(&mut self, message: InterfaceMessage) {
+        let (file_buf, file_path) = match &message {
+            InterfaceMessage::CSVInputFile(file_path) => (self.ux_input_csv_txt.borrow().buffer(), file_path),
+            InterfaceMessage::XMLInputFile(file_path) => (self.ux_input_xml_txt.borrow().buffer(), file_path),
+            InterfaceMessage::OutputFile(file_path) => (self.ux_output_file_txt.borrow().buffer(), file_path),
+            _ => {self.msg_sender.send(message); return;},
+        };//end matching which box shows the file name
+        if let (Some(mut file_buf), Some(file_name)) = (file_buf, file_path.file_name()) {
+            file_buf.set_text(&file_name.to_string_lossy());
+        }//end if we can show the file name
+        self.msg_sender.send(message);
+    }//end send_file_message(self, message)
+
+    /// Shows the file dialogs needed to replay a recording, one for the
+    /// recording, one for the new input files, and one for the output file.  
+    /// Returns (recording, new input files, output file), or None if the
+    /// user cancels any of them.
+    pub fn choose_replay_files(&mut self) -> Option<(PathBuf, Vec<PathBuf>, PathBuf)> {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("Recordings\t*.json");
+        dialog.set_title("Please select the recording to replay");
+        dialog.show();
+        let recording_path = dialog.filename();
+        if recording_path.as_os_str().is_empty() {return None;}
+
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseMultiFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("Data Files\t*.{csv,xml,xlsx}\nAll Files\t*");
+        dialog.set_title("Please select the new input files, in the order the recording loads them");
+        dialog.show();
+        let input_files: Vec<PathBuf> = dialog.filenames().into_iter().filter(|path| !path.as_os_str().is_empty()).collect();
+        if input_files.len() == 0 {return None;}
+
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+        dialog.set_title("Please specify the output file for the replay.");
+        dialog.show();
+        let output_path = dialog.filename();
+        if output_path.as_os_str().is_empty() {return None;}
+        Some((recording_path, input_files, output_path))
+    }//end choose_replay_files(self)
+
+    /// Shows a file dialog for choosing where to save a recording.  
+    /// Returns None if the user cancels.
+    pub fn choose_recording_save_path(&mut self) -> Option<PathBuf> {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+        dialog.set_filter("Recordings\t*.json");
+        dialog.set_title("Please choose where to save the recording.");
+        dialog.show();
+        let mut recording_path = dialog.filename();
+        if recording_path.as_os_str().is_empty() {return None;}
+        if recording_path.extension().is_none() {recording_path.set_extension("json");}
+        Some(recording_path)
+    }//end choose_recording_save_path(self)
+
+    /// Asks the user where to save a run bundle, starting with default_path.  
+    /// Returns None if the user cancels.
+    pub fn choose_bundle_save_path(&mut self, default_path: &PathBuf) -> Option<PathBuf> {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+        dialog.set_filter("Zip Files\t*.zip");
+        if let Some(parent_dir) = default_path.parent() {
+            if let Err(error) = dialog.set_directory(&parent_dir) {log::warn!("Couldn't start the file dialog in the output folder.\n{}", error);}
+        }//end if we know which folder to start in
+        dialog.set_preset_file(&default_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default());
+        dialog.set_title("Please choose where to save the run bundle.");
+        dialog.show();
+        let mut bundle_path = dialog.filename();
+        if bundle_path.as_os_str().is_empty() {return None;}
+        if bundle_path.extension().is_none() {bundle_path.set_extension("zip");}
+        Some(bundle_path)
+    }//end choose_bundle_save_path(self, default_path)
+
+    /// Shows the command palette, where the user can search every action
+    /// by name and run it, then does whatever the chosen command needs.  
+    /// Actions which change settings are left out in operator mode,
+    /// the same as in the Process menu.
+    pub fn show_command_palette(&mut self) {
+        let operator_mode = self.config_store.borrow().operator_mode_enabled;
+        let mut commands = vec![
+            PaletteCommand::new("Open Data File(s)", PaletteAction::OpenDataFiles),
+            PaletteCommand::new("Open Recent File", PaletteAction::OpenRecentFile),
+            PaletteCommand::new("Open CSV Input File", PaletteAction::OpenCsv),
+            PaletteCommand::new("Open XML Input File", PaletteAction::OpenXml),
+            PaletteCommand::new("Choose Output File", PaletteAction::ChooseOutput),
+            PaletteCommand::new("Process Data", PaletteAction::Send(InterfaceMessage::ProcessSum)),
+            PaletteCommand::new("Explore Loaded Data", PaletteAction::Send(InterfaceMessage::ExploreData)),
+            PaletteCommand::new("View Results", PaletteAction::Send(InterfaceMessage::ViewResults)),
+            PaletteCommand::new("Pin Results for Comparison", PaletteAction::Send(InterfaceMessage::PinResults)),
+            PaletteCommand::new("Compare with Pinned Results", PaletteAction::Send(InterfaceMessage::CompareResults)),
+            PaletteCommand::new("Print Summary", PaletteAction::Send(InterfaceMessage::PrintSummary)),
+            PaletteCommand::new("Email Summary Text", PaletteAction::Send(InterfaceMessage::ShowRunSummary)),
+            PaletteCommand::new("Export Run Bundle", PaletteAction::Send(InterfaceMessage::ExportRunBundle)),
+            PaletteCommand::new("View Run Ledger", PaletteAction::Send(InterfaceMessage::ViewRunLedger)),
+            PaletteCommand::new("View Log", PaletteAction::Send(InterfaceMessage::ViewLog)),
+            PaletteCommand::new("Verify Output File", PaletteAction::VerifyOutput),
+            PaletteCommand::new("Open Existing Summary", PaletteAction::OpenSummary),
+            PaletteCommand::new("Merge Summary Workbooks", PaletteAction::MergeSummaries),
+            PaletteCommand::new("Batch Process Folder", PaletteAction::BatchProcess),
+            PaletteCommand::new("Summarize Large CSV File", PaletteAction::StreamLargeCsv),
+            PaletteCommand::new("Replay Recording on New Files", PaletteAction::Send(InterfaceMessage::ReplayRecording)),
+            PaletteCommand::new("Collapse or Expand Config Panel", PaletteAction::Send(InterfaceMessage::ToggleConfigPanel)),
+            PaletteCommand::new("Help", PaletteAction::Help),
+        ];
+        match operator_mode {
+            true => commands.push(PaletteCommand::new("Switch to Analyst Mode", PaletteAction::Send(InterfaceMessage::SwitchUserMode))),
+            false => {
+                commands.append(&mut vec![
+                    PaletteCommand::new("Edit Column Layout", PaletteAction::Send(InterfaceMessage::EditColumnLayout)),
+                    PaletteCommand::new("Edit Processing Pipeline", PaletteAction::Send(InterfaceMessage::EditPipeline)),
+                    PaletteCommand::new("Edit Column Aliases", PaletteAction::Send(InterfaceMessage::EditHeaderAliases)),
+                    PaletteCommand::new("Edit Report Metrics", PaletteAction::Send(InterfaceMessage::EditMetrics)),
+                    PaletteCommand::new("Edit Flag Rules", PaletteAction::Send(InterfaceMessage::EditFlagRules)),
+                    PaletteCommand::new("Edit Formula Columns", PaletteAction::Send(InterfaceMessage::EditFormulaColumns)),
+                    PaletteCommand::new("Edit Column Groups", PaletteAction::Send(InterfaceMessage::EditColumnGroups)),
+                    PaletteCommand::new("Edit Warning Policies", PaletteAction::Send(InterfaceMessage::EditWarningPolicies)),
+                    PaletteCommand::new("Edit Recipient Profiles", PaletteAction::Send(InterfaceMessage::EditRecipientProfiles)),
+                    PaletteCommand::new("Reprocess Samples", PaletteAction::Send(InterfaceMessage::ReprocessSamples)),
+                    PaletteCommand::new("Start or Stop Recording Session", PaletteAction::Send(InterfaceMessage::ToggleRecording)),
+                    PaletteCommand::new("Save Preset", PaletteAction::Send(InterfaceMessage::SavePreset)),
+                    PaletteCommand::new("Choose Preset", PaletteAction::Send(InterfaceMessage::ConfigReset)),
+                    PaletteCommand::new("Manage Presets", PaletteAction::Send(InterfaceMessage::ManagePresets)),
+                    PaletteCommand::new("Save Filter Set", PaletteAction::Send(InterfaceMessage::SaveFilterSet)),
+                    PaletteCommand::new("Delete Filter Set", PaletteAction::Send(InterfaceMessage::DeleteFilterSet)),
+                    PaletteCommand::new("Notification Settings", PaletteAction::Send(InterfaceMessage::NotificationSettings)),
+                    PaletteCommand::new("Scheduled Processing Settings", PaletteAction::Send(InterfaceMessage::ScheduleSettings)),
+                    PaletteCommand::new("Switch to Operator Mode", PaletteAction::Send(InterfaceMessage::SwitchUserMode)),
+                ]);
+                // merged summaries can only be pushed by builds with the google-sheets feature
+                if cfg!(feature = "google-sheets") {commands.push(PaletteCommand::new("Google Sheets Settings", PaletteAction::Send(InterfaceMessage::GoogleSheetsSettings)));}
+                for preset_name in config_store::get_preset_names() {
+                    commands.push(PaletteCommand::new(&format!("Switch Preset: {}", preset_name), PaletteAction::Send(InterfaceMessage::SwitchPreset(preset_name))));
+                }//end adding a command for each preset
+            },
+        }//end matching which commands can be used in this mode
+
+        match palette::show_command_palette(commands) {
+            Some(PaletteAction::Send(message)) => self.msg_sender.send(message),
+            Some(PaletteAction::OpenDataFiles) => GUI::choose_data_files(&self.msg_sender),
+            Some(PaletteAction::OpenRecentFile) => GUI::choose_recent_file(&self.msg_sender, &self.recent_files.borrow()),
+            Some(PaletteAction::OpenCsv) => {
+                let mut input_csv_buf = self.ux_input_csv_txt.borrow().buffer().unwrap_or_default();
+                if let Err(err_message) = GUI::create_io_dialog(&self.msg_sender, "CSVInputFile", &mut input_csv_buf, dialog::NativeFileChooserType::BrowseFile, dialog::NativeFileChooserOptions::UseFilterExt, "*.{csv,xlsx}", "Please select a csv or xlsx input file") {
+                    log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                }//end if we got an error
+            },
+            Some(PaletteAction::OpenXml) => {
+                let mut input_xml_buf = self.ux_input_xml_txt.borrow().buffer().unwrap_or_default();
+                if let Err(err_message) = GUI::create_io_dialog(&self.msg_sender, "XMLInputFile", &mut input_xml_buf, dialog::NativeFileChooserType::BrowseFile, dialog::NativeFileChooserOptions::UseFilterExt, "*.xml", "Please select an xml input file") {
+                    log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                }//end if we got an error
+            },
+            Some(PaletteAction::ChooseOutput) => {
+                let mut output_file_buf = self.ux_output_file_txt.borrow().buffer().unwrap_or_default();
+                if let Err(err_message) = GUI::create_io_dialog(&self.msg_sender, "OutputFile", &mut output_file_buf, dialog::NativeFileChooserType::BrowseSaveFile, dialog::NativeFileChooserOptions::SaveAsConfirm, "", "Please specify the output file.") {
+                    log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                }//end if we got an error
+            },
+            Some(PaletteAction::VerifyOutput) => GUI::choose_output_to_verify(&self.msg_sender),
+            Some(PaletteAction::OpenSummary) => GUI::choose_summary_to_open(&self.msg_sender),
+            Some(PaletteAction::MergeSummaries) => GUI::choose_summaries_to_merge(&self.msg_sender),
+            Some(PaletteAction::BatchProcess) => GUI::choose_batch_folder(&self.msg_sender),
+            Some(PaletteAction::StreamLargeCsv) => GUI::choose_large_csv(&self.msg_sender),
+            Some(PaletteAction::Help) => help::show_help_dialog("header"),
+            None => {},
+        }//end matching what the chosen command needs
+    }//end show_command_palette(self)
+
+    /// Deletes the filter set chosen in the Filter Set dropdown, after
+    /// asking the user to confirm.
+    pub fn delete_filter_set(&mut self) {
+        let mut config = self.get_config_store();
+        if config.active_filter_set.is_empty() {
+            self.integrated_dialog_alert("Please choose a filter set in the Filter Set dropdown first.");
+            return;
+        }//end if no filter set is chosen
+        if !self.integrated_dialog_yes_no(&format!("Are you sure you want to delete the filter set \"{}\"?", config.active_filter_set)) {return;}
+        config.filter_sets.retain(|filter_set| filter_set.name != config.active_filter_set);
+        config.active_filter_set = "".to_string();
+        self.set_config_store(&config);
+    }//end delete_filter_set(self)
+
+    /// Shows a window where the user can select any number of the
+    /// given sample ids, such as to choose which samples to reprocess.  
+    /// Returns the selected sample ids, or an empty Vec if the user cancels.
+    pub fn show_sample_select_dialog(&mut self, sample_ids: &Vec<String>) -> Vec<String> {
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(300,360)
+            .with_label("Reprocess Samples");
+        dialog_window.make_modal(true);
+        let mut sample_label = Frame::default()
+            .with_size(260,25)
+            .with_pos(20,10)
+            .with_label("Select the samples to reprocess:")
+            .with_align(Align::Inside | Align::Left);
+        sample_label.set_frame(FrameType::NoBox);
+        let mut sample_browser = MultiBrowser::default()
+            .with_size(260,230)
+            .with_pos(20,40);
+        sample_browser.set_tooltip("Click to select a sample.\nHold Ctrl or Shift to select more than one sample.");
+        for sample_id in sample_ids.iter() {sample_browser.add(sample_id);}
+        let mut select_all_button = Button::default()
+            .with_size(90,30)
+            .with_pos(20,280)
+            .with_label("Select All");
+        select_all_button.set_frame(FrameType::GtkRoundUpFrame);
+        select_all_button.clear_visible_focus();
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(60,320)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(120,320)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        select_all_button.set_callback({
+            let mut browser = sample_browser.clone();
+            move |_| {
+                for line in 1..=browser.size() {browser.select(line);}
+            }//end moving for closure
+        });
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        let mut selected_ids = Vec::new();
+        if *clicked_ok.borrow() {
+            for line in 1..=sample_browser.size() {
+                if sample_browser.selected(line) {
+                    if let Some(sample_id) = sample_browser.text(line) {selected_ids.push(sample_id);}
+                }//end if this line is selected
+            }//end checking each line of the browser
+        }//end if user clicked ok
+        selected_ids
+    }//end show_sample_select_dialog(self, sample_ids)
+
+    /// Shows a window with a dropdown for each of missing_headers, so the
+    /// user can choose which of file_headers to use for each column their
+    /// settings need but the file doesn't have.  
+    /// Returns the chosen columns as (header in the file, needed header),
+    /// leaving out any left on (none), or None if the user cancels.
+    pub fn show_header_mapping_dialog(&mut self, missing_headers: &Vec<String>, file_headers: &Vec<String>) -> Option<Vec<(String, String)>> {
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let dialog_height = 100 + 50 * missing_headers.len() as i32;
+        let mut dialog_window = Window::default()
+            .with_size(420,dialog_height)
+            .with_label("Choose Columns");
+        dialog_window.make_modal(true);
+        let mut mapping_label = Frame::default()
+            .with_size(380,25)
+            .with_pos(20,10)
+            .with_label("Choose the column in the file to use for each needed column:")
+            .with_align(Align::Inside | Align::Left);
+        mapping_label.set_frame(FrameType::NoBox);
+        let mut header_choices = Vec::new();
+        for (index, missing_header) in missing_headers.iter().enumerate() {
+            let mut header_choice = Choice::default()
+                .with_size(380,25)
+                .with_pos(20,60 + 50 * index as i32)
+                .with_label(&format!("Column to use as {}:", missing_header))
+                .with_align(Align::TopLeft);
+            header_choice.add_choice("(none)");
+            for file_header in file_headers.iter() {header_choice.add_choice(&file_header.replace("/", "\\/"));}
+            header_choice.set_value(0);
+            header_choices.push(header_choice);
+        }//end adding a dropdown for each missing header
+        let mut ok_button = Button::default()
+            .with_size(50,30)
+            .with_pos(20,dialog_height - 45)
+            .with_label("Ok");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(80,dialog_height - 45)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if !*clicked_ok.borrow() {return None;}
+        let mut chosen_headers = Vec::new();
+        for (missing_header, header_choice) in missing_headers.iter().zip(header_choices.iter()) {
+            // the first choice is (none)
+            if header_choice.value() < 1 {continue;}
+            if let Some(file_header) = file_headers.get(header_choice.value() as usize - 1) {
+                chosen_headers.push((file_header.clone(), missing_header.clone()));
+            }//end if a column was chosen
+        }//end getting the column chosen for each missing header
+        Some(chosen_headers)
+    }//end show_header_mapping_dialog(self, missing_headers, file_headers)
+
+    /// Shows a window listing the given flagged samples, as (sample id, why it was flagged),
+    /// so the user can check the ones to exclude before the output is written.  
+    /// Returns the sample ids to exclude, or None if the user cancels processing.
+    pub fn show_exclusion_dialog(&mut self, flagged: &Vec<(String, String)>) -> Option<Vec<String>> {
+        let clicked_ok = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(460,360)
+            .with_label("Review Flagged Samples");
+        dialog_window.make_modal(true);
+        let mut flagged_label = Frame::default()
+            .with_size(420,25)
+            .with_pos(20,10)
+            .with_label("Check any flagged samples to exclude from the output:")
+            .with_align(Align::Inside | Align::Left);
+        flagged_label.set_frame(FrameType::NoBox);
+        let mut flagged_browser = CheckBrowser::default()
+            .with_size(420,260)
+            .with_pos(20,40);
+        flagged_browser.set_tooltip("Checked samples will be left out of every output sheet.\nExcluded samples are listed in the Run_Info sheet.");
+        for (sample_id, reasons) in flagged.iter() {flagged_browser.add(&format!("{} - {}", sample_id, reasons), false);}
+        let mut ok_button = Button::default()
+            .with_size(110,30)
+            .with_pos(20,315)
+            .with_label("Write Output");
+        ok_button.set_frame(FrameType::GtkRoundUpFrame);
+        ok_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(140,315)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        ok_button.set_callback({
+            let mut window = dialog_window.clone();
+            let ok_ref = (&clicked_ok).clone();
+            move |_| {
+                *(ok_ref.borrow_mut()) = true;
+                window.hide();
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if !*clicked_ok.borrow() {return None;}
+        let mut excluded_ids = Vec::new();
+        for (index, (sample_id, _)) in flagged.iter().enumerate() {
+            if flagged_browser.checked(index as i32 + 1) {excluded_ids.push(sample_id.clone());}
+        }//end checking each flagged sample
+        Some(excluded_ids)
+    }//end show_exclusion_dialog(self, flagged)
+
+    /// Shows a window for browsing the runs in the run ledger, newest
+    /// first, with the details of the selected run, such as its input files
+    /// and metrics. The output of the selected run can be opened here, like
+    /// Open Existing Summary, or shown in its folder.  
+    /// problems, from ledger::read_ledger(), are shown above the details.
+    pub fn show_ledger_dialog(&mut self, entries: &Vec<LedgerEntry>, problems: &Vec<String>) {
+        let entries: Rc<Vec<LedgerEntry>> = Rc::from(entries.iter().rev().cloned().collect::<Vec<LedgerEntry>>());
+
+        let mut dialog_window = Window::default()
+            .with_size(620,480)
+            .with_label("Run Ledger");
+        dialog_window.make_modal(true);
+        let mut ledger_label = Frame::default()
+            .with_size(580,25)
+            .with_pos(20,10)
+            .with_label(&format!("{} runs, newest first. Select a run to see its details:", entries.len()))
+            .with_align(Align::Inside | Align::Left);
+        ledger_label.set_frame(FrameType::NoBox);
+        let mut run_browser = HoldBrowser::default()
+            .with_size(580,200)
+            .with_pos(20,40);
+        for entry in entries.iter() {
+            let output_name = PathBuf::from(&entry.output_path).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            run_browser.add(&format!("{}  -  {}", entry.timestamp, output_name));
+        }//end listing each run
+        let mut details_buf = TextBuffer::default();
+        let problems_text = match problems.len() {
+            0 => String::new(),
+            _ => format!("The ledger has problems, so some runs might have been changed or removed:\n{}\n\n", problems.join("\n")),
+        };
+        details_buf.set_text(&problems_text);
+        let mut details_box = TextDisplay::default()
+            .with_size(580,180)
+            .with_pos(20,250);
+        details_box.set_frame(FrameType::GtkDownFrame);
+        details_box.wrap_mode(fltk::text::WrapMode::AtBounds, 1);
+        details_box.set_scrollbar_align(Align::Right);
+        details_box.set_scrollbar_size(10);
+        details_box.set_buffer(details_buf.clone());
+        let mut open_button = Button::default()
+            .with_size(120,30)
+            .with_pos(20,440)
+            .with_label("Open Summary");
+        open_button.set_tooltip("Opens the output of the selected run here, like Open Existing Summary.");
+        open_button.set_frame(FrameType::GtkRoundUpFrame);
+        open_button.clear_visible_focus();
+        let mut folder_button = Button::default()
+            .with_size(120,30)
+            .with_pos(150,440)
+            .with_label("Show in Folder");
+        folder_button.set_frame(FrameType::GtkRoundUpFrame);
+        folder_button.clear_visible_focus();
+        let mut close_button = Button::default()
+            .with_size(70,30)
+            .with_pos(280,440)
+            .with_label("Close");
+        close_button.set_frame(FrameType::GtkRoundUpFrame);
+        close_button.clear_visible_focus();
+        dialog_window.end();
+
+        run_browser.set_callback({
+            let entries = (&entries).clone();
+            let mut details_buf = details_buf.clone();
+            move |browser| {
+                if browser.value() < 1 {return;}
+                let entry = &entries[browser.value() as usize - 1];
+                let mut details = format!("{}Finished: {}\nOutput: {}\n", problems_text, entry.timestamp, entry.output_path);
+                for input in entry.inputs.iter() {details.push_str(&format!("Input: {} (hash {})\n", input.path, input.hash));}
+                for (metric_name, value) in entry.metrics.iter() {details.push_str(&format!("{}: {}\n", metric_name, value));}
+                details.push_str(&format!("Settings hash: {}\nChecksum: {}", entry.config_hash, entry.checksum));
+                details_buf.set_text(&details);
+            }//end moving for closure
+        });
+        open_button.set_callback({
+            let entries = (&entries).clone();
+            let run_browser = run_browser.clone();
+            let sender = self.msg_sender.clone();
+            let mut window = dialog_window.clone();
+            move |_| {
+                if run_browser.value() < 1 {GUI::show_alert("Please select a run to open."); return;}
+                let output_path = PathBuf::from(&entries[run_browser.value() as usize - 1].output_path);
+                if !output_path.exists() {GUI::show_alert(&format!("The output \"{}\" doesn't exist anymore.", output_path.to_string_lossy())); return;}
+                sender.send(InterfaceMessage::OpenSummary(output_path));
+                window.hide();
+            }//end moving for closure
+        });
+        folder_button.set_callback({
+            let entries = (&entries).clone();
+            let run_browser = run_browser.clone();
+            move |_| {
+                if run_browser.value() < 1 {GUI::show_alert("Please select a run to show."); return;}
+                let output_path = PathBuf::from(&entries[run_browser.value() as usize - 1].output_path);
+                if let Err(error) = opener::reveal(&output_path) {GUI::show_alert(&format!("Couldn't show \"{}\" in its folder.\n{}", output_path.to_string_lossy(), error));}
+            }//end moving for closure
+        });
+        close_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+    }//end show_ledger_dialog(self, entries, problems)
+
+    /// Shows a multi-step window walking the user through setting up
+    /// their configuration for the first time.  
+    /// The steps are choosing a preset, choosing the key csv and xml
+    /// headers, and optionally test-loading a csv file to check that
+    /// the chosen headers can be found.  
+    /// Returns the new config if the user clicks Finish, or None if they cancel.
+    pub fn show_setup_wizard(&mut self) -> Option<ConfigStore> {
+        let mut preset_names = config_store::get_preset_names();
+        preset_names.push("Other".to_string());
+        let get_preset = {
+            let preset_names = preset_names.clone();
+            move |preset_idx: i32| -> ConfigStore {
+                preset_names.get(preset_idx as usize)
+                    .and_then(|preset_name| config_store::get_preset_config(preset_name))
+                    .unwrap_or_default()
+            }//end moving for closure
+        };
+        let working_config = Rc::from(RefCell::from(get_preset(0)));
+        let current_page = Rc::from(RefCell::from(0));
+        let clicked_finish = Rc::from(RefCell::from(false));
+
+        let mut dialog_window = Window::default()
+            .with_size(420,330)
+            .with_label("First Time Setup");
+        dialog_window.make_modal(true);
+        let mut step_label = Frame::default()
+            .with_size(400,25)
+            .with_pos(10,10)
+            .with_align(Align::Inside.union(Align::Left));
+        step_label.set_label_size(16);
+
+        // page 1, choosing a preset
+        let page1 = Group::default()
+            .with_size(400,230)
+            .with_pos(10,45);
+        let mut preset_choice = Choice::default()
+            .with_size(200,25)
+            .with_pos(150,55)
+            .with_label("Config Preset:");
+        for preset_name in preset_names.iter() {preset_choice.add_choice(&preset_name.replace("/", "\\/"));}
+        preset_choice.set_value(0);
+        let _preset_info = Frame::default()
+            .with_size(380,150)
+            .with_pos(20,100)
+            .with_label("Presets set up which output is produced, which classes\nare filtered for, and which csv columns have statistics.\n\nWheat: Stats for Sound kernels, with XML sieve data.\nSorghum: Stats for Sorghum kernels, with class percents.\nCorn: Stats for Sound kernels, with class percents and sieves.\nOther: Stats for Sound kernels, with all output enabled.\nAny presets you've saved are also listed.\nYou can change any of these settings later.")
+            .with_align(Align::Inside.union(Align::TopLeft));
+        page1.end();
+
+        // page 2, choosing key headers
+        let mut page2 = Group::default()
+            .with_size(400,230)
+            .with_pos(10,45);
+        let csv_sample_id_buf = TextBuffer::default();
+        let mut csv_sample_id_box = TextEditor::default()
+            .with_size(220,30)
+            .with_pos(20,70)
+            .with_label("Column to read as sample-id in csv:")
+            .with_align(Align::TopLeft);
+        csv_sample_id_box.set_tooltip("Default is \"external-sample-id\" without quotation marks.");
+        csv_sample_id_box.set_frame(FrameType::GtkDownFrame);
+        csv_sample_id_box.set_scrollbar_align(Align::Bottom);
+        csv_sample_id_box.set_scrollbar_size(7);
+        csv_sample_id_box.set_buffer(csv_sample_id_buf.clone());
+        let csv_class_buf = TextBuffer::default();
+        let mut csv_class_box = TextEditor::default()
+            .with_size(220,30)
+            .with_pos(20,130)
+            .with_label("Column holding class in csv:")
+            .with_align(Align::TopLeft);
+        csv_class_box.set_tooltip("Default is \"raw-filtered-as\" without quotation marks.");
+        csv_class_box.set_frame(FrameType::GtkDownFrame);
+        csv_class_box.set_scrollbar_align(Align::Bottom);
+        csv_class_box.set_scrollbar_size(7);
+        csv_class_box.set_buffer(csv_class_buf.clone());
+        let xml_sample_id_buf = TextBuffer::default();
+        let mut xml_sample_id_box = TextEditor::default()
+            .with_size(220,30)
+            .with_pos(20,190)
+            .with_label("Tag to read as sample-id in xml:")
+            .with_align(Align::TopLeft);
+        xml_sample_id_box.set_tooltip("Default is \"reference\" without quotation marks.");
+        xml_sample_id_box.set_frame(FrameType::GtkDownFrame);
+        xml_sample_id_box.set_scrollbar_align(Align::Bottom);
+        xml_sample_id_box.set_scrollbar_size(7);
+        xml_sample_id_box.set_buffer(xml_sample_id_buf.clone());
+        page2.end();
+        page2.hide();
+
+        // page 3, test loading a csv file
+        let mut page3 = Group::default()
+            .with_size(400,230)
+            .with_pos(10,45);
+        let mut test_load_btn = Button::default()
+            .with_size(150,30)
+            .with_pos(20,55)
+            .with_label("Test Load a CSV...");
+        test_load_btn.set_frame(FrameType::GtkRoundUpFrame);
+        test_load_btn.set_tooltip("Optional. Choose a csv file from the C-Grain to check that the chosen columns can be found in it.");
+        test_load_btn.clear_visible_focus();
+        let mut test_load_buf = TextBuffer::default();
+        test_load_buf.set_text("Optionally, load a csv file from the C-Grain to check that\nthe columns you chose can be found.\nClick Finish when you're done.");
+        let mut test_load_box = TextDisplay::default()
+            .with_size(380,170)
+            .with_pos(20,95);
+        test_load_box.set_frame(FrameType::GtkDownFrame);
+        test_load_box.wrap_mode(fltk::text::WrapMode::AtBounds, 1);
+        test_load_box.set_scrollbar_align(Align::Right);
+        test_load_box.set_scrollbar_size(10);
+        test_load_box.set_buffer(test_load_buf.clone());
+        page3.end();
+        page3.hide();
+
+        let mut back_button = Button::default()
+            .with_size(70,30)
+            .with_pos(170,285)
+            .with_label("Back");
+        back_button.set_frame(FrameType::GtkRoundUpFrame);
+        back_button.clear_visible_focus();
+        back_button.deactivate();
+        let mut next_button = Button::default()
+            .with_size(70,30)
+            .with_pos(250,285)
+            .with_label("Next");
+        next_button.set_frame(FrameType::GtkRoundUpFrame);
+        next_button.clear_visible_focus();
+        let mut cancel_button = Button::default()
+            .with_size(70,30)
+            .with_pos(330,285)
+            .with_label("Cancel");
+        cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+        cancel_button.clear_visible_focus();
+        dialog_window.end();
+
+        let step_titles = ["Step 1 of 3: Choose a Preset", "Step 2 of 3: Choose Key Columns", "Step 3 of 3: Test Load a File"];
+        step_label.set_label(step_titles[0]);
+        let pages = vec![page1, page2, page3];
+
+        // helper closure for saving the header boxes to the working config
+        let save_headers = {
+            let working_config = (&working_config).clone();
+            let csv_sample_id_buf = csv_sample_id_buf.clone();
+            let csv_class_buf = csv_class_buf.clone();
+            let xml_sample_id_buf = xml_sample_id_buf.clone();
+            move || {
+                let mut config = working_config.borrow_mut();
+                config.csv_sample_id_header = csv_sample_id_buf.text().trim().to_string();
+                config.csv_class_filter_class = csv_class_buf.text().trim().to_string();
+                config.xml_sample_id_header = xml_sample_id_buf.text().trim().to_string();
+            }//end closure
+        };
+
+        // helper closure for switching to a different page
+        let show_page = {
+            let current_page = (&current_page).clone();
+            let mut pages = pages.clone();
+            let mut step_label = step_label.clone();
+            let mut back_button = back_button.clone();
+            let mut next_button = next_button.clone();
+            move |page_idx: usize| {
+                for (idx, page) in pages.iter_mut().enumerate() {
+                    if idx == page_idx {page.show();} else {page.hide();}
+                }//end showing only the selected page
+                step_label.set_label(step_titles[page_idx]);
+                if page_idx == 0 {back_button.deactivate();} else {back_button.activate();}
+                next_button.set_label(if page_idx == step_titles.len() - 1 {"Finish"} else {"Next"});
+                *current_page.borrow_mut() = page_idx;
+            }//end closure
+        };
+
+        next_button.set_callback({
+            let current_page = (&current_page).clone();
+            let working_config = (&working_config).clone();
+            let preset_choice = preset_choice.clone();
+            let mut csv_sample_id_buf = csv_sample_id_buf.clone();
+            let mut csv_class_buf = csv_class_buf.clone();
+            let mut xml_sample_id_buf = xml_sample_id_buf.clone();
+            let save_headers = save_headers.clone();
+            let mut show_page = show_page.clone();
+            let mut window = dialog_window.clone();
+            let finish_ref = (&clicked_finish).clone();
+            move |_| {
+                let page_idx = *current_page.borrow();
+                match page_idx {
+                    0 => {
+                        let config = get_preset(preset_choice.value());
+                        csv_sample_id_buf.set_text(&config.csv_sample_id_header);
+                        csv_class_buf.set_text(&config.csv_class_filter_class);
+                        xml_sample_id_buf.set_text(&config.xml_sample_id_header);
+                        *working_config.borrow_mut() = config;
+                        show_page(1);
+                    },
+                    1 => {
+                        save_headers();
+                        show_page(2);
+                    },
+                    _ => {
+                        *(finish_ref.borrow_mut()) = true;
+                        window.hide();
+                    },
+                }//end matching which page we're moving on from
+            }//end moving for closure
+        });
+        back_button.set_callback({
+            let current_page = (&current_page).clone();
+            let mut show_page = show_page.clone();
+            move |_| {
+                let page_idx = *current_page.borrow();
+                if page_idx > 0 {show_page(page_idx - 1);}
+            }//end moving for closure
+        });
+        cancel_button.set_callback({
+            let mut window = dialog_window.clone();
+            move |_| window.hide()
+        });
+        test_load_btn.set_callback({
+            let working_config = (&working_config).clone();
+            let mut test_load_buf = test_load_buf.clone();
+            move |_| {
+                let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+                dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+                dialog.set_filter("*.csv");
+                dialog.set_title("Please select a csv file to test");
+                dialog.show();
+                let file_path = dialog.filename();
+                if file_path.as_os_str().is_empty() {return;}
+                let config = working_config.borrow();
+                let reader = match csv::ReaderBuilder::new().flexible(true).from_path(&file_path) {
+                    Ok(reader) => reader,
+                    Err(error) => {test_load_buf.set_text(&format!("Couldn't open \"{}\".\nError message was \"{}\".", file_path.to_string_lossy(), error)); return;}
+                };
+                let data = match Data::from_csv_reader(reader, config.csv_header_row, &config.csv_manual_headers, config.csv_footer_rows_to_skip) {
+                    Some(data) => data,
+                    None => {test_load_buf.set_text(&format!("Couldn't find a header row in \"{}\".", file_path.to_string_lossy())); return;}
+                };
+                let mut report = format!("Read {} rows and {} columns from \"{}\".\n", data.get_records_ref().len(), data.get_headers_ref().len(), file_path.file_name().unwrap_or_default().to_string_lossy());
+                if data.has_generated_headers() {report.push_str("The file doesn't seem to have a header row, so none of the chosen columns can be found.\nYou can provide headers later by right-clicking the csv input button.\n");}
+                let missing_headers = config.get_missing_csv_headers(data.get_headers_ref());
+                if missing_headers.len() == 0 {report.push_str("\nAll the columns needed by this config were found. You're all set!");}
+                else {report.push_str(&format!("\nThese columns needed by this config were not found:\n{}\n\nYou can go back to change the key columns, or change the stat columns later in the main window.", missing_headers.join(", ")));}
+                test_load_buf.set_text(&report);
+            }//end moving for closure
+        });
+
+        dialog_window.show();
+        while dialog_window.shown() && self.app.wait() {}
+
+        if *clicked_finish.borrow() {
+            let config = working_config.borrow().clone();
+            return Some(config);
+        }//end if user clicked finish
+        return None;
+    }//end show_setup_wizard(self)
+
+    /// Gets the current size and position of the main window,
+    /// along with where the dividers between its sections are.
+    /// If the config panel is collapsed, the layout has the width it had before.
+    pub fn get_window_layout(&self) -> WindowLayout {
+        WindowLayout {
+            x: self.ux_main_window.x(),
+            y: self.ux_main_window.y(),
+            width: self.ux_main_window.w(),
+            height: self.ux_main_window.h(),
+            config_divider_x: match self.ux_config_panel_width {
+                Some(panel_width) => self.ux_main_window.w() - panel_width,
+                None => self.ux_config_group.x(),
+            },
+            header_divider_y: self.ux_io_controls_group.y(),
+            dialog_divider_y: self.ux_dialog_group.y(),
+        }//end struct construction
+    }//end get_window_layout(self)
+
+    /// Sets the files listed in the Recent Files menu, newest first.
+    pub fn set_recent_files(&mut self, recent_files: &Vec<PathBuf>) {
+        *self.recent_files.borrow_mut() = recent_files.clone();
+    }//end set_recent_files(self, recent_files)
+
+    /// Moves and resizes the main window and its sections to match layout.  
+    /// If the layout has sections too small to use, or the window would be
+    /// off screen, such as after a monitor was unplugged, the layout is ignored.
+    pub fn set_window_layout(&mut self, layout: &WindowLayout) {
+        let (screen_x, screen_y, screen_w, screen_h) = app::screen_xywh(app::screen_num(layout.x, layout.y));
+        let on_screen = layout.x >= screen_x - layout.width / 2 && layout.x < screen_x + screen_w - 50
+            && layout.y >= screen_y && layout.y < screen_y + screen_h - 50;
+        if !layout.is_valid(40) || !on_screen {log::warn!("The saved window layout doesn't fit the screen, so the default layout will be used."); return;}
+        self.ux_main_window.resize(layout.x, layout.y, layout.width, layout.height);
+        self.set_section_dividers(layout.config_divider_x, layout.header_divider_y, layout.dialog_divider_y);
+    }//end set_window_layout(self, layout)
+
+    /// Resizes the sections of the main window so that the dividers between
+    /// them are at the given positions, relative to the window.
+    fn set_section_dividers(&mut self, config_divider_x: i32, header_divider_y: i32, dialog_divider_y: i32) {
+        // sections fill the window down to the status bar
+        let (width, height) = (self.ux_main_window.w(), self.ux_status_bar.y());
+        self.ux_header_group.resize(0, 0, config_divider_x, header_divider_y);
+        self.ux_io_controls_group.resize(0, header_divider_y, config_divider_x, dialog_divider_y - header_divider_y);
+        self.ux_dialog_group.resize(0, dialog_divider_y, config_divider_x, height - dialog_divider_y);
+        self.ux_config_group.resize(config_divider_x, 0, width - config_divider_x, height);
+        self.ux_main_window.redraw();
+    }//end set_section_dividers(self, config_divider_x, header_divider_y, dialog_divider_y)
+
+    /// Collapses the config panel to a narrow strip, giving the rest of the
+    /// window more room, or expands it back to the width it had before.
+    pub fn toggle_config_panel(&mut self) {
+        let header_divider_y = self.ux_io_controls_group.y();
+        let dialog_divider_y = self.ux_dialog_group.y();
+        match self.ux_config_panel_width.take() {
+            Some(panel_width) => {
+                let config_divider_x = (self.ux_main_window.w() - panel_width).max(self.ux_main_window.w() / 2);
+                self.set_section_dividers(config_divider_x, header_divider_y, dialog_divider_y);
+                self.ux_config_expand_btn.hide();
+                self.ux_config_flex.show();
+            },
+            None => {
+                self.ux_config_panel_width = Some(self.ux_config_group.w());
+                self.ux_config_flex.hide();
+                let config_divider_x = self.ux_main_window.w() - 30;
+                self.set_section_dividers(config_divider_x, header_divider_y, dialog_divider_y);
+                self.ux_config_expand_btn.resize(config_divider_x + 5, 10, 20, 20);
+                self.ux_config_expand_btn.show();
+            },
+        }//end matching whether the panel is collapsed
+        self.ux_main_window.redraw();
+    }//end toggle_config_panel(self)
+
+    /// Sets the text shown in the status bar at the bottom of the window.
+    pub fn set_status_text(&mut self, txt: &str) {
+        self.ux_status_bar.set_label(txt);
+        self.ux_status_bar.redraw();
+    }//end set_status_text(self, txt)
+
+    /// Shows the progress bar in the status bar, filled to fraction, from
+    /// 0 to 1, and labeled with stage, such as "Calculating class percents".  
+    /// The controls are disabled until end_progress() is called, so nothing
+    /// else is started while work is running on a worker thread.
+    pub fn show_progress(&mut self, fraction: f64, stage: &str) {
+        self.ux_io_controls_group.deactivate();
+        self.ux_config_group.deactivate();
+        // the status bar moves when the window layout is restored, so the progress bar follows it
+        self.ux_progress_bar.resize(self.ux_status_bar.x() + self.ux_status_bar.w() - 205, self.ux_status_bar.y() + 2, 200, self.ux_status_bar.h() - 4);
+        self.ux_progress_bar.set_value((fraction * 100.0).clamp(0.0, 100.0));
+        self.ux_progress_bar.set_label(&format!("{} ({:.0}%)", stage, (fraction * 100.0).clamp(0.0, 100.0)));
+        self.ux_progress_bar.show();
+        self.ux_progress_bar.redraw();
+    }//end show_progress(self, fraction, stage)
+
+    /// Hides the progress bar from show_progress() and enables the controls again.
+    pub fn end_progress(&mut self) {
+        self.ux_progress_bar.hide();
+        self.ux_io_controls_group.activate();
+        self.ux_config_group.activate();
+        self.ux_main_window.redraw();
+    }//end end_progress(self)
+
+    /// Handles events for up to seconds, so the window stays responsive
+    /// while waiting on a worker thread.
+    pub fn wait_for(&self, seconds: f64) {
+        if let Err(error) = app::wait_for(seconds) {log::warn!("Couldn't wait for events.\n{}", error);}
+    }//end wait_for(self, seconds)
+
+    /// Writes msg to the log file and console, and keeps it in the debug
+    /// log, so it can be saved along with the output, such as in a run bundle.
+    pub fn log(&mut self, msg: &str) {
+        log::info!("{}", msg);
+        self.debug_log.push(msg.to_string());
+    }//end log(self, msg)
+
+    /// Gets the messages logged so far, including any errors shown with
+    /// integrated_dialog_alert(), oldest first.
+    pub fn get_log(&self) -> &Vec<String> {
+        &self.debug_log
+    }//end get_log(self)
+
+    /// Gives a small visual indication that the program is doing something in the background.
+    pub fn start_wait(&mut self) {
+        self.ux_main_window.set_cursor(fltk::enums::Cursor::Wait);
+    }//end start_wait(self)
+
+    /// Clears the visual indication from start_wait()
+    pub fn end_wait(&mut self) {
+        self.ux_main_window.set_cursor(fltk::enums::Cursor::Default);
+    }//end end_wait(self)
+
+    /// Sets up all the properties and appearances of
+    /// various widgets and UI settings.
+    pub fn initialize() -> GUI {
+        let c_grain_app = app::App::default();
+        let mut main_window = window::Window::default().with_size(700, 455).with_label("USDA C-Grain Summarizer");
+        main_window.end();
+
+        let config_ref = Rc::from(RefCell::from(ConfigStore::default()));
+        let locked_ref = Rc::from(RefCell::from(LockedSettings::default()));
+        // define some constants to be used repeatedly for sizing and styling
+        let io_btn_width = 150;
+        let io_btn_height = 30;
+        let io_btn_padding = 10;
+        let io_btn_frame = FrameType::GtkRoundUpFrame;
+        let io_btn_down_frame = FrameType::GtkRoundDownFrame;
+        let io_btn_color = Color::from_rgb(248,248,255);
+        let io_btn_down_color = Color::from_rgb(240,255,240);
+        // let io_box_width = 240; boxes are centered between btn and rest of space in tile
+        let io_box_height = 30;
+        let io_box_padding = 10;
+        let io_box_frame = FrameType::GtkDownFrame;
+        let cf_padding = 5;
+        let cf_chck_height = 20;
+        let cf_chck_frame = FrameType::GtkUpFrame;
+        let cf_box_frame = FrameType::GtkDownFrame;
+
+        let (s, r): (Sender<InterfaceMessage>, Receiver<InterfaceMessage>) = app::channel();
+
+        let status_bar_height = 20;
+        let mut tile_group = Tile::default()
+            .with_pos(0, 0)
+            .with_size(main_window.w(), main_window.h() - status_bar_height);
+        tile_group.end();
+        main_window.add(&tile_group);
+
+        // shows what's loaded and how the last run went, along the bottom of the window
+        let mut status_bar = Frame::default()
+            .with_pos(0, tile_group.h())
+            .with_size(main_window.w(), status_bar_height)
+            .with_label("CSV: none   |   XML: none")
+            .with_align(Align::Inside | Align::Left | Align::Clip);
+        status_bar.set_frame(FrameType::GtkThinDownBox);
+        status_bar.set_label_size(12);
+        status_bar.set_label_color(Color::from_rgb(0,0,64));
+        main_window.add(&status_bar);
+        let mut progress_bar = Progress::default()
+            .with_pos(status_bar.w() - 205, status_bar.y() + 2)
+            .with_size(200, status_bar_height - 4);
+        progress_bar.set_minimum(0.0);
+        progress_bar.set_maximum(100.0);
+        progress_bar.set_frame(FrameType::GtkThinDownBox);
+        progress_bar.set_selection_color(Color::from_rgb(144,238,144));
+        progress_bar.set_label_size(11);
+        progress_bar.hide();
+        main_window.add(&progress_bar);
+
+        // set up header information
+        let mut header_group = Group::default()
+            .with_pos(0,0)
+            .with_size(tile_group.w() / 7 * 4, 90);
+        header_group.end();
+        header_group.set_color(Color::from_rgb(255, 250, 240));
+        tile_group.add(&header_group);
+
+        let header_label_align = Align::Inside.union(Align::Left);
+        let header_label_frame = FrameType::NoBox;
+        let header_label_color = Color::from_rgb(0,0,64);
+        let mut header_label1 = Frame::default()
+            .with_pos(5,5)
+            .with_size(header_group.w() - 10, header_group.h() / 3)
+            .with_label(&format!("USDA C-Grain Sum\tv{}\t{}",GUI::header_version_day().0,GUI::header_version_day().1))
+            .with_align(header_label_align);
+        header_label1.set_label_size(18);
+        header_label1.set_label_type(fltk::enums::LabelType::Embossed);
+        header_label1.set_label_color(header_label_color);
+        header_label1.set_frame(header_label_frame);
+        header_group.add(&header_label1);
+        let mut header_label2 = Frame::default()
+            .with_pos(header_label1.x(),header_group.y() + (header_group.h() / 2 - 5))
+            .with_size(header_label1.w(), header_group.h() / 4)
+            .with_label("Processes CSV and XML Data from C-Grain into Sum Files")
+            .with_align(header_label_align);
+        header_label2.set_frame(header_label_frame);
+        header_label2.set_label_color(header_label_color);
+        header_group.add(&header_label2);
+        let mut header_label3 = Frame::default()
+            .with_pos(header_label2.x(), header_label2.y() + header_label2.h())
+            .with_size(header_label2.w(),header_label2.h())
+            .with_label("Nicholas Sixbury/Dan Brabec\tUSDA-ARS Manhattan,KS\n")
+            .with_align(header_label_align);
+        header_label3.set_frame(header_label_frame);
+        header_label3.set_label_color(header_label_color);
+        header_group.add(&header_label3);
+        header_group.set_tooltip("Left click to see version, build, and license information.\nRight click if you want to configure where to check for updates,\nbeeps and desktop notifications, or a user script,\nor to switch between operator mode and analyst mode.");
+        header_group.handle({
+            let config_ref_clone = (&config_ref).clone();
+            let sender_clone = s.clone();
+            move |_, ev| {
+                match ev {
+                    Event::Released => {
+                        // event_button => 1 for left click, 2 for middle, 3 for right
+                        if app::event_button() == 1 {GUI::show_about_dialog();}
+                        else if app::event_button() == 3 {
+                            let operator_mode = {config_ref_clone.borrow().operator_mode_enabled};
+                            let header_menu = match operator_mode {
+                                true => MenuItem::new(&["Switch to Analyst Mode"]),
+                                false => {
+                                    let mut menu_choices = vec!["Set Update Check Location", "Notification Settings", "Scheduled Processing Settings"];
+                                    // scripts can only be run by builds with the scripting feature
+                                    if cfg!(feature = "scripting") {menu_choices.push("Set Script File");}
+                                    // merged summaries can only be pushed by builds with the google-sheets feature
+                                    if cfg!(feature = "google-sheets") {menu_choices.push("Google Sheets Settings");}
+                                    menu_choices.push("Switch to Operator Mode");
+                                    MenuItem::new(&menu_choices)
+                                },
+                            };
+                            match header_menu.popup(app::event_x(), app::event_y()).and_then(|item| item.label()).as_deref() {
+                                Some("Set Update Check Location") => {
+                                    let mut config = config_ref_clone.as_ref().borrow_mut();
+                                    if let Some(choice) = dialog::input_default("Please indicate a url or file path to check for newer versions on startup.\nThe file there should have the latest version number on the first line,\nfollowed by any release notes.\nLeave this empty to disable update checks.", &config.update_check_location) {
+                                        config.update_check_location = choice.trim().to_string();
+                                    }//end if user chose to change setting
+                                },
+                                Some("Notification Settings") => sender_clone.send(InterfaceMessage::NotificationSettings),
+                                Some("Google Sheets Settings") => sender_clone.send(InterfaceMessage::GoogleSheetsSettings),
+                                Some("Scheduled Processing Settings") => sender_clone.send(InterfaceMessage::ScheduleSettings),
+                                Some("Set Script File") => {
+                                    let mut config = config_ref_clone.as_ref().borrow_mut();
+                                    let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+                                    dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+                                    dialog.set_filter("Rhai Scripts\t*.rhai");
+                                    dialog.set_title("Please select a script to run after loading data and before writing output");
+                                    dialog.show();
+                                    let script_path = dialog.filename();
+                                    if !script_path.as_os_str().is_empty() {
+                                        config.script_path = paths::get_setting_path(&script_path);
+                                    } else if !config.script_path.is_empty() {
+                                        if dialog::choice2_default(&format!("No script was chosen. Would you like to stop using the current script?\n{}", config.script_path), "Yes", "No", "") == Some(0) {
+                                            config.script_path = "".to_string();
+                                        }//end if user wants to stop using the script
+                                    }//end else if the user cancelled while a script is set
+                                },
+                                Some("Switch to Analyst Mode") | Some("Switch to Operator Mode") => sender_clone.send(InterfaceMessage::SwitchUserMode),
+                                _ => {},
+                            }//end matching the chosen menu item
+                        }//end if we have a right-click event
+                        true
+                    },
+                    _ => false
+                }
+            }//end moving for closure
+        });
+        // let mut header_buf = TextBuffer::default();
+        // let mut header_box = TextDisplay::default()
+        //     .with_pos(10, 10)
+        //     .with_size(header_group.w() - 20,header_group.h() - 20);
+        // header_group.add_resizable(&header_box);
+        // header_box.set_buffer(header_buf.clone());
+        // header_buf.append(&GUI::default_header_info());
+        // header_box.set_scrollbar_align(Align::empty());
+
+        // set up group with input and output controls, processing stuff
+        let mut io_controls_group = Group::default()
+            .with_pos(0, header_group.y() + header_group.h())
+            .with_size(tile_group.w() / 7 * 4, tile_group.h() - header_group.h() - 125);
+        io_controls_group.end();
+        io_controls_group.set_color(Color::from_rgb(245,255,250));
+        tile_group.add(&io_controls_group);
+
+        let mut io_controls_label = Frame::default()
+            .with_pos(io_controls_group.x(), io_controls_group.y() + 10)
+            .with_size(io_controls_group.w() - io_btn_width - io_btn_padding, 20)
+            .with_label("Input and Output Controls")
+            .with_align(Align::Center);
+        io_controls_label.set_label_size(16);
+        io_controls_group.add(&io_controls_label);
+
+        // choose data files without caring which button they go with
+        let mut open_data_btn = Button::default()
+            .with_label("Open Data File(s)")
+            .with_pos(io_controls_label.x() + io_controls_label.w(), io_controls_label.y() - 3)
+            .with_size(io_btn_width, io_controls_label.h() + 6);
+        open_data_btn.set_frame(io_btn_frame);
+        open_data_btn.set_down_frame(io_btn_down_frame);
+        open_data_btn.set_tooltip("Left Click this button to choose one or more csv or xml input files at once.\nEach file is loaded as csv or xml depending on what it contains,\nso you don't need to pick the matching button.\nRight Click this button to open one of the files opened recently.");
+        open_data_btn.clear_visible_focus();
+        open_data_btn.set_color(io_btn_color);
+        open_data_btn.set_selection_color(io_btn_down_color);
+        io_controls_group.add(&open_data_btn);
+        let recent_files_ref: Rc<RefCell<Vec<PathBuf>>> = Rc::from(RefCell::from(Vec::new()));
+        open_data_btn.set_callback({
+            let sender_clone = s.clone();
+            let recent_files_clone = (&recent_files_ref).clone();
+            move |_| {
+                match app::event_button() == 3 {
+                    true => GUI::choose_recent_file(&sender_clone, &recent_files_clone.borrow()),
+                    false => GUI::choose_data_files(&sender_clone),
+                }//end matching whether the user right-clicked
+            }//end moving for closure
+        });
+
+        // get input file from user
+        let mut input_csv_btn = Button::default()
+            .with_label("Select Input CSV")
+            .with_pos(io_controls_label.x() + io_btn_padding, io_controls_label.y() +  io_controls_label.h() + io_btn_padding)
+            .with_size(io_btn_width, io_btn_height);
+        input_csv_btn.set_frame(io_btn_frame);
+        input_csv_btn.set_down_frame(io_btn_down_frame);
+        input_csv_btn.set_tooltip("Left Click this button to choose a csv input file.\nRight Click this button to configure advanced csv input options.");
+        input_csv_btn.clear_visible_focus();
+        input_csv_btn.set_color(io_btn_color);
+        input_csv_btn.set_selection_color(io_btn_down_color);
+        io_controls_group.add(&input_csv_btn);
+
+        let input_csv_buf = TextBuffer::default();
+        let mut input_csv_box = TextDisplay::default()
+            .with_pos(input_csv_btn.x() + input_csv_btn.w() + io_box_padding, input_csv_btn.y())
+            .with_size(io_controls_group.w() - (input_csv_btn.w() + (3 * io_box_padding)), io_box_height);
+        input_csv_box.set_frame(io_box_frame);
+        input_csv_box.set_scrollbar_align(Align::Bottom);
+        input_csv_box.set_scrollbar_size(7);
+        input_csv_box.set_color(Color::from_rgb(245,245,245));
+        input_csv_box.set_buffer(input_csv_buf);
+        io_controls_group.add_resizable(&input_csv_box);
+        let input_csv_ref = Rc::from(RefCell::from(input_csv_box));
+
+        input_csv_btn.set_callback({
+            let input_csv_ref_clone = (&input_csv_ref).clone();
+            let sender_clone = s.clone();
+            let config_ref_clone = (&config_ref).clone();
+            let locked_ref_clone = (&locked_ref).clone();
+            move |_| {
+                if app::event_button() == 3 {
+                    let clicked_ok = Rc::from(RefCell::from(false));
+                    let config = {config_ref_clone.borrow().clone()};
+                    // advanced options are locked in operator mode
+                    if config.operator_mode_enabled {return;}
+                    // create a basic window in order to show custom dialog
+                    // need sample id, header row, manual headers, and number of footer rows
+                    let mut dialog_window = Window::default()
+                        .with_size(470,390)
+                        .with_label("Advanced CSV Options");
+                    dialog_window.make_resizable(true);
+                    dialog_window.make_modal(true);
+                    let mut ok_button = Button::default()
+                        .with_size(50,30)
+                        .with_pos(60,350)
+                        .with_label("Ok");
+                    ok_button.set_frame(FrameType::GtkRoundUpFrame);
+                    ok_button.clear_visible_focus();
+                    let mut cancel_button = Button::default()
+                        .with_size(70,30)
+                        .with_pos(120,350)
+                        .with_label("Cancel");
+                    cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+                    cancel_button.clear_visible_focus();
+                    let mut csv_sample_id_header_buf = TextBuffer::default();
+                    csv_sample_id_header_buf.set_text(&config.csv_sample_id_header);
+                    let mut csv_sample_id_header_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,20)
+                        .with_label("Column to read as sample-id in csv:")
+                        .with_align(Align::TopLeft);
+                    csv_sample_id_header_box.set_tooltip("Default is \"external-sample-id\" without quotation marks.");
+                    csv_sample_id_header_box.set_frame(FrameType::GtkDownFrame);
+                    csv_sample_id_header_box.set_scrollbar_align(Align::Bottom);
+                    csv_sample_id_header_box.set_scrollbar_size(7);
+                    csv_sample_id_header_box.set_buffer(csv_sample_id_header_buf);
+                    let mut csv_header_row_buf = TextBuffer::default();
+                    csv_header_row_buf.set_text(&config.csv_header_row.to_string());
+                    let mut csv_header_row_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,70)
+                        .with_label("Row index of headers in csv:")
+                        .with_align(Align::TopLeft);
+                    csv_header_row_box.set_tooltip("0-based index of the row holding the column headers. Rows before it are skipped.\nIf manual headers are given, this is the index of the first row of data instead.\nDefault is 0.");
+                    csv_header_row_box.set_frame(FrameType::GtkDownFrame);
+                    csv_header_row_box.set_scrollbar_align(Align::Bottom);
+                    csv_header_row_box.set_scrollbar_size(7);
+                    csv_header_row_box.set_buffer(csv_header_row_buf);
+                    let mut csv_footer_rows_buf = TextBuffer::default();
+                    csv_footer_rows_buf.set_text(&config.csv_footer_rows_to_skip.to_string());
+                    let mut csv_footer_rows_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,120)
+                        .with_label("Footer rows to skip at end of csv:")
+                        .with_align(Align::TopLeft);
+                    csv_footer_rows_box.set_tooltip("Number of rows at the end of the file to ignore, such as a summary row.\nBlank rows at the end of the file are always ignored.\nDefault is 0.");
+                    csv_footer_rows_box.set_frame(FrameType::GtkDownFrame);
+                    csv_footer_rows_box.set_scrollbar_align(Align::Bottom);
+                    csv_footer_rows_box.set_scrollbar_size(7);
+                    csv_footer_rows_box.set_buffer(csv_footer_rows_buf);
+                    let mut strict_schema_chck = CheckButton::default()
+                        .with_size(220,20)
+                        .with_pos(20,165)
+                        .with_label("Only accept known C-Grain files");
+                    strict_schema_chck.set_tooltip("If checked, csv and xml files are only loaded if they match a known C-Grain export layout,\nso files from other instruments aren't processed by mistake.");
+                    strict_schema_chck.set_checked(config.strict_schema_enabled);
+                    strict_schema_chck.clear_visible_focus();
+                    let mut csv_manual_headers_buf = TextBuffer::default();
+                    csv_manual_headers_buf.set_text(&config.csv_manual_headers.join("\n"));
+                    let mut csv_manual_headers_box = TextEditor::default()
+                        .with_size(200,180)
+                        .with_pos(250,20)
+                        .with_label("Manual Headers (if csv has none):")
+                        .with_align(Align::TopRight);
+                    csv_manual_headers_box.set_tooltip("Separate headers by newlines, in the order of the columns in the csv.\nOnly use this if the csv file doesn't have a header row.\nDefault is empty.");
+                    csv_manual_headers_box.set_frame(FrameType::GtkDownFrame);
+                    csv_manual_headers_box.set_scrollbar_align(Align::Right);
+                    csv_manual_headers_box.set_scrollbar_size(12);
+                    csv_manual_headers_box.set_buffer(csv_manual_headers_buf);
+                    let mut profile_patterns_buf = TextBuffer::default();
+                    profile_patterns_buf.set_text(&config.profile_patterns.join("\n"));
+                    let mut profile_patterns_box = TextEditor::default()
+                        .with_size(430,50)
+                        .with_pos(20,230)
+                        .with_label("Presets for file names (pattern -> preset):")
+                        .with_align(Align::TopLeft);
+                    profile_patterns_box.set_tooltip("When an input file with a matching name is loaded, the settings switch to that preset.\nPut one pattern per line. In a pattern, * matches any characters and ? matches one character.\nExample: *_sorghum_* -> Sorghum");
+                    profile_patterns_box.set_frame(FrameType::GtkDownFrame);
+                    profile_patterns_box.set_scrollbar_align(Align::Right);
+                    profile_patterns_box.set_scrollbar_size(12);
+                    profile_patterns_box.set_buffer(profile_patterns_buf);
+                    let mut csv_frame_header_buf = TextBuffer::default();
+                    csv_frame_header_buf.set_text(&config.csv_frame_header);
+                    let mut csv_frame_header_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,305)
+                        .with_label("Column to read as the frame of each kernel:")
+                        .with_align(Align::TopLeft);
+                    csv_frame_header_box.set_tooltip("If the csv has this column, a Frame_Stats sheet is added to the output,\nwith the number of frames in each sample and statistics on kernels per frame.\nLeave this empty to never add frame stats.\nDefault is \"image-id\" without quotation marks.");
+                    csv_frame_header_box.set_frame(FrameType::GtkDownFrame);
+                    csv_frame_header_box.set_scrollbar_align(Align::Bottom);
+                    csv_frame_header_box.set_scrollbar_size(7);
+                    csv_frame_header_box.set_buffer(csv_frame_header_buf);
+                    let mut settle_seconds_buf = TextBuffer::default();
+                    settle_seconds_buf.set_text(&config.input_settle_seconds.to_string());
+                    let mut settle_seconds_box = TextEditor::default()
+                        .with_size(200,30)
+                        .with_pos(250,305)
+                        .with_label("Seconds unchanged before loading:")
+                        .with_align(Align::TopLeft);
+                    settle_seconds_box.set_tooltip("Input files changed more recently than this are watched until they stop changing for this long,\nso a file the C-Grain is still exporting isn't read half written. Set this to 0 to load files right away.\nDefault is 2.");
+                    settle_seconds_box.set_frame(FrameType::GtkDownFrame);
+                    settle_seconds_box.set_scrollbar_align(Align::Bottom);
+                    settle_seconds_box.set_scrollbar_size(7);
+                    settle_seconds_box.set_buffer(settle_seconds_buf);
+
+                    GUI::lock_setting_widgets(&locked_ref_clone.borrow(), &mut vec![
+                        (csv_sample_id_header_box.as_base_widget(), "csv_sample_id_header"),
+                        (csv_header_row_box.as_base_widget(), "csv_header_row"),
+                        (csv_footer_rows_box.as_base_widget(), "csv_footer_rows_to_skip"),
+                        (csv_manual_headers_box.as_base_widget(), "csv_manual_headers"),
+                        (strict_schema_chck.as_base_widget(), "strict_schema_enabled"),
+                        (csv_frame_header_box.as_base_widget(), "csv_frame_header"),
+                        (profile_patterns_box.as_base_widget(), "profile_patterns"),
+                        (settle_seconds_box.as_base_widget(), "input_settle_seconds"),
+                    ]);
+                    dialog_window.end();
+
+                    dialog_window.set_callback({
+                        let clicked_ref = (&clicked_ok).clone();
+                        let config_clone = (&config_ref_clone).clone();
+                        move |win| {
+                            let clicked_ok = clicked_ref.borrow();
+                            if *clicked_ok {
+                                let mut config = config_clone.borrow_mut();
+                                config.csv_sample_id_header = csv_sample_id_header_box.buffer().unwrap().text();
+                                config.strict_schema_enabled = strict_schema_chck.is_checked();
+                                config.csv_frame_header = csv_frame_header_box.buffer().unwrap().text().trim().to_string();
+                                config.profile_patterns = profile_patterns_box
+                                    .buffer().unwrap().text()
+                                    .split("\n").filter(|el| el.trim() != "")
+                                    .map(|el| el.trim().to_owned()).collect();
+                                config.csv_manual_headers = csv_manual_headers_box
+                                    .buffer().unwrap().text()
+                                    .split("\n").into_iter().filter(|el| el.trim() != "")
+                                    .map(|el| el.to_owned()).collect();
+                                let header_row = csv_header_row_box.buffer().unwrap().text().trim().parse::<usize>();
+                                let footer_rows = csv_footer_rows_box.buffer().unwrap().text().trim().parse::<usize>();
+                                let settle_seconds = settle_seconds_box.buffer().unwrap().text().trim().parse::<u64>();
+                                match (header_row, footer_rows, settle_seconds) {
+                                    (Ok(header_row), Ok(footer_rows), Ok(settle_seconds)) => {
+                                        config.csv_header_row = header_row;
+                                        config.csv_footer_rows_to_skip = footer_rows;
+                                        config.input_settle_seconds = settle_seconds;
+                                        dialog::message_title("Success!");
+                                        dialog::message_default("Advanced CSV Options have been successfully updated.");
+                                    },
+                                    _ => {
+                                        dialog::message_title("Invalid Numbers");
+                                        dialog::alert_default("The header row index, number of footer rows to skip, and seconds unchanged before loading must be whole numbers.\nThe previous values for those will be kept.");
+                                    },
+                                }//end matching whether we could parse the row numbers and seconds
+                            }//end if user clicked ok to change their config
+                            win.hide();
+                        }//end closure
+                    });
+
+                    let dialog_window_ref = Rc::from(RefCell::from(dialog_window));
+                    ok_button.set_callback({
+                        let window_ref = (&dialog_window_ref).clone();
+                        let ok_ref = (&clicked_ok).clone();
+                        move |_| {
+                            let mut window_ref = window_ref.borrow_mut();
+                            *(ok_ref.borrow_mut()) = true;
+                            window_ref.do_callback();
+                        }//end moving closure
+                    });
+                    cancel_button.set_callback({
+                        let window_ref = (&dialog_window_ref).clone();
+                        move |_| {
+                            let mut window_ref = window_ref.borrow_mut();
+                            window_ref.do_callback();
+                        }//end moving closure
+                    });
+
+                    let window_ref_clone = (&dialog_window_ref).clone();
+                    let mut window_ref = window_ref_clone.borrow_mut();
+                    window_ref.show();
+                } else {
+                    let mut input_csv_ref = input_csv_ref_clone.as_ref().borrow_mut();
+                    let mut input_csv_buf = input_csv_ref.buffer().unwrap_or_else(|| TextBuffer::default());
+                    if let Err(err_message) = GUI::create_io_dialog(&sender_clone, "CSVInputFile", &mut input_csv_buf, dialog::NativeFileChooserType::BrowseFile, dialog::NativeFileChooserOptions::UseFilterExt, "*.{csv,xlsx}", "Please select a csv or xlsx input file") {
+                        log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                    }//end if we got an error
+                    input_csv_ref.set_buffer(input_csv_buf);
+                }//end else user didn't right-click
+            }//end moving for closure
+        });
+
+        let mut input_xml_btn = Button::default()
+            .with_label("Select Input XML")
+            .with_pos(input_csv_btn.x(), input_csv_btn.y() + input_csv_btn.h() + io_btn_padding)
+            .with_size(io_btn_width, io_btn_height);
+        input_xml_btn.set_frame(io_btn_frame);
+        input_xml_btn.set_down_frame(io_btn_down_frame);
+        input_xml_btn.set_tooltip("Left Click this button to choose an xml input file.\nRight click this button to configure advanced xml input options.");
+        input_xml_btn.clear_visible_focus();
+        input_xml_btn.set_color(io_btn_color);
+        input_xml_btn.set_selection_color(io_btn_down_color);
+        io_controls_group.add(&input_xml_btn);
+
+        let input_xml_buf = TextBuffer::default();
+        let mut input_xml_box = TextDisplay::default()
+            .with_pos(input_xml_btn.x() + input_xml_btn.w() + io_box_padding, input_xml_btn.y())
+            .with_size(io_controls_group.w() - (input_xml_btn.w() + (3 * io_box_padding)), io_box_height);
+        input_xml_box.set_frame(io_box_frame);
+        input_xml_box.set_scrollbar_align(Align::Bottom);
+        input_xml_box.set_scrollbar_size(7);
+        input_xml_box.set_color(Color::from_rgb(245,245,245));
+        input_xml_box.set_buffer(input_xml_buf);
+        io_controls_group.add_resizable(&input_xml_box);
+        let input_xml_ref = Rc::from(RefCell::from(input_xml_box));
 
         input_xml_btn.set_callback({
             let input_xml_ref_clone = input_xml_ref.clone();
             let sender_clone = s.clone();
             let config_clone = (&config_ref).clone();
+            let locked_ref_clone = (&locked_ref).clone();
             move |_| {
                 if app::event_button() == 3 {
                     let clicked_ok = Rc::from(RefCell::from(false));
                     let config = {config_clone.borrow().clone()};
+                    // advanced options are locked in operator mode
+                    if config.operator_mode_enabled {return;}
                     // create a basic window in order to show custom dialog
                     // need sample id, custom tags, and closing tag
                     let mut dialog_window = Window::default()
-                        .with_size(470,160)
+                        .with_size(470,280)
                         .with_label("Advanced XML Options");
                     dialog_window.make_resizable(true);
                     dialog_window.make_modal(true);
                     let mut ok_button = Button::default()
                         .with_size(50,30)
-                        .with_pos(60,115)
+                        .with_pos(60,235)
                         .with_label("Ok");
                     ok_button.set_frame(FrameType::GtkRoundUpFrame);
                     ok_button.clear_visible_focus();
                     let mut cancel_button = Button::default()
                         .with_size(70,30)
-                        .with_pos(120,115)
+                        .with_pos(120,235)
                         .with_label("Cancel");
                     cancel_button.set_frame(FrameType::GtkRoundUpFrame);
                     cancel_button.clear_visible_focus();
@@ -595,6 +4465,18 @@ impl GUI {
                     xml_closing_tag_box.set_scrollbar_align(Align::Bottom);
                     xml_closing_tag_box.set_scrollbar_size(7);
                     xml_closing_tag_box.set_buffer(xml_closing_tag_buf);
+                    let mut xml_sum_tolerance_buf = TextBuffer::default();
+                    xml_sum_tolerance_buf.set_text(&config.xml_sieve_sum_tolerance);
+                    let mut xml_sum_tolerance_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,120)
+                        .with_label("Sieve total tolerance (% from 100):")
+                        .with_align(Align::TopLeft);
+                    xml_sum_tolerance_box.set_tooltip("Samples whose sieve fractions add up to more than this many percentage points\naway from 100% are flagged in the Sieve Total Check column, such as for a short pour.\nLeave this empty to skip the check. Default is 2.");
+                    xml_sum_tolerance_box.set_frame(FrameType::GtkDownFrame);
+                    xml_sum_tolerance_box.set_scrollbar_align(Align::Bottom);
+                    xml_sum_tolerance_box.set_scrollbar_size(7);
+                    xml_sum_tolerance_box.set_buffer(xml_sum_tolerance_buf);
                     let mut xml_extra_tags_buf = TextBuffer::default();
                     xml_extra_tags_buf.set_text(&config.xml_tags_to_include.join("\n"));
                     let mut xml_extra_tags_box = TextEditor::default()
@@ -607,7 +4489,26 @@ impl GUI {
                     xml_extra_tags_box.set_scrollbar_align(Align::Right);
                     xml_extra_tags_box.set_scrollbar_size(12);
                     xml_extra_tags_box.set_buffer(xml_extra_tags_buf);
+                    let mut xml_sieve_sizes_buf = TextBuffer::default();
+                    xml_sieve_sizes_buf.set_text(&config.xml_sieve_sizes.join("\n"));
+                    let mut xml_sieve_sizes_box = TextEditor::default()
+                        .with_size(430,60)
+                        .with_pos(20,170)
+                        .with_label("Sieve Sizes (tag -> screen mm, target %):")
+                        .with_align(Align::TopLeft);
+                    xml_sieve_sizes_box.set_tooltip("Put one sieve per line, giving the xml tag, the screen size in mm,\nand optionally the target percent for that sieve.\nExample: filter-sieving-1 -> 4.76, 10\nSieve columns are labeled with their size and ordered largest screen first.\nDefault is empty, which writes sieve columns as they're named in the xml.");
+                    xml_sieve_sizes_box.set_frame(FrameType::GtkDownFrame);
+                    xml_sieve_sizes_box.set_scrollbar_align(Align::Right);
+                    xml_sieve_sizes_box.set_scrollbar_size(12);
+                    xml_sieve_sizes_box.set_buffer(xml_sieve_sizes_buf);
 
+                    GUI::lock_setting_widgets(&locked_ref_clone.borrow(), &mut vec![
+                        (xml_sample_id_header_box.as_base_widget(), "xml_sample_id_header"),
+                        (xml_closing_tag_box.as_base_widget(), "xml_sample_closing_tag"),
+                        (xml_extra_tags_box.as_base_widget(), "xml_tags_to_include"),
+                        (xml_sieve_sizes_box.as_base_widget(), "xml_sieve_sizes"),
+                        (xml_sum_tolerance_box.as_base_widget(), "xml_sieve_sum_tolerance"),
+                    ]);
                     dialog_window.end();
 
                     dialog_window.set_callback({
@@ -623,8 +4524,24 @@ impl GUI {
                                     .buffer().unwrap().text()
                                     .split("\n").into_iter().filter(|el| el.trim() != "")
                                     .map(|el| el.to_owned()).collect();
-                                dialog::message_title("Success!");
-                                dialog::message_default("Advanced XML Options have been successfully updated.");
+                                let xml_sieve_sizes: Vec<String> = xml_sieve_sizes_box
+                                    .buffer().unwrap().text()
+                                    .split("\n").into_iter().filter(|el| el.trim() != "")
+                                    .map(|el| el.trim().to_owned()).collect();
+                                let xml_sum_tolerance = xml_sum_tolerance_box.buffer().unwrap().text().trim().to_string();
+                                match process::parse_sieve_sizes(&xml_sieve_sizes).and_then(|_| process::parse_sieve_sum_tolerance(&xml_sum_tolerance)) {
+                                    Ok(_) => {
+                                        config.xml_sieve_sizes = xml_sieve_sizes;
+                                        config.xml_sieve_sum_tolerance = xml_sum_tolerance;
+                                        dialog::message_title("Success!");
+                                        dialog::message_default("Advanced XML Options have been successfully updated.");
+                                    },
+                                    Err(msg) => {
+                                        dialog::message_title("Sieve Settings Not Saved");
+                                        dialog::alert_default(&format!("The other options were updated, but the sieve sizes or sieve total tolerance couldn't be read, so they weren't changed.\n{}", msg));
+                                    },
+                                }//end matching whether the sieve settings can be read
+
                             }//end if user clicked ok to change their config
                             win.hide();
                         }//end closure
@@ -655,7 +4572,7 @@ impl GUI {
                     let mut input_xml_ref = input_xml_ref_clone.as_ref().borrow_mut();
                     let mut input_xml_buf = input_xml_ref.buffer().unwrap_or_else(|| TextBuffer::default());
                     if let Err(err_message) = GUI::create_io_dialog(&sender_clone, "XMLInputFile", &mut input_xml_buf, dialog::NativeFileChooserType::BrowseFile, dialog::NativeFileChooserOptions::UseFilterExt, "*.xml", "Please select an xml input file") {
-                        println!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                        log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
                     }//end if we got an error
                     input_xml_ref.set_buffer(input_xml_buf);
                 }//end else user didn't right-click
@@ -669,7 +4586,7 @@ impl GUI {
             .with_size(io_btn_width, io_btn_height);
         output_file_btn.set_frame(io_btn_frame);
         output_file_btn.set_down_frame(io_btn_down_frame);
-        output_file_btn.set_tooltip("Click this button to set where the output file will be located.\nOr, just type a name in the box to right.");
+        output_file_btn.set_tooltip("Click this button to set where the output file will be located.\nOr, just type a name in the box to right.\nRight click this button to configure advanced output options.");
         output_file_btn.clear_visible_focus();
         output_file_btn.set_color(io_btn_color);
         output_file_btn.set_selection_color(io_btn_down_color);
@@ -689,22 +4606,530 @@ impl GUI {
         output_file_btn.set_callback({
             let output_file_ref_clone = output_file_ref.clone();
             let sender_clone = s.clone();
+            let config_clone = (&config_ref).clone();
+            let locked_ref_clone = (&locked_ref).clone();
             move |_| {
-                let mut output_file_ref = output_file_ref_clone.as_ref().borrow_mut();
-                let mut output_file_buf = output_file_ref.buffer().unwrap_or_else(|| TextBuffer::default());
-                if let Err(err_message) = GUI::create_io_dialog(&sender_clone, "OutputFile", &mut output_file_buf, dialog::NativeFileChooserType::BrowseSaveFile, dialog::NativeFileChooserOptions::SaveAsConfirm, "", "Please specify the output file.") {
-                    println!("Encountered an error when attempting to show file dialog:\n{}", err_message);
-                }//end if we got an error
-                output_file_ref.set_buffer(output_file_buf);
+                if app::event_button() == 3 {
+                    let clicked_ok = Rc::from(RefCell::from(false));
+                    let config = {config_clone.borrow().clone()};
+                    // advanced options are locked in operator mode
+                    if config.operator_mode_enabled {return;}
+                    // create a basic window in order to show custom dialog
+                    // need font name, font size, and treatment comparison settings
+                    let mut dialog_window = Window::default()
+                        .with_size(560,690)
+                        .with_label("Advanced Output Options");
+                    dialog_window.make_resizable(true);
+                    dialog_window.make_modal(true);
+                    let mut ok_button = Button::default()
+                        .with_size(50,30)
+                        .with_pos(60,645)
+                        .with_label("Ok");
+                    ok_button.set_frame(FrameType::GtkRoundUpFrame);
+                    ok_button.clear_visible_focus();
+                    let mut cancel_button = Button::default()
+                        .with_size(70,30)
+                        .with_pos(120,645)
+                        .with_label("Cancel");
+                    cancel_button.set_frame(FrameType::GtkRoundUpFrame);
+                    cancel_button.clear_visible_focus();
+                    let mut font_name_buf = TextBuffer::default();
+                    font_name_buf.set_text(&config.output_font_name);
+                    let mut font_name_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,20)
+                        .with_label("Font for output workbook:")
+                        .with_align(Align::TopLeft);
+                    font_name_box.set_tooltip("Name of the font to use, such as \"Arial\" without quotation marks.\nLeave this empty to use the default font.");
+                    font_name_box.set_frame(FrameType::GtkDownFrame);
+                    font_name_box.set_scrollbar_align(Align::Bottom);
+                    font_name_box.set_scrollbar_size(7);
+                    font_name_box.set_buffer(font_name_buf);
+                    let mut font_size_buf = TextBuffer::default();
+                    font_size_buf.set_text(&config.output_font_size.to_string());
+                    let mut font_size_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,70)
+                        .with_label("Font size for output workbook:")
+                        .with_align(Align::TopLeft);
+                    font_size_box.set_tooltip("Size of the font to use, such as 11.\nSet this to 0 to use the default font size.");
+                    font_size_box.set_frame(FrameType::GtkDownFrame);
+                    font_size_box.set_scrollbar_align(Align::Bottom);
+                    font_size_box.set_scrollbar_size(7);
+                    font_size_box.set_buffer(font_size_buf);
+                    let mut kernel_csv_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,165)
+                        .with_label("Write kernel csv for each sample");
+                    kernel_csv_chck.set_tooltip("If checked, the kernel rows of each sample, after class filtering,\nwill be written to their own csv file in a folder next to the output file.");
+                    kernel_csv_chck.set_checked(config.kernel_csv_export_enabled);
+                    kernel_csv_chck.clear_visible_focus();
+                    let mut protect_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,190)
+                        .with_label("Protect output sheets");
+                    protect_chck.set_tooltip("If checked, the cells of each output sheet will be locked, so recipients can't\naccidentally change the numbers. Sorting and filtering are still allowed.");
+                    protect_chck.set_checked(config.sheet_protection_enabled);
+                    protect_chck.clear_visible_focus();
+                    let mut password_input = SecretInput::default()
+                        .with_size(220,30)
+                        .with_pos(20,235)
+                        .with_label("Password for protected sheets:")
+                        .with_align(Align::TopLeft);
+                    password_input.set_tooltip("The password needed to unprotect the sheets in Excel.\nLeave this empty to protect sheets without a password.\nThis is saved in the config file as plain text, so don't reuse an important password.");
+                    password_input.set_frame(FrameType::GtkDownFrame);
+                    password_input.set_value(&config.sheet_protection_password);
+                    let mut diagnostics_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,275)
+                        .with_label("Add Diagnostics sheet");
+                    diagnostics_chck.set_tooltip("If checked, the output will have a Diagnostics sheet counting, for each sample and csv stat column,\nthe values which were non-numeric or missing, and so were left out of the statistics.");
+                    diagnostics_chck.set_checked(config.diagnostics_sheet_enabled);
+                    diagnostics_chck.clear_visible_focus();
+                    let mut split_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,300)
+                        .with_label("Split output by metadata column");
+                    split_chck.set_tooltip("If checked, a separate workbook is also written for each value of the column below in the metadata file,\nsuch as one per customer, with only the rows of the samples with that value.");
+                    split_chck.set_checked(config.split_output_enabled);
+                    split_chck.clear_visible_focus();
+                    let mut split_col_buf = TextBuffer::default();
+                    split_col_buf.set_text(&config.split_output_column);
+                    let mut split_col_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,345)
+                        .with_label("Metadata column to split by:")
+                        .with_align(Align::TopLeft);
+                    split_col_box.set_tooltip("Name of the column in the metadata file, such as \"Customer\" without quotation marks.\nEach workbook is named after the output file and the value, such as summary_Customer_A.xlsx.");
+                    split_col_box.set_frame(FrameType::GtkDownFrame);
+                    split_col_box.set_scrollbar_align(Align::Bottom);
+                    split_col_box.set_scrollbar_size(7);
+                    split_col_box.set_buffer(split_col_buf);
+                    let mut drift_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,385)
+                        .with_label("Add Drift Check sheet");
+                    drift_chck.set_tooltip("If checked, the output will have a Drift_Check sheet comparing the first and second halves\nof the run for each csv stat column, and flagging columns which changed significantly,\nsuch as from the instrument warming up.");
+                    drift_chck.set_checked(config.drift_sheet_enabled);
+                    drift_chck.clear_visible_focus();
+                    let mut drift_time_buf = TextBuffer::default();
+                    drift_time_buf.set_text(&config.drift_timestamp_header);
+                    let mut drift_time_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,430)
+                        .with_label("Timestamp column for drift check:")
+                        .with_align(Align::TopLeft);
+                    drift_time_box.set_tooltip("Name of the csv column giving the time each kernel was measured.\nLeave this empty to use the order of the rows in the csv instead.");
+                    drift_time_box.set_frame(FrameType::GtkDownFrame);
+                    drift_time_box.set_scrollbar_align(Align::Bottom);
+                    drift_time_box.set_scrollbar_size(7);
+                    drift_time_box.set_buffer(drift_time_buf);
+                    let mut review_chck = CheckButton::default()
+                        .with_size(230,20)
+                        .with_pos(20,470)
+                        .with_label("Review flagged samples first");
+                    review_chck.set_tooltip("If checked, samples with few kernels or unusually low class percents are listed before the output\nis written, so you can choose which to exclude. Excluded samples are listed in the Run_Info sheet.");
+                    review_chck.set_checked(config.review_flagged_enabled);
+                    review_chck.clear_visible_focus();
+                    let mut review_kernels_buf = TextBuffer::default();
+                    review_kernels_buf.set_text(&config.review_min_kernels.to_string());
+                    let mut review_kernels_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,515)
+                        .with_label("Flag samples with fewer kernels than:")
+                        .with_align(Align::TopLeft);
+                    review_kernels_box.set_tooltip("Samples with fewer kernels than this, after class filtering, are flagged for review.\nSet this to 0 to only flag unusually low class percents.");
+                    review_kernels_box.set_frame(FrameType::GtkDownFrame);
+                    review_kernels_box.set_scrollbar_align(Align::Bottom);
+                    review_kernels_box.set_scrollbar_size(7);
+                    review_kernels_box.set_buffer(review_kernels_buf);
+                    let mut sample_link_buf = TextBuffer::default();
+                    sample_link_buf.set_text(&config.sample_link_template);
+                    let mut sample_link_box = TextEditor::default()
+                        .with_size(220,30)
+                        .with_pos(20,575)
+                        .with_label("Link sample ids to:")
+                        .with_align(Align::TopLeft);
+                    sample_link_box.set_tooltip("A folder or web address for each sample, with {sample_id} where the sample id goes.\nSample ids in the output are written as links to it, such as to the kernel images of each sample.\nExample: D:\\CGrain\\Images\\{sample_id}\nLeave this empty to write sample ids as plain text.");
+                    sample_link_box.set_frame(FrameType::GtkDownFrame);
+                    sample_link_box.set_scrollbar_align(Align::Bottom);
+                    sample_link_box.set_scrollbar_size(7);
+                    sample_link_box.set_buffer(sample_link_buf);
+                    let mut treatment_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,15)
+                        .with_label("Add Treatment Comparison sheet");
+                    treatment_chck.set_tooltip("If checked, the output will have a sheet with the mean and standard deviation\nof each metric across the samples in each treatment, using the metadata file.");
+                    treatment_chck.set_checked(config.treatment_sheet_enabled);
+                    treatment_chck.clear_visible_focus();
+                    let mut metadata_path_buf = TextBuffer::default();
+                    metadata_path_buf.set_text(&config.metadata_file_path);
+                    let mut metadata_path_box = TextEditor::default()
+                        .with_size(230,30)
+                        .with_pos(270,60)
+                        .with_label("Metadata csv file:")
+                        .with_align(Align::TopLeft);
+                    metadata_path_box.set_tooltip("A csv file with a row for each sample, giving the treatment or variety of each sample.");
+                    metadata_path_box.set_frame(FrameType::GtkDownFrame);
+                    metadata_path_box.set_scrollbar_align(Align::Bottom);
+                    metadata_path_box.set_scrollbar_size(7);
+                    metadata_path_box.set_buffer(metadata_path_buf.clone());
+                    let mut metadata_browse_btn = Button::default()
+                        .with_size(35,30)
+                        .with_pos(505,60)
+                        .with_label("...");
+                    metadata_browse_btn.set_frame(FrameType::GtkRoundUpFrame);
+                    metadata_browse_btn.clear_visible_focus();
+                    metadata_browse_btn.set_callback({
+                        let mut metadata_path_buf = metadata_path_buf.clone();
+                        move |_| {
+                            let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+                            dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+                            dialog.set_filter("*.csv");
+                            dialog.set_title("Please select a metadata csv file");
+                            dialog.show();
+                            let file_path = dialog.filename();
+                            if !file_path.as_os_str().is_empty() {metadata_path_buf.set_text(&paths::get_setting_path(&file_path));}
+                        }//end moving for closure
+                    });
+                    let mut metadata_id_buf = TextBuffer::default();
+                    metadata_id_buf.set_text(&config.metadata_sample_id_header);
+                    let mut metadata_id_box = TextEditor::default()
+                        .with_size(270,30)
+                        .with_pos(270,110)
+                        .with_label("Column to read as sample-id in metadata:")
+                        .with_align(Align::TopLeft);
+                    metadata_id_box.set_tooltip("Default is \"external-sample-id\" without quotation marks.");
+                    metadata_id_box.set_frame(FrameType::GtkDownFrame);
+                    metadata_id_box.set_scrollbar_align(Align::Bottom);
+                    metadata_id_box.set_scrollbar_size(7);
+                    metadata_id_box.set_buffer(metadata_id_buf);
+                    let mut treatment_cols_buf = TextBuffer::default();
+                    treatment_cols_buf.set_text(&config.treatment_columns.join(", "));
+                    let mut treatment_cols_box = TextEditor::default()
+                        .with_size(270,30)
+                        .with_pos(270,160)
+                        .with_label("Treatment columns in metadata:")
+                        .with_align(Align::TopLeft);
+                    treatment_cols_box.set_tooltip("Separate columns by a comma, such as \"Treatment, Variety\" without quotation marks.\nSamples are grouped by the combination of these columns.");
+                    treatment_cols_box.set_frame(FrameType::GtkDownFrame);
+                    treatment_cols_box.set_scrollbar_align(Align::Bottom);
+                    treatment_cols_box.set_scrollbar_size(7);
+                    treatment_cols_box.set_buffer(treatment_cols_buf);
+                    let mut treatment_metrics_buf = TextBuffer::default();
+                    treatment_metrics_buf.set_text(&config.treatment_metrics.join(", "));
+                    let mut treatment_metrics_box = TextEditor::default()
+                        .with_size(270,30)
+                        .with_pos(270,210)
+                        .with_label("CSV columns to compare:")
+                        .with_align(Align::TopLeft);
+                    treatment_metrics_box.set_tooltip("Separate columns by a comma, such as \"Area, Length, Weight\" without quotation marks.");
+                    treatment_metrics_box.set_frame(FrameType::GtkDownFrame);
+                    treatment_metrics_box.set_scrollbar_align(Align::Bottom);
+                    treatment_metrics_box.set_scrollbar_size(7);
+                    treatment_metrics_box.set_buffer(treatment_metrics_buf);
+                    let mut snapshot_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,255)
+                        .with_label("Copy filtered input rows into output");
+                    snapshot_chck.set_tooltip("If checked, the csv rows which pass the class filter will be copied into an Input_Data sheet,\nso the output file can be archived without the input file.");
+                    snapshot_chck.set_checked(config.input_snapshot_enabled);
+                    snapshot_chck.clear_visible_focus();
+                    let mut run_info_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,280)
+                        .with_label("Add Run Info sheet with signature");
+                    run_info_chck.set_tooltip("If checked, the output will have a Run Info sheet with the program version, the input files,\nand an HMAC-SHA256 signature of the output values, keyed with a secret key kept by this install,\nwhich can be used to check whether the output was edited.");
+                    run_info_chck.set_checked(config.run_info_sheet_enabled);
+                    run_info_chck.clear_visible_focus();
+                    let mut anonymization_choice = Choice::default()
+                        .with_size(270,25)
+                        .with_pos(270,330)
+                        .with_label("Anonymize sample ids in output:")
+                        .with_align(Align::TopLeft);
+                    anonymization_choice.set_tooltip("Replaces sample ids in the output workbook with pseudonyms, for sharing results without revealing grower identities.\nA key file matching pseudonyms to sample ids is written next to the output. Don't share the key file.");
+                    anonymization_choice.add_choice("Off|Stable Hash|Sequential Codes");
+                    anonymization_choice.set_value(match config.sample_id_anonymization {
+                        SampleIdAnonymization::Off => 0,
+                        SampleIdAnonymization::StableHash => 1,
+                        SampleIdAnonymization::SequentialCodes => 2,
+                    });
+                    let mut output_format_choice = Choice::default()
+                        .with_size(170,22)
+                        .with_pos(370,368)
+                        .with_label("Output format:")
+                        .with_align(Align::Left);
+                    output_format_choice.set_tooltip("The xlsx output is always written. The output sheets can also be written\nto a csv file for each sheet, in a folder next to the output file named after it,\nor to one csv file next to the output file, with a section for each sheet.");
+                    output_format_choice.add_choice("XLSX only|XLSX + CSV file per sheet|XLSX + one CSV with sections");
+                    output_format_choice.set_value(match config.output_format {
+                        OutputFormat::Xlsx => 0,
+                        OutputFormat::XlsxAndCsvFiles => 1,
+                        OutputFormat::XlsxAndCsvSections => 2,
+                    });
+                    // (name shown to user, character)
+                    let csv_delimiters = [("Comma", ','), ("Semicolon", ';'), ("Tab", '\t')];
+                    let csv_decimal_separators = [("Period", '.'), ("Comma", ',')];
+                    let mut csv_delimiter_choice = Choice::default()
+                        .with_size(125,25)
+                        .with_pos(270,415)
+                        .with_label("CSV delimiter:")
+                        .with_align(Align::TopLeft);
+                    csv_delimiter_choice.set_tooltip("The character between values in csv files written by this program,\nincluding the kernel csv files. Excel on European-locale machines expects Semicolon.");
+                    for (delimiter_name, _) in csv_delimiters.iter() {csv_delimiter_choice.add_choice(delimiter_name);}
+                    csv_delimiter_choice.set_value(csv_delimiters.iter().position(|(_, delimiter)| *delimiter == config.csv_output_delimiter).unwrap_or(0) as i32);
+                    let mut csv_decimal_choice = Choice::default()
+                        .with_size(135,25)
+                        .with_pos(405,415)
+                        .with_label("CSV decimal separator:")
+                        .with_align(Align::TopLeft);
+                    csv_decimal_choice.set_tooltip("The decimal separator of numbers in csv files written by this program,\nincluding the kernel csv files. Excel on European-locale machines expects Comma.");
+                    for (separator_name, _) in csv_decimal_separators.iter() {csv_decimal_choice.add_choice(separator_name);}
+                    csv_decimal_choice.set_value(csv_decimal_separators.iter().position(|(_, separator)| *separator == config.csv_output_decimal_separator).unwrap_or(0) as i32);
+                    let mut json_output_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,455)
+                        .with_label("Also write output sheets as json");
+                    json_output_chck.set_tooltip("If checked, the output sheets are also written to a json file next to the output file,\nfor reading into other programs.");
+                    json_output_chck.set_checked(config.json_output_enabled);
+                    json_output_chck.clear_visible_focus();
+                    let mut sqlite_output_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,480)
+                        .with_label("Also write output sheets to sqlite");
+                    sqlite_output_chck.set_tooltip("If checked, the output sheets are also written to a sqlite database next to the output file,\nwith a table for each sheet.");
+                    sqlite_output_chck.set_checked(config.sqlite_output_enabled);
+                    sqlite_output_chck.clear_visible_focus();
+                    // sqlite output is only written by builds with the sqlite feature
+                    if !cfg!(feature = "sqlite") {sqlite_output_chck.deactivate();}
+                    let mut name_template_buf = TextBuffer::default();
+                    name_template_buf.set_text(&config.output_name_template);
+                    let mut name_template_box = TextEditor::default()
+                        .with_size(130,30)
+                        .with_pos(270,525)
+                        .with_label("Output name template:")
+                        .with_align(Align::TopLeft);
+                    name_template_box.set_tooltip("The name used for the output file when none is typed, such as {csv_stem}_{date}_{operator}.\nThe tokens {csv_stem}, {date}, {preset}, and {operator} are filled in when the output is written,\nand can also be typed into the output file box.");
+                    name_template_box.set_frame(FrameType::GtkDownFrame);
+                    name_template_box.set_scrollbar_align(Align::Bottom);
+                    name_template_box.set_scrollbar_size(7);
+                    name_template_box.set_buffer(name_template_buf);
+                    let mut operator_name_buf = TextBuffer::default();
+                    operator_name_buf.set_text(&config.operator_name);
+                    let mut operator_name_box = TextEditor::default()
+                        .with_size(130,30)
+                        .with_pos(410,525)
+                        .with_label("Operator name:")
+                        .with_align(Align::TopLeft);
+                    operator_name_box.set_tooltip("The name or initials of the person running the program on this machine,\nused for the {operator} token in output names. This is kept when switching presets.");
+                    operator_name_box.set_frame(FrameType::GtkDownFrame);
+                    operator_name_box.set_scrollbar_align(Align::Bottom);
+                    operator_name_box.set_scrollbar_size(7);
+                    operator_name_box.set_buffer(operator_name_buf);
+                    let mut operator_prompt_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,565)
+                        .with_label("Ask for operator name at startup");
+                    operator_prompt_chck.set_tooltip("If checked, the operator name is asked for each time the program starts,\nsuch as on a machine shared by several people.");
+                    operator_prompt_chck.set_checked(config.operator_prompt_enabled);
+                    operator_prompt_chck.clear_visible_focus();
+                    let mut merged_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,590)
+                        .with_label("Add Merged sheet joining csv and xml");
+                    merged_chck.set_tooltip("If checked, the output will have a Merged sheet with the csv stat columns and xml sieve data\nof each sample side by side on one row, matched by the sample-id columns.\nBoth a csv and an xml file need to be loaded.");
+                    merged_chck.set_checked(config.merged_sheet_enabled);
+                    merged_chck.clear_visible_focus();
+                    let mut charts_chck = CheckButton::default()
+                        .with_size(270,20)
+                        .with_pos(270,615)
+                        .with_label("Add charts to output sheets");
+                    charts_chck.set_tooltip("If checked, the Class_Percents sheet will have a stacked column chart of the class percents of each sample,\nand the CSV_Stats sheet will have a scatter of the average Area against the average Weight.");
+                    charts_chck.set_checked(config.output_charts_enabled);
+                    charts_chck.clear_visible_focus();
+
+                    GUI::lock_setting_widgets(&locked_ref_clone.borrow(), &mut vec![
+                        (font_name_box.as_base_widget(), "output_font_name"),
+                        (font_size_box.as_base_widget(), "output_font_size"),
+                        (kernel_csv_chck.as_base_widget(), "kernel_csv_export_enabled"),
+                        (protect_chck.as_base_widget(), "sheet_protection_enabled"),
+                        (password_input.as_base_widget(), "sheet_protection_password"),
+                        (diagnostics_chck.as_base_widget(), "diagnostics_sheet_enabled"),
+                        (split_chck.as_base_widget(), "split_output_enabled"),
+                        (split_col_box.as_base_widget(), "split_output_column"),
+                        (drift_chck.as_base_widget(), "drift_sheet_enabled"),
+                        (drift_time_box.as_base_widget(), "drift_timestamp_header"),
+                        (review_chck.as_base_widget(), "review_flagged_enabled"),
+                        (review_kernels_box.as_base_widget(), "review_min_kernels"),
+                        (sample_link_box.as_base_widget(), "sample_link_template"),
+                        (treatment_chck.as_base_widget(), "treatment_sheet_enabled"),
+                        (metadata_path_box.as_base_widget(), "metadata_file_path"),
+                        (metadata_browse_btn.as_base_widget(), "metadata_file_path"),
+                        (metadata_id_box.as_base_widget(), "metadata_sample_id_header"),
+                        (treatment_cols_box.as_base_widget(), "treatment_columns"),
+                        (treatment_metrics_box.as_base_widget(), "treatment_metrics"),
+                        (snapshot_chck.as_base_widget(), "input_snapshot_enabled"),
+                        (run_info_chck.as_base_widget(), "run_info_sheet_enabled"),
+                        (anonymization_choice.as_base_widget(), "sample_id_anonymization"),
+                        (output_format_choice.as_base_widget(), "output_format"),
+                        (csv_delimiter_choice.as_base_widget(), "csv_output_delimiter"),
+                        (csv_decimal_choice.as_base_widget(), "csv_output_decimal_separator"),
+                        (json_output_chck.as_base_widget(), "json_output_enabled"),
+                        (sqlite_output_chck.as_base_widget(), "sqlite_output_enabled"),
+                        (name_template_box.as_base_widget(), "output_name_template"),
+                        (operator_name_box.as_base_widget(), "operator_name"),
+                        (operator_prompt_chck.as_base_widget(), "operator_prompt_enabled"),
+                        (merged_chck.as_base_widget(), "merged_sheet_enabled"),
+                        (charts_chck.as_base_widget(), "output_charts_enabled"),
+                    ]);
+                    dialog_window.end();
+
+                    dialog_window.set_callback({
+                        let clicked_ref = (&clicked_ok).clone();
+                        let config_clone = (&config_clone).clone();
+                        move |win| {
+                            let clicked_ok = clicked_ref.borrow();
+                            if *clicked_ok {
+                                let mut config = config_clone.borrow_mut();
+                                config.output_font_name = font_name_box.buffer().unwrap().text().trim().to_string();
+                                config.kernel_csv_export_enabled = kernel_csv_chck.is_checked();
+                                config.sheet_protection_enabled = protect_chck.is_checked();
+                                config.sheet_protection_password = password_input.value();
+                                config.input_snapshot_enabled = snapshot_chck.is_checked();
+                                config.run_info_sheet_enabled = run_info_chck.is_checked();
+                                config.diagnostics_sheet_enabled = diagnostics_chck.is_checked();
+                                config.split_output_enabled = split_chck.is_checked();
+                                config.split_output_column = split_col_box.buffer().unwrap().text().trim().to_string();
+                                config.drift_sheet_enabled = drift_chck.is_checked();
+                                config.drift_timestamp_header = drift_time_box.buffer().unwrap().text().trim().to_string();
+                                config.review_flagged_enabled = review_chck.is_checked();
+                                config.sample_link_template = sample_link_box.buffer().unwrap().text().trim().to_string();
+                                config.output_format = match output_format_choice.value() {
+                                    1 => OutputFormat::XlsxAndCsvFiles,
+                                    2 => OutputFormat::XlsxAndCsvSections,
+                                    _ => OutputFormat::Xlsx,
+                                };
+                                config.csv_output_delimiter = csv_delimiters.get(csv_delimiter_choice.value() as usize).map(|(_, delimiter)| *delimiter).unwrap_or(',');
+                                config.csv_output_decimal_separator = csv_decimal_separators.get(csv_decimal_choice.value() as usize).map(|(_, separator)| *separator).unwrap_or('.');
+                                config.json_output_enabled = json_output_chck.is_checked();
+                                config.sqlite_output_enabled = sqlite_output_chck.is_checked();
+                                config.output_name_template = name_template_box.buffer().unwrap().text().trim().to_string();
+                                config.operator_name = operator_name_box.buffer().unwrap().text().trim().to_string();
+                                config.operator_prompt_enabled = operator_prompt_chck.is_checked();
+                                config.merged_sheet_enabled = merged_chck.is_checked();
+                                config.output_charts_enabled = charts_chck.is_checked();
+                                config.sample_id_anonymization = match anonymization_choice.value() {
+                                    1 => SampleIdAnonymization::StableHash,
+                                    2 => SampleIdAnonymization::SequentialCodes,
+                                    _ => SampleIdAnonymization::Off,
+                                };
+                                config.treatment_sheet_enabled = treatment_chck.is_checked();
+                                config.metadata_file_path = metadata_path_box.buffer().unwrap().text().trim().to_string();
+                                config.metadata_sample_id_header = metadata_id_box.buffer().unwrap().text().trim().to_string();
+                                config.treatment_columns = treatment_cols_box.buffer().unwrap().text()
+                                    .split(',').map(|el| el.trim().to_owned()).filter(|el| el != "").collect();
+                                config.treatment_metrics = treatment_metrics_box.buffer().unwrap().text()
+                                    .split(',').map(|el| el.trim().to_owned()).filter(|el| el != "").collect();
+                                let font_size = font_size_box.buffer().unwrap().text().trim().parse::<u16>();
+                                let review_min_kernels = review_kernels_box.buffer().unwrap().text().trim().parse::<usize>();
+                                match (font_size, review_min_kernels) {
+                                    (Ok(font_size), Ok(review_min_kernels)) => {
+                                        config.output_font_size = font_size;
+                                        config.review_min_kernels = review_min_kernels;
+                                        dialog::message_title("Success!");
+                                        dialog::message_default("Advanced Output Options have been successfully updated.");
+                                    },
+                                    _ => {
+                                        dialog::message_title("Invalid Number");
+                                        dialog::alert_default("The font size and minimum kernels for review must be whole numbers.\nThe previous values for those will be kept.");
+                                    },
+                                }//end matching whether we could parse the font size and minimum kernels
+                            }//end if user clicked ok to change their config
+                            win.hide();
+                        }//end closure
+                    });
+
+                    let dialog_window_ref = Rc::from(RefCell::from(dialog_window));
+                    ok_button.set_callback({
+                        let window_ref = (&dialog_window_ref).clone();
+                        let ok_ref = (&clicked_ok).clone();
+                        move |_| {
+                            let mut window_ref = window_ref.borrow_mut();
+                            *(ok_ref.borrow_mut()) = true;
+                            window_ref.do_callback();
+                        }//end moving closure
+                    });
+                    cancel_button.set_callback({
+                        let window_ref = (&dialog_window_ref).clone();
+                        move |_| {
+                            let mut window_ref = window_ref.borrow_mut();
+                            window_ref.do_callback();
+                        }//end moving closure
+                    });
+
+                    let window_ref_clone = (&dialog_window_ref).clone();
+                    let mut window_ref = window_ref_clone.borrow_mut();
+                    window_ref.show();
+                } else {
+                    let mut output_file_ref = output_file_ref_clone.as_ref().borrow_mut();
+                    let mut output_file_buf = output_file_ref.buffer().unwrap_or_else(|| TextBuffer::default());
+                    if let Err(err_message) = GUI::create_io_dialog(&sender_clone, "OutputFile", &mut output_file_buf, dialog::NativeFileChooserType::BrowseSaveFile, dialog::NativeFileChooserOptions::SaveAsConfirm, "", "Please specify the output file.") {
+                        log::warn!("Encountered an error when attempting to show file dialog:\n{}", err_message);
+                    }//end if we got an error
+                    output_file_ref.set_buffer(output_file_buf);
+                }//end else user didn't right-click
             }//end moving for closure
         });
 
         // process the data we have
         let mut process_file_btn = Button::default()
             .with_label("Process Data")
-            .with_pos(output_file_btn.x() + 60, output_file_btn.y() + output_file_btn.h() + 10)
-            .with_size(250, 50);
-        process_file_btn.emit(s.clone(), InterfaceMessage::ProcessSum);
+            .with_pos(output_file_btn.x(), output_file_btn.y() + output_file_btn.h() + 10)
+            .with_size(230, 50);
+        process_file_btn.set_tooltip("Left Click this button to process the data and write the output file.\nRight Click this button to choose the order and visibility of columns in the output,\nto choose the preprocessing steps run on the csv data before processing,\nto define the report metrics shown on the Metrics sheet,\nto define the rules for the Flags column, to add formula columns to the output,\nto choose what happens when the loaded data has problems, such as missing columns,\nto define the conventions of the people the output is sent to, such as decimal places and sheet names,\nto look over the statistics of each column in the loaded data,\nto reprocess selected samples from the last output with the current settings,\nto view or print the tables from the last output, to compare them with the results of another dataset,\nto get summary text of the last output for an email, to export it as a zip for archiving, to browse every past run in the run ledger, to check whether an output file has been edited,\nto open a summary written before to look at it or write it in another format,\nto merge several summaries into one, such as for a season,\nto process every run in a folder of csv and xml files at once,\nto summarize a csv file too large to load, reading it a row at a time,\nor to record the steps of this session and replay them on new files.");
+        process_file_btn.set_callback({
+            let sender_clone = s.clone();
+            let config_ref_clone = (&config_ref).clone();
+            move |_| {
+                if app::event_button() == 3 {
+                    // options which change settings are left out in operator mode
+                    let process_menu = match config_ref_clone.borrow().operator_mode_enabled {
+                        true => MenuItem::new(&["Explore Loaded Data", "View Results", "Pin Results for Comparison", "Compare with Pinned Results", "Print Summary", "Email Summary Text", "Export Run Bundle", "View Run Ledger", "View Log", "Verify Output File", "Open Existing Summary", "Merge Summary Workbooks", "Batch Process Folder", "Summarize Large CSV File", "Replay Recording on New Files"]),
+                        false => MenuItem::new(&["Edit Column Layout", "Edit Processing Pipeline", "Edit Column Aliases", "Edit Report Metrics", "Edit Flag Rules", "Edit Formula Columns", "Edit Column Groups", "Edit Warning Policies", "Edit Recipient Profiles", "Explore Loaded Data", "Reprocess Samples", "View Results", "Pin Results for Comparison", "Compare with Pinned Results", "Print Summary", "Email Summary Text", "Export Run Bundle", "View Run Ledger", "View Log", "Verify Output File", "Open Existing Summary", "Merge Summary Workbooks", "Batch Process Folder", "Summarize Large CSV File", "Start or Stop Recording Session", "Replay Recording on New Files"]),
+                    };
+                    match process_menu.popup(app::event_x(), app::event_y()).and_then(|item| item.label()).as_deref() {
+                        Some("Edit Column Layout") => sender_clone.send(InterfaceMessage::EditColumnLayout),
+                        Some("Edit Processing Pipeline") => sender_clone.send(InterfaceMessage::EditPipeline),
+                        Some("Edit Column Aliases") => sender_clone.send(InterfaceMessage::EditHeaderAliases),
+                        Some("Edit Report Metrics") => sender_clone.send(InterfaceMessage::EditMetrics),
+                        Some("Edit Flag Rules") => sender_clone.send(InterfaceMessage::EditFlagRules),
+                        Some("Edit Formula Columns") => sender_clone.send(InterfaceMessage::EditFormulaColumns),
+                        Some("Edit Column Groups") => sender_clone.send(InterfaceMessage::EditColumnGroups),
+                        Some("Edit Warning Policies") => sender_clone.send(InterfaceMessage::EditWarningPolicies),
+                        Some("Edit Recipient Profiles") => sender_clone.send(InterfaceMessage::EditRecipientProfiles),
+                        Some("Explore Loaded Data") => sender_clone.send(InterfaceMessage::ExploreData),
+                        Some("Reprocess Samples") => sender_clone.send(InterfaceMessage::ReprocessSamples),
+                        Some("View Results") => sender_clone.send(InterfaceMessage::ViewResults),
+                        Some("Pin Results for Comparison") => sender_clone.send(InterfaceMessage::PinResults),
+                        Some("Compare with Pinned Results") => sender_clone.send(InterfaceMessage::CompareResults),
+                        Some("Print Summary") => sender_clone.send(InterfaceMessage::PrintSummary),
+                        Some("Email Summary Text") => sender_clone.send(InterfaceMessage::ShowRunSummary),
+                        Some("Export Run Bundle") => sender_clone.send(InterfaceMessage::ExportRunBundle),
+                        Some("View Run Ledger") => sender_clone.send(InterfaceMessage::ViewRunLedger),
+                        Some("View Log") => sender_clone.send(InterfaceMessage::ViewLog),
+                        Some("Verify Output File") => GUI::choose_output_to_verify(&sender_clone),
+                        Some("Open Existing Summary") => GUI::choose_summary_to_open(&sender_clone),
+                        Some("Merge Summary Workbooks") => GUI::choose_summaries_to_merge(&sender_clone),
+                        Some("Batch Process Folder") => GUI::choose_batch_folder(&sender_clone),
+                        Some("Summarize Large CSV File") => GUI::choose_large_csv(&sender_clone),
+                        Some("Start or Stop Recording Session") => sender_clone.send(InterfaceMessage::ToggleRecording),
+                        Some("Replay Recording on New Files") => sender_clone.send(InterfaceMessage::ReplayRecording),
+                        _ => {},
+                    }//end matching what the user wants to do
+                } else {
+                    sender_clone.send(InterfaceMessage::ProcessSum);
+                }//end else user didn't right-click
+            }//end moving for closure
+        });
         process_file_btn.set_frame(io_btn_frame);
         process_file_btn.set_down_frame(io_btn_down_frame);
         process_file_btn.clear_visible_focus();
@@ -712,6 +5137,46 @@ impl GUI {
         process_file_btn.set_selection_color(io_btn_down_color);
         io_controls_group.add_resizable(&process_file_btn);
 
+        let mut filter_set_choice = Choice::default()
+            .with_pos(process_file_btn.x() + process_file_btn.w() + io_btn_padding, process_file_btn.y() + 20)
+            .with_size(io_controls_group.x() + io_controls_group.w() - (process_file_btn.x() + process_file_btn.w() + 2 * io_btn_padding), 25)
+            .with_label("Filter Set:")
+            .with_align(Align::TopLeft);
+        filter_set_choice.add_choice("(Config Filters)");
+        filter_set_choice.set_value(0);
+        filter_set_choice.set_tooltip("The filters to use when processing.\n(Config Filters) uses the class filters in the configuration settings and the filters in the processing pipeline.\nRight click to save the current filters as a named set, or to delete the chosen set.");
+        filter_set_choice.clear_visible_focus();
+        filter_set_choice.set_callback({
+            let config_ref_clone = (&config_ref).clone();
+            move |choice| {
+                let mut config = config_ref_clone.as_ref().borrow_mut();
+                // the first choice is the config's own filters, and the rest are the sets in order
+                config.active_filter_set = match choice.value() {
+                    idx if idx > 0 => config.filter_sets.get(idx as usize - 1).map(|filter_set| filter_set.name.clone()).unwrap_or_default(),
+                    _ => "".to_string(),
+                };
+            }//end moving for closure
+        });
+        filter_set_choice.handle({
+            let sender_clone = s.clone();
+            move |_, ev| {
+                match ev {
+                    // event_button => 1 for left click, 2 for middle, 3 for right
+                    Event::Push if app::event_button() == 3 => {
+                        let filter_set_menu = MenuItem::new(&["Save Current Filters as a Set", "Delete Chosen Set"]);
+                        match filter_set_menu.popup(app::event_x(), app::event_y()).and_then(|item| item.label()).as_deref() {
+                            Some("Save Current Filters as a Set") => sender_clone.send(InterfaceMessage::SaveFilterSet),
+                            Some("Delete Chosen Set") => sender_clone.send(InterfaceMessage::DeleteFilterSet),
+                            _ => {},
+                        }//end matching what the user wants to do
+                        true
+                    },
+                    _ => false
+                }
+            }//end moving for closure
+        });
+        io_controls_group.add(&filter_set_choice);
+
         // set up group with configuration options
         let mut config_group = Group::default()
             .with_pos(io_controls_group.x() + io_controls_group.w(), 0)
@@ -719,16 +5184,27 @@ impl GUI {
         config_group.end();
         config_group.set_color(Color::from_rgb(220,239,220));
         tile_group.add(&config_group);
+
+        // config widgets are laid out in a column, so the stat columns box takes up any extra height
+        let mut config_flex = Flex::default()
+            .with_pos(config_group.x() + cf_padding, config_group.y() + 10)
+            .with_size(config_group.w() - cf_padding * 2, config_group.h() - 10 - cf_padding)
+            .column();
+        config_flex.end();
+        config_flex.set_pad(cf_padding);
+        config_group.add_resizable(&config_flex);
+        let mut config_title_row = Flex::default().row();
+        config_title_row.end();
+        config_flex.add(&config_title_row);
+        config_flex.fixed(&config_title_row, 20);
         
         let mut config_label = Frame::default()
-            .with_pos(config_group.x(), config_group.y() + 10)
-            .with_size(config_group.width(), 20)
             .with_label("Configuration Settings")
             .with_align(Align::Inside);
         config_label.set_label_size(16);
-        config_group.add(&config_label);
+        config_title_row.add(&config_label);
         
-        config_label.set_tooltip("Right click if you want to change config presets.");
+        config_label.set_tooltip("Right click if you want to change config presets,\nsave the current settings as a preset, or rename and delete presets.");
         config_label.handle({
             let sender_clone = s.clone();
             move |_, ev| {
@@ -736,9 +5212,13 @@ impl GUI {
                     Event::Released => {
                         // event_button => 1 for left click, 2 for middle, 3 for right
                         if app::event_button() == 3 {
-                            if GUI::show_yes_no_message("Would you like to reset the current configuration preset?") {
-                                sender_clone.send(InterfaceMessage::ConfigReset);
-                            }//end if we want to reset the current config preset
+                            let preset_menu = MenuItem::new(&["Switch Preset", "Save Settings as Preset", "Manage Presets"]);
+                            match preset_menu.popup(app::event_x(), app::event_y()).and_then(|item| item.label()).as_deref() {
+                                Some("Switch Preset") => sender_clone.send(InterfaceMessage::ConfigReset),
+                                Some("Save Settings as Preset") => sender_clone.send(InterfaceMessage::SavePreset),
+                                Some("Manage Presets") => sender_clone.send(InterfaceMessage::ManagePresets),
+                                _ => {},
+                            }//end matching the chosen menu item
                         }//end if we have a right-click event
                         true
                     },
@@ -747,22 +5227,56 @@ impl GUI {
             }//end moving for closure
         });
 
+        let mut config_help_btn = Button::default()
+            .with_label("?");
+        config_help_btn.set_frame(FrameType::GtkRoundUpFrame);
+        config_help_btn.set_down_frame(FrameType::GtkRoundDownFrame);
+        config_help_btn.set_tooltip("Click to show help on the configuration settings.\nYou can also press F1 while hovering over any setting.");
+        config_help_btn.clear_visible_focus();
+        config_title_row.add(&config_help_btn);
+        config_title_row.fixed(&config_help_btn, 20);
+        config_help_btn.set_callback(|_| help::show_help_dialog("config"));
+
+        let mut view_log_btn = Button::default()
+            .with_label("Log");
+        view_log_btn.set_frame(FrameType::GtkRoundUpFrame);
+        view_log_btn.set_down_frame(FrameType::GtkRoundDownFrame);
+        view_log_btn.set_tooltip("Click to open the log file, which lists the errors, warnings,\nand other messages from this and earlier sessions.");
+        view_log_btn.clear_visible_focus();
+        config_title_row.add(&view_log_btn);
+        config_title_row.fixed(&view_log_btn, 35);
+        view_log_btn.set_callback({
+            let sender_clone = s.clone();
+            move |_| sender_clone.send(InterfaceMessage::ViewLog)
+        });
+
+        let mut config_collapse_btn = Button::default()
+            .with_label("@>>");
+        config_collapse_btn.set_frame(FrameType::GtkRoundUpFrame);
+        config_collapse_btn.set_down_frame(FrameType::GtkRoundDownFrame);
+        config_collapse_btn.set_tooltip("Click to collapse the configuration settings, leaving more room for the rest of the window.");
+        config_collapse_btn.clear_visible_focus();
+        config_title_row.add(&config_collapse_btn);
+        config_title_row.fixed(&config_collapse_btn, 20);
+        config_collapse_btn.set_callback({
+            let sender_clone = s.clone();
+            move |_| sender_clone.send(InterfaceMessage::ToggleConfigPanel)
+        });
+
         let config_preset_frm = Frame::default()
-            .with_pos(config_label.x(), config_label.y() + config_label.h())
-            .with_size(config_label.w(),config_label.h())
             .with_label("No Named Preset Active")
             .with_align(Align::Inside);
-        config_group.add(&config_preset_frm);
+        config_flex.add(&config_preset_frm);
+        config_flex.fixed(&config_preset_frm, 20);
 
         let mut class_filter_chck = CheckButton::default()
-            .with_pos(config_preset_frm.x() + cf_padding, config_preset_frm.y() + config_preset_frm.h() + cf_padding)
-            .with_size(config_group.w() - cf_padding * 2,cf_chck_height)
             .with_label("Filter CSV Stat Columns to Class:");
         class_filter_chck.set_checked(true);
         class_filter_chck.set_frame(cf_chck_frame);
         class_filter_chck.set_tooltip("If checked, processing will only consider rows in csv data matching the given classification(s).\nRight click if you want to configure which column is considered for class filtering.");
         class_filter_chck.clear_visible_focus();
-        config_group.add(&class_filter_chck);
+        config_flex.add(&class_filter_chck);
+        config_flex.fixed(&class_filter_chck, cf_chck_height);
         class_filter_chck.set_callback({
             let config_ref_clone = (&config_ref).clone();
             move |chck| {
@@ -778,64 +5292,185 @@ impl GUI {
         });
 
         let mut class_filter_buf = TextBuffer::default();
-        let mut class_filter_box = TextEditor::default()
-            .with_pos(class_filter_chck.x(), class_filter_chck.y() + class_filter_chck.h() + cf_padding)
-            .with_size(config_group.width() - cf_padding * 2, 25);
+        let mut class_filter_box = TextEditor::default();
         class_filter_box.set_buffer(class_filter_buf.clone());
         class_filter_buf.set_text("Sound");
         class_filter_box.set_frame(cf_box_frame);
         class_filter_box.set_tooltip("Class(es) to filter for when calculating stat columns.\nSeparate values by a comma or |. When separating by comma, include 1 or 0 spaces after the comma. When separating by |, include 1 space on either side or no space on either side.");
         class_filter_box.set_scrollbar_align(Align::Clip);
-        config_group.add_resizable(&class_filter_box);
+        config_flex.add(&class_filter_box);
+        config_flex.fixed(&class_filter_box, 25);
 
         let mut stat_cols_chck = CheckButton::default()
-            .with_pos(class_filter_chck.x(), class_filter_box.y() + class_filter_box.h() + cf_padding)
-            .with_size(config_group.w() - cf_padding * 2, cf_chck_height)
             .with_label("Output CSV Stat Columns:");
         stat_cols_chck.set_checked(true);
         stat_cols_chck.set_frame(cf_chck_frame);
-        stat_cols_chck.set_tooltip("If checked, then columns will be added to the output with the Avg and Stdev per sample of certain columns in the CSV data.");
+        stat_cols_chck.set_tooltip("If checked, then columns will be added to the output with the Avg and Stdev per sample of certain columns in the CSV data.\nRight click if you want to configure how the output columns are named,\nor to group the rows of each sample by another column, such as Treatment.");
         stat_cols_chck.clear_visible_focus();
-        config_group.add(&stat_cols_chck);
+        config_flex.add(&stat_cols_chck);
+        config_flex.fixed(&stat_cols_chck, cf_chck_height);
+        stat_cols_chck.set_callback({
+            let config_ref_clone = (&config_ref).clone();
+            move |chck| {
+                if app::event_button() == 3 {
+                    // this is just done to cancel the toggle of checked
+                    chck.set_checked(!chck.is_checked());
+                    let mut config = config_ref_clone.as_ref().borrow_mut();
+                    if let Some(choice) = dialog::input_default("Please indicate how average columns should be named.\n{col} will be replaced with the name of the csv column.\nThe default is Avg {col}", &config.csv_stat_avg_header_template) {
+                        config.csv_stat_avg_header_template = choice;
+                    }//end if user chose to change setting
+                    if let Some(choice) = dialog::input_default("Please indicate how standard deviation columns should be named.\n{col} will be replaced with the name of the csv column.\nThe default is Std {col}", &config.csv_stat_std_header_template) {
+                        config.csv_stat_std_header_template = choice;
+                    }//end if user chose to change setting
+                    if let Some(choice) = dialog::input_default(&format!("Please list any other statistics to add for each column, separated by commas.\nThe choices are {}. CV is the coefficient of variation.\nLeave this empty for only the average and standard deviation.", stats::EXTRA_STATS.join(", ")), &config.csv_stat_columns_stats.join(", ")) {
+                        let extra_stats: Vec<&str> = choice.split(',').map(|stat| stat.trim()).filter(|stat| !stat.is_empty()).collect();
+                        match extra_stats.iter().map(|stat| stats::parse_extra_stat(stat)).collect::<Result<Vec<&str>,String>>() {
+                            Ok(extra_stats) => config.csv_stat_columns_stats = extra_stats.into_iter().map(|stat| stat.to_string()).collect(),
+                            Err(msg) => dialog::alert_default(&format!("{}\nThe other statistics weren't changed.", msg)),
+                        }//end matching whether we know each statistic
+                    }//end if user chose to change setting
+                    if let Some(choice) = dialog::input_default("Please indicate a column to group the rows of each sample by, such as Treatment or Plot,\nto get stats for each group within each sample.\nLeave this empty to only group rows by sample id.", &config.csv_stat_group_header) {
+                        config.csv_stat_group_header = choice.trim().to_string();
+                        if !config.csv_stat_group_header.is_empty() {
+                            match dialog::choice2_default(&format!("Should each {} be a column after the sample id, or get its own sheet?", config.csv_stat_group_header), "Group Column", "Sheet per Group", "") {
+                                Some(0) => config.csv_stat_group_output = StatGroupOutput::GroupColumn,
+                                Some(1) => config.csv_stat_group_output = StatGroupOutput::SheetPerGroup,
+                                _ => (),
+                            }//end matching how the user wants groups shown
+                        }//end if the user chose a column to group by
+                    }//end if user chose to change setting
+                }//end if user right-clicked
+            }//end moving closure
+        });
 
         let mut stat_cols_buf = TextBuffer::default();
-        let mut stat_cols_box = TextEditor::default()
-            .with_pos(stat_cols_chck.x(), stat_cols_chck.y() + stat_cols_chck.h() + cf_padding)
-            .with_size(stat_cols_chck.w(), 235);
+        let mut stat_cols_box = TextEditor::default();
         stat_cols_box.set_buffer(stat_cols_buf.clone());
         stat_cols_buf.set_text("Area, Length, Width, Thickness, \nRatio, Mean Width, Volume, Weight\nLight, Hue, Saturation\nRed, Green, Blue");
         stat_cols_box.set_frame(cf_box_frame);
-        stat_cols_box.set_tooltip("Columns in CSV input to do statistics on. Separate values by a new line or comma. When separating by comma, include 1 or 0 spaces after the comma. To get a list of potential column headers, click this box and press F1.");
+        stat_cols_box.set_tooltip("Columns in CSV input to do statistics on. Separate values by a new line or comma. When separating by comma, include 1 or 0 spaces after the comma. To get a list of potential column headers, hover over this box and press F1.");
         stat_cols_box.set_scrollbar_align(Align::Right);
         stat_cols_box.set_scrollbar_size(12);
-        config_group.add_resizable(&stat_cols_box);
-
-        stat_cols_box.add_key_binding(fltk::enums::Key::F1, fltk::enums::Shortcut::None, |_, _| {
-            dialog::message_title("Some Potential Column Headings");
-            dialog::message(0, 0, "Some of the possible column headers are: \nArea, Length, Thickness, Mean Width, Ratio, Volume, Weight, \nBrightness, Hue, Saturation, Red, Green, Blue, Severity.");
-            0
-        });
-        
+        // not fixed, so that it grows with the window
+        config_flex.add(&stat_cols_box);
 
         let mut class_perc_chck = CheckButton::default()
-            .with_pos(stat_cols_chck.x(), stat_cols_box.y() + stat_cols_box.h() + cf_padding)
-            .with_size(stat_cols_chck.w(), cf_chck_height)
             .with_label("Outut % per Class per Sample in CSV");
         class_perc_chck.set_checked(true);
         class_perc_chck.set_frame(cf_chck_frame);
-        class_perc_chck.set_tooltip("If checked, then columns will be added to the output giving the percentage of each sample of each possible classification. These percentages are calculated independently of any other classification fitlering.");
+        class_perc_chck.set_tooltip("If checked, then columns will be added to the output giving the percentage of each sample of each possible classification. These percentages are calculated independently of any other classification fitlering.\nRight click if you want to configure how the output columns are named, which kernels the percents are out of, and the class taxonomy file for parent class totals.");
         class_perc_chck.clear_visible_focus();
-        config_group.add(&class_perc_chck);
+        config_flex.add(&class_perc_chck);
+        config_flex.fixed(&class_perc_chck, cf_chck_height);
+        class_perc_chck.set_callback({
+            let config_ref_clone = (&config_ref).clone();
+            move |chck| {
+                if app::event_button() == 3 {
+                    // this is just done to cancel the toggle of checked
+                    chck.set_checked(!chck.is_checked());
+                    let mut config = config_ref_clone.as_ref().borrow_mut();
+                    if let Some(choice) = dialog::input_default("Please indicate how class percent columns should be named.\n{class} will be replaced with the name of the class.\nThe default is %{class}", &config.csv_class_percent_header_template) {
+                        config.csv_class_percent_header_template = choice;
+                    }//end if user chose to change setting
+                    let current_denominator = match config.csv_class_percent_denominator {
+                        ClassPercentDenominator::AllKernels => "all kernels",
+                        ClassPercentDenominator::PassingNumericFilters => "kernels passing numeric filters",
+                        ClassPercentDenominator::ExcludingClass => "kernels not in an excluded class",
+                    };
+                    match dialog::choice2_default(&format!("Please indicate which kernels class percents should be out of.\nCurrently, percents are out of {}.", current_denominator), "All Kernels", "Passing Filters", "Excluding Class") {
+                        Some(0) => config.csv_class_percent_denominator = ClassPercentDenominator::AllKernels,
+                        Some(1) => {
+                            if let Some(choice) = dialog::input_default("Please indicate the numeric filters kernels must pass, separated by commas.\nExample: Area > 10, Length <= 7.5", &config.csv_numeric_filters.join(", ")) {
+                                config.csv_numeric_filters = choice.split(',').map(|el| el.trim().to_owned()).filter(|el| el != "").collect();
+                                config.csv_class_percent_denominator = ClassPercentDenominator::PassingNumericFilters;
+                            }//end if user chose filters
+                        },
+                        Some(2) => {
+                            if let Some(choice) = dialog::input_default("Please indicate the class to leave out of class percents.\nThe default is Debris", &config.csv_class_percent_excluded_class) {
+                                config.csv_class_percent_excluded_class = choice.trim().to_string();
+                                config.csv_class_percent_denominator = ClassPercentDenominator::ExcludingClass;
+                            }//end if user chose a class
+                        },
+                        _ => (),
+                    }//end matching which denominator the user chose
+                    let current_taxonomy = match config.class_taxonomy_file.trim().is_empty() {
+                        true => String::from("no taxonomy file is chosen"),
+                        false => format!("the taxonomy file is {}", config.class_taxonomy_file),
+                    };
+                    match dialog::choice2_default(&format!("Please choose a class taxonomy file to add columns totaling the percents of parent classes,\nsuch as Damaged for every kind of damage.\nCurrently, {}.", current_taxonomy), "Keep", "Choose File", "No Taxonomy") {
+                        Some(1) => {
+                            let mut file_chooser = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+                            file_chooser.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+                            file_chooser.set_filter("*.txt");
+                            file_chooser.set_title("Please select a class taxonomy file");
+                            file_chooser.show();
+                            let file_path = file_chooser.filename();
+                            if !file_path.as_os_str().is_empty() {
+                                match taxonomy::ClassTaxonomy::from_file(&file_path) {
+                                    Ok(_) => config.class_taxonomy_file = paths::get_setting_path(&file_path),
+                                    Err(msg) => dialog::alert_default(&format!("{}\nThe taxonomy file wasn't changed.", msg)),
+                                }//end matching whether the taxonomy file can be read
+                            }//end if the user chose a file
+                        },
+                        Some(2) => config.class_taxonomy_file = String::new(),
+                        _ => (),
+                    }//end matching what the user wants for the taxonomy
+                }//end if user right-clicked
+            }//end moving closure
+        });
 
         let mut xml_sieve_chck = CheckButton::default()
-            .with_pos(class_perc_chck.x(), class_perc_chck.y() + class_perc_chck.h() + cf_padding)
-            .with_size(stat_cols_chck.w(), cf_chck_height)
             .with_label("Output XML Sieve Data");
         xml_sieve_chck.set_checked(true);
         xml_sieve_chck.set_frame(cf_chck_frame);
         xml_sieve_chck.set_tooltip("If checked, then columns will be added to the output giving sieve data for each sample. Since this data is only found in the xml file, columns will only be added if an xml input file is loaded.");
         xml_sieve_chck.clear_visible_focus();
-        config_group.add(&xml_sieve_chck);
+        config_flex.add(&xml_sieve_chck);
+        config_flex.fixed(&xml_sieve_chck, cf_chck_height);
+
+        let mut lot_group_chck = CheckButton::default()
+            .with_label("Group Samples into Lots");
+        lot_group_chck.set_checked(false);
+        lot_group_chck.set_frame(cf_chck_frame);
+        lot_group_chck.set_tooltip("If checked, then samples will be grouped into lots, and a row for each lot will be added to the output after the rows for its samples.\nRight click if you want to configure how the lot of each sample is found.");
+        lot_group_chck.clear_visible_focus();
+        config_flex.add(&lot_group_chck);
+        config_flex.fixed(&lot_group_chck, cf_chck_height);
+        lot_group_chck.set_callback({
+            let config_ref_clone = (&config_ref).clone();
+            move |chck| {
+                if app::event_button() == 3 {
+                    // this is just done to cancel the toggle of checked
+                    chck.set_checked(!chck.is_checked());
+                    let mut config = config_ref_clone.as_ref().borrow_mut();
+                    if let Some(choice) = dialog::input_default("Please indicate the name of the csv column holding the lot of each row.\nLeave this empty to take the lot from the sample id instead.", &config.lot_id_header) {
+                        config.lot_id_header = choice.trim().to_string();
+                    }//end if user chose to change setting
+                    if let Some(choice) = dialog::input_default("When taking the lot from the sample id, the lot is the part\nof the sample id before the last instance of this delimiter.\nFor example, with -, the lot of W23-07-3 is W23-07.\nThe default is -", &config.lot_id_delimiter) {
+                        config.lot_id_delimiter = choice;
+                    }//end if user chose to change setting
+                    if let Some(choice) = dialog::input_default("Please indicate how lot rows should be labeled.\n{lot} will be replaced with the lot.\nThe default is Lot {lot}", &config.lot_row_label_template) {
+                        config.lot_row_label_template = choice;
+                    }//end if user chose to change setting
+                }//end if user right-clicked
+            }//end moving closure
+        });
+
+        // shown in place of the config widgets while they're collapsed
+        let mut config_expand_btn = Button::default()
+            .with_pos(config_group.x() + 5, config_group.y() + 10)
+            .with_size(20, 20)
+            .with_label("@<<");
+        config_expand_btn.set_frame(FrameType::GtkRoundUpFrame);
+        config_expand_btn.set_down_frame(FrameType::GtkRoundDownFrame);
+        config_expand_btn.set_tooltip("Click to show the configuration settings again.");
+        config_expand_btn.clear_visible_focus();
+        config_expand_btn.hide();
+        config_group.add(&config_expand_btn);
+        config_expand_btn.set_callback({
+            let sender_clone = s.clone();
+            move |_| sender_clone.send(InterfaceMessage::ToggleConfigPanel)
+        });
 
         let mut dialog_group = Group::default()
             .with_pos(io_controls_group.x(), io_controls_group.y() + io_controls_group.h())
@@ -870,6 +5505,8 @@ impl GUI {
         dialog_btns.set_color(Color::from_rgb(255,248,220));
         dialog_btns.set_frame(FrameType::FlatBox);
         dialog_group.add(&dialog_btns);
+        // extra height goes to the message, keeping the buttons the same size
+        dialog_group.resizable(&dialog_box);
 
         // set frame type for borders between sections, make sure to use box type
         let group_frames = FrameType::GtkThinUpBox;
@@ -879,13 +5516,59 @@ impl GUI {
         dialog_group.set_frame(group_frames);
         dialog_group.deactivate();
 
-        main_window.make_resizable(true);
+        // the status bar keeps its height when the window is resized
+        main_window.resizable(&tile_group);
+        let setting_widgets: Vec<(Widget, &'static str)> = vec![
+            (class_filter_chck.as_base_widget(), "csv_class_filter_enabled"),
+            (class_filter_box.as_base_widget(), "csv_class_filter_filters"),
+            (stat_cols_chck.as_base_widget(), "csv_stat_columns_enabled"),
+            (stat_cols_box.as_base_widget(), "csv_stat_columns_columns"),
+            (class_perc_chck.as_base_widget(), "csv_class_percent_enabled"),
+            (xml_sieve_chck.as_base_widget(), "xml_sieve_cols_enabled"),
+            (lot_group_chck.as_base_widget(), "lot_grouping_enabled"),
+            (filter_set_choice.as_base_widget(), "active_filter_set"),
+        ];
+        // F1 shows help for whatever section or setting the mouse is over
+        main_window.handle({
+            let help_regions: Vec<(Widget, &str)> = vec![
+                (class_filter_chck.as_base_widget(), "class_filter"),
+                (class_filter_box.as_base_widget(), "class_filter"),
+                (stat_cols_chck.as_base_widget(), "stat_columns"),
+                (stat_cols_box.as_base_widget(), "stat_columns"),
+                (class_perc_chck.as_base_widget(), "class_percent"),
+                (xml_sieve_chck.as_base_widget(), "xml_sieve"),
+                (lot_group_chck.as_base_widget(), "lot_grouping"),
+                (header_group.as_base_widget(), "header"),
+                (io_controls_group.as_base_widget(), "io"),
+                (config_group.as_base_widget(), "config"),
+                (dialog_group.as_base_widget(), "dialog"),
+            ];
+            let sender_clone = s.clone();
+            move |_, ev| {
+                match ev {
+                    Event::KeyDown | Event::Shortcut if app::is_event_ctrl() && app::event_key() == Key::from_char('k') => {
+                        sender_clone.send(InterfaceMessage::ShowCommandPalette);
+                        true
+                    },
+                    Event::KeyDown | Event::Shortcut => {
+                        if app::event_key() != Key::F1 {return false;}
+                        let topic_id = help_regions.iter()
+                            .find(|(widget, _)| app::event_inside_widget(widget))
+                            .map(|(_, topic_id)| *topic_id)
+                            .unwrap_or("header");
+                        help::show_help_dialog(topic_id);
+                        true
+                    },
+                    _ => false
+                }
+            }//end moving for closure
+        });
         // callback for window occurs when user tries to close it
         main_window.set_callback({
             let sender_clone = s.clone();
             move |_| {
                 sender_clone.send(InterfaceMessage::AppClosing);
-                println!("GUI Ready to Close!");
+                log::info!("GUI Ready to Close!");
             }
         });
         main_window.show();
@@ -896,7 +5579,13 @@ impl GUI {
             debug_log: Vec::new(),
             msg_sender: s,
             msg_receiver: r,
+            ux_header_group: header_group,
             ux_config_group: config_group,
+            ux_config_flex: config_flex,
+            ux_config_expand_btn: config_expand_btn,
+            ux_config_panel_width: None,
+            ux_status_bar: status_bar,
+            ux_progress_bar: progress_bar,
             ux_io_controls_group: io_controls_group,
             ux_dialog_group: dialog_group,
             ux_dialog_box: dialog_box,
@@ -910,11 +5599,145 @@ impl GUI {
             ux_cf_stat_cols_buf: stat_cols_buf,
             ux_cf_class_perc_chck: class_perc_chck,
             ux_cf_xml_sieve_chck: xml_sieve_chck,
+            ux_cf_lot_group_chck: lot_group_chck,
             ux_cf_setting_preset_buf: config_preset_frm,
+            ux_filter_set_choice: filter_set_choice,
             config_store: config_ref,
+            locked_settings: locked_ref,
+            ux_setting_widgets: setting_widgets,
+            recent_files: recent_files_ref,
         }//end struct construction
     }
 
+    /// Shows a file dialog for choosing any number of data files, then
+    /// sends them to main to be loaded by what kind of file each is.  
+    /// Used by the Open Data File(s) button and the command palette.
+    fn choose_data_files(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseMultiFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("Data Files\t*.{csv,xml,xlsx}\nAll Files\t*");
+        dialog.set_title("Please select one or more data files");
+        dialog.show();
+        let file_paths: Vec<PathBuf> = dialog.filenames().into_iter().filter(|path| !path.as_os_str().is_empty()).collect();
+        if file_paths.len() > 0 {sender.send(InterfaceMessage::OpenDataFiles(file_paths));}
+    }//end choose_data_files(sender)
+
+    /// Shows a menu of recent_files at the mouse, then sends the chosen
+    /// file to main to be loaded by what kind of file it is, the same as
+    /// a file chosen with the Open Data File(s) button.  
+    /// Used by the Open Data File(s) button and the command palette.
+    fn choose_recent_file(sender: &Sender<InterfaceMessage>, recent_files: &Vec<PathBuf>) {
+        if recent_files.len() == 0 {
+            MenuItem::new(&["No Recent Files"]).popup(app::event_x(), app::event_y());
+            return;
+        }//end if there aren't any recent files
+        // labels are numbered so files with the same name can be told apart, and & would mark a shortcut
+        let labels: Vec<String> = recent_files.iter().enumerate()
+            .map(|(idx, file_path)| format!("{}. {}", idx + 1, file_path.file_name().unwrap_or_default().to_string_lossy().replace('&', "&&")))
+            .collect();
+        let recent_menu = MenuItem::new(&labels.iter().map(|label| label.as_str()).collect::<Vec<&str>>());
+        let chosen_idx = recent_menu.popup(app::event_x(), app::event_y())
+            .and_then(|item| item.label())
+            .and_then(|label| label.split_once('.').and_then(|(number, _)| number.parse::<usize>().ok()));
+        if let Some(file_path) = chosen_idx.and_then(|number| recent_files.get(number - 1)) {
+            sender.send(InterfaceMessage::OpenDataFiles(vec![file_path.clone()]));
+        }//end if the user chose a file
+    }//end choose_recent_file(sender, recent_files)
+
+    /// Shows a file dialog for choosing an output file to verify, then
+    /// sends it to main. Used by the Process menu and the command palette.
+    fn choose_output_to_verify(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("*.xlsx");
+        dialog.set_title("Please select an output file to verify");
+        dialog.show();
+        let file_path = dialog.filename();
+        if !file_path.as_os_str().is_empty() {sender.send(InterfaceMessage::VerifyOutput(file_path));}
+    }//end choose_output_to_verify(sender)
+
+    /// Shows a file dialog for choosing a summary workbook to open, then
+    /// sends it to main. Used by the Process menu and the command palette.
+    fn choose_summary_to_open(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("*.xlsx");
+        dialog.set_title("Please select a summary workbook to open");
+        dialog.show();
+        let file_path = dialog.filename();
+        if !file_path.as_os_str().is_empty() {sender.send(InterfaceMessage::OpenSummary(file_path));}
+    }//end choose_summary_to_open(sender)
+
+    /// Shows a file dialog for choosing the summary workbooks to merge,
+    /// then another for choosing where the merged workbook is written,
+    /// and sends both to main. Used by the Process menu and the command palette.
+    fn choose_summaries_to_merge(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseMultiFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::UseFilterExt);
+        dialog.set_filter("*.xlsx");
+        dialog.set_title("Please select the summary workbooks to merge");
+        dialog.show();
+        let file_paths: Vec<PathBuf> = dialog.filenames().into_iter().filter(|path| !path.as_os_str().is_empty()).collect();
+        if file_paths.len() == 0 {return;}
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+        dialog.set_filter("*.xlsx");
+        dialog.set_title("Please specify where the merged workbook should be saved");
+        dialog.show();
+        let mut merged_path = dialog.filename();
+        if merged_path.as_os_str().is_empty() {return;}
+        merged_path.set_extension("xlsx");
+        sender.send(InterfaceMessage::MergeSummaries(file_paths, merged_path));
+    }//end choose_summaries_to_merge(sender)
+
+    /// Shows a file dialog for choosing the folder of runs to batch process,
+    /// then asks whether to write a workbook for each run or one combined
+    /// workbook, and where the combined workbook is written, and sends the
+    /// choices to main. Used by the Process menu and the command palette.
+    fn choose_batch_folder(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseDir);
+        dialog.set_title("Please select the folder of csv and xml files to process");
+        dialog.show();
+        let input_dir = dialog.filename();
+        if input_dir.as_os_str().is_empty() {return;}
+        match dialog::choice2_default("Would you like to write a workbook for each run in the folder,\nor one workbook combining every run?", "Cancel", "Each Run", "Combined") {
+            Some(1) => sender.send(InterfaceMessage::BatchProcess(input_dir, None)),
+            Some(2) => {
+                let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+                dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+                dialog.set_filter("*.xlsx");
+                dialog.set_title("Please specify where the combined workbook should be saved");
+                dialog.show();
+                let mut combined_path = dialog.filename();
+                if combined_path.as_os_str().is_empty() {return;}
+                combined_path.set_extension("xlsx");
+                sender.send(InterfaceMessage::BatchProcess(input_dir, Some(combined_path)));
+            },
+            _ => {},
+        }//end matching which output the user wants
+    }//end choose_batch_folder(sender)
+
+    /// Shows file dialogs for choosing a csv file too large to load and
+    /// where its summary should be saved, and sends them to main.
+    /// Used by the Process menu and the command palette.
+    fn choose_large_csv(sender: &Sender<InterfaceMessage>) {
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseFile);
+        dialog.set_filter("*.csv");
+        dialog.set_title("Please select the large csv file to summarize");
+        dialog.show();
+        let csv_path = dialog.filename();
+        if csv_path.as_os_str().is_empty() {return;}
+        let mut dialog = dialog::NativeFileChooser::new(dialog::NativeFileChooserType::BrowseSaveFile);
+        dialog.set_option(dialog::NativeFileChooserOptions::SaveAsConfirm);
+        dialog.set_filter("*.xlsx");
+        dialog.set_title("Please specify where the summary should be saved");
+        dialog.show();
+        let mut output_path = dialog.filename();
+        if output_path.as_os_str().is_empty() {return;}
+        output_path.set_extension("xlsx");
+        sender.send(InterfaceMessage::StreamLargeCsv(csv_path, output_path));
+    }//end choose_large_csv(sender)
+
     /// Helper method used in initialize to share code between handlers
     /// of io buttons.
     fn create_io_dialog(sender: &Sender<InterfaceMessage>, msg_header: &str, txt: &mut TextBuffer, dialog_type: dialog::NativeFileChooserType, dialog_option: dialog::NativeFileChooserOptions, dialog_filter: &str, dialog_title: &str ) -> Result<(), String> {
@@ -0,0 +1,138 @@
+use fltk::{browser::HoldBrowser, enums::{Align, Color, FrameType}, prelude::{BrowserExt, DisplayExt, GroupExt, WidgetBase, WidgetExt, WindowExt}, text::{TextBuffer, TextDisplay}, window::Window};
+
+/// Represents a single topic of help text that
+/// can be shown to the user in the help dialog.
+pub struct HelpTopic {
+    /// The identifier used to look up this topic,
+    /// such as "config" or "stat_columns".
+    pub id: &'static str,
+    /// The title of this topic, shown in the list of topics.
+    pub title: &'static str,
+    /// The actual help text for this topic.
+    pub text: &'static str,
+}//end struct HelpTopic
+
+/// The table of all help text bundled with the application.
+/// The first few topics correspond to sections of the gui, and
+/// are shown when the user presses F1 over that section.
+/// The rest explain individual configuration options.
+pub const HELP_TOPICS: [HelpTopic; 14] = [
+    HelpTopic {
+        id: "header",
+        title: "About This Program",
+        text: "USDA C-Grain Sum processes CSV and XML output files from the C-Grain machine into summary files in xlsx format.\n\nThe general workflow is:\n1. Choose your configuration settings on the right side of the window.\n2. Load a CSV and/or XML input file.\n3. Choose a name for the output file.\n4. Click Process Data.\n\nYou can drag the borders between the sections of the window to resize them. The size and position of the window and its sections are saved when the program closes, and restored the next time it starts. The input files and output file you had chosen are restored too, so you can pick up where you left off without choosing them again. Files which can't be found anymore, such as ones on a network share which isn't connected, are skipped.\n\nThe status bar along the bottom of the window shows the loaded csv and xml files with how many rows each has, the active preset, and how long the last processing took. Input files are cleared after processing, so the status bar shows which files still need to be loaded for the next run.\n\nThe Log button at the top of the configuration section opens the log file, which lists the errors, warnings, and other messages from this and earlier sessions with the time of each. It's kept in a logs folder next to the program, or in your user data folder if the program's folder can't be written to. Once it reaches 1 MB, it's renamed with a number, keeping the last five, so it doesn't grow forever. If something goes wrong, the log file is a good thing to send along with the input files.\n\nPress F1 while your mouse is over any section of the window to get help for that section, or click the ? button in the configuration section to browse all of the help topics.\n\nPress Ctrl+K to open the command palette, which lists every action in the program, such as opening files, processing, viewing results, and switching presets. Type part of the name of an action to narrow the list. The letters don't need to be next to each other, so \"pd\" finds Process Data. Use the up and down arrows to move through the list, and press Enter to run the selected action.\n\nLeft click this section to see the version of the program, when it was built, and the licenses of the libraries it uses.\n\nRight click this section to set a url or file path, such as one on a network share, to check for newer versions when the program starts. The file there should have the latest version number on its first line, followed by any release notes.\n\nNotification Settings: Right click this section and choose Notification Settings to turn sounds off entirely, or to choose whether the program beeps on error messages and when processing completes. You can also have a desktop notification shown when processing finishes, so you can work in other windows during long runs. Only runs that take at least the minimum run time show a desktop notification. On Linux, desktop notifications need notify-send to be installed. To keep a lab dashboard up to date, give a webhook url, and after Batch Process Folder, json about each run is posted to it, with run_id (the name of the run), output_path, succeeded, and metrics (samples, kernels, class_name, overall_percent, and flagged_samples) for runs with csv data, or error for runs which failed. Leave the url empty to not post anything.\n\nScheduled Processing: Right click this section and choose Scheduled Processing Settings to have a folder swept at set times, such as 6 pm each day, while the program is open. At each time, the runs in the folder which haven't been processed yet are processed like Batch Process Folder, and merged into one combined workbook in a Summaries folder inside it, named with the date and time, such as combined_2024-05-30_1800.xlsx. The schedule is written like cron, as the minute, hour, day of month, month, and day of week, with * for every value and 0 as Sunday. Times use this computer's clock. The sweep runs in the background, so you can keep using the program while it does, and a report is only shown once it's done if something went wrong. If a sweep is still going at the next time, that time is skipped, and closing the program waits for the sweep to finish. Runs which were already processed are skipped, and each skipped file is logged, unless Process Runs Again Even If Already Processed is checked.\nA run whose files were changed within the seconds between tries, such as while they're still being exported, or are open in another program, is tried again after waiting that long. Once a run has failed the number of tries before quarantine, its files are moved to a Quarantine folder inside the folder, along with a file such as run_1_error.txt saying why each try failed, so the next sweep isn't held up by it. Move the files back once they're fixed to have them processed. A run which fails for another reason, such as a file which can't be read as C-Grain data or settings which need an xml file the run doesn't have, would fail the same way every time, so it isn't tried again or quarantined, and is listed in the report instead.\nExample: 0 18 * * * for 6 pm every day, or 0 7,18 * * 1-5 for 7 am and 6 pm on weekdays.\n\nGoogle Sheets: Builds with the google-sheets feature can push each merged summary to a Google Sheet, so it doesn't need to be uploaded by hand. Right click this section and choose Google Sheets Settings to turn this on, then give the spreadsheet id, which is the part of the sheet's address between /d/ and /edit, and the json key file of a Google Cloud service account. Share the Google Sheet with the email address of the service account as an editor. Each time Merge Summary Workbooks runs, each merged sheet replaces the tab of the same name in the Google Sheet, and tabs it doesn't have yet are added. Other tabs are left alone.\n\nUser Scripts: Builds with the scripting feature can run a script written in Rhai, for lab-specific changes such as adding a custom column. Right click this section and choose Set Script File to pick the script, or cancel the file dialog to stop using it. A script can define fn after_load(kind, rows), which gets \"csv\" or \"xml\" and an array with a map of header to value for each row of the loaded file, and returns the rows to use. Any new keys in the rows become new columns. A script can also define fn before_write(sheet_name, sheet), which gets each output sheet right before it's written, as a map with id_header, headers (each with name, decimals, and percent), and rows (each with id and values), and returns the sheet to write. A for loop in Rhai gets a copy of each item, so change rows through an index, such as sheet.rows[i]. If the script fails, the data or sheet is used as it was, and you'll get a message saying why.\n\nOperator Mode: Right click this section and choose Switch to Operator Mode to hide the configuration settings and advanced options, leaving only file selection and Process Data, so operators can't accidentally change settings such as the stat column list. You'll be asked for an admin password, which is then needed to switch back to analyst mode, where every setting can be changed. The password can't be empty. The mode is remembered the next time the program starts. The password itself isn't saved anywhere. Only a salted hash of it is kept, as admin_password.hash in the data folder, rather than in the config file, so it can't be read or changed by editing or sharing the config. A password saved in the config file by an older version is moved there the next time the program starts. If admin_password.hash is missing while operator mode is on, such as if it was deleted, operator mode stays on, and you'll be asked to choose a new admin password before you can switch back.",
+    },
+    HelpTopic {
+        id: "io",
+        title: "Input and Output Controls",
+        text: "Open Data File(s): Choose one or more input files at once, such as a csv and an xml file from the same run. Each file is loaded as csv or xml depending on what it contains, even if its extension is wrong, so you don't need to pick the matching button. Kernel data saved as an xlsx workbook, such as after opening a csv export in excel, is loaded the same way as a csv file, using the first sheet. Only one csv and one xml file can be loaded at a time. Output files from this program can't be read as input data, but you'll be offered to check whether one has been edited. Right click to choose from the last ten files you opened, which is also in the command palette as Open Recent File.\n\nSelect Input CSV: Choose the csv file exported from the C-Grain, or an xlsx workbook with the same columns. This is needed for CSV Stat Columns and Class Percents. Right click for advanced csv options. If the file is missing columns your settings need, such as the sample id column, you'll be asked which of its columns to use instead. Your choices are saved and used automatically for later files with the same columns. Saved choices are kept under header_mappings in the config file, so delete them there to be asked again.\n\nSelect Input XML: Choose the xml file exported from the C-Grain. This is needed for XML Sieve Data. Right click for advanced xml options.\n\nSelect Output XLSX: Choose where the output file should be saved. You can also just type a file name in the box to the right, in which case the output is saved next to the input file. Right click for advanced output options. If the output file is still open in another program, such as Excel, you'll be asked whether to retry once you've closed it, save the output under another name like sum (2).xlsx, or wait up to a minute for it to be closed. Batches run on a schedule wait for the file to be closed, then use another name if it's still open.\nBefore anything is processed, the output folder is checked to make sure it exists, such as a network share which is connected, that files can be created there, and that the drive has enough free space, estimated as twice the size of the input files. If the folder is missing, you can create it or retry once it's connected. If the drive seems too full, you can retry after freeing up space or process anyway.\n\nProcess Data: Reads the loaded input files and writes the output file. While large files are loaded or processed, a progress bar at the bottom right of the window shows the current step and how far along it is, and the controls are disabled until it's done. Right click to choose the order and visibility of columns in each output sheet, to edit the processing pipeline, to define report metrics, to define the rules for the Flags column, to add formula columns, to group columns under shared headers, to choose what happens when the loaded data has problems, to define recipient profiles, to explore the loaded data, to reprocess samples, to view, compare, or print the results, to get summary text for an email, to export a run bundle, or to verify an output file.\n\nFilter Set: Choose which filters to use when processing. (Config Filters) uses the class filters in the configuration settings and the Filters step of the processing pipeline. Right click the dropdown to save those filters as a named set, such as one for export grading and one for research, or to delete the chosen set. Choosing a set only changes which filters are used, so the filters in the configuration settings are kept as they were.\n\nEdit Processing Pipeline: Choose steps to run on the csv data before it is processed, and the order to run them in. Select a step and click On/Off to turn it on or off, or Move Up and Move Down to change when it runs. Each step has a box for its settings, one per line:\n- Alias Mapping renames columns, such as \"Mass -> Weight\".\n- Class Remapping changes the classes from the instrument into the classes of your report, such as \"SorghumBroken -> Damaged\" and \"SorghumChipped -> Damaged\" to count both as Damaged. It changes the class column set for class filtering, so class filters, class percents, and percent() metrics all use the new classes. Each class is only remapped once, so remappings aren't chained. Configs from older versions have this step turned off, so turn it on with On/Off.\n- Unit Conversion multiplies or divides a column, such as \"Weight * 1000\".\n- Filters removes kernels which don't pass a rule, such as \"Area > 10\".\n- Outlier Removal removes kernels more than 1.5 times the interquartile range away from the rest of their sample, for each listed column.\n- Color Conversion adds columns in other color spaces, calculated from the Red, Green, and Blue columns. Lab adds L*, a*, and b*, and LCh adds Chroma and Hue Angle. To use other columns, write something like \"Lab from R, G, B\". Configs from older versions have this step turned off, so turn it on with On/Off.\n- Derived Columns adds columns calculated from others, such as \"Aspect = Length / Width\". The new columns can be used in CSV Stat Columns.\nSteps with empty settings are skipped. The Input_Data sheet still has the rows as they were loaded.\n\nEdit Column Aliases: Different C-Grain firmware can name the same column differently, such as Mean Width, MeanWidth, or mean-width. Add an alias from the name in the file to the name in your settings, such as \"MeanWidth -> Mean Width\" or \"Mass -> Weight\". The name on the left of an alias is found ignoring case, spaces, and punctuation, so \"MeanWidth -> Mean Width\" also renames mean-width. If more than one column of the file matches it that way, the alias isn't used and you're told which columns matched, so use the exact name instead. Other columns in your settings must match the file exactly. If a csv file is loaded, its columns are listed, and clicking one fills in the column in the file. Aliases are used by the Alias Mapping step of the processing pipeline, which is turned on if it was off. Columns renamed by an alias aren't counted as missing when a file is loaded.\n\nEdit Report Metrics: Define the metrics shown on the Metrics sheet, such as %Sound or TKW. Each metric is defined once, one per line, as a name, an equals sign, and one of count(), mean(column), std(column), or percent(class). Means and standard deviations only use kernels passing the class filters, while counts and percents use every kernel in the sample. To scale a metric, add * or / and a number, such as \"TKW = mean(Weight) * 1000\". To choose the decimal places, add a comma and a number at the end. Below that, list the names of the metrics to show on the Metrics sheet, in order, and check Output Metrics Sheet to add the sheet to the output.\n\nEdit Flag Rules: Define the rules for the Flags column, and check Add Flags Column to add it to the end of each output sheet with a row for each sample. The Flags column lists the name of every rule the sample trips, such as \"LOW_N, CHECK_FAIL\", so reviewers can spot samples needing a second look. Each rule is one line, as a name, an equals sign, a measure, < or >, and a number, such as \"LOW_N = kernels < 50\". The measures are kernels, the number of kernels in the sample after the processing pipeline, outliers, the number of kernels removed from the sample by the Outlier Removal step, and failed_checks, the number of check columns, such as Sieve Total Check, which aren't empty for the sample. When samples are reprocessed, the whole Flags column is filled in again.\nThe sample id pattern is a regular expression every sample id should match in full, such as \\d{12} for twelve digits, or \\d{8}-\\d{3} for a date and a number, which catches barcode misreads like 2024031900l9 before they reach the archive. Samples whose id doesn't match can be flagged as BAD_ID in the Flags column, which is then added even if Add Flags Column isn't checked, or left out of the output and listed on the Run Info sheet. Either way, they're reported by the Invalid Sample Id warning policy. Leave the pattern empty to not check sample ids.\n\nEdit Formula Columns: Add columns to the output sheets which are calculated from other columns, such as a ratio, and written as Excel formulas, so recipients can click a cell to see how it was calculated. Each formula column is one line, as the name of the sheet, a colon, the name of the new column, an equals sign, and a calculation written like a derived column, such as \"CSV_Stats: L/W Ratio = Avg Length / Avg Width\". Each side of the calculation can be a column of that sheet or a number. Cells where a value is missing or would be divided by zero are left empty. If a column used by the formula is hidden in the column layout, the calculated number is written instead of the formula. Csv, json, and sqlite output get the calculated numbers.\n\nEdit Column Groups: Write a second row of headers above the column headers in excel output, with a header merged across each group of columns, such as Length (mm) above Avg Length, Std Length, and CV Length, which makes wide sheets easier to read. The first box is the header above the stat columns of each csv column, where {col} is replaced with the name of the csv column, so {col} (mm) groups the stats of every column in CSV_Stats. Below that, list any other groups, one per line, as the header of the group, a colon, then its columns separated by commas, such as \"Size: Avg Length, Avg Width\". These take the place of the groups from the first box. Columns of a group are only merged under one header while they're next to each other, so keep them together in the column layout. Headers of columns without a group span both rows. Csv, json, and sqlite output keep a single row of headers. Summaries with grouped columns can still be opened and merged.\n\nEdit Warning Policies: Choose what happens for each kind of problem found in the loaded data when you click Process Data or reprocess samples. Missing Column is for columns your settings need which aren't in the loaded files, Text in Numeric Column is for stat columns holding text which isn't a number, Unmatched Sample is for samples in only one of the csv and xml files, and Invalid Sample Id is for sample ids which don't match the sample id pattern set with Edit Flag Rules. Abort stops processing and tells you why, Prompt lists the problems and asks whether to process anyway, and Log only writes the problem to the log file, so processing goes on as usual. Columns added by the processing pipeline, such as derived columns, aren't counted as missing.\n\nEdit Recipient Profiles: Define the conventions of the people the output is sent to, such as a partner lab which reads commas as decimal points, so one run can be written for each of them without changing your settings back and forth. Choose (new profile), give the recipient a name, fill in what should be different for them, then click Save Profile. Decimal Places sets the decimal places of every column. Unit Conversions converts output columns, such as \"Avg Length * 0.03937\" for inches, and Column Renames and Sheet Renames rename columns and sheets, such as \"CSV_Stats -> Kernel Stats\", which can also be used for another language. Conversions use the names of columns before they're renamed. Sheet Layout changes how the sheets are laid out, such as for a recipient whose macro reads them. Sheet per Metric splits CSV_Stats into a sheet for each csv stat column, such as Area, with a row for each sample and a column for each stat, such as Avg and Std, followed by Flags. Formula columns are left off those sheets, since they use stats which end up on other sheets. Renames use the names of the new sheets and columns, such as \"Area -> Surface\" or \"Avg -> Mean\". Leave anything empty to keep it as it is in your settings. Once you have a profile, you'll be asked which recipients to write the output for when you click Process Data, with the ones chosen last time already checked. A workbook is written for each of them next to the output file, with the name of the recipient added to its name.\n\nExplore Loaded Data: Before processing, opens a window listing the columns of the loaded input files. Click a column to see how many of its values are numbers, its min, max, mean, and standard deviation, and a histogram of its values. This can help with choosing columns and filters, or with spotting bad data before processing. Below that is a preview of the loaded rows. Type a sample id or class in the search box to only show the rows for that sample or class, such as to check that a questionable tray made it into the file.\n\nView Results: After processing, opens a window with the tables from the output, so you can check the numbers without opening Excel. Click a column header to sort by that column, and type in the filter box to only show matching rows, such as the samples from one lot. Click Chart to see a bar chart of one column across the samples, which makes swapped or unusual samples easy to spot.\n\nPin Results for Comparison: After processing, keeps the results of that run, so you can load and process a second dataset, such as another station or another day, without opening the program twice. Then choose Compare with Pinned Results to see the tables of both runs side by side. Choose the table to compare, and type in the filter box to only show matching rows in both tables, such as one sample. Pinning a new run replaces the old pinned results.\n\nPrint Summary: After processing, choose one of the output tables to print. Columns or rows that don't fit on one page continue on the next page, with the sample ids repeated. To get a pdf instead, choose a pdf printer, such as Microsoft Print to PDF, in the print dialog.\n\nEmail Summary Text: After processing, shows a few lines of text with the number of samples and kernels, the overall percent of the first class filter class (usually Sound), and any samples with an unusually low percent of that class. You can edit the text, then click Copy to Clipboard to paste it into the email sent out after each run. A sample is flagged when its percent is more than 1.5 times the interquartile range below the first quartile of all the samples, so at least 4 samples are needed.\n\nExport Run Bundle: After processing, saves a zip file holding the output workbook, a provenance.json file describing how it was made, and a log.txt file with the messages and errors from the run, so the whole run can be archived or emailed as one file. provenance.json has the lines of the Run Info sheet, even if the sheet wasn't written, a hash of each input file, and every setting used. You'll be asked whether to include the input files too, which makes the bundle larger, but lets the run be checked or processed again later.\n\nView Run Ledger: Every time processing finishes and the output is written, including each run of a batch and each large csv file summarized, a line is added to the run ledger, run_ledger.jsonl in the data folder. Each line has when the run finished, the path and a hash of each input file, the path of the output, a hash of the settings used, and key metrics such as the number of samples and kernels and the overall class percent. Lines are only ever added, and each has a checksum which includes the checksum of the line before it, so if a line is edited or removed, the ledger says so when it's viewed. The checksums are made with a secret key kept as ledger.key in the data directory, so someone editing the ledger can't work out new checksums to match, and the ledger should be backed up along with that key. Right click Process Data and choose View Run Ledger to browse past runs, newest first, see the details of a run, and open its output here or show it in its folder.\n\nReprocess Samples: After processing, you can change your settings, then select some samples to process again. Only the rows for those samples are updated, and the output file is written again, along with the workbook of each recipient it was written for. If CSV_Stats has a row for each group, such as each treatment, the rows of each group are updated, and a sample which now has a new group gets a row for it, or a new sheet if each group has its own sheet. The whole output is written with your new settings, such as for anonymization, number formats, and other output formats, and the Run Info sheet lists a hash of the settings of the first run along with a Reprocessed line for each time samples were reprocessed, giving the hash of the settings used then. Lot rows and the Treatment Comparison sheet are not updated, so process all of your data again if you need those updated.\n\nOpen Existing Summary: Right click Process Data and choose Open Existing Summary to open a summary workbook written before, without loading any input files. You can view its sheets in the results viewer, or write them next to the workbook in another format, such as csv or json, using the csv and number settings you have now. Percents are read back as fractions, such as 0.9 for 90%, since the workbook's number formats aren't read.\n\nMerge Summary Workbooks: Right click Process Data and choose Merge Summary Workbooks to combine several summaries, such as every run of a season, into one workbook. Choose the summaries, then where to save the merged workbook. Each merged sheet has the rows of that sheet from every summary, with a Source column giving the name of the summary each row came from. Columns are matched by header, so summaries with different columns can still be merged, and cells a summary didn't have are left empty. Input_Data and Run_Info sheets aren't merged.\n\nBatch Process Folder: Right click Process Data and choose Batch Process Folder to process every run in a folder at once, such as a day's worth of runs. Each csv file is paired with the xml file of the same name, ignoring the extension and capitalization, so run_0612.csv goes with run_0612.xml. Runs with only a csv or only an xml file are processed by themselves. Output workbooks from this program in the folder are ignored. Choose Each Run to write a workbook next to the input files for each run, named from the output file name template, with {csv_stem} added to the end if the template doesn't have it, or choose Combined to write one workbook with the rows of every run, like Merge Summary Workbooks. If a run's file names match a profile pattern, that run is processed with the preset of the pattern. Nothing is asked about each run, so warnings which would ask whether to process anyway, and columns without a saved column mapping, are listed in the report instead. Runs whose files were processed in an earlier batch can be skipped, even if the files were renamed or copied, since they're known by their contents. Choose no when asked to skip them to process them again, such as after changing the settings. Each skipped file is written to the log. Once every run is done, a report lists which runs succeeded and which failed, along with why. Builds with the google-sheets feature can also push the merged sheets to a Google Sheet, as set in Google Sheets Settings.\n\nSummarize Large CSV File: Right click Process Data and choose Summarize Large CSV File to make the CSV_Stats and Class_Percents sheets from a csv file too large to load, such as a long export with millions of kernels. The file is read a row at a time, keeping only running totals for each sample, and a progress bar shows how much has been read. The other sheets aren't written, and only averages and standard deviations are found for the stat columns, so extra stats such as the median need to be turned off, along with lot grouping and any steps of the processing pipeline with settings. The header row, column names, and footer rows to skip are used as usual.\n\nRecording a Session: Right click Process Data and choose Start or Stop Recording Session, then load your files and process them as usual. Choose it again to save the recording as a json file, which holds the files you loaded, when you processed, and the settings used to process. Later, right click Process Data and choose Replay Recording on New Files to do the same steps on new files. You'll choose the recording, then the new input files, in the order the recording loaded them, then the output file. The settings from the recording are used, except for operator mode and the update check location, and settings locked by an administrator still apply. This is handy for weekly runs which are the same except for the file names.",
+    },
+    HelpTopic {
+        id: "config",
+        title: "Configuration Settings",
+        text: "The configuration section controls which output is produced and how the input data is filtered.\nThese settings are saved when the program closes, and loaded the next time it starts.\n\nRight click the \"Configuration Settings\" label to switch to a different configuration preset, such as Wheat, Sorghum, or Corn. Each preset sets up the stat columns, classes, and sieve columns suited to that crop.\nFrom the same menu, you can save the current settings as your own preset, such as for another crop. Every preset, including the built-in ones, is kept as a file in the presets folder next to the config file.\nChoose Manage Presets from the same menu to see every preset, switch to one, save the current settings as a new preset, or rename and delete presets. A built-in preset which is deleted isn't added back.\n\nMany of the check boxes can also be right clicked to configure more advanced options. See the other help topics for details on each option.\n\nClick the arrow button next to the ? button to collapse the configuration settings to a narrow strip, such as once your settings are ready, and click it again to bring them back. When the window is resized, the stat columns box grows or shrinks to fill the configuration section.\n\nLocked Settings: An administrator can lock settings, such as to keep several lab stations processing the same way, by putting a file named admin.config next to the config file. It should hold the name and value of each locked setting, written the same way as in the config file.\nExample: {\"csv_stat_columns_columns\": [\"Area\", \"Length\"], \"csv_class_filter_enabled\": true}\nLocked settings are greyed out, keep their locked values when switching presets, and processing refuses to run if one of them has been changed some other way, such as by a filter set.",
+    },
+    HelpTopic {
+        id: "dialog",
+        title: "Messages",
+        text: "The box in the bottom left of the window shows messages from the program, such as errors or questions.\nWhen a message is shown, the rest of the window is disabled until you click one of the buttons below the message.",
+    },
+    HelpTopic {
+        id: "class_filter",
+        title: "Filter CSV Stat Columns to Class",
+        text: "If checked, only rows in the csv with one of the listed classes are used to calculate the stat columns.\n\nSeparate multiple classes with a comma or |.\nExample: \"Sound\" only uses Sound kernels.\nExample: \"Sound, Sorghum\" uses both Sound and Sorghum kernels.\n\nRight click the check box to choose which csv column holds the class of each row. The default is raw-filtered-as.",
+    },
+    HelpTopic {
+        id: "stat_columns",
+        title: "Output CSV Stat Columns",
+        text: "If checked, the output will have a CSV_Stats sheet with the average and standard deviation of each listed csv column, per sample.\n\nSeparate columns with a new line or comma.\nExample: \"Area, Length, Width\" gives Avg Area, Std Area, Avg Length, Std Length, Avg Width, and Std Width.\n\nOther statistics can be added after the average and standard deviation of each column: Median, Min, Max, Q1 and Q3 (the lower and upper quartiles), and CV (the coefficient of variation, which is the standard deviation as a percent of the average). Right click the check box, and list the ones you want on the third question, separated by commas. Like the average, these only use kernels passing the class filter, and text in a column is skipped.\nExample: \"Median, CV\" with Area also gives Median Area and CV Area.\n\nIf a column has text where there should be numbers, its standard deviation for that sample is listed as -1000.0, and the cell gets a comment saying why. Hover over the red triangle in the corner of the cell in Excel to read it.\n\nSome of the possible column headers are:\nArea, Length, Thickness, Mean Width, Ratio, Volume, Weight, Brightness, Hue, Saturation, Red, Green, Blue, Severity.\n\nRight click the check box to change how output columns are named. See Header Name Templates.\n\nThe last question when right clicking asks for a column to group the rows of each sample by, such as Treatment or Plot, to get stats for each group within each sample. You can then choose whether each group is a column after the sample id, with a row for each group in each sample, or gets its own sheet, named after the group, with a row for each sample in that group. Leave the column empty to only group rows by sample id. Grouping can't be used when streaming large csv files.\nExample: grouping by Treatment with sheets per group gives CSV_Stats_Control and CSV_Stats_Dried sheets.",
+    },
+    HelpTopic {
+        id: "class_percent",
+        title: "Output % per Class per Sample",
+        text: "If checked, the output will have a Class_Percents sheet with the percentage of kernels in each sample which belong to each class.\nThese percentages are calculated independently of the class filter.\n\nExample: A sample with 90 Sound kernels and 10 Sorghum kernels gives %Sound of 90.0% and %Sorghum of 10.0%.\n\nRight click the check box to change how output columns are named. See Header Name Templates.\n\nAfter choosing the names, you can choose which kernels the percents are out of:\nAll Kernels: Every kernel in the sample is counted.\nPassing Filters: Only kernels passing all of the numeric filters are counted, such as \"Area > 10, Length <= 7.5\".\nExcluding Class: Kernels in one class, such as Debris, are not counted at all.\n\nLast, you can choose a class taxonomy file, which groups detailed classes under parent classes, so the output also has a column totaling the percents of each parent, such as %Damaged for every kind of damage. The file is plain text, with a line for each parent, giving the parent, a colon, then its children separated by commas:\nDamaged: SorghumBroken, SorghumChipped, HeatDamaged\nDefects: Damaged, Foreign Material\nA child can be a parent itself, like Damaged above, so totals can roll up over several levels. Lines starting with # are skipped. The parent columns come after the detailed classes, and aren't stacked in the class percent chart. A parent can't have the same name as a class in the data, so to simply rename classes, use the Class Remapping step of the processing pipeline instead.",
+    },
+    HelpTopic {
+        id: "xml_sieve",
+        title: "Output XML Sieve Data",
+        text: "If checked, the output will have an XML_Sieve_Data sheet with the sieve data of each sample in the xml file.\nThis requires an xml input file to be loaded.",
+    },
+    HelpTopic {
+        id: "lot_grouping",
+        title: "Group Samples into Lots",
+        text: "If checked, samples are grouped into lots, and each output sheet gets a row for each lot after the rows for its samples.\nFor csv sheets, lot rows are calculated from all the kernels in the lot. For the xml sieve sheet, lot rows are the average of the lot's samples.\n\nRight click the check box to choose how the lot of each sample is found:\nLot column: A csv column holding the lot of each row. Leave empty to use the sample id instead.\nDelimiter: The lot is the part of the sample id before the last delimiter.\nExample: With -, the lot of W23-07-3 is W23-07.\nLabel: How lot rows are labeled, where {lot} is replaced with the lot.\nExample: \"Lot {lot}\" gives \"Lot W23-07\".",
+    },
+    HelpTopic {
+        id: "header_templates",
+        title: "Header Name Templates",
+        text: "The names of output columns can be customized with templates.\n\nIn stat column templates, {col} is replaced with the name of the csv column.\nExample: \"Avg {col}\" gives \"Avg Length\".\nExample: \"Mean{col}_mm\" gives \"MeanLength_mm\".\n\nIn the class percent template, {class} is replaced with the name of the class.\nExample: \"%{class}\" gives \"%Sound\".\nExample: \"Pct_{class}\" gives \"Pct_Sound\".",
+    },
+    HelpTopic {
+        id: "csv_advanced",
+        title: "Advanced CSV Options",
+        text: "Column to read as sample-id: The csv column used to group rows into samples. Default is external-sample-id.\n\nRow index of headers: The 0-based index of the row with column names. Rows before it are skipped.\nExample: 2 if the file has two lines of notes before the column names.\n\nFooter rows to skip: The number of rows at the end of the file to ignore, such as a summary row. Blank rows at the end are always ignored.\nExample: 1 if the C-Grain appended a totals row.\n\nManual Headers: Column names to use if the csv has no header row, one per line, in the order of the columns.\n\nOnly accept known C-Grain files: When checked, csv and xml files are checked against the known C-Grain export layouts when they're loaded, such as requiring external-sample-id, raw-filtered-as, Area, Length, and Width columns with numbers in the measurement columns. Files which don't match any known layout, such as files from other instruments, are not loaded, and a message explains what didn't match.\n\nWhether or not this is checked, files from older C-Grain exports are recognized when they're loaded, and their columns are renamed to match current exports, so old archives process without changing any settings.\nExample: an older csv with a cor-filtered-as column has it renamed to raw-filtered-as.\n\nPresets for file names: Lets files for different crops be processed with the right settings. Put one pattern per line, followed by -> and the name of a preset. When a csv or xml file is loaded, the first pattern matching its name is used, and the settings switch to that preset. In a pattern, * matches any characters and ? matches one character, and case is ignored.\nExample: *_sorghum_* -> Sorghum switches to the Sorghum preset for 0612_sorghum_tray3.csv.\n\nColumn to read as the frame of each kernel: If the loaded csv has this column, the output gets a Frame_Stats sheet with the number of frames (images) in each sample, the number of kernels, and the average, standard deviation, and max kernels per frame. All kernels are counted, even those filtered out by class. An unusually high number of kernels per frame can point to a conveyor jam. Leave this empty to never add frame stats. Default is image-id.\n\nSeconds unchanged before loading: If a csv or xml file was changed more recently than this, such as while the C-Grain is still exporting it, the program waits until its size stops changing for this long before reading it, so a half written file isn't processed as a shorter run. If it's still changing after a minute, it isn't loaded. This is also used by Batch Process Folder. Set this to 0 to load files right away. Default is 2.",
+    },
+    HelpTopic {
+        id: "xml_advanced",
+        title: "Advanced XML Options",
+        text: "Tag to read as sample-id: The xml tag used as the id of each sample. Default is reference.\n\nTag to read as the end of a sample: When this tag closes, the program assumes one sample has ended. Default is sample-result.\n\nExtra Tags to Read: Any other tags whose values should be included in the output, one per line.\nExample: good-images\n\nSieve Sizes: The screen size in mm of each sieve tag in the xml, one per line, optionally followed by a target percent for that sieve.\nExample: filter-sieving-1 -> 4.76, 10\nSieve columns are then labeled with their screen size and ordered largest screen first, and sieves with a target get a column giving the difference from it. When an xml file is loaded, you'll be warned about sieve tags it doesn't have, and sieving columns without a size. Default is empty.\n\nSieve total tolerance: The sieve fractions of each sample should add up to about 100%. Samples whose total is more than this many percentage points away, such as from a short pour, are flagged in the Sieve Total Check column of the XML_Sieve_Data sheet. Leave this empty to skip the check. Default is 2.",
+    },
+    HelpTopic {
+        id: "output_advanced",
+        title: "Advanced Output Options",
+        text: "Font: The font used for all cells in the output.\nExample: Arial\n\nFont size: The size of the font used for all cells. Use 0 for the default size.\nExample: 11\n\nWrite kernel csv for each sample: Writes the kernel rows of each sample, after class filtering, to their own csv file. The files are put in a folder next to the output file, named after the output file.\nExample: Output of sum.xlsx puts files in sum_kernels.\n\nProtect output sheets: Locks the cells of each output sheet, so recipients can't accidentally change the numbers. Recipients can still sort and filter.\nPassword for protected sheets: The password needed to unprotect the sheets in Excel. Leave it empty to protect sheets without a password. The password is saved in the config file as plain text.\n\nAdd Diagnostics sheet: Adds a Diagnostics sheet with a row for each sample, counting the values in each csv stat column which were non-numeric, such as text, or missing, such as an empty cell. These values are left out of the averages and standard deviations, so a high count points to a data quality problem in that sample.\n\nSplit output by metadata column: Also writes a separate workbook for each value of a column in the metadata file, with only the rows of the samples with that value. Sheets without any of those samples, such as Treatment Comparison, are left out. Each workbook is named after the output file and the value.\nExample: With the column Customer, output sum.xlsx also gives sum_Acme.xlsx and sum_Birch.xlsx.\n\nAdd Drift Check sheet: Adds a Drift_Check sheet with a row for each csv stat column, comparing the mean of the first half of the run's kernels to the mean of the second half. Columns where the halves differ significantly (Welch's t-test, |t| above 2.576) are marked Drift, which can point to the instrument warming up. Only kernels passing the class filters are used.\nTimestamp column for drift check: The csv column used to put kernels in time order. Leave this empty to use the order of the rows.\n\nReview flagged samples first: Before the output is written, lists samples with too few kernels after class filtering, or an unusually low percent of a class compared to the rest of the run, along with why each was flagged. Check the samples to exclude, then click Write Output, and those samples are left out of every output sheet. Excluded samples and their reasons are listed in the Run_Info sheet, which is added whenever samples are excluded. Click Cancel to stop without writing anything.\nFlag samples with fewer kernels than: The kernel count below which a sample is flagged. Use 0 to only flag low class percents. Default is 50.\n\nLink sample ids to: A folder or web address for each sample, with {sample_id} where the sample id goes. The sample ids in the output sheets are written as links to it, so you can jump from a suspect row straight to that sample's kernel images. Leave this empty to write sample ids as plain text. Sample ids aren't linked when they're anonymized.\nExample: D:\\CGrain\\Images\\{sample_id}\n\nOutput format: The xlsx output is always written, and the output sheets can also be written as csv, with the same columns and rounding as the output.\nXLSX + CSV file per sheet: Writes each output sheet to its own csv file, in a folder next to the output file named after it.\nExample: Output of sum.xlsx gives sum_csv\\CSV_Stats.csv.\nXLSX + one CSV with sections: Writes every output sheet to one csv file next to the output file. Each sheet starts with a line giving its name, followed by its headers and rows, with a blank line between sheets.\nExample: Output of sum.xlsx gives sum.csv.\n\nAlso write output sheets as json: Writes every output sheet to one json file next to the output file, for reading into other programs. Numbers are written without rounding, along with the decimal places and whether each column is a percent.\nExample: Output of sum.xlsx gives sum.json.\n\nAlso write output sheets to sqlite: Writes every output sheet to a table in a sqlite database next to the output file. This is only available in builds with the sqlite feature.\nExample: Output of sum.xlsx gives sum.sqlite.\n\nOutput name template: The name used for the output file when the output file box is left empty. It can hold tokens, which are filled in when the output is written: {csv_stem} is the name of the csv input file, or the xml file if there's no csv, {date} is the date, {preset} is the active preset, and {operator} is the operator name. Tokens can also be typed into the output file box itself.\nExample: {csv_stem}_{date}_{operator} gives lot7_2024-05-30_NS.xlsx.\nOperator name: Your name or initials, which is recorded in the Run_Info sheet and used for the {operator} token. This stays the same when switching presets.\nAsk for operator name at startup: Asks for the operator name each time the program starts, such as on a machine shared by several people.\n\nAdd Merged sheet joining csv and xml: Adds a Merged sheet with a row for each sample, giving its csv stat columns and its xml sieve data side by side, matched by the csv and xml sample-id columns. Samples in only one of the files still get a row, with the other side left blank. Xml columns with the same name as a csv column have (XML) added to their name. Both a csv and an xml file need to be loaded.\n\nAdd charts to output sheets: Adds a stacked column chart of the class percents of each sample next to the Class_Percents sheet, and a scatter of the average Area against the average Weight next to the CSV_Stats sheet. The charts read from the cells of their sheet, so they change if the values are edited. The scatter is only added if both average columns are in the output, and columns hidden by the column layout are left out of the charts.\n\nCSV delimiter and decimal separator: The character between values, and the decimal separator of numbers, in csv files written by this program, including the kernel csv files. Excel on machines with a European locale expects Semicolon and Comma, so choose those if the files open with everything in one column.\n\nAdd Treatment Comparison sheet: Adds a sheet with a row for each treatment, giving the number of samples in the treatment, and the mean and standard deviation across those samples of each sample's average for each compared column.\nMetadata csv file: A csv file with a row for each sample, giving its treatment, variety, etc. A file picked from inside the config folder is saved relative to it, and you can type a relative path such as metadata/lots.csv, so a shared config finds its files on machines where the drive is mapped differently.\nColumn to read as sample-id in metadata: The metadata column matching the sample-id column of the csv input.\nTreatment columns: The metadata columns to group samples by.\nExample: Treatment, Variety\nCSV columns to compare: The csv input columns to compare treatments on.\nExample: Area, Length, Weight\n\nCopy filtered input rows into output: Adds an Input_Data sheet with every csv row which passes the class filter, so the output file can be archived on its own without losing track of its input.\n\nAdd Run Info sheet with signature: Adds a Run_Info sheet with the program version, when the output was made, the operator name, the input files, and an Output Signature of every value in the other sheets. The signature is an HMAC-SHA256 keyed with a secret key made for this install, kept as output_signing.key in the data directory, so someone who edits the values can't work out a new signature to match. To check whether an output file has been edited since it was written, right click Process Data and choose Verify Output File. Only an install with the same key can verify a file, so back up output_signing.key, and copy it to any other computer which should verify files from this one. Files from older versions only have an Output Checksum, which can't be verified.\n\nAnonymize sample ids in output: Replaces the sample ids in the output workbook, including lot rows and the Input_Data sheet, with pseudonyms, for sharing results with external collaborators without revealing grower identities. A key file matching each pseudonym to its sample id is written next to the output, such as sum_id_key.csv for sum.xlsx, so keep that file to yourself. Kernel csv files are named and filled with the same pseudonyms, and sample ids in the log and run info of a run bundle are replaced too. The input file paths in the Run_Info sheet are not anonymized, so turn those off before sharing if needed.\nStable Hash: Each sample id is replaced with a code made from the id itself and a secret salt kept with this install, such as ID-3F2A9C1B07D4E615, so a sample gets the same code in every output from this computer. The salt is saved as anonymization.salt in the data folder, not in the config file, so codes can't be worked out from a list of likely sample ids. Copy that file to another computer to get the same codes there.\nSequential Codes: Sample ids are numbered in order, such as ID-01, ID-02, so codes can differ between outputs."
+    },
+    HelpTopic {
+        id: "column_layout",
+        title: "Output Column Layout",
+        text: "Right click Process Data to choose the order and visibility of columns in each output sheet.\n\nDrag a column, or use Move Up and Move Down, to change its position. Use Show/Hide to choose whether it is written. Columns marked with [x] are written.\n\nCheck \"Write samples as columns\" to transpose a sheet, so that each sample is a column and each output column is a row. This is useful for sheets with few samples.\n\nColumn names are only known after loading input files, so load your files first for the most complete list.",
+    },
+];
+
+/// Shows a window with a list of all help topics on the left, and
+/// the text of the selected topic on the right.
+/// The topic with the id of initial_topic is selected when the window opens.
+/// If there is no topic with that id, the first topic is selected.
+pub fn show_help_dialog(initial_topic: &str) {
+    let mut help_window = Window::default()
+        .with_size(640,400)
+        .with_label("C-Grain Sum Help");
+    help_window.make_resizable(true);
+
+    let mut topic_browser = HoldBrowser::default()
+        .with_pos(10,10)
+        .with_size(190,380);
+    topic_browser.set_frame(FrameType::GtkDownFrame);
+    for topic in HELP_TOPICS.iter() {topic_browser.add(topic.title);}
+
+    let mut help_buf = TextBuffer::default();
+    let mut help_box = TextDisplay::default()
+        .with_pos(210,10)
+        .with_size(420,380)
+        .with_align(Align::Inside);
+    help_box.set_text_color(Color::from_rgb(0,0,64));
+    help_box.set_color(Color::from_rgb(255,250,240));
+    help_box.set_frame(FrameType::GtkThinDownFrame);
+    help_box.wrap_mode(fltk::text::WrapMode::AtBounds, 1);
+    help_box.set_scrollbar_align(Align::Right);
+    help_box.set_scrollbar_size(10);
+    help_box.set_buffer(help_buf.clone());
+    help_window.resizable(&help_box);
+    help_window.end();
+
+    let initial_idx = HELP_TOPICS.iter().position(|topic| topic.id.eq(initial_topic)).unwrap_or(0);
+    topic_browser.select(initial_idx as i32 + 1);
+    help_buf.set_text(HELP_TOPICS[initial_idx].text);
+
+    topic_browser.set_callback({
+        let mut help_buf = help_buf.clone();
+        move |browser| {
+            if browser.value() < 1 {return;}
+            if let Some(topic) = HELP_TOPICS.get(browser.value() as usize - 1) {
+                help_buf.set_text(topic.text);
+            }//end if we can get the selected topic
+        }//end moving for closure
+    });
+
+    help_window.show();
+}//end show_help_dialog(initial_topic)
@@ -0,0 +1,9 @@
+pub mod schema;
+
+pub mod checksum;
+
+pub mod xlsx;
+
+pub mod source;
+
+pub mod sink;
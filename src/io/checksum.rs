@@ -0,0 +1,302 @@
+use std::{collections::BTreeMap, fs, io::{Cursor, Read}, path::Path};
+
+use quick_xml::{events::Event, Reader};
+use zip::ZipArchive;
+
+use crate::app::secrets;
+
+/// The name of the sheet holding information about how the
+/// output was made, including the signature of the other sheets.
+pub const RUN_INFO_SHEET_NAME: &str = "Run_Info";
+/// The label, in the first column of the Run Info sheet, of
+/// the row which holds the signature of the output.
+pub const SIGNATURE_LABEL: &str = "Output Signature (HMAC-SHA256)";
+/// The label older versions used for an unkeyed checksum of the output,
+/// which anyone could recalculate after editing, so it isn't checked.
+pub const LEGACY_CHECKSUM_LABEL: &str = "Output Checksum";
+
+/// The cells of one sheet read from an xlsx file.
+/// Each cell is (0-based row, 0-based column, value), in the order
+/// they appear in the file. Empty cells are left out.
+pub type SheetCells = Vec<(u32, u16, String)>;
+
+/// Converts a cell reference like "B3" into a 0-based (row, column), like (2, 1).
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::checksum::parse_cell_ref;
+///
+/// assert_eq!(parse_cell_ref("A1"), Some((0, 0)));
+/// assert_eq!(parse_cell_ref("AB12"), Some((11, 27)));
+/// assert_eq!(parse_cell_ref("12"), None);
+/// ```
+pub fn parse_cell_ref(cell_ref: &str) -> Option<(u32, u16)> {
+    let letters: String = cell_ref.chars().take_while(|c| c.is_ascii_alphabetic()).collect();
+    if letters.is_empty() {return None;}
+    let row: u32 = cell_ref[letters.len()..].parse().ok()?;
+    if row == 0 {return None;}
+    let mut col: u32 = 0;
+    for letter in letters.to_ascii_uppercase().bytes() {col = col * 26 + (letter - b'A' + 1) as u32;}
+    Some((row - 1, (col - 1) as u16))
+}//end parse_cell_ref(cell_ref)
+
+/// Reads the file at name inside the zip archive to a string.
+fn read_zip_file(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Result<String,String> {
+    let mut contents = String::new();
+    match archive.by_name(name) {
+        Ok(mut file) => match file.read_to_string(&mut contents) {
+            Ok(_) => Ok(contents),
+            Err(error) => Err(format!("Couldn't read \"{}\" in the workbook.\n{}", name, error)),
+        },
+        Err(error) => Err(format!("Couldn't find \"{}\" in the workbook.\n{}", name, error)),
+    }//end matching whether the file is in the archive
+}//end read_zip_file(archive, name)
+
+/// Gets the value of the attribute called key in the xml tag, if it has one.
+fn get_attribute(tag: &quick_xml::events::BytesStart, key: &[u8]) -> Option<String> {
+    match tag.try_get_attribute(key) {
+        Ok(Some(attribute)) => attribute.unescape_value().ok().map(|value| value.into_owned()),
+        _ => None,
+    }//end matching whether we found the attribute
+}//end get_attribute(tag, key)
+
+/// Reads the value of every non-empty cell in each sheet of the xlsx
+/// file in bytes, returned as (name of sheet, cells in that sheet) in
+/// the order of the sheets in the workbook.
+/// Numbers are formatted the same way no matter how they were written,
+/// so that the same number always gives the same value.
+pub fn read_workbook_cells(bytes: &[u8]) -> Result<Vec<(String, SheetCells)>,String> {
+    let mut archive = match ZipArchive::new(Cursor::new(bytes)) {
+        Ok(archive) => archive,
+        Err(error) => return Err(format!("Couldn't open the workbook as an xlsx file.\n{}", error)),
+    };
+
+    // get the name and relationship id of each sheet, in order
+    let workbook_xml = read_zip_file(&mut archive, "xl/workbook.xml")?;
+    let mut sheet_ids: Vec<(String, String)> = Vec::new();
+    let mut reader = Reader::from_str(&workbook_xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(tag)) | Ok(Event::Empty(tag)) if tag.name().as_ref() == b"sheet" => {
+                if let (Some(name), Some(rel_id)) = (get_attribute(&tag, b"name"), get_attribute(&tag, b"r:id")) {sheet_ids.push((name, rel_id));}
+            },
+            Ok(Event::Eof) => break,
+            Err(error) => return Err(format!("Couldn't read the list of sheets in the workbook.\n{}", error)),
+            _ => {},
+        }//end matching each xml event
+    }//end reading the sheets in the workbook
+
+    // find the file holding each sheet
+    let rels_xml = read_zip_file(&mut archive, "xl/_rels/workbook.xml.rels")?;
+    let mut rel_targets: Vec<(String, String)> = Vec::new();
+    let mut reader = Reader::from_str(&rels_xml);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(tag)) | Ok(Event::Empty(tag)) if tag.name().as_ref() == b"Relationship" => {
+                if let (Some(rel_id), Some(target)) = (get_attribute(&tag, b"Id"), get_attribute(&tag, b"Target")) {
+                    let target = match target.strip_prefix('/') {
+                        Some(absolute_target) => absolute_target.to_string(),
+                        None => format!("xl/{}", target),
+                    };
+                    rel_targets.push((rel_id, target));
+                }//end if this relationship has an id and target
+            },
+            Ok(Event::Eof) => break,
+            Err(error) => return Err(format!("Couldn't read the sheet locations in the workbook.\n{}", error)),
+            _ => {},
+        }//end matching each xml event
+    }//end reading the relationships of the workbook
+
+    // shared strings are optional, since a workbook might not have any strings
+    let mut shared_strings: Vec<String> = Vec::new();
+    if let Ok(shared_strings_xml) = read_zip_file(&mut archive, "xl/sharedStrings.xml") {
+        let mut reader = Reader::from_str(&shared_strings_xml);
+        let mut in_text = false;
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(tag)) if tag.name().as_ref() == b"si" => shared_strings.push(String::new()),
+                Ok(Event::Start(tag)) if tag.name().as_ref() == b"t" => in_text = true,
+                Ok(Event::End(tag)) if tag.name().as_ref() == b"t" => in_text = false,
+                Ok(Event::Text(text)) if in_text => {
+                    if let (Some(shared_string), Ok(text)) = (shared_strings.last_mut(), text.unescape()) {shared_string.push_str(&text);}
+                },
+                Ok(Event::Eof) => break,
+                Err(error) => return Err(format!("Couldn't read the strings in the workbook.\n{}", error)),
+                _ => {},
+            }//end matching each xml event
+        }//end reading each shared string
+    }//end if the workbook has shared strings
+
+    let mut workbook_cells = Vec::new();
+    for (sheet_name, rel_id) in sheet_ids {
+        let target = match rel_targets.iter().find(|(known_id, _)| known_id.eq(&rel_id)) {
+            Some((_, target)) => target.clone(),
+            None => return Err(format!("Couldn't find where sheet {} is kept in the workbook.", sheet_name)),
+        };
+        let sheet_xml = read_zip_file(&mut archive, &target)?;
+        let mut cells: SheetCells = Vec::new();
+        let mut reader = Reader::from_str(&sheet_xml);
+        // (position of cell, type of cell, value read so far)
+        let mut current_cell: Option<((u32, u16), String, String)> = None;
+        let mut in_value = false;
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(tag)) if tag.name().as_ref() == b"c" => {
+                    let position = get_attribute(&tag, b"r").and_then(|cell_ref| parse_cell_ref(&cell_ref));
+                    current_cell = position.map(|position| (position, get_attribute(&tag, b"t").unwrap_or_default(), String::new()));
+                },
+                Ok(Event::Start(tag)) if tag.name().as_ref() == b"v" || tag.name().as_ref() == b"t" => in_value = true,
+                Ok(Event::End(tag)) if tag.name().as_ref() == b"v" || tag.name().as_ref() == b"t" => in_value = false,
+                Ok(Event::Text(text)) if in_value => {
+                    if let (Some((_, _, value)), Ok(text)) = (current_cell.as_mut(), text.unescape()) {value.push_str(&text);}
+                },
+                Ok(Event::End(tag)) if tag.name().as_ref() == b"c" => {
+                    if let Some(((row, col), cell_type, raw_value)) = current_cell.take() {
+                        let value = match cell_type.as_str() {
+                            "s" => raw_value.trim().parse::<usize>().ok().and_then(|idx| shared_strings.get(idx).cloned()).unwrap_or(raw_value),
+                            "inlineStr" | "str" | "b" | "e" => raw_value,
+                            _ => match raw_value.trim().parse::<f64>() {
+                                Ok(number) => format!("{}", number),
+                                Err(_) => raw_value,
+                            },
+                        };
+                        if !value.is_empty() {cells.push((row, col, value));}
+                    }//end if we were reading a cell
+                },
+                Ok(Event::Eof) => break,
+                Err(error) => return Err(format!("Couldn't read the cells in sheet {}.\n{}", sheet_name, error)),
+                _ => {},
+            }//end matching each xml event
+        }//end reading each cell in the sheet
+        workbook_cells.push((sheet_name, cells));
+    }//end reading the cells of each sheet
+    Ok(workbook_cells)
+}//end read_workbook_cells(bytes)
+
+/// Gets the number of cells needed to hold row of sheet_cells, which is
+/// one past its last non-empty cell, or 0 if the row is empty.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::checksum::get_row_width;
+///
+/// let sheet_cells = vec![(0, 0, "external-sample-id".to_string()), (0, 2, "Area".to_string()), (1, 5, "stray".to_string())];
+/// assert_eq!(get_row_width(&sheet_cells, 0), 3);
+/// assert_eq!(get_row_width(&sheet_cells, 2), 0);
+/// ```
+pub fn get_row_width(sheet_cells: &SheetCells, row: u32) -> usize {
+    sheet_cells.iter()
+        .filter(|(cell_row, _, value)| *cell_row == row && !value.trim().is_empty())
+        .map(|(_, col, _)| *col as usize + 1)
+        .max()
+        .unwrap_or(0)
+}//end get_row_width(sheet_cells, row)
+
+/// Groups sheet_cells into rows, as (0-based row, text of each cell), in
+/// order, leaving out rows which have no cells. Each row has width cells,
+/// with empty text for empty cells, and cells past width are left out.  
+/// Only rows with cells take up space, and the width is chosen by the
+/// caller, such as from the header row with get_row_width(), so a stray
+/// cell far from the rest, such as at XFD1048576, doesn't fill in every
+/// row and column up to it.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::checksum::get_sheet_rows;
+///
+/// let sheet_cells = vec![(0, 0, "external-sample-id".to_string()), (0, 1, "Area".to_string()), (3, 0, "S1".to_string()), (1048575, 16383, "stray".to_string())];
+/// assert_eq!(get_sheet_rows(sheet_cells, 2), vec![
+///     (0, vec!["external-sample-id".to_string(), "Area".to_string()]),
+///     (3, vec!["S1".to_string(), String::new()]),
+///     (1048575, vec![String::new(), String::new()]),
+/// ]);
+/// ```
+pub fn get_sheet_rows(sheet_cells: SheetCells, width: usize) -> Vec<(u32, Vec<String>)> {
+    let mut rows: BTreeMap<u32, Vec<String>> = BTreeMap::new();
+    for (row, col, value) in sheet_cells {
+        let row_cells = rows.entry(row).or_insert_with(|| vec![String::new(); width]);
+        if let Some(cell) = row_cells.get_mut(col as usize) {*cell = value;}
+    }//end putting each cell in its row
+    rows.into_iter().collect()
+}//end get_sheet_rows(sheet_cells, width)
+
+/// Calculates the HMAC-SHA256, keyed with key, over the position and value
+/// of every cell in every sheet except the Run Info sheet, so that any
+/// edit to the output values gives a different signature, and someone
+/// without the key can't work out the signature for values they edited.
+pub fn get_cells_signature(workbook_cells: &Vec<(String, SheetCells)>, key: &[u8]) -> Result<String,String> {
+    let mut canonical = String::new();
+    for (sheet_name, cells) in workbook_cells.iter() {
+        if sheet_name.eq(RUN_INFO_SHEET_NAME) {continue;}
+        canonical.push_str(&format!("sheet\u{1f}{}\u{1e}", sheet_name));
+        for (row, col, value) in cells.iter() {canonical.push_str(&format!("{}\u{1f}{}\u{1f}{}\u{1e}", row, col, value));}
+    }//end adding the cells of each sheet
+    secrets::get_keyed_hash(key, canonical.as_bytes())
+}//end get_cells_signature(workbook_cells, key)
+
+/// Calculates the signature of the xlsx file in bytes with key, as in get_cells_signature().
+///
+/// # Examples
+///
+/// ```
+/// use rust_xlsxwriter::Workbook;
+/// use usda_c_grain_sum::io::checksum::get_workbook_signature;
+///
+/// let mut workbook = Workbook::new();
+/// let sheet = workbook.add_worksheet();
+/// sheet.write(0, 0, "external-sample-id").unwrap();
+/// sheet.write(1, 0, "A").unwrap();
+/// sheet.write(1, 1, 12.25).unwrap();
+/// let original = get_workbook_signature(&workbook.save_to_buffer().unwrap(), b"key one").unwrap();
+/// assert_eq!(original, get_workbook_signature(&workbook.save_to_buffer().unwrap(), b"key one").unwrap());
+/// assert_ne!(original, get_workbook_signature(&workbook.save_to_buffer().unwrap(), b"key two").unwrap());
+///
+/// workbook.worksheet_from_index(0).unwrap().write(1, 1, 12.5).unwrap();
+/// assert_ne!(original, get_workbook_signature(&workbook.save_to_buffer().unwrap(), b"key one").unwrap());
+/// ```
+pub fn get_workbook_signature(bytes: &[u8], key: &[u8]) -> Result<String,String> {
+    get_cells_signature(&read_workbook_cells(bytes)?, key)
+}//end get_workbook_signature(bytes, key)
+
+/// Re-opens the workbook at path, and checks whether the signature stored
+/// in its Run Info sheet still matches the values in the workbook, signed
+/// with key.
+/// Returns (stored signature, current signature). If they're different,
+/// the workbook has been edited since it was written, or it was signed
+/// with another key, such as by another install.  
+/// Workbooks from older versions, which only have an unkeyed checksum,
+/// give an error, since that checksum can't show the values weren't edited.
+pub fn verify_workbook_signature(path: &Path, key: &[u8]) -> Result<(String, String),String> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => return Err(format!("Couldn't read \"{}\".\n{}", path.to_string_lossy(), error)),
+    };
+    let workbook_cells = read_workbook_cells(&bytes)?;
+    let run_info_cells = match workbook_cells.iter().find(|(sheet_name, _)| sheet_name.eq(RUN_INFO_SHEET_NAME)) {
+        Some((_, cells)) => cells,
+        None => return Err(format!("The workbook doesn't have a {} sheet, so there's no signature to check against.", RUN_INFO_SHEET_NAME)),
+    };
+    let get_labeled_value = |label: &str| run_info_cells.iter()
+        .find(|(_, col, value)| *col == 0 && value.eq(label))
+        .and_then(|(label_row, _, _)| run_info_cells.iter().find(|(row, col, _)| row == label_row && *col == 1))
+        .map(|(_, _, value)| value.clone());
+    match get_labeled_value(SIGNATURE_LABEL) {
+        Some(stored_signature) => Ok((stored_signature, get_cells_signature(&workbook_cells, key)?)),
+        None if get_labeled_value(LEGACY_CHECKSUM_LABEL).is_some() => Err(format!("The workbook was written by an older version, which stored an {} instead of a signature. That checksum can be recalculated by anyone who edits the workbook, so it can't show whether the values were edited.", LEGACY_CHECKSUM_LABEL)),
+        None => Err(format!("Couldn't find the {} in the {} sheet.", SIGNATURE_LABEL, RUN_INFO_SHEET_NAME)),
+    }//end matching whether we found the stored signature
+}//end verify_workbook_signature(path, key)
+
+/// Tells whether the file at path is an xlsx workbook written by this
+/// program, which is known by its Run Info sheet.  
+/// Files which can't be read as a workbook aren't output files.
+pub fn is_output_workbook(path: &Path) -> bool {
+    match fs::read(path) {
+        Ok(bytes) => read_workbook_cells(&bytes)
+            .is_ok_and(|workbook_cells| workbook_cells.iter().any(|(sheet_name, _)| sheet_name.eq(RUN_INFO_SHEET_NAME))),
+        Err(_) => false,
+    }//end matching whether we could read the file
+}//end is_output_workbook(path)
@@ -0,0 +1,242 @@
+use crate::{app::manifest, model::Data, stats};
+
+/// The kind of values a column of a known export layout should hold.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ColumnKind {
+    /// Any text, such as a sample id or class name.
+    Text,
+    /// Numbers. A few stray strings are allowed, but most
+    /// non-empty cells have to be numbers.
+    Number,
+}//end enum ColumnKind
+
+/// The type of input file an export layout describes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ExportFileKind {
+    Csv,
+    Xml,
+}//end enum ExportFileKind
+
+/// The format of a data file chosen with the Open Data File(s) button,
+/// used to decide whether it's read as a csv or xml input file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum InputFileKind {
+    Csv,
+    Xml,
+    /// An xlsx workbook, such as an output file from this program.
+    Xlsx,
+}//end enum InputFileKind
+
+/// A column which a known export layout always has.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ColumnSchema {
+    /// The header of the column.
+    /// If is_prefix is true, any header starting with this matches.
+    pub name: &'static str,
+    pub is_prefix: bool,
+    pub kind: ColumnKind,
+}//end struct ColumnSchema
+
+/// The layout of a known C-Grain export version, given by the
+/// columns it always has and the kind of values in each.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ExportSchema {
+    /// The name shown to the user, such as "C-Grain CSV export".
+    pub name: &'static str,
+    pub file_kind: ExportFileKind,
+    pub columns: &'static [ColumnSchema],
+    /// For older export versions, pairs of (header in this version,
+    /// header in the current version), used to rename the columns of
+    /// an older file so it processes with the current config.
+    pub header_aliases: &'static [(&'static str, &'static str)],
+}//end struct ExportSchema
+
+/// The export layouts which strict schema mode accepts, in the order
+/// they're checked, so newer versions should come first.
+pub const KNOWN_SCHEMAS: &[ExportSchema] = &[
+    ExportSchema {
+        name: "C-Grain CSV export",
+        file_kind: ExportFileKind::Csv,
+        columns: &[
+            ColumnSchema { name: "external-sample-id", is_prefix: false, kind: ColumnKind::Text },
+            ColumnSchema { name: "raw-filtered-as", is_prefix: false, kind: ColumnKind::Text },
+            ColumnSchema { name: "Area", is_prefix: false, kind: ColumnKind::Number },
+            ColumnSchema { name: "Length", is_prefix: false, kind: ColumnKind::Number },
+            ColumnSchema { name: "Width", is_prefix: false, kind: ColumnKind::Number },
+        ],
+        header_aliases: &[],
+    },
+    ExportSchema {
+        name: "C-Grain CSV export (older, with cor-filtered-as)",
+        file_kind: ExportFileKind::Csv,
+        columns: &[
+            ColumnSchema { name: "external-sample-id", is_prefix: false, kind: ColumnKind::Text },
+            ColumnSchema { name: "cor-filtered-as", is_prefix: false, kind: ColumnKind::Text },
+            ColumnSchema { name: "Area", is_prefix: false, kind: ColumnKind::Number },
+            ColumnSchema { name: "Length", is_prefix: false, kind: ColumnKind::Number },
+            ColumnSchema { name: "Width", is_prefix: false, kind: ColumnKind::Number },
+        ],
+        header_aliases: &[("cor-filtered-as", "raw-filtered-as")],
+    },
+    ExportSchema {
+        name: "C-Grain XML export",
+        file_kind: ExportFileKind::Xml,
+        columns: &[
+            ColumnSchema { name: "reference", is_prefix: false, kind: ColumnKind::Text },
+            ColumnSchema { name: "filter-sieving", is_prefix: true, kind: ColumnKind::Number },
+        ],
+        header_aliases: &[],
+    },
+];
+
+/// Checks whether data has every column of schema, with the right kind
+/// of values in each. If it doesn't, the error describes the first
+/// problem found.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::io::schema::{check_schema, KNOWN_SCHEMAS};
+///
+/// let headers: Vec<String> = vec!["reference", "filter-sieving-small"].into_iter().map(|h| h.to_string()).collect();
+/// let row = DataRow::new(0, vec![DataCell::new(&headers[0], "S1".to_string()), DataCell::new(&headers[1], "3.5".to_string())]);
+/// let data = Data::from_row_data(headers, vec![row]);
+///
+/// let xml_schema = KNOWN_SCHEMAS.iter().find(|schema| schema.name == "C-Grain XML export").unwrap();
+/// assert!(check_schema(&data, xml_schema).is_ok());
+/// assert!(check_schema(&data, &KNOWN_SCHEMAS[0]).unwrap_err().contains("external-sample-id"));
+/// ```
+pub fn check_schema(data: &Data, schema: &ExportSchema) -> Result<(),String> {
+    for column in schema.columns.iter() {
+        let col_idxs: Vec<usize> = data.get_headers_ref().iter().enumerate()
+            .filter(|(_, header)| if column.is_prefix {header.starts_with(column.name)} else {header.as_str() == column.name})
+            .map(|(col_idx, _)| col_idx)
+            .collect();
+        if col_idxs.len() == 0 {
+            return Err(match column.is_prefix {
+                true => format!("no column starting with \"{}\"", column.name),
+                false => format!("no \"{}\" column", column.name),
+            });
+        }//end if the column is missing
+        if column.kind == ColumnKind::Number {
+            for col_idx in col_idxs {
+                let (values, non_numeric_count, _) = stats::get_column_values(data, col_idx);
+                if non_numeric_count > values.len() {
+                    let header = data.get_header_from_index(col_idx).cloned().unwrap_or_default();
+                    return Err(format!("column \"{}\" should hold numbers, but {} of its cells are text", header, non_numeric_count));
+                }//end if this column is mostly text
+            }//end checking each matching column
+        }//end if the column should hold numbers
+    }//end checking each column of the schema
+    Ok(())
+}//end check_schema(data, schema)
+
+/// Gets a fingerprint of headers, which is the same for every file with
+/// the same headers in the same order, so a layout of file can be
+/// recognized again, such as for a saved column mapping.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::schema::get_header_fingerprint;
+///
+/// let headers = vec!["Sample Name".to_string(), "Area".to_string()];
+/// assert_eq!(get_header_fingerprint(&headers), get_header_fingerprint(&headers.clone()));
+/// assert_ne!(get_header_fingerprint(&headers), get_header_fingerprint(&vec!["Area".to_string(), "Sample Name".to_string()]));
+/// ```
+pub fn get_header_fingerprint(headers: &Vec<String>) -> String {
+    manifest::hash_bytes(headers.join("\n").as_bytes())
+}//end get_header_fingerprint(headers)
+
+/// Finds which of the known export layouts of file_kind data matches.
+/// If it matches none of them, the error lists why each layout didn't
+/// match, suitable for showing the user.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::io::schema::{detect_schema, ExportFileKind};
+///
+/// let make_data = |headers: Vec<&str>, values: Vec<&str>| {
+///     let headers: Vec<String> = headers.into_iter().map(|h| h.to_string()).collect();
+///     let cells = headers.iter().zip(values).map(|(h, v)| DataCell::new(h, v.to_string())).collect();
+///     Data::from_row_data(headers.clone(), vec![DataRow::new(0, cells)])
+/// };
+///
+/// let current = make_data(vec!["external-sample-id", "raw-filtered-as", "Area", "Length", "Width"], vec!["S1", "Sound", "12.1", "5.2", "3.1"]);
+/// assert_eq!(detect_schema(&current, ExportFileKind::Csv).unwrap().name, "C-Grain CSV export");
+/// let older = make_data(vec!["external-sample-id", "cor-filtered-as", "Area", "Length", "Width"], vec!["S1", "Sound", "12.1", "5.2", "3.1"]);
+/// assert_eq!(detect_schema(&older, ExportFileKind::Csv).unwrap().name, "C-Grain CSV export (older, with cor-filtered-as)");
+/// let other = make_data(vec!["id", "Area", "Length", "Width"], vec!["S1", "12.1", "5.2", "3.1"]);
+/// assert!(detect_schema(&other, ExportFileKind::Csv).is_err());
+/// ```
+pub fn detect_schema(data: &Data, file_kind: ExportFileKind) -> Result<&'static ExportSchema,String> {
+    let mut mismatches = Vec::new();
+    for schema in KNOWN_SCHEMAS.iter().filter(|schema| schema.file_kind == file_kind) {
+        match check_schema(data, schema) {
+            Ok(_) => return Ok(schema),
+            Err(msg) => mismatches.push(format!("{}: {}", schema.name, msg)),
+        }//end matching whether data has this layout
+    }//end checking each known layout
+    Err(format!("The file doesn't match any known C-Grain export layout, so it may be from another instrument.\n{}", mismatches.join("\n")))
+}//end detect_schema(data, file_kind)
+
+/// Decides whether a file is csv, xml, or xlsx, from its extension
+/// and the first bytes of its contents.  
+/// The contents win over the extension, since files are sometimes
+/// renamed, so the extension is only used when the contents could
+/// be either, such as a file with a single line of text.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::schema::{sniff_input_kind, InputFileKind};
+///
+/// assert_eq!(sniff_input_kind("csv", b"external-sample-id,Area\nS1,12.1"), Some(InputFileKind::Csv));
+/// assert_eq!(sniff_input_kind("xml", b"\xEF\xBB\xBF<?xml version=\"1.0\"?><Samples>"), Some(InputFileKind::Xml));
+/// assert_eq!(sniff_input_kind("xlsx", b"PK\x03\x04"), Some(InputFileKind::Xlsx));
+/// // a csv file that was saved with the wrong extension
+/// assert_eq!(sniff_input_kind("xml", b"external-sample-id,Area\nS1,12.1"), Some(InputFileKind::Csv));
+/// assert_eq!(sniff_input_kind("txt", b"<Samples><Sample>"), Some(InputFileKind::Xml));
+/// assert_eq!(sniff_input_kind("CSV", b"Area"), Some(InputFileKind::Csv));
+/// assert_eq!(sniff_input_kind("png", b"\x89PNG\r\n"), None);
+/// ```
+pub fn sniff_input_kind(extension: &str, first_bytes: &[u8]) -> Option<InputFileKind> {
+    if first_bytes.starts_with(b"PK\x03\x04") {return Some(InputFileKind::Xlsx);}
+    let text = String::from_utf8_lossy(first_bytes);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    // binary files, such as images, are never read as csv or xml
+    if text.chars().any(|c| c.is_control() && !c.is_whitespace()) {return None;}
+    if text.starts_with('<') {return Some(InputFileKind::Xml);}
+    let lines: Vec<&str> = text.lines().take(2).collect();
+    let looks_delimited = lines.len() > 1 && lines.iter().all(|line| line.contains(',') || line.contains(';') || line.contains('\t'));
+    if looks_delimited {return Some(InputFileKind::Csv);}
+    match extension.to_lowercase().as_str() {
+        "csv" | "txt" => Some(InputFileKind::Csv),
+        "xml" => Some(InputFileKind::Xml),
+        _ => None,
+    }//end matching the extension when contents aren't clear
+}//end sniff_input_kind(extension, first_bytes)
+
+/// Reads the start of the file at file_path and decides whether
+/// it's csv, xml, or xlsx, using sniff_input_kind().
+pub fn sniff_input_file_kind(file_path: &std::path::Path) -> Result<InputFileKind,String> {
+    use std::io::Read;
+    let mut first_bytes = Vec::new();
+    match std::fs::File::open(file_path) {
+        Ok(file) => if let Err(error) = file.take(1024).read_to_end(&mut first_bytes) {
+            return Err(format!("Couldn't read the file: {}", error));
+        },
+        Err(error) => return Err(format!("Couldn't open the file: {}", error)),
+    }//end matching whether we could open the file
+    let extension = file_path.extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_default();
+    match sniff_input_kind(&extension, &first_bytes) {
+        Some(file_kind) => Ok(file_kind),
+        None => Err(String::from("It doesn't look like a csv, xml, or xlsx file.")),
+    }//end matching whether we recognize the file
+}//end sniff_input_file_kind(file_path)
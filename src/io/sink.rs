@@ -0,0 +1,488 @@
+use std::{fs, path::PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::{app::{config_store::{ConfigStore, OutputFormat}, lock::{self, FileLock}, process}, io::{checksum, xlsx}, model::{Data, DataVal, SampleOutput}};
+
+/// Everything from one run of processing which is written by the sinks.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SinkOutput<'a> {
+    /// The output sheets, as (sheet name, sheet data), in the order they're written.
+    pub sheets: &'a Vec<(String, SampleOutput)>,
+    /// The csv input, for sinks which keep a copy of it, if it was loaded.
+    pub input_snapshot: Option<&'a Data>,
+    /// The lines of the Run Info sheet, other than the signature,
+    /// or None if the Run Info sheet shouldn't be written.
+    pub run_info: Option<Vec<(String, String)>>,
+    /// The key the Run Info sheet signs the output with, such as from
+    /// secrets::get_install_salt(secrets::OUTPUT_SIGNING_KEY_FILE_NAME).
+    /// This is needed when run_info is given.
+    pub signing_key: Option<&'a [u8]>,
+}//end struct SinkOutput
+
+/// A format the output sheets can be written in.
+/// To support a new format, implement this trait for it, then add it
+/// to get_sinks(), so it's written whenever it's enabled in the config.
+pub trait OutputSink {
+    /// The name of the format, used in messages to the user.
+    fn name(&self) -> &'static str;
+    /// Tells whether this sink should be written, according to config.
+    fn is_enabled(&self, config: &ConfigStore) -> bool;
+    /// Gets where this sink writes to, based on the output file the user chose.
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf;
+    /// Writes output to the path from get_output_path(output).
+    /// Returns a description of what was written, to print for the user.
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String>;
+}//end trait OutputSink
+
+/// Writes the output workbook the user chose, with a sheet for each
+/// output sheet, along with the input snapshot and Run Info sheets
+/// when they're enabled. This sink is always written.
+pub struct XlsxSink;
+
+impl OutputSink for XlsxSink {
+    fn name(&self) -> &'static str {"xlsx"}
+    fn is_enabled(&self, _config: &ConfigStore) -> bool {true}
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {output.clone()}
+
+    /// Saves the workbook from XlsxSink::get_bytes() to output_path,
+    /// while holding a lock on it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    /// use usda_c_grain_sum::io::{checksum, sink::{OutputSink, SinkOutput, XlsxSink}};
+    /// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+    ///
+    /// let sheet_data = SampleOutput {
+    ///     id_header: "external-sample-id".to_string(),
+    ///     columns: vec![OutputColumn::number("Avg Area", 2)],
+    ///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.345)])],
+    /// };
+    /// let sheets = vec![("CSV_Stats".to_string(), sheet_data)];
+    /// let output = SinkOutput { sheets: &sheets, input_snapshot: None, run_info: Some(Vec::new()), signing_key: Some(&b"signing key"[..]) };
+    /// let path = std::env::temp_dir().join("c_grain_sum_xlsx_sink_doctest.xlsx");
+    /// XlsxSink.write(&output, &ConfigStore::default(), &path).unwrap();
+    ///
+    /// let (stored, current) = checksum::verify_workbook_signature(&path, b"signing key").unwrap();
+    /// assert_eq!(stored, current);
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String> {
+        let bytes = self.get_bytes(output, config)?;
+        let _output_lock = match FileLock::try_acquire(output_path) {
+            Ok(output_lock) => output_lock,
+            Err(msg) => return Err(format!("Couldn't write the output file, since another copy of this program is writing it.\n{}", msg)),
+        };
+        match fs::write(output_path, bytes) {
+            Ok(_) => Ok(format!("Wrote {} sheets to {}", output.sheets.len(), output_path.to_string_lossy())),
+            Err(error) if lock::is_sharing_violation(&error) => Err(format!("\"{}\" is open in another program, such as Excel. Close it, then try again.\n{}", output_path.to_string_lossy(), error)),
+            Err(error) => Err(format!("Encountered an error while attempting to write data to worksheet.\n{}", error)),
+        }//end matching whether we could save the workbook
+    }//end write(self, output, config, output_path)
+}//end impl OutputSink for XlsxSink
+
+impl XlsxSink {
+    /// Writes each sheet to a new workbook, then gets the workbook as the
+    /// bytes of an xlsx file, so library users can get the output without
+    /// writing it to disk.
+    /// The Run Info sheet is written last, so its signature covers every other sheet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    /// use usda_c_grain_sum::io::{checksum, sink::{SinkOutput, XlsxSink}};
+    /// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+    ///
+    /// let sheet_data = SampleOutput {
+    ///     id_header: "external-sample-id".to_string(),
+    ///     columns: vec![OutputColumn::number("Avg Area", 2)],
+    ///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.345)])],
+    /// };
+    /// let sheets = vec![("CSV_Stats".to_string(), sheet_data)];
+    /// let output = SinkOutput { sheets: &sheets, input_snapshot: None, run_info: None, signing_key: None };
+    /// let bytes = XlsxSink.get_bytes(&output, &ConfigStore::default()).unwrap();
+    ///
+    /// let workbook_cells = checksum::read_workbook_cells(&bytes).unwrap();
+    /// assert_eq!(workbook_cells[0].0, "CSV_Stats");
+    /// ```
+    pub fn get_bytes(&self, output: &SinkOutput, config: &ConfigStore) -> Result<Vec<u8>,String> {
+        let mut wb = xlsx::get_workbook();
+        for (sheet_name, sheet_data) in output.sheets.iter() {
+            if let Err(error) = xlsx::write_output_to_sheet(&mut wb, sheet_data, sheet_name, config) {
+                return Err(format!("Ecountered an error while attempting to write data to worksheet {}.\n{}", sheet_name, error));
+            }//end if we couldn't write this sheet
+        }//end writing data from each output sheet
+        if config.input_snapshot_enabled {
+            if let Some(input_csv) = output.input_snapshot {
+                if let Err(msg) = xlsx::write_input_snapshot_sheet(&mut wb, input_csv, "Input_Data", config) {
+                    return Err(format!("Ecountered an error while attempting to copy the input rows into the output.\n{}", msg));
+                }//end if we couldn't write the input snapshot
+            }//end if we have csv input to copy
+        }//end if we should copy the input into the output
+        if let Some(ref run_info) = output.run_info {
+            let signing_key = output.signing_key.ok_or(String::from("Couldn't sign the output for the Run Info sheet, since there's no signing key."))?;
+            let output_signature = match xlsx::workbook_to_bytes(&mut wb).map_err(|error| error.to_string()).and_then(|bytes| checksum::get_workbook_signature(&bytes, signing_key)) {
+                Ok(output_signature) => output_signature,
+                Err(msg) => return Err(format!("Couldn't calculate the signature of the output for the Run Info sheet.\n{}", msg)),
+            };
+            let mut run_info = run_info.clone();
+            run_info.push((checksum::SIGNATURE_LABEL.to_string(), output_signature));
+            if let Err(error) = xlsx::write_run_info_sheet(&mut wb, &run_info, config) {
+                return Err(format!("Ecountered an error while attempting to write the Run Info sheet.\n{}", error));
+            }//end if we couldn't write the run info sheet
+        }//end if we should write the run info sheet
+        match xlsx::workbook_to_bytes(&mut wb) {
+            Ok(bytes) => Ok(bytes),
+            Err(error) => Err(format!("Couldn't get the bytes of the output workbook.\n{}", error)),
+        }//end matching whether we could save the workbook to bytes
+    }//end get_bytes(self, output, config)
+}//end impl XlsxSink
+
+/// Writes each output sheet to its own csv file, in a folder next to
+/// the output file, when the output format is OutputFormat::XlsxAndCsvFiles.
+pub struct CsvSink;
+
+impl OutputSink for CsvSink {
+    fn name(&self) -> &'static str {"csv"}
+    fn is_enabled(&self, config: &ConfigStore) -> bool {config.output_format == OutputFormat::XlsxAndCsvFiles}
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {process::get_csv_output_dir(output)}
+
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String> {
+        let files_written = process::write_output_to_csv(output.sheets, config, output_path)?;
+        Ok(format!("Wrote {} csv files to {}", files_written, output_path.to_string_lossy()))
+    }//end write(self, output, config, output_path)
+}//end impl OutputSink for CsvSink
+
+/// Writes every output sheet to a single csv file next to the output
+/// file, with a section for each sheet, when the output format is
+/// OutputFormat::XlsxAndCsvSections.
+pub struct CsvSectionsSink;
+
+impl OutputSink for CsvSectionsSink {
+    fn name(&self) -> &'static str {"sectioned csv"}
+    fn is_enabled(&self, config: &ConfigStore) -> bool {config.output_format == OutputFormat::XlsxAndCsvSections}
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {output.with_extension("csv")}
+
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String> {
+        let sheets_written = process::write_output_to_csv(output.sheets, config, output_path)?;
+        Ok(format!("Wrote {} sheets to {}", sheets_written, output_path.to_string_lossy()))
+    }//end write(self, output, config, output_path)
+}//end impl OutputSink for CsvSectionsSink
+
+/// Writes the output sheets to a json file next to the output file,
+/// when json output is enabled, for other programs to read.
+pub struct JsonSink;
+
+impl OutputSink for JsonSink {
+    fn name(&self) -> &'static str {"json"}
+    fn is_enabled(&self, config: &ConfigStore) -> bool {config.json_output_enabled}
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {output.with_extension("json")}
+
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String> {
+        let sheets_json = get_output_json(output.sheets, config);
+        let output_json = match serde_json::to_string_pretty(&sheets_json) {
+            Ok(output_json) => output_json,
+            Err(error) => return Err(format!("Couldn't convert the output sheets to json.\n{}", error)),
+        };
+        match fs::write(output_path, output_json) {
+            Ok(_) => Ok(format!("Wrote {} sheets to {}", output.sheets.len(), output_path.to_string_lossy())),
+            Err(error) => Err(format!("Couldn't write json file \"{}\".\n{}", output_path.to_string_lossy(), error)),
+        }//end matching whether we could write the json file
+    }//end write(self, output, config, output_path)
+}//end impl OutputSink for JsonSink
+
+/// Writes the output sheets to a sqlite database next to the output file,
+/// with a table for each sheet, when sqlite output is enabled.
+#[cfg(feature = "sqlite")]
+pub struct SqliteSink;
+
+#[cfg(feature = "sqlite")]
+impl OutputSink for SqliteSink {
+    fn name(&self) -> &'static str {"sqlite"}
+    fn is_enabled(&self, config: &ConfigStore) -> bool {config.sqlite_output_enabled}
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {output.with_extension("sqlite")}
+
+    /// Replaces the database at output_path with one holding a table for
+    /// each sheet, with a column for the sample id, then one for each
+    /// visible column in the sheet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::app::config_store::ConfigStore;
+    /// use usda_c_grain_sum::io::sink::{OutputSink, SinkOutput, SqliteSink};
+    /// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+    ///
+    /// let sheet_data = SampleOutput {
+    ///     id_header: "external-sample-id".to_string(),
+    ///     columns: vec![OutputColumn::number("Avg Area", 2)],
+    ///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(12.5)])],
+    /// };
+    /// let sheets = vec![("CSV_Stats".to_string(), sheet_data)];
+    /// let output = SinkOutput { sheets: &sheets, input_snapshot: None, run_info: None, signing_key: None };
+    /// let path = std::env::temp_dir().join("c_grain_sum_sqlite_sink_doctest.sqlite");
+    /// SqliteSink.write(&output, &ConfigStore::default(), &path).unwrap();
+    ///
+    /// let connection = rusqlite::Connection::open(&path).unwrap();
+    /// let area: f64 = connection.query_row("SELECT \"Avg Area\" FROM \"CSV_Stats\" WHERE \"external-sample-id\" = 'S1'", [], |row| row.get(0)).unwrap();
+    /// assert_eq!(area, 12.5);
+    /// drop(connection);
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, output_path: &PathBuf) -> Result<String,String> {
+        use rusqlite::{types::Value as SqlValue, Connection};
+        let quote = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+        if output_path.exists() {
+            if let Err(error) = fs::remove_file(output_path) {return Err(format!("Couldn't replace the database at \"{}\".\n{}", output_path.to_string_lossy(), error));}
+        }//end if there's an old database to replace
+        let mut connection = match Connection::open(output_path) {
+            Ok(connection) => connection,
+            Err(error) => return Err(format!("Couldn't create the database at \"{}\".\n{}", output_path.to_string_lossy(), error)),
+        };
+        let transaction = match connection.transaction() {
+            Ok(transaction) => transaction,
+            Err(error) => return Err(format!("Couldn't start writing to the database.\n{}", error)),
+        };
+        for (sheet_name, sheet_data) in output.sheets.iter() {
+            let column_order = get_visible_columns(sheet_data, sheet_name, config);
+            let mut column_names = vec![quote(&sheet_data.id_header)];
+            for col_idx in column_order.iter() {column_names.push(quote(&sheet_data.columns[*col_idx].name));}
+            let create_sql = format!("CREATE TABLE {} ({})", quote(sheet_name), column_names.join(", "));
+            if let Err(error) = transaction.execute(&create_sql, []) {return Err(format!("Couldn't create a table for the {} sheet.\n{}", sheet_name, error));}
+            let placeholders = vec!["?"; column_names.len()].join(", ");
+            let insert_sql = format!("INSERT INTO {} VALUES ({})", quote(sheet_name), placeholders);
+            for output_row in sheet_data.rows.iter() {
+                let mut row_values = vec![SqlValue::Text(output_row.sample_id.clone())];
+                for col_idx in column_order.iter() {
+                    row_values.push(match output_row.cells.get(*col_idx) {
+                        Some(DataVal::Int(i)) => SqlValue::Integer(*i),
+                        Some(DataVal::Float(f)) => SqlValue::Real(*f),
+                        Some(DataVal::String(s)) => SqlValue::Text(s.clone()),
+                        None => SqlValue::Null,
+                    });
+                }//end getting the value of each visible column
+                if let Err(error) = transaction.execute(&insert_sql, rusqlite::params_from_iter(row_values)) {
+                    return Err(format!("Couldn't write the row for {} in the {} table.\n{}", output_row.sample_id, sheet_name, error));
+                }//end if we couldn't write this row
+            }//end writing each row of this sheet
+        }//end writing a table for each sheet
+        match transaction.commit() {
+            Ok(_) => Ok(format!("Wrote {} tables to {}", output.sheets.len(), output_path.to_string_lossy())),
+            Err(error) => Err(format!("Couldn't finish writing the database.\n{}", error)),
+        }//end matching whether we could save the database
+    }//end write(self, output, config, output_path)
+}//end impl OutputSink for SqliteSink
+
+/// The address of the Google Sheets API.
+#[cfg(feature = "google-sheets")]
+const GOOGLE_SHEETS_API: &str = "https://sheets.googleapis.com/v4/spreadsheets";
+
+/// Pushes the output sheets to the Google Sheet from the config, with the
+/// Sheets API, when Google Sheets output is enabled. Each sheet replaces
+/// the tab of the same name, which is added if the Google Sheet doesn't
+/// have it yet. Cells have the same text as the csv output, so Google
+/// Sheets reads numbers and percents as it would when typed in.  
+/// This sink isn't in get_sinks(), since it's only written for merged
+/// summaries, which are what's shared in Google Sheets.
+#[cfg(feature = "google-sheets")]
+pub struct GoogleSheetsSink;
+
+#[cfg(feature = "google-sheets")]
+impl OutputSink for GoogleSheetsSink {
+    fn name(&self) -> &'static str {"Google Sheets"}
+    fn is_enabled(&self, config: &ConfigStore) -> bool {config.google_sheets_enabled}
+    /// The Google Sheet is chosen in the config, so this is only
+    /// the output file the user chose.
+    fn get_output_path(&self, output: &PathBuf) -> PathBuf {output.clone()}
+
+    fn write(&self, output: &SinkOutput, config: &ConfigStore, _output_path: &PathBuf) -> Result<String,String> {
+        let spreadsheet_id = config.google_sheets_spreadsheet_id.trim();
+        if spreadsheet_id.is_empty() {return Err(String::from("No Google Sheet is set to push to."));}
+        let authorization = format!("Bearer {}", get_google_access_token(&crate::app::paths::resolve_setting_path(&config.google_sheets_key_path))?);
+        let spreadsheet_url = format!("{}/{}", GOOGLE_SHEETS_API, encode_url_component(spreadsheet_id));
+
+        let metadata = send_google_request(ureq::get(&format!("{}?fields=sheets.properties.title", spreadsheet_url)).set("Authorization", &authorization), None)
+            .map_err(|msg| format!("Couldn't open the Google Sheet.\n{}", msg))?;
+        let tab_names: Vec<&str> = metadata["sheets"].as_array().map(|tabs| tabs.iter().filter_map(|tab| tab["properties"]["title"].as_str()).collect()).unwrap_or_default();
+        let add_requests: Vec<Value> = output.sheets.iter()
+            .filter(|(sheet_name, _)| !tab_names.contains(&sheet_name.as_str()))
+            .map(|(sheet_name, _)| json!({"addSheet": {"properties": {"title": sheet_name}}}))
+            .collect();
+        if add_requests.len() > 0 {
+            send_google_request(ureq::post(&format!("{}:batchUpdate", spreadsheet_url)).set("Authorization", &authorization), Some(json!({"requests": add_requests})))
+                .map_err(|msg| format!("Couldn't add tabs for the output sheets to the Google Sheet.\n{}", msg))?;
+        }//end if any tabs need to be added
+
+        for (sheet_name, sheet_data) in output.sheets.iter() {
+            let range_url = format!("{}/values/{}", spreadsheet_url, encode_url_component(&format!("'{}'", sheet_name.replace('\'', "''"))));
+            send_google_request(ureq::post(&format!("{}:clear", range_url)).set("Authorization", &authorization), Some(json!({})))
+                .map_err(|msg| format!("Couldn't clear the {} tab of the Google Sheet.\n{}", sheet_name, msg))?;
+            let (headers, rows) = process::get_sheet_text_table(sheet_data, sheet_name, config);
+            let mut values = vec![headers];
+            values.extend(rows);
+            send_google_request(ureq::put(&format!("{}?valueInputOption=USER_ENTERED", range_url)).set("Authorization", &authorization), Some(json!({"values": values})))
+                .map_err(|msg| format!("Couldn't write the {} tab of the Google Sheet.\n{}", sheet_name, msg))?;
+        }//end writing each sheet to its tab
+        Ok(format!("Wrote {} sheets to Google Sheet {}", output.sheets.len(), spreadsheet_id))
+    }//end write(self, output, config, _output_path)
+}//end impl OutputSink for GoogleSheetsSink
+
+/// Gets an access token for the Sheets API, signed in as the service
+/// account whose json key file is at key_path.
+#[cfg(feature = "google-sheets")]
+fn get_google_access_token(key_path: &PathBuf) -> Result<String,String> {
+    #[derive(serde::Deserialize)]
+    struct ServiceAccountKey {client_email: String, private_key: String, token_uri: String}
+    let key_json = match fs::read_to_string(key_path) {
+        Ok(key_json) => key_json,
+        Err(error) => return Err(format!("Couldn't read the service account key at \"{}\".\n{}", key_path.to_string_lossy(), error)),
+    };
+    let key = match serde_json::from_str::<ServiceAccountKey>(&key_json) {
+        Ok(key) => key,
+        Err(error) => return Err(format!("The file at \"{}\" isn't a service account key.\n{}", key_path.to_string_lossy(), error)),
+    };
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+    let claims = json!({
+        "iss": key.client_email,
+        "scope": "https://www.googleapis.com/auth/spreadsheets",
+        "aud": key.token_uri,
+        "iat": now,
+        "exp": now + 3600,
+    });
+    let signing_key = jsonwebtoken::EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+        .map_err(|error| format!("Couldn't read the private key of the service account.\n{}", error))?;
+    let assertion = jsonwebtoken::encode(&jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256), &claims, &signing_key)
+        .map_err(|error| format!("Couldn't sign the request for an access token.\n{}", error))?;
+    let token_response = match ureq::post(&key.token_uri).send_form(&[("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())]) {
+        Ok(response) => response.into_string().map_err(|error| error.to_string()),
+        Err(ureq::Error::Status(code, response)) => Err(format!("Google answered with status {}: {}", code, response.into_string().unwrap_or_default())),
+        Err(error) => Err(error.to_string()),
+    }.map_err(|msg| format!("Couldn't sign in as the service account.\n{}", msg))?;
+    match serde_json::from_str::<Value>(&token_response).ok().and_then(|token_json| token_json["access_token"].as_str().map(|token| token.to_string())) {
+        Some(access_token) => Ok(access_token),
+        None => Err(format!("Google didn't give an access token for the service account.\n{}", token_response)),
+    }//end matching whether we got an access token
+}//end get_google_access_token(key_path)
+
+/// Sends request to the Sheets API, with body as json if there is one,
+/// and gets the json it answers with.
+#[cfg(feature = "google-sheets")]
+fn send_google_request(request: ureq::Request, body: Option<Value>) -> Result<Value,String> {
+    let response = match body {
+        Some(body) => request.set("Content-Type", "application/json").send_string(&body.to_string()),
+        None => request.call(),
+    };
+    let response_text = match response {
+        Ok(response) => response.into_string().map_err(|error| error.to_string())?,
+        Err(ureq::Error::Status(code, response)) => return Err(format!("Google answered with status {}: {}", code, response.into_string().unwrap_or_default())),
+        Err(error) => return Err(error.to_string()),
+    };
+    match response_text.trim().is_empty() {
+        true => Ok(Value::Null),
+        false => serde_json::from_str(&response_text).map_err(|error| format!("Couldn't read the answer from Google as json.\n{}", error)),
+    }//end matching whether Google answered with anything
+}//end send_google_request(request, body)
+
+/// Percent-encodes text for use in one part of a url, such as the
+/// name of a sheet, leaving only letters, digits, and -._~ as they are.
+#[cfg(feature = "google-sheets")]
+fn encode_url_component(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }//end matching whether this byte needs encoding
+    }//end encoding each byte
+    encoded
+}//end encode_url_component(text)
+
+/// Gets every format the output can be written in.
+/// The sqlite sink is only included when built with the sqlite feature.
+pub fn get_sinks() -> Vec<Box<dyn OutputSink>> {
+    #[allow(unused_mut)]
+    let mut sinks: Vec<Box<dyn OutputSink>> = vec![Box::new(XlsxSink), Box::new(CsvSink), Box::new(CsvSectionsSink), Box::new(JsonSink)];
+    #[cfg(feature = "sqlite")]
+    sinks.push(Box::new(SqliteSink));
+    sinks
+}//end get_sinks()
+
+/// Gets the sinks which are enabled in config, in the order they should be written.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::io::sink::get_enabled_sinks;
+///
+/// let mut config = ConfigStore::default();
+/// config.json_output_enabled = true;
+/// let names: Vec<&str> = get_enabled_sinks(&config).iter().map(|sink| sink.name()).collect();
+/// assert_eq!(names, vec!["xlsx", "json"]);
+///
+/// config.output_format = usda_c_grain_sum::app::config_store::OutputFormat::XlsxAndCsvSections;
+/// let names: Vec<&str> = get_enabled_sinks(&config).iter().map(|sink| sink.name()).collect();
+/// assert_eq!(names, vec!["xlsx", "sectioned csv", "json"]);
+/// ```
+pub fn get_enabled_sinks(config: &ConfigStore) -> Vec<Box<dyn OutputSink>> {
+    get_sinks().into_iter().filter(|sink| sink.is_enabled(config)).collect()
+}//end get_enabled_sinks(config)
+
+/// Gets the indices of the columns in sheet_data which are visible in
+/// the column layout for sheet_name, in the order they're shown.
+fn get_visible_columns(sheet_data: &SampleOutput, sheet_name: &str, config: &ConfigStore) -> Vec<usize> {
+    let column_layout = config.output_column_layouts.iter().find(|layout| layout.sheet_name.eq(sheet_name));
+    let header_names: Vec<String> = sheet_data.columns.iter().map(|column| column.name.clone()).collect();
+    xlsx::get_column_order(&header_names, column_layout)
+        .into_iter().filter(|(_, is_visible)| *is_visible).map(|(col_idx, _)| col_idx).collect()
+}//end get_visible_columns(sheet_data, sheet_name, config)
+
+/// Gets output_sheets as json, with the visible columns of each sheet
+/// from the column layouts in config. Numbers are kept as numbers,
+/// rather than rounded, with the decimals and whether each column
+/// is a percent given with the column.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::io::sink::get_output_json;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, OutputRow, SampleOutput};
+///
+/// let sheet_data = SampleOutput {
+///     id_header: "external-sample-id".to_string(),
+///     columns: vec![OutputColumn::percent("%Sound", 1)],
+///     rows: vec![OutputRow::new("S1", vec![DataVal::Float(0.9)])],
+/// };
+/// let output_json = get_output_json(&vec![("Class_Percents".to_string(), sheet_data)], &ConfigStore::default());
+/// assert_eq!(output_json["sheets"][0]["name"], "Class_Percents");
+/// assert_eq!(output_json["sheets"][0]["columns"][0]["percent"], true);
+/// assert_eq!(output_json["sheets"][0]["rows"][0]["sample_id"], "S1");
+/// assert_eq!(output_json["sheets"][0]["rows"][0]["values"][0], 0.9);
+/// ```
+pub fn get_output_json(output_sheets: &Vec<(String, SampleOutput)>, config: &ConfigStore) -> Value {
+    let mut sheets_json = Vec::new();
+    for (sheet_name, sheet_data) in output_sheets.iter() {
+        let column_order = get_visible_columns(sheet_data, sheet_name, config);
+        let columns: Vec<Value> = column_order.iter()
+            .map(|col_idx| {
+                let column = &sheet_data.columns[*col_idx];
+                json!({"name": column.name, "decimals": column.precision, "percent": column.is_percent()})
+            }).collect();
+        let rows: Vec<Value> = sheet_data.rows.iter()
+            .map(|output_row| {
+                let values: Vec<Value> = column_order.iter().map(|col_idx| match output_row.cells.get(*col_idx) {
+                    Some(DataVal::Int(i)) => json!(i),
+                    Some(DataVal::Float(f)) => json!(f),
+                    Some(DataVal::String(s)) => json!(s),
+                    None => Value::Null,
+                }).collect();
+                json!({"sample_id": output_row.sample_id, "values": values})
+            }).collect();
+        sheets_json.push(json!({"name": sheet_name, "id_header": sheet_data.id_header, "columns": columns, "rows": rows}));
+    }//end getting the json of each sheet
+    json!({"sheets": sheets_json})
+}//end get_output_json(output_sheets, config)
@@ -0,0 +1,280 @@
+use std::{fs, path::Path, thread, time::{Duration, Instant, SystemTime}};
+
+use csv::StringRecord;
+
+use crate::{app::config_store::ConfigStore, io::{checksum::{self, RUN_INFO_SHEET_NAME}, schema::{self, ExportFileKind, InputFileKind}}, model::Data};
+
+/// The settings used while loading an input file, such as which row
+/// holds the headers. Each source only uses the settings for its format.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LoadOptions {
+    /// The 0-based index of the header row in csv and xlsx files.
+    pub header_row: usize,
+    /// Headers to use for files with no header row, or empty to read them from the file.
+    pub manual_headers: Vec<String>,
+    /// The number of rows at the end of csv and xlsx files to ignore.
+    pub footer_rows_to_skip: usize,
+    /// The xml tags to read into columns, starting with the sample id tag.
+    pub xml_tags_to_include: Vec<String>,
+    /// The xml tag which ends each sample.
+    pub xml_sample_closing_tag: String,
+}//end struct LoadOptions
+
+impl LoadOptions {
+    /// Gets the load options from the input settings in config.
+    pub fn from_config(config: &ConfigStore) -> LoadOptions {
+        let mut xml_tags_to_include = vec![config.xml_sample_id_header.clone()];
+        xml_tags_to_include.extend(config.xml_tags_to_include.iter().cloned());
+        LoadOptions {
+            header_row: config.csv_header_row,
+            manual_headers: config.csv_manual_headers.clone(),
+            footer_rows_to_skip: config.csv_footer_rows_to_skip,
+            xml_tags_to_include,
+            xml_sample_closing_tag: config.xml_sample_closing_tag.clone(),
+        }
+    }//end from_config(config)
+}//end impl LoadOptions
+
+/// A format of input file that can be loaded as Data.
+/// To support a new format, implement this trait for it, then add it
+/// to get_sources(), so it's found for any file of that kind.
+pub trait DataSource {
+    /// The kind of file this source reads.
+    fn file_kind(&self) -> InputFileKind;
+    /// The kind of export the loaded data is used as, which decides whether
+    /// it's processed as kernel data, like a csv, or sieve data, like an xml.
+    fn export_kind(&self) -> ExportFileKind;
+    /// Reads the file at path into Data, using the settings in options.
+    fn load(&self, path: &Path, options: &LoadOptions) -> Result<Data,String>;
+}//end trait DataSource
+
+/// Loads csv files exported from the C-Grain.
+pub struct CsvSource;
+
+impl DataSource for CsvSource {
+    fn file_kind(&self) -> InputFileKind {InputFileKind::Csv}
+    fn export_kind(&self) -> ExportFileKind {ExportFileKind::Csv}
+
+    /// Reads the csv file at path, skipping any rows before options.header_row.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::io::source::{CsvSource, DataSource, LoadOptions};
+    ///
+    /// let path = std::env::temp_dir().join("c_grain_sum_csv_source_doctest.csv");
+    /// std::fs::write(&path, "external-sample-id,Area\nS1,12.5\nS2,3\n").unwrap();
+    /// let data = CsvSource.load(&path, &LoadOptions::default()).unwrap();
+    /// assert_eq!(data.get_headers(), vec!["external-sample-id", "Area"]);
+    /// assert_eq!(data.get_records().len(), 2);
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn load(&self, path: &Path, options: &LoadOptions) -> Result<Data,String> {
+        let reader = match csv::ReaderBuilder::new().flexible(true).from_path(path) {
+            Ok(reader) => reader,
+            Err(error) => return Err(format!("Couldn't get csv reader.\n{}", error)),
+        };
+        match Data::from_csv_reader(reader, options.header_row, &options.manual_headers, options.footer_rows_to_skip) {
+            Some(data) => Ok(data),
+            None => Err(format!("Couldn't find a header row at row index {} in the csv file.", options.header_row)),
+        }//end matching whether we could read the csv data
+    }//end load(self, path, options)
+}//end impl DataSource for CsvSource
+
+/// Loads xml files exported from the C-Grain.
+pub struct XmlSource;
+
+impl DataSource for XmlSource {
+    fn file_kind(&self) -> InputFileKind {InputFileKind::Xml}
+    fn export_kind(&self) -> ExportFileKind {ExportFileKind::Xml}
+
+    /// Reads the tags in options.xml_tags_to_include from the xml file at
+    /// path, with a row for each options.xml_sample_closing_tag.
+    fn load(&self, path: &Path, options: &LoadOptions) -> Result<Data,String> {
+        let reader = match quick_xml::Reader::from_file(path) {
+            Ok(reader) => reader,
+            Err(error) => return Err(format!("Error occured when trying to open xml file:\n{:?}", error)),
+        };
+        match Data::from_xml_reader(reader, Some(options.xml_tags_to_include.clone()), Some(options.xml_sample_closing_tag.as_bytes())) {
+            Ok(data) => Ok(data),
+            Err(msg) => Err(format!("Encountered an error while trying to parse xml data.\n{}", msg)),
+        }//end matching whether we can parse xml data
+    }//end load(self, path, options)
+}//end impl DataSource for XmlSource
+
+/// Loads kernel data which was saved as an excel workbook, such as
+/// after a csv export was opened and saved in excel.
+pub struct XlsxSource;
+
+impl DataSource for XlsxSource {
+    fn file_kind(&self) -> InputFileKind {InputFileKind::Xlsx}
+    fn export_kind(&self) -> ExportFileKind {ExportFileKind::Csv}
+
+    /// Reads the first sheet of the workbook at path, other than a Run_Info
+    /// sheet, the same way as a csv file, with Data::from_string_records(),
+    /// so a header row which looks like data and footer rows are handled the
+    /// same way. Empty cells are read as empty text. options.header_row
+    /// counts every row of the sheet, including empty ones, like the row
+    /// numbers shown in excel, and rows after the header row which are
+    /// entirely empty are ignored.  
+    /// Each row is as wide as the header row, or manual_headers, so cells
+    /// past the last header are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::io::source::{DataSource, LoadOptions, XlsxSource};
+    ///
+    /// let mut workbook = rust_xlsxwriter::Workbook::new();
+    /// let sheet = workbook.add_worksheet();
+    /// sheet.write(0, 0, "external-sample-id").unwrap();
+    /// sheet.write(0, 1, "Area").unwrap();
+    /// sheet.write(1, 0, "S1").unwrap();
+    /// sheet.write(1, 1, 12.5).unwrap();
+    /// let path = std::env::temp_dir().join("c_grain_sum_xlsx_source_doctest.xlsx");
+    /// workbook.save(&path).unwrap();
+    ///
+    /// let data = XlsxSource.load(&path, &LoadOptions::default()).unwrap();
+    /// assert_eq!(data.get_headers(), vec!["external-sample-id", "Area"]);
+    /// assert_eq!(data.get_record(0, 1).unwrap().get_data().to_string(), "12.5");
+    ///
+    /// // a title and an empty row above the headers
+    /// let mut workbook = rust_xlsxwriter::Workbook::new();
+    /// let sheet = workbook.add_worksheet();
+    /// sheet.write(0, 0, "C-Grain Export").unwrap();
+    /// sheet.write(2, 0, "external-sample-id").unwrap();
+    /// sheet.write(4, 0, "S1").unwrap();
+    /// workbook.save(&path).unwrap();
+    ///
+    /// let options = LoadOptions { header_row: 2, ..LoadOptions::default() };
+    /// let data = XlsxSource.load(&path, &options).unwrap();
+    /// assert_eq!(data.get_headers(), vec!["external-sample-id"]);
+    /// assert_eq!(data.get_records().len(), 1);
+    /// assert_eq!(data.get_record(0, 0).unwrap().get_data().to_string(), "S1");
+    ///
+    /// // a stray cell far from the rest is left out, rather than filling in every cell up to it
+    /// let mut workbook = rust_xlsxwriter::Workbook::new();
+    /// let sheet = workbook.add_worksheet();
+    /// sheet.write(0, 0, "external-sample-id").unwrap();
+    /// sheet.write(1, 0, "S1").unwrap();
+    /// sheet.write(1048575, 16383, "stray").unwrap();
+    /// workbook.save(&path).unwrap();
+    /// let data = XlsxSource.load(&path, &LoadOptions::default()).unwrap();
+    /// assert_eq!(data.get_headers(), vec!["external-sample-id"]);
+    /// assert_eq!(data.get_records().len(), 1);
+    ///
+    /// // without a header row, the first row is read as data
+    /// let mut workbook = rust_xlsxwriter::Workbook::new();
+    /// let sheet = workbook.add_worksheet();
+    /// sheet.write(0, 0, 1.5).unwrap();
+    /// sheet.write(0, 1, 2.5).unwrap();
+    /// workbook.save(&path).unwrap();
+    /// let data = XlsxSource.load(&path, &LoadOptions::default()).unwrap();
+    /// assert!(data.has_generated_headers());
+    /// assert_eq!(data.get_headers(), vec!["Column 1", "Column 2"]);
+    /// assert_eq!(data.get_records().len(), 1);
+    /// std::fs::remove_file(&path).unwrap();
+    /// ```
+    fn load(&self, path: &Path, options: &LoadOptions) -> Result<Data,String> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(error) => return Err(format!("Couldn't read the workbook at {}.\n{}", path.to_string_lossy(), error)),
+        };
+        let workbook_cells = checksum::read_workbook_cells(&bytes)?;
+        let sheet_cells = match workbook_cells.into_iter().find(|(sheet_name, _)| sheet_name != RUN_INFO_SHEET_NAME) {
+            Some((_, sheet_cells)) => sheet_cells,
+            None => return Err(String::from("The workbook doesn't have any sheets with data.")),
+        };
+        let header_row = u32::try_from(options.header_row).unwrap_or(u32::MAX);
+        let no_header_row_msg = format!("Couldn't find a header row at row index {} in the workbook.", options.header_row);
+        let (width, first_data_row) = match options.manual_headers.len() {
+            0 => match checksum::get_row_width(&sheet_cells, header_row) {
+                0 => return Err(no_header_row_msg),
+                width => (width, header_row.saturating_add(1)),
+            },
+            manual_header_count => (manual_header_count, header_row),
+        };
+
+        // the header row is found by its row in the sheet, then empty rows after it are dropped
+        let records: Vec<Result<StringRecord, csv::Error>> = checksum::get_sheet_rows(sheet_cells, width).into_iter()
+            .filter(|(row, cells)| (*row == header_row && options.manual_headers.is_empty()) || (*row >= first_data_row && cells.iter().any(|cell| !cell.trim().is_empty())))
+            .map(|(_, cells)| Ok(StringRecord::from(cells)))
+            .collect();
+        Data::from_string_records(records, 0, &options.manual_headers, options.footer_rows_to_skip).ok_or(no_header_row_msg)
+    }//end load(self, path, options)
+}//end impl DataSource for XlsxSource
+
+/// How long wait_for_settled_file() waits for a file to stop changing
+/// before giving up, such as when an export has stalled.
+pub const SETTLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tells whether the file at path was last changed at least settle_time
+/// ago, so it's not still being written, such as while the C-Grain is
+/// exporting it. Files whose last change can't be read are treated as settled,
+/// so they fail later with a clearer error when they're loaded.
+pub fn is_file_settled(path: &Path, settle_time: Duration) -> bool {
+    match fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(modified) => SystemTime::now().duration_since(modified).map(|age| age >= settle_time).unwrap_or(false),
+        Err(_) => true,
+    }//end matching whether we know when the file was last changed
+}//end is_file_settled(path, settle_time)
+
+/// Waits until the file at path is settled, so that a file which is still
+/// being written isn't loaded as a truncated dataset. If the file was
+/// changed recently, its size is checked every quarter second until it
+/// stays the same for settle_time.  
+/// Returns whether we had to wait, or an error if the file was still
+/// changing after timeout, or couldn't be read.
+pub fn wait_for_settled_file(path: &Path, settle_time: Duration, timeout: Duration) -> Result<bool,String> {
+    if is_file_settled(path, settle_time) {return Ok(false);}
+    let get_size = || fs::metadata(path).map(|metadata| metadata.len())
+        .map_err(|error| format!("Couldn't check whether {} is still being written.\n{}", path.to_string_lossy(), error));
+    let wait_start = Instant::now();
+    let mut last_size = get_size()?;
+    let mut last_change = Instant::now();
+    loop {
+        thread::sleep(Duration::from_millis(250));
+        let size = get_size()?;
+        if size != last_size {
+            last_size = size;
+            last_change = Instant::now();
+        } else if last_change.elapsed() >= settle_time {return Ok(true);}
+        if wait_start.elapsed() >= timeout {
+            return Err(format!("{} was still being written after waiting {} seconds, so it wasn't loaded. Please try again once the export has finished.", path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(), timeout.as_secs()));
+        }//end if we've waited too long
+    }//end checking the size of the file until it stops changing
+}//end wait_for_settled_file(path, settle_time, timeout)
+
+/// Gets every format of input file that can be loaded.
+pub fn get_sources() -> Vec<Box<dyn DataSource>> {
+    vec![Box::new(CsvSource), Box::new(XmlSource), Box::new(XlsxSource)]
+}//end get_sources()
+
+/// Finds the source which can load the file at path, using the contents
+/// of the file to tell its format, or the extension if that isn't clear.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::io::schema::{ExportFileKind, InputFileKind};
+/// use usda_c_grain_sum::io::source::find_source;
+///
+/// let path = std::env::temp_dir().join("c_grain_sum_find_source_doctest.txt");
+/// std::fs::write(&path, "<Samples><Sample></Sample></Samples>").unwrap();
+/// let source = find_source(&path).unwrap();
+/// assert_eq!(source.file_kind(), InputFileKind::Xml);
+/// assert_eq!(source.export_kind(), ExportFileKind::Xml);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn find_source(path: &Path) -> Result<Box<dyn DataSource>,String> {
+    let file_kind = schema::sniff_input_file_kind(path)?;
+    match get_sources().into_iter().find(|source| source.file_kind() == file_kind) {
+        Some(source) => Ok(source),
+        None => Err(format!("{:?} files can't be loaded by this version.", file_kind)),
+    }//end matching whether any source reads this kind of file
+}//end find_source(path)
+
+/// Loads the file at path with whichever source reads its format.
+pub fn load_data_file(path: &Path, options: &LoadOptions) -> Result<Data,String> {
+    find_source(path)?.load(path, options)
+}//end load_data_file(path, options)
@@ -0,0 +1,626 @@
+use std::path::{Path, PathBuf};
+
+use rust_xlsxwriter::{utility, Chart, ChartType, Format, FormatAlign, Formula, Note, ProtectionOptions, Url, Workbook, Worksheet, XlsxError};
+
+use crate::{app::{config_store::{ConfigStore, OutputColumnLayout, SampleIdAnonymization}, process::{fill_header_template, get_class_filtered_records, get_column_groups}}, io::checksum::{self, RUN_INFO_SHEET_NAME}, model::{Data, DataVal, OutputColumn, SampleOutput}, pipeline::{DerivedColumn, Operand}};
+
+/// Creates an excel workbook, which can then be used in
+/// further funtions.
+pub fn get_workbook() -> Workbook {
+    Workbook::new()
+}//end get_workbook()
+
+/// Should be called after done working with a workbook, for performance reasons.
+pub fn close_workbook(workbook: &mut Workbook, output_path: &PathBuf) -> Result<(),XlsxError> {
+    workbook.save(output_path)?;
+    Ok(())
+}//end close_workbook(workbook)
+
+/// Gets the contents of workbook as the bytes of an xlsx file, instead of
+/// saving it to disk, such as for sending it somewhere else.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::io::xlsx;
+/// 
+/// let mut workbook = xlsx::get_workbook();
+/// workbook.add_worksheet().write(0, 0, "Sample").unwrap();
+/// let bytes = xlsx::workbook_to_bytes(&mut workbook).unwrap();
+/// // xlsx files are zip archives, which start with PK
+/// assert_eq!(&bytes[0..2], b"PK");
+/// ```
+pub fn workbook_to_bytes(workbook: &mut Workbook) -> Result<Vec<u8>,XlsxError> {
+    workbook.save_to_buffer()
+}//end workbook_to_bytes(workbook)
+
+/// Reads the sheets of a summary workbook written by this program back
+/// into SampleOutput, as (sheet name, sheet data), leaving out the Run
+/// Info sheet, so an old summary can be looked at or written in another format.  
+/// The first row of each sheet is read as the headers, and the first
+/// column as the sample ids. Sheets with grouped columns, which have two
+/// rows of headers, are told apart by the cell under the sample id header
+/// being empty, and their columns are read back with their groups.
+/// Cells past the last header, and rows which are entirely empty, are left out.  
+/// Numbers are read back as numbers, with the
+/// most decimal places written in their column, up to 4. Number formats
+/// aren't read, so percents are read as fractions, such as 0.9 for 90%.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::io::xlsx;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+/// 
+/// let mut sheet_data = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Area", 2))
+///     .with_column(OutputColumn::number("Notes", 0));
+/// sheet_data.add_row("S1", vec![DataVal::Float(12.25), DataVal::String("ok".to_string())]);
+/// let mut workbook = xlsx::get_workbook();
+/// xlsx::write_output_to_sheet(&mut workbook, &sheet_data, "CSV_Stats", &ConfigStore::default()).unwrap();
+/// let path = std::env::temp_dir().join("c_grain_sum_read_summary_doctest.xlsx");
+/// xlsx::close_workbook(&mut workbook, &path).unwrap();
+/// 
+/// let sheets = xlsx::read_summary_workbook(&path).unwrap();
+/// assert_eq!(sheets, vec![("CSV_Stats".to_string(), sheet_data)]);
+/// 
+/// // grouped columns are written under a second row of headers, and read back with their groups
+/// let mut grouped_data = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Length", 2).with_group("Length (mm)"))
+///     .with_column(OutputColumn::number("Std Length", 2).with_group("Length (mm)"))
+///     .with_column(OutputColumn::number("Notes", 0));
+/// grouped_data.add_row("S1", vec![DataVal::Float(6.25), DataVal::Float(0.25), DataVal::String("ok".to_string())]);
+/// let mut workbook = xlsx::get_workbook();
+/// xlsx::write_output_to_sheet(&mut workbook, &grouped_data, "CSV_Stats", &ConfigStore::default()).unwrap();
+/// xlsx::close_workbook(&mut workbook, &path).unwrap();
+/// assert_eq!(xlsx::read_summary_workbook(&path).unwrap(), vec![("CSV_Stats".to_string(), grouped_data)]);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn read_summary_workbook(path: &Path) -> Result<Vec<(String, SampleOutput)>,String> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => return Err(format!("Couldn't read the workbook at {}.\n{}", path.to_string_lossy(), error)),
+    };
+    let mut sheets = Vec::new();
+    for (sheet_name, sheet_cells) in checksum::read_workbook_cells(&bytes)? {
+        if sheet_name == RUN_INFO_SHEET_NAME {continue;}
+        // rows are only as wide as the headers, so a stray cell far away doesn't widen every row
+        let col_count = checksum::get_row_width(&sheet_cells, 0).max(checksum::get_row_width(&sheet_cells, 1));
+        if col_count == 0 {continue;}
+        let sheet_rows = checksum::get_sheet_rows(sheet_cells, col_count);
+        let empty_row = vec![String::new(); col_count];
+        let get_row = |row_idx: u32| sheet_rows.iter().find(|(row, _)| *row == row_idx).map(|(_, cells)| cells).unwrap_or(&empty_row);
+        let grid = [get_row(0), get_row(1)];
+
+        let mut sheet_data = SampleOutput::new(&grid[0][0]);
+        // the sample id header is merged down across both rows of headers when columns are grouped
+        let is_grouped = sheet_rows.iter().any(|(row, _)| *row > 0) && grid[1][0].is_empty() && !grid[0][0].is_empty();
+        let mut current_group: Option<String> = None;
+        for (top_cell, bottom_cell) in grid[0].iter().zip(grid[1].iter()).skip(1) {
+            match (is_grouped, top_cell.is_empty(), bottom_cell.is_empty()) {
+                (false, _, _) => sheet_data.add_column(OutputColumn::number(top_cell, 0)),
+                // a header merged across both rows, so it isn't in a group
+                (true, false, true) => {
+                    current_group = None;
+                    sheet_data.add_column(OutputColumn::number(top_cell, 0));
+                },
+                // the first column of a group, or a column in the same group as the one before
+                (true, group_cell_empty, _) => {
+                    if !group_cell_empty {current_group = Some(top_cell.clone());}
+                    let column = OutputColumn::number(bottom_cell, 0);
+                    match &current_group {
+                        Some(group) => sheet_data.add_column(column.with_group(group)),
+                        None => sheet_data.add_column(column),
+                    }//end matching whether there's a group to put the column in
+                },
+            }//end matching how the header of this column was written
+        }//end reading the header of each column
+        let header_depth = if is_grouped {2} else {1};
+        for (_, row) in sheet_rows.iter().filter(|(row, cells)| *row >= header_depth && cells.iter().any(|cell| !cell.is_empty())) {
+            let mut data_cells = Vec::new();
+            for (col_offset, value) in row.iter().skip(1).enumerate() {
+                match value.parse::<f64>() {
+                    Ok(number) => {
+                        let decimals = value.split_once('.').map(|(_, fraction)| fraction.len().min(4)).unwrap_or(0);
+                        sheet_data.columns[col_offset].precision = sheet_data.columns[col_offset].precision.max(decimals);
+                        data_cells.push(DataVal::Float(number));
+                    },
+                    Err(_) => data_cells.push(DataVal::String(value.clone())),
+                }//end matching whether this cell is a number
+            }//end reading each cell after the sample id
+            sheet_data.add_row(&row[0], data_cells);
+        }//end reading each row after the headers
+        sheets.push((sheet_name, sheet_data));
+    }//end reading each sheet
+    Ok(sheets)
+}//end read_summary_workbook(path)
+
+/// Writes output from another function to a workbook that has already
+/// been created. After you're done calling this function (however many times),  
+/// make sure to call close_workbook().  
+/// If the config has an OutputColumnLayout for sheet_name, then it is used
+/// to determine the order of the columns in the sheet, which columns are
+/// left out, and whether the sheet is transposed so that samples are columns.  
+/// If charts are enabled in the config, charts are added next to the
+/// data, as explained in insert_output_charts().
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::io::Read;
+/// use usda_c_grain_sum::app::config_store::ConfigStore;
+/// use usda_c_grain_sum::io::xlsx;
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+/// 
+/// let mut sheet_data = SampleOutput::new("external-sample-id")
+///     .with_column(OutputColumn::number("Avg Area", 2))
+///     .with_column(OutputColumn::number("Avg Weight", 2));
+/// sheet_data.add_row("S1", vec![DataVal::Float(12.25), DataVal::Float(0.03)]);
+/// sheet_data.add_row("S2", vec![DataVal::Float(13.5), DataVal::Float(0.04)]);
+/// let mut config = ConfigStore::default();
+/// config.output_charts_enabled = true;
+/// let mut workbook = xlsx::get_workbook();
+/// xlsx::write_output_to_sheet(&mut workbook, &sheet_data, "CSV_Stats", &config).unwrap();
+/// let bytes = xlsx::workbook_to_bytes(&mut workbook).unwrap();
+/// 
+/// // the scatter of Avg Area vs Avg Weight is written as its own part of the xlsx file
+/// let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+/// let mut chart_xml = String::new();
+/// archive.by_name("xl/charts/chart1.xml").unwrap().read_to_string(&mut chart_xml).unwrap();
+/// assert!(chart_xml.contains("<c:scatterChart>"));
+/// assert!(chart_xml.contains("CSV_Stats!$B$2:$B$3"));
+/// assert!(chart_xml.contains("CSV_Stats!$C$2:$C$3"));
+/// ```
+pub fn write_output_to_sheet(workbook: &mut Workbook, sheet_data: &SampleOutput, sheet_name: &str, config: &ConfigStore) -> Result<(),XlsxError> {
+    let sheet = workbook.add_worksheet();//workbook.create_sheet(sheet_name);
+    sheet.set_name(sheet_name)?;
+
+    // figure out which columns to write, in which order
+    let column_layout = config.output_column_layouts.iter().find(|layout| layout.sheet_name.eq(sheet_name));
+    let transposed = column_layout.map(|layout| layout.transposed).unwrap_or(false);
+    let header_names: Vec<String> = sheet_data.columns.iter().map(|column| column.name.clone()).collect();
+    let column_order: Vec<usize> = get_column_order(&header_names, column_layout)
+        .into_iter().filter(|(_, is_visible)| *is_visible).map(|(col_idx, _)| col_idx).collect();
+
+    // create formats for each header row
+    let base_format = get_base_format(config);
+    let mut formats = Vec::new();
+    for column in sheet_data.columns.iter() {
+        let num_format = match column.is_text() {
+            true => String::from("@"),
+            false => get_num_format(column.precision, column.is_percent()),
+        };
+        let this_format = base_format.clone().set_num_format(num_format);
+        formats.push(this_format);
+    }//end creating format for each header
+    let default_format = base_format.clone().set_num_format(get_num_format(2, false));
+    // the calculation of each formula column, or None for other columns
+    let formulas: Vec<Option<DerivedColumn>> = sheet_data.columns.iter()
+        .map(|column| column.formula.as_ref().and_then(|formula| DerivedColumn::parse(&format!("{} = {}", column.name, formula)).ok()))
+        .collect();
+
+    // the group of each column written, in order, which needs a second tier of headers
+    let all_groups = get_column_groups(sheet_data, &config.output_column_groups);
+    let column_groups: Vec<Option<&String>> = column_order.iter().map(|col_idx| all_groups.get(*col_idx).and_then(|group| group.as_ref())).collect();
+    let header_depth: u32 = match column_groups.iter().any(|group| group.is_some()) {
+        true => 2,
+        false => 1,
+    };
+
+    // write the header row (or column, if transposed)
+    let bold = base_format.clone().set_bold();
+    write_output_headers(sheet, sheet_data, &column_order, &column_groups, transposed, &bold)?;
+    if transposed {
+        for (index, output_row) in sheet_data.rows.iter().enumerate() {
+            write_sample_id(sheet, 0, (header_depth as usize + index) as u16, &output_row.sample_id, &bold, config)?;
+        }//end adding sample ids as column headers
+    }//end if sample ids are written as column headers
+
+    for (sample_offset, output_row) in sheet_data.rows.iter().enumerate() {
+        if !transposed {write_sample_id(sheet, header_depth + sample_offset as u32, 0, &output_row.sample_id, &base_format, config)?;}
+        for (col_offset, col_idx) in column_order.iter().enumerate() {
+            let data_cell = match output_row.cells.get(*col_idx) {
+                Some(data_cell) => data_cell,
+                None => continue,
+            };
+            let format = formats.get(*col_idx).unwrap_or(&default_format);
+            let (row_num, col_num) = match transposed {
+                false => (header_depth + sample_offset as u32, 1 + col_offset as u16),
+                true => (1 + col_offset as u32, (header_depth as usize + sample_offset) as u16),
+            };
+            let excel_formula = formulas.get(*col_idx).and_then(|derived| derived.as_ref())
+                .and_then(|derived| get_excel_formula(derived, sheet_data, &column_order, row_num, col_num, transposed));
+            match (excel_formula, data_cell) {
+                (Some(excel_formula), _) => sheet.write_formula_with_format(row_num,col_num,Formula::new(excel_formula).set_result(data_cell.to_string()), format)?,
+                (None, DataVal::Float(f)) => sheet.write_number_with_format(row_num,col_num,*f, format)?,
+                (None, DataVal::Int(i)) => sheet.write_number_with_format(row_num,col_num,*i as f64, format)?,
+                (None, DataVal::String(s)) => sheet.write_with_format(row_num,col_num,s,&base_format)?,
+            };
+            if let Some(note) = output_row.get_note(*col_idx) {
+                sheet.insert_note(row_num, col_num, &Note::new(note))?;
+            }//end if this cell has a note to show as a comment
+        }//end adding each data cell to output
+    }//end looping over each line of data to write
+
+    if config.sheet_protection_enabled {
+        let (first_row, last_row, last_col) = match transposed {
+            false => (header_depth - 1, sheet_data.rows.len() as u32 + header_depth - 1, column_order.len() as u16),
+            true => (0, column_order.len() as u32, (sheet_data.rows.len() as u32 + header_depth - 1) as u16),
+        };
+        protect_sheet(sheet, first_row, last_row, last_col, config)?;
+    }//end if we should protect the sheet
+
+    if config.output_charts_enabled && sheet_data.rows.len() > 0 {
+        insert_output_charts(sheet, sheet_data, sheet_name, &column_order, transposed, header_depth, config)?;
+    }//end if we should add charts
+
+    Ok(())
+}//end write_output_to_sheet()
+
+/// Writes the header of the sample ids and the headers of the columns in
+/// column_order to sheet, along the first row, or down the first column
+/// if transposed. If any column has a group in column_groups, which has the
+/// group of each column in column_order, the headers take two tiers, with
+/// the header of each group merged across its columns above their headers,
+/// and the headers of columns without a group merged across both tiers.
+fn write_output_headers(sheet: &mut Worksheet, sheet_data: &SampleOutput, column_order: &Vec<usize>, column_groups: &Vec<Option<&String>>, transposed: bool, bold: &Format) -> Result<(),XlsxError> {
+    // the cell at position along the headers, in the tier of headers counting from the top, or the left if transposed
+    let get_header_cell = |tier: u32, position: usize| -> (u32, u16) {
+        match transposed {
+            false => (tier, position as u16),
+            true => (position as u32, tier as u16),
+        }//end matching whether headers go down the first column
+    };
+    let is_grouped = column_groups.iter().any(|group| group.is_some());
+    if !is_grouped {
+        sheet.write_with_format(0,0,&sheet_data.id_header, bold)?;
+        for (index,col_idx) in column_order.iter().enumerate() {
+            let (row, col) = get_header_cell(0, index + 1);
+            sheet.write_with_format(row,col,&sheet_data.columns[*col_idx].name,bold)?;
+        }//end adding each header
+        return Ok(());
+    }//end if there's only one tier of headers
+
+    let (id_last_row, id_last_col) = get_header_cell(1, 0);
+    sheet.merge_range(0, 0, id_last_row, id_last_col, &sheet_data.id_header, bold)?;
+    let group_format = bold.clone().set_align(FormatAlign::Center);
+    let mut index = 0;
+    while index < column_order.len() {
+        let position = index + 1;
+        match column_groups[index] {
+            Some(group) => {
+                // the columns of a group are only merged while they're next to each other
+                let group_len = column_groups[index..].iter().take_while(|other_group| **other_group == Some(group)).count();
+                let (first_row, first_col) = get_header_cell(0, position);
+                let (last_row, last_col) = get_header_cell(0, position + group_len - 1);
+                match group_len {
+                    1 => sheet.write_with_format(first_row, first_col, group, &group_format)?,
+                    _ => sheet.merge_range(first_row, first_col, last_row, last_col, group, &group_format)?,
+                };
+                for group_offset in 0..group_len {
+                    let (row, col) = get_header_cell(1, position + group_offset);
+                    sheet.write_with_format(row, col, &sheet_data.columns[column_order[index + group_offset]].name, bold)?;
+                }//end adding the header of each column in the group
+                index += group_len;
+            },
+            None => {
+                let (first_row, first_col) = get_header_cell(0, position);
+                let (last_row, last_col) = get_header_cell(1, position);
+                sheet.merge_range(first_row, first_col, last_row, last_col, &sheet_data.columns[column_order[index]].name, bold)?;
+                index += 1;
+            },
+        }//end matching whether this column is in a group
+    }//end adding the headers of each column or group
+    Ok(())
+}//end write_output_headers(sheet, sheet_data, column_order, column_groups, transposed, bold)
+
+/// Adds charts of the output in sheet_data to sheet, placed to the right
+/// of the data, or below it if the sheet is transposed.  
+/// Class_Percents sheets get a stacked column chart of the class percents of
+/// each sample, leaving out the totals of parent classes, and CSV_Stats sheets get a scatter of the average Area
+/// against the average Weight, using the avg header template in config.
+/// Columns hidden by the column layout are left out of the charts, and a
+/// chart is skipped if none of its columns are written.  
+/// header_depth is the number of rows, or columns if transposed, taken
+/// by the headers, which is 2 when columns are grouped.
+fn insert_output_charts(sheet: &mut Worksheet, sheet_data: &SampleOutput, sheet_name: &str, column_order: &Vec<usize>, transposed: bool, header_depth: u32, config: &ConfigStore) -> Result<(),XlsxError> {
+    let sample_count = sheet_data.rows.len() as u32;
+    let (first_sample, last_sample) = (header_depth, header_depth + sample_count - 1);
+    // the cells holding the sample ids, as (first row, first col, last row, last col)
+    let id_range = match transposed {
+        false => (first_sample, 0, last_sample, 0),
+        true => (0, first_sample as u16, 0, last_sample as u16),
+    };
+    // the cells holding the values of the column with col_idx, and the cell holding its header
+    let get_column_cells = |col_idx: usize| -> Option<((u32, u16, u32, u16), (u32, u16))> {
+        let position = column_order.iter().position(|idx| *idx == col_idx)? + 1;
+        match transposed {
+            false => Some(((first_sample, position as u16, last_sample, position as u16), (header_depth - 1, position as u16))),
+            true => Some(((position as u32, first_sample as u16, position as u32, last_sample as u16), (position as u32, (header_depth - 1) as u16))),
+        }//end matching whether samples are rows or columns
+    };
+    // charts go past the last row or column of data
+    let (chart_row, chart_col) = match transposed {
+        false => (1, column_order.len() as u16 + 2),
+        true => (column_order.len() as u32 + 2, 0),
+    };
+
+    let mut output_chart = None;
+    if sheet_name.starts_with("Class_Percents") {
+        let percent_cells: Vec<_> = (0..sheet_data.columns.len())
+            .filter(|col_idx| sheet_data.columns[*col_idx].is_percent() && !sheet_data.columns[*col_idx].is_total())
+            .filter_map(&get_column_cells)
+            .collect();
+        if percent_cells.len() > 0 {
+            let mut chart = Chart::new(ChartType::ColumnStacked);
+            for ((first_row, first_col, last_row, last_col), (name_row, name_col)) in percent_cells {
+                chart.add_series()
+                    .set_categories((sheet_name, id_range.0, id_range.1, id_range.2, id_range.3))
+                    .set_values((sheet_name, first_row, first_col, last_row, last_col))
+                    .set_name((sheet_name, name_row, name_col));
+            }//end adding a series for each class
+            chart.title().set_name("Class Percents by Sample");
+            chart.x_axis().set_name(&sheet_data.id_header);
+            chart.y_axis().set_name("Percent of Kernels").set_num_format("0%");
+            // make room for a bar for each sample, within what fits on a screen
+            chart.set_width((sample_count * 24).clamp(480, 1600));
+            output_chart = Some(chart);
+        }//end if there are class percents to chart
+    }//end if this is a class percents sheet
+    else if sheet_name.starts_with("CSV_Stats") {
+        let area_header = fill_header_template(&config.csv_stat_avg_header_template, "{col}", "Area");
+        let weight_header = fill_header_template(&config.csv_stat_avg_header_template, "{col}", "Weight");
+        let area_cells = sheet_data.get_column_index(&area_header).and_then(&get_column_cells);
+        let weight_cells = sheet_data.get_column_index(&weight_header).and_then(&get_column_cells);
+        if let (Some((area_range, _)), Some((weight_range, _))) = (area_cells, weight_cells) {
+            let mut chart = Chart::new(ChartType::Scatter);
+            chart.add_series()
+                .set_categories((sheet_name, area_range.0, area_range.1, area_range.2, area_range.3))
+                .set_values((sheet_name, weight_range.0, weight_range.1, weight_range.2, weight_range.3))
+                .set_name(format!("{} vs {}", area_header, weight_header).as_str());
+            chart.title().set_name(&format!("{} vs {}", weight_header, area_header));
+            chart.x_axis().set_name(&area_header);
+            chart.y_axis().set_name(&weight_header);
+            chart.legend().set_hidden();
+            output_chart = Some(chart);
+        }//end if both columns are written
+    }//end if this is a csv stats sheet
+
+    if let Some(chart) = output_chart {sheet.insert_chart(chart_row, chart_col, &chart)?;}
+    Ok(())
+}//end insert_output_charts(sheet, sheet_data, sheet_name, column_order, transposed, header_depth, config)
+
+/// Gets the link for sample_id from template, such as a folder of kernel
+/// images, by replacing {sample_id} in template with sample_id.
+/// Links which aren't web addresses are treated as file paths.  
+/// Returns None if template is empty.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::io::xlsx::get_sample_link;
+/// 
+/// assert_eq!(get_sample_link("D:\\Images\\{sample_id}", "S1"), Some("file:///D:\\Images\\S1".to_string()));
+/// assert_eq!(get_sample_link("https://lims.example.org/samples/{sample_id}", "S1"), Some("https://lims.example.org/samples/S1".to_string()));
+/// assert_eq!(get_sample_link(" ", "S1"), None);
+/// ```
+pub fn get_sample_link(template: &str, sample_id: &str) -> Option<String> {
+    if template.trim().is_empty() {return None;}
+    let link = fill_header_template(template.trim(), "{sample_id}", sample_id);
+    let is_address = ["http://", "https://", "ftp://", "mailto:", "file:///"].iter().any(|prefix| link.starts_with(prefix));
+    match is_address {
+        true => Some(link),
+        false => Some(format!("file:///{}", link)),
+    }//end matching whether link needs to be marked as a file
+}//end get_sample_link(template, sample_id)
+
+/// Writes sample_id to the cell at row and col, as a link to the address
+/// from config.sample_link_template if there is one.  
+/// Anonymized sample ids are never linked, since the link would give
+/// away the real sample id.
+fn write_sample_id(sheet: &mut Worksheet, row: u32, col: u16, sample_id: &str, format: &Format, config: &ConfigStore) -> Result<(),XlsxError> {
+    match get_sample_link(&config.sample_link_template, sample_id) {
+        Some(link) if config.sample_id_anonymization == SampleIdAnonymization::Off => {
+            sheet.write_url_with_format(row, col, Url::new(link).set_text(sample_id), format)?;
+        },
+        _ => {sheet.write_with_format(row, col, sample_id, format)?;},
+    }//end matching whether to link the sample id
+    Ok(())
+}//end write_sample_id(sheet, row, col, sample_id, format, config)
+
+/// Gets the excel formula for the formula column calculated by derived,
+/// in the cell at row_num and col_num, using the other cells of the same
+/// sample. column_order gives the index in sheet_data of each column
+/// written, in order.  
+/// Returns None if a column used by the formula isn't written, in which
+/// case the calculated value should be written instead.
+fn get_excel_formula(derived: &DerivedColumn, sheet_data: &SampleOutput, column_order: &Vec<usize>, row_num: u32, col_num: u16, transposed: bool) -> Option<String> {
+    let get_operand_text = |operand: &Operand| match operand {
+        Operand::Number(number) => Some(number.to_string()),
+        Operand::Column(column) => {
+            let col_idx = sheet_data.get_column_index(column)?;
+            let col_offset = column_order.iter().position(|order_idx| *order_idx == col_idx)?;
+            match transposed {
+                false => Some(utility::row_col_to_cell(row_num, 1 + col_offset as u16)),
+                true => Some(utility::row_col_to_cell(1 + col_offset as u32, col_num)),
+            }//end matching whether columns are written as rows
+        },
+    };
+    // empty cells and dividing by zero are left empty, like the calculated values
+    Some(format!("=IFERROR({}{}{},\"\")", get_operand_text(&derived.left)?, derived.op, get_operand_text(&derived.right)?))
+}//end get_excel_formula(derived, sheet_data, column_order, row_num, col_num, transposed)
+
+/// Writes a sheet describing how the output was made, such as the
+/// program version and the signature of the output values, with a
+/// row for each (label, value) in run_info.  
+/// This should be the last sheet written, since its signature is
+/// calculated from the sheets written before it.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::{app::config_store::ConfigStore, io::{checksum, xlsx}};
+/// 
+/// let mut workbook = xlsx::get_workbook();
+/// workbook.add_worksheet().write(0, 0, 7.5).unwrap();
+/// let output_signature = checksum::get_workbook_signature(&workbook.save_to_buffer().unwrap(), b"signing key").unwrap();
+/// let run_info = vec![
+///     ("Program Version".to_string(), "0.4.3".to_string()),
+///     (checksum::SIGNATURE_LABEL.to_string(), output_signature.clone()),
+/// ];
+/// xlsx::write_run_info_sheet(&mut workbook, &run_info, &ConfigStore::default()).unwrap();
+/// let path = std::env::temp_dir().join("c_grain_sum_run_info_doctest.xlsx");
+/// xlsx::close_workbook(&mut workbook, &path).unwrap();
+/// 
+/// let (stored, current) = checksum::verify_workbook_signature(&path, b"signing key").unwrap();
+/// assert_eq!(stored, output_signature);
+/// assert_eq!(stored, current);
+/// let (stored, current) = checksum::verify_workbook_signature(&path, b"another key").unwrap();
+/// assert_ne!(stored, current);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub fn write_run_info_sheet(workbook: &mut Workbook, run_info: &Vec<(String, String)>, config: &ConfigStore) -> Result<(),XlsxError> {
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(RUN_INFO_SHEET_NAME)?;
+    let base_format = get_base_format(config);
+    let bold = base_format.clone().set_bold();
+    for (row_idx, (label, value)) in run_info.iter().enumerate() {
+        sheet.write_with_format(row_idx as u32, 0, label, &bold)?;
+        sheet.write_with_format(row_idx as u32, 1, value, &base_format)?;
+    }//end writing each row of run info
+    sheet.set_column_width(0, 20)?;
+    sheet.set_column_width(1, 30)?;
+    if config.sheet_protection_enabled {sheet.protect_with_options(&ProtectionOptions::new());}
+    Ok(())
+}//end write_run_info_sheet(workbook, run_info, config)
+
+/// Locks every cell in sheet, using the password from config if there is one,
+/// while still letting recipients sort and filter the cells from first_row,
+/// the row of headers the filters go on, to last_row and last_col.
+fn protect_sheet(sheet: &mut Worksheet, first_row: u32, last_row: u32, last_col: u16, config: &ConfigStore) -> Result<(),XlsxError> {
+    sheet.autofilter(first_row, 0, last_row, last_col)?;
+    let mut protection_options = ProtectionOptions::new();
+    protection_options.sort = true;
+    protection_options.use_autofilter = true;
+    sheet.protect_with_options(&protection_options);
+    if !config.sheet_protection_password.is_empty() {sheet.protect_with_password(&config.sheet_protection_password);}
+    Ok(())
+}//end protect_sheet(sheet, first_row, last_row, last_col, config)
+
+/// The most rows that an excel worksheet can hold.
+const MAX_SHEET_ROWS: usize = 1_048_576;
+
+/// Writes a copy of the csv rows in data which pass the class filters
+/// in config to a new sheet called sheet_name, so that the output
+/// workbook can be archived without the input file.  
+/// The sheet has the same headers as data, with numbers written as numbers.
+pub fn write_input_snapshot_sheet(workbook: &mut Workbook, data: &Data, sheet_name: &str, config: &ConfigStore) -> Result<(),String> {
+    let filtered_data = get_class_filtered_records(data, config)?;
+    if filtered_data.len() + 1 > MAX_SHEET_ROWS {return Err(format!("There are {} filtered input rows, which is more than a sheet can hold.", filtered_data.len()));}
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name).map_err(|error| error.to_string())?;
+
+    let base_format = get_base_format(config);
+    let bold = base_format.clone().set_bold();
+    for (col_idx, header) in data.get_headers_ref().iter().enumerate() {
+        sheet.write_with_format(0, col_idx as u16, header, &bold).map_err(|error| error.to_string())?;
+    }//end writing each header
+    for (row_offset, row) in filtered_data.iter().enumerate() {
+        let row_num = 1 + row_offset as u32;
+        for (col_idx, cell) in row.get_row_data().iter().enumerate() {
+            let col_num = col_idx as u16;
+            match cell.get_data() {
+                DataVal::Float(f) => sheet.write_number_with_format(row_num, col_num, *f, &base_format),
+                DataVal::Int(i) => sheet.write_number_with_format(row_num, col_num, *i as f64, &base_format),
+                DataVal::String(s) => sheet.write_with_format(row_num, col_num, s, &base_format),
+            }.map_err(|error| error.to_string())?;
+        }//end writing each cell in this row
+    }//end writing each filtered row
+
+    if config.sheet_protection_enabled {
+        let last_col = data.get_headers_ref().len().saturating_sub(1) as u16;
+        protect_sheet(sheet, 0, filtered_data.len() as u32, last_col, config).map_err(|error| error.to_string())?;
+    }//end if we should protect the sheet
+    Ok(())
+}//end write_input_snapshot_sheet(workbook, data, sheet_name, config)
+
+/// Creates the Format that every other Format in an output sheet
+/// should be built from, using the font settings in config.
+pub fn get_base_format(config: &ConfigStore) -> Format {
+    let mut base_format = Format::new();
+    if config.output_font_name.trim() != "" {base_format = base_format.set_font_name(config.output_font_name.trim());}
+    if config.output_font_size > 0 {base_format = base_format.set_font_size(config.output_font_size);}
+    base_format
+}//end get_base_format(config)
+
+/// Builds an excel number format string which shows the given number
+/// of decimal places, optionally as a percent.  
+/// The "." in a format stands for the decimal separator of the computer
+/// the workbook is opened on, so a recipient whose computer uses a decimal
+/// comma sees one. Numbers don't get a thousands separator.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::io::xlsx::get_num_format;
+/// 
+/// assert_eq!(get_num_format(2, false), "0.00");
+/// assert_eq!(get_num_format(1, true), "0.0%");
+/// assert_eq!(get_num_format(0, false), "0");
+/// assert_eq!(get_num_format(0, true), "0%");
+/// ```
+pub fn get_num_format(decimals: usize, is_percent: bool) -> String {
+    let mut num_format = String::from("0");
+    if decimals > 0 {num_format.push('.')}
+    for _ in 0..decimals {num_format.push('0')}
+    if is_percent {num_format.push('%')}
+    num_format
+}//end get_num_format(decimals, is_percent)
+
+/// Determines the order that the columns with the given headers should
+/// be written in, according to column_layout.  
+/// The returned Vec holds the 0-based index of each header, in the order
+/// it should be written, paired with whether or not that column is visible.  
+/// Headers listed in the column order of the layout come first, followed by
+/// the rest of the headers in their original order. If column_layout is None,
+/// then the original order is kept, and every column is visible.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::app::config_store::OutputColumnLayout;
+/// use usda_c_grain_sum::io::xlsx::get_column_order;
+/// 
+/// let headers = vec!["Avg Area".to_string(), "Std Area".to_string(), "Avg Length".to_string(), "Std Length".to_string()];
+/// let layout = OutputColumnLayout {
+///     sheet_name: "CSV_Stats".to_string(),
+///     column_order: vec!["Avg Length".to_string(), "Avg Area".to_string()],
+///     hidden_columns: vec!["Std Length".to_string()],
+///     transposed: false,
+/// };
+/// 
+/// assert_eq!(get_column_order(&headers, None), vec![(0,true),(1,true),(2,true),(3,true)]);
+/// assert_eq!(get_column_order(&headers, Some(&layout)), vec![(2,true),(0,true),(1,true),(3,false)]);
+/// ```
+pub fn get_column_order(headers: &Vec<String>, column_layout: Option<&OutputColumnLayout>) -> Vec<(usize, bool)> {
+    let mut column_order: Vec<usize> = Vec::new();
+    if let Some(layout) = column_layout {
+        for ordered_header in layout.column_order.iter() {
+            if let Some(col_idx) = headers.iter().position(|header| header.eq(ordered_header)) {
+                if !column_order.contains(&col_idx) {column_order.push(col_idx);}
+            }//end if we found the header from the layout
+        }//end adding each header in the layout order
+    }//end if we have a layout to follow
+    for col_idx in 0..headers.len() {
+        if !column_order.contains(&col_idx) {column_order.push(col_idx);}
+    }//end adding any headers not in the layout
+
+    column_order.into_iter().map(|col_idx| {
+        let is_visible = match column_layout {
+            Some(layout) => !layout.hidden_columns.contains(&headers[col_idx]),
+            None => true,
+        };
+        (col_idx, is_visible)
+    }).collect()
+}//end get_column_order(headers, column_layout)
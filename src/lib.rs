@@ -1,5 +1,17 @@
-pub mod data;
+/// The loaded data, such as the rows and cells of a csv or xml file,
+/// and the SampleOutput of processing, which each output sheet is written from.
+pub mod model;
 
-pub mod config_store;
+/// Reading and writing files, such as telling which known export layout
+/// a file has, writing output workbooks, and checking written workbooks.
+pub mod io;
 
-pub mod process;
\ No newline at end of file
+/// Summary statistics and histograms for single columns of loaded data.
+pub mod stats;
+
+/// The preprocessing steps run on csv data before processing.
+pub mod pipeline;
+
+/// Everything the program does with the data, such as processing it into
+/// output sheets, along with the config and the files kept between runs.
+pub mod app;
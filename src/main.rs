@@ -1,17 +1,34 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 use core::str;
-use std::path::PathBuf;
+use std::{fs, path::PathBuf, thread, time::{Duration, Instant}};
 
-use usda_c_grain_sum::config_store::{self, ConfigStore};
-use usda_c_grain_sum::data::Data;
-use usda_c_grain_sum::process::{self, SampleOutput};
+use usda_c_grain_sum::app::config_store::{self, ConfigStore, HeaderMapping, LockedSettings, PipelineStep, RecipientProfile, SessionState, StatGroupOutput, WarningAction};
+use usda_c_grain_sum::app::lock::FileLock;
+use usda_c_grain_sum::app::process;
+use usda_c_grain_sum::app::{batch, bundle, flags, frontend::{self, Frontend}, ledger, logging, manifest, paths, preflight, schedule::{self, ScheduledFrontend}, secrets, summary, update, workflow};
+use usda_c_grain_sum::io::{checksum, xlsx};
+use usda_c_grain_sum::io::schema::{self, ExportFileKind};
+use usda_c_grain_sum::io::source::{self, LoadOptions};
+use usda_c_grain_sum::io::sink::{self, OutputSink, SinkOutput};
+use usda_c_grain_sum::model::{Data, SampleOutput};
+use usda_c_grain_sum::pipeline;
 use gui::GUI;
+use recording::SessionRecording;
 
 use crate::gui::InterfaceMessage;
 
 mod gui;
+mod help;
+mod palette;
+mod recording;
+mod view;
 
 fn main() {
+    // start logging first, so messages from startup are kept too
+    let log_path = match logging::init() {
+        Ok(log_path) => Some(log_path),
+        Err(msg) => {log::warn!("Couldn't set up the log file, so messages will only go to the console.\n{}", msg); None},
+    };
     // setup gui
     let mut gui = GUI::initialize();
     
@@ -19,9 +36,26 @@ fn main() {
     let config_name = "config";
     let mut config_path: Option<PathBuf> = None;
     let mut config_store: Option<ConfigStore> = None;
+    // only the copy of the program holding this lock saves the config when closing
+    let config_lock = acquire_config_lock(&mut gui, config_name);
+    // settings an administrator has locked, which the config can't change
+    let locked_settings = read_locked_settings(&mut gui);
+    gui.set_locked_settings(&locked_settings);
 
     // make sure we get config information, update gui, walk user through fix if necessary
     ensure_config_valid(&mut gui, &mut config_store, &mut config_path, config_name);
+    // the files and window layout from the last time the program was used
+    let session_path = config_store::get_session_path().map_err(|msg| log::warn!("Couldn't find where the session is saved, so it won't be restored or saved.\n{}", msg)).ok();
+    let mut session = match session_path.as_ref().map(|session_path| config_store::try_read_session(session_path)) {
+        Some(Ok(session)) => session,
+        Some(Err(msg)) => {log::warn!("The last session couldn't be restored.\n{}", msg); SessionState::default()},
+        None => SessionState::default(),
+    };
+    if let Some(layout) = session.window_layout.as_ref().or(config_store.as_ref().and_then(|config| config.window_layout.as_ref())) {gui.set_window_layout(layout);}
+    gui.set_recent_files(&session.recent_files);
+    restore_session_files(&mut gui, &session);
+    check_for_updates(&mut gui, &config_store);
+    if gui.get_config_store().operator_prompt_enabled {gui.ask_operator_name();}
 
     // set up data containers for use during app loop
     let recv = gui.get_receiver();
@@ -30,166 +64,1253 @@ fn main() {
     let mut csv_input_file = None;
     let mut xml_input_file = None;
     let mut output_file = None;
+    // data and output from the last successful processing, kept so that selected samples can be reprocessed
+    let mut last_run: Option<LastRun> = None;
+    // results kept by the user to compare with a later run
+    let mut pinned_run: Option<LastRun> = None;
+    // how long the last successful processing took, shown in the status bar
+    let mut last_duration: Option<Duration> = None;
+    // the steps of this session, if the user is recording it to replay later
+    let mut session_recording: Option<SessionRecording> = None;
+    // the last time the schedule was checked, so each scheduled time runs once
+    let mut last_schedule_check = schedule::get_local_time();
+    // scheduled processing which is running on a worker thread
+    let mut scheduled_worker: Option<thread::JoinHandle<ScheduledFrontend>> = None;
+    gui.start_schedule_timer();
+    update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
 
     while gui.wait() {
-        match recv.recv() {
+        let message = recv.recv();
+        if let (Some(recording), Some(message)) = (session_recording.as_mut(), message.as_ref()) {
+            recording.record(message, || gui.get_config_store());
+        }//end if we're recording the session
+        match message {
             Some(InterfaceMessage::CSVInputFile(file_path)) => {
+                apply_file_profile(&mut gui, &file_path);
                 // try to get csv file
                 gui.start_wait();
-                match csv::Reader::from_path(file_path.clone()) {
-                    Ok(reader) => {
-                        println!("We got the csv reader");
-                        let data = Data::from_csv_reader(reader).unwrap();
-                        println!("We finished reading {} records from the csv", data.get_records().len());
-                        input_csv_data = Some(data);
-                        csv_input_file = Some(file_path);
-                        // format_csv_sum(&data);
-                    },
-                    Err(_) => gui.integrated_dialog_message("Couldn't get csv reader."),
-                }//end matching result of getting csv reader
+                if let Some(data) = load_input_data(&mut gui, &file_path, ExportFileKind::Csv) {
+                    input_csv_data = Some(data);
+                    session.csv_input_file = Some(file_path.clone());
+                    session.add_recent_file(&file_path);
+                    gui.set_recent_files(&session.recent_files);
+                    csv_input_file = Some(file_path);
+                }//end if we could load the csv data
+                update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
                 gui.end_wait();
             },
             Some(InterfaceMessage::XMLInputFile(file_path)) => {
+                apply_file_profile(&mut gui, &file_path);
                 // try to get the xml file
                 gui.start_wait();
-                match quick_xml::Reader::from_file(file_path.clone()) {
-                    Ok(reader) => {
-                        let mut config = gui.get_config_store();
-                        println!("We got the xml reader");
-                        let mut tags_to_include = vec![config.xml_sample_id_header]; tags_to_include.append(&mut config.xml_tags_to_include);
-                        match Data::from_xml_reader(reader, Some(tags_to_include), Some(config.xml_sample_closing_tag.as_bytes())) {
-                            Ok(xml_data) => {
-                                println!("We finished reading {} records from the xml file.", xml_data.get_records().len());
-                                input_xml_data = Some(xml_data);
-                                xml_input_file = Some(file_path);
-                            }, Err(msg) => gui.integrated_dialog_alert(&format!("Encountered an error while trying to parse xml data.\n{}",msg)),
-                        }//end matching whether we can parse xml data
-                    },
-                    Err(error) => gui.integrated_dialog_alert(&format!("Error occured when trying to open xml file:\n{:?}",error)),
-                }//end matching whether we can open the xml file
+                if let Some(xml_data) = load_input_data(&mut gui, &file_path, ExportFileKind::Xml) {
+                    let config = gui.get_config_store();
+                    if config.xml_sieve_sizes.len() > 0 {
+                        match process::parse_sieve_sizes(&config.xml_sieve_sizes) {
+                            Ok(sieve_sizes) => {
+                                let problems = process::check_sieve_sizes(&xml_data, &sieve_sizes);
+                                if problems.len() > 0 {gui.integrated_dialog_message(&format!("The sieve sizes in the config don't match the xml file, so some sieve columns won't have screen sizes:\n{}", problems.join("\n")));}
+                            },
+                            Err(msg) => gui.integrated_dialog_alert(&format!("The sieve sizes in the config couldn't be read, so sieve data can't be processed until they're fixed.\n{}", msg)),
+                        }//end matching whether we could read the sieve sizes
+                    }//end if there are sieve sizes to check
+                    input_xml_data = Some(xml_data);
+                    session.xml_input_file = Some(file_path.clone());
+                    session.add_recent_file(&file_path);
+                    gui.set_recent_files(&session.recent_files);
+                    xml_input_file = Some(file_path);
+                }//end if we could load the xml data
+                update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
                 gui.end_wait();
             },
+            Some(InterfaceMessage::OpenDataFiles(file_paths)) => {
+                // work out which kind each file is, then load it as if its matching button was used
+                let mut problems = Vec::new();
+                let mut csv_chosen = false;
+                let mut xml_chosen = false;
+                for file_path in file_paths {
+                    let file_name = paths::get_file_name(&file_path);
+                    if checksum::is_output_workbook(&file_path) {
+                        if gui.integrated_dialog_yes_no(&format!("{} is an output file from this program, which can't be read as input data.\nWould you like to check whether it's been edited since it was written?", file_name)) {
+                            gui.send_file_message(InterfaceMessage::VerifyOutput(file_path));
+                        }//end if the user wants to verify the workbook
+                        continue;
+                    }//end if this is one of our output files
+                    match source::find_source(&file_path).map(|data_source| data_source.export_kind()) {
+                        Ok(ExportFileKind::Csv) if csv_chosen => problems.push(format!("{}: Only one csv file can be loaded at a time.", file_name)),
+                        Ok(ExportFileKind::Xml) if xml_chosen => problems.push(format!("{}: Only one xml file can be loaded at a time.", file_name)),
+                        Ok(ExportFileKind::Csv) => {
+                            log::info!("{} looks like kernel data, like a csv file.", file_name);
+                            csv_chosen = true;
+                            gui.send_file_message(InterfaceMessage::CSVInputFile(file_path));
+                        },
+                        Ok(ExportFileKind::Xml) => {
+                            log::info!("{} looks like sieve data, like an xml file.", file_name);
+                            xml_chosen = true;
+                            gui.send_file_message(InterfaceMessage::XMLInputFile(file_path));
+                        },
+                        Ok(other_kind) => problems.push(format!("{}: {:?} data can't be processed by this version.", file_name, other_kind)),
+                        Err(msg) => problems.push(format!("{}: {}", file_name, msg)),
+                    }//end matching what kind of data this file has
+                }//end checking each chosen file
+                if problems.len() > 0 {
+                    gui.integrated_dialog_alert(&format!("Some of the chosen files weren't loaded.\n{}", problems.join("\n")));
+                }//end if any files couldn't be loaded
+            },
             Some(InterfaceMessage::OutputFile(file_path)) => {
                 // we got an output file
-                println!("Got output file path: \"{}\"", file_path.to_string_lossy());
+                log::info!("Got output file path: \"{}\"", file_path.to_string_lossy());
+                session.output_file = Some(file_path.clone());
                 output_file = Some(file_path);
             },
             Some(InterfaceMessage::ProcessSum) => {
-                let config_store = Some(gui.get_config_store());
+                // the chosen filter set only applies to this run, so the config itself isn't changed
+                let config_store = match gui.get_config_store().with_active_filter_set() {
+                    Ok(run_config) => Some(run_config),
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't use the chosen filter set, so nothing was processed.\n{}", msg)); continue;},
+                };
+                if !workflow::ensure_locked_settings_kept(&mut gui, &locked_settings, config_store.as_ref().unwrap()) {continue;}
                 if ensure_data_valid_for_output(&mut gui, &config_store, &input_csv_data, &input_xml_data, &mut output_file, &csv_input_file, &xml_input_file) {
-                    println!("Started processing and outputing file.");
+                    // where this run starts in the log, so its messages can be bundled with the output
+                    let log_start = gui.get_log().len();
+                    gui.log("Started processing and outputing file.");
                     
                     let output = output_file.clone().unwrap();
                     let config = config_store.clone().unwrap();
+                    if !ensure_warnings_allowed(&mut gui, &input_csv_data, &input_xml_data, &config) {continue;}
+                    let input_files: Vec<&PathBuf> = csv_input_file.iter().chain(xml_input_file.iter()).collect();
+                    if !workflow::ensure_output_folder_ready(&mut gui, &preflight::get_output_folder(&output), &input_files, false) {continue;}
+                    // (sample id, why it was flagged) for each sample the user chose to leave out
+                    let mut excluded_samples = match config.review_flagged_enabled {
+                        true => match review_flagged_samples(&mut gui, &input_csv_data, &config) {
+                            Some(excluded_samples) => excluded_samples,
+                            None => continue,
+                        },
+                        false => Vec::new(),
+                    };
+                    match workflow::get_invalid_id_exclusions(&input_csv_data, &input_xml_data, &config) {
+                        Ok(invalid_id_exclusions) => {
+                            for (sample_id, reason) in invalid_id_exclusions {
+                                if !excluded_samples.iter().any(|(excluded_id, _)| excluded_id.eq(&sample_id)) {excluded_samples.push((sample_id, reason));}
+                            }//end adding each sample with an invalid id which isn't already left out
+                        },
+                        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't check the sample ids against the sample id pattern, so nothing was processed.\n{}", msg)); continue;},
+                    }//end matching whether we could check the sample ids
+                    if excluded_samples.len() > 0 {
+                        (input_csv_data, input_xml_data) = workflow::exclude_samples(input_csv_data, input_xml_data, &excluded_samples, &config);
+                    }//end if we should leave out any samples
+                    let recipient_profiles: Vec<RecipientProfile> = match config.recipient_profiles.len() > 0 {
+                        true => match gui.show_recipient_select_dialog() {
+                            Some(selected_profiles) => config.recipient_profiles.iter().filter(|profile| selected_profiles.contains(&profile.name)).cloned().collect(),
+                            None => continue,
+                        },
+                        false => Vec::new(),
+                    };
+                    let process_start = Instant::now();
                     gui.start_wait();
-                    // actually call the processing functions
-                    let mut wb = process::get_workbook();
+                    // the data is moved to the worker thread and handed back once it's processed
+                    let (worker_csv_data, worker_xml_data, worker_config, worker_output) = (input_csv_data.take(), input_xml_data.take(), config.clone(), output.clone());
+                    let worker_result = frontend::run_in_background(&mut gui, move |progress| {
+                        let mut problems = Vec::new();
+                        let output_sheets = workflow::get_output_sheets(&worker_csv_data, &worker_xml_data, &worker_config, &worker_output, &mut problems, progress);
+                        (worker_csv_data, worker_xml_data, output_sheets, problems)
+                    });
+                    let (output_sheets, problems) = match worker_result {
+                        Ok((csv_data, xml_data, output_sheets, problems)) => {
+                            input_csv_data = csv_data;
+                            input_xml_data = xml_data;
+                            (output_sheets, problems)
+                        },
+                        Err(msg) => {
+                            gui.integrated_dialog_alert(&format!("An Error Occurred while processing, so nothing was processed, and the input files will need to be loaded again.\n{}", msg));
+                            gui.end_wait();
+                            update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
+                            continue;
+                        },
+                    };
+                    let mut output_sheets = match output_sheets {
+                        Ok(output_sheets) => output_sheets,
+                        Err(msg) => {
+                            gui.integrated_dialog_alert(&format!("An Error Occurred while running the processing pipeline, so nothing was processed.\n{}", msg));
+                            gui.end_wait();
+                            continue;
+                        },
+                    };
+                    if problems.len() > 0 {gui.integrated_dialog_alert(&problems.join("\n\n"));}
+
+                    for (sheet_name, sheet_data) in output_sheets.iter_mut() {
+                        match workflow::apply_before_write_script(&config.script_path, sheet_name, sheet_data) {
+                            Ok(scripted_sheet) => *sheet_data = scripted_sheet,
+                            Err(msg) => gui.integrated_dialog_alert(&format!("The user script couldn't change the {} sheet, so it will be written as it is.\n{}", sheet_name, msg)),
+                        }//end matching whether the script could change this sheet
+                    }//end running the script on each sheet
+
+                    let output = match workflow::ensure_output_writable(&mut gui, &output, false) {
+                        Some(output) => output,
+                        None => {
+                            gui.log("Nothing was written, since the output file is open in another program.");
+                            gui.end_wait();
+                            continue;
+                        },
+                    };
+                    // output_sheets keeps the real sample ids, so selected samples can still be reprocessed
+                    let anonymized_output = match workflow::get_anonymized_output(&mut gui, &output_sheets, &input_csv_data, &config, &output) {
+                        Ok(anonymized_output) => anonymized_output,
+                        Err(msg) => {
+                            gui.integrated_dialog_alert(&format!("Couldn't anonymize the sample ids, so nothing was written.\n{}", msg));
+                            gui.end_wait();
+                            continue;
+                        },
+                    };
+                    let (sheets_to_write, snapshot_data) = match anonymized_output {
+                        Some((ref anonymized_sheets, ref anonymized_csv)) => (anonymized_sheets, anonymized_csv.as_ref()),
+                        None => (&output_sheets, input_csv_data.as_ref()),
+                    };
+                    let run_info = workflow::get_run_info(&config, &csv_input_file, &xml_input_file, &excluded_samples);
+                    let run_info_sheet = match config.run_info_sheet_enabled || excluded_samples.len() > 0 {
+                        true => Some(run_info.clone()),
+                        false => None,
+                    };
+                    let signing_key = match workflow::get_output_signing_key(&run_info_sheet) {
+                        Ok(signing_key) => signing_key,
+                        Err(msg) => {gui.integrated_dialog_alert(&msg); gui.end_wait(); continue;},
+                    };
+                    let sink_output = SinkOutput { sheets: sheets_to_write, input_snapshot: snapshot_data, run_info: run_info_sheet, signing_key: signing_key.as_deref() };
                     // make sure we aren't asking user to see workbook if nothing finished successfully
-                    let mut successfully_processed_at_least_once = false;
-                    // (name of sheet, data to go in that sheet)
-                    let mut output_sheets: Vec<(String, SampleOutput)> = Vec::new();
-                    
-                    // get all data we might want, based on config
-                    if config.csv_stat_columns_enabled || config.csv_class_percent_enabled {
-                        let input_csv = input_csv_data.unwrap();
-                        if config.csv_stat_columns_enabled {
-                            match process::proc_csv_stat_cols(&input_csv, &config) {
-                                Ok(sample_output) => output_sheets.push(("CSV_Stats".to_string(), sample_output)),
-                                Err(msg) => gui.integrated_dialog_alert(&format!("An Error Occurred while trying to process CSV STAT Columns!\n{}",msg)),
-                            }//end matching whether or not csv stat columns were processed successfully
-                        }//end if we should output csv stat columns
-                        if config.csv_class_percent_enabled {
-                            match process::proc_csv_class_per(&input_csv, &config) {
-                                Ok(sample_output) => output_sheets.push(("Class_Percents".to_string(), sample_output)),
-                                Err(msg) => gui.integrated_dialog_alert(&format!("An Error Occured while trying to process CSV Class Percent Columns!\n{}",msg)),
-                            }//end matching whether or not csv class percents were processed successfully
-                        }//end if we should output class percents
-                        input_csv_data = Some(input_csv);
-                    }//end if we're doing csv stuff
-                    if config.xml_sieve_cols_enabled {
-                        let input_xml = input_xml_data.unwrap();
-                        match process::proc_xml_sieve_data(&input_xml, &config) {
-                            Ok(sample_output) => output_sheets.push(("XML_Sieve_Data".to_string(),sample_output)),
-                            Err(msg) => gui.integrated_dialog_alert(&format!("An Error occured while trying to process XML Sieve Data!\n{}", msg)),
-                        }//end matching whether or not xml sieve stuff was processed correctly
-                        input_xml_data = Some(input_xml);
-                    }//end if we should output xml sieve cols
-
-                    for (sheet_name, sheet_data) in output_sheets {
-                        match process::write_output_to_sheet(&mut wb, &sheet_data, &sheet_name) {
-                            Ok(_) => successfully_processed_at_least_once = true,
-                            Err(msg) => gui.integrated_dialog_alert(&format!("Ecountered an error while attempting to write data to worksheet {}.\n{}", sheet_name, msg)),
-                        }//end matching whether writing to sheet was a success
-                    }//end writing data from each output sheet
-
-                    if let Err(error) = process::close_workbook(&mut wb, &output) {gui.integrated_dialog_alert(&format!("Encountered an error while attempting to write data to worksheet.\n{}",error));}
+                    let successfully_processed_at_least_once = sheets_to_write.len() > 0 && workflow::write_output_sinks(&mut gui, &sink_output, &config, &output);
+                    if successfully_processed_at_least_once && config.split_output_enabled {
+                        match workflow::get_output_sample_id_key(&output_sheets, &input_csv_data, &config) {
+                            Ok(key) => workflow::write_split_workbooks(&mut gui, &output_sheets, &key, &config, &output),
+                            Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't anonymize the sample ids, so the workbooks split by metadata column weren't written.\n{}", msg)),
+                        }//end matching whether we could get the pseudonyms
+                    }//end if we should also write a workbook for each metadata value
+                    if successfully_processed_at_least_once && recipient_profiles.len() > 0 {
+                        write_recipient_workbooks(&mut gui, sheets_to_write, &recipient_profiles, &config, &output);
+                    }//end if we should also write the output for any recipients
 
                     if successfully_processed_at_least_once {
-                        println!("Finished outputing processed file.");
+                        gui.log("Finished outputing processed file.");
                         gui.clear_output_text();
-                        if gui.integrated_dialog_yes_no("Processing complete. Would you like to open the folder where the output file is located?") {
-                            opener::reveal(output).unwrap();
-                        }//end if user wants to open folder
-                        input_csv_data = None;
-                        input_xml_data = None;
+                        last_run = Some(LastRun {
+                            output: output.clone(),
+                            config,
+                            csv_data: input_csv_data.take(),
+                            xml_data: input_xml_data.take(),
+                            output_sheets,
+                            csv_input_file: csv_input_file.take(),
+                            xml_input_file: xml_input_file.take(),
+                            excluded_samples,
+                            run_info,
+                            log: gui.get_log()[log_start..].to_vec(),
+                            recipient_profiles,
+                        });
+                        if let Some(ref run) = last_run {
+                            let run_summary = run.csv_data.as_ref().and_then(|csv_data| pipeline::run_pipeline(csv_data, &run.config).ok()).and_then(|(piped_csv, _)| summary::get_run_summary(&piped_csv, &run.config).ok());
+                            let input_files: Vec<&PathBuf> = [&run.csv_input_file, &run.xml_input_file].into_iter().flatten().collect();
+                            workflow::record_ledger_run(&mut gui, &input_files, &run.output, &run.config, &run.output_sheets, run_summary.as_ref());
+                        }//end if we have the run to add to the ledger
+                        last_duration = Some(process_start.elapsed());
+                        update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
+                        gui.notify_completion(process_start.elapsed(), &paths::get_file_name(&output));
                         output_file = None;
-                        csv_input_file = None;
-                        xml_input_file = None;
+                        match gui.integrated_dialog_message_choice("Processing complete. Would you like to open the folder where the output file is located, view the results here, or get summary text for an email?", vec!["Open Folder", "View Results", "Email Summary", "Done"]) {
+                            Some(0) => opener::reveal(&output).unwrap(),
+                            Some(1) => if let Some(ref run) = last_run {GUI::show_results_viewer(get_results_tables(run));},
+                            Some(2) => if let Some(ref run) = last_run {
+                                match get_run_summary_text(run) {
+                                    Ok(summary_text) => GUI::show_summary_dialog(&summary_text),
+                                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't summarize the output.\n{}", msg)),
+                                }//end matching whether we could summarize the run
+                            },
+                            _ => {},
+                        }//end matching what user wants to do with the output
                     } else {
                         gui.integrated_dialog_alert("It seems that a processing routine was run without any successful outputs.\nThis shouldn't happen...");
                     }//end else we never managed to process anything
                     gui.end_wait();
                 }//end if all our data is valid for the output we want to make
             },
+            Some(InterfaceMessage::EditColumnLayout) => {
+                let config = gui.get_config_store();
+                let config = config.with_active_filter_set().unwrap_or(config);
+                // (name of sheet, headers in that sheet)
+                let mut sheet_headers: Vec<(String, Vec<String>)> = Vec::new();
+                // we can only know some headers (like class names) by processing loaded data
+                let piped_csv = input_csv_data.as_ref().and_then(|input_csv| pipeline::run_pipeline(input_csv, &config).ok()).map(|(piped_csv, _)| piped_csv);
+                let csv_stats = match piped_csv {Some(ref input_csv) => process::proc_csv_stat_cols(input_csv, &config).ok(), None => None};
+                let class_percents = match piped_csv {Some(ref input_csv) => process::proc_csv_class_per(input_csv, &config).ok(), None => None};
+                let xml_sieve = match input_xml_data {Some(ref input_xml) => process::proc_xml_sieve_data(input_xml, &config).ok(), None => None};
+                let treatments = match piped_csv {Some(ref input_csv) => workflow::load_metadata(&config).and_then(|metadata| process::proc_treatment_comparison(input_csv, &metadata, &config)).ok(), None => None};
+                let diagnostics = match piped_csv {Some(ref input_csv) => process::proc_csv_diagnostics(input_csv, &config).ok(), None => None};
+                let frame_stats = match piped_csv {Some(ref input_csv) => process::proc_csv_frame_stats(input_csv, &config).ok(), None => None};
+                let drift_check = match piped_csv {Some(ref input_csv) => process::proc_csv_drift_check(input_csv, &config).ok(), None => None};
+                let metrics = match piped_csv {Some(ref input_csv) => process::proc_csv_metrics(input_csv, &config).ok(), None => None};
+                let merged = match (&piped_csv, &input_xml_data) {(Some(input_csv), Some(input_xml)) => process::proc_merged_output(input_csv, input_xml, &config).ok(), _ => None};
+                for (sheet_name, sheet_data) in [("CSV_Stats", csv_stats), ("Class_Percents", class_percents), ("XML_Sieve_Data", xml_sieve), ("Treatment_Comparison", treatments), ("Diagnostics", diagnostics), ("Frame_Stats", frame_stats), ("Drift_Check", drift_check), ("Metrics", metrics), ("Merged", merged)] {
+                    let headers = match sheet_data {
+                        Some(mut sample_output) => {
+                            let _ = process::add_formula_columns(sheet_name, &mut sample_output, &config.formula_columns);
+                            sample_output.columns.into_iter().map(|column| column.name).collect()
+                        },
+                        None => Vec::new(),
+                    };
+                    sheet_headers.push((sheet_name.to_string(), headers));
+                }//end getting headers for each sheet
+                gui.show_column_layout_dialog(sheet_headers);
+            },
+            Some(InterfaceMessage::EditPipeline) => gui.show_pipeline_dialog(),
+            Some(InterfaceMessage::EditHeaderAliases) => gui.show_header_alias_dialog(&input_csv_data.as_ref().map(|csv_data| csv_data.get_headers_ref().clone()).unwrap_or_default()),
+            Some(InterfaceMessage::EditMetrics) => gui.show_metrics_dialog(),
+            Some(InterfaceMessage::EditFlagRules) => gui.show_flag_rules_dialog(),
+            Some(InterfaceMessage::EditFormulaColumns) => gui.show_formula_columns_dialog(),
+            Some(InterfaceMessage::EditColumnGroups) => gui.show_column_groups_dialog(),
+            Some(InterfaceMessage::EditRecipientProfiles) => gui.show_recipient_profiles_dialog(),
+            Some(InterfaceMessage::EditWarningPolicies) => gui.show_warning_policy_dialog(),
+            Some(InterfaceMessage::SaveFilterSet) => gui.save_filter_set(),
+            Some(InterfaceMessage::SavePreset) => {
+                gui.save_preset();
+                update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
+            },
+            Some(InterfaceMessage::ManagePresets) => {
+                gui.show_preset_manager();
+                update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
+            },
+            Some(InterfaceMessage::SwitchUserMode) => gui.switch_user_mode(),
+            Some(InterfaceMessage::NotificationSettings) => gui.show_notification_settings_dialog(),
+            Some(InterfaceMessage::GoogleSheetsSettings) => gui.show_google_sheets_dialog(),
+            Some(InterfaceMessage::ShowCommandPalette) => gui.show_command_palette(),
+            Some(InterfaceMessage::ToggleRecording) => {
+                match session_recording.take() {
+                    None => {
+                        session_recording = Some(SessionRecording::new(gui.get_config_store()));
+                        gui.integrated_dialog_message("Recording started. Load your files and process them as usual,\nthen choose Start or Stop Recording Session again to save the recording.");
+                    },
+                    Some(recording) if !recording.steps.contains(&InterfaceMessage::ProcessSum) => {
+                        gui.integrated_dialog_alert("Recording stopped. Nothing was processed while recording, so there's nothing to save.");
+                    },
+                    Some(recording) => {
+                        if let Some(recording_path) = gui.choose_recording_save_path() {
+                            match recording::try_write_recording(&recording_path, &recording) {
+                                Ok(_) => gui.integrated_dialog_message(&format!("Saved the recording with {} steps to {}.", recording.steps.len(), recording_path.to_string_lossy())),
+                                Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't save the recording.\n{}", msg)),
+                            }//end matching whether we could save the recording
+                        }//end if the user chose where to save the recording
+                    },
+                }//end matching whether we're starting or stopping a recording
+            },
+            Some(InterfaceMessage::ReplayRecording) => {
+                let (recording_path, input_files, output) = match gui.choose_replay_files() {
+                    Some(replay_files) => replay_files,
+                    None => continue,
+                };
+                let replay_steps = recording::try_read_recording(&recording_path)
+                    .and_then(|recording| recording::sort_input_files(&input_files).map(|files| (recording, files)))
+                    .and_then(|(recording, (csv_files, xml_files))| recording.get_replay_steps(&csv_files, &xml_files, &output).map(|steps| (recording.config, steps)));
+                match replay_steps {
+                    Ok((recorded_config, steps)) => {
+                        config_store = Some(switch_preset(&mut gui, recorded_config));
+                        // the steps are queued, so they run one after another once this message is done
+                        for step in steps {gui.send_file_message(step);}
+                    },
+                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't replay the recording.\n{}", msg)),
+                }//end matching whether we can replay the recording
+            },
+            Some(InterfaceMessage::SwitchPreset(preset_name)) => {
+                match config_store::get_preset_config(&preset_name) {
+                    Some(new_conf) => {
+                        config_store = Some(switch_preset(&mut gui, new_conf));
+                        update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
+                    },
+                    None => gui.integrated_dialog_alert(&format!("Couldn't find the preset \"{}\".", preset_name)),
+                }//end matching whether the preset exists
+            },
+            Some(InterfaceMessage::ToggleConfigPanel) => gui.toggle_config_panel(),
+            Some(InterfaceMessage::DeleteFilterSet) => gui.delete_filter_set(),
+            Some(InterfaceMessage::ExploreData) => {
+                let config = gui.get_config_store();
+                let csv_search_headers = vec![config.csv_sample_id_header.clone(), config.csv_class_filter_class.clone()];
+                let xml_search_headers = vec![config.xml_sample_id_header.clone()];
+                // (name of input, data from that input, headers of columns to search)
+                let mut datasets: Vec<(String, Data, Vec<String>)> = Vec::new();
+                if let Some(ref input_csv) = input_csv_data {datasets.push(("CSV Input".to_string(), input_csv.clone(), csv_search_headers.clone()));}
+                if let Some(ref input_xml) = input_xml_data {datasets.push(("XML Input".to_string(), input_xml.clone(), xml_search_headers.clone()));}
+                // data moves to the last run after processing, so fall back to that
+                if datasets.len() == 0 {
+                    if let Some(ref run) = last_run {
+                        if let Some(ref csv_data) = run.csv_data {datasets.push(("CSV Input".to_string(), csv_data.clone(), csv_search_headers));}
+                        if let Some(ref xml_data) = run.xml_data {datasets.push(("XML Input".to_string(), xml_data.clone(), xml_search_headers));}
+                    }//end if we have data from the last run
+                }//end if there's no newly loaded data
+                match datasets.len() {
+                    0 => gui.integrated_dialog_alert("There's no loaded data to explore yet. Please select an input file first."),
+                    _ => GUI::show_data_explorer(datasets),
+                }//end matching whether we have any data to explore
+            },
+            Some(InterfaceMessage::ReprocessSamples) => {
+                match last_run {
+                    Some(ref mut run) => reprocess_samples(&mut gui, run, &locked_settings),
+                    None => gui.integrated_dialog_alert("There's no output to update yet. Please process your data first, then you can reprocess selected samples."),
+                }//end matching whether we have a previous run to update
+            },
+            Some(InterfaceMessage::ViewResults) => {
+                match last_run {
+                    Some(ref run) => GUI::show_results_viewer(get_results_tables(run)),
+                    None => gui.integrated_dialog_alert("There are no results to view yet. Please process your data first."),
+                }//end matching whether we have a previous run to view
+            },
+            Some(InterfaceMessage::PinResults) => {
+                match last_run.take() {
+                    Some(run) => {
+                        gui.integrated_dialog_message(&format!("The results of {} are pinned. Load and process another dataset, then right click Process Data and choose Compare with Pinned Results to see them side by side.", paths::get_file_name(&run.output)));
+                        pinned_run = Some(run);
+                    },
+                    None => gui.integrated_dialog_alert("There are no results to pin yet. Please process your data first."),
+                }//end matching whether we have a previous run to pin
+            },
+            Some(InterfaceMessage::CompareResults) => {
+                match (&pinned_run, &last_run) {
+                    (Some(pinned), Some(run)) => GUI::show_comparison_viewer(
+                        (format!("Pinned: {}", paths::get_file_name(&pinned.output)), get_results_tables(pinned)),
+                        (format!("Last Run: {}", paths::get_file_name(&run.output)), get_results_tables(run))),
+                    (None, _) => gui.integrated_dialog_alert("There are no pinned results yet. Please process your first dataset, then right click Process Data and choose Pin Results for Comparison."),
+                    (Some(_), None) => gui.integrated_dialog_alert("There are no results to compare with the pinned results yet. Please process another dataset first."),
+                }//end matching whether we have two runs to compare
+            },
+            Some(InterfaceMessage::PrintSummary) => {
+                match last_run {
+                    Some(ref run) => {
+                        let sheet_names: Vec<&str> = run.output_sheets.iter().map(|(sheet_name, _)| sheet_name.as_str()).collect();
+                        if let Some(sheet_idx) = gui.integrated_dialog_message_choice("Which table would you like to print?", sheet_names) {
+                            let (sheet_name, sheet_data) = &run.output_sheets[sheet_idx];
+                            let (headers, rows) = process::get_sheet_text_table(sheet_data, sheet_name, &run.config);
+                            let output_name = run.output.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                            match GUI::print_table(&format!("{} - {}", output_name, sheet_name), &headers, &rows) {
+                                Ok(pages_printed) => log::info!("Printed {} pages of {}.", pages_printed, sheet_name),
+                                Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't print the summary.\n{}", msg)),
+                            }//end matching whether we could print the table
+                        }//end if the user chose a table to print
+                    },
+                    None => gui.integrated_dialog_alert("There's no output to print yet. Please process your data first."),
+                }//end matching whether we have a previous run to print
+            },
+            Some(InterfaceMessage::ShowRunSummary) => {
+                match last_run.as_ref().map(get_run_summary_text) {
+                    Some(Ok(summary_text)) => GUI::show_summary_dialog(&summary_text),
+                    Some(Err(msg)) => gui.integrated_dialog_alert(&format!("Couldn't summarize the output.\n{}", msg)),
+                    None => gui.integrated_dialog_alert("There's no output to summarize yet. Please process your data first."),
+                }//end matching whether we could summarize the last run
+            },
+            Some(InterfaceMessage::ExportRunBundle) => match last_run {
+                Some(ref run) => export_run_bundle(&mut gui, run),
+                None => gui.integrated_dialog_alert("There's no output to bundle yet. Please process your data first."),
+            },
+            Some(InterfaceMessage::ViewRunLedger) => match ledger::get_ledger_path().and_then(|ledger_path| ledger::read_ledger(&ledger_path, &secrets::get_install_salt(secrets::LEDGER_KEY_FILE_NAME)?)) {
+                Ok((entries, problems)) if entries.len() == 0 && problems.len() == 0 => gui.integrated_dialog_message("There aren't any runs in the run ledger yet.\nA run is added each time processing finishes and the output is written."),
+                Ok((entries, problems)) => gui.show_ledger_dialog(&entries, &problems),
+                Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't read the run ledger.\n{}", msg)),
+            },
+            Some(InterfaceMessage::ViewLog) => match log_path {
+                Some(ref log_path) => {
+                    log::logger().flush();
+                    if let Err(error) = opener::open(log_path) {gui.integrated_dialog_alert(&format!("Couldn't open the log file \"{}\".\n{}", log_path.to_string_lossy(), error));}
+                },
+                None => gui.integrated_dialog_alert("The log file couldn't be set up when the program started, so there's no log to open.\nMessages are only shown in the console."),
+            },
+            Some(InterfaceMessage::VerifyOutput(file_path)) => {
+                match secrets::get_install_salt(secrets::OUTPUT_SIGNING_KEY_FILE_NAME).and_then(|signing_key| checksum::verify_workbook_signature(&file_path, &signing_key)) {
+                    Ok((stored_signature, current_signature)) => {
+                        if stored_signature == current_signature {gui.integrated_dialog_message(&format!("The values in \"{}\" match its signature of {}.\nThe output values haven't been edited since the file was written.", file_path.to_string_lossy(), stored_signature));}
+                        else {gui.integrated_dialog_alert(&format!("The values in \"{}\" don't match its signature!\nThe stored signature is {}, but the values now give {}.\nThe output values have been edited since the file was written, or the file was written by another install, which signs with its own key.", file_path.to_string_lossy(), stored_signature, current_signature));}
+                    },
+                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't verify the output file.\n{}", msg)),
+                }//end matching whether we could check the signature
+            },
+            Some(InterfaceMessage::OpenSummary(file_path)) => {
+                let summary_sheets = match xlsx::read_summary_workbook(&file_path) {
+                    Ok(summary_sheets) => summary_sheets,
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't open the summary workbook.\n{}", msg)); continue;},
+                };
+                // the column layouts were already used when the summary was written
+                let mut config = gui.get_config_store();
+                config.output_column_layouts.clear();
+                let export_sinks: Vec<Box<dyn OutputSink>> = sink::get_sinks().into_iter().filter(|output_sink| output_sink.get_output_path(&file_path) != file_path).collect();
+                let mut choices = vec!["View Results".to_string()];
+                for output_sink in export_sinks.iter() {choices.push(format!("Export as {}", output_sink.name()));}
+                choices.push("Done".to_string());
+                loop {
+                    let choice = gui.integrated_dialog_message_choice(&format!("Opened {} sheets from \"{}\".\nWould you like to view them, or write them in another format next to the workbook?", summary_sheets.len(), paths::get_file_name(&file_path)), choices.iter().map(|choice| choice.as_str()).collect());
+                    match choice {
+                        Some(0) => {
+                            let results_tables = summary_sheets.iter().map(|(sheet_name, sheet_data)| {
+                                let (headers, rows) = process::get_sheet_text_table(sheet_data, sheet_name, &config);
+                                (sheet_name.clone(), headers, rows)
+                            }).collect();
+                            GUI::show_results_viewer(results_tables);
+                        },
+                        Some(choice_idx) if choice_idx <= export_sinks.len() => {
+                            let output_sink = &export_sinks[choice_idx - 1];
+                            let sink_path = output_sink.get_output_path(&file_path);
+                            let sink_output = SinkOutput { sheets: &summary_sheets, input_snapshot: None, run_info: None, signing_key: None };
+                            match output_sink.write(&sink_output, &config, &sink_path) {
+                                Ok(description) => gui.integrated_dialog_message(&description),
+                                Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the summary as {}.\n{}", output_sink.name(), msg)),
+                            }//end matching whether we could export the summary
+                        },
+                        _ => break,
+                    }//end matching what the user wants to do with the summary
+                }//end letting the user view or export the summary until they're done
+            },
+            Some(InterfaceMessage::MergeSummaries(file_paths, merged_path)) => {
+                if !workflow::ensure_output_folder_ready(&mut gui, &preflight::get_output_folder(&merged_path), &file_paths.iter().collect(), false) {continue;}
+                gui.start_wait();
+                let merged_path = match workflow::ensure_output_writable(&mut gui, &merged_path, false) {
+                    Some(merged_path) => merged_path,
+                    None => {gui.end_wait(); continue;},
+                };
+                let mut summaries = Vec::new();
+                let mut problems = Vec::new();
+                for file_path in file_paths.iter() {
+                    match xlsx::read_summary_workbook(file_path) {
+                        // the kernel rows of the input aren't per-sample, so they aren't merged
+                        Ok(summary_sheets) => summaries.push((file_path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default(), summary_sheets.into_iter().filter(|(sheet_name, _)| sheet_name != "Input_Data").collect())),
+                        Err(msg) => problems.push(format!("{}: {}", paths::get_file_name(file_path), msg)),
+                    }//end matching whether we could read this summary
+                }//end reading each summary
+                let merged_sheets = process::merge_summary_sheets(&summaries);
+                // the column layouts were already used when each summary was written
+                let mut config = gui.get_config_store();
+                config.output_column_layouts.clear();
+                let sink_output = SinkOutput { sheets: &merged_sheets, input_snapshot: None, run_info: None, signing_key: None };
+                match sink::XlsxSink.write(&sink_output, &config, &merged_path) {
+                    Ok(_) => {
+                        let mut message = match problems.len() {
+                            0 => format!("Merged {} summaries into \"{}\".", summaries.len(), merged_path.to_string_lossy()),
+                            _ => format!("Merged {} summaries into \"{}\", but some couldn't be read and were left out.\n{}", summaries.len(), merged_path.to_string_lossy(), problems.join("\n")),
+                        };
+                        let mut had_problems = problems.len() > 0;
+                        match push_to_google_sheets(&sink_output, &config) {
+                            Some(Ok(description)) => message.push_str(&format!("\n{}", description)),
+                            Some(Err(msg)) => {
+                                had_problems = true;
+                                message.push_str(&format!("\nCouldn't push the merged summary to Google Sheets.\n{}", msg));
+                            },
+                            None => {},
+                        }//end matching whether we pushed the merged summary to Google Sheets
+                        if had_problems {gui.integrated_dialog_alert(&message);}
+                        else {gui.integrated_dialog_message(&message);}
+                    },
+                    Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the merged workbook.\n{}", msg)),
+                }//end matching whether we could write the merged workbook
+                gui.end_wait();
+            },
+            Some(InterfaceMessage::BatchProcess(input_dir, combined_output)) => batch::batch_process(&mut gui, &input_dir, &combined_output, &locked_settings, false),
+            Some(InterfaceMessage::StreamLargeCsv(csv_path, output_path)) => stream_large_csv(&mut gui, &csv_path, &output_path, &locked_settings),
+            Some(InterfaceMessage::ScheduleTick) => {
+                if scheduled_worker.as_ref().is_some_and(|worker| worker.is_finished()) {
+                    if let Some(worker) = scheduled_worker.take() {finish_scheduled_processing(&mut gui, worker);}
+                }//end if the last sweep just finished
+                let config = gui.get_config_store();
+                let now = schedule::get_local_time();
+                if config.schedule_enabled && !config.schedule_folder.trim().is_empty() {
+                    match schedule::is_schedule_due(&config.schedule_cron, last_schedule_check, now) {
+                        Ok(true) if scheduled_worker.is_some() => log::warn!("Scheduled processing is due, but the last sweep hasn't finished, so this one was skipped."),
+                        Ok(true) => scheduled_worker = Some(schedule::start_scheduled_processing(&config, &locked_settings, now)),
+                        Ok(false) => {},
+                        Err(msg) => log::warn!("Couldn't check the schedule for scheduled processing.\n{}", msg),
+                    }//end matching whether scheduled processing should run
+                }//end if scheduled processing is turned on
+                last_schedule_check = now;
+            },
+            Some(InterfaceMessage::ScheduleSettings) => gui.show_schedule_dialog(),
             Some(InterfaceMessage::AppClosing) => {
+                // stopping partway through a sweep could leave a half-written workbook
+                if let Some(worker) = scheduled_worker.take() {
+                    log::info!("Waiting for scheduled processing to finish before closing.");
+                    if worker.join().is_err() {log::error!("Scheduled processing stopped unexpectedly while closing.");}
+                }//end if scheduled processing is still running
+                // the session is saved by every copy of the program, so the last one closed is restored
+                if let Some(session_path) = session_path.as_ref() {
+                    session.window_layout = Some(gui.get_window_layout());
+                    match config_store::try_write_session(session_path, &session) {
+                        Ok(_) => log::info!("Session saved!"),
+                        Err(msg) => log::error!("Couldn't save the session, so it won't be restored next time.\n{}", msg),
+                    }//end matching whether we could save the session
+                }//end if we know where to save the session
                 match config_path {
+                    Some(_) if config_lock.is_none() => log::warn!("Config not saved, since another copy of the program is using it."),
                     Some(ref config_path_tmp) => {
-                        if config_store.is_some() {config_store = Some(gui.get_config_store())}
+                        if config_store.is_some() {
+                            let mut closing_config = gui.get_config_store();
+                            closing_config.window_layout = Some(gui.get_window_layout());
+                            config_store = Some(closing_config);
+                        }//end if we have a config to update
                         match config_store {
                             Some(ref config_store_tmp) => {
                                 match config_store::try_write_config(config_path_tmp, config_store_tmp) {
-                                    Ok(_) => println!("Config file updated!"),
-                                    Err(msg) => println!("Couldn't write config to file!\nReceived message \"{}\"!", msg),
+                                    Ok(_) => log::info!("Config file updated!"),
+                                    Err(msg) => log::error!("Couldn't write config to file!\nReceived message \"{}\"!", msg),
                                 }//end matching whether or not we can write the config to file
                             },
-                            None => println!("Config Store not Initialized!"),
+                            None => log::warn!("Config Store not Initialized!"),
                         }//end matching whether we have config store
                     },
-                    None => println!("Config Path not Found!"),
+                    None => log::warn!("Config Path not Found!"),
                 };
                 GUI::quit();
             },
             Some(InterfaceMessage::ConfigReset) => {
-                let new_conf = match gui.integrated_dialog_message_choice("Please choose the configuration preset you'd like to switch to:", vec!["Wheat", "Sorghum", "Other"]) {
-                    Some(0) => config_store::get_rhett_config(),
-                    Some(1) => config_store::get_scott_config(),
-                    _ => config_store::ConfigStore::default(),
-                };
-                gui.set_config_store(&new_conf);
-                config_store = Some(new_conf);
+                let mut preset_names = config_store::get_preset_names();
+                preset_names.push("Other".to_string());
+                let new_conf = gui.integrated_dialog_message_choice("Please choose the configuration preset you'd like to switch to:", preset_names.iter().map(|name| name.as_str()).collect())
+                    .and_then(|choice_idx| preset_names.get(choice_idx))
+                    .and_then(|preset_name| config_store::get_preset_config(preset_name))
+                    .unwrap_or_default();
+                config_store = Some(switch_preset(&mut gui, new_conf));
+                update_status_bar(&mut gui, &csv_input_file, &input_csv_data, &xml_input_file, &input_xml_data, &last_duration);
             },
             Some(unrecognized_message) => gui.integrated_dialog_alert(&format!("Recieved unrecognized message {:?}",unrecognized_message)),
             None => {}, 
         }//end if we recieved a message
     }//end main application loop
 
-    println!("Program Exiting!");
+    log::info!("Program Exiting!");
 }
 
+/// Shows new_conf in the gui as the chosen preset, keeping the settings
+/// which belong to this machine rather than the preset, such as operator mode.  
+/// Returns the config that was set.
+fn switch_preset(gui: &mut GUI, mut new_conf: ConfigStore) -> ConfigStore {
+    let old_conf = gui.get_config_store();
+    new_conf.update_check_location = old_conf.update_check_location;
+    new_conf.operator_mode_enabled = old_conf.operator_mode_enabled;
+    new_conf.operator_name = old_conf.operator_name;
+    new_conf.operator_prompt_enabled = old_conf.operator_prompt_enabled;
+    gui.set_config_store(&new_conf);
+    new_conf
+}//end switch_preset(gui, new_conf)
+
+/// Loads the input file at file_path with whichever source reads its format,
+/// renames the headers of older export layouts, and runs the user script.  
+/// export_kind is what the file is loaded as, such as Csv for the kernel
+/// data chosen with the csv button, even if it was saved as an xlsx file.
+/// The file is read on a worker thread, so the window stays responsive
+/// while large files load.  
+/// Any problems are shown to the user, and None is returned if the file
+/// shouldn't be loaded.
+fn load_input_data(gui: &mut GUI, file_path: &PathBuf, export_kind: ExportFileKind) -> Option<Data> {
+    let config = gui.get_config_store();
+    let kind_name = if export_kind == ExportFileKind::Xml {"xml"} else {"csv"};
+    let (worker_path, worker_config) = (file_path.clone(), config.clone());
+    let loaded_data = frontend::run_in_background(gui, move |progress| -> Result<Data,String> {
+        if worker_config.input_settle_seconds > 0 {
+            progress(0.0, &format!("Waiting for the {} file to finish being written", kind_name));
+            if source::wait_for_settled_file(&worker_path, Duration::from_secs(worker_config.input_settle_seconds), source::SETTLE_TIMEOUT)? {
+                log::info!("Waited for the {} file to finish being written.", kind_name);
+            }//end if we had to wait for the file
+        }//end if we should wait for files still being written
+        let data_source = match source::find_source(&worker_path) {
+            Ok(data_source) if data_source.export_kind() != export_kind => return Err(format!("The chosen file looks like a {:?} file, which can't be loaded as {} data.\nPlease choose it with the matching button instead.", data_source.file_kind(), kind_name)),
+            Ok(data_source) => data_source,
+            Err(msg) => return Err(format!("Couldn't tell how to load the {} file.\n{}", kind_name, msg)),
+        };
+        progress(0.5, &format!("Reading the {} file", kind_name));
+        data_source.load(&worker_path, &LoadOptions::from_config(&worker_config))
+    });
+    let mut data = match loaded_data.and_then(|loaded_data| loaded_data) {
+        Ok(data) => data,
+        Err(msg) => {gui.integrated_dialog_alert(&msg); return None;},
+    };
+    log::info!("We finished reading {} records from the {} file.", data.get_records().len(), kind_name);
+    // older exports have their headers renamed, so they process with the current config
+    match schema::detect_schema(&data, export_kind) {
+        Ok(export_schema) => {
+            log::info!("The {} file matches the {} layout.", kind_name, export_schema.name);
+            let applied = data.rename_headers(export_schema.header_aliases);
+            if applied.len() > 0 {
+                let renamed: Vec<String> = applied.iter().map(|(old_header, new_header)| format!("{} -> {}", old_header, new_header)).collect();
+                gui.integrated_dialog_message(&format!("The {} file matches the {} layout, so these columns were renamed to match current exports:\n{}", kind_name, export_schema.name, renamed.join("\n")));
+            }//end if we renamed any headers
+        },
+        Err(msg) if config.strict_schema_enabled => {
+            gui.integrated_dialog_alert(&format!("The {} file wasn't loaded, since only known C-Grain files are accepted.\n{}", kind_name, msg));
+            return None;
+        },
+        Err(_) => log::info!("The {} file doesn't match a known C-Grain export layout.", kind_name),
+    }//end matching whether the file has a known layout
+    if export_kind == ExportFileKind::Csv && !data.has_generated_headers() {apply_header_mapping(gui, &mut data);}
+    if data.has_generated_headers() {
+        gui.integrated_dialog_alert(&format!("The header row of the {} file (row index {}) looks like data instead of column names,\nso it was read as data and the columns were named \"Column 1\", \"Column 2\", etc.\nIf this file has no header row, you can provide headers by right-clicking the csv input button.", kind_name, config.csv_header_row));
+    }//end if we had to make up headers for the file
+    match workflow::apply_after_load_script(&config.script_path, kind_name, data) {
+        Ok(data) => Some(data),
+        Err((data, msg)) => {gui.integrated_dialog_alert(&format!("The user script couldn't change the {} data, so it was loaded as it is.\n{}", kind_name, msg)); Some(data)},
+    }//end matching whether the script could change the data
+}//end load_input_data(gui, file_path, export_kind)
+
+/// Renames the columns of the csv data using the mapping saved for its
+/// layout of headers, if there is one. Otherwise, if any columns needed
+/// by the config are missing, lets the user choose which columns to use
+/// instead, and saves their choices for later files with the same headers.
+fn apply_header_mapping(gui: &mut GUI, data: &mut Data) {
+    let mut config = gui.get_config_store();
+    let fingerprint = schema::get_header_fingerprint(data.get_headers_ref());
+    if let Some(header_mapping) = config.header_mappings.iter().find(|header_mapping| header_mapping.fingerprint == fingerprint) {
+        match pipeline::apply_header_aliases(data, &header_mapping.aliases) {
+            Ok(renamed) => log::info!("Renamed {} columns using the mapping saved for this layout of csv file.", renamed),
+            Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't use the column mapping saved for this layout of csv file.\n{}", msg)),
+        }//end matching whether we could apply the saved mapping
+        return;
+    }//end if we have a saved mapping for this layout
+
+    // columns renamed by the alias mapping step aren't missing
+    let aliased_headers = match config.pipeline_steps.contains(&PipelineStep::AliasMapping) {
+        true => pipeline::get_aliased_headers(data.get_headers_ref(), &config.csv_header_aliases),
+        false => data.get_headers_ref().clone(),
+    };
+    let missing_headers = config.get_missing_csv_headers(&aliased_headers);
+    if missing_headers.len() == 0 {return;}
+    if !gui.integrated_dialog_yes_no(&format!("The csv file doesn't have these columns needed by your settings:\n{}\nWould you like to choose which of its columns to use instead?\nYour choices will be used again for files with the same columns.", missing_headers.join("\n"))) {return;}
+    let aliases: Vec<String> = match gui.show_header_mapping_dialog(&missing_headers, data.get_headers_ref()) {
+        Some(chosen_headers) => chosen_headers.iter().map(|(file_header, needed_header)| format!("{} -> {}", file_header, needed_header)).collect(),
+        None => return,
+    };
+    if aliases.len() == 0 {return;}
+    match pipeline::apply_header_aliases(data, &aliases) {
+        Ok(renamed) => {
+            log::info!("Renamed {} columns using the chosen mapping.", renamed);
+            config.header_mappings.push(HeaderMapping { fingerprint, aliases });
+            gui.set_config_store(&config);
+        },
+        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't rename the chosen columns.\n{}", msg)),
+    }//end matching whether we could rename the chosen columns
+}//end apply_header_mapping(gui, data)
+
+/// Pushes the merged summary in sink_output to the Google Sheet from config,
+/// if that's enabled, giving the result, or None if it isn't enabled.
+#[cfg(feature = "google-sheets")]
+fn push_to_google_sheets(sink_output: &SinkOutput, config: &ConfigStore) -> Option<Result<String,String>> {
+    if !sink::GoogleSheetsSink.is_enabled(config) {return None;}
+    Some(sink::GoogleSheetsSink.write(sink_output, config, &PathBuf::new()))
+}//end push_to_google_sheets(sink_output, config)
+
+/// Without the google-sheets feature, nothing is pushed to Google Sheets.
+#[cfg(not(feature = "google-sheets"))]
+fn push_to_google_sheets(_sink_output: &SinkOutput, config: &ConfigStore) -> Option<Result<String,String>> {
+    if config.google_sheets_enabled {log::warn!("Google Sheets output is enabled in the config, but this build doesn't include Google Sheets, so it was skipped.");}
+    None
+}//end push_to_google_sheets(_sink_output, config)
+
+/// The data and output sheets from the last successful processing,
+/// kept so that the output can be updated for only a few samples.
+struct LastRun {
+    /// The path of the output file that was written.
+    output: PathBuf,
+    /// The config used to write the output, used for sheet formatting.
+    config: ConfigStore,
+    /// The csv data that was processed, if any.
+    csv_data: Option<Data>,
+    /// The xml data that was processed, if any.
+    xml_data: Option<Data>,
+    /// (name of sheet, data in that sheet)
+    output_sheets: Vec<(String, SampleOutput)>,
+    /// The path of the csv input file, if any.
+    csv_input_file: Option<PathBuf>,
+    /// The path of the xml input file, if any.
+    xml_input_file: Option<PathBuf>,
+    /// (sample id, why it was flagged) for each sample left out of the
+    /// output after review. These are already removed from csv_data and xml_data.
+    excluded_samples: Vec<(String, String)>,
+    /// The lines of the Run Info sheet, as (label, value), even if the
+    /// sheet wasn't written.
+    run_info: Vec<(String, String)>,
+    /// The messages logged while processing, including any errors shown.
+    log: Vec<String>,
+    /// The recipients a workbook was written for, whose workbooks are
+    /// written again when samples are reprocessed.
+    recipient_profiles: Vec<RecipientProfile>,
+}//end struct LastRun
+
+/// Loads the input files and output file from session again, as if they
+/// were chosen with their buttons. Files which can't be found anymore,
+/// such as ones on a network share which isn't connected, are skipped.
+fn restore_session_files(gui: &mut GUI, session: &SessionState) {
+    let mut missing_files = Vec::new();
+    if let Some(csv_input_file) = &session.csv_input_file {
+        match csv_input_file.is_file() {
+            true => gui.send_file_message(InterfaceMessage::CSVInputFile(csv_input_file.clone())),
+            false => missing_files.push(csv_input_file),
+        }//end matching whether the csv file is still there
+    }//end if there was a csv file loaded
+    if let Some(xml_input_file) = &session.xml_input_file {
+        match xml_input_file.is_file() {
+            true => gui.send_file_message(InterfaceMessage::XMLInputFile(xml_input_file.clone())),
+            false => missing_files.push(xml_input_file),
+        }//end matching whether the xml file is still there
+    }//end if there was an xml file loaded
+    if let Some(output_file) = &session.output_file {
+        // the output file doesn't have to exist yet, only the folder it goes in
+        match preflight::get_output_folder(output_file).is_dir() {
+            true => gui.send_file_message(InterfaceMessage::OutputFile(output_file.clone())),
+            false => missing_files.push(output_file),
+        }//end matching whether the output folder is still there
+    }//end if there was an output file chosen
+    if missing_files.len() > 0 {
+        log::warn!("Some files from the last session couldn't be found, so they weren't restored.\n{}", missing_files.iter().map(|file_path| file_path.to_string_lossy()).collect::<Vec<_>>().join("\n"));
+    }//end if any files couldn't be restored
+}//end restore_session_files(gui, session)
+
+/// Updates the status bar with the name and row count of each loaded
+/// input file, the active preset, and how long the last processing took.
+fn update_status_bar(gui: &mut GUI, csv_input_file: &Option<PathBuf>, input_csv_data: &Option<Data>, xml_input_file: &Option<PathBuf>, input_xml_data: &Option<Data>, last_duration: &Option<Duration>) {
+    let describe_input = |input_file: &Option<PathBuf>, input_data: &Option<Data>| match (input_file, input_data) {
+        (Some(file_path), Some(data)) => format!("{} ({} rows)", paths::get_file_name(file_path), data.get_records_ref().len()),
+        _ => String::from("none"),
+    };
+    let preset_name = match gui.get_config_store().personalized_config_name {
+        preset_name if preset_name.is_empty() => String::from("none"),
+        preset_name => preset_name,
+    };
+    let duration_text = match last_duration {
+        Some(duration) => format!("{:.1} s", duration.as_secs_f64()),
+        None => String::from("not yet"),
+    };
+    gui.set_status_text(&format!("CSV: {}   |   XML: {}   |   Preset: {}   |   Last processing: {}", describe_input(csv_input_file, input_csv_data), describe_input(xml_input_file, input_xml_data), preset_name, duration_text));
+}//end update_status_bar(gui, csv_input_file, input_csv_data, xml_input_file, input_xml_data, last_duration)
+
+/// Asks the user where to save a zip bundle of run, and whether to include
+/// its input files, then writes the bundle with the output workbook, the
+/// provenance json describing how it was made, and the log of the run.
+fn export_run_bundle(gui: &mut GUI, run: &LastRun) {
+    let input_files: Vec<&PathBuf> = [&run.csv_input_file, &run.xml_input_file].into_iter().flatten().collect();
+    let include_inputs = input_files.len() > 0 && gui.integrated_dialog_yes_no("Would you like to include the input files in the bundle?\nThis makes the bundle larger, but lets the run be checked or processed again later.");
+    let bundle_path = match gui.choose_bundle_save_path(&bundle::get_bundle_path(&run.output)) {
+        Some(bundle_path) => bundle_path,
+        None => return,
+    };
+    let input_hashes: Vec<(String, String)> = input_files.iter().filter_map(|input_file| match manifest::hash_file(input_file) {
+        Ok(hash) => Some((paths::get_file_name(input_file), hash)),
+        Err(msg) => {log::warn!("Couldn't hash an input file for the provenance json.\n{}", msg); None},
+    }).collect();
+    // the log and run info can name samples, so they get the same pseudonyms as the output
+    let key = match workflow::get_output_sample_id_key(&run.output_sheets, &run.csv_data, &run.config) {
+        Ok(key) => key,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't anonymize the sample ids, so the bundle wasn't written.\n{}", msg)); return;},
+    };
+    let run_info: Vec<(String, String)> = run.run_info.iter().map(|(label, value)| (label.clone(), process::replace_sample_ids_in_text(value, &key))).collect();
+    let provenance = match bundle::get_provenance_json(&run_info, &input_hashes, &run.config) {
+        Ok(provenance) => provenance,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't describe how the output was made, so the bundle wasn't written.\n{}", msg)); return;},
+    };
+    let mut files = vec![(format!("output/{}", paths::get_file_name(&run.output)), run.output.clone())];
+    if include_inputs {
+        for input_file in input_files {files.push((format!("inputs/{}", paths::get_file_name(input_file)), input_file.clone()));}
+    }//end if we should bundle the input files
+    let text_files = vec![
+        (bundle::PROVENANCE_FILE_NAME.to_string(), provenance),
+        (bundle::LOG_FILE_NAME.to_string(), process::replace_sample_ids_in_text(&run.log.join("\n"), &key)),
+    ];
+    gui.start_wait();
+    match bundle::write_run_bundle(&bundle_path, &files, &text_files) {
+        Ok(file_count) => gui.integrated_dialog_message(&format!("Wrote the run bundle with {} files to {}", file_count, bundle_path.to_string_lossy())),
+        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the run bundle.\n{}", msg)),
+    }//end matching whether we could write the bundle
+    gui.end_wait();
+}//end export_run_bundle(gui, run)
+
+/// Gets the text of each output sheet from run, as (name of sheet, headers,
+/// text of each row), for showing in the results viewer.
+fn get_results_tables(run: &LastRun) -> Vec<(String, Vec<String>, Vec<Vec<String>>)> {
+    let mut results_tables = Vec::new();
+    for (sheet_name, sheet_data) in run.output_sheets.iter() {
+        let (headers, rows) = process::get_sheet_text_table(sheet_data, sheet_name, &run.config);
+        results_tables.push((sheet_name.clone(), headers, rows));
+    }//end getting text of each sheet
+    results_tables
+}//end get_results_tables(run)
+
+/// If the name of file_path matches one of the profile patterns in the
+/// config, switches the settings to the preset of that profile, so that
+/// files for different crops are processed with the right settings.
+fn apply_file_profile(gui: &mut GUI, file_path: &PathBuf) {
+    let config = gui.get_config_store();
+    match config.get_file_profile_config(file_path) {
+        Ok(Some((profile_name, profile_config))) => {
+            // no need to switch if we're already using this preset
+            if profile_name == config.personalized_config_name {return;}
+            gui.set_config_store(&profile_config);
+            gui.integrated_dialog_message(&format!("The name of \"{}\" matches a profile pattern, so the settings were switched to the {} preset.", file_path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(), profile_name));
+        },
+        Ok(None) => {},
+        Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't check the file name against the profile patterns, so the current settings will be used.\n{}", msg)),
+    }//end matching whether the file matches a profile
+}//end apply_file_profile(gui, file_path)
+
+/// Gets summary text of the csv data from run, such as for pasting into
+/// an email. If sample ids were anonymized in the output, then flagged
+/// samples are given by their pseudonyms.
+fn get_run_summary_text(run: &LastRun) -> Result<String,String> {
+    let csv_data = run.csv_data.as_ref().ok_or(String::from("The last output didn't use any csv data, which is needed for the summary."))?;
+    let (piped_csv, _) = pipeline::run_pipeline(csv_data, &run.config)?;
+    let mut run_summary = summary::get_run_summary(&piped_csv, &run.config)?;
+    let key = workflow::get_output_sample_id_key(&run.output_sheets, &run.csv_data, &run.config)?;
+    for (sample_id, _) in run_summary.flagged_samples.iter_mut() {
+        if let Some((_, pseudonym)) = key.iter().find(|(key_id, _)| key_id.eq(sample_id)) {*sample_id = pseudonym.clone();}
+    }//end replacing the id of each flagged sample with its pseudonym
+    let output_name = run.output.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    Ok(run_summary.to_text(&output_name))
+}//end get_run_summary_text(run)
+
+/// Writes a workbook of output_sheets for each of recipient_profiles,
+/// next to output, in the conventions of that recipient.
+fn write_recipient_workbooks(gui: &mut GUI, output_sheets: &Vec<(String, SampleOutput)>, recipient_profiles: &Vec<RecipientProfile>, config: &ConfigStore, output: &PathBuf) {
+    let mut workbooks_written = 0;
+    for profile in recipient_profiles.iter() {
+        // the layout is changed first, so renames can use the names of the new sheets and columns
+        let layout_sheets = process::apply_sheet_layout(output_sheets, profile.sheet_layout, config);
+        let recipient_sheets = match process::apply_recipient_profile(&layout_sheets, profile) {
+            Ok(recipient_sheets) => recipient_sheets,
+            Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't write the workbook for {}.\n{}", profile.name, msg)); continue;},
+        };
+        let mut wb = xlsx::get_workbook();
+        for (sheet_name, sheet_data) in recipient_sheets.iter() {
+            if let Err(msg) = xlsx::write_output_to_sheet(&mut wb, sheet_data, sheet_name, config) {
+                gui.integrated_dialog_alert(&format!("Ecountered an error while attempting to write data to worksheet {} for {}.\n{}", sheet_name, profile.name, msg));
+            }//end if writing to sheet failed
+        }//end writing each sheet in the conventions of this recipient
+        let recipient_path = process::get_split_output_path(output, &profile.name);
+        match FileLock::try_acquire(&recipient_path) {
+            Ok(_recipient_lock) => match xlsx::close_workbook(&mut wb, &recipient_path) {
+                Ok(_) => workbooks_written += 1,
+                Err(error) => gui.integrated_dialog_alert(&format!("Encountered an error while attempting to write the workbook for {}.\n{}", profile.name, error)),
+            },
+            Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't write the workbook for {}, since another copy of this program is writing it.\n{}", profile.name, msg)),
+        }//end matching whether we can lock the recipient workbook
+    }//end writing a workbook for each recipient
+    log::info!("Wrote {} workbooks for recipients next to {}", workbooks_written, output.to_string_lossy());
+}//end write_recipient_workbooks(gui, output_sheets, recipient_profiles, config, output)
+
+/// Asks the user which samples from the last run they want to reprocess,
+/// then processes only those samples with the current settings, replaces
+/// their rows in the output sheets, and writes the output file again.  
+/// The output is written with the current settings, which are kept in run
+/// for later, and Run Info gets a line giving the hash of those settings
+/// next to the hash of the settings of the first run.  
+/// Lot rows and the Treatment Comparison sheet are left as they were.
+fn reprocess_samples(gui: &mut GUI, run: &mut LastRun, locked_settings: &LockedSettings) {
+    let config = match gui.get_config_store().with_active_filter_set() {
+        Ok(run_config) => run_config,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't use the chosen filter set, so nothing was reprocessed.\n{}", msg)); return;},
+    };
+    if !workflow::ensure_locked_settings_kept(gui, locked_settings, &config) {return;}
+    if !ensure_warnings_allowed(gui, &run.csv_data, &run.xml_data, &config) {return;}
+    let mut sample_ids = Vec::new();
+    if let Some(ref csv_data) = run.csv_data {sample_ids.append(&mut process::get_sample_ids(csv_data, &config.csv_sample_id_header));}
+    if let Some(ref xml_data) = run.xml_data {
+        for sample_id in process::get_sample_ids(xml_data, &config.xml_sample_id_header) {
+            if !sample_ids.contains(&sample_id) {sample_ids.push(sample_id);}
+        }//end adding each xml sample we don't already have
+    }//end if we have xml data
+    let selected_ids = gui.show_sample_select_dialog(&sample_ids);
+    if selected_ids.len() == 0 {return;}
+
+    gui.start_wait();
+    let csv_subset = run.csv_data.as_ref()
+        .and_then(|csv_data| process::get_sample_subset(csv_data, &config.csv_sample_id_header, &selected_ids).ok())
+        .and_then(|subset| match pipeline::run_pipeline(&subset, &config) {
+            Ok((piped_subset, _)) => Some(piped_subset),
+            Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't run the processing pipeline on the selected samples, so csv sheets will be left as they were.\n{}", msg)); None},
+        });
+    let xml_subset = run.xml_data.as_ref().and_then(|xml_data| process::get_sample_subset(xml_data, &config.xml_sample_id_header, &selected_ids).ok());
+    let group_header = config.csv_stat_group_header.trim();
+    // (name of sheet, new rows of that group) when each group has its own stats sheet
+    let group_sheets = match config.csv_stat_group_output {
+        StatGroupOutput::SheetPerGroup if !group_header.is_empty() => csv_subset.as_ref().map(|subset| {
+            process::proc_csv_stat_cols(subset, &config).map(|stat_output| process::split_output_by_group("CSV_Stats", &stat_output, group_header))
+        }),
+        _ => None,
+    };
+    let mut group_sheets_found = Vec::new();
+    let mut rows_replaced = 0;
+    for (sheet_name, sheet_data) in run.output_sheets.iter_mut() {
+        let is_group_sheet = group_sheets.is_some() && sheet_name.starts_with("CSV_Stats_");
+        let new_output = match sheet_name.as_str() {
+            "CSV_Stats" => csv_subset.as_ref().map(|subset| process::proc_csv_stat_cols(subset, &config)),
+            // a reprocessed sample might not have this group anymore, so the sheet can get no new rows
+            _ if is_group_sheet => group_sheets.as_ref().map(|result| result.clone().map(|new_sheets| {
+                match new_sheets.into_iter().find(|(new_sheet_name, _)| new_sheet_name.eq(sheet_name)) {
+                    Some((_, new_sheet)) => new_sheet,
+                    None => SampleOutput { id_header: sheet_data.id_header.clone(), columns: sheet_data.columns.clone(), rows: Vec::new() },
+                }//end matching whether this group still has any rows
+            })),
+            "Class_Percents" => csv_subset.as_ref().map(|subset| process::proc_csv_class_per(subset, &config)),
+            "Diagnostics" => csv_subset.as_ref().map(|subset| process::proc_csv_diagnostics(subset, &config)),
+            "Frame_Stats" => csv_subset.as_ref().map(|subset| process::proc_csv_frame_stats(subset, &config)),
+            "Metrics" => csv_subset.as_ref().map(|subset| process::proc_csv_metrics(subset, &config)),
+            "XML_Sieve_Data" => xml_subset.as_ref().map(|subset| process::proc_xml_sieve_data(subset, &config)),
+            "Merged" => csv_subset.as_ref().zip(xml_subset.as_ref()).map(|(csv_subset, xml_subset)| process::proc_merged_output(csv_subset, xml_subset, &config)),
+            _ => None,
+        };
+        // the script runs on the new rows too, so they have the same columns as the rest of the sheet
+        let new_output = new_output.map(|result| result.and_then(|new_output| workflow::apply_before_write_script(&config.script_path, sheet_name, &new_output)));
+        match new_output {
+            Some(Ok(new_output)) if is_group_sheet => {
+                group_sheets_found.push(sheet_name.clone());
+                // samples which were reprocessed but aren't in this group anymore
+                let new_ids: Vec<String> = new_output.rows.iter().map(|row| row.sample_id.clone()).collect();
+                if let Some(Ok(new_sheets)) = group_sheets.as_ref() {
+                    sheet_data.rows.retain(|row| new_ids.contains(&row.sample_id) || !new_sheets.iter().any(|(_, new_sheet)| new_sheet.rows.iter().any(|new_row| new_row.sample_id.eq(&row.sample_id))));
+                }//end if we know which samples were reprocessed
+                rows_replaced += process::replace_sample_rows(sheet_data, new_output, "");
+            },
+            Some(Ok(new_output)) => rows_replaced += process::replace_sample_rows(sheet_data, new_output, group_header),
+            Some(Err(msg)) => gui.integrated_dialog_alert(&format!("An Error Occurred while trying to reprocess the {} sheet, so it was left as it was.\n{}", sheet_name, msg)),
+            None => {},
+        }//end matching whether we could reprocess this sheet
+        // reprocessed rows only have the columns processing gives
+        process::fill_formula_columns(sheet_data);
+    }//end reprocessing the selected samples in each sheet
+    // groups which are new to the selected samples get a sheet after the other groups
+    if let Some(Ok(new_sheets)) = group_sheets {
+        let mut insert_idx = run.output_sheets.iter().rposition(|(sheet_name, _)| sheet_name.starts_with("CSV_Stats_")).map(|idx| idx + 1).unwrap_or(run.output_sheets.len());
+        for (sheet_name, new_sheet) in new_sheets.into_iter().filter(|(sheet_name, _)| !group_sheets_found.contains(sheet_name)) {
+            match workflow::apply_before_write_script(&config.script_path, &sheet_name, &new_sheet) {
+                Ok(mut new_sheet) => {
+                    process::fill_formula_columns(&mut new_sheet);
+                    rows_replaced += new_sheet.rows.len();
+                    gui.log(&format!("Added the {} sheet for a group the selected samples didn't have before.", sheet_name));
+                    run.output_sheets.insert(insert_idx, (sheet_name, new_sheet));
+                    insert_idx += 1;
+                },
+                Err(msg) => gui.integrated_dialog_alert(&format!("An Error Occurred while trying to reprocess the {} sheet, so it was left out.\n{}", sheet_name, msg)),
+            }//end matching whether the script could run on the new sheet
+        }//end adding a sheet for each new group
+    }//end if each group has its own stats sheet
+    // reprocessed rows don't have flags yet, so every sample is flagged again
+    if config.is_flags_column_needed() {
+        match flags::get_sample_flags(run.csv_data.as_ref(), &run.output_sheets, &config) {
+            Ok(sample_flags) => flags::add_flags_column(&mut run.output_sheets, &sample_flags),
+            Err(msg) => gui.integrated_dialog_alert(&format!("An Error Occurred while trying to flag samples, so the Flags column wasn't updated.\n{}", msg)),
+        }//end matching whether we could flag the samples
+    }//end if we should update the flags column
+    // the other rows were made with the earlier settings, so Run Info records both
+    let get_settings_hash = |config: &ConfigStore| serde_json::to_string(config).map(|config_serial| manifest::hash_bytes(config_serial.as_bytes())).unwrap_or_default();
+    if !run.run_info.iter().any(|(label, _)| label.eq("Settings Hash")) {
+        run.run_info.push(("Settings Hash".to_string(), get_settings_hash(&run.config)));
+    }//end if we haven't recorded the settings of the first run yet
+    let reprocessed = time::OffsetDateTime::now_utc().format(time::macros::format_description!("[year]-[month]-[day] [hour]:[minute]:[second] UTC")).unwrap_or_default();
+    run.run_info.push(("Reprocessed".to_string(), format!("{} samples at {}, with settings hash {}", selected_ids.len(), reprocessed, get_settings_hash(&config))));
+    // anything written from here on uses the settings the samples were reprocessed with
+    run.config = config;
+    if run.config.kernel_csv_export_enabled {
+        if let Some(ref subset) = csv_subset {
+            let kernel_csvs_written = workflow::get_output_sample_id_key(&run.output_sheets, &run.csv_data, &run.config)
+                .and_then(|key| process::write_sample_kernel_csvs(subset, &run.config, &process::get_kernel_csv_dir(&run.output), &key));
+            if let Err(msg) = kernel_csvs_written {
+                gui.integrated_dialog_alert(&format!("An Error Occurred while trying to write the kernel csv file for each sample!\n{}",msg));
+            }//end if we couldn't write the kernel csv files
+        }//end if we have csv data for the selected samples
+    }//end if we should write kernel csv files
+
+    match workflow::ensure_output_writable(gui, &run.output, false) {
+        Some(output) => run.output = output,
+        None => {gui.end_wait(); return;},
+    }//end matching whether we can write the output file
+    let anonymized_output = match workflow::get_anonymized_output(gui, &run.output_sheets, &run.csv_data, &run.config, &run.output) {
+        Ok(anonymized_output) => anonymized_output,
+        Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't anonymize the sample ids, so nothing was written.\n{}", msg)); gui.end_wait(); return;},
+    };
+    let (sheets_to_write, snapshot_data) = match anonymized_output {
+        Some((ref anonymized_sheets, ref anonymized_csv)) => (anonymized_sheets, anonymized_csv.as_ref()),
+        None => (&run.output_sheets, run.csv_data.as_ref()),
+    };
+    let run_info = match run.config.run_info_sheet_enabled || run.excluded_samples.len() > 0 {
+        true => Some(run.run_info.clone()),
+        false => None,
+    };
+    let signing_key = match workflow::get_output_signing_key(&run_info) {
+        Ok(signing_key) => signing_key,
+        Err(msg) => {gui.integrated_dialog_alert(&msg); gui.end_wait(); return;},
+    };
+    let sink_output = SinkOutput { sheets: sheets_to_write, input_snapshot: snapshot_data, run_info, signing_key: signing_key.as_deref() };
+    if !workflow::write_output_sinks(gui, &sink_output, &run.config, &run.output) {gui.end_wait(); return;}
+    gui.integrated_dialog_message(&format!("Updated {} rows for {} selected samples in \"{}\".", rows_replaced, selected_ids.len(), run.output.to_string_lossy()));
+    if run.config.split_output_enabled {
+        match workflow::get_output_sample_id_key(&run.output_sheets, &run.csv_data, &run.config) {
+            Ok(key) => workflow::write_split_workbooks(gui, &run.output_sheets, &key, &run.config, &run.output),
+            Err(msg) => gui.integrated_dialog_alert(&format!("Couldn't anonymize the sample ids, so the workbooks split by metadata column weren't written.\n{}", msg)),
+        }//end matching whether we could get the pseudonyms
+    }//end if we should also write a workbook for each metadata value
+    // recipient sheets, such as a sheet per metric, are made from the updated output
+    if run.recipient_profiles.len() > 0 {
+        write_recipient_workbooks(gui, sheets_to_write, &run.recipient_profiles, &run.config, &run.output);
+    }//end if we should also write the output for any recipients
+    gui.end_wait();
+}//end reprocess_samples(gui, run, locked_settings)
+
+/// Finds the samples in csv_data which should be reviewed before the output
+/// is written, such as those with few kernels, after running the processing
+/// pipeline, and lets the user choose which of them to exclude.  
+/// Returns the excluded samples along with why they were flagged, or None
+/// if the user cancelled processing.
+fn review_flagged_samples(gui: &mut GUI, csv_data: &Option<Data>, config: &ConfigStore) -> Option<Vec<(String, String)>> {
+    let csv_data = match csv_data {
+        Some(csv_data) => csv_data,
+        None => return Some(Vec::new()),
+    };
+    let flagged = match pipeline::run_pipeline(csv_data, config).and_then(|(piped_csv, _)| summary::get_flagged_samples(&piped_csv, config)) {
+        Ok(flagged) => flagged,
+        Err(msg) => {
+            gui.integrated_dialog_alert(&format!("Couldn't check for flagged samples, so none will be excluded.\n{}", msg));
+            return Some(Vec::new());
+        },
+    };
+    if flagged.len() == 0 {log::info!("No samples were flagged for review."); return Some(Vec::new());}
+    let excluded_ids = gui.show_exclusion_dialog(&flagged)?;
+    Some(flagged.into_iter().filter(|(sample_id, _)| excluded_ids.contains(sample_id)).collect())
+}//end review_flagged_samples(gui, csv_data, config)
+
+impl Frontend for GUI {
+    fn get_config_store(&self) -> ConfigStore {GUI::get_config_store(self)}
+    fn integrated_dialog_message(&mut self, txt: &str) {GUI::integrated_dialog_message(self, txt)}
+    fn integrated_dialog_alert(&mut self, txt: &str) {GUI::integrated_dialog_alert(self, txt)}
+    fn integrated_dialog_yes_no(&mut self, txt: &str) -> bool {GUI::integrated_dialog_yes_no(self, txt)}
+    fn integrated_dialog_message_choice(&mut self, txt: &str, options: Vec<&str>) -> Option<usize> {GUI::integrated_dialog_message_choice(self, txt, options)}
+    fn log(&mut self, msg: &str) {GUI::log(self, msg)}
+    fn start_wait(&mut self) {GUI::start_wait(self)}
+    fn end_wait(&mut self) {GUI::end_wait(self)}
+    fn notify_completion(&mut self, run_duration: Duration, output_name: &str) {GUI::notify_completion(self, run_duration, output_name)}
+    fn show_progress(&mut self, fraction: f64, stage: &str) {GUI::show_progress(self, fraction, stage)}
+    fn end_progress(&mut self) {GUI::end_progress(self)}
+    fn wait_for(&self, seconds: f64) {GUI::wait_for(self, seconds)}
+}//end impl Frontend for GUI
+
+/// Waits for the sweep from schedule::start_scheduled_processing() to finish, then
+/// lets the user know, showing the problems it had, if any.
+fn finish_scheduled_processing(gui: &mut GUI, worker: thread::JoinHandle<ScheduledFrontend>) {
+    match worker.join() {
+        Ok(frontend) => {
+            if let Some((run_duration, output_name)) = frontend.completion {gui.notify_completion(run_duration, &output_name);}
+            if frontend.alerts.len() > 0 {gui.integrated_dialog_alert(&format!("Scheduled processing had problems:\n\n{}", frontend.alerts.join("\n\n")));}
+        },
+        Err(_) => gui.integrated_dialog_alert("Scheduled processing stopped unexpectedly. There might be more information in the console."),
+    }//end matching whether the sweep finished
+}//end finish_scheduled_processing(gui, worker)
+
+/// Summarizes the csv file at csv_path without loading it, using
+/// process::proc_csv_file_streaming() on a worker thread, then writes the
+/// CSV_Stats and Class_Percents sheets to the enabled outputs at output.
+fn stream_large_csv(gui: &mut GUI, csv_path: &PathBuf, output: &PathBuf, locked_settings: &LockedSettings) {
+    let config = gui.get_config_store();
+    if !workflow::ensure_locked_settings_kept(gui, locked_settings, &config) {return;}
+    if let Err(msg) = process::check_streaming_supported(&config) {
+        gui.integrated_dialog_alert(&format!("Couldn't summarize the csv file without loading it. You can change these settings, or load the file as usual.\n{}", msg));
+        return;
+    }//end if the settings need the whole file loaded
+
+    let process_start = Instant::now();
+    gui.start_wait();
+    gui.log(&format!("Started summarizing \"{}\" a row at a time.", csv_path.to_string_lossy()));
+    let (worker_path, worker_config) = (csv_path.clone(), config.clone());
+    let worker_result = frontend::run_in_background(gui, move |progress| {
+        process::proc_csv_file_streaming(&worker_path, &worker_config, &|fraction| progress(fraction, "Reading the csv file"))
+    });
+    let output_sheets = match worker_result {
+        Ok(Ok(output_sheets)) => output_sheets,
+        Ok(Err(msg)) | Err(msg) => {
+            gui.integrated_dialog_alert(&format!("Couldn't summarize \"{}\".\n{}", csv_path.to_string_lossy(), msg));
+            gui.end_wait();
+            return;
+        },
+    };
+    let run_info = match config.run_info_sheet_enabled {
+        true => Some(workflow::get_run_info(&config, &Some(csv_path.clone()), &None, &Vec::new())),
+        false => None,
+    };
+    let output = match workflow::ensure_output_writable(gui, output, false) {
+        Some(output) => output,
+        None => {gui.end_wait(); return;},
+    };
+    let signing_key = match workflow::get_output_signing_key(&run_info) {
+        Ok(signing_key) => signing_key,
+        Err(msg) => {gui.integrated_dialog_alert(&msg); gui.end_wait(); return;},
+    };
+    let sink_output = SinkOutput { sheets: &output_sheets, input_snapshot: None, run_info, signing_key: signing_key.as_deref() };
+    if workflow::write_output_sinks(gui, &sink_output, &config, &output) {
+        let sample_count = output_sheets.first().map(|(_, sheet_data)| sheet_data.rows.len()).unwrap_or(0);
+        workflow::record_ledger_run(gui, &vec![csv_path], &output, &config, &output_sheets, None);
+        gui.integrated_dialog_message(&format!("Summarized {} samples from \"{}\" in {:.1} seconds, writing \"{}\".", sample_count, paths::get_file_name(csv_path), process_start.elapsed().as_secs_f64(), output.to_string_lossy()));
+    }//end if the output was written
+    gui.end_wait();
+}//end stream_large_csv(gui, csv_path, output, locked_settings)
+
+/// Reads the settings locked by an administrator from the admin config file,
+/// which is kept next to the regular config file.  
+/// If there's no admin config, or it can't be read, no settings are locked.
+fn read_locked_settings(gui: &mut GUI) -> LockedSettings {
+    let admin_config_path = match paths::get_config_path(config_store::ADMIN_CONFIG_NAME) {
+        Ok(admin_config_path) => admin_config_path,
+        Err(msg) => {log::warn!("Couldn't get admin config path: {}", msg); return LockedSettings::default();},
+    };
+    match config_store::try_read_locked_settings(&admin_config_path) {
+        Ok(locked_settings) => locked_settings,
+        Err(msg) => {
+            gui.integrated_dialog_alert(&format!("Couldn't read the admin config at {}, so no settings are locked.\n{}", admin_config_path.to_string_lossy(), msg));
+            LockedSettings::default()
+        },
+    }//end matching whether we could read the admin config
+}//end read_locked_settings(gui)
+
+/// Attempts to lock the config file, so that two copies of the program
+/// don't write the config at the same time.  
+/// If another copy holds the lock, the user can continue without saving
+/// the config, take over the lock if it was left behind by a crash, or quit.  
+/// Returns the lock if it was acquired.
+fn acquire_config_lock(gui: &mut GUI, config_name: &str) -> Option<FileLock> {
+    let config_path = match paths::get_config_path(config_name) {
+        Ok(config_path) => config_path,
+        Err(msg) => {log::warn!("Couldn't get config path to lock it: {}", msg); return None;},
+    };
+    match FileLock::try_acquire(&config_path) {
+        Ok(config_lock) => Some(config_lock),
+        Err(msg) => {
+            match gui.integrated_dialog_message_choice(&format!("It looks like another copy of this program is already open.\n{}\n\nIf both copies save the config, it could be corrupted, so this copy won't save config changes unless you take over.", msg), vec!["Continue", "Take Over", "Quit"]) {
+                Some(1) => match FileLock::force_acquire(&config_path) {
+                    Ok(config_lock) => Some(config_lock),
+                    Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't take over the config lock, so config changes won't be saved.\n{}", msg)); None},
+                },
+                Some(2) => {
+                    log::info!("Program Exiting!");
+                    std::process::exit(0);
+                },
+                _ => None,
+            }//end matching what the user wants to do about the lock
+        },
+    }//end matching whether we could lock the config
+}//end acquire_config_lock(gui, config_name)
+
 /// Tries to confirm that file information and data containers  
 /// are appropriate for what the user wants. If things are fine,
 /// returns true. Otherwise, returns false.
 fn ensure_data_valid_for_output(gui: &mut GUI, config_store: &Option<ConfigStore>, input_csv_data: &Option<Data>, input_xml_data: &Option<Data>, output_file: &mut Option<PathBuf>, csv_input_file: &Option<PathBuf>, xml_input_file: &Option<PathBuf>) -> bool {
     match config_store {
         Some(config) => {
-            if input_csv_data.is_none() && (config.csv_stat_columns_enabled || config.csv_class_percent_enabled) {gui.integrated_dialog_alert("You have enabled one of the CSV output columns, but you haven't loaded a CSV file!"); return false;}
-            if input_xml_data.is_none() && (config.xml_sieve_cols_enabled) {gui.integrated_dialog_alert("You have enabled output based on XML input, but you haven't loaded an XML file!"); return false;}
+            if input_csv_data.is_none() && config.is_csv_output_enabled() {gui.integrated_dialog_alert("You have enabled one of the CSV output columns, but you haven't loaded a CSV file!"); return false;}
+            if input_xml_data.is_none() && (config.xml_sieve_cols_enabled || config.merged_sheet_enabled) {gui.integrated_dialog_alert("You have enabled output based on XML input, but you haven't loaded an XML file!"); return false;}
             
             let csv_input_clone = csv_input_file.clone();
             let xml_input_clone = xml_input_file.clone();
 
             // lots of checking to make sure output file path is working correctly
-            let output_txt = gui.get_output_text();
+            let output_txt = match gui.get_output_text() {
+                output_txt if output_txt.is_empty() => config.output_name_template.clone(),
+                output_txt => output_txt,
+            };
+            let date = time::OffsetDateTime::now_utc().format(time::macros::format_description!("[year]-[month]-[day]")).unwrap_or_default();
+            let name_tokens = process::get_output_name_tokens(csv_input_file, xml_input_file, config, &date);
+            let output_txt = match process::expand_output_name(&output_txt, &name_tokens) {
+                Ok(output_txt) => output_txt,
+                Err(msg) => {gui.integrated_dialog_alert(&format!("Couldn't fill in the output file name.\n{}", msg)); return false;}
+            };
             if output_txt != "" && output_file.is_none() {
                 // gets directory of input file, either csv or xml depending on config
                 let input_dir = match config {
@@ -238,6 +1359,114 @@ fn ensure_data_valid_for_output(gui: &mut GUI, config_store: &Option<ConfigStore
     return true;
 }//end ensure_data_valid_for_output()
 
+/// Checks the loaded data for problems, such as missing columns or samples
+/// in only one file, and handles each with the warning action in config
+/// for its category. Warnings which prompt are asked about all at once.  
+/// The csv data is checked after the processing pipeline, so columns
+/// added by the pipeline aren't counted as missing.  
+/// Returns false if nothing should be processed.
+fn ensure_warnings_allowed(gui: &mut GUI, input_csv_data: &Option<Data>, input_xml_data: &Option<Data>, config: &ConfigStore) -> bool {
+    let piped_csv = input_csv_data.as_ref().map(|csv_data| match pipeline::run_pipeline(csv_data, config) {
+        Ok((piped_csv, _)) => piped_csv,
+        Err(_) => csv_data.clone(),
+    });
+    let mut prompt_warnings = Vec::new();
+    for (category, msg) in process::get_input_warnings(piped_csv.as_ref(), input_xml_data.as_ref(), config) {
+        match config.get_warning_action(category) {
+            WarningAction::Abort => {
+                gui.integrated_dialog_alert(&format!("{}: {}\nYour warning policies stop processing for this, so nothing was processed.", category.get_name(), msg));
+                return false;
+            },
+            WarningAction::Prompt => prompt_warnings.push(format!("{}: {}", category.get_name(), msg)),
+            WarningAction::Log => log::warn!("Warning, {}: {}", category.get_name(), msg),
+        }//end matching what to do about this warning
+    }//end handling each warning
+    if prompt_warnings.len() > 0 {
+        return gui.integrated_dialog_yes_no(&format!("Some problems were found in the loaded data.\n{}\nDo you want to process anyway?", prompt_warnings.join("\n")));
+    }//end if we should ask the user about any warnings
+    true
+}//end ensure_warnings_allowed(gui, input_csv_data, input_xml_data, config)
+
+/// If the config file at config_path still has the admin password as plain
+/// text, from before only its hash was kept, saves the hash of it in the
+/// data directory, then writes config back to config_path without it.
+fn move_legacy_admin_password(gui: &mut GUI, config_path: &PathBuf, config: &ConfigStore) {
+    let legacy_password = match fs::read_to_string(config_path).ok().and_then(|config_contents| config_store::get_legacy_admin_password(&config_contents)) {
+        Some(legacy_password) => legacy_password,
+        None => return,
+    };
+    let password_saved = secrets::get_admin_password_path().and_then(|password_path| match secrets::is_password_set(&password_path) {
+        // a password set since then is newer than the one in the config
+        true => Ok(()),
+        false => secrets::save_password_hash(&password_path, &legacy_password),
+    });
+    match password_saved.and_then(|_| config_store::try_write_config(config_path, config)) {
+        Ok(_) => log::info!("Moved the admin password out of the config file, keeping only its hash."),
+        Err(msg) => gui.integrated_dialog_alert(&format!("Your config file has the admin password as plain text, but we couldn't move it out of the config file, so it's still there.\n{}", msg)),
+    }//end matching whether we could move the password
+}//end move_legacy_admin_password(gui, config_path, config)
+
+/// Attempts to migrate the config file at config_path from a different
+/// version of the program, letting the user know which settings were added.  
+/// If successful, the migrated config is written back to config_path,
+/// set in the gui, and stored in config_store, and true is returned.  
+/// If the config file can't be migrated, false is returned.
+fn try_migrate_config(gui: &mut GUI, config_store: &mut Option<ConfigStore>, config_path: &PathBuf) -> bool {
+    let old_config = match fs::read_to_string(config_path) {
+        Ok(old_config) => old_config,
+        Err(_) => return false,
+    };
+    match config_store::migrate_config_json(&old_config) {
+        Ok((new_config, added_settings)) => {
+            let mut notice = format!("Your config file was from a different version of this program, so it has been updated for version {}.", env!("CARGO_PKG_VERSION"));
+            if added_settings.len() > 0 {notice.push_str(&format!("\nThese new settings were added with their default values:\n{}", added_settings.join(", ")));}
+            if let Err(msg) = config_store::try_write_config(config_path, &new_config) {
+                notice.push_str(&format!("\n\nWe couldn't save the updated config file, so you might see this message again next time.\nError message was \"{}\".", msg));
+            }//end if we couldn't write the migrated config
+            gui.integrated_dialog_message(&notice);
+            gui.set_config_store(&new_config);
+            *config_store = Some(new_config);
+            true
+        },
+        Err(msg) => {
+            log::warn!("Couldn't migrate config file, received error {}", msg);
+            false
+        },
+    }//end matching whether we could migrate the config
+}//end try_migrate_config()
+
+/// If the config has an update check location, checks it for a newer
+/// version of the program, and shows the release notes if there is one.  
+/// Problems reaching the update check location are only printed, since
+/// the location might just be unavailable, such as when off the network.
+fn check_for_updates(gui: &mut GUI, config_store: &Option<ConfigStore>) {
+    let location = match config_store {
+        Some(config) if !config.update_check_location.trim().is_empty() => config.update_check_location.trim().to_string(),
+        _ => return,
+    };
+    let release_info = if location.starts_with("http://") || location.starts_with("https://") {
+        match ureq::get(&location).timeout(Duration::from_secs(5)).call() {
+            Ok(response) => match response.into_string() {
+                Ok(contents) => update::parse_release_info(&contents),
+                Err(error) => Err(error.to_string()),
+            },
+            Err(error) => Err(error.to_string()),
+        }//end matching whether we could reach the url
+    } else {update::try_read_release_info_file(&location)};
+
+    match release_info {
+        Ok(release_info) => {
+            let current_version = env!("CARGO_PKG_VERSION");
+            if update::is_newer_version(current_version, &release_info.version) {
+                let mut notice = format!("A newer version of this program is available.\nYou have version {}, and the latest version is {}.", current_version, release_info.version);
+                if !release_info.notes.is_empty() {notice.push_str(&format!("\n\nRelease Notes:\n{}", release_info.notes));}
+                gui.integrated_dialog_message(&notice);
+            }//end if there's a newer version
+        },
+        Err(msg) => log::warn!("Couldn't check for updates at \"{}\", received error {}", location, msg),
+    }//end matching whether we could get release info
+}//end check_for_updates()
+
 /// Gets the config information from the config file.  
 /// If we encounter issues with that, walk the user through a fix via the gui.
 fn ensure_config_valid(gui: &mut GUI, config_store: &mut Option<ConfigStore>, config_path: &mut Option<PathBuf>, config_name: &str) {
@@ -247,16 +1476,13 @@ fn ensure_config_valid(gui: &mut GUI, config_store: &mut Option<ConfigStore>, co
     match config_store::try_read_config_path(config_name, false) {
         Ok(config_path_tmp) => {
             if !config_path_tmp.exists() {
-                let should_create_personal_file = gui.integrated_dialog_yes_no("The configuration hasn't been set up yet.\nWould you like to choose a preset configuration?");
-                let mut new_conf_stor = ConfigStore::default();
-                if should_create_personal_file {
-                    match gui.integrated_dialog_message_choice("Please choose the config preset you want.", vec!["Wheat", "Sorghum", "Other"]) {
-                        Some(0) => new_conf_stor = config_store::get_rhett_config(),
-                        Some(1) => new_conf_stor = config_store::get_scott_config(),
-                        Some(2) => new_conf_stor = ConfigStore::default(),
-                        _ => gui.integrated_dialog_message("Guided configuration setting cancelled (somehow?). We'll just use the default then."),
-                    }//end matching dialog result for config preset
-                }//end if we should create a personalized config file
+                let new_conf_stor = match gui.show_setup_wizard() {
+                    Some(new_conf_stor) => new_conf_stor,
+                    None => {
+                        gui.integrated_dialog_message("Guided configuration setup cancelled. We'll just use the default then.\nYou can always change the config yourself using the section on the right.");
+                        ConfigStore::default()
+                    },
+                };
                 match config_store::try_write_config(&config_path_tmp, &new_conf_stor) {
                     Ok(_) => {
                         gui.set_config_store(&new_conf_stor);
@@ -267,34 +1493,39 @@ fn ensure_config_valid(gui: &mut GUI, config_store: &mut Option<ConfigStore>, co
                 }//end matching whether or not we successfully wrote a new config file
             }//end if config_path_tmp doesn't point to a real file
             else {
+                let current_version = env!("CARGO_PKG_VERSION");
                 match config_store::try_read_config(&config_path_tmp) {
-                    Ok(config_store_tmp) => {
+                    Ok(mut config_store_tmp) => {
+                        if config_store_tmp.config_version != current_version {
+                            gui.integrated_dialog_message(&format!("Your config was last saved by version {} of this program, and has been updated for version {}.", config_store_tmp.config_version, current_version));
+                            config_store_tmp.config_version = current_version.to_string();
+                        }//end if config came from a different version
                         gui.set_config_store(&config_store_tmp);
                         *config_store = Some(config_store_tmp);
                     },
                     Err(msg) => {
-                        gui.integrated_dialog_alert(&format!("Could not read config file at path \"{}\".\nReceived error msg {}", config_path_tmp.to_string_lossy(), msg));
-                        let should_create_new = gui.integrated_dialog_yes_no("Problems with the config file might occur when changing versions.\nWhen the config file is deleted, the program will automatically create a new one by default.\nEven if a config file is not loaded, you can always set the config yourself using the section in the bottom right.\n\nWould you like to be delete the old config file and create a personalized one now?");
-                        if should_create_new {
-                            let mut new_conf_stor: Option<ConfigStore> = None;
-                            match gui.integrated_dialog_message_choice("Do you want a personalized config file?\nIf so, choose which preset you want:", vec!["Wheat", "Sorghum", "Other"]) {
-                                Some(0) => new_conf_stor = Some(config_store::get_rhett_config()),
-                                Some(1) => new_conf_stor = Some(config_store::get_scott_config()),
-                                Some(2) => new_conf_stor = Some(ConfigStore::default()),
-                                _ => gui.integrated_dialog_message("Guided configuration setting cancelled."),
-                            }//end matching dialog result
-                            if let Some(new_conf_stor) = new_conf_stor {
-                                match config_store::try_write_config(&config_path_tmp, &new_conf_stor) {
-                                    Ok(_) => {
-                                        gui.integrated_dialog_message("Congrats, we successfully wrote your changes to the config file.\nWhatever the problem was, it should be fixed.\nIf you continue seeing messages about this everytime you open the application, please contact the developer.");
-                                        *config_store = Some(new_conf_stor);
-                                    },
-                                    Err(msg) => gui.integrated_dialog_alert(&format!("We couldn't write your config to the file, though you should still be able\nto use the program for now with the config you selected.\nError message was \"{}\".\nIf this operation keeps failing, please contact the developer.", msg))
-                                }//end matching whether or not we can write to file
-                            }//end if we have a new config store to write
-                        }//end if we get the ok to make a new config file
+                        if try_migrate_config(gui, config_store, &config_path_tmp) {log::info!("Migrated config file after read error {}", msg);}
+                        else {
+                            gui.integrated_dialog_alert(&format!("Could not read config file at path \"{}\".\nReceived error msg {}", config_path_tmp.to_string_lossy(), msg));
+                            let should_create_new = gui.integrated_dialog_yes_no("Problems with the config file might occur when changing versions.\nWhen the config file is deleted, the program will automatically create a new one by default.\nEven if a config file is not loaded, you can always set the config yourself using the section in the bottom right.\n\nWould you like to be delete the old config file and create a personalized one now?");
+                            if should_create_new {
+                                let new_conf_stor = gui.show_setup_wizard();
+                                if new_conf_stor.is_none() {gui.integrated_dialog_message("Guided configuration setup cancelled.");}
+                                if let Some(new_conf_stor) = new_conf_stor {
+                                    match config_store::try_write_config(&config_path_tmp, &new_conf_stor) {
+                                        Ok(_) => {
+                                            gui.set_config_store(&new_conf_stor);
+                                            gui.integrated_dialog_message("Congrats, we successfully wrote your changes to the config file.\nWhatever the problem was, it should be fixed.\nIf you continue seeing messages about this everytime you open the application, please contact the developer.");
+                                            *config_store = Some(new_conf_stor);
+                                        },
+                                        Err(msg) => gui.integrated_dialog_alert(&format!("We couldn't write your config to the file, though you should still be able\nto use the program for now with the config you selected.\nError message was \"{}\".\nIf this operation keeps failing, please contact the developer.", msg))
+                                    }//end matching whether or not we can write to file
+                                }//end if we have a new config store to write
+                            }//end if we get the ok to make a new config file
+                        }//end else we need to fall back on making a new config
                     }//end case of not being able to parse file at config_path_tmp
                 }//end matching whether we can read file at config_path_tmp
+                if let Some(ref config) = config_store {move_legacy_admin_password(gui, &config_path_tmp, config);}
                 *config_path = Some(config_path_tmp);
             }//end else the config file already exists
         },
@@ -0,0 +1,1045 @@
+use std::{fmt, fs::File, io::BufReader};
+
+use csv::{Reader, StringRecord};
+use quick_xml::events::Event;
+
+/// Holds the value within a Cell, which might be a String, Int, or Float.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DataVal{
+    Int(i64),
+    String(String),
+    Float(f64),
+}//end enum ColumnType
+
+impl fmt::Display for DataVal {
+    /// Writes the string representation of the
+    /// underlying data, so to_string() gives the same as format!()
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataVal::Int(i) => write!(f, "{}", i),
+            DataVal::String(s) => write!(f, "{}", s),
+            DataVal::Float(fl) => write!(f, "{}", fl),
+        }//end matching self
+    }//end fmt()
+}//end impl Display for DataVal
+/// Represents an individual cell of data,
+/// holding a copy of the header it's under.  
+/// This struct is largely intended to be used by 
+/// DataRow and Data structs.  
+/// For more info, see documentation example for DataCell::new() and DataCell::new_from_val().
+#[derive(Clone, PartialEq, Debug)]
+pub struct DataCell {
+    header: String,
+    data: DataVal,
+}//end struct DataLine
+
+#[allow(dead_code)]
+impl DataCell {
+    /// Constructs a new DataLine, automatically creating
+    /// the proper DataVal by parsing and testing value.  
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::DataVal;
+    /// use usda_c_grain_sum::model::DataCell;
+    /// 
+    /// let header = String::from("Length");
+    /// let val_str = String::from("5.2");
+    /// 
+    /// let datacell = DataCell::new(&header, val_str);
+    /// 
+    /// assert_eq!(*datacell.get_data(), DataVal::Float(5.2));
+    /// ```
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::DataVal;
+    /// use usda_c_grain_sum::model::DataCell;
+    /// 
+    /// let header = String::from("Red");
+    /// let val_str = String::from("55");
+    /// 
+    /// let datacell = DataCell::new(&header, val_str);
+    /// 
+    /// assert_eq!(*datacell.get_data(), DataVal::Int(55));
+    /// ```
+    /// 
+    pub fn new(header: &String, value: String) -> DataCell {
+        // test if value is int
+        match value.parse::<i64>() {
+            Ok(i) => {
+                DataCell {
+                    header: header.to_owned(),
+                    data: DataVal::Int(i)
+                }//end struct construction
+            },//end Ok int Case
+            Err(_) => {
+                // test if value is float
+                match value.parse::<f64>() {
+                    Ok(f) => {
+                        DataCell {
+                            header: header.to_owned(),
+                            data: DataVal::Float(f),
+                        }//end struct Construction
+                    },// end Ok float Case
+                    Err(_) => {
+                        DataCell {
+                            header: header.to_owned(),
+                            data: DataVal::String(value),
+                        }//end struct Construction
+                    }//end Err float, must be str Case
+                }//end matching if value is float
+            },//end Err int, test float Case
+        }//end matching if value is int
+    }//end fn new()
+
+    /// Constructs a DataCell from a DataVal object without needing to do String parsing.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::DataVal;
+    /// use usda_c_grain_sum::model::DataCell;
+    /// 
+    /// let header = String::from("Area");
+    /// let dataval = DataVal::Float(5.4);
+    /// 
+    /// let datacell = DataCell::new_from_val(&header, dataval.clone());
+    /// 
+    /// assert_eq!(*datacell.get_data(), dataval);
+    /// ```
+    pub fn new_from_val(header: &String, value: DataVal) -> DataCell {
+        DataCell {
+            header: header.to_owned(),
+            data: value,
+        }//end struct construction
+    }//end new_from_val(header, value)
+
+    /// Gets reference to the header label of this cell.
+    pub fn get_header(&self) -> &String {&self.header}
+    /// Gets reference to the DataVal of this cell.
+    pub fn get_data(&self) -> &DataVal {&self.data}
+}
+
+
+/// Represents a single row of data, with each cell in that row
+/// being represented by a DataCell.  
+/// Also holds a copy of the index of this Row in the larger Data struct.  
+/// This struct is largely meant to be constructed by Data.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// 
+/// // create the column headers
+/// let header_0 = String::from("Area");
+/// let header_1 = String::from("Length");
+/// let header_2 = String::from("Width");
+/// let header_3 = String::from("Thickness");
+/// 
+/// // create the cells to go in the row
+/// let mut cell_vec: Vec<DataCell> = Vec::new();
+/// cell_vec.push(DataCell::new_from_val(&header_0, DataVal::Float(5.4)));
+/// cell_vec.push(DataCell::new_from_val(&header_1, DataVal::Float(3.7)));
+/// cell_vec.push(DataCell::new_from_val(&header_2, DataVal::Float(1.5)));
+/// cell_vec.push(DataCell::new_from_val(&header_3, DataVal::Float(1.3)));
+/// 
+/// // create the row itself
+/// let row_index = 0;
+/// let datarow = DataRow::new(row_index, cell_vec.clone());
+/// 
+/// // test the values
+/// assert_eq!(*datarow.get_row_idx(), 0);
+/// assert_eq!(*datarow.get_row_data(), cell_vec);
+/// assert_eq!(*datarow.get_data(0).unwrap().get_data(), DataVal::Float(5.4));
+/// assert_eq!(*datarow.get_data(1).unwrap().get_data(), DataVal::Float(3.7));
+/// assert_eq!(*datarow.get_data(2).unwrap().get_data(), DataVal::Float(1.5));
+/// assert_eq!(*datarow.get_data(3).unwrap().get_data(), DataVal::Float(1.3));
+/// assert_eq!(datarow.get_data(4), None);
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct DataRow {
+    row_idx: usize,
+    row_data: Vec<DataCell>,
+}//end struct DataRow
+
+#[allow(dead_code)]
+impl DataRow {
+    /// Constructs a new DataRow
+    pub fn new(row_idx: usize, row_data: Vec<DataCell>) -> DataRow {
+        DataRow {
+            row_idx,
+            row_data,
+        }//end struct construction
+    }//end new()
+
+    /// Gets reference to row index in of this DataRow.
+    pub fn get_row_idx(&self) -> &usize {&self.row_idx}
+    /// Gets Reference to the vector of DataCells contained in this struct.
+    pub fn get_row_data(&self) -> &Vec<DataCell> {&self.row_data}
+    /// Returns a reference to the particular DataCell if the index is valid.  
+    /// If the index is out of bounds, returns None.
+    pub fn get_data(&self, idx: usize) -> Option<&DataCell> {self.row_data.get(idx)}
+}//end impl for DataRow
+
+/// Holds all the data from one csv/xlsx file.  
+/// Uses something like "Parse, don't Validate" to ensure
+/// data is accurate to the file.  
+/// Because of this, it is intended for processing functions to 
+/// run filters and processing on references to the records 
+/// contained here instead of mutating the struct itself.  
+/// The component structs, DataRow and DataCell, also
+/// reflect this design.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// use usda_c_grain_sum::model::Data;
+/// 
+/// // create the column headers
+/// let mut column_headers: Vec<String> = Vec::new();
+/// let header_0 = String::from("Length");
+/// let header_1 = String::from("Width");
+/// let header_2 = String::from("Thickness");
+/// column_headers.push(header_0.clone());
+/// column_headers.push(header_1.clone());
+/// column_headers.push(header_2.clone());
+/// 
+/// // create vectors of DataCells, to be made into DataRows
+/// let mut cell_row_0: Vec<DataCell> = Vec::new();
+/// cell_row_0.push(DataCell::new_from_val(&header_0, DataVal::Float(5.4)));
+/// cell_row_0.push(DataCell::new_from_val(&header_1, DataVal::Float(3.2)));
+/// cell_row_0.push(DataCell::new_from_val(&header_2, DataVal::Float(2.1)));
+/// let mut cell_row_1: Vec<DataCell> = Vec::new();
+/// cell_row_1.push(DataCell::new_from_val(&header_0, DataVal::Float(6.7)));
+/// cell_row_1.push(DataCell::new_from_val(&header_1, DataVal::Float(4.5)));
+/// cell_row_1.push(DataCell::new_from_val(&header_2, DataVal::Float(2.9)));
+/// 
+/// // construct the two DataRows and add them to a Vec
+/// let mut datarow_vec: Vec<DataRow> = Vec::new();
+/// let datarow_0 = DataRow::new(0, cell_row_0);
+/// let datarow_1 = DataRow::new(1, cell_row_1);
+/// datarow_vec.push(datarow_0);
+/// datarow_vec.push(datarow_1);
+/// 
+/// // construct the Data struct
+/// let data = Data::from_row_data(column_headers.clone(), datarow_vec.clone());
+/// 
+/// // test that headers are correct
+/// assert_eq!(*data.get_headers_ref(), column_headers);
+/// assert_eq!(data.get_header_index("Length").unwrap(), 0);
+/// assert_eq!(data.get_header_index("Width").unwrap(), 1);
+/// assert_eq!(data.get_header_index("Thickness").unwrap(), 2);
+/// assert_eq!(*data.get_header_from_index(0).unwrap(), header_0);
+/// assert_eq!(*data.get_header_from_index(1).unwrap(), header_1);
+/// assert_eq!(*data.get_header_from_index(2).unwrap(), header_2);
+/// 
+/// // test that records are correct
+/// assert_eq!(*data.get_records_ref(), datarow_vec);
+/// assert_eq!(*data.get_record(0,0).unwrap().get_data(), DataVal::Float(5.4));
+/// assert_eq!(*data.get_record(0,1).unwrap().get_data(), DataVal::Float(3.2));
+/// assert_eq!(*data.get_record(0,2).unwrap().get_data(), DataVal::Float(2.1));
+/// assert_eq!(*data.get_record(1,0).unwrap().get_data(), DataVal::Float(6.7));
+/// assert_eq!(*data.get_record(1,1).unwrap().get_data(), DataVal::Float(4.5));
+/// assert_eq!(*data.get_record(1,2).unwrap().get_data(), DataVal::Float(2.9));
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct Data {
+    headers: Vec<String>,
+    records: Vec<DataRow>,
+    headers_generated: bool,
+}//end struct Data
+
+#[allow(dead_code)]
+impl Data {
+    /// Reads all csv info into Data struct from reader.
+    /// Any UTF-8 BOM left on the first header is removed, and rows at the end
+    /// of the file which are entirely blank (such as ",,,,") are ignored.
+    /// After those blank rows are removed, the last footer_rows_to_skip rows
+    /// are also discarded, which is useful for the summary/footer row
+    /// that the C-Grain sometimes appends to the end of a file.
+    /// 
+    /// The row at the 0-based index header_row is used as the header row,
+    /// and any rows before it are ignored. If manual_headers is not empty,
+    /// then the file is assumed to have no header row, so manual_headers are
+    /// used as the headers and the row at header_row is read as the first row
+    /// of data instead.  
+    /// If manual_headers is empty and the header row looks like data instead
+    /// of a header (see looks_like_header_row()), then headers of the form
+    /// "Column 1", "Column 2", etc are generated, the row is read as data,
+    /// and has_generated_headers() will return true for the returned Data.
+    /// 
+    /// Rows which don't have the same number of cells as there are headers
+    /// are skipped, so it is recommended that the reader is built with
+    /// flexible set to true, allowing rows before the header row to have
+    /// a different length.
+    /// Todo: Maybe look into csvs_convert crate to convert to database for storage/speed
+    pub fn from_csv_reader(mut reader: Reader<File>, header_row: usize, manual_headers: &Vec<String>, footer_rows_to_skip: usize) -> Option<Data> {
+        // we treat the header record of the reader as just another row
+        let mut row_strs: Vec<Result<StringRecord, csv::Error>> = match reader.headers() {
+            Ok(header_rec) => vec![Ok(header_rec.clone())],
+            Err(_) => return None,
+        };
+        row_strs.extend(reader.records());
+        Data::from_string_records(row_strs, header_row, manual_headers, footer_rows_to_skip)
+    }//end from_csv_reader()
+
+    /// Reads rows which have already been split into cells into a Data
+    /// struct, the same way as from_csv_reader(), including finding the
+    /// header row, checking whether it looks like data, and skipping footer
+    /// rows. This lets other sources, such as xlsx workbooks, be read the
+    /// same way as csv files.  
+    /// Rows which couldn't be read are logged and skipped.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use csv::StringRecord;
+    /// use usda_c_grain_sum::model::Data;
+    /// 
+    /// let get_rows = || vec![
+    ///     Ok(StringRecord::from(vec!["C-Grain Export", ""])),
+    ///     Ok(StringRecord::from(vec!["external-sample-id", "Area"])),
+    ///     Ok(StringRecord::from(vec!["S1", "12.5"])),
+    ///     Ok(StringRecord::from(vec!["Total", "12.5"])),
+    /// ];
+    /// let data = Data::from_string_records(get_rows(), 1, &Vec::new(), 1).unwrap();
+    /// assert_eq!(data.get_headers(), vec!["external-sample-id", "Area"]);
+    /// assert_eq!(data.get_records().len(), 1);
+    /// 
+    /// // a header row which looks like data is read as data
+    /// let data = Data::from_string_records(get_rows(), 2, &Vec::new(), 0).unwrap();
+    /// assert!(data.has_generated_headers());
+    /// assert_eq!(data.get_records().len(), 2);
+    /// ```
+    pub fn from_string_records(mut row_strs: Vec<Result<StringRecord, csv::Error>>, header_row: usize, manual_headers: &Vec<String>, footer_rows_to_skip: usize) -> Option<Data> {
+        if let Some(Ok(first_row)) = row_strs.first_mut() {
+            let first_row_trimmed: StringRecord = first_row.iter().enumerate()
+                .map(|(idx, cell)| if idx == 0 {cell.trim_start_matches('\u{feff}')} else {cell})
+                .collect();
+            *first_row = first_row_trimmed;
+        }//end removing BOM from the first cell, if it somehow made it through
+
+        // remove any trailing garbage before we do the parsing
+        while let Some(Ok(last_record)) = row_strs.last() {
+            if last_record.iter().all(|cell| cell.trim() == "") {row_strs.pop();}
+            else {break;}
+        }//end removing trailing blank rows
+        row_strs.truncate(row_strs.len().saturating_sub(footer_rows_to_skip));
+
+        // figure out where the headers are coming from
+        let mut row_strs = row_strs.into_iter().skip(header_row);
+        let mut headers_generated = false;
+        let mut first_data_row = None;
+        let headers: Vec<String> = {
+            if manual_headers.len() > 0 {
+                manual_headers.clone()
+            } else {
+                match row_strs.next() {
+                    Some(Ok(header_rec)) => {
+                        if Data::looks_like_header_row(&header_rec) {
+                            header_rec.iter().map(|header| header.to_string()).collect()
+                        } else {
+                            log::warn!("The header row at index {} looks like data, so generic headers will be used instead.", header_row);
+                            headers_generated = true;
+                            let generated_headers = (1..=header_rec.len()).map(|col_num| format!("Column {}", col_num)).collect();
+                            first_data_row = Some(Ok(header_rec));
+                            generated_headers
+                        }//end else the header row is really data
+                    },
+                    Some(Err(error)) => {log::error!("Couldn't read header row:\n{}", error); return None;},
+                    None => return None,
+                }//end matching whether we can get the header row
+            }//end else we need to get headers from the file
+        };
+
+        let mut data_records: Vec<DataRow> = Vec::new();
+
+        // Parse records from everything in the csvs
+        for (row_idx, row_str) in first_data_row.into_iter().chain(row_strs).enumerate() {
+            match row_str {
+                Ok(row_record) => {
+                    // row_record is format of StringRecord(["893", "202403190019", "23GRY_DTD_264"...])
+                    if row_record.len() != headers.len() {
+                        log::warn!("Skipping row idx {} because it has {} cells, but we have {} headers.", row_idx, row_record.len(), headers.len());
+                        continue;
+                    }//end if this row doesn't match up with the headers
+                    let mut tmp_row_data = Vec::new();
+                    for (col_idx, cell_str) in row_record.into_iter().enumerate() {
+                        match headers.get(col_idx) {
+                            Some(header) => {
+                                let new_data_cell = DataCell::new(header, cell_str.to_string());
+                                tmp_row_data.push(new_data_cell);
+                            },
+                            None => {log::warn!("Couldn't find header at col index {}. We have {} headers.", col_idx, headers.len())}
+                        }//end matching whether we can get the header
+                    }//end looping over each cell in this row
+                    // add this whole row of data as a new DataRow
+                    let new_data_row = DataRow::new(row_idx, tmp_row_data);
+                    data_records.push(new_data_row);
+                },
+                Err(error) => log::warn!("{}", error),
+            }//end matching whether we got this row correctly
+        }//end looping over each non-header record/row in csv
+        return Some( Data {headers, records: data_records, headers_generated} );
+    }//end from_string_records(row_strs, header_row, manual_headers, footer_rows_to_skip)
+
+    /// Tries to guess whether the given csv row is a header row, rather than
+    /// a row of data. A row is considered to be a header row if it has at
+    /// least one non-empty cell, and less than half of its non-empty cells
+    /// can be read as numbers.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use csv::StringRecord;
+    /// use usda_c_grain_sum::model::Data;
+    /// 
+    /// let header_row = StringRecord::from(vec!["external-sample-id", "Area", "Length"]);
+    /// let data_row = StringRecord::from(vec!["202403190019", "12.5", "5.2"]);
+    /// 
+    /// assert!(Data::looks_like_header_row(&header_row));
+    /// assert!(!Data::looks_like_header_row(&data_row));
+    /// ```
+    pub fn looks_like_header_row(row: &StringRecord) -> bool {
+        let non_empty_cells: Vec<&str> = row.iter().map(|cell| cell.trim()).filter(|cell| !cell.is_empty()).collect();
+        let numeric_cells = non_empty_cells.iter().filter(|cell| cell.parse::<f64>().is_ok()).count();
+        non_empty_cells.len() > 0 && numeric_cells * 2 < non_empty_cells.len()
+    }//end looks_like_header_row(row)
+
+    /// Reads data in from an xml file.
+    pub fn from_xml_reader(mut reader: quick_xml::Reader<BufReader<File>>, tags_to_include: Option<Vec<String>>, sample_closing_tag: Option<&[u8]>) -> Result<Data,String> {
+        let mut buf = Vec::new();
+
+        let mut data_rows: Vec<DataRow> = Vec::new();
+        let mut data_cells: Vec<DataCell> = Vec::new();
+
+        let tags_to_include = tags_to_include.unwrap_or(vec!["sample-id".to_string(),"reference".to_string()]);
+        let sample_start_end_tag = sample_closing_tag.unwrap_or(b"sample-result");
+        let sieving_starts_with = b"filter-sieving";
+        let mut most_recent_tag = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Err(e) => return Err(format!("Encountered error at position {}: {:?}",reader.buffer_position(),e)),
+                Ok(Event::Eof) => break,
+
+                Ok(Event::Start(byte_start)) => {
+                    let tag_name = String::from_utf8(byte_start.name().as_ref().to_vec()).unwrap();
+                    if tags_to_include.contains(&tag_name) || tag_name.as_bytes().starts_with(sieving_starts_with) {
+                        most_recent_tag = Some(tag_name);
+                    }//end cases of tag being relevant
+                }, //end start event case
+                Ok(Event::Text(btxt)) => {
+                    if most_recent_tag.is_some() {
+                        let cur_tag = most_recent_tag.unwrap();
+                        let txt = btxt.unescape().unwrap().into_owned();
+                        let data_cell = DataCell::new(&cur_tag, txt);
+                        data_cells.push(data_cell);
+                        most_recent_tag = Some(cur_tag);
+                    }//end if we have a recent tag
+                },
+                Ok(Event::End(bytes_end)) => {
+                    // assume tag is either end of sample, valid for most recent, or not important
+                    if bytes_end.name().as_ref().eq(sample_start_end_tag) {
+                        let datarow = DataRow::new(data_rows.len(),data_cells.clone());
+                        data_cells.clear();
+                        data_rows.push(datarow);
+                    }//end if this is the end of a sample
+                    else if most_recent_tag.is_some() {
+                        let m_r_t = most_recent_tag.unwrap();
+                        // check for current tag closing, updating most_recent
+                        if m_r_t.as_bytes().eq(bytes_end.name().as_ref()) {
+                            most_recent_tag = None;
+                        } else {most_recent_tag = Some(m_r_t);}
+                    }//end if there was a recent tag
+                },
+
+                Ok(event) => log::warn!("Unhandled event {:?}",event),
+            }//end matching reader events
+            buf.clear();
+        }//end looping while we have stuff to read from file
+
+        /*
+        Instead of checking for new headers on every sample (within the loop),
+        we'll assume that each sample has the same headers, and can thus be gathered
+        by looking at each DataCell in the first DataRow.
+         */
+        let mut headers_vec = Vec::new();
+        if let Some(first_row) = data_rows.first() {
+            for data_cell in first_row.get_row_data() {
+                let this_header = data_cell.get_header();
+                if !headers_vec.contains(this_header) {
+                    headers_vec.push(data_cell.get_header().clone());
+                }//end if we found a new header
+            }//end looping over each cell in first row
+        }//end if we have at least one row
+
+        let data = Data::from_row_data(headers_vec, data_rows);
+        return Ok(data);
+    }//end from_xml_reader(reader)
+
+    /// Constructs a Data struct from a vector of headers and DataRows.
+    /// 
+    /// 
+    pub fn from_row_data(headers: Vec<String>, row_data: Vec<DataRow>) -> Data {
+        Data {
+            headers,
+            records: row_data,
+            headers_generated: false,
+        }//end struct construction
+    }//end from_row_data(headers, row_data)
+
+    /// Returns vector of references to headers in struct.
+    pub fn get_headers(&self) -> Vec<&String> {
+        let mut ref_vec = Vec::new();
+        for header in &self.headers {ref_vec.push(header)}
+        ref_vec
+    }//end get_headers()
+    /// Returns true if the headers of this Data were generated because
+    /// the file didn't seem to have a header row, rather than being read from the file.
+    pub fn has_generated_headers(&self) -> bool {self.headers_generated}
+    /// Returns reference to vector containing list of headers.
+    pub fn get_headers_ref(&self) -> &Vec<String> {&self.headers}
+    /// Finds the first index of the header specified.  
+    /// If the header is not found, returns None.
+    pub fn get_header_index(&self, target_header: &str) -> Option<usize> {
+        for (i, header) in self.headers.iter().enumerate() {
+            if header.eq(target_header) {
+                return Some(i);
+            }//end if we found a match
+        }//end checking headers for match to header
+        return None;
+    }//end get_header_index(self, target_header)
+    /// Finds the index of the header specified, like get_header_index(),
+    /// but if no header matches exactly, the header which matches after
+    /// normalize_header() is used instead, so "MeanWidth" and "mean-width"
+    /// are both found for "Mean Width", as different firmware names them.  
+    /// This is only meant for the old side of column aliases, so other
+    /// lookups don't pick up a column the user never named.  
+    /// Returns an error if more than one header matches after normalizing,
+    /// since there's no telling which was meant.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::Data;
+    /// 
+    /// let data = Data::from_row_data(vec!["external-sample-id".to_string(), "MeanWidth".to_string(), "mean width".to_string()], Vec::new());
+    /// assert_eq!(data.find_header_index("mean width"), Ok(Some(2)));
+    /// assert_eq!(data.find_header_index("External_Sample_ID"), Ok(Some(0)));
+    /// assert_eq!(data.find_header_index("Width"), Ok(None));
+    /// assert!(data.find_header_index("Mean-Width").is_err());
+    /// assert_eq!(data.get_header_index("External_Sample_ID"), None);
+    /// ```
+    pub fn find_header_index(&self, target_header: &str) -> Result<Option<usize>,String> {
+        if let Some(col_idx) = self.get_header_index(target_header) {return Ok(Some(col_idx));}
+        let normalized_target = normalize_header(target_header);
+        if normalized_target.is_empty() {return Ok(None);}
+        let matches: Vec<&String> = self.headers.iter().filter(|header| normalize_header(header) == normalized_target).collect();
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(self.get_header_index(matches[0])),
+            _ => Err(format!("More than one column could be meant by \"{}\": {}. Use the exact name of the column instead.", target_header, matches.iter().map(|header| format!("\"{}\"", header)).collect::<Vec<String>>().join(", "))),
+        }//end matching how many headers match after normalizing
+    }//end find_header_index(self, target_header)
+    /// Gets the header at the specified index, if that index exists.  
+    /// If the index is out of bounds, returns None.
+    pub fn get_header_from_index(&self, index: usize) -> Option<&String> { self.headers.get(index) }
+    /// Gets vector of references to DataRows in this struct.
+    pub fn get_records(&self) -> Vec<&DataRow> {
+        let mut ref_vec = Vec::new();
+        for data_row in &self.records {ref_vec.push(data_row);}
+        ref_vec
+    }//end get_records()
+    /// Gets a reference to the vector of DataRows in this struct.
+    pub fn get_records_ref(&self) -> &Vec<DataRow> {&self.records}
+    /// Gets a specific record at a given row and column index, returning 
+    /// a reference to the DataCell there if the bounds are valid.  
+    /// If the row or column index are not valid, returns None
+    pub fn get_record(&self, row_idx: usize, col_idx: usize) -> Option<&DataCell> {
+        if let Some(data_row) = self.records.get(row_idx) {
+            data_row.get_data(col_idx)
+        } else {None}
+    }//end get_record()
+    /// Renames headers using aliases, given as (old header, new header),
+    /// such as for making an older export use the current column names.
+    /// The header of each cell is renamed along with the header row.  
+    /// An alias is skipped if data already has a column with the new header.
+    /// Returns the aliases which were applied.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+    /// 
+    /// let headers = vec!["external-sample-id".to_string(), "cor-filtered-as".to_string()];
+    /// let row = DataRow::new(0, vec![DataCell::new(&headers[0], "S1".to_string()), DataCell::new(&headers[1], "Sound".to_string())]);
+    /// let mut data = Data::from_row_data(headers, vec![row]);
+    /// 
+    /// let applied = data.rename_headers(&[("cor-filtered-as", "raw-filtered-as"), ("Weight", "Mass")]);
+    /// assert_eq!(applied, vec![("cor-filtered-as".to_string(), "raw-filtered-as".to_string())]);
+    /// assert_eq!(data.get_header_index("raw-filtered-as"), Some(1));
+    /// assert_eq!(data.get_record(0, 1).unwrap().get_header(), "raw-filtered-as");
+    /// ```
+    pub fn rename_headers(&mut self, aliases: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut applied = Vec::new();
+        for (old_header, new_header) in aliases.iter() {
+            if self.headers.iter().any(|header| header.eq(new_header)) {continue;}
+            let col_idx = match self.get_header_index(old_header) {
+                Some(col_idx) => col_idx,
+                None => continue,
+            };
+            let file_header = std::mem::replace(&mut self.headers[col_idx], new_header.to_string());
+            for data_row in self.records.iter_mut() {
+                for data_cell in data_row.row_data.iter_mut() {
+                    if data_cell.header.eq(&file_header) {data_cell.header = new_header.to_string();}
+                }//end renaming each matching cell in this row
+            }//end renaming cells in each row
+            applied.push((file_header, new_header.to_string()));
+        }//end applying each alias
+        applied
+    }//end rename_headers(self, aliases)
+}
+
+/// Gets header in the form used to match headers named differently by
+/// different firmware, which is lowercase, without spaces or punctuation.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::normalize_header;
+/// 
+/// assert_eq!(normalize_header("Mean Width"), "meanwidth");
+/// assert_eq!(normalize_header("mean-width"), "meanwidth");
+/// assert_eq!(normalize_header("L*"), "l");
+/// ```
+pub fn normalize_header(header: &str) -> String {
+    header.chars().filter(|c| c.is_alphanumeric()).flat_map(|c| c.to_lowercase()).collect()
+}//end normalize_header(header)
+
+/// Splits records up based on unique values in the specified column index.
+/// So, for example, if a sample id has header index 0, and you have sample ids
+/// of \[1,2,3\], then calling this function with col_splt_idx of 0 would give
+/// a Vec of data rows which sample id 1, a Vec of with only sample id 2, etc.
+/// 
+/// # Errors
+/// 
+/// This function will return Err() if it cannot get a DataCell at some index.  
+/// The Err String will contain information on which DataCell couldn't be accessed.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// use usda_c_grain_sum::model::Data;
+/// use usda_c_grain_sum::model::get_split_records;
+/// 
+/// // set up headers
+/// let mut column_headers: Vec<String> = Vec::new();
+/// let header_0 = String::from("Class");
+/// let header_1 = String::from("Volume");
+/// column_headers.push(header_0.clone());
+/// column_headers.push(header_1.clone());
+/// 
+/// // set up rows of DataCells
+/// let mut cell_row_0: Vec<DataCell> = Vec::new();
+/// let mut cell_row_1: Vec<DataCell> = Vec::new();
+/// let mut cell_row_2: Vec<DataCell> = Vec::new();
+/// cell_row_0.push(DataCell::new_from_val(&header_0, DataVal::String("Sorghum".to_string())));
+/// cell_row_0.push(DataCell::new_from_val(&header_1, DataVal::Float(3.2)));
+/// cell_row_1.push(DataCell::new_from_val(&header_0, DataVal::String("Sound".to_string())));
+/// cell_row_1.push(DataCell::new_from_val(&header_1, DataVal::Float(6.7)));
+/// cell_row_2.push(DataCell::new_from_val(&header_0, DataVal::String("Sorghum".to_string())));
+/// cell_row_2.push(DataCell::new_from_val(&header_1, DataVal::Float(2.9)));
+/// 
+/// // set up DataRows and add them to vec
+/// let mut datarow_vec: Vec<DataRow> = Vec::new();
+/// let datarow_0 = DataRow::new(0, cell_row_0);
+/// let datarow_1 = DataRow::new(1, cell_row_1);
+/// let datarow_2 = DataRow::new(2, cell_row_2);
+/// datarow_vec.push(datarow_0.clone());
+/// datarow_vec.push(datarow_1.clone());
+/// datarow_vec.push(datarow_2.clone());
+/// 
+/// // create the Data struct from everything
+/// let data = Data::from_row_data(column_headers, datarow_vec);
+/// 
+/// let base_records: Vec<&DataRow> = data.get_records();
+/// let class_split_records = get_split_records(&base_records, 0).unwrap();
+/// let class_split_records_first: &(&DataVal, Vec<&DataRow>) = class_split_records.get(0).unwrap();
+/// let class_split_records_second: &(&DataVal, Vec<&DataRow>) = class_split_records.get(1).unwrap();
+/// 
+/// assert_eq!(*class_split_records_first.0, DataVal::String("Sorghum".to_string()));
+/// assert_eq!(class_split_records_first.1.len(), 2);
+/// assert_eq!(*class_split_records_second.0, DataVal::String("Sound".to_string()));
+/// assert_eq!(class_split_records_second.1.len(), 1);
+/// ```
+pub fn get_split_records<'a>(records: &'a Vec<&'a DataRow>, col_splt_idx: usize) -> Result<Vec<(&'a DataVal, Vec<&'a DataRow>)>, String> {
+    let mut wrapping_vec: Vec<(&DataVal, Vec<&DataRow>)> = Vec::new();
+    for record in records {
+        if let Some(this_data_at_col) = record.get_data(col_splt_idx) {
+            // test if this_data_at_col matches DataVals we've already recorded
+            let this_data_val = this_data_at_col.get_data();
+            let mut found_match = false;
+            for (data_val, row_group) in &mut wrapping_vec.iter_mut() {
+                if this_data_val == *data_val {
+                    found_match = true;
+                    row_group.push(record);
+                }//end if we found a match
+            }//end adding row with this_data_val/this_data_at_col to entry for matching
+            if !found_match {
+                let new_row_group: Vec<&DataRow> = vec![record];
+                wrapping_vec.push((this_data_val, new_row_group));
+            }//end if we need to add another group to wrapping vec
+        } else { return Err(format!("Couldn't get DataCell at col idx {} and row idx {} for row data {:?}", col_splt_idx, record.get_row_idx(), record.get_row_data())); }
+    }//end looping over all records
+
+    return Ok(wrapping_vec);
+}//end get_split_records()
+
+/// Splits records the same way as get_split_records(), by the values in
+/// column col_splt_idx, then splits the rows of each of those groups again
+/// by the values in column group_splt_idx, such as a treatment or plot.  
+/// Both levels are kept in order of first appearance.
+/// 
+/// This function will return Err() if it cannot get a DataCell at either index.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{get_nested_split_records, Data, DataCell, DataRow, DataVal};
+/// 
+/// let headers = vec!["external-sample-id".to_string(), "Treatment".to_string(), "Area".to_string()];
+/// let rows = vec![("S1", "Control", "10"), ("S1", "Dried", "14"), ("S2", "Control", "12"), ("S1", "Control", "11")].into_iter().enumerate()
+///     .map(|(idx, (id, treatment, area))| DataRow::new(idx, vec![
+///         DataCell::new(&headers[0], id.to_string()),
+///         DataCell::new(&headers[1], treatment.to_string()),
+///         DataCell::new(&headers[2], area.to_string()),
+///     ])).collect();
+/// let data = Data::from_row_data(headers.clone(), rows);
+/// 
+/// let base_records: Vec<&DataRow> = data.get_records();
+/// let nested_records = get_nested_split_records(&base_records, 0, 1).unwrap();
+/// assert_eq!(nested_records.len(), 2);
+/// let (sample_val, groups) = &nested_records[0];
+/// assert_eq!(**sample_val, DataVal::String("S1".to_string()));
+/// assert_eq!(*groups[0].0, DataVal::String("Control".to_string()));
+/// assert_eq!(groups[0].1.len(), 2);
+/// assert_eq!(*groups[1].0, DataVal::String("Dried".to_string()));
+/// assert_eq!(nested_records[1].1.len(), 1);
+/// assert!(get_nested_split_records(&base_records, 0, 3).is_err());
+/// ```
+pub fn get_nested_split_records<'a>(records: &'a Vec<&'a DataRow>, col_splt_idx: usize, group_splt_idx: usize) -> Result<Vec<(&'a DataVal, Vec<(&'a DataVal, Vec<&'a DataRow>)>)>, String> {
+    let mut wrapping_vec: Vec<(&DataVal, Vec<(&DataVal, Vec<&DataRow>)>)> = Vec::new();
+    for record in records {
+        let this_data_val = match record.get_data(col_splt_idx) {
+            Some(this_data_at_col) => this_data_at_col.get_data(),
+            None => return Err(format!("Couldn't get DataCell at col idx {} and row idx {} for row data {:?}", col_splt_idx, record.get_row_idx(), record.get_row_data())),
+        };
+        let this_group_val = match record.get_data(group_splt_idx) {
+            Some(this_group_at_col) => this_group_at_col.get_data(),
+            None => return Err(format!("Couldn't get DataCell at col idx {} and row idx {} for row data {:?}", group_splt_idx, record.get_row_idx(), record.get_row_data())),
+        };
+        let groups = match wrapping_vec.iter().position(|(data_val, _)| this_data_val == *data_val) {
+            Some(split_idx) => &mut wrapping_vec[split_idx].1,
+            None => {
+                wrapping_vec.push((this_data_val, Vec::new()));
+                &mut wrapping_vec.last_mut().unwrap().1
+            },
+        };
+        match groups.iter_mut().find(|(group_val, _)| this_group_val == *group_val) {
+            Some((_, row_group)) => row_group.push(record),
+            None => groups.push((this_group_val, vec![record])),
+        }//end matching whether we've seen this group in this split already
+    }//end looping over all records
+
+    Ok(wrapping_vec)
+}//end get_nested_split_records()
+
+/// This function returns a Vector only containing DataRows 
+/// whose value at column col_idx is equal to the expected.  
+/// The intended purpose of this function is to return rows 
+/// belonging to a particular category.  
+/// For example, if you have a column 2 with header type, you 
+/// might want to get rows with a type matching Sound.  
+/// Notably, this function does not change the values of 
+/// any DataRows or clone anything.  
+/// Instead, the function reorganizes a list of references 
+/// by returning a new list that only contains references 
+/// to DataRows with the specified DataVal.  
+/// 
+/// # Errors
+/// 
+/// If the given column index is invalid, this function will 
+/// return an error.
+/// An error will also be returned if the function cannot access a 
+/// particular row for some reason.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::DataVal;
+/// use usda_c_grain_sum::model::DataCell;
+/// use usda_c_grain_sum::model::DataRow;
+/// use usda_c_grain_sum::model::Data;
+/// use usda_c_grain_sum::model::get_filtered_records;
+/// 
+/// // set up headers
+/// let mut column_headers: Vec<String> = Vec::new();
+/// let header_0 = String::from("Length");
+/// let header_1 = String::from("Width");
+/// let header_2 = String::from("Thickness");
+/// column_headers.push(header_0.clone());
+/// column_headers.push(header_1.clone());
+/// column_headers.push(header_2.clone());
+/// 
+/// // set up rows of DataCells
+/// let mut cell_row_0: Vec<DataCell> = Vec::new();
+/// let mut cell_row_1: Vec<DataCell> = Vec::new();
+/// cell_row_0.push(DataCell::new_from_val(&header_0, DataVal::Float(5.4)));
+/// cell_row_0.push(DataCell::new_from_val(&header_1, DataVal::Float(3.2)));
+/// cell_row_0.push(DataCell::new_from_val(&header_2, DataVal::Float(2.1)));
+/// cell_row_1.push(DataCell::new_from_val(&header_0, DataVal::Float(6.7)));
+/// cell_row_1.push(DataCell::new_from_val(&header_1, DataVal::Float(4.5)));
+/// cell_row_1.push(DataCell::new_from_val(&header_2, DataVal::Float(2.9)));
+/// 
+/// // set up DataRows and add them to vec
+/// let mut datarow_vec: Vec<DataRow> = Vec::new();
+/// let datarow_0 = DataRow::new(0, cell_row_0);
+/// let datarow_1 = DataRow::new(1, cell_row_1);
+/// datarow_vec.push(datarow_0.clone());
+/// datarow_vec.push(datarow_1.clone());
+/// 
+/// // create the Data struct from everything
+/// let data = Data::from_row_data(column_headers, datarow_vec);
+/// 
+/// assert_eq!(data.get_records().len(), 2);
+/// 
+/// let data_records: Vec<&DataRow> = data.get_records();
+/// let length_5_4_filtered = get_filtered_records(&data_records, 0, DataVal::Float(5.4)).unwrap();
+/// 
+/// assert_eq!(length_5_4_filtered.len(), 1);
+/// 
+/// let first_row_first_col_data_cell: &DataCell = length_5_4_filtered
+///     .first().unwrap() // get first (and only) DataRow from Vec
+///     .get_data(0).unwrap(); // get first DataCell from first DataRow
+/// 
+/// assert_eq!(*first_row_first_col_data_cell.get_data(), DataVal::Float(5.4));
+/// ```
+/// 
+pub fn get_filtered_records<'a>(records: &'a Vec<&'a DataRow>, col_idx: usize, expected: DataVal) -> Result<Vec<&'a DataRow>, String> {
+    let mut filtered_vec: Vec<&DataRow> = Vec::new();
+
+    for row in records {
+        match row.get_data(col_idx) {
+            Some(data_cell) => {
+                // make sure data cell is equal to expected
+                if expected == *data_cell.get_data() {
+                    filtered_vec.push(row);
+                }//end if this row matches the filter
+            } None => return Err(format!("Couldn't get DataCell at col idx {} and row idx {} for row data {:?}, ", col_idx, row.get_row_idx(), row.get_row_data())),
+        }//end matching whether we could access row data at col_idx
+    }//end looping over each row
+
+    Ok(filtered_vec)
+}//end get_filtered_records()
+
+/// The kind of values held in an output column, which
+/// decides how they're formatted when written.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub enum ColumnKind {
+    /// Regular numbers, or text.
+    Number,
+    /// Fractions shown as percents, such as 0.5 for 50%.
+    Percent,
+    /// Percents which total other percent columns of the same output,
+    /// such as the rollup of a parent class. These are shown the same
+    /// as Percent, but aren't stacked with the others in charts.
+    PercentTotal,
+    /// Text which is never a number, such as the treatment rows are
+    /// grouped by, which is shown as text even if it looks like a number.
+    Text,
+}//end enum ColumnKind
+
+/// A column of output, after the sample id column.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OutputColumn {
+    /// The header written above the column.
+    pub name: String,
+    /// The number of decimal places shown for values in the column.
+    pub precision: usize,
+    /// The kind of values in the column.
+    pub kind: ColumnKind,
+    /// The calculation for a formula column, such as "Avg Length / Avg Width",
+    /// which is written as an excel formula using the other cells of the
+    /// row, or None for a column of fixed values.
+    pub formula: Option<String>,
+    /// The header of the group this column is part of, such as "Length"
+    /// for Avg Length and Std Length, which is written above the headers
+    /// of the group's columns in excel output, or None if it isn't in a group.
+    pub group: Option<String>,
+}//end struct OutputColumn
+
+impl OutputColumn {
+    /// Creates a column of regular numbers, shown with precision decimal places.
+    pub fn number(name: &str, precision: usize) -> OutputColumn {
+        OutputColumn { name: name.to_string(), precision, kind: ColumnKind::Number, formula: None, group: None }
+    }//end number(name, precision)
+
+    /// Creates a column of percents, shown with precision decimal places.
+    pub fn percent(name: &str, precision: usize) -> OutputColumn {
+        OutputColumn { name: name.to_string(), precision, kind: ColumnKind::Percent, formula: None, group: None }
+    }//end percent(name, precision)
+
+    /// Creates a column of text, such as the group of each row.
+    pub fn text(name: &str) -> OutputColumn {
+        OutputColumn { name: name.to_string(), precision: 0, kind: ColumnKind::Text, formula: None, group: None }
+    }//end text(name)
+
+    /// Creates a column of percents totaling other percent columns,
+    /// shown with precision decimal places.
+    pub fn percent_total(name: &str, precision: usize) -> OutputColumn {
+        OutputColumn { name: name.to_string(), precision, kind: ColumnKind::PercentTotal, formula: None, group: None }
+    }//end percent_total(name, precision)
+
+    /// Gets this column as a formula column, calculated with formula,
+    /// such as "Avg Length / Avg Width".
+    pub fn with_formula(mut self, formula: &str) -> OutputColumn {
+        self.formula = Some(formula.to_string());
+        self
+    }//end with_formula(self, formula)
+
+    /// Gets this column as part of the group with the header group,
+    /// such as "Length".
+    pub fn with_group(mut self, group: &str) -> OutputColumn {
+        self.group = Some(group.to_string());
+        self
+    }//end with_group(self, group)
+
+    /// Tells whether the values in this column are shown as percents.
+    pub fn is_percent(&self) -> bool {self.kind == ColumnKind::Percent || self.kind == ColumnKind::PercentTotal}
+
+    /// Tells whether the values in this column total other columns.
+    pub fn is_total(&self) -> bool {self.kind == ColumnKind::PercentTotal}
+
+    /// Tells whether the values in this column are text, rather than numbers.
+    pub fn is_text(&self) -> bool {self.kind == ColumnKind::Text}
+}//end impl OutputColumn
+
+/// A row of output for one sample, or for a group of
+/// samples such as a lot.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OutputRow {
+    /// The sample id written in the first column.
+    pub sample_id: String,
+    /// The values in the row, one for each column of the output.
+    pub cells: Vec<DataVal>,
+    /// Notes about specific cells, as (column index, note), such as
+    /// why a value was skipped. These are written as cell comments.
+    pub notes: Vec<(usize, String)>,
+}//end struct OutputRow
+
+impl OutputRow {
+    /// Creates a row for sample_id with the given values and no notes.
+    pub fn new(sample_id: &str, cells: Vec<DataVal>) -> OutputRow {
+        OutputRow { sample_id: sample_id.to_string(), cells, notes: Vec::new() }
+    }//end new(sample_id, cells)
+
+    /// Attaches note to the cell at col_idx. If that cell already has
+    /// a note, this one is added on a new line after it.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use usda_c_grain_sum::model::{DataVal, OutputRow};
+    /// 
+    /// let mut row = OutputRow::new("S1", vec![DataVal::Float(12.5), DataVal::Float(-1000.0)]);
+    /// row.add_note(1, "stdev skipped: non-numeric data");
+    /// row.add_note(1, "2 values left out");
+    /// assert_eq!(row.get_note(0), None);
+    /// assert_eq!(row.get_note(1).unwrap(), "stdev skipped: non-numeric data\n2 values left out");
+    /// ```
+    pub fn add_note(&mut self, col_idx: usize, note: &str) {
+        match self.notes.iter_mut().find(|(note_col, _)| *note_col == col_idx) {
+            Some((_, existing_note)) => {existing_note.push('\n'); existing_note.push_str(note);},
+            None => self.notes.push((col_idx, note.to_string())),
+        }//end matching whether this cell already has a note
+    }//end add_note(self, col_idx, note)
+
+    /// Gets the note attached to the cell at col_idx, if there is one.
+    pub fn get_note(&self, col_idx: usize) -> Option<&String> {
+        self.notes.iter().find(|(note_col, _)| *note_col == col_idx).map(|(_, note)| note)
+    }//end get_note(self, col_idx)
+}//end impl OutputRow
+
+/// Data which has already been processed and is ready to be
+/// written, such as to an excel sheet.
+/// 
+/// The primary intention is that data processing methods can
+/// export this as one format, and then functions which write
+/// to files can simply take this as input.
+/// 
+/// id_header is the header written above the sample ids,
+/// such as "external-sample-id". Each of columns describes
+/// one column after that, and each of rows holds the values
+/// for one sample, in the same order as columns.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{DataVal, OutputColumn, SampleOutput};
+/// 
+/// let mut output = SampleOutput::new("Sample")
+///     .with_column(OutputColumn::number("Area", 2))
+///     .with_column(OutputColumn::percent("%Sound", 1));
+/// output.add_row("S1", vec![DataVal::Float(12.5), DataVal::Float(0.9)]);
+/// assert_eq!(output.get_column_index("%Sound"), Some(1));
+/// assert!(output.columns[1].is_percent());
+/// assert_eq!(output.rows[0].sample_id, "S1");
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct SampleOutput {
+    pub id_header: String,
+    pub columns: Vec<OutputColumn>,
+    pub rows: Vec<OutputRow>,
+}//end struct SampleOutput
+
+impl SampleOutput {
+    /// Creates output with no columns or rows, with id_header
+    /// written above the sample ids.
+    pub fn new(id_header: &str) -> SampleOutput {
+        SampleOutput { id_header: id_header.to_string(), columns: Vec::new(), rows: Vec::new() }
+    }//end new(id_header)
+
+    /// Adds column after the other columns, for building output in one expression.
+    pub fn with_column(mut self, column: OutputColumn) -> SampleOutput {
+        self.columns.push(column);
+        self
+    }//end with_column(self, column)
+
+    /// Adds column after the other columns.
+    pub fn add_column(&mut self, column: OutputColumn) {
+        self.columns.push(column);
+    }//end add_column(self, column)
+
+    /// Adds a row for sample_id after the other rows.
+    pub fn add_row(&mut self, sample_id: &str, cells: Vec<DataVal>) {
+        self.rows.push(OutputRow::new(sample_id, cells));
+    }//end add_row(self, sample_id, cells)
+
+    /// Finds the index in columns of the column named name.  
+    /// If there is no such column, returns None.
+    pub fn get_column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name == name)
+    }//end get_column_index(self, name)
+}//end impl SampleOutput
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, rc::Rc};
+
+use fltk::{app, browser::HoldBrowser, enums::{CallbackTrigger, Event, FrameType, Key}, input::Input, prelude::{BrowserExt, GroupExt, InputExt, WidgetBase, WidgetExt, WindowExt}, window::Window};
+
+use crate::gui::InterfaceMessage;
+
+/// What happens when a command in the command palette is chosen.
+#[derive(Clone,PartialEq,Debug)]
+pub enum PaletteAction {
+    /// The message is sent to main, as if the matching button or menu item was used.
+    Send(InterfaceMessage),
+    /// A file dialog is shown to choose data files of any kind.
+    OpenDataFiles,
+    /// A menu of recently opened files is shown to choose one to open again.
+    OpenRecentFile,
+    /// A file dialog is shown to choose the csv input file.
+    OpenCsv,
+    /// A file dialog is shown to choose the xml input file.
+    OpenXml,
+    /// A file dialog is shown to choose where the output file is written.
+    ChooseOutput,
+    /// A file dialog is shown to choose an output file to verify.
+    VerifyOutput,
+    /// A file dialog is shown to choose a summary workbook to open.
+    OpenSummary,
+    /// File dialogs are shown to choose summary workbooks to merge and where to write them.
+    MergeSummaries,
+    /// A dialog is shown to choose a folder of runs to process, and how to write them.
+    BatchProcess,
+    /// File dialogs are shown to choose a large csv file to summarize and where to write it.
+    StreamLargeCsv,
+    /// The help window is shown.
+    Help,
+}//end enum PaletteAction
+
+/// A single action listed in the command palette.
+#[derive(Clone,PartialEq,Debug)]
+pub struct PaletteCommand {
+    /// The name shown in the list and matched against the search text.
+    pub name: String,
+    pub action: PaletteAction,
+}//end struct PaletteCommand
+
+impl PaletteCommand {
+    pub fn new(name: &str, action: PaletteAction) -> PaletteCommand {
+        PaletteCommand { name: name.to_string(), action }
+    }//end new(name, action)
+}//end impl for PaletteCommand
+
+/// Scores how well query matches name, ignoring case, or returns None if
+/// the letters of query don't all appear in name in the same order.
+/// Letters which follow each other or start a word score higher, so
+/// "pd" ranks "Process Data" above "Explore Loaded Data".
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i32> {
+    let name_chars: Vec<char> = name.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut name_idx = 0;
+    let mut last_match: Option<usize> = None;
+    for query_char in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let match_idx = (name_idx..name_chars.len()).find(|idx| name_chars[*idx] == query_char)?;
+        score += 1;
+        if last_match.is_some_and(|last_idx| last_idx + 1 == match_idx) {score += 5;}
+        if match_idx == 0 || !name_chars[match_idx - 1].is_alphanumeric() {score += 10;}
+        last_match = Some(match_idx);
+        name_idx = match_idx + 1;
+    }//end finding each letter of the query in order
+    // shorter names are a closer match for the same letters
+    Some(score * 100 - name_chars.len() as i32)
+}//end fuzzy_score(query, name)
+
+/// Gets the commands matching query, best matches first.
+/// If query is empty, every command is returned in its original order.
+fn filter_commands<'a>(commands: &'a Vec<PaletteCommand>, query: &str) -> Vec<&'a PaletteCommand> {
+    let mut matches: Vec<(i32, &PaletteCommand)> = commands.iter()
+        .filter_map(|command| fuzzy_score(query, &command.name).map(|score| (score, command)))
+        .collect();
+    if !query.trim().is_empty() {matches.sort_by(|a, b| b.0.cmp(&a.0));}
+    matches.into_iter().map(|(_, command)| command).collect()
+}//end filter_commands(commands, query)
+
+/// Shows a small window with a search box over a list of the given
+/// commands, narrowing the list as the user types.
+/// Enter or double-clicking chooses the selected command, and the up
+/// and down arrows move the selection while typing.
+/// Returns the action of the chosen command, or None if the window is closed.
+pub fn show_command_palette(commands: Vec<PaletteCommand>) -> Option<PaletteAction> {
+    let mut palette_window = Window::default()
+        .with_size(420,330)
+        .with_label("Command Palette");
+    palette_window.make_modal(true);
+
+    let mut search_box = Input::default()
+        .with_pos(10,10)
+        .with_size(400,30);
+    search_box.set_tooltip("Type part of the name of an action, then press Enter to run it.");
+    search_box.set_trigger(CallbackTrigger::Changed);
+
+    let mut command_browser = HoldBrowser::default()
+        .with_pos(10,50)
+        .with_size(400,270);
+    command_browser.set_frame(FrameType::GtkDownFrame);
+    palette_window.end();
+
+    let chosen_action: Rc<RefCell<Option<PaletteAction>>> = Rc::from(RefCell::from(None));
+    let shown_commands: Rc<RefCell<Vec<PaletteCommand>>> = Rc::from(RefCell::from(Vec::new()));
+    let mut fill_browser = {
+        let mut browser = command_browser.clone();
+        let shown_commands = shown_commands.clone();
+        move |query: &str| {
+            browser.clear();
+            let matches: Vec<PaletteCommand> = filter_commands(&commands, query).into_iter().cloned().collect();
+            for command in matches.iter() {browser.add(&command.name.replace("@", "@@"));}
+            if matches.len() > 0 {browser.select(1);}
+            *shown_commands.borrow_mut() = matches;
+        }//end moving for closure
+    };
+    fill_browser("");
+
+    let choose_selected = {
+        let browser = command_browser.clone();
+        let shown_commands = shown_commands.clone();
+        let chosen_action = chosen_action.clone();
+        let mut window = palette_window.clone();
+        move || {
+            if browser.value() < 1 {return;}
+            if let Some(command) = shown_commands.borrow().get(browser.value() as usize - 1) {
+                *chosen_action.borrow_mut() = Some(command.action.clone());
+                window.hide();
+            }//end if we can get the selected command
+        }//end moving for closure
+    };
+
+    search_box.set_callback(move |search_box| fill_browser(&search_box.value()));
+    search_box.handle({
+        let mut browser = command_browser.clone();
+        let mut choose_selected = choose_selected.clone();
+        move |_, ev| {
+            if ev != Event::KeyDown {return false;}
+            match app::event_key() {
+                Key::Enter | Key::KPEnter => {choose_selected(); true},
+                Key::Down => {if browser.value() < browser.size() {browser.select(browser.value() + 1);} true},
+                Key::Up => {if browser.value() > 1 {browser.select(browser.value() - 1);} true},
+                _ => false,
+            }//end matching which key was pressed
+        }//end moving for closure
+    });
+    command_browser.set_callback({
+        let mut choose_selected = choose_selected.clone();
+        move |_| {
+            if app::event_clicks() || app::event_key() == Key::Enter {choose_selected();}
+        }//end moving for closure
+    });
+
+    palette_window.show();
+    let _ = search_box.take_focus();
+    while palette_window.shown() && app::wait() {}
+    let action = chosen_action.borrow().clone();
+    action
+}//end show_command_palette(commands)
@@ -0,0 +1,752 @@
+use std::collections::HashMap;
+
+use crate::{app::{config_store::{ConfigStore, PipelineStep}, process}, model::{Data, DataCell, DataRow, DataVal}, stats};
+
+/// A conversion of the units of a numeric column, such as "Weight * 1000"
+/// or "Area / 100". Values which aren't numbers are left as they are.
+#[derive(Clone, PartialEq, Debug)]
+pub struct UnitConversion {
+    /// The name of the column to convert.
+    pub column: String,
+    /// The number each value is multiplied by.
+    pub factor: f64,
+}//end struct UnitConversion
+
+impl UnitConversion {
+    /// Parses a conversion from text in the form of "column * factor"
+    /// or "column / divisor".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::pipeline::UnitConversion;
+    ///
+    /// assert_eq!(UnitConversion::parse("Weight * 1000").unwrap(), UnitConversion { column: "Weight".to_string(), factor: 1000.0 });
+    /// assert_eq!(UnitConversion::parse("Mean Width / 4").unwrap().factor, 0.25);
+    /// assert!(UnitConversion::parse("Weight / 0").is_err());
+    /// assert!(UnitConversion::parse("Weight in mg").is_err());
+    /// ```
+    pub fn parse(conversion: &str) -> Result<UnitConversion,String> {
+        let (op_idx, op) = match conversion.rfind(['*', '/']) {
+            Some(op_idx) => (op_idx, &conversion[op_idx..op_idx + 1]),
+            None => return Err(format!("Couldn't find * or / in unit conversion \"{}\".", conversion)),
+        };
+        let column = conversion[..op_idx].trim().to_string();
+        if column.is_empty() {return Err(format!("The unit conversion \"{}\" has no column name.", conversion));}
+        let value = match conversion[op_idx + 1..].trim().parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => return Err(format!("Couldn't read \"{}\" in unit conversion \"{}\" as a number.", conversion[op_idx + 1..].trim(), conversion)),
+        };
+        match op {
+            "/" if value == 0.0 => Err(format!("The unit conversion \"{}\" divides by zero.", conversion)),
+            "/" => Ok(UnitConversion { column, factor: 1.0 / value }),
+            _ => Ok(UnitConversion { column, factor: value }),
+        }//end matching whether we multiply or divide
+    }//end parse(conversion)
+}//end impl UnitConversion
+
+/// One side of the calculation for a derived column.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Operand {
+    /// The value of a column in the same row.
+    Column(String),
+    /// A fixed number.
+    Number(f64),
+}//end enum Operand
+
+/// A column calculated from two other columns or numbers, such as
+/// "Aspect = Length / Width" or "Area mm2 = Area * 0.01".
+#[derive(Clone, PartialEq, Debug)]
+pub struct DerivedColumn {
+    /// The header of the new column.
+    pub name: String,
+    pub left: Operand,
+    /// The operation, one of +, -, *, or /.
+    pub op: char,
+    pub right: Operand,
+}//end struct DerivedColumn
+
+impl DerivedColumn {
+    /// Parses a derived column from text in the form of
+    /// "name = left op right", where op is +, -, *, or /.
+    /// The operator needs a space on each side, since column
+    /// names like "external-sample-id" can hold a -.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::pipeline::{DerivedColumn, Operand};
+    ///
+    /// let derived = DerivedColumn::parse("Aspect = Length / Mean Width").unwrap();
+    /// assert_eq!(derived.name, "Aspect");
+    /// assert_eq!(derived.left, Operand::Column("Length".to_string()));
+    /// assert_eq!(derived.op, '/');
+    /// assert_eq!(derived.right, Operand::Column("Mean Width".to_string()));
+    /// assert_eq!(DerivedColumn::parse("Area mm2 = Area * 0.01").unwrap().right, Operand::Number(0.01));
+    /// assert!(DerivedColumn::parse("Aspect = Length/Width").is_err());
+    /// assert!(DerivedColumn::parse("Length / Width").is_err());
+    /// ```
+    pub fn parse(derived: &str) -> Result<DerivedColumn,String> {
+        let (name, expression) = match derived.split_once('=') {
+            Some((name, expression)) if !name.trim().is_empty() => (name.trim().to_string(), expression),
+            _ => return Err(format!("The derived column \"{}\" needs a name, then =, like \"Aspect = Length / Width\".", derived)),
+        };
+        for op in ['+', '-', '*', '/'] {
+            if let Some((left, right)) = expression.split_once(&format!(" {} ", op)) {
+                let get_operand = |text: &str| match text.trim().parse::<f64>() {
+                    Ok(number) => Operand::Number(number),
+                    Err(_) => Operand::Column(text.trim().to_string()),
+                };
+                return Ok(DerivedColumn { name, left: get_operand(left), op, right: get_operand(right) });
+            }//end if we found this operator
+        }//end checking for each operator
+        Err(format!("Couldn't find +, -, *, or / with a space on each side in derived column \"{}\".", derived))
+    }//end parse(derived)
+
+    /// Calculates the value from the values of the left and right operands.  
+    /// Returns None when dividing by zero.
+    pub fn calculate(&self, left: f64, right: f64) -> Option<f64> {
+        match self.op {
+            '+' => Some(left + right),
+            '-' => Some(left - right),
+            '*' => Some(left * right),
+            _ if right != 0.0 => Some(left / right),
+            _ => None,
+        }//end matching the operation
+    }//end calculate(self, left, right)
+}//end impl DerivedColumn
+
+/// The color space of the columns added by a color conversion.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ColorSpace {
+    /// Adds the columns L*, a*, and b* of CIE L*a*b*.
+    Lab,
+    /// Adds the columns Chroma and Hue Angle, which are the polar
+    /// form of a* and b* from CIE L*a*b*.
+    LCh,
+}//end enum ColorSpace
+
+impl ColorSpace {
+    /// Gets the headers of the columns added for this color space.
+    pub fn get_headers(&self) -> Vec<&'static str> {
+        match self {
+            ColorSpace::Lab => vec!["L*", "a*", "b*"],
+            ColorSpace::LCh => vec!["Chroma", "Hue Angle"],
+        }//end matching self
+    }//end get_headers(self)
+}//end impl ColorSpace
+
+/// A conversion of the red, green, and blue columns of each kernel into
+/// another color space, such as "Lab" or "LCh from R, G, B".
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColorConversion {
+    pub space: ColorSpace,
+    /// The headers of the red, green, and blue columns, which hold
+    /// values from 0 to 255.
+    pub rgb_columns: [String; 3],
+}//end struct ColorConversion
+
+impl ColorConversion {
+    /// Parses a color conversion from text in the form of "space", or
+    /// "space from red, green, blue" to give the rgb columns, where space
+    /// is Lab or LCh. The rgb columns default to Red, Green, and Blue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use usda_c_grain_sum::pipeline::{ColorConversion, ColorSpace};
+    ///
+    /// let conversion = ColorConversion::parse("lab").unwrap();
+    /// assert_eq!(conversion.space, ColorSpace::Lab);
+    /// assert_eq!(conversion.rgb_columns, ["Red".to_string(), "Green".to_string(), "Blue".to_string()]);
+    /// assert_eq!(ColorConversion::parse("LCh from R, G, B").unwrap().rgb_columns[2], "B");
+    /// assert!(ColorConversion::parse("HSV").is_err());
+    /// assert!(ColorConversion::parse("Lab from R, G").is_err());
+    /// ```
+    pub fn parse(conversion: &str) -> Result<ColorConversion,String> {
+        let (space_text, columns_text) = match conversion.split_once(" from ") {
+            Some((space_text, columns_text)) => (space_text.trim(), Some(columns_text)),
+            None => (conversion.trim(), None),
+        };
+        let space = match space_text.to_lowercase().as_str() {
+            "lab" | "l*a*b*" => ColorSpace::Lab,
+            "lch" => ColorSpace::LCh,
+            _ => return Err(format!("Couldn't read \"{}\" in color conversion \"{}\" as a color space. It should be Lab or LCh.", space_text, conversion)),
+        };
+        let rgb_columns = match columns_text {
+            Some(columns_text) => {
+                let columns: Vec<String> = columns_text.split(',').map(|column| column.trim().to_string()).collect();
+                match <[String; 3]>::try_from(columns) {
+                    Ok(rgb_columns) if rgb_columns.iter().all(|column| !column.is_empty()) => rgb_columns,
+                    _ => return Err(format!("The color conversion \"{}\" should give three columns after from, for red, green, and blue.", conversion)),
+                }//end matching whether there are three columns
+            },
+            None => ["Red".to_string(), "Green".to_string(), "Blue".to_string()],
+        };
+        Ok(ColorConversion { space, rgb_columns })
+    }//end parse(conversion)
+}//end impl ColorConversion
+
+/// Converts a color from sRGB, with each channel from 0 to 255, into
+/// CIE L*a*b* with a D65 white point, as (L*, a*, b*).
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::pipeline::rgb_to_lab;
+///
+/// let (l, a, b) = rgb_to_lab(255.0, 255.0, 255.0);
+/// assert!((l - 100.0).abs() < 0.01 && a.abs() < 0.01 && b.abs() < 0.01);
+/// let (l, a, b) = rgb_to_lab(255.0, 0.0, 0.0);
+/// assert!((l - 53.24).abs() < 0.01 && (a - 80.09).abs() < 0.01 && (b - 67.20).abs() < 0.01);
+/// ```
+pub fn rgb_to_lab(red: f64, green: f64, blue: f64) -> (f64, f64, f64) {
+    let to_linear = |channel: f64| {
+        let channel = (channel / 255.0).clamp(0.0, 1.0);
+        if channel <= 0.04045 {channel / 12.92} else {((channel + 0.055) / 1.055).powf(2.4)}
+    };
+    let (r, g, b) = (to_linear(red), to_linear(green), to_linear(blue));
+    // xyz, relative to the D65 white point
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.1191920 * g + 0.9503041 * b) / 1.08883;
+    let f = |t: f64| if t > 216.0 / 24389.0 {t.cbrt()} else {(24389.0 / 27.0 * t + 16.0) / 116.0};
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}//end rgb_to_lab(red, green, blue)
+
+/// Gets the number in cell, if it holds one.
+fn get_cell_number(cell: Option<&DataCell>) -> Option<f64> {
+    match cell.map(|cell| cell.get_data()) {
+        Some(DataVal::Int(i)) => Some(*i as f64),
+        Some(DataVal::Float(f)) => Some(*f),
+        _ => None,
+    }//end matching the type of the cell
+}//end get_cell_number(cell)
+
+/// Renames columns of data using aliases in the form of "old -> new".
+/// The old column is found with Data::find_header_index(), so it matches
+/// ignoring case, spaces, and punctuation, such as "mean-width" for an
+/// alias from "MeanWidth".  
+/// Returns the number of columns renamed, or an error if an alias can't be
+/// read or its old column could be more than one column of data.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::pipeline::apply_header_aliases;
+///
+/// let header = "Mass".to_string();
+/// let mut data = Data::from_row_data(vec![header.clone()], vec![DataRow::new(0, vec![DataCell::new(&header, "1".to_string())])]);
+/// assert_eq!(apply_header_aliases(&mut data, &vec!["Mass -> Weight".to_string()]), Ok(1));
+/// assert_eq!(data.get_header_index("Weight"), Some(0));
+/// assert!(apply_header_aliases(&mut data, &vec!["Mass = Weight".to_string()]).is_err());
+///
+/// let headers = vec!["mean-width".to_string(), "MEAN WIDTH".to_string()];
+/// assert!(apply_header_aliases(&mut Data::from_row_data(headers, Vec::new()), &vec!["MeanWidth -> Mean Width".to_string()]).is_err());
+/// ```
+pub fn apply_header_aliases(data: &mut Data, aliases: &Vec<String>) -> Result<usize,String> {
+    let mut alias_pairs = parse_header_aliases(aliases)?;
+    // aliases use the name of the column as it is in data, so rename_headers() finds it exactly
+    for (old_header, _) in alias_pairs.iter_mut() {
+        if let Some(header) = data.find_header_index(old_header)?.and_then(|col_idx| data.get_header_from_index(col_idx)) {
+            *old_header = header.clone();
+        }//end if the old column is in data
+    }//end finding the old column of each alias
+    let alias_pairs: Vec<(&str, &str)> = alias_pairs.iter().map(|(old_header, new_header)| (old_header.as_str(), new_header.as_str())).collect();
+    Ok(data.rename_headers(&alias_pairs).len())
+}//end apply_header_aliases(data, aliases)
+
+/// Parses column aliases in the form of "old -> new" into (old, new)
+/// pairs, skipping empty lines.  
+/// Returns an error if an alias has no -> or is missing either name.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::pipeline::parse_header_aliases;
+///
+/// let aliases = vec!["MeanWidth -> Mean Width".to_string(), "".to_string(), "Mass->Weight".to_string()];
+/// assert_eq!(parse_header_aliases(&aliases), Ok(vec![
+///     ("MeanWidth".to_string(), "Mean Width".to_string()),
+///     ("Mass".to_string(), "Weight".to_string()),
+/// ]));
+/// assert!(parse_header_aliases(&vec!["Mass = Weight".to_string()]).is_err());
+/// assert!(parse_header_aliases(&vec!["Mass -> ".to_string()]).is_err());
+/// ```
+pub fn parse_header_aliases(aliases: &Vec<String>) -> Result<Vec<(String, String)>,String> {
+    let mut alias_pairs = Vec::new();
+    for alias in aliases.iter().filter(|alias| !alias.trim().is_empty()) {
+        match alias.split_once("->").map(|(old_header, new_header)| (old_header.trim(), new_header.trim())) {
+            Some((old_header, new_header)) if !old_header.is_empty() && !new_header.is_empty() => alias_pairs.push((old_header.to_string(), new_header.to_string())),
+            Some(_) => return Err(format!("Column alias \"{}\" needs a column name on each side of ->.", alias)),
+            None => return Err(format!("Couldn't find -> in column alias \"{}\".", alias)),
+        }//end matching whether we could split the alias
+    }//end parsing each alias
+    Ok(alias_pairs)
+}//end parse_header_aliases(aliases)
+
+/// Gets the headers data would have after renaming columns with aliases,
+/// in the form of "old -> new", without changing any data. This is used
+/// to check for needed columns before the pipeline is run.  
+/// Aliases which can't be read are skipped.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::pipeline::get_aliased_headers;
+///
+/// let headers = vec!["external-sample-id".to_string(), "mean-width".to_string()];
+/// assert_eq!(get_aliased_headers(&headers, &vec!["MeanWidth -> Mean Width".to_string()]), vec!["external-sample-id".to_string(), "Mean Width".to_string()]);
+/// ```
+pub fn get_aliased_headers(headers: &Vec<String>, aliases: &Vec<String>) -> Vec<String> {
+    let mut header_data = Data::from_row_data(headers.clone(), Vec::new());
+    for alias in aliases.iter() {
+        // each alias is applied on its own, so one which can't be read doesn't stop the rest
+        let _ = apply_header_aliases(&mut header_data, &vec![alias.clone()]);
+    }//end applying each alias
+    header_data.get_headers_ref().clone()
+}//end get_aliased_headers(headers, aliases)
+
+/// Parses class remappings in the form of "raw -> report" into
+/// (raw, report) pairs, skipping empty lines.  
+/// Returns an error if a remapping has no -> or is missing either class,
+/// or if the same raw class is remapped to two different classes.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::pipeline::parse_class_remappings;
+///
+/// let remappings = vec!["SorghumBroken -> Damaged".to_string(), "".to_string(), "SorghumChipped->Damaged".to_string()];
+/// assert_eq!(parse_class_remappings(&remappings), Ok(vec![
+///     ("SorghumBroken".to_string(), "Damaged".to_string()),
+///     ("SorghumChipped".to_string(), "Damaged".to_string()),
+/// ]));
+/// assert!(parse_class_remappings(&vec!["SorghumBroken = Damaged".to_string()]).is_err());
+/// assert!(parse_class_remappings(&vec!["SorghumBroken -> Damaged".to_string(), "SorghumBroken -> Sound".to_string()]).is_err());
+/// ```
+pub fn parse_class_remappings(remappings: &Vec<String>) -> Result<Vec<(String, String)>,String> {
+    let mut class_pairs: Vec<(String, String)> = Vec::new();
+    for remapping in remappings.iter().filter(|remapping| !remapping.trim().is_empty()) {
+        let (raw_class, report_class) = match remapping.split_once("->").map(|(raw_class, report_class)| (raw_class.trim(), report_class.trim())) {
+            Some((raw_class, report_class)) if !raw_class.is_empty() && !report_class.is_empty() => (raw_class.to_string(), report_class.to_string()),
+            Some(_) => return Err(format!("Class remapping \"{}\" needs a class on each side of ->.", remapping)),
+            None => return Err(format!("Couldn't find -> in class remapping \"{}\".", remapping)),
+        };
+        match class_pairs.iter().find(|(other_raw, _)| other_raw.eq(&raw_class)) {
+            Some((_, other_report)) if other_report.ne(&report_class) => return Err(format!("The class {} is remapped to both {} and {}.", raw_class, other_report, report_class)),
+            Some(_) => {},
+            None => class_pairs.push((raw_class, report_class)),
+        }//end matching whether this class was already remapped
+    }//end parsing each remapping
+    Ok(class_pairs)
+}//end parse_class_remappings(remappings)
+
+/// Gets a copy of data with the classes in the class_header column
+/// changed using remappings, like "SorghumBroken -> Damaged", so that
+/// class filters and class percents use the classes of the report
+/// rather than those of the instrument. Classes without a remapping
+/// are left as they are, and remappings aren't chained, so a class is
+/// only ever remapped once.  
+/// Returns the copy and the number of kernels whose class was changed.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::pipeline::apply_class_remappings;
+///
+/// let header = "raw-filtered-as".to_string();
+/// let rows = vec!["Sound", "SorghumBroken", "SorghumChipped"].into_iter().enumerate().map(|(idx, class)| DataRow::new(idx, vec![DataCell::new(&header, class.to_string())])).collect();
+/// let data = Data::from_row_data(vec![header.clone()], rows);
+/// let remappings = vec!["SorghumBroken -> Damaged".to_string(), "SorghumChipped -> Damaged".to_string()];
+/// let (remapped, changed) = apply_class_remappings(&data, &header, &remappings).unwrap();
+/// assert_eq!(changed, 2);
+/// assert_eq!(remapped.get_record(0, 0).unwrap().get_data(), &DataVal::String("Sound".to_string()));
+/// assert_eq!(remapped.get_record(2, 0).unwrap().get_data(), &DataVal::String("Damaged".to_string()));
+/// assert!(apply_class_remappings(&data, "Class", &remappings).is_err());
+/// ```
+pub fn apply_class_remappings(data: &Data, class_header: &str, remappings: &Vec<String>) -> Result<(Data, usize),String> {
+    let class_pairs = parse_class_remappings(remappings)?;
+    let class_idx = match data.get_header_index(class_header) {
+        Some(class_idx) => class_idx,
+        None => return Err(format!("Couldn't find the class column \"{}\" to remap classes in.", class_header)),
+    };
+    let mut changed = 0;
+    let rows = data.get_records_ref().iter().map(|row| {
+        let cells = row.get_row_data().iter().enumerate().map(|(col_idx, cell)| {
+            let raw_class = cell.get_data().to_string();
+            match class_pairs.iter().find(|(pair_raw, _)| col_idx == class_idx && pair_raw.eq(raw_class.trim())) {
+                Some((_, report_class)) => {
+                    changed += 1;
+                    DataCell::new(cell.get_header(), report_class.clone())
+                },
+                None => cell.clone(),
+            }//end matching whether this cell's class is remapped
+        }).collect();
+        DataRow::new(*row.get_row_idx(), cells)
+    }).collect();
+    Ok((Data::from_row_data(data.get_headers_ref().clone(), rows), changed))
+}//end apply_class_remappings(data, class_header, remappings)
+
+/// Gets a copy of data with each of conversions, like "Weight * 1000",
+/// applied to its column.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::pipeline::apply_unit_conversions;
+///
+/// let header = "Weight".to_string();
+/// let rows = vec![DataRow::new(0, vec![DataCell::new(&header, "0.025".to_string())]), DataRow::new(1, vec![DataCell::new(&header, "n/a".to_string())])];
+/// let data = Data::from_row_data(vec![header.clone()], rows);
+/// let converted = apply_unit_conversions(&data, &vec!["Weight * 1000".to_string()]).unwrap();
+/// assert_eq!(converted.get_record(0, 0).unwrap().get_data(), &DataVal::Float(25.0));
+/// assert_eq!(converted.get_record(1, 0).unwrap().get_data(), &DataVal::String("n/a".to_string()));
+/// ```
+pub fn apply_unit_conversions(data: &Data, conversions: &Vec<String>) -> Result<Data,String> {
+    let mut col_factors: Vec<(usize, f64)> = Vec::new();
+    for conversion in conversions.iter().filter(|conversion| !conversion.trim().is_empty()) {
+        let unit_conversion = UnitConversion::parse(conversion)?;
+        match data.get_header_index(&unit_conversion.column) {
+            Some(col_idx) => col_factors.push((col_idx, unit_conversion.factor)),
+            None => return Err(format!("Couldn't find column \"{}\" from unit conversion \"{}\".", unit_conversion.column, conversion)),
+        }//end matching whether we can find the column to convert
+    }//end parsing each conversion
+    let rows = data.get_records_ref().iter().map(|row| {
+        let cells = row.get_row_data().iter().enumerate().map(|(col_idx, cell)| {
+            let factor = col_factors.iter().filter(|(factor_col_idx, _)| *factor_col_idx == col_idx).fold(1.0, |accum, (_, factor)| accum * factor);
+            match get_cell_number(Some(cell)) {
+                Some(value) if factor != 1.0 => DataCell::new_from_val(cell.get_header(), DataVal::Float(value * factor)),
+                _ => cell.clone(),
+            }//end matching whether to convert this cell
+        }).collect();
+        DataRow::new(*row.get_row_idx(), cells)
+    }).collect();
+    Ok(Data::from_row_data(data.get_headers_ref().clone(), rows))
+}//end apply_unit_conversions(data, conversions)
+
+/// Gets a copy of data with only the rows which pass every one of
+/// filters, like "Area > 10". Returns the copy and the number of
+/// rows removed.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::pipeline::apply_row_filters;
+///
+/// let header = "Area".to_string();
+/// let rows = vec!["5", "15", "25"].into_iter().enumerate().map(|(idx, val)| DataRow::new(idx, vec![DataCell::new(&header, val.to_string())])).collect();
+/// let data = Data::from_row_data(vec![header.clone()], rows);
+/// let (filtered, removed) = apply_row_filters(&data, &vec!["Area > 10".to_string()]).unwrap();
+/// assert_eq!((filtered.get_records().len(), removed), (2, 1));
+/// ```
+pub fn apply_row_filters(data: &Data, filters: &Vec<String>) -> Result<(Data, usize),String> {
+    let numeric_filters = process::get_numeric_filters(data, filters)?;
+    let rows: Vec<DataRow> = data.get_records_ref().iter()
+        .filter(|row| process::row_passes_numeric_filters(row, &numeric_filters))
+        .cloned()
+        .collect();
+    let rows_removed = data.get_records_ref().len() - rows.len();
+    Ok((Data::from_row_data(data.get_headers_ref().clone(), rows), rows_removed))
+}//end apply_row_filters(data, filters)
+
+/// Gets a copy of data without outlier rows. Within each sample, a row is
+/// an outlier if its value in any of columns is more than 1.5 times the
+/// interquartile range below the first quartile or above the third quartile.
+/// Samples with fewer than 4 values in a column aren't checked for that column.
+/// Returns the copy and the number of rows removed.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::pipeline::remove_outliers;
+///
+/// let headers = vec!["external-sample-id".to_string(), "Area".to_string()];
+/// let rows = vec![("A", "10"), ("A", "11"), ("A", "12"), ("A", "11"), ("A", "90"), ("B", "90"), ("B", "91")]
+///     .into_iter().enumerate()
+///     .map(|(idx, (id, area))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], area.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let (cleaned, removed) = remove_outliers(&data, &vec!["Area".to_string()], "external-sample-id").unwrap();
+/// assert_eq!(removed, 1);
+/// assert_eq!(cleaned.get_records().len(), 6);
+/// ```
+pub fn remove_outliers(data: &Data, columns: &Vec<String>, sample_id_header: &str) -> Result<(Data, usize),String> {
+    let sample_id_col_idx = data.get_header_index(sample_id_header).ok_or(format!("Couldn't find sample id header \"{}\" to check for outliers within each sample.", sample_id_header))?;
+    let mut col_idxs = Vec::new();
+    for column in columns.iter().filter(|column| !column.trim().is_empty()) {
+        match data.get_header_index(column.trim()) {
+            Some(col_idx) => col_idxs.push(col_idx),
+            None => return Err(format!("Couldn't find column \"{}\" to check for outliers.", column)),
+        }//end matching whether we can find the column
+    }//end finding each column to check
+
+    // (sample id, indices of the rows in that sample)
+    let mut sample_rows: HashMap<String, Vec<usize>> = HashMap::new();
+    for (row_idx, row) in data.get_records_ref().iter().enumerate() {
+        let sample_id = row.get_data(sample_id_col_idx).map(|cell| cell.get_data().to_string()).unwrap_or_default();
+        sample_rows.entry(sample_id).or_default().push(row_idx);
+    }//end grouping rows by sample
+    let mut is_outlier = vec![false; data.get_records_ref().len()];
+    for col_idx in col_idxs {
+        for row_idxs in sample_rows.values() {
+            let row_values: Vec<(usize, f64)> = row_idxs.iter()
+                .filter_map(|row_idx| get_cell_number(data.get_record(*row_idx, col_idx)).map(|value| (*row_idx, value)))
+                .collect();
+            if row_values.len() < 4 {continue;}
+            let values = row_values.iter().map(|(_, value)| *value).collect();
+            let (q1, q3) = match (stats::get_quantile(&values, 0.25), stats::get_quantile(&values, 0.75)) {
+                (Some(q1), Some(q3)) => (q1, q3),
+                _ => continue,
+            };
+            let fence = 1.5 * (q3 - q1);
+            for (row_idx, value) in row_values {
+                if value < q1 - fence || value > q3 + fence {is_outlier[row_idx] = true;}
+            }//end marking each outlier in this sample
+        }//end checking each sample
+    }//end checking each column
+
+    let rows: Vec<DataRow> = data.get_records_ref().iter().enumerate()
+        .filter(|(row_idx, _)| !is_outlier[*row_idx])
+        .map(|(_, row)| row.clone())
+        .collect();
+    let rows_removed = is_outlier.iter().filter(|outlier| **outlier).count();
+    Ok((Data::from_row_data(data.get_headers_ref().clone(), rows), rows_removed))
+}//end remove_outliers(data, columns, sample_id_header)
+
+/// Gets a copy of data with each of derived_columns, like
+/// "Aspect = Length / Width", added as a new column at the end.
+/// Derived columns are added in order, so later ones can use earlier ones.
+/// Rows where a value isn't a number, or which would divide by zero,
+/// get an empty cell.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::pipeline::add_derived_columns;
+///
+/// let headers = vec!["Length".to_string(), "Width".to_string()];
+/// let rows = vec![("6", "2"), ("5", "0")].into_iter().enumerate()
+///     .map(|(idx, (length, width))| DataRow::new(idx, vec![DataCell::new(&headers[0], length.to_string()), DataCell::new(&headers[1], width.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let derived = add_derived_columns(&data, &vec!["Aspect = Length / Width".to_string(), "Double Aspect = Aspect * 2".to_string()]).unwrap();
+/// assert_eq!(derived.get_header_index("Double Aspect"), Some(3));
+/// assert_eq!(derived.get_record(0, 3).unwrap().get_data(), &DataVal::Float(6.0));
+/// assert_eq!(derived.get_record(1, 2).unwrap().get_data(), &DataVal::String("".to_string()));
+/// ```
+pub fn add_derived_columns(data: &Data, derived_columns: &Vec<String>) -> Result<Data,String> {
+    let mut headers = data.get_headers_ref().clone();
+    let mut rows = data.get_records_ref().clone();
+    for derived in derived_columns.iter().filter(|derived| !derived.trim().is_empty()) {
+        let derived_column = DerivedColumn::parse(derived)?;
+        if headers.contains(&derived_column.name) {return Err(format!("The derived column \"{}\" has the same name as a column that already exists.", derived_column.name));}
+        let get_col_idx = |operand: &Operand| match operand {
+            Operand::Column(column) => match headers.iter().position(|header| header.eq(column)) {
+                Some(col_idx) => Ok(Some(col_idx)),
+                None => Err(format!("Couldn't find column \"{}\" from derived column \"{}\".", column, derived)),
+            },
+            Operand::Number(_) => Ok(None),
+        };
+        let (left_idx, right_idx) = (get_col_idx(&derived_column.left)?, get_col_idx(&derived_column.right)?);
+        let get_value = |row: &DataRow, operand: &Operand, col_idx: Option<usize>| match operand {
+            Operand::Number(number) => Some(*number),
+            Operand::Column(_) => get_cell_number(col_idx.and_then(|col_idx| row.get_data(col_idx))),
+        };
+        rows = rows.into_iter().map(|row| {
+            let value = match (get_value(&row, &derived_column.left, left_idx), get_value(&row, &derived_column.right, right_idx)) {
+                (Some(left), Some(right)) => derived_column.calculate(left, right),
+                _ => None,
+            };
+            let mut cells = row.get_row_data().clone();
+            cells.push(DataCell::new_from_val(&derived_column.name, match value {
+                Some(value) => DataVal::Float(value),
+                None => DataVal::String(String::new()),
+            }));
+            DataRow::new(*row.get_row_idx(), cells)
+        }).collect();
+        headers.push(derived_column.name);
+    }//end adding each derived column
+    Ok(Data::from_row_data(headers, rows))
+}//end add_derived_columns(data, derived_columns)
+
+/// Adds the columns from each color conversion, such as "Lab" or
+/// "LCh from R, G, B", to data.  
+/// Kernels missing a red, green, or blue value get empty cells.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal};
+/// use usda_c_grain_sum::pipeline::add_color_columns;
+///
+/// let headers = vec!["Red".to_string(), "Green".to_string(), "Blue".to_string()];
+/// let rows = vec![("0", "0", "255"), ("10", "", "10")].into_iter().enumerate()
+///     .map(|(idx, (r, g, b))| DataRow::new(idx, vec![DataCell::new(&headers[0], r.to_string()), DataCell::new(&headers[1], g.to_string()), DataCell::new(&headers[2], b.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let colored = add_color_columns(&data, &vec!["Lab".to_string(), "LCh".to_string()]).unwrap();
+/// assert_eq!(colored.get_header_index("Hue Angle"), Some(7));
+/// match colored.get_record(0, 7).unwrap().get_data() {
+///     DataVal::Float(hue_angle) => assert!((hue_angle - 306.29).abs() < 0.01),
+///     other => panic!("expected a number, got {:?}", other),
+/// }
+/// assert_eq!(colored.get_record(1, 3).unwrap().get_data(), &DataVal::String("".to_string()));
+/// assert!(add_color_columns(&data, &vec!["Lab from R, G, B".to_string()]).is_err());
+/// ```
+pub fn add_color_columns(data: &Data, conversions: &Vec<String>) -> Result<Data,String> {
+    let mut headers = data.get_headers_ref().clone();
+    let mut rows = data.get_records_ref().clone();
+    for conversion in conversions.iter().filter(|conversion| !conversion.trim().is_empty()) {
+        let color_conversion = ColorConversion::parse(conversion)?;
+        let mut rgb_idxs = [0; 3];
+        for (channel_idx, column) in color_conversion.rgb_columns.iter().enumerate() {
+            rgb_idxs[channel_idx] = match headers.iter().position(|header| header.eq(column)) {
+                Some(col_idx) => col_idx,
+                None => return Err(format!("Couldn't find column \"{}\" from color conversion \"{}\".", column, conversion)),
+            };
+        }//end finding each rgb column
+        let new_headers = color_conversion.space.get_headers();
+        if let Some(existing) = new_headers.iter().find(|new_header| headers.iter().any(|header| header.eq(*new_header))) {
+            return Err(format!("The color conversion \"{}\" adds a column \"{}\", but that column already exists.", conversion, existing));
+        }//end if a new column would have the same name as another
+        rows = rows.into_iter().map(|row| {
+            let rgb: Vec<Option<f64>> = rgb_idxs.iter().map(|col_idx| get_cell_number(row.get_data(*col_idx))).collect();
+            let values: Vec<Option<f64>> = match (rgb[0], rgb[1], rgb[2]) {
+                (Some(red), Some(green), Some(blue)) => {
+                    let (l, a, b) = rgb_to_lab(red, green, blue);
+                    match color_conversion.space {
+                        ColorSpace::Lab => vec![Some(l), Some(a), Some(b)],
+                        ColorSpace::LCh => vec![Some((a * a + b * b).sqrt()), Some(b.atan2(a).to_degrees().rem_euclid(360.0))],
+                    }//end matching which columns to calculate
+                },
+                _ => vec![None; new_headers.len()],
+            };
+            let mut cells = row.get_row_data().clone();
+            for (new_header, value) in new_headers.iter().zip(values) {
+                cells.push(DataCell::new_from_val(&new_header.to_string(), match value {
+                    Some(value) => DataVal::Float(value),
+                    None => DataVal::String(String::new()),
+                }));
+            }//end adding each new cell
+            DataRow::new(*row.get_row_idx(), cells)
+        }).collect();
+        headers.extend(new_headers.iter().map(|new_header| new_header.to_string()));
+    }//end adding the columns of each color conversion
+    Ok(Data::from_row_data(headers, rows))
+}//end add_color_columns(data, conversions)
+
+/// Gets the steps in config.pipeline_steps which have settings, and so
+/// would change the data when the pipeline is run, in order.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::{ConfigStore, PipelineStep};
+/// use usda_c_grain_sum::pipeline::get_active_steps;
+///
+/// let mut config = ConfigStore::default();
+/// assert!(get_active_steps(&config).is_empty());
+/// config.derived_columns = vec!["Aspect = Length / Width".to_string()];
+/// assert_eq!(get_active_steps(&config), vec![PipelineStep::DerivedColumns]);
+/// ```
+pub fn get_active_steps(config: &ConfigStore) -> Vec<PipelineStep> {
+    config.pipeline_steps.iter().copied().filter(|step| match step {
+        PipelineStep::AliasMapping => config.csv_header_aliases.len() > 0,
+        PipelineStep::ClassRemapping => config.class_remappings.len() > 0,
+        PipelineStep::UnitConversion => config.unit_conversions.len() > 0,
+        PipelineStep::Filters => config.csv_row_filters.len() > 0,
+        PipelineStep::OutlierRemoval => config.outlier_columns.len() > 0,
+        PipelineStep::ColorConversion => config.color_conversions.len() > 0,
+        PipelineStep::DerivedColumns => config.derived_columns.len() > 0,
+    }).collect()
+}//end get_active_steps(config)
+
+/// Runs each step in config.pipeline_steps on data, in order, before
+/// any output is calculated. Steps without any settings do nothing.
+/// Returns the processed copy of data, along with a message describing
+/// what each step changed, which can be shown to the user.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::app::config_store::{ConfigStore, PipelineStep};
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::pipeline::run_pipeline;
+///
+/// let headers = vec!["external-sample-id".to_string(), "Length".to_string(), "Width".to_string()];
+/// let rows = vec![("A", "6", "2"), ("A", "4", "2"), ("B", "3", "3")].into_iter().enumerate()
+///     .map(|(idx, (id, length, width))| DataRow::new(idx, vec![DataCell::new(&headers[0], id.to_string()), DataCell::new(&headers[1], length.to_string()), DataCell::new(&headers[2], width.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(headers, rows);
+/// let mut config = ConfigStore::default();
+/// config.derived_columns = vec!["Aspect = Length / Width".to_string()];
+/// config.csv_row_filters = vec!["Aspect > 1.5".to_string()];
+///
+/// // by default, filters run before derived columns, so they can't use them
+/// assert!(run_pipeline(&data, &config).is_err());
+/// config.pipeline_steps = vec![PipelineStep::DerivedColumns, PipelineStep::Filters];
+/// let (processed, messages) = run_pipeline(&data, &config).unwrap();
+/// assert_eq!(processed.get_records().len(), 2);
+/// assert_eq!(messages.len(), 2);
+/// ```
+pub fn run_pipeline(data: &Data, config: &ConfigStore) -> Result<(Data, Vec<String>),String> {
+    let mut data = data.clone();
+    let mut messages = Vec::new();
+    for step in config.pipeline_steps.iter() {
+        let step_result = match step {
+            PipelineStep::AliasMapping if config.csv_header_aliases.len() > 0 => {
+                apply_header_aliases(&mut data, &config.csv_header_aliases).map(|renamed| format!("Renamed {} columns.", renamed))
+            },
+            PipelineStep::ClassRemapping if config.class_remappings.len() > 0 => {
+                apply_class_remappings(&data, &config.csv_class_filter_class, &config.class_remappings).map(|(remapped, changed)| {
+                    data = remapped;
+                    format!("Remapped the class of {} kernels.", changed)
+                })
+            },
+            PipelineStep::UnitConversion if config.unit_conversions.len() > 0 => {
+                apply_unit_conversions(&data, &config.unit_conversions).map(|converted| {
+                    data = converted;
+                    format!("Converted units of {} columns.", config.unit_conversions.len())
+                })
+            },
+            PipelineStep::Filters if config.csv_row_filters.len() > 0 => {
+                apply_row_filters(&data, &config.csv_row_filters).map(|(filtered, removed)| {
+                    data = filtered;
+                    format!("Removed {} kernels which didn't pass the filters.", removed)
+                })
+            },
+            PipelineStep::OutlierRemoval if config.outlier_columns.len() > 0 => {
+                remove_outliers(&data, &config.outlier_columns, &config.csv_sample_id_header).map(|(cleaned, removed)| {
+                    data = cleaned;
+                    format!("Removed {} outlier kernels.", removed)
+                })
+            },
+            PipelineStep::ColorConversion if config.color_conversions.len() > 0 => {
+                add_color_columns(&data, &config.color_conversions).map(|colored| {
+                    data = colored;
+                    format!("Added columns for {} color conversions.", config.color_conversions.len())
+                })
+            },
+            PipelineStep::DerivedColumns if config.derived_columns.len() > 0 => {
+                add_derived_columns(&data, &config.derived_columns).map(|derived| {
+                    data = derived;
+                    format!("Added {} derived columns.", config.derived_columns.len())
+                })
+            },
+            _ => continue,
+        };
+        match step_result {
+            Ok(message) => messages.push(format!("{}: {}", step.get_name(), message)),
+            Err(msg) => return Err(format!("The {} step of the processing pipeline failed.\n{}", step.get_name(), msg)),
+        }//end matching whether this step worked
+    }//end running each step
+    Ok((data, messages))
+}//end run_pipeline(data, config)
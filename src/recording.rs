@@ -0,0 +1,131 @@
+use std::{fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use usda_c_grain_sum::{app::config_store::{self, ConfigStore}, io::{schema::ExportFileKind, source}};
+
+use crate::gui::InterfaceMessage;
+
+/// The steps of a recorded processing session, along with the settings
+/// used to process, so the same session can be replayed on new files.
+#[derive(Clone,PartialEq,Debug,Serialize,Deserialize)]
+pub struct SessionRecording {
+    /// The settings from the last time Process Data was recorded.
+    pub config: ConfigStore,
+    /// The recorded messages, in the order they were sent.
+    pub steps: Vec<InterfaceMessage>,
+}//end struct SessionRecording
+
+impl SessionRecording {
+    /// Starts a recording with no steps, using config until
+    /// Process Data is recorded.
+    pub fn new(config: ConfigStore) -> SessionRecording {
+        SessionRecording { config, steps: Vec::new() }
+    }//end new(config)
+
+    /// Adds message to the steps, if it should be recorded.
+    /// When Process Data is recorded, the settings from get_config are
+    /// kept as well, since those are the settings used for the output.
+    pub fn record(&mut self, message: &InterfaceMessage, get_config: impl FnOnce() -> ConfigStore) {
+        if !SessionRecording::is_recordable(message) {return;}
+        self.steps.push(message.clone());
+        if *message == InterfaceMessage::ProcessSum {
+            self.config = get_config();
+        }//end if we should keep the settings used to process
+    }//end record(self, message, get_config)
+
+    /// Tells whether message is part of the processing itself, and so
+    /// should be recorded.
+    /// Settings changes, such as switching presets, aren't recorded,
+    /// since the config saved with the recording already covers them,
+    /// and actions which only show windows aren't needed to replay.
+    pub fn is_recordable(message: &InterfaceMessage) -> bool {
+        match message {
+            InterfaceMessage::CSVInputFile(_) => true,
+            InterfaceMessage::XMLInputFile(_) => true,
+            InterfaceMessage::OutputFile(_) => true,
+            InterfaceMessage::ProcessSum => true,
+            _ => false,
+        }//end matching whether message is part of processing
+    }//end is_recordable(message)
+
+    /// Gets the steps to send to replay this recording on new files.
+    /// Each recorded csv file is replaced by the next of csv_files, and each
+    /// recorded xml file by the next of xml_files. Recorded output files are
+    /// left out, and output is chosen before each Process Data instead, so
+    /// last week's output isn't overwritten.
+    pub fn get_replay_steps(&self, csv_files: &Vec<PathBuf>, xml_files: &Vec<PathBuf>, output: &PathBuf) -> Result<Vec<InterfaceMessage>,String> {
+        let csv_needed = self.steps.iter().filter(|step| matches!(step, InterfaceMessage::CSVInputFile(_))).count();
+        let xml_needed = self.steps.iter().filter(|step| matches!(step, InterfaceMessage::XMLInputFile(_))).count();
+        if csv_files.len() != csv_needed || xml_files.len() != xml_needed {
+            return Err(format!("The recording loads {} csv and {} xml file(s), but {} csv and {} xml file(s) were chosen.", csv_needed, xml_needed, csv_files.len(), xml_files.len()));
+        }//end if the wrong number of files were chosen
+        let mut csv_files = csv_files.iter();
+        let mut xml_files = xml_files.iter();
+        let mut replay_steps = Vec::new();
+        for step in self.steps.iter() {
+            match step {
+                InterfaceMessage::CSVInputFile(_) => if let Some(csv_file) = csv_files.next() {replay_steps.push(InterfaceMessage::CSVInputFile(csv_file.clone()));},
+                InterfaceMessage::XMLInputFile(_) => if let Some(xml_file) = xml_files.next() {replay_steps.push(InterfaceMessage::XMLInputFile(xml_file.clone()));},
+                InterfaceMessage::OutputFile(_) => {},
+                InterfaceMessage::ProcessSum => {
+                    replay_steps.push(InterfaceMessage::OutputFile(output.clone()));
+                    replay_steps.push(InterfaceMessage::ProcessSum);
+                },
+                other_step => replay_steps.push(other_step.clone()),
+            }//end matching what to replay for this step
+        }//end getting the replay of each step
+        Ok(replay_steps)
+    }//end get_replay_steps(self, csv_files, xml_files, output)
+}//end impl SessionRecording
+
+/// Sorts new input files into (csv files, xml files) in the order they
+/// were chosen, using the source which loads each file to tell which it is.
+pub fn sort_input_files(file_paths: &Vec<PathBuf>) -> Result<(Vec<PathBuf>, Vec<PathBuf>),String> {
+    let mut csv_files = Vec::new();
+    let mut xml_files = Vec::new();
+    for file_path in file_paths {
+        match source::find_source(file_path).map(|data_source| data_source.export_kind()) {
+            Ok(ExportFileKind::Csv) => csv_files.push(file_path.clone()),
+            Ok(ExportFileKind::Xml) => xml_files.push(file_path.clone()),
+            Ok(other_kind) => return Err(format!("{} has {:?} data, which can't be processed by this version.", file_path.to_string_lossy(), other_kind)),
+            Err(msg) => return Err(format!("{}: {}", file_path.to_string_lossy(), msg)),
+        }//end matching what kind of data this file has
+    }//end sorting each file
+    Ok((csv_files, xml_files))
+}//end sort_input_files(file_paths)
+
+/// Attempts to write recording to the given path as json.
+pub fn try_write_recording(recording_path: &PathBuf, recording: &SessionRecording) -> Result<(),String> {
+    match serde_json::to_string_pretty(recording) {
+        Ok(recording_json) => match fs::write(recording_path, recording_json) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(format!("Couldn't write the recording to {}: {}", recording_path.to_string_lossy(), error)),
+        },
+        Err(error) => Err(format!("Couldn't convert the recording to json: {}", error)),
+    }//end matching whether we could serialize the recording
+}//end try_write_recording(recording_path, recording)
+
+/// Attempts to read a recording from the json file at the given path.
+/// The config in the recording is migrated like the config file, so
+/// recordings from older versions can still be replayed.
+pub fn try_read_recording(recording_path: &PathBuf) -> Result<SessionRecording,String> {
+    let recording_json = match fs::read_to_string(recording_path) {
+        Ok(recording_json) => recording_json,
+        Err(error) => return Err(format!("Couldn't read the recording at {}: {}", recording_path.to_string_lossy(), error)),
+    };
+    let mut recording_value = match serde_json::from_str::<serde_json::Value>(&recording_json) {
+        Ok(serde_json::Value::Object(recording_value)) => recording_value,
+        Ok(_) => return Err(String::from("The recording doesn't hold a json object.")),
+        Err(error) => return Err(format!("Couldn't read the recording as json: {}", error)),
+    };
+    let config = match recording_value.remove("config") {
+        Some(config_value) => config_store::migrate_config_json(&config_value.to_string())?.0,
+        None => return Err(String::from("The recording doesn't have any settings.")),
+    };
+    let steps = match recording_value.remove("steps").map(serde_json::from_value::<Vec<InterfaceMessage>>) {
+        Some(Ok(steps)) => steps,
+        Some(Err(error)) => return Err(format!("Couldn't read the steps of the recording: {}", error)),
+        None => return Err(String::from("The recording doesn't have any steps.")),
+    };
+    Ok(SessionRecording { config, steps })
+}//end try_read_recording(recording_path)
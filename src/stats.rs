@@ -0,0 +1,220 @@
+use crate::model::{Data, DataRow, DataVal};
+
+/// Summary statistics for one column of loaded data, such as
+/// for helping choose filters before processing.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColumnSummary {
+    /// The number of cells in the column which hold a number.
+    pub numeric_count: usize,
+    /// The number of cells in the column which hold text that isn't a number.
+    pub non_numeric_count: usize,
+    /// The number of cells in the column which are empty, or missing from short rows.
+    pub missing_count: usize,
+    /// The smallest number in the column.
+    pub min: f64,
+    /// The largest number in the column.
+    pub max: f64,
+    /// The mean of the numbers in the column.
+    pub mean: f64,
+    /// The population standard deviation of the numbers in the column,
+    /// calculated the same way as the stat columns in the output.
+    pub std_dev: f64,
+}//end struct ColumnSummary
+
+/// Gets the numbers in column col_idx of data, along with the number
+/// of cells which held other text, and the number of cells which were
+/// empty or missing, as (numbers, non-numeric count, missing count).
+pub fn get_column_values(data: &Data, col_idx: usize) -> (Vec<f64>, usize, usize) {
+    get_row_values(&data.get_records(), col_idx)
+}//end get_column_values(data, col_idx)
+
+/// Gets the numbers in column col_idx of rows, along with the number
+/// of cells which held other text, and the number of cells which were
+/// empty or missing, as (numbers, non-numeric count, missing count).
+/// 
+/// # Examples
+/// 
+/// ```
+/// use usda_c_grain_sum::model::{DataCell, DataRow};
+/// use usda_c_grain_sum::stats::get_row_values;
+/// 
+/// let header = "Weight".to_string();
+/// let rows: Vec<DataRow> = vec!["1.5", "NaN?", "", "3"].into_iter().enumerate()
+///     .map(|(idx, val)| DataRow::new(idx, vec![DataCell::new(&header, val.to_string())]))
+///     .collect();
+/// let row_refs = rows.iter().collect();
+/// assert_eq!(get_row_values(&row_refs, 0), (vec![1.5, 3.0], 1, 1));
+/// ```
+pub fn get_row_values(rows: &Vec<&DataRow>, col_idx: usize) -> (Vec<f64>, usize, usize) {
+    let mut values = Vec::new();
+    let mut non_numeric_count = 0;
+    let mut missing_count = 0;
+    for row in rows.iter() {
+        match row.get_data(col_idx).map(|cell| cell.get_data()) {
+            Some(DataVal::Int(i)) => values.push(*i as f64),
+            Some(DataVal::Float(f)) => values.push(*f),
+            Some(DataVal::String(s)) if !s.trim().is_empty() => non_numeric_count += 1,
+            _ => missing_count += 1,
+        }//end matching the type of this cell
+    }//end checking each row
+    (values, non_numeric_count, missing_count)
+}//end get_row_values(rows, col_idx)
+
+/// Gets summary statistics for column col_idx of data.
+/// Returns an error if the column doesn't hold any numbers.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::model::{Data, DataCell, DataRow};
+/// use usda_c_grain_sum::stats::get_column_summary;
+///
+/// let header = "Area".to_string();
+/// let rows = vec!["2", "4", "oops", "", "6"].into_iter().enumerate()
+///     .map(|(idx, val)| DataRow::new(idx, vec![DataCell::new(&header, val.to_string())]))
+///     .collect();
+/// let data = Data::from_row_data(vec![header.clone()], rows);
+///
+/// let summary = get_column_summary(&data, 0).unwrap();
+/// assert_eq!((summary.numeric_count, summary.non_numeric_count, summary.missing_count), (3, 1, 1));
+/// assert_eq!((summary.min, summary.max, summary.mean), (2.0, 6.0, 4.0));
+/// assert!((summary.std_dev - 1.63299).abs() < 0.0001);
+/// assert!(get_column_summary(&data, 1).is_err());
+/// ```
+pub fn get_column_summary(data: &Data, col_idx: usize) -> Result<ColumnSummary,String> {
+    let (values, non_numeric_count, missing_count) = get_column_values(data, col_idx);
+    if values.len() == 0 {
+        let header = data.get_header_from_index(col_idx).cloned().unwrap_or(format!("at index {}", col_idx));
+        return Err(format!("Column {} doesn't have any numbers.", header));
+    }//end if there aren't any numbers to summarize
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / count;
+    Ok(ColumnSummary {
+        numeric_count: values.len(),
+        non_numeric_count,
+        missing_count,
+        min: values.iter().copied().fold(f64::INFINITY, f64::min),
+        max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        mean,
+        std_dev: variance.sqrt(),
+    })
+}//end get_column_summary(data, col_idx)
+
+/// Splits the range of values into bin_count bins of equal width, and
+/// counts the values in each bin. The last bin includes the max value.
+/// Returns (start of bin, end of bin, count) for each bin.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::stats::get_histogram;
+///
+/// let bins = get_histogram(&vec![0.0, 1.0, 1.5, 4.0], 4);
+/// assert_eq!(bins, vec![(0.0, 1.0, 1), (1.0, 2.0, 2), (2.0, 3.0, 0), (3.0, 4.0, 1)]);
+/// assert_eq!(get_histogram(&vec![5.0, 5.0], 3), vec![(5.0, 5.0, 2)]);
+/// assert!(get_histogram(&Vec::new(), 3).is_empty());
+/// ```
+pub fn get_histogram(values: &Vec<f64>, bin_count: usize) -> Vec<(f64, f64, usize)> {
+    if values.len() == 0 || bin_count == 0 {return Vec::new();}
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    // every value is the same, so one bin holds them all
+    if max <= min {return vec![(min, max, values.len())];}
+    let bin_width = (max - min) / bin_count as f64;
+    let mut bins: Vec<(f64, f64, usize)> = (0..bin_count)
+        .map(|bin_idx| (min + bin_width * bin_idx as f64, min + bin_width * (bin_idx + 1) as f64, 0))
+        .collect();
+    for value in values.iter() {
+        let bin_idx = (((value - min) / bin_width) as usize).min(bin_count - 1);
+        bins[bin_idx].2 += 1;
+    }//end counting each value in its bin
+    bins
+}//end get_histogram(values, bin_count)
+
+/// Gets the quantile q, from 0 to 1, of values, interpolating linearly
+/// between the closest two values. Returns None if values is empty.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::stats::get_quantile;
+///
+/// let values = vec![4.0, 1.0, 3.0, 2.0];
+/// assert_eq!(get_quantile(&values, 0.0), Some(1.0));
+/// assert_eq!(get_quantile(&values, 0.5), Some(2.5));
+/// assert_eq!(get_quantile(&values, 0.25), Some(1.75));
+/// assert_eq!(get_quantile(&Vec::new(), 0.5), None);
+/// ```
+pub fn get_quantile(values: &Vec<f64>, q: f64) -> Option<f64> {
+    if values.len() == 0 {return None;}
+    let mut sorted = values.clone();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower_idx = position.floor() as usize;
+    let upper_idx = position.ceil() as usize;
+    let fraction = position - lower_idx as f64;
+    Some(sorted[lower_idx] + (sorted[upper_idx] - sorted[lower_idx]) * fraction)
+}//end get_quantile(values, q)
+
+/// The statistics which can be added to the csv stat columns, after
+/// the average and standard deviation of each column.
+pub const EXTRA_STATS: [&str; 6] = ["Median", "Min", "Max", "Q1", "Q3", "CV"];
+
+/// Finds the name in EXTRA_STATS matching stat, ignoring case and
+/// surrounding whitespace, so it can be written in column headers.
+/// Returns an error listing the choices if stat isn't one of them.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::stats::parse_extra_stat;
+///
+/// assert_eq!(parse_extra_stat(" median ").unwrap(), "Median");
+/// assert_eq!(parse_extra_stat("q3").unwrap(), "Q3");
+/// assert!(parse_extra_stat("Mode").is_err());
+/// ```
+pub fn parse_extra_stat(stat: &str) -> Result<&'static str,String> {
+    match EXTRA_STATS.iter().find(|known_stat| known_stat.eq_ignore_ascii_case(stat.trim())) {
+        Some(known_stat) => Ok(known_stat),
+        None => Err(format!("\"{}\" isn't a known statistic. The choices are {}.", stat.trim(), EXTRA_STATS.join(", "))),
+    }//end matching whether this is a known statistic
+}//end parse_extra_stat(stat)
+
+/// Calculates stat, one of EXTRA_STATS, for values. CV is the coefficient
+/// of variation, which is the standard deviation as a fraction of the mean.
+/// Quartiles and medians are interpolated like get_quantile().  
+/// Returns None if values is empty, or for CV, if the mean is 0.
+///
+/// # Examples
+///
+/// ```
+/// use usda_c_grain_sum::stats::get_extra_stat;
+///
+/// let values = vec![4.0, 1.0, 3.0, 2.0];
+/// assert_eq!(get_extra_stat(&values, "Median").unwrap(), Some(2.5));
+/// assert_eq!(get_extra_stat(&values, "Min").unwrap(), Some(1.0));
+/// assert_eq!(get_extra_stat(&values, "Max").unwrap(), Some(4.0));
+/// assert_eq!(get_extra_stat(&values, "Q1").unwrap(), Some(1.75));
+/// assert_eq!(get_extra_stat(&values, "Q3").unwrap(), Some(3.25));
+/// let cv = get_extra_stat(&values, "CV").unwrap().unwrap();
+/// assert!((cv - 0.44721).abs() < 0.0001);
+/// assert_eq!(get_extra_stat(&Vec::new(), "Max").unwrap(), None);
+/// assert!(get_extra_stat(&values, "Mode").is_err());
+/// ```
+pub fn get_extra_stat(values: &Vec<f64>, stat: &str) -> Result<Option<f64>,String> {
+    if values.len() == 0 {return parse_extra_stat(stat).map(|_| None);}
+    match parse_extra_stat(stat)? {
+        "Median" => Ok(get_quantile(values, 0.5)),
+        "Min" => Ok(Some(values.iter().copied().fold(f64::INFINITY, f64::min))),
+        "Max" => Ok(Some(values.iter().copied().fold(f64::NEG_INFINITY, f64::max))),
+        "Q1" => Ok(get_quantile(values, 0.25)),
+        "Q3" => Ok(get_quantile(values, 0.75)),
+        _ => {
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            if mean == 0.0 {return Ok(None);}
+            let variance = values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / values.len() as f64;
+            Ok(Some(variance.sqrt() / mean))
+        },
+    }//end matching which statistic to calculate
+}//end get_extra_stat(values, stat)
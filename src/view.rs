@@ -0,0 +1,172 @@
+/// Sorts rows of text, such as from process::get_sheet_text_table(),
+/// by the text in col_idx.  
+/// Cells which hold numbers, including percents, are sorted by their value,
+/// and come before cells with other text. Empty cells always come last.
+pub fn sort_text_rows(rows: &mut Vec<Vec<String>>, col_idx: usize, ascending: bool) {
+    rows.sort_by(|row_a, row_b| {
+        let cell_a = row_a.get(col_idx).map(|cell| cell.trim()).unwrap_or("");
+        let cell_b = row_b.get(col_idx).map(|cell| cell.trim()).unwrap_or("");
+        // empty cells go last, no matter which way we sort
+        match (cell_a.is_empty(), cell_b.is_empty()) {
+            (true, true) => return std::cmp::Ordering::Equal,
+            (true, false) => return std::cmp::Ordering::Greater,
+            (false, true) => return std::cmp::Ordering::Less,
+            (false, false) => {},
+        }//end matching whether either cell is empty
+        let ordering = match (parse_text_number(cell_a), parse_text_number(cell_b)) {
+            (Some(num_a), Some(num_b)) => num_a.partial_cmp(&num_b).unwrap_or(std::cmp::Ordering::Equal),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => cell_a.cmp(cell_b),
+        };
+        if ascending {ordering} else {ordering.reverse()}
+    });
+}//end sort_text_rows(rows, col_idx, ascending)
+
+/// Parses the number shown in a cell of text, such as "12.35" or "90.0%".  
+/// Percents are parsed as shown, so "90.0%" gives 90.0.
+fn parse_text_number(cell: &str) -> Option<f64> {
+    cell.trim().trim_end_matches('%').replace(',', "").parse::<f64>().ok()
+}//end parse_text_number(cell)
+
+/// Gets the value in col_idx of each row of text which holds a number,
+/// paired with the first cell of the row, such as for charting a column
+/// across samples. Rows without a number in that column are left out.
+pub fn get_chart_values(rows: &Vec<Vec<String>>, col_idx: usize) -> Vec<(String, f64)> {
+    rows.iter()
+        .filter_map(|row| row.get(col_idx)
+            .and_then(|cell| parse_text_number(cell))
+            .map(|value| (row.first().cloned().unwrap_or_default(), value)))
+        .collect()
+}//end get_chart_values(rows, col_idx)
+
+/// Gets a round number at least as large as value, to use as the
+/// top of a chart axis, such as 100 for 87 or 0.5 for 0.42.
+pub fn get_nice_axis_max(value: f64) -> f64 {
+    if value <= 0.0 || !value.is_finite() {return 1.0;}
+    let magnitude = 10f64.powf(value.log10().floor());
+    for step in [1.0, 2.0, 2.5, 5.0, 10.0] {
+        if step * magnitude >= value {return step * magnitude;}
+    }//end finding the smallest round number above value
+    return 10.0 * magnitude;
+}//end get_nice_axis_max(value)
+
+/// Gets the rows of text which have a cell containing filter,
+/// ignoring case. If filter is empty, every row is returned.
+pub fn filter_text_rows(rows: &Vec<Vec<String>>, filter: &str) -> Vec<Vec<String>> {
+    let filter = filter.trim().to_lowercase();
+    rows.iter()
+        .filter(|row| filter.is_empty() || row.iter().any(|cell| cell.to_lowercase().contains(&filter)))
+        .cloned()
+        .collect()
+}//end filter_text_rows(rows, filter)
+
+/// Gets the rows of text which have a cell containing filter,
+/// ignoring case, but only looking at the columns in col_idxs,
+/// such as the sample id and class columns. If filter is empty,
+/// every row is returned.
+pub fn filter_text_rows_in_columns(rows: &Vec<Vec<String>>, col_idxs: &Vec<usize>, filter: &str) -> Vec<Vec<String>> {
+    let filter = filter.trim().to_lowercase();
+    rows.iter()
+        .filter(|row| filter.is_empty() || col_idxs.iter().any(|col_idx| row.get(*col_idx).is_some_and(|cell| cell.to_lowercase().contains(&filter))))
+        .cloned()
+        .collect()
+}//end filter_text_rows_in_columns(rows, col_idxs, filter)
+
+/// Splits a table into printed pages.  
+/// col_widths is the width of each column, where the first column holds
+/// the sample ids, and is repeated at the left of every page. The other
+/// columns are split into groups that fit within page_width, and the rows
+/// are split into groups of rows_per_page, with every group of columns
+/// printed for one group of rows before moving on to the next rows.  
+/// Returns the columns, first row, and end row of each page, where the end row is exclusive.
+pub fn get_print_pages(col_widths: &Vec<i32>, page_width: i32, row_count: usize, rows_per_page: usize) -> Vec<(Vec<usize>, usize, usize)> {
+    let rows_per_page = rows_per_page.max(1);
+    let id_width = col_widths.first().copied().unwrap_or(0);
+    let mut col_groups: Vec<Vec<usize>> = Vec::new();
+    let mut current_group = vec![0];
+    let mut current_width = id_width;
+    for (col_idx, col_width) in col_widths.iter().enumerate().skip(1) {
+        // always put at least one column on a page, even if it's too wide
+        if current_group.len() > 1 && current_width + col_width > page_width {
+            col_groups.push(current_group);
+            current_group = vec![0];
+            current_width = id_width;
+        }//end if this column needs to start a new page
+        current_group.push(col_idx);
+        current_width += col_width;
+    }//end fitting each column onto a page
+    col_groups.push(current_group);
+
+    let mut pages = Vec::new();
+    let mut first_row = 0;
+    while first_row < row_count || (row_count == 0 && pages.is_empty()) {
+        let end_row = (first_row + rows_per_page).min(row_count);
+        for col_group in col_groups.iter() {pages.push((col_group.clone(), first_row, end_row));}
+        first_row = end_row;
+        if row_count == 0 {break;}
+    }//end splitting rows into pages
+    pages
+}//end get_print_pages(col_widths, page_width, row_count, rows_per_page)
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_text_rows() {
+        let mut rows: Vec<Vec<String>> = vec![vec!["A", "9.5%"], vec!["B", ""], vec!["C", "10.0%"], vec!["D", "n/a"]]
+            .into_iter().map(|row| row.into_iter().map(|cell| cell.to_string()).collect()).collect();
+        sort_text_rows(&mut rows, 1, true);
+        let ids: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(ids, vec!["A", "C", "D", "B"]);
+        sort_text_rows(&mut rows, 1, false);
+        let ids: Vec<&str> = rows.iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(ids, vec!["D", "C", "A", "B"]);
+    }//end test_sort_text_rows()
+
+    #[test]
+    fn test_get_chart_values() {
+        let rows: Vec<Vec<String>> = vec![vec!["A", "12.5"], vec!["B", ""], vec!["C", "90.0%"]]
+            .into_iter().map(|row| row.into_iter().map(|cell| cell.to_string()).collect()).collect();
+        assert_eq!(get_chart_values(&rows, 1), vec![("A".to_string(), 12.5), ("C".to_string(), 90.0)]);
+    }//end test_get_chart_values()
+
+    #[test]
+    fn test_get_nice_axis_max() {
+        assert_eq!(get_nice_axis_max(87.0), 100.0);
+        assert_eq!(get_nice_axis_max(0.42), 0.5);
+        assert_eq!(get_nice_axis_max(13.0), 20.0);
+        assert_eq!(get_nice_axis_max(0.0), 1.0);
+    }//end test_get_nice_axis_max()
+
+    #[test]
+    fn test_filter_text_rows() {
+        let rows = vec![vec!["W23-07-1".to_string(), "12.3".to_string()], vec!["S23-01-1".to_string(), "8.1".to_string()]];
+        assert_eq!(filter_text_rows(&rows, "w23").len(), 1);
+        assert_eq!(filter_text_rows(&rows, "").len(), 2);
+    }//end test_filter_text_rows()
+
+    #[test]
+    fn test_filter_text_rows_in_columns() {
+        let rows = vec![
+            vec!["W23-07-1".to_string(), "Sound".to_string(), "7".to_string()],
+            vec!["W23-07-2".to_string(), "Broken".to_string(), "23".to_string()],
+        ];
+        assert_eq!(filter_text_rows_in_columns(&rows, &vec![0, 1], "07-2").len(), 1);
+        assert_eq!(filter_text_rows_in_columns(&rows, &vec![0, 1], "sound").len(), 1);
+        assert_eq!(filter_text_rows_in_columns(&rows, &vec![0, 1], "23").len(), 2);
+        assert_eq!(filter_text_rows_in_columns(&rows, &vec![1], "23").len(), 0);
+    }//end test_filter_text_rows_in_columns()
+
+    #[test]
+    fn test_get_print_pages() {
+        let pages = get_print_pages(&vec![40, 30, 30, 30], 100, 5, 3);
+        assert_eq!(pages, vec![
+            (vec![0, 1, 2], 0, 3),
+            (vec![0, 3], 0, 3),
+            (vec![0, 1, 2], 3, 5),
+            (vec![0, 3], 3, 5),
+        ]);
+    }//end test_get_print_pages()
+}//end mod tests
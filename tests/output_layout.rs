@@ -0,0 +1,242 @@
+//! Writes the output of each processor to an actual xlsx file, then reads it
+//! back with calamine to check the layout of the sheet a user would open.
+
+mod support;
+
+use support::WorkbookView;
+use usda_c_grain_sum::app::config_store::{ConfigStore, SheetLayout};
+use usda_c_grain_sum::app::process;
+use usda_c_grain_sum::io::xlsx;
+use usda_c_grain_sum::model::{Data, DataCell, DataRow, DataVal, OutputColumn, SampleOutput};
+
+/// Makes Data with headers and a row for each of rows.
+fn make_data(headers: Vec<&str>, rows: Vec<Vec<&str>>) -> Data {
+    let headers: Vec<String> = headers.into_iter().map(|header| header.to_string()).collect();
+    let rows = rows.into_iter().enumerate()
+        .map(|(idx, row)| DataRow::new(idx, row.into_iter().zip(headers.iter()).map(|(value, header)| DataCell::new(header, value.to_string())).collect()))
+        .collect();
+    Data::from_row_data(headers, rows)
+}//end make_data(headers, rows)
+
+#[test]
+fn csv_stats_and_class_percents_layout() {
+    let data = make_data(vec!["external-sample-id", "raw-filtered-as", "Area"], vec![vec!["S1", "Sound", "10"], vec!["S1", "Broken", "14"], vec!["S2", "Sound", "12"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string()];
+
+    let output_sheets = vec![
+        ("CSV_Stats".to_string(), process::proc_csv_stat_cols(&data, &config).unwrap()),
+        ("Class_Percents".to_string(), process::proc_csv_class_per(&data, &config).unwrap()),
+    ];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_sheet_names(&["CSV_Stats", "Class_Percents"]).unwrap();
+    workbook.assert_headers("CSV_Stats", &["external-sample-id", "Avg Area", "Std Area"]).unwrap();
+    workbook.assert_column("CSV_Stats", "external-sample-id", &["S1", "S2"]).unwrap();
+    workbook.assert_cell("CSV_Stats", "B2", "12").unwrap();
+    workbook.assert_headers("Class_Percents", &["external-sample-id", "%Sound", "%Broken"]).unwrap();
+    workbook.assert_column("Class_Percents", "%Sound", &["0.5", "1"]).unwrap();
+    workbook.assert_row_count("Class_Percents", 2).unwrap();
+}//end csv_stats_and_class_percents_layout()
+
+#[test]
+fn csv_stats_extra_stats_layout() {
+    let data = make_data(vec!["external-sample-id", "Area"], vec![vec!["A", "1"], vec!["A", "3"], vec!["A", "8"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string()];
+    config.csv_stat_columns_stats = vec!["Median".to_string(), "max".to_string()];
+
+    // the written sheet has the sample id, then each stat
+    let output_sheets = vec![("CSV_Stats".to_string(), process::proc_csv_stat_cols(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("CSV_Stats", &["external-sample-id", "Avg Area", "Std Area", "Median Area", "Max Area"]).unwrap();
+    workbook.assert_cell("CSV_Stats", "A2", "A").unwrap();
+    workbook.assert_cell("CSV_Stats", "E2", "8").unwrap();
+}//end csv_stats_extra_stats_layout()
+
+#[test]
+fn csv_stats_group_layout() {
+    let data = make_data(vec!["external-sample-id", "Treatment", "Area"], vec![vec!["S1", "Control", "10"], vec!["S1", "Dried", "14"], vec!["S2", "Control", "12"], vec!["S3", "100", "9"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string()];
+    config.csv_stat_group_header = "Treatment".to_string();
+    let output = process::proc_csv_stat_cols(&data, &config).unwrap();
+
+    // a row for each treatment in each sample, with the treatment written as text
+    let output_sheets = vec![("CSV_Stats".to_string(), output.clone())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("CSV_Stats", &["external-sample-id", "Treatment", "Avg Area", "Std Area"]).unwrap();
+    workbook.assert_column("CSV_Stats", "Treatment", &["Control", "Dried", "Control", "100"]).unwrap();
+
+    // or a sheet for each treatment
+    let group_sheets = process::split_output_by_group("CSV_Stats", &output, "Treatment");
+    let workbook = WorkbookView::from_output_sheets(&group_sheets, &config).unwrap();
+    workbook.assert_sheet_names(&["CSV_Stats_Control", "CSV_Stats_Dried", "CSV_Stats_100"]).unwrap();
+    workbook.assert_headers("CSV_Stats_Control", &["external-sample-id", "Avg Area", "Std Area"]).unwrap();
+    workbook.assert_column("CSV_Stats_Control", "external-sample-id", &["S1", "S2"]).unwrap();
+}//end csv_stats_group_layout()
+
+#[test]
+fn sheet_per_metric_layout() {
+    let data = make_data(vec!["external-sample-id", "Area", "Length"], vec![vec!["S1", "10", "4"], vec!["S1", "14", "6"], vec!["S2", "12", "5"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string(), "Length".to_string()];
+    config.csv_stat_columns_stats = vec!["Max".to_string()];
+    let mut output = process::proc_csv_stat_cols(&data, &config).unwrap();
+    output.add_column(OutputColumn::number("Flags", 0));
+    for output_row in output.rows.iter_mut() {output_row.cells.push(DataVal::String(String::new()));}
+
+    let output_sheets = vec![("CSV_Stats".to_string(), output)];
+    let layout_sheets = process::apply_sheet_layout(&output_sheets, SheetLayout::SheetPerMetric, &config);
+    let workbook = WorkbookView::from_output_sheets(&layout_sheets, &config).unwrap();
+    workbook.assert_sheet_names(&["Area", "Length"]).unwrap();
+    workbook.assert_headers("Area", &["external-sample-id", "Avg", "Std", "Max", "Flags"]).unwrap();
+    workbook.assert_column("Area", "external-sample-id", &["S1", "S2"]).unwrap();
+    workbook.assert_column("Length", "Max", &["6", "5"]).unwrap();
+}//end sheet_per_metric_layout()
+
+#[test]
+fn diagnostics_layout() {
+    let data = make_data(vec!["external-sample-id", "Area"], vec![vec!["A", "10"], vec!["A", "bad"], vec!["A", ""], vec!["B", "12"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string()];
+    config.diagnostics_sheet_enabled = true;
+
+    let output_sheets = vec![("Diagnostics".to_string(), process::proc_csv_diagnostics(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("Diagnostics", &["external-sample-id", "Non-Numeric Area", "Missing Area"]).unwrap();
+    workbook.assert_row_count("Diagnostics", 2).unwrap();
+}//end diagnostics_layout()
+
+#[test]
+fn frame_stats_layout() {
+    let data = make_data(vec!["external-sample-id", "image-id"], vec![vec!["A", "1"], vec!["A", "1"], vec!["A", "1"], vec!["A", "2"], vec!["B", "7"]]);
+    let config = ConfigStore::default();
+
+    let output = process::proc_csv_frame_stats(&data, &config).unwrap();
+    let mut headers = vec!["external-sample-id".to_string()];
+    headers.extend(output.columns.iter().map(|column| column.name.clone()));
+    let output_sheets = vec![("Frame_Stats".to_string(), output)];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    let headers: Vec<&str> = headers.iter().map(|header| header.as_str()).collect();
+    workbook.assert_headers("Frame_Stats", &headers).unwrap();
+    workbook.assert_column("Frame_Stats", "Avg Kernels per Frame", &["2", "1"]).unwrap();
+}//end frame_stats_layout()
+
+#[test]
+fn drift_check_layout() {
+    let mut rows = Vec::new();
+    // Area grows over time while Weight doesn't
+    for (time, area, weight) in [("8", "19", "30"), ("1", "10", "31"), ("2", "11", "29"), ("7", "18", "29"), ("3", "10", "30"), ("6", "20", "31"), ("4", "9", "30"), ("5", "21", "30")] {
+        rows.push(vec!["A", "Sound", time, area, weight]);
+    }//end making a row for each kernel
+    let data = make_data(vec!["external-sample-id", "raw-filtered-as", "Time", "Area", "Weight"], rows);
+    let mut config = ConfigStore::default();
+    config.csv_stat_columns_columns = vec!["Area".to_string(), "Weight".to_string()];
+    config.drift_sheet_enabled = true;
+    config.drift_timestamp_header = "Time".to_string();
+
+    // the written sheet has a row for each stat column
+    let output_sheets = vec![("Drift_Check".to_string(), process::proc_csv_drift_check(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_row_count("Drift_Check", 2).unwrap();
+    workbook.assert_cell("Drift_Check", "A2", "Area").unwrap();
+    workbook.assert_cell("Drift_Check", "G2", "Drift").unwrap();
+    workbook.assert_cell("Drift_Check", "G3", "").unwrap();
+}//end drift_check_layout()
+
+#[test]
+fn class_percents_layout() {
+    let data = make_data(vec!["external-sample-id", "raw-filtered-as"], vec![vec!["A", "Sound"], vec!["A", "Sound"], vec!["A", "Sound"], vec!["A", "Broken"], vec!["B", "Sound"]]);
+    let config = ConfigStore::default();
+
+    // the written sheet has a percent column for each class
+    let output_sheets = vec![("Class_Percents".to_string(), process::proc_csv_class_per(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("Class_Percents", &["external-sample-id", "%Sound", "%Broken"]).unwrap();
+    workbook.assert_column("Class_Percents", "%Broken", &["0.25", "0"]).unwrap();
+}//end class_percents_layout()
+
+#[test]
+fn metrics_layout() {
+    let data = make_data(vec!["external-sample-id", "raw-filtered-as", "Weight"], vec![vec!["A", "Sound", "0.03"], vec!["A", "Broken", "0.01"], vec!["B", "Sound", "0.02"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.metrics_sheet_metrics = vec!["Kernel Count".to_string(), "%Sound".to_string()];
+
+    let output_sheets = vec![("Metrics".to_string(), process::proc_csv_metrics(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("Metrics", &["external-sample-id", "Kernel Count", "%Sound"]).unwrap();
+    workbook.assert_column("Metrics", "%Sound", &["0.5", "1"]).unwrap();
+}//end metrics_layout()
+
+#[test]
+fn treatment_comparison_layout() {
+    let data = make_data(vec!["external-sample-id", "Area"], vec![vec!["S1", "1.0"], vec!["S1", "3.0"], vec!["S2", "4.0"], vec!["S3", "9.0"]]);
+    let metadata = make_data(vec!["external-sample-id", "Treatment"], vec![vec!["S1", "Dry"], vec!["S2", "Dry"], vec!["S3", "Wet"]]);
+    let mut config = ConfigStore::default();
+    config.treatment_sheet_enabled = true;
+    config.treatment_columns = vec!["Treatment".to_string()];
+    config.treatment_metrics = vec!["Area".to_string()];
+
+    // the written sheet has a row for each treatment
+    let output_sheets = vec![("Treatment_Comparison".to_string(), process::proc_treatment_comparison(&data, &metadata, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_column("Treatment_Comparison", "Treatment", &["Dry", "Wet"]).unwrap();
+    workbook.assert_cell("Treatment_Comparison", "C2", "3").unwrap();
+}//end treatment_comparison_layout()
+
+#[test]
+fn xml_sieve_data_layout() {
+    let data = make_data(vec!["reference", "filter-sieving-top", "filter-sieving-bottom"], vec![vec!["S1", "60", "40"], vec!["S2", "50", "40"]]);
+    let config = ConfigStore::default();
+
+    let output_sheets = vec![("XML_Sieve_Data".to_string(), process::proc_xml_sieve_data(&data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("XML_Sieve_Data", &["external-sample-id", "filter-sieving-top", "filter-sieving-bottom", "Sieve Total %", "Sieve Total Check"]).unwrap();
+    workbook.assert_column("XML_Sieve_Data", "Sieve Total %", &["100", "90"]).unwrap();
+    workbook.assert_column("XML_Sieve_Data", "Sieve Total Check", &["", "Off by -10.0%"]).unwrap();
+}//end xml_sieve_data_layout()
+
+#[test]
+fn merged_layout() {
+    let csv_data = make_data(vec!["external-sample-id", "Area"], vec![vec!["S1", "10"], vec!["S1", "14"], vec!["S2", "12"]]);
+    let xml_data = make_data(vec!["reference", "filter-sieving-top"], vec![vec!["S2", "55"], vec!["S3", "60"]]);
+    let mut config = ConfigStore::default();
+    config.csv_class_filter_enabled = false;
+    config.csv_stat_columns_columns = vec!["Area".to_string()];
+    config.xml_sieve_sum_tolerance = String::new();
+    config.merged_sheet_enabled = true;
+
+    let output_sheets = vec![("Merged".to_string(), process::proc_merged_output(&csv_data, &xml_data, &config).unwrap())];
+    let workbook = WorkbookView::from_output_sheets(&output_sheets, &config).unwrap();
+    workbook.assert_headers("Merged", &["external-sample-id", "Avg Area", "Std Area", "filter-sieving-top"]).unwrap();
+    workbook.assert_column("Merged", "external-sample-id", &["S1", "S2", "S3"]).unwrap();
+    workbook.assert_column("Merged", "filter-sieving-top", &["", "55", "60"]).unwrap();
+}//end merged_layout()
+
+#[test]
+fn grouped_headers_layout() {
+    let mut grouped_data = SampleOutput::new("external-sample-id")
+        .with_column(OutputColumn::number("Avg Length", 2).with_group("Length (mm)"))
+        .with_column(OutputColumn::number("Std Length", 2).with_group("Length (mm)"))
+        .with_column(OutputColumn::number("Notes", 0));
+    grouped_data.add_row("S1", vec![DataVal::Float(6.25), DataVal::Float(0.25), DataVal::String("ok".to_string())]);
+    let mut workbook = xlsx::get_workbook();
+    xlsx::write_output_to_sheet(&mut workbook, &grouped_data, "CSV_Stats", &ConfigStore::default()).unwrap();
+    let path = std::env::temp_dir().join(format!("c_grain_sum_grouped_headers_test_{}.xlsx", std::process::id()));
+    xlsx::close_workbook(&mut workbook, &path).unwrap();
+
+    // grouped columns are written under a second row of headers
+    let workbook_view = WorkbookView::from_path(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    workbook_view.assert_cell("CSV_Stats", "B1", "Length (mm)").unwrap();
+    workbook_view.assert_cell("CSV_Stats", "C2", "Std Length").unwrap();
+    workbook_view.assert_cell("CSV_Stats", "D1", "Notes").unwrap();
+    workbook_view.assert_cell("CSV_Stats", "A3", "S1").unwrap();
+    workbook_view.assert_cell("CSV_Stats", "B3", "6.25").unwrap();
+}//end grouped_headers_layout()
@@ -0,0 +1,144 @@
+use std::{path::Path, sync::atomic::{AtomicUsize, Ordering}};
+
+use calamine::{open_workbook, Reader, Xlsx};
+use usda_c_grain_sum::{app::config_store::ConfigStore, io::{checksum, xlsx}, model::SampleOutput};
+
+/// Counts the workbooks written by from_output_sheets(), so tests
+/// running at the same time don't write to the same file.
+static WORKBOOKS_WRITTEN: AtomicUsize = AtomicUsize::new(0);
+
+/// A workbook read back from an xlsx file with calamine, for checking
+/// the layout of the output after it's written.
+/// Calamine doesn't share any code with how the program reads workbooks,
+/// so a mistake in writing isn't hidden by the same mistake in reading.
+/// Each sheet is held as a grid of the text of its cells, so numbers are
+/// given without their number format, such as "0.9" for a cell shown as 90.0%.
+/// The assert functions return an error describing what was found
+/// instead of what was expected, so they can be used with unwrap().
+#[derive(Clone, PartialEq, Debug)]
+pub struct WorkbookView {
+    /// The name and grid of each sheet, in the order of the sheets in the workbook.
+    pub sheets: Vec<(String, Vec<Vec<String>>)>,
+}//end struct WorkbookView
+
+impl WorkbookView {
+    /// Reads the xlsx file at path.
+    pub fn from_path(path: &Path) -> Result<WorkbookView,String> {
+        let mut workbook: Xlsx<_> = open_workbook(path).map_err(|error| format!("Couldn't open the workbook at {}.\n{}", path.to_string_lossy(), error))?;
+        let mut sheets = Vec::new();
+        for sheet_name in workbook.sheet_names() {
+            let range = workbook.worksheet_range(&sheet_name).map_err(|error| format!("Couldn't read the {} sheet.\n{}", sheet_name, error))?;
+            // the range starts at the first cell with a value, rather than A1
+            let (start_row, start_col) = range.start().unwrap_or((0, 0));
+            let (end_row, end_col) = range.end().map(|(row, col)| (row as usize + 1, col as usize + 1)).unwrap_or((0, 0));
+            let mut grid = vec![vec![String::new(); end_col]; end_row];
+            for (row, col, value) in range.cells() {
+                grid[start_row as usize + row][start_col as usize + col] = value.to_string();
+            }//end copying the text of each cell
+            sheets.push((sheet_name, grid));
+        }//end reading each sheet into a grid
+        Ok(WorkbookView { sheets })
+    }//end from_path(path)
+
+    /// Writes each of output_sheets to an xlsx file in the temp directory
+    /// with xlsx::write_output_to_sheet(), the same way the output file is
+    /// written, then reads the file back and removes it.
+    pub fn from_output_sheets(output_sheets: &[(String, SampleOutput)], config: &ConfigStore) -> Result<WorkbookView,String> {
+        let mut workbook = xlsx::get_workbook();
+        for (sheet_name, sheet_data) in output_sheets.iter() {
+            if let Err(error) = xlsx::write_output_to_sheet(&mut workbook, sheet_data, sheet_name, config) {
+                return Err(format!("Couldn't write the {} sheet.\n{}", sheet_name, error));
+            }//end if we couldn't write this sheet
+        }//end writing each sheet
+        let workbook_num = WORKBOOKS_WRITTEN.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("c_grain_sum_layout_test_{}_{}.xlsx", std::process::id(), workbook_num));
+        xlsx::close_workbook(&mut workbook, &path).map_err(|error| format!("Couldn't write the workbook to {}.\n{}", path.to_string_lossy(), error))?;
+        let workbook_view = WorkbookView::from_path(&path);
+        let _ = std::fs::remove_file(&path);
+        workbook_view
+    }//end from_output_sheets(output_sheets, config)
+
+    /// Gets the name of each sheet, in order.
+    pub fn get_sheet_names(&self) -> Vec<&str> {
+        self.sheets.iter().map(|(sheet_name, _)| sheet_name.as_str()).collect()
+    }//end get_sheet_names(self)
+
+    /// Gets the grid of the sheet called sheet_name, or an error if there isn't one.
+    pub fn get_sheet(&self, sheet_name: &str) -> Result<&Vec<Vec<String>>,String> {
+        match self.sheets.iter().find(|(name, _)| name == sheet_name) {
+            Some((_, grid)) => Ok(grid),
+            None => Err(format!("There's no {} sheet in the workbook. The sheets are {}.", sheet_name, self.get_sheet_names().join(", "))),
+        }//end matching whether there's a sheet with that name
+    }//end get_sheet(self, sheet_name)
+
+    /// Gets the text of the cell at cell_ref, like "B3", in the sheet
+    /// called sheet_name. Empty cells give an empty string.
+    pub fn get_cell(&self, sheet_name: &str, cell_ref: &str) -> Result<&str,String> {
+        let (row, col) = checksum::parse_cell_ref(cell_ref).ok_or(format!("{} isn't a cell reference.", cell_ref))?;
+        let grid = self.get_sheet(sheet_name)?;
+        Ok(grid.get(row as usize).and_then(|grid_row| grid_row.get(col as usize)).map(|value| value.as_str()).unwrap_or(""))
+    }//end get_cell(self, sheet_name, cell_ref)
+
+    /// Gets the first row of the sheet called sheet_name, which is the
+    /// headers, unless the sheet is transposed.
+    pub fn get_headers(&self, sheet_name: &str) -> Result<Vec<&str>,String> {
+        let grid = self.get_sheet(sheet_name)?;
+        Ok(grid.first().map(|header_row| header_row.iter().map(|header| header.as_str()).collect()).unwrap_or_default())
+    }//end get_headers(self, sheet_name)
+
+    /// Gets the cells under header in the sheet called sheet_name, leaving out the header.
+    pub fn get_column(&self, sheet_name: &str, header: &str) -> Result<Vec<&str>,String> {
+        let col_idx = match self.get_headers(sheet_name)?.iter().position(|name| *name == header) {
+            Some(col_idx) => col_idx,
+            None => return Err(format!("There's no {} column in the {} sheet.", header, sheet_name)),
+        };
+        let grid = self.get_sheet(sheet_name)?;
+        Ok(grid.iter().skip(1).map(|grid_row| grid_row.get(col_idx).map(|value| value.as_str()).unwrap_or("")).collect())
+    }//end get_column(self, sheet_name, header)
+
+    /// Checks that the workbook has exactly the sheets in expected, in order.
+    pub fn assert_sheet_names(&self, expected: &[&str]) -> Result<(),String> {
+        let sheet_names = self.get_sheet_names();
+        match sheet_names == expected {
+            true => Ok(()),
+            false => Err(format!("Expected the sheets {:?}, but found {:?}.", expected, sheet_names)),
+        }//end matching whether the sheets match
+    }//end assert_sheet_names(self, expected)
+
+    /// Checks that the first row of the sheet called sheet_name is exactly expected.
+    pub fn assert_headers(&self, sheet_name: &str, expected: &[&str]) -> Result<(),String> {
+        let headers = self.get_headers(sheet_name)?;
+        match headers == expected {
+            true => Ok(()),
+            false => Err(format!("Expected the headers of the {} sheet to be {:?}, but found {:?}.", sheet_name, expected, headers)),
+        }//end matching whether the headers match
+    }//end assert_headers(self, sheet_name, expected)
+
+    /// Checks that the cells under header in the sheet called sheet_name are exactly expected.
+    pub fn assert_column(&self, sheet_name: &str, header: &str, expected: &[&str]) -> Result<(),String> {
+        let column = self.get_column(sheet_name, header)?;
+        match column == expected {
+            true => Ok(()),
+            false => Err(format!("Expected the {} column of the {} sheet to be {:?}, but found {:?}.", header, sheet_name, expected, column)),
+        }//end matching whether the column matches
+    }//end assert_column(self, sheet_name, header, expected)
+
+    /// Checks that the cell at cell_ref, like "B3", in the sheet called
+    /// sheet_name has the text expected.
+    pub fn assert_cell(&self, sheet_name: &str, cell_ref: &str, expected: &str) -> Result<(),String> {
+        let value = self.get_cell(sheet_name, cell_ref)?;
+        match value == expected {
+            true => Ok(()),
+            false => Err(format!("Expected cell {} of the {} sheet to be \"{}\", but found \"{}\".", cell_ref, sheet_name, expected, value)),
+        }//end matching whether the cell matches
+    }//end assert_cell(self, sheet_name, cell_ref, expected)
+
+    /// Checks that the sheet called sheet_name has expected rows after the header row.
+    pub fn assert_row_count(&self, sheet_name: &str, expected: usize) -> Result<(),String> {
+        let row_count = self.get_sheet(sheet_name)?.len().saturating_sub(1);
+        match row_count == expected {
+            true => Ok(()),
+            false => Err(format!("Expected {} rows after the headers in the {} sheet, but found {}.", expected, sheet_name, row_count)),
+        }//end matching whether the row count matches
+    }//end assert_row_count(self, sheet_name, expected)
+}//end impl WorkbookView